@@ -1,11 +1,20 @@
 #![feature(box_patterns, box_syntax)]
 
+//! Encoding of the inputs and decoding of the outputs of ZoKrates programs, following their ABI
+//!
+//! Values are parsed from the JSON format of the ABI with `parse_strict`, or built as `Value`s and checked against the
+//! types of a signature with `check_values`. `Value` implements `Serialize` and `Deserialize` in the same format.
+//! With the `proptest` feature, `strategy` generates random valid inputs from an ABI.
+
+#[cfg(feature = "proptest")]
+pub mod strategy;
+
 pub enum Inputs<T> {
     Raw(Vec<T>),
     Abi(CheckedValues<T>),
 }
 
-impl<T: From<usize>> Encode<T> for Inputs<T> {
+impl<T: From<u128>> Encode<T> for Inputs<T> {
     fn encode(self) -> Vec<T> {
         match self {
             Inputs::Raw(v) => v,
@@ -14,6 +23,7 @@ impl<T: From<usize>> Encode<T> for Inputs<T> {
     }
 }
 
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::BTreeMap;
 use std::convert::TryFrom;
 use std::fmt;
@@ -40,11 +50,10 @@ impl fmt::Display for Error {
     }
 }
 
-#[derive(PartialEq, Debug)]
-enum Value<T> {
-    U8(u8),
-    U16(u16),
-    U32(u32),
+/// A value passed to or returned by a program, which is checked against the expected type before encoding
+#[derive(PartialEq, Debug, Clone)]
+pub enum Value<T> {
+    Uint(UBitwidth, u128),
     Field(T),
     Boolean(bool),
     Array(Vec<Value<T>>),
@@ -53,9 +62,7 @@ enum Value<T> {
 
 #[derive(PartialEq, Debug)]
 enum CheckedValue<T> {
-    U8(u8),
-    U16(u16),
-    U32(u32),
+    Uint(UBitwidth, u128),
     Field(T),
     Boolean(bool),
     Array(Vec<CheckedValue<T>>),
@@ -65,13 +72,16 @@ enum CheckedValue<T> {
 #[derive(PartialEq, Debug)]
 pub struct CheckedValues<T>(Vec<CheckedValue<T>>);
 
+/// Returns the hexadecimal representation of an unsigned integer, with as many digits as its bitwidth requires
+fn format_uint(bitwidth: UBitwidth, value: u128) -> String {
+    format!("{:#0width$x}", value, width = bitwidth.to_usize() / 4 + 2)
+}
+
 impl<T: Field> fmt::Display for Value<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Value::Field(v) => write!(f, "{}", v),
-            Value::U8(v) => write!(f, "{:#04x}", v),
-            Value::U16(v) => write!(f, "{:#06x}", v),
-            Value::U32(v) => write!(f, "{:#010x}", v),
+            Value::Uint(bitwidth, v) => write!(f, "{}", format_uint(*bitwidth, *v)),
             Value::Boolean(v) => write!(f, "{}", v),
             Value::Array(v) => write!(
                 f,
@@ -97,9 +107,12 @@ impl<T: Field> Value<T> {
     fn check(self, ty: Type) -> Result<CheckedValue<T>, String> {
         match (self, ty) {
             (Value::Field(f), Type::FieldElement) => Ok(CheckedValue::Field(f)),
-            (Value::U8(f), Type::Uint(UBitwidth::B8)) => Ok(CheckedValue::U8(f)),
-            (Value::U16(f), Type::Uint(UBitwidth::B16)) => Ok(CheckedValue::U16(f)),
-            (Value::U32(f), Type::Uint(UBitwidth::B32)) => Ok(CheckedValue::U32(f)),
+            (Value::Uint(bitwidth, v), Type::Uint(expected)) if bitwidth == expected => {
+                match v.checked_shr(bitwidth.to_usize() as u32).unwrap_or(0) {
+                    0 => Ok(CheckedValue::Uint(bitwidth, v)),
+                    _ => Err(format!("Value `{}` doesn't fit in u{}", v, bitwidth)),
+                }
+            }
             (Value::Boolean(b), Type::Boolean) => Ok(CheckedValue::Boolean(b)),
             (Value::Array(a), Type::Array(array_type)) => {
                 if a.len() != array_type.size {
@@ -152,14 +165,12 @@ pub trait Decode<T> {
     fn decode(raw: Vec<T>, expected: Self::Expected) -> Self;
 }
 
-impl<T: From<usize>> Encode<T> for CheckedValue<T> {
+impl<T: From<u128>> Encode<T> for CheckedValue<T> {
     fn encode(self) -> Vec<T> {
         match self {
             CheckedValue::Field(t) => vec![t],
-            CheckedValue::U8(t) => vec![T::from(t as usize)],
-            CheckedValue::U16(t) => vec![T::from(t as usize)],
-            CheckedValue::U32(t) => vec![T::from(t as usize)],
-            CheckedValue::Boolean(b) => vec![if b { 1.into() } else { 0.into() }],
+            CheckedValue::Uint(_, t) => vec![T::from(t)],
+            CheckedValue::Boolean(b) => vec![T::from(b as u128)],
             CheckedValue::Array(a) => a.into_iter().flat_map(|v| v.encode()).collect(),
             CheckedValue::Struct(s) => s.into_iter().flat_map(|(_, v)| v.encode()).collect(),
         }
@@ -192,14 +203,9 @@ impl<T: Field> Decode<T> for CheckedValue<T> {
 
         match expected {
             Type::FieldElement => CheckedValue::Field(raw.pop().unwrap()),
-            Type::Uint(UBitwidth::B8) => CheckedValue::U8(
-                u8::from_str_radix(&raw.pop().unwrap().to_dec_string(), 10).unwrap(),
-            ),
-            Type::Uint(UBitwidth::B16) => CheckedValue::U16(
-                u16::from_str_radix(&raw.pop().unwrap().to_dec_string(), 10).unwrap(),
-            ),
-            Type::Uint(UBitwidth::B32) => CheckedValue::U32(
-                u32::from_str_radix(&raw.pop().unwrap().to_dec_string(), 10).unwrap(),
+            Type::Uint(bitwidth) => CheckedValue::Uint(
+                bitwidth,
+                u128::from_str_radix(&raw.pop().unwrap().to_dec_string(), 10).unwrap(),
             ),
             Type::Boolean => {
                 let v = raw.pop().unwrap();
@@ -231,12 +237,33 @@ impl<T: Field> Decode<T> for CheckedValue<T> {
     }
 }
 
-impl<T: From<usize>> Encode<T> for CheckedValues<T> {
+impl<T: From<u128>> Encode<T> for CheckedValues<T> {
     fn encode(self) -> Vec<T> {
         self.0.into_iter().flat_map(|v| v.encode()).collect()
     }
 }
 
+impl<T> From<CheckedValue<T>> for Value<T> {
+    fn from(v: CheckedValue<T>) -> Self {
+        match v {
+            CheckedValue::Uint(bitwidth, v) => Value::Uint(bitwidth, v),
+            CheckedValue::Field(v) => Value::Field(v),
+            CheckedValue::Boolean(v) => Value::Boolean(v),
+            CheckedValue::Array(a) => Value::Array(a.into_iter().map(Value::from).collect()),
+            CheckedValue::Struct(s) => {
+                Value::Struct(s.into_iter().map(|(k, v)| (k, Value::from(v))).collect())
+            }
+        }
+    }
+}
+
+impl<T> CheckedValues<T> {
+    /// Returns the values, for example after decoding the outputs of a program
+    pub fn into_values(self) -> Vec<Value<T>> {
+        self.0.into_iter().map(Value::from).collect()
+    }
+}
+
 #[derive(PartialEq, Debug)]
 struct Values<T>(Vec<Value<T>>);
 
@@ -261,17 +288,18 @@ impl<T: Field> TryFrom<serde_json::Value> for Value<T> {
         match v {
             serde_json::Value::String(s) => T::try_from_dec_str(&s)
                 .map(|v| Value::Field(v))
-                .or_else(|_| match s.len() {
-                    4 => u8::from_str_radix(&s[2..], 16)
-                        .map(|v| Value::U8(v))
-                        .map_err(|_| format!("Expected u8 value, found {}", s)),
-                    6 => u16::from_str_radix(&s[2..], 16)
-                        .map(|v| Value::U16(v))
-                        .map_err(|_| format!("Expected u16 value, found {}", s)),
-                    10 => u32::from_str_radix(&s[2..], 16)
-                        .map(|v| Value::U32(v))
-                        .map_err(|_| format!("Expected u32 value, found {}", s)),
-                    _ => Err(format!("Cannot parse {} to any type", s)),
+                .or_else(|_| {
+                    let bitwidth = match s.len() {
+                        4 => UBitwidth::B8,
+                        6 => UBitwidth::B16,
+                        10 => UBitwidth::B32,
+                        18 => UBitwidth::B64,
+                        34 => UBitwidth::B128,
+                        _ => return Err(format!("Cannot parse {} to any type", s)),
+                    };
+                    u128::from_str_radix(&s[2..], 16)
+                        .map(|v| Value::Uint(bitwidth, v))
+                        .map_err(|_| format!("Expected u{} value, found {}", bitwidth, s))
                 }),
             serde_json::Value::Bool(b) => Ok(Value::Boolean(b)),
             serde_json::Value::Number(n) => Err(format!(
@@ -297,9 +325,7 @@ impl<T: Field> Into<serde_json::Value> for CheckedValue<T> {
     fn into(self) -> serde_json::Value {
         match self {
             CheckedValue::Field(f) => serde_json::Value::String(f.to_dec_string()),
-            CheckedValue::U8(u) => serde_json::Value::String(format!("{:#04x}", u)),
-            CheckedValue::U16(u) => serde_json::Value::String(format!("{:#06x}", u)),
-            CheckedValue::U32(u) => serde_json::Value::String(format!("{:#010x}", u)),
+            CheckedValue::Uint(bitwidth, u) => serde_json::Value::String(format_uint(bitwidth, u)),
             CheckedValue::Boolean(b) => serde_json::Value::Bool(b),
             CheckedValue::Array(a) => {
                 serde_json::Value::Array(a.into_iter().map(|e| e.into()).collect())
@@ -317,6 +343,25 @@ impl<T: Field> Into<serde_json::Value> for CheckedValues<T> {
     }
 }
 
+impl<T: Field> Serialize for Value<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Uint(bitwidth, v) => serializer.serialize_str(&format_uint(*bitwidth, *v)),
+            Value::Field(v) => serializer.serialize_str(&v.to_dec_string()),
+            Value::Boolean(v) => serializer.serialize_bool(*v),
+            Value::Array(a) => serializer.collect_seq(a),
+            Value::Struct(s) => serializer.collect_map(s),
+        }
+    }
+}
+
+impl<'de, T: Field> Deserialize<'de> for Value<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let v = serde_json::Value::deserialize(deserializer)?;
+        Value::try_from(v).map_err(serde::de::Error::custom)
+    }
+}
+
 fn parse<T: Field>(s: &str) -> Result<Values<T>, Error> {
     let json_values: serde_json::Value =
         serde_json::from_str(s).map_err(|e| Error::Json(e.to_string()))?;
@@ -324,16 +369,22 @@ fn parse<T: Field>(s: &str) -> Result<Values<T>, Error> {
 }
 
 pub fn parse_strict<T: Field>(s: &str, types: Vec<Type>) -> Result<CheckedValues<T>, Error> {
-    let parsed = parse(s)?;
-    if parsed.0.len() != types.len() {
+    check_values(parse(s)?.0, types)
+}
+
+/// Checks values against the types of the inputs of a program, returning them ready to be encoded
+pub fn check_values<T: Field>(
+    values: Vec<Value<T>>,
+    types: Vec<Type>,
+) -> Result<CheckedValues<T>, Error> {
+    if values.len() != types.len() {
         return Err(Error::Type(format!(
             "Expected {} inputs, found {}",
             types.len(),
-            parsed.0.len()
+            values.len()
         )));
     }
-    let checked = parsed
-        .0
+    let checked = values
         .into_iter()
         .zip(types.into_iter())
         .map(|(v, ty)| v.check(ty))
@@ -506,24 +557,90 @@ mod tests {
         }
     }
 
+    mod typed {
+        use super::*;
+
+        #[test]
+        fn check() {
+            let values = vec![
+                Value::Uint(UBitwidth::B8, 42),
+                Value::Array(vec![Value::Field(Bn128Field::from(1))]),
+            ];
+            let types = vec![
+                Type::Uint(UBitwidth::B8),
+                Type::array(Type::FieldElement, 1),
+            ];
+
+            let checked = check_values(values.clone(), types).unwrap();
+            assert_eq!(checked.into_values(), values);
+
+            assert_eq!(
+                check_values(
+                    vec![Value::<Bn128Field>::Uint(UBitwidth::B8, 256)],
+                    vec![Type::Uint(UBitwidth::B8)]
+                )
+                .unwrap_err(),
+                Error::Type("Value `256` doesn't fit in u8".into())
+            );
+            assert!(check_values(
+                vec![Value::<Bn128Field>::Uint(UBitwidth::B16, 1)],
+                vec![Type::Uint(UBitwidth::B8)]
+            )
+            .is_err());
+        }
+
+        #[test]
+        fn json() {
+            let value: Value<Bn128Field> = Value::Struct(
+                vec![
+                    ("a".to_string(), Value::Uint(UBitwidth::B16, 1)),
+                    ("b".to_string(), Value::Boolean(true)),
+                    ("c".to_string(), Value::Field(42.into())),
+                ]
+                .into_iter()
+                .collect(),
+            );
+
+            let json = serde_json::to_string(&value).unwrap();
+            assert_eq!(json, r#"{"a":"0x0001","b":true,"c":"42"}"#);
+            assert_eq!(serde_json::from_str::<Value<_>>(&json).unwrap(), value);
+            assert!(serde_json::from_str::<Value<Bn128Field>>("1").is_err());
+        }
+
+        #[test]
+        fn decode() {
+            let decoded = CheckedValues::decode(
+                vec![Bn128Field::from(3), Bn128Field::from(1)],
+                vec![Type::Uint(UBitwidth::B32), Type::Boolean],
+            );
+            assert_eq!(
+                decoded.into_values(),
+                vec![Value::Uint(UBitwidth::B32, 3), Value::Boolean(true)]
+            );
+        }
+    }
+
     mod encode {
         use super::*;
 
         #[test]
         fn fields() {
-            let v = CheckedValues(vec![CheckedValue::Field(1), CheckedValue::Field(2)]);
+            let v = CheckedValues::<u128>(vec![CheckedValue::Field(1), CheckedValue::Field(2)]);
             assert_eq!(v.encode(), vec![1, 2]);
         }
 
         #[test]
         fn u8s() {
-            let v = CheckedValues::<usize>(vec![CheckedValue::U8(1), CheckedValue::U8(2)]);
+            let v = CheckedValues::<u128>(vec![
+                CheckedValue::Uint(UBitwidth::B8, 1),
+                CheckedValue::Uint(UBitwidth::B8, 2),
+            ]);
             assert_eq!(v.encode(), vec![1, 2]);
         }
 
         #[test]
         fn bools() {
-            let v: CheckedValues<usize> = CheckedValues(vec![
+            let v: CheckedValues<u128> = CheckedValues(vec![
                 CheckedValue::Boolean(true),
                 CheckedValue::Boolean(false),
             ]);
@@ -532,7 +649,7 @@ mod tests {
 
         #[test]
         fn array() {
-            let v: CheckedValues<usize> = CheckedValues(vec![CheckedValue::Array(vec![
+            let v: CheckedValues<u128> = CheckedValues(vec![CheckedValue::Array(vec![
                 CheckedValue::Boolean(true),
                 CheckedValue::Boolean(false),
             ])]);
@@ -541,7 +658,7 @@ mod tests {
 
         #[test]
         fn struc() {
-            let v: CheckedValues<usize> = CheckedValues(vec![CheckedValue::Struct(
+            let v: CheckedValues<u128> = CheckedValues(vec![CheckedValue::Struct(
                 vec![("a".to_string(), CheckedValue::Field(42))]
                     .into_iter()
                     .collect(),
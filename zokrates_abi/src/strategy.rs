@@ -0,0 +1,103 @@
+//! Proptest strategies generating the inputs of a program from its ABI, to fuzz programs with values which are always
+//! valid for `check_values`
+//!
+//! Field elements are sampled uniformly, with zero and the largest element generated more often, as they are common
+//! edge cases.
+
+use crate::Value;
+use proptest::prelude::*;
+use zokrates_core::typed_absy::abi::Abi;
+use zokrates_core::typed_absy::Type;
+use zokrates_field::Field;
+
+/// Returns a strategy generating the inputs of a program whose ABI is `abi`
+pub fn inputs<T: Field + 'static>(abi: &Abi) -> BoxedStrategy<Vec<Value<T>>> {
+    abi.inputs
+        .iter()
+        .map(|input| value::<T>(input.ty.clone()))
+        .collect::<Vec<_>>()
+        .boxed()
+}
+
+/// Returns a strategy generating values of type `ty`
+pub fn value<T: Field + 'static>(ty: Type) -> BoxedStrategy<Value<T>> {
+    match ty {
+        Type::FieldElement => field::<T>().prop_map(Value::Field).boxed(),
+        Type::Boolean => any::<bool>().prop_map(Value::Boolean).boxed(),
+        Type::Uint(bitwidth) => (0..=(u128::max_value() >> (128 - bitwidth.to_usize())))
+            .prop_map(move |v| Value::Uint(bitwidth, v))
+            .boxed(),
+        Type::Array(array_type) => {
+            proptest::collection::vec(value::<T>(*array_type.ty), array_type.size)
+                .prop_map(Value::Array)
+                .boxed()
+        }
+        Type::Struct(struct_type) => {
+            let (ids, members): (Vec<_>, Vec<_>) = struct_type
+                .members
+                .into_iter()
+                .map(|member| (member.id, value::<T>(*member.ty)))
+                .unzip();
+
+            members
+                .prop_map(move |values| Value::Struct(ids.iter().cloned().zip(values).collect()))
+                .boxed()
+        }
+    }
+}
+
+fn field<T: Field + 'static>() -> BoxedStrategy<T> {
+    let uniform = proptest::collection::vec(any::<u8>(), T::byte_len())
+        .prop_filter_map("Value larger than the modulus", |bytes| {
+            T::try_from_le_bytes(&mask(bytes, T::get_required_bits())).ok()
+        });
+
+    prop_oneof![
+        1 => Just(T::zero()),
+        1 => Just(T::max_value()),
+        8 => uniform,
+    ]
+    .boxed()
+}
+
+/// Clears the bits of `bytes` above `bits`, so that a value is rejected by `try_from_le_bytes` less than half of the time
+fn mask(mut bytes: Vec<u8>, bits: usize) -> Vec<u8> {
+    let last = bytes.len() - 1;
+    bytes[last] &= 0xff >> (8 * bytes.len() - bits);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check_values;
+    use zokrates_core::typed_absy::types::{StructMember, StructType};
+    use zokrates_core::typed_absy::UBitwidth;
+    use zokrates_field::Bn128Field;
+
+    fn types() -> Vec<Type> {
+        vec![
+            Type::FieldElement,
+            Type::array(Type::Uint(UBitwidth::B8), 3),
+            Type::Struct(StructType::new(
+                "".into(),
+                "Foo".into(),
+                vec![
+                    StructMember::new("a".into(), Type::Boolean),
+                    StructMember::new("b".into(), Type::Uint(UBitwidth::B128)),
+                ],
+            )),
+        ]
+    }
+
+    fn values() -> Vec<BoxedStrategy<Value<Bn128Field>>> {
+        types().into_iter().map(value).collect()
+    }
+
+    proptest! {
+        #[test]
+        fn generates_valid_values(generated in values()) {
+            prop_assert!(check_values(generated, types()).is_ok());
+        }
+    }
+}
@@ -4,22 +4,38 @@
 // @author Dennis Kuhnert <dennis.kuhnert@campus.tu-berlin.de>
 // @date 2017
 
+mod cache;
 mod constants;
+mod diff;
+mod doc;
+mod evm;
 mod helpers;
+mod inspect;
+mod remote;
+mod templates;
+mod verifier_tests;
 
+use cache::CachedKeys;
 use constants::*;
 use helpers::*;
+use remote::Remote;
 
 use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
 use serde_json::{from_reader, to_writer_pretty, Value};
 use std::convert::TryFrom;
 use std::env;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::{stdin, BufReader, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::string::String;
 use zokrates_abi::Encode;
-use zokrates_core::compile::{check, compile, CompilationArtifacts, CompileError};
+use zokrates_core::compile::{
+    check, compile, differential_test, taint_analysis, typed_ast, verification_conditions,
+    CompilationArtifacts, CompileConfig, CompileError, CompileWarning,
+};
+use zokrates_core::entry_point::with_entry_point;
+use zokrates_core::error_codes::explain;
 use zokrates_core::ir::{self, ProgEnum};
 use zokrates_core::proof_system::bellman::groth16::G16;
 #[cfg(feature = "libsnark")]
@@ -27,9 +43,12 @@ use zokrates_core::proof_system::libsnark::gm17::GM17;
 #[cfg(feature = "libsnark")]
 use zokrates_core::proof_system::libsnark::pghr13::PGHR13;
 use zokrates_core::proof_system::*;
+use zokrates_core::public_inputs::PublicInputLimits;
 use zokrates_core::typed_absy::abi::Abi;
 use zokrates_core::typed_absy::{types::Signature, Type};
-use zokrates_field::{Bls12Field, Bn128Field, Field};
+use zokrates_field::{
+    Bls12Field, Bn128Field, CustomField, Field, GoldilocksField, PallasField, VestaField,
+};
 use zokrates_fs_resolver::FileSystemResolver;
 
 fn main() {
@@ -55,7 +74,32 @@ fn cli_generate_proof<T: Field, P: ProofSystem<T>>(
     let witness = ir::Witness::read(witness_file)
         .map_err(|why| format!("Could not load witness: {:?}", why))?;
 
-    let pk_path = Path::new(sub_matches.value_of("proving-key-path").unwrap());
+    let pk_path = match sub_matches.value_of("cache-dir") {
+        // the proving key is looked up in the cache unless its path is given explicitly
+        Some(cache_dir) if sub_matches.occurrences_of("proving-key-path") == 0 => {
+            let keys = CachedKeys::new(
+                Path::new(cache_dir),
+                Path::new(sub_matches.value_of("input").unwrap()),
+                sub_matches.value_of("backend").unwrap(),
+                sub_matches.value_of("proving-scheme").unwrap(),
+            )?;
+            match (keys.exist(), sub_matches.value_of("key-uri")) {
+                (true, _) => keys.proving_key_path(),
+                // keys missing from the cache are downloaded to it
+                (false, Some(uri)) => {
+                    println!("Downloading keys from {}...", uri);
+                    remote::pull_keys(
+                        &Remote::try_from(uri)?,
+                        &keys.proving_key_path(),
+                        &keys.verification_key_path(),
+                    )?;
+                    keys.proving_key_path()
+                }
+                (false, None) => PathBuf::from(sub_matches.value_of("proving-key-path").unwrap()),
+            }
+        }
+        _ => PathBuf::from(sub_matches.value_of("proving-key-path").unwrap()),
+    };
     let proof_path = Path::new(sub_matches.value_of("proof-path").unwrap());
 
     let pk_file = File::open(&pk_path)
@@ -67,7 +111,7 @@ fn cli_generate_proof<T: Field, P: ProofSystem<T>>(
         .read_to_end(&mut pk)
         .map_err(|why| format!("Couldn't read {}: {}", pk_path.display(), why))?;
 
-    let proof = P::generate_proof(program, witness, pk);
+    let proof = P::generate_proof(&program, witness, &pk);
     let mut proof_file = File::create(proof_path).unwrap();
 
     let proof = serde_json::to_string_pretty(&proof).unwrap();
@@ -85,18 +129,83 @@ fn cli_export_verifier<T: Field, P: ProofSystem<T>>(
 ) -> Result<(), String> {
     println!("Exporting verifier...");
 
-    // read vk file
-    let input_path = Path::new(sub_matches.value_of("input").unwrap());
-    let input_file = File::open(&input_path)
-        .map_err(|why| format!("Couldn't open {}: {}", input_path.display(), why))?;
-    let reader = BufReader::new(input_file);
+    let abi = SolidityAbi::from(sub_matches.value_of("solidity-abi").unwrap())?;
 
-    let vk = serde_json::from_reader(reader)
-        .map_err(|why| format!("Couldn't deserialize verifying key: {}", why))?;
+    let hardened = sub_matches.is_present("hardened");
 
-    let abi = SolidityAbi::from(sub_matches.value_of("solidity-abi").unwrap())?;
+    if hardened && sub_matches.is_present("dynamic-vk") {
+        return Err(String::from(
+            "Hardened verifiers do not support dynamic verification keys",
+        ));
+    }
 
-    let verifier = P::export_solidity_verifier(vk, abi);
+    // the number of public inputs, unknown if the verification key is provided at verification time
+    let (verifier, public_inputs) = match sub_matches.is_present("dynamic-vk") {
+        true if sub_matches.occurrences_of("template") > 0 => {
+            return Err(String::from(
+                "Dynamic verification keys are only supported by the solidity template",
+            ))
+        }
+        // the verification key is provided at verification time, so it is not read
+        true => match abi {
+            SolidityAbi::V2 => (
+                P::export_solidity_dynamic_verifier().ok_or_else(|| {
+                    String::from("This proving scheme does not support dynamic verification keys")
+                })?,
+                None,
+            ),
+            SolidityAbi::V1 => {
+                return Err(String::from(
+                    "Dynamic verification keys require the v2 Solidity ABI",
+                ))
+            }
+        },
+        false => {
+            // read vk file
+            let input_path = Path::new(sub_matches.value_of("input").unwrap());
+            let input_file = File::open(&input_path)
+                .map_err(|why| format!("Couldn't open {}: {}", input_path.display(), why))?;
+            let reader = BufReader::new(input_file);
+
+            let vk: Value = serde_json::from_reader(reader)
+                .map_err(|why| format!("Couldn't deserialize verifying key: {}", why))?;
+            let fingerprint = inspect::fingerprint(&vk);
+            let public_inputs = inspect::public_inputs(&vk).unwrap_or(0);
+            let vk = serde_json::from_value(vk)
+                .map_err(|why| format!("Couldn't deserialize verifying key: {}", why))?;
+
+            let verifier = match sub_matches.value_of("template").unwrap() {
+                "yul" if hardened => {
+                    return Err(String::from(
+                        "Hardened verifiers are only supported by the solidity template",
+                    ))
+                }
+                "yul" => P::export_yul_verifier(vk).ok_or_else(|| {
+                    String::from("This proving scheme does not support the yul template")
+                })?,
+                _ if hardened => {
+                    P::export_hardened_solidity_verifier(vk, abi).ok_or_else(|| {
+                        String::from("This proving scheme does not support hardened verifiers")
+                    })?
+                }
+                _ => P::export_solidity_verifier(vk, abi),
+            };
+
+            (
+                inspect::embed_fingerprint(&verifier, &fingerprint),
+                Some(public_inputs),
+            )
+        }
+    };
+
+    let verifier = match sub_matches.is_present("input-hash") {
+        true => match public_inputs {
+            Some(0) => return Err(String::from("The program has no public inputs to hash")),
+            // dynamic verifiers take the inputs as an array of any length
+            public_inputs => inspect::embed_input_hash(&verifier, public_inputs),
+        },
+        false => verifier,
+    };
 
     //write output file
     let output_path = Path::new(sub_matches.value_of("output").unwrap());
@@ -108,8 +217,206 @@ fn cli_export_verifier<T: Field, P: ProofSystem<T>>(
     writer
         .write_all(&verifier.as_bytes())
         .map_err(|_| "Failed writing output to file.".to_string())?;
+    writer
+        .flush()
+        .map_err(|_| "Unable to flush buffer.".to_string())?;
 
     println!("Finished exporting verifier.");
+
+    let tests = match sub_matches.value_of("tests") {
+        Some(framework) => Some(verifier_tests::Framework::from(framework)?),
+        None => None,
+    };
+
+    let gas_report = sub_matches.is_present("gas-report");
+
+    // measuring gas and generating tests both run the verifier on the EVM against the proof given with `--proof`
+    if !gas_report && tests.is_none() {
+        return Ok(());
+    }
+
+    let curve = sub_matches.value_of("curve").unwrap();
+    if curve != constants::BN128 {
+        return Err(format!(
+            "Running a verifier on the EVM requires the {} curve, found {}",
+            constants::BN128,
+            curve
+        ));
+    }
+    if sub_matches.is_present("dynamic-vk") {
+        return Err(String::from(
+            "Verifiers with dynamic verification keys cannot be run against a proof",
+        ));
+    }
+
+    // the yul template exposes the v1 ABI whatever the ABI requested
+    let abi = match sub_matches.value_of("template").unwrap() {
+        "yul" => "v1",
+        _ => sub_matches.value_of("solidity-abi").unwrap(),
+    };
+
+    let scheme = sub_matches.value_of("proving-scheme").unwrap();
+    let proof_path = Path::new(sub_matches.value_of("proof").unwrap());
+
+    if let Some(framework) = tests {
+        let proof_file = File::open(proof_path)
+            .map_err(|why| format!("Couldn't open {}: {}", proof_path.display(), why))?;
+        let proof: Value = from_reader(BufReader::new(proof_file))
+            .map_err(|why| format!("Couldn't deserialize proof: {}", why))?;
+
+        let test = verifier_tests::generate(&framework, output_path, &proof, scheme, abi)?;
+
+        let test_path = framework.test_path(output_path);
+        fs::write(&test_path, test)
+            .map_err(|why| format!("Couldn't write {}: {}", test_path.display(), why))?;
+
+        println!("Verifier tests written to '{}'", test_path.display());
+    }
+
+    if gas_report {
+        let gas = evm::measure_gas(
+            Path::new(sub_matches.value_of("harness-dir").unwrap()),
+            output_path,
+            proof_path,
+            scheme,
+            abi,
+        )?;
+
+        println!("Deployment gas: {}", gas.deployment);
+        println!("Verification gas: {}", gas.verification);
+    }
+
+    Ok(())
+}
+
+fn cli_export_envelope(sub_matches: &ArgMatches) -> Result<(), String> {
+    let dir = Path::new(sub_matches.value_of("output").unwrap());
+    fs::create_dir_all(dir).map_err(|why| format!("Couldn't create {}: {}", dir.display(), why))?;
+
+    for (file, content) in templates::ENVELOPE {
+        let file_path = dir.join(file);
+        fs::write(&file_path, content)
+            .map_err(|why| format!("Couldn't write {}: {}", file_path.display(), why))?;
+        println!("Envelope helper written to '{}'", file_path.display());
+    }
+
+    Ok(())
+}
+
+fn cli_diff<T: Field>(
+    old: ir::Prog<T>,
+    new: ir::Prog<T>,
+    sub_matches: &ArgMatches,
+) -> Result<(), String> {
+    let mut differences = diff::programs(&old, &new);
+
+    if let (Some(old), Some(new)) = (
+        sub_matches.value_of("old-abi"),
+        sub_matches.value_of("new-abi"),
+    ) {
+        differences.extend(diff::abis(&read_abi(old)?, &read_abi(new)?));
+    }
+
+    if let (Some(old), Some(new)) = (
+        sub_matches.value_of("old-source-map"),
+        sub_matches.value_of("new-source-map"),
+    ) {
+        differences.extend(diff::source_maps::<T>(
+            &read_source_map(old)?,
+            &read_source_map(new)?,
+        ));
+    }
+
+    match differences.len() {
+        0 => {
+            println!("The programs are identical");
+            Ok(())
+        }
+        _ => {
+            for difference in differences {
+                println!("{}", difference);
+            }
+            Err(String::from("The programs differ"))
+        }
+    }
+}
+
+fn cli_abi_check(sub_matches: &ArgMatches) -> Result<(), String> {
+    let changes = diff::compatibility(
+        &read_abi(sub_matches.value_of("old").unwrap())?,
+        &read_abi(sub_matches.value_of("new").unwrap())?,
+    );
+
+    for (compatibility, change) in &changes {
+        println!(
+            "{}: {}",
+            match compatibility {
+                diff::Compatibility::Compatible => "compatible",
+                diff::Compatibility::Breaking => "breaking",
+            },
+            change
+        );
+    }
+
+    match changes
+        .iter()
+        .any(|(c, _)| *c == diff::Compatibility::Breaking)
+    {
+        true => Err(String::from("The ABI changes are breaking")),
+        false => {
+            println!("The ABI changes are compatible");
+            Ok(())
+        }
+    }
+}
+
+fn cli_lint<T: Field>(program: ir::Prog<T>, sub_matches: &ArgMatches) -> Result<(), String> {
+    let source_map = read_source_map::<T>(sub_matches.value_of("source_map").unwrap())?;
+
+    let warnings = ir::lint::lint(&program, &source_map);
+
+    match warnings.len() {
+        0 => {
+            println!("No issues found");
+            Ok(())
+        }
+        n => {
+            for warning in warnings {
+                println!("{}", warning);
+            }
+            Err(format!("{} issue(s) found", n))
+        }
+    }
+}
+
+fn read_abi(path: &str) -> Result<Abi, String> {
+    let path = Path::new(path);
+    let file =
+        File::open(&path).map_err(|why| format!("Couldn't open {}: {}", path.display(), why))?;
+
+    from_reader(BufReader::new(file))
+        .map_err(|why| format!("Couldn't read ABI {}: {}", path.display(), why))
+}
+
+fn read_source_map<T: Field>(path: &str) -> Result<ir::SourceMap<T>, String> {
+    let path = Path::new(path);
+    let file =
+        File::open(&path).map_err(|why| format!("Couldn't open {}: {}", path.display(), why))?;
+
+    from_reader(BufReader::new(file))
+        .map_err(|why| format!("Couldn't read source map {}: {}", path.display(), why))
+}
+
+fn cli_inspect(sub_matches: &ArgMatches) -> Result<(), String> {
+    let path = Path::new(sub_matches.value_of("input").unwrap());
+    let file =
+        File::open(&path).map_err(|why| format!("Couldn't open {}: {}", path.display(), why))?;
+
+    let vk: Value = serde_json::from_reader(BufReader::new(file))
+        .map_err(|why| format!("Couldn't deserialize verifying key: {}", why))?;
+
+    println!("{}", inspect::Summary::from(&vk));
+
     Ok(())
 }
 
@@ -128,18 +435,45 @@ fn cli_setup<T: Field, P: ProofSystem<T>>(
     let pk_path = Path::new(sub_matches.value_of("proving-key-path").unwrap());
     let vk_path = Path::new(sub_matches.value_of("verification-key-path").unwrap());
 
+    let cached_keys = match sub_matches.value_of("cache-dir") {
+        Some(cache_dir) => Some(CachedKeys::new(
+            Path::new(cache_dir),
+            Path::new(sub_matches.value_of("input").unwrap()),
+            sub_matches.value_of("backend").unwrap(),
+            sub_matches.value_of("proving-scheme").unwrap(),
+        )?),
+        None => None,
+    };
+
+    let remote = match sub_matches.value_of("key-uri") {
+        Some(uri) => Some(Remote::try_from(uri)?),
+        None => None,
+    };
+
+    // reuse the keys of the same program if they were cached
+    if let Some(keys) = cached_keys.as_ref().filter(|keys| keys.exist()) {
+        keys.load(pk_path, vk_path)?;
+        if let Some(remote) = remote {
+            remote::push_keys(&remote, pk_path, vk_path)?;
+        }
+        println!("Setup completed, reusing cached keys.");
+        return Ok(());
+    }
+
     // run setup phase
     let keypair = P::setup(program);
 
-    // write verification key
+    // write verification key, recording the program it was generated for
+    let vk = inspect::annotate(
+        serde_json::to_value(&keypair.vk).unwrap(),
+        T::name(),
+        sub_matches.value_of("proving-scheme").unwrap(),
+        &sha256_file(Path::new(sub_matches.value_of("input").unwrap()))?,
+    );
     let mut vk_file = File::create(vk_path)
         .map_err(|why| format!("couldn't create {}: {}", vk_path.display(), why))?;
     vk_file
-        .write(
-            serde_json::to_string_pretty(&keypair.vk)
-                .unwrap()
-                .as_bytes(),
-        )
+        .write(serde_json::to_string_pretty(&vk).unwrap().as_bytes())
         .map_err(|why| format!("couldn't write to {}: {}", vk_path.display(), why))?;
 
     // write proving key
@@ -149,11 +483,143 @@ fn cli_setup<T: Field, P: ProofSystem<T>>(
         .write(keypair.pk.as_ref())
         .map_err(|why| format!("couldn't write to {}: {}", pk_path.display(), why))?;
 
+    if let Some(keys) = cached_keys {
+        keys.store(pk_path, vk_path)?;
+    }
+
+    if let Some(remote) = remote {
+        remote::push_keys(&remote, pk_path, vk_path)?;
+    }
+
     println!("Setup completed.");
 
     Ok(())
 }
 
+/// Returns an interpreter executing the oracles passed with `--oracle` as subprocesses
+fn build_interpreter(sub_matches: &ArgMatches) -> Result<ir::Interpreter, String> {
+    let mut interpreter = ir::Interpreter::default();
+
+    for oracle in sub_matches.values_of("oracle").into_iter().flatten() {
+        let mut parts = oracle.splitn(2, '=');
+        let (name, command) = match (parts.next(), parts.next()) {
+            (Some(name), Some(command)) => (name, command.to_string()),
+            _ => return Err(format!("Invalid oracle {}, expected NAME=COMMAND", oracle)),
+        };
+
+        interpreter = interpreter.oracle(name, move |inputs| {
+            let output = Command::new(&command)
+                .args(inputs.iter().map(|i| i.to_string()))
+                .output()
+                .map_err(|why| format!("couldn't run {}: {}", command, why))?;
+
+            if !output.status.success() {
+                return Err(format!("{} exited with {}", command, output.status));
+            }
+
+            String::from_utf8_lossy(&output.stdout)
+                .split_whitespace()
+                .map(|o| o.parse().map_err(|_| format!("invalid output {}", o)))
+                .collect()
+        });
+    }
+
+    Ok(interpreter)
+}
+
+/// Writes a partial witness or witness fragment to a file
+fn write_partial_witness<T: Field>(
+    partial: &ir::PartialWitness<T>,
+    output_path: &Path,
+) -> Result<(), String> {
+    let output_file = File::create(&output_path)
+        .map_err(|why| format!("couldn't create {}: {}", output_path.display(), why))?;
+
+    partial
+        .write(BufWriter::new(output_file))
+        .map_err(|why| format!("could not save partial witness: {:?}", why))?;
+
+    println!(
+        "Partial witness written to '{}', {} statements pending",
+        output_path.display(),
+        partial.pending.len()
+    );
+
+    Ok(())
+}
+
+/// Reads a partial witness or witness fragment from a file
+fn read_partial_witness<T: Field>(path: &Path) -> Result<ir::PartialWitness<T>, String> {
+    let file =
+        File::open(&path).map_err(|why| format!("couldn't open {}: {}", path.display(), why))?;
+
+    ir::PartialWitness::read(BufReader::new(file))
+        .map_err(|why| format!("could not read partial witness: {}", why))
+}
+
+fn cli_compute_fragment<T: Field>(
+    ir_prog: ir::Prog<T>,
+    sub_matches: &ArgMatches,
+) -> Result<(), String> {
+    // `_` stands for the inputs held by other parties
+    let inputs = sub_matches
+        .values_of("arguments")
+        .map(|a| {
+            a.map(|x| match x {
+                "_" => Ok(None),
+                x => T::try_from_dec_str(x).map(Some).map_err(|_| x.to_string()),
+            })
+            .collect::<Result<Vec<_>, _>>()
+        })
+        .unwrap_or(Ok(vec![]))
+        .map_err(|e| format!("Could not parse argument: {}", e))?;
+
+    let fragment = build_interpreter(sub_matches)?
+        .execute_fragment(&ir_prog, &inputs)
+        .map_err(|e| format!("Execution failed: {}", e))?;
+
+    write_partial_witness(
+        &fragment,
+        Path::new(sub_matches.value_of("output").unwrap()),
+    )
+}
+
+fn cli_witness_merge<T: Field>(
+    ir_prog: ir::Prog<T>,
+    sub_matches: &ArgMatches,
+) -> Result<(), String> {
+    let mut fragments = sub_matches
+        .values_of("fragments")
+        .unwrap()
+        .map(|path| read_partial_witness::<T>(Path::new(path)));
+
+    // at least one fragment is required by clap
+    let first = fragments.next().unwrap()?;
+
+    let merged = fragments
+        .try_fold(first, |merged, fragment| {
+            merged.merge(fragment?).map_err(|e| e.to_string())
+        })
+        .map_err(|e| format!("Could not merge fragments: {}", e))?;
+
+    let witness = build_interpreter(sub_matches)?
+        .complete(&ir_prog, merged)
+        .map_err(|e| format!("Execution failed: {}", e))?;
+
+    // write witness to file
+    let output_path = Path::new(sub_matches.value_of("output").unwrap());
+    let output_file = File::create(&output_path)
+        .map_err(|why| format!("couldn't create {}: {}", output_path.display(), why))?;
+
+    witness
+        .write(BufWriter::new(output_file))
+        .map_err(|why| format!("could not save witness: {:?}", why))?;
+
+    println!("Witness written to '{}'", output_path.display());
+
+    Ok(())
+}
+
 fn cli_compute<T: Field>(ir_prog: ir::Prog<T>, sub_matches: &ArgMatches) -> Result<(), String> {
     println!("Computing witness...");
 
@@ -162,6 +628,10 @@ fn cli_compute<T: Field>(ir_prog: ir::Prog<T>, sub_matches: &ArgMatches) -> Resu
         println!("{}", ir_prog);
     }
 
+    if sub_matches.is_present("fragment") {
+        return cli_compute_fragment(ir_prog, sub_matches);
+    }
+
     let is_stdin = sub_matches.is_present("stdin");
     let is_abi = sub_matches.is_present("abi");
 
@@ -235,11 +705,48 @@ fn cli_compute<T: Field>(ir_prog: ir::Prog<T>, sub_matches: &ArgMatches) -> Resu
     }
     .map_err(|e| format!("Could not parse argument: {}", e))?;
 
-    let interpreter = ir::Interpreter::default();
+    let interpreter = build_interpreter(sub_matches)?;
 
-    let witness = interpreter
-        .execute(&ir_prog, &arguments.encode())
-        .map_err(|e| format!("Execution failed: {}", e))?;
+    let output_path = Path::new(sub_matches.value_of("output").unwrap());
+
+    if sub_matches.is_present("partial") {
+        let partial = interpreter
+            .execute_partial(&ir_prog, &arguments.encode())
+            .map_err(|e| format!("Execution failed: {}", e))?;
+
+        return write_partial_witness(&partial, output_path);
+    }
+
+    let witness = match sub_matches.value_of("resume") {
+        Some(path) => {
+            let partial = read_partial_witness(Path::new(path))?;
+            interpreter.resume(&ir_prog, partial, &arguments.encode())
+        }
+        None => match sub_matches.value_of("trace") {
+            Some(path) => {
+                let sampling = sub_matches
+                    .value_of("trace-sampling")
+                    .map(|n| n.parse::<usize>().ok().filter(|n| *n > 0))
+                    .unwrap_or(Some(1))
+                    .ok_or_else(|| String::from("Trace sampling must be a positive integer"))?;
+
+                let mut trace = ir::Trace::new(sampling);
+                let res = interpreter.execute_with_trace(&ir_prog, &arguments.encode(), &mut trace);
+
+                // the trace is written even if execution failed, as it records the failure
+                let path = Path::new(path);
+                let file = File::create(&path)
+                    .map_err(|why| format!("Couldn't create {}: {}", path.display(), why))?;
+                to_writer_pretty(BufWriter::new(file), &trace)
+                    .map_err(|why| format!("Couldn't write trace: {}", why))?;
+                println!("Trace written to '{}'", path.display());
+
+                res
+            }
+            None => interpreter.execute(&ir_prog, &arguments.encode()),
+        },
+    }
+    .map_err(|e| format!("Execution failed: {}", e))?;
 
     use zokrates_abi::Decode;
 
@@ -249,7 +756,6 @@ fn cli_compute<T: Field>(ir_prog: ir::Prog<T>, sub_matches: &ArgMatches) -> Resu
     println!("\nWitness: \n\n{}", results_json_value);
 
     // write witness to file
-    let output_path = Path::new(sub_matches.value_of("output").unwrap());
     let output_file = File::create(&output_path)
         .map_err(|why| format!("couldn't create {}: {}", output_path.display(), why))?;
 
@@ -268,12 +774,48 @@ fn cli_compile<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
 
     let light = sub_matches.occurrences_of("light") > 0;
 
+    let show_ranges = sub_matches.is_present("show-ranges");
+
+    let public_input_limits = sub_matches
+        .values_of("max-public-inputs")
+        .into_iter()
+        .flatten()
+        .try_fold(PublicInputLimits::default(), |limits, value| {
+            let mut parts = value.splitn(2, '=');
+            let template = parts.next().unwrap();
+            let max = parts
+                .next()
+                .ok_or_else(|| format!("Expected TEMPLATE=MAX, found `{}`", value))?;
+            let max =
+                match max {
+                    "none" => None,
+                    max => Some(max.parse::<usize>().map_err(|_| {
+                        format!("Invalid maximum number of public inputs `{}`", max)
+                    })?),
+                };
+            limits.set(template, max)
+        })?;
+
+    let config = CompileConfig::default()
+        .validate_inputs(sub_matches.value_of("validate-inputs").unwrap() == "true")
+        .uint_ranges(show_ranges)
+        .public_input_limits(public_input_limits);
+
     let bin_output_path = Path::new(sub_matches.value_of("output").unwrap());
 
     let abi_spec_path = Path::new(sub_matches.value_of("abi_spec").unwrap());
 
+    let source_map_path = Path::new(sub_matches.value_of("source_map").unwrap());
+
     let hr_output_path = bin_output_path.to_path_buf().with_extension("ztf");
 
+    let typed_ast_path = Path::new(sub_matches.value_of("typed-ast-path").unwrap());
+
+    let emit_typed_ast = sub_matches
+        .values_of("emit")
+        .map(|mut values| values.any(|v| v == "typed-ast"))
+        .unwrap_or(false);
+
     let file = File::open(path.clone())
         .map_err(|why| format!("Couldn't open input file {}: {}", path.display(), why))?;
 
@@ -281,29 +823,43 @@ fn cli_compile<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
     let mut source = String::new();
     reader.read_to_string(&mut source).unwrap();
 
-    let fmt_error = |e: &CompileError| {
-        let file = e.file().canonicalize().unwrap();
-        format!(
-            "{}:{}",
-            file.strip_prefix(std::env::current_dir().unwrap())
-                .unwrap_or(file.as_path())
-                .display(),
-            e.value()
-        )
+    let source = match sub_matches.value_of("function") {
+        Some(function) => {
+            println!(
+                "Compiling function `{}` as a standalone circuit\n",
+                function
+            );
+            with_entry_point(&source, function).map_err(|e| format!("{}", e))?
+        }
+        None => source,
     };
 
     let resolver = FileSystemResolver::new();
     let artifacts: CompilationArtifacts<T> =
-        compile(source, path, Some(&resolver)).map_err(|e| {
+        compile(source.clone(), path.clone(), Some(&resolver), &config).map_err(|e| {
             format!(
                 "Compilation failed:\n\n{}",
                 e.0.iter()
-                    .map(|e| fmt_error(e))
+                    .map(fmt_compile_error)
                     .collect::<Vec<_>>()
                     .join("\n\n")
             )
         })?;
 
+    for warning in artifacts.warnings() {
+        println!("{}\n", fmt_compile_warning(warning));
+    }
+
+    if emit_typed_ast {
+        // the program compiled, so checking it again cannot fail
+        let typed_ast = typed_ast::<T, _>(source, path, Some(&resolver)).unwrap();
+
+        fs::write(typed_ast_path, typed_ast)
+            .map_err(|why| format!("Couldn't write {}: {}", typed_ast_path.display(), why))?;
+
+        println!("Typed AST written to '{}'", typed_ast_path.display());
+    }
+
     let program_flattened = artifacts.prog();
 
     // number of constraints the flattened program will translate to.
@@ -327,6 +883,15 @@ fn cli_compile<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
 
     to_writer_pretty(&mut writer, &abi).map_err(|_| "Unable to write data to file.".to_string())?;
 
+    // serialize source map and write to JSON file
+    let source_map_file = File::create(&source_map_path)
+        .map_err(|why| format!("Couldn't create {}: {}", source_map_path.display(), why))?;
+
+    let mut writer = BufWriter::new(source_map_file);
+
+    to_writer_pretty(&mut writer, artifacts.source_map())
+        .map_err(|_| "Unable to write data to file.".to_string())?;
+
     if !light {
         // write human-readable output file
         let hr_output_file = File::create(&hr_output_path)
@@ -352,6 +917,48 @@ fn cli_compile<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
     }
 
     println!("Number of constraints: {}", num_constraints);
+
+    if show_ranges {
+        println!(
+            "\nRanges of uint variables, and of expressions reduced from beyond their bitwidth:"
+        );
+        for range in artifacts.uint_ranges() {
+            println!("  {}", range);
+        }
+    }
+
+    Ok(())
+}
+
+fn cli_witness_get<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
+    let source_map_path = Path::new(sub_matches.value_of("source_map").unwrap());
+    let source_map_file = File::open(&source_map_path)
+        .map_err(|why| format!("Couldn't open {}: {}", source_map_path.display(), why))?;
+
+    let source_map: ir::SourceMap<T> = from_reader(BufReader::new(source_map_file))
+        .map_err(|why| format!("Couldn't read source map: {}", why))?;
+
+    let witness_path = Path::new(sub_matches.value_of("witness").unwrap());
+    let witness_file = File::open(&witness_path)
+        .map_err(|why| format!("Couldn't open {}: {}", witness_path.display(), why))?;
+
+    let witness = ir::Witness::read(BufReader::new(witness_file))
+        .map_err(|why| format!("Couldn't read witness: {}", why))?;
+
+    let occurrence = sub_matches
+        .value_of("occurrence")
+        .map(|o| {
+            o.parse::<usize>()
+                .map_err(|_| format!("Invalid occurrence: {}", o))
+        })
+        .transpose()?;
+
+    let (_, value) = source_map
+        .evaluate(sub_matches.value_of("var").unwrap(), occurrence, &witness)
+        .map_err(|e| e.to_string())?;
+
+    println!("{}", value.to_dec_string());
+
     Ok(())
 }
 
@@ -366,30 +973,124 @@ fn cli_check<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
     let mut source = String::new();
     reader.read_to_string(&mut source).unwrap();
 
-    let fmt_error = |e: &CompileError| {
-        let file = e.file().canonicalize().unwrap();
-        format!(
-            "{}:{}",
-            file.strip_prefix(std::env::current_dir().unwrap())
-                .unwrap_or(file.as_path())
-                .display(),
-            e.value()
-        )
-    };
-
     let resolver = FileSystemResolver::new();
-    let _ = check::<T, _>(source, path, Some(&resolver)).map_err(|e| {
+    let warnings = check::<T, _>(
+        source.clone(),
+        path.clone(),
+        Some(&resolver),
+        &CompileConfig::default(),
+    )
+    .map_err(|e| {
         format!(
             "Check failed:\n\n{}",
             e.0.iter()
-                .map(|e| fmt_error(e))
+                .map(fmt_compile_error)
                 .collect::<Vec<_>>()
                 .join("\n\n")
         )
     })?;
 
+    for warning in &warnings {
+        println!("{}\n", fmt_compile_warning(warning));
+    }
+
     println!("Program checked, no errors found.");
 
+    if sub_matches.is_present("taint") {
+        let leaks =
+            taint_analysis::<T, _>(source.clone(), path.clone(), Some(&resolver)).map_err(|e| {
+                format!(
+                    "Taint analysis failed:\n\n{}",
+                    e.0.iter()
+                        .map(fmt_compile_error)
+                        .collect::<Vec<_>>()
+                        .join("\n\n")
+                )
+            })?;
+
+        match leaks.len() {
+            0 => println!("No leak of secret inputs found."),
+            n => {
+                for leak in &leaks {
+                    println!("{}", leak);
+                }
+                return Err(format!("{} leak(s) of secret inputs found", n));
+            }
+        }
+    }
+
+    if let Some(rounds) = sub_matches.value_of("differential") {
+        let rounds = rounds
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid number of rounds {}", rounds))?;
+
+        println!("Comparing the compiled program with the reference evaluator...");
+
+        let mismatch = differential_test::<T, _>(
+            source,
+            path,
+            Some(&resolver),
+            &CompileConfig::default(),
+            rounds,
+        )
+        .map_err(|e| {
+            format!(
+                "Compilation failed:\n\n{}",
+                e.0.iter()
+                    .map(fmt_compile_error)
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            )
+        })?;
+
+        match mismatch {
+            Some(mismatch) => return Err(format!("Miscompilation detected: {}", mismatch)),
+            None => println!("No difference found on {} random inputs.", rounds),
+        }
+    }
+
+    Ok(())
+}
+
+fn cli_export_smt<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
+    let path = PathBuf::from(sub_matches.value_of("input").unwrap());
+    let output_dir = Path::new(sub_matches.value_of("output").unwrap());
+
+    let source = fs::read_to_string(&path)
+        .map_err(|why| format!("Couldn't open input file {}: {}", path.display(), why))?;
+
+    let resolver = FileSystemResolver::new();
+    let conditions =
+        verification_conditions::<T, _>(source, path, Some(&resolver), &CompileConfig::default())
+            .map_err(|e| {
+            format!(
+                "Export failed:\n\n{}",
+                e.0.iter()
+                    .map(fmt_compile_error)
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            )
+        })?;
+
+    if conditions.is_empty() {
+        println!("No annotated function found");
+        return Ok(());
+    }
+
+    fs::create_dir_all(output_dir)
+        .map_err(|why| format!("Couldn't create {}: {}", output_dir.display(), why))?;
+
+    for (function, conditions) in conditions {
+        let file_path = output_dir.join(format!("{}.smt2", function));
+        fs::write(&file_path, conditions)
+            .map_err(|why| format!("Couldn't write {}: {}", file_path.display(), why))?;
+        println!(
+            "Verification conditions of `{}` written to '{}'",
+            function,
+            file_path.display()
+        );
+    }
+
     Ok(())
 }
 
@@ -411,9 +1112,22 @@ fn cli_verify<T: Field, P: ProofSystem<T>>(sub_matches: &ArgMatches) -> Result<(
         .map_err(|why| format!("Couldn't deserialize proof: {}", why))?;
 
     println!("Performing verification...");
+
+    let result = match sub_matches.is_present("hardened") {
+        true => P::verify_hardened(vk, proof)
+            .ok_or_else(|| {
+                String::from("This proving scheme does not support hardened verification")
+            })?
+            .unwrap_or_else(|e| {
+                println!("{}", e);
+                false
+            }),
+        false => P::verify(vk, proof),
+    };
+
     println!(
         "The verification result is: {}",
-        match P::verify(vk, proof) {
+        match result {
             true => "PASS",
             false => "FAIL",
         }
@@ -422,9 +1136,290 @@ fn cli_verify<T: Field, P: ProofSystem<T>>(sub_matches: &ArgMatches) -> Result<(
     Ok(())
 }
 
+fn new_project(path: &Path, template_name: &str) -> Result<(), String> {
+    let template = Template::try_from(template_name)?;
+
+    if path.exists() {
+        return Err(format!("Destination {} already exists", path.display()));
+    }
+
+    let name = path
+        .file_name()
+        .ok_or_else(|| format!("Invalid project path {}", path.display()))?
+        .to_string_lossy();
+
+    fs::create_dir_all(path)
+        .map_err(|why| format!("Couldn't create {}: {}", path.display(), why))?;
+
+    for (file, content) in templates::files(&template) {
+        let file_path = path.join(file);
+        let content = content
+            .replace("{{name}}", &name)
+            .replace("{{template}}", template_name);
+        fs::write(&file_path, content)
+            .map_err(|why| format!("Couldn't write {}: {}", file_path.display(), why))?;
+    }
+
+    Ok(())
+}
+
+/// Formats a compilation error with its code, locating it relative to the current directory
+fn fmt_compile_error(e: &CompileError) -> String {
+    let file = e.file().canonicalize().unwrap();
+    format!(
+        "error[{}]: {}:{}",
+        e.value().code(),
+        file.strip_prefix(std::env::current_dir().unwrap())
+            .unwrap_or(file.as_path())
+            .display(),
+        e.value()
+    )
+}
+
+fn fmt_compile_warning(w: &CompileWarning) -> String {
+    let file = w.file().canonicalize().unwrap();
+    format!(
+        "warning: {}:{}",
+        file.strip_prefix(std::env::current_dir().unwrap())
+            .unwrap_or(file.as_path())
+            .display(),
+        w
+    )
+}
+
+fn cli_doc(sub_matches: &ArgMatches) -> Result<(), String> {
+    let input = Path::new(sub_matches.value_of("input").unwrap());
+    let output = Path::new(sub_matches.value_of("output").unwrap());
+    let format = sub_matches.value_of("format").unwrap();
+
+    let input = input
+        .canonicalize()
+        .map_err(|why| format!("Couldn't open {}: {}", input.display(), why))?;
+
+    // page names are relative to the documented directory, or to the directory of the documented program
+    let (inputs, root) = if input.is_dir() {
+        (doc::modules(&input)?, input)
+    } else {
+        let root = input.parent().unwrap().to_path_buf();
+        (vec![input], root)
+    };
+
+    let pages = doc::pages(inputs, &root)?;
+    doc::write(&pages, output, format)?;
+
+    println!(
+        "Documentation of {} module(s) written to {}",
+        pages.len(),
+        output.display()
+    );
+
+    Ok(())
+}
+
+fn cli_explain(sub_matches: &ArgMatches) -> Result<(), String> {
+    let code = sub_matches.value_of("code").unwrap();
+    let error = explain(code).ok_or_else(|| format!("Unknown error code {}", code))?;
+
+    println!("{}: {}\n\n{}", error.code, error.title, error.explanation);
+
+    Ok(())
+}
+
+fn cli_new(sub_matches: &ArgMatches) -> Result<(), String> {
+    let path = Path::new(sub_matches.value_of("path").unwrap());
+    let template = sub_matches.value_of("template").unwrap();
+
+    new_project(path, template)?;
+
+    println!(
+        "Created project in {} from the {} template",
+        path.display(),
+        template
+    );
+
+    Ok(())
+}
+
+fn cli_test(sub_matches: &ArgMatches) -> Result<(), String> {
+    let work_dir = env::temp_dir().join(format!("zokrates-test-{}", std::process::id()));
+    fs::create_dir_all(&work_dir)
+        .map_err(|why| format!("Couldn't create {}: {}", work_dir.display(), why))?;
+
+    match run_test(&work_dir, sub_matches) {
+        Ok(()) => {
+            fs::remove_dir_all(&work_dir)
+                .map_err(|why| format!("Couldn't remove {}: {}", work_dir.display(), why))?;
+            println!("Test passed.");
+            Ok(())
+        }
+        Err(e) => Err(format!(
+            "Test failed: {}\nArtifacts were kept in {}",
+            e,
+            work_dir.display()
+        )),
+    }
+}
+
+/// Runs the whole pipeline on the program, from compilation to verification, writing artifacts to `work_dir`
+fn run_test(work_dir: &Path, sub_matches: &ArgMatches) -> Result<(), String> {
+    let zokrates = env::current_exe()
+        .map_err(|why| format!("Couldn't locate the zokrates executable: {}", why))?;
+
+    let curve = sub_matches.value_of("curve").unwrap();
+    let backend = sub_matches.value_of("backend").unwrap();
+    let scheme = sub_matches.value_of("proving-scheme").unwrap();
+    let evm = sub_matches.is_present("evm");
+
+    if evm && curve != constants::BN128 {
+        return Err(format!(
+            "Verifying on the EVM requires the {} curve, found {}",
+            constants::BN128,
+            curve
+        ));
+    }
+
+    let program_path = Path::new(sub_matches.value_of("input").unwrap());
+    let flattened_path = work_dir.join("out");
+    let abi_spec_path = work_dir.join("abi.json");
+    let witness_path = work_dir.join("witness");
+    let proving_key_path = work_dir.join("proving.key");
+    let verification_key_path = work_dir.join("verification.key");
+    let proof_path = work_dir.join("proof.json");
+    let verifier_path = work_dir.join("verifier.sol");
+
+    run(Command::new(&zokrates)
+        .arg("compile")
+        .arg("-i")
+        .arg(program_path)
+        .arg("-o")
+        .arg(&flattened_path)
+        .arg("-s")
+        .arg(&abi_spec_path)
+        .args(&["-c", curve, "--light"]))?;
+
+    let mut compute_witness = Command::new(&zokrates);
+    compute_witness
+        .arg("compute-witness")
+        .arg("-i")
+        .arg(&flattened_path)
+        .arg("-s")
+        .arg(&abi_spec_path)
+        .arg("-o")
+        .arg(&witness_path)
+        .arg("--light");
+    if let Some(arguments) = sub_matches.values_of("arguments") {
+        compute_witness.arg("-a").args(arguments);
+    }
+    run(&mut compute_witness)?;
+
+    run(Command::new(&zokrates)
+        .arg("setup")
+        .arg("-i")
+        .arg(&flattened_path)
+        .arg("-p")
+        .arg(&proving_key_path)
+        .arg("-v")
+        .arg(&verification_key_path)
+        .args(&["-b", backend, "-s", scheme, "--light"]))?;
+
+    run(Command::new(&zokrates)
+        .arg("generate-proof")
+        .arg("-i")
+        .arg(&flattened_path)
+        .arg("-w")
+        .arg(&witness_path)
+        .arg("-p")
+        .arg(&proving_key_path)
+        .arg("-j")
+        .arg(&proof_path)
+        .args(&["-b", backend, "-s", scheme]))?;
+
+    // `verify` reports a failed verification in its output rather than in its exit status
+    let verification = Command::new(&zokrates)
+        .arg("verify")
+        .arg("-j")
+        .arg(&proof_path)
+        .arg("-v")
+        .arg(&verification_key_path)
+        .args(&["-c", curve, "-b", backend, "-s", scheme])
+        .output()
+        .map_err(|why| format!("Couldn't run verify: {}", why))?;
+    if !String::from_utf8_lossy(&verification.stdout).contains("PASS") {
+        return Err(String::from(
+            "The proof was rejected by the verification key",
+        ));
+    }
+
+    if evm {
+        let abi = sub_matches.value_of("solidity-abi").unwrap();
+
+        run(Command::new(&zokrates)
+            .arg("export-verifier")
+            .arg("-i")
+            .arg(&verification_key_path)
+            .arg("-o")
+            .arg(&verifier_path)
+            .args(&["-c", curve, "-b", backend, "-s", scheme, "-a", abi]))?;
+
+        evm::verify(
+            Path::new(sub_matches.value_of("harness-dir").unwrap()),
+            &verifier_path,
+            &proof_path,
+            scheme,
+            abi,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn cli_conformance(sub_matches: &ArgMatches) -> Result<(), String> {
+    let dir = Path::new(sub_matches.value_of("path").unwrap());
+    let files = zokrates_test::test_files(dir)?;
+
+    let mut failures = vec![];
+
+    for file in &files {
+        match zokrates_test::run_test_file(file) {
+            Ok(()) => println!("{} ... ok", file.display()),
+            Err(e) => {
+                println!("{} ... FAILED", file.display());
+                failures.push((file, e));
+            }
+        }
+    }
+
+    for (file, e) in &failures {
+        println!("\n---- {} ----\n{}", file.display(), e);
+    }
+
+    println!(
+        "\n{} passed, {} failed",
+        files.len() - failures.len(),
+        failures.len()
+    );
+
+    match failures.len() {
+        0 => Ok(()),
+        n => Err(format!("{} of {} test files failed", n, files.len())),
+    }
+}
+
+/// Returns the value of an argument given to a command or to any of its nested subcommands
+fn find_value<'a>(matches: &'a ArgMatches, name: &str) -> Option<&'a str> {
+    matches.value_of(name).or_else(|| {
+        matches
+            .subcommand()
+            .1
+            .and_then(|sub_matches| find_value(sub_matches, name))
+    })
+}
+
 fn cli() -> Result<(), String> {
     const FLATTENED_CODE_DEFAULT_PATH: &str = "out";
     const ABI_SPEC_DEFAULT_PATH: &str = "abi.json";
+    const SOURCE_MAP_DEFAULT_PATH: &str = "source_map.json";
+    const TYPED_AST_DEFAULT_PATH: &str = "typed_ast.json";
     const VERIFICATION_KEY_DEFAULT_PATH: &str = "verification.key";
     const PROVING_KEY_DEFAULT_PATH: &str = "proving.key";
     const VERIFICATION_CONTRACT_DEFAULT_PATH: &str = "verifier.sol";
@@ -434,6 +1429,10 @@ fn cli() -> Result<(), String> {
     let default_backend = env::var("ZOKRATES_BACKEND").unwrap_or(constants::BELLMAN.into());
     let default_scheme = env::var("ZOKRATES_PROVING_SCHEME").unwrap_or(constants::G16.into());
     let default_solidity_abi = "v1";
+    let default_harness_dir = env::temp_dir()
+        .join("zokrates-evm")
+        .to_string_lossy()
+        .into_owned();
 
     // cli specification using clap library
     let matches = App::new("ZoKrates")
@@ -441,6 +1440,14 @@ fn cli() -> Result<(), String> {
     .version(env!("CARGO_PKG_VERSION"))
     .author("Jacob Eberhardt, Thibaut Schaeffer, Stefan Deml")
     .about("Supports generation of zkSNARKs from high level language code including Smart Contracts for proof verification on the Ethereum Blockchain.\n'I know that I show nothing!'")
+    .arg(Arg::with_name("field-modulus")
+        .long("field-modulus")
+        .help("Prime modulus of the `custom` curve, in decimal or in hexadecimal with a `0x` prefix. Required to compile for this curve and to read the programs compiled for it")
+        .value_name("MODULUS")
+        .takes_value(true)
+        .global(true)
+        .required(false)
+    )
     .subcommand(SubCommand::with_name("compile")
         .about("Compiles into flattened conditions. Produces two files: human-readable '.ztf' file for debugging and binary file")
         .arg(Arg::with_name("input")
@@ -458,6 +1465,14 @@ fn cli() -> Result<(), String> {
             .takes_value(true)
             .required(false)
             .default_value(ABI_SPEC_DEFAULT_PATH)
+        ).arg(Arg::with_name("source_map")
+            .short("m")
+            .long("source-map")
+            .help("Path of the source map, locating the variables of `main` in the witness")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(false)
+            .default_value(SOURCE_MAP_DEFAULT_PATH)
         ).arg(Arg::with_name("output")
             .short("o")
             .long("output")
@@ -478,6 +1493,48 @@ fn cli() -> Result<(), String> {
             .long("light")
             .help("Skip logs and human readable output")
             .required(false)
+        ).arg(Arg::with_name("validate-inputs")
+            .long("validate-inputs")
+            .help("Constrain public boolean and unsigned integer inputs to be in range")
+            .value_name("BOOL")
+            .takes_value(true)
+            .required(false)
+            .possible_values(&["true", "false"])
+            .default_value("true")
+        ).arg(Arg::with_name("emit")
+            .long("emit")
+            .help("Additional compilation artifacts to write")
+            .value_name("ARTIFACT")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .required(false)
+            .possible_values(&["typed-ast"])
+        ).arg(Arg::with_name("typed-ast-path")
+            .long("typed-ast-path")
+            .help("Path of the typed AST, written with `--emit typed-ast`")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(false)
+            .default_value(TYPED_AST_DEFAULT_PATH)
+        ).arg(Arg::with_name("function")
+            .long("function")
+            .help("Function of the module to compile instead of `main`, called from a `main` generated from its signature")
+            .value_name("NAME")
+            .takes_value(true)
+            .required(false)
+        ).arg(Arg::with_name("show-ranges")
+            .long("show-ranges")
+            .help("Show the number of bits inferred for each unsigned integer variable, and how many times it is reduced to its bitwidth")
+            .required(false)
+        ).arg(Arg::with_name("max-public-inputs")
+            .long("max-public-inputs")
+            .help("Maximum number of public inputs of a verifier template, above which compilation warns, or `none` not to check the template. The defaults target Ethereum mainnet")
+            .value_name("TEMPLATE=MAX")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .required(false)
         )
      )
     .subcommand(SubCommand::with_name("check")
@@ -497,6 +1554,43 @@ fn cli() -> Result<(), String> {
             .required(false)
             .possible_values(CURVES)
             .default_value(&default_curve)
+        ).arg(Arg::with_name("differential")
+            .long("differential")
+            .help("Also compile the program and compare its outputs with those of a slow reference evaluator on the given number of random inputs")
+            .value_name("ROUNDS")
+            .takes_value(true)
+            .required(false)
+        ).arg(Arg::with_name("taint")
+            .long("taint")
+            .help("Check that the private inputs annotated with `@secret` do not leak to public inputs and outputs")
+            .required(false)
+        )
+     )
+    .subcommand(SubCommand::with_name("export-smt")
+        .about("Exports the verification conditions of functions annotated with `@requires` and `@ensures` to SMT-LIB")
+        .arg(Arg::with_name("input")
+            .short("i")
+            .long("input")
+            .help("Path of the source code")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(true)
+        ).arg(Arg::with_name("output")
+            .short("o")
+            .long("output")
+            .help("Directory in which to write one SMT-LIB script per annotated function")
+            .value_name("DIR")
+            .takes_value(true)
+            .required(false)
+            .default_value("smt")
+        ).arg(Arg::with_name("curve")
+            .short("c")
+            .long("curve")
+            .help("Curve to be used in the compilation")
+            .takes_value(true)
+            .required(false)
+            .possible_values(CURVES)
+            .default_value(&default_curve)
         )
      )
     .subcommand(SubCommand::with_name("setup")
@@ -545,6 +1639,116 @@ fn cli() -> Result<(), String> {
             .long("light")
             .help("Skip logging the human-readable program and writing it to a file")
             .required(false)
+        ).arg(Arg::with_name("cache-dir")
+            .long("cache-dir")
+            .help("Directory in which keys are cached by program hash. Keys of a program found in the cache are reused instead of running the setup")
+            .value_name("DIR")
+            .takes_value(true)
+            .required(false)
+        ).arg(Arg::with_name("key-uri")
+            .long("key-uri")
+            .help("Remote location to which the keys are uploaded, as file://, s3://, gs:// or http(s):// URI")
+            .value_name("URI")
+            .takes_value(true)
+            .required(false)
+        )
+    )
+    .subcommand(SubCommand::with_name("export-envelope")
+        .about("Exports a Solidity library and TypeScript helpers wrapping proof submission in an EIP-712 message signed by the prover")
+        .arg(Arg::with_name("output")
+            .short("o")
+            .long("output")
+            .help("Directory in which the helpers are written")
+            .value_name("DIR")
+            .takes_value(true)
+            .required(false)
+            .default_value(".")
+        )
+    )
+    .subcommand(SubCommand::with_name("diff")
+        .about("Reports the differences between two compiled programs: number of constraints, public input layout and, if given, ABI and variables of `main`. Exits with an error if they differ")
+        .arg(Arg::with_name("old")
+            .help("Path of the old binary")
+            .value_name("OLD")
+            .index(1)
+            .required(true)
+        ).arg(Arg::with_name("new")
+            .help("Path of the new binary")
+            .value_name("NEW")
+            .index(2)
+            .required(true)
+        ).arg(Arg::with_name("old-abi")
+            .long("old-abi")
+            .help("Path of the ABI specification of the old program")
+            .value_name("FILE")
+            .takes_value(true)
+            .requires("new-abi")
+            .required(false)
+        ).arg(Arg::with_name("new-abi")
+            .long("new-abi")
+            .help("Path of the ABI specification of the new program")
+            .value_name("FILE")
+            .takes_value(true)
+            .requires("old-abi")
+            .required(false)
+        ).arg(Arg::with_name("old-source-map")
+            .long("old-source-map")
+            .help("Path of the source map of the old program")
+            .value_name("FILE")
+            .takes_value(true)
+            .requires("new-source-map")
+            .required(false)
+        ).arg(Arg::with_name("new-source-map")
+            .long("new-source-map")
+            .help("Path of the source map of the new program")
+            .value_name("FILE")
+            .takes_value(true)
+            .requires("old-source-map")
+            .required(false)
+        )
+    )
+    .subcommand(SubCommand::with_name("abi-check")
+        .about("Classifies the changes between two ABI specifications as compatible or breaking for verifiers. Exits with an error if a change is breaking")
+        .arg(Arg::with_name("old")
+            .help("Path of the old ABI specification")
+            .value_name("OLD")
+            .index(1)
+            .required(true)
+        ).arg(Arg::with_name("new")
+            .help("Path of the new ABI specification")
+            .value_name("NEW")
+            .index(2)
+            .required(true)
+        )
+    )
+    .subcommand(SubCommand::with_name("lint")
+        .about("Reports trivially satisfied constraints, variables fixed to constants, unused public inputs and public outputs which do not depend on any private input. Exits with an error if any is found")
+        .arg(Arg::with_name("input")
+            .short("i")
+            .long("input")
+            .help("Path of the binary")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(false)
+            .default_value(FLATTENED_CODE_DEFAULT_PATH)
+        ).arg(Arg::with_name("source_map")
+            .short("m")
+            .long("source-map")
+            .help("Path of the source map, used to locate the issues")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(false)
+            .default_value(SOURCE_MAP_DEFAULT_PATH)
+        )
+    )
+    .subcommand(SubCommand::with_name("inspect")
+        .about("Prints the fingerprint of a verification key, its curve, proving scheme, number of public inputs and the hash of its program")
+        .arg(Arg::with_name("input")
+            .help("Path of the verification key")
+            .value_name("FILE")
+            .index(1)
+            .required(false)
+            .default_value(VERIFICATION_KEY_DEFAULT_PATH)
         )
     )
     .subcommand(SubCommand::with_name("export-verifier")
@@ -598,6 +1802,50 @@ fn cli() -> Result<(), String> {
             .possible_values(&["v1", "v2"])
             .default_value(&default_solidity_abi)
             .required(false)
+        ).arg(Arg::with_name("template")
+            .long("template")
+            .help("Template of the verifier. `yul` inlines the verification key in assembly calling the precompiles directly, which minimizes gas costs and exposes the v1 ABI")
+            .takes_value(true)
+            .possible_values(&["solidity", "yul"])
+            .default_value("solidity")
+            .required(false)
+        ).arg(Arg::with_name("dynamic-vk")
+            .long("dynamic-vk")
+            .help("Export a verifier taking the verification key as an argument or from a registry contract instead of embedding it. Requires the v2 Solidity ABI")
+            .required(false)
+        ).arg(Arg::with_name("input-hash")
+            .long("input-hash")
+            .help("Add an `inputHash` function to the verifier, returning the hash of public inputs after checking that they are reduced, which identifies them uniquely")
+            .required(false)
+        ).arg(Arg::with_name("hardened")
+            .long("hardened")
+            .help("Export a verifier which also rejects the proofs whose coordinates are not reduced or which contain the point at infinity. Only supported by G16")
+            .required(false)
+        ).arg(Arg::with_name("gas-report")
+            .long("gas-report")
+            .help("Deploy the exported verifier to an in-process EVM, verify the proof given with `--proof`, and report the gas used by both. Requires node and npm")
+            .required(false)
+        ).arg(Arg::with_name("tests")
+            .long("tests")
+            .help("Write tests checking that the exported verifier accepts the proof given with `--proof` and rejects it once falsified, next to the verifier")
+            .value_name("FRAMEWORK")
+            .takes_value(true)
+            .required(false)
+            .possible_values(&["hardhat", "foundry"])
+        ).arg(Arg::with_name("proof")
+            .long("proof")
+            .help("Path of the JSON proof file used to measure the verification gas and to generate tests")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(false)
+            .default_value(JSON_PROOF_PATH)
+        ).arg(Arg::with_name("harness-dir")
+            .long("harness-dir")
+            .help("Directory where the EVM test harness and its dependencies are installed")
+            .value_name("DIR")
+            .takes_value(true)
+            .required(false)
+            .default_value(&default_harness_dir)
         )
     )
     .subcommand(SubCommand::with_name("compute-witness")
@@ -649,6 +1897,133 @@ fn cli() -> Result<(), String> {
             .long("light")
             .help("Skip logging the human-readable program")
             .required(false)
+        ).arg(Arg::with_name("partial")
+            .long("partial")
+            .help("Only execute the part of the program depending on public inputs, which are the expected arguments, and write the partial witness to the output file")
+            .conflicts_with("abi")
+            .conflicts_with("resume")
+            .required(false)
+        ).arg(Arg::with_name("resume")
+            .long("resume")
+            .help("Complete a partial witness computed with `--partial`, the expected arguments being the private inputs")
+            .value_name("FILE")
+            .takes_value(true)
+            .conflicts_with("abi")
+            .required(false)
+        ).arg(Arg::with_name("fragment")
+            .long("fragment")
+            .help("Execute the part of the program depending on the inputs known to this party and write the witness fragment to the output file. Inputs held by other parties are passed as `_`")
+            .conflicts_with("abi")
+            .conflicts_with("stdin")
+            .conflicts_with("partial")
+            .conflicts_with("resume")
+            .required(false)
+        ).arg(Arg::with_name("oracle")
+            .long("oracle")
+            .help("Executable providing the values of an oracle, called with the oracle inputs as decimal arguments and printing its outputs separated by whitespace")
+            .value_name("NAME=COMMAND")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .required(false)
+        ).arg(Arg::with_name("trace")
+            .long("trace")
+            .help("Path of a JSON file recording the executed statements and the values they operate on, as well as the error if execution fails")
+            .value_name("FILE")
+            .takes_value(true)
+            .conflicts_with("partial")
+            .conflicts_with("resume")
+            .conflicts_with("fragment")
+            .required(false)
+        ).arg(Arg::with_name("trace-sampling")
+            .long("trace-sampling")
+            .help("Only record the statements whose index is a multiple of N in the trace. Defaults to every statement")
+            .value_name("N")
+            .takes_value(true)
+            .requires("trace")
+            .required(false)
+        )
+    )
+    .subcommand(SubCommand::with_name("witness")
+        .about("Inspects and assembles witnesses")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(SubCommand::with_name("get")
+            .about("Prints the value of a variable of `main` in a witness")
+            .arg(Arg::with_name("var")
+                .long("var")
+                .help("Name of the variable, like `a`, `a[0]` or `s.member`")
+                .value_name("NAME")
+                .takes_value(true)
+                .required(true)
+            ).arg(Arg::with_name("occurrence")
+                .short("n")
+                .long("occurrence")
+                .help("Occurrence of the variable, `0` being its declaration and `n` its value after `n` assignments. Defaults to the last occurrence")
+                .value_name("N")
+                .takes_value(true)
+                .required(false)
+            ).arg(Arg::with_name("witness")
+                .short("w")
+                .long("witness")
+                .help("Path of the witness file")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(false)
+                .default_value(WITNESS_DEFAULT_PATH)
+            ).arg(Arg::with_name("source_map")
+                .short("m")
+                .long("source-map")
+                .help("Path of the source map")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(false)
+                .default_value(SOURCE_MAP_DEFAULT_PATH)
+            ).arg(Arg::with_name("curve")
+                .short("c")
+                .long("curve")
+                .help("Curve used in the compilation")
+                .takes_value(true)
+                .required(false)
+                .possible_values(CURVES)
+                .default_value(&default_curve)
+            )
+        )
+        .subcommand(SubCommand::with_name("merge")
+            .about("Merges witness fragments computed with `compute-witness --fragment` and completes the witness")
+            .arg(Arg::with_name("input")
+                .short("i")
+                .long("input")
+                .help("Path of the binary")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(false)
+                .default_value(FLATTENED_CODE_DEFAULT_PATH)
+            ).arg(Arg::with_name("fragments")
+                .short("f")
+                .long("fragment")
+                .help("Path of a witness fragment")
+                .value_name("FILE")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .required(true)
+            ).arg(Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .help("Path of the output file")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(false)
+                .default_value(WITNESS_DEFAULT_PATH)
+            ).arg(Arg::with_name("oracle")
+                .long("oracle")
+                .help("Executable providing the values of an oracle, called with the oracle inputs as decimal arguments and printing its outputs separated by whitespace")
+                .value_name("NAME=COMMAND")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .required(false)
+            )
         )
     )
     .subcommand(SubCommand::with_name("generate-proof")
@@ -702,6 +2077,19 @@ fn cli() -> Result<(), String> {
             .required(false)
             .possible_values(SCHEMES)
             .default_value(&default_scheme)
+        ).arg(Arg::with_name("cache-dir")
+            .long("cache-dir")
+            .help("Directory in which keys are cached by program hash. The proving key of the program is taken from the cache unless `--proving-key-path` is given")
+            .value_name("DIR")
+            .takes_value(true)
+            .required(false)
+        ).arg(Arg::with_name("key-uri")
+            .long("key-uri")
+            .help("Remote location from which the keys are downloaded to the cache when they are missing from it, as file://, s3://, gs://, ipfs:// or http(s):// URI")
+            .value_name("URI")
+            .takes_value(true)
+            .required(false)
+            .requires("cache-dir")
         )
     )
      .subcommand(SubCommand::with_name("print-proof")
@@ -766,16 +2154,161 @@ fn cli() -> Result<(), String> {
             .required(false)
             .possible_values(CURVES)
             .default_value(&default_curve)
+        ).arg(Arg::with_name("hardened")
+            .long("hardened")
+            .help("Reject the proofs whose points are the point at infinity or outside of the subgroup of prime order, or whose inputs are not reduced, instead of only checking the pairing equation. Only supported by G16")
+            .required(false)
+        )
+    )
+    .subcommand(SubCommand::with_name("test")
+        .about("Compiles a program, runs the setup, generates a proof and checks that it verifies, optionally with the exported Solidity verifier on an in-process EVM")
+        .arg(Arg::with_name("input")
+            .short("i")
+            .long("input")
+            .help("Path of the source code")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(true)
+        ).arg(Arg::with_name("arguments")
+            .short("a")
+            .long("arguments")
+            .help("Arguments for the program's main function. Expects a space-separated list of field elements like `-a 1 2 3`")
+            .takes_value(true)
+            .multiple(true) // allows multiple values
+            .required(false)
+        ).arg(Arg::with_name("curve")
+            .short("c")
+            .long("curve")
+            .help("Curve to be used in the compilation")
+            .takes_value(true)
+            .required(false)
+            .possible_values(CURVES)
+            .default_value(&default_curve)
+        ).arg(Arg::with_name("backend")
+            .short("b")
+            .long("backend")
+            .help("Backend to use")
+            .takes_value(true)
+            .required(false)
+            .possible_values(BACKENDS)
+            .default_value(&default_backend)
+        ).arg(Arg::with_name("proving-scheme")
+            .short("s")
+            .long("proving-scheme")
+            .help("Proving scheme to use")
+            .takes_value(true)
+            .required(false)
+            .possible_values(SCHEMES)
+            .default_value(&default_scheme)
+        ).arg(Arg::with_name("evm")
+            .long("evm")
+            .help("Deploy the exported verifier to an in-process EVM and check that `verifyTx` accepts the proof and rejects a tampered one. Requires node and npm")
+            .required(false)
+        ).arg(Arg::with_name("solidity-abi")
+            .long("solidity-abi")
+            .help("Flag for setting the version of the ABI Encoder used in the contract")
+            .takes_value(true)
+            .possible_values(&["v1", "v2"])
+            .default_value(&default_solidity_abi)
+            .required(false)
+        ).arg(Arg::with_name("harness-dir")
+            .long("harness-dir")
+            .help("Directory where the EVM test harness and its dependencies are installed")
+            .value_name("DIR")
+            .takes_value(true)
+            .required(false)
+            .default_value(&default_harness_dir)
+        )
+    )
+    .subcommand(SubCommand::with_name("conformance")
+        .about("Runs a directory of JSON test cases, each describing a program, its inputs and its expected outputs or failure, on the listed curves and proving schemes")
+        .arg(Arg::with_name("path")
+            .help("Directory of the test cases")
+            .value_name("DIR")
+            .takes_value(true)
+            .required(true)
+            .index(1)
+        )
+    )
+    .subcommand(SubCommand::with_name("new")
+        .about("Creates a new project from a template, with a circuit using the standard library, a zokrates.js integration script and a verifier deployment script")
+        .arg(Arg::with_name("path")
+            .help("Path of the project to create")
+            .value_name("PATH")
+            .takes_value(true)
+            .required(true)
+            .index(1)
+        ).arg(Arg::with_name("template")
+            .short("t")
+            .long("template")
+            .help("Template to use")
+            .takes_value(true)
+            .required(false)
+            .possible_values(TEMPLATES)
+            .default_value(MIXER)
+        )
+    )
+    .subcommand(SubCommand::with_name("doc")
+        .about("Generates the documentation of a program and of the modules it imports, or of all modules in a directory, from their doc comments")
+        .arg(Arg::with_name("input")
+            .short("i")
+            .long("input")
+            .help("Path of the program or of the directory to document")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(true)
+        ).arg(Arg::with_name("output")
+            .short("o")
+            .long("output")
+            .help("Path of the output directory")
+            .value_name("DIR")
+            .takes_value(true)
+            .required(false)
+            .default_value("doc")
+        ).arg(Arg::with_name("format")
+            .short("f")
+            .long("format")
+            .help("Format of the documentation")
+            .value_name("FORMAT")
+            .takes_value(true)
+            .required(false)
+            .possible_values(doc::FORMATS)
+            .default_value(doc::HTML)
+        )
+    )
+    .subcommand(SubCommand::with_name("explain")
+        .about("Prints an extended description of a compiler error, with an example and how to fix it")
+        .arg(Arg::with_name("code")
+            .help("Code of the error, for example E0005")
+            .value_name("CODE")
+            .takes_value(true)
+            .required(true)
+            .index(1)
         )
     )
     .get_matches();
 
+    // the modulus must be set before any element of the custom field is created
+    match find_value(&matches, "field-modulus") {
+        Some(modulus) => {
+            zokrates_field::custom::set_modulus(zokrates_field::custom::parse_modulus(modulus)?)?
+        }
+        None if find_value(&matches, "curve") == Some(constants::CUSTOM) => {
+            return Err(String::from("The custom curve requires --field-modulus"))
+        }
+        None => {}
+    }
+
     match matches.subcommand() {
         ("compile", Some(sub_matches)) => {
             let curve = Curve::try_from(sub_matches.value_of("curve").unwrap())?;
             match curve {
                 Curve::Bn128 => cli_compile::<Bn128Field>(sub_matches)?,
                 Curve::Bls12 => cli_compile::<Bls12Field>(sub_matches)?,
+                Curve::Pallas => cli_compile::<PallasField>(sub_matches)?,
+                Curve::Vesta => cli_compile::<VestaField>(sub_matches)?,
+                Curve::Goldilocks => cli_compile::<GoldilocksField>(sub_matches)?,
+                Curve::Custom => cli_compile::<CustomField>(sub_matches)?,
             }
         }
         ("check", Some(sub_matches)) => {
@@ -784,6 +2317,23 @@ fn cli() -> Result<(), String> {
             match curve {
                 constants::BN128 => cli_check::<Bn128Field>(sub_matches)?,
                 constants::BLS12_381 => cli_check::<Bls12Field>(sub_matches)?,
+                constants::PALLAS => cli_check::<PallasField>(sub_matches)?,
+                constants::VESTA => cli_check::<VestaField>(sub_matches)?,
+                constants::GOLDILOCKS => cli_check::<GoldilocksField>(sub_matches)?,
+                constants::CUSTOM => cli_check::<CustomField>(sub_matches)?,
+                _ => unreachable!(),
+            }
+        }
+        ("export-smt", Some(sub_matches)) => {
+            let curve = sub_matches.value_of("curve").unwrap();
+
+            match curve {
+                constants::BN128 => cli_export_smt::<Bn128Field>(sub_matches)?,
+                constants::BLS12_381 => cli_export_smt::<Bls12Field>(sub_matches)?,
+                constants::PALLAS => cli_export_smt::<PallasField>(sub_matches)?,
+                constants::VESTA => cli_export_smt::<VestaField>(sub_matches)?,
+                constants::GOLDILOCKS => cli_export_smt::<GoldilocksField>(sub_matches)?,
+                constants::CUSTOM => cli_export_smt::<CustomField>(sub_matches)?,
                 _ => unreachable!(),
             }
         }
@@ -798,8 +2348,43 @@ fn cli() -> Result<(), String> {
             match ProgEnum::deserialize(&mut reader)? {
                 ProgEnum::Bn128Program(p) => cli_compute(p, sub_matches)?,
                 ProgEnum::Bls12Program(p) => cli_compute(p, sub_matches)?,
+                ProgEnum::PallasProgram(p) => cli_compute(p, sub_matches)?,
+                ProgEnum::VestaProgram(p) => cli_compute(p, sub_matches)?,
+                ProgEnum::GoldilocksProgram(p) => cli_compute(p, sub_matches)?,
+                ProgEnum::CustomProgram(p) => cli_compute(p, sub_matches)?,
             }
         }
+        ("witness", Some(sub_matches)) => match sub_matches.subcommand() {
+            ("get", Some(sub_matches)) => {
+                let curve = Curve::try_from(sub_matches.value_of("curve").unwrap())?;
+                match curve {
+                    Curve::Bn128 => cli_witness_get::<Bn128Field>(sub_matches)?,
+                    Curve::Bls12 => cli_witness_get::<Bls12Field>(sub_matches)?,
+                    Curve::Pallas => cli_witness_get::<PallasField>(sub_matches)?,
+                    Curve::Vesta => cli_witness_get::<VestaField>(sub_matches)?,
+                    Curve::Goldilocks => cli_witness_get::<GoldilocksField>(sub_matches)?,
+                    Curve::Custom => cli_witness_get::<CustomField>(sub_matches)?,
+                }
+            }
+            ("merge", Some(sub_matches)) => {
+                // read compiled program
+                let path = Path::new(sub_matches.value_of("input").unwrap());
+                let file = File::open(&path)
+                    .map_err(|why| format!("Couldn't open {}: {}", path.display(), why))?;
+
+                let mut reader = BufReader::new(file);
+
+                match ProgEnum::deserialize(&mut reader)? {
+                    ProgEnum::Bn128Program(p) => cli_witness_merge(p, sub_matches)?,
+                    ProgEnum::Bls12Program(p) => cli_witness_merge(p, sub_matches)?,
+                    ProgEnum::PallasProgram(p) => cli_witness_merge(p, sub_matches)?,
+                    ProgEnum::VestaProgram(p) => cli_witness_merge(p, sub_matches)?,
+                    ProgEnum::GoldilocksProgram(p) => cli_witness_merge(p, sub_matches)?,
+                    ProgEnum::CustomProgram(p) => cli_witness_merge(p, sub_matches)?,
+                }
+            }
+            _ => unreachable!(),
+        },
         ("setup", Some(sub_matches)) => {
             // read compiled program
             let path = Path::new(sub_matches.value_of("input").unwrap());
@@ -814,6 +2399,10 @@ fn cli() -> Result<(), String> {
                 match prog {
                     ProgEnum::Bn128Program(_) => constants::BN128,
                     ProgEnum::Bls12Program(_) => constants::BLS12_381,
+                    ProgEnum::PallasProgram(_) => constants::PALLAS,
+                    ProgEnum::VestaProgram(_) => constants::VESTA,
+                    ProgEnum::GoldilocksProgram(_) => constants::GOLDILOCKS,
+                    ProgEnum::CustomProgram(_) => constants::CUSTOM,
                 },
                 sub_matches.value_of("proving-scheme").unwrap(),
             ))?;
@@ -822,6 +2411,8 @@ fn cli() -> Result<(), String> {
                 Dimensions(Backend::Bellman, _, ProvingScheme::G16) => match prog {
                     ProgEnum::Bn128Program(p) => cli_setup::<_, G16>(p, sub_matches),
                     ProgEnum::Bls12Program(p) => cli_setup::<_, G16>(p, sub_matches),
+                    // the dimensions only allow pairing-friendly curves
+                    _ => unreachable!(),
                 },
                 #[cfg(feature = "libsnark")]
                 Dimensions(Backend::Libsnark, Curve::Bn128, ProvingScheme::GM17) => match prog {
@@ -859,10 +2450,56 @@ fn cli() -> Result<(), String> {
                 Dimensions(Backend::Libsnark, Curve::Bn128, ProvingScheme::PGHR13) => {
                     cli_export_verifier::<Bn128Field, PGHR13>(sub_matches)
                 }
-                #[cfg(feature = "libsnark")]
                 _ => unreachable!(),
             }?
         }
+        ("export-envelope", Some(sub_matches)) => cli_export_envelope(sub_matches)?,
+        ("diff", Some(sub_matches)) => {
+            let read = |name: &str| -> Result<ProgEnum, String> {
+                let path = Path::new(sub_matches.value_of(name).unwrap());
+                let file = File::open(&path)
+                    .map_err(|why| format!("Couldn't open {}: {}", path.display(), why))?;
+                ProgEnum::deserialize(&mut BufReader::new(file))
+            };
+
+            match (read("old")?, read("new")?) {
+                (ProgEnum::Bn128Program(old), ProgEnum::Bn128Program(new)) => {
+                    cli_diff(old, new, sub_matches)?
+                }
+                (ProgEnum::Bls12Program(old), ProgEnum::Bls12Program(new)) => {
+                    cli_diff(old, new, sub_matches)?
+                }
+                (ProgEnum::PallasProgram(old), ProgEnum::PallasProgram(new)) => {
+                    cli_diff(old, new, sub_matches)?
+                }
+                (ProgEnum::VestaProgram(old), ProgEnum::VestaProgram(new)) => {
+                    cli_diff(old, new, sub_matches)?
+                }
+                (ProgEnum::GoldilocksProgram(old), ProgEnum::GoldilocksProgram(new)) => {
+                    cli_diff(old, new, sub_matches)?
+                }
+                (ProgEnum::CustomProgram(old), ProgEnum::CustomProgram(new)) => {
+                    cli_diff(old, new, sub_matches)?
+                }
+                _ => return Err(String::from("The programs use different curves")),
+            }
+        }
+        ("abi-check", Some(sub_matches)) => cli_abi_check(sub_matches)?,
+        ("lint", Some(sub_matches)) => {
+            let path = Path::new(sub_matches.value_of("input").unwrap());
+            let file = File::open(&path)
+                .map_err(|why| format!("Couldn't open {}: {}", path.display(), why))?;
+
+            match ProgEnum::deserialize(&mut BufReader::new(file))? {
+                ProgEnum::Bn128Program(p) => cli_lint(p, sub_matches)?,
+                ProgEnum::Bls12Program(p) => cli_lint(p, sub_matches)?,
+                ProgEnum::PallasProgram(p) => cli_lint(p, sub_matches)?,
+                ProgEnum::VestaProgram(p) => cli_lint(p, sub_matches)?,
+                ProgEnum::GoldilocksProgram(p) => cli_lint(p, sub_matches)?,
+                ProgEnum::CustomProgram(p) => cli_lint(p, sub_matches)?,
+            }
+        }
+        ("inspect", Some(sub_matches)) => cli_inspect(sub_matches)?,
         ("generate-proof", Some(sub_matches)) => {
             let program_path = Path::new(sub_matches.value_of("input").unwrap());
             let program_file = File::open(&program_path)
@@ -876,6 +2513,10 @@ fn cli() -> Result<(), String> {
                 match prog {
                     ProgEnum::Bn128Program(_) => constants::BN128,
                     ProgEnum::Bls12Program(_) => constants::BLS12_381,
+                    ProgEnum::PallasProgram(_) => constants::PALLAS,
+                    ProgEnum::VestaProgram(_) => constants::VESTA,
+                    ProgEnum::GoldilocksProgram(_) => constants::GOLDILOCKS,
+                    ProgEnum::CustomProgram(_) => constants::CUSTOM,
                 },
                 sub_matches.value_of("proving-scheme").unwrap(),
             ))?;
@@ -884,6 +2525,8 @@ fn cli() -> Result<(), String> {
                 Dimensions(Backend::Bellman, _, ProvingScheme::G16) => match prog {
                     ProgEnum::Bn128Program(p) => cli_generate_proof::<_, G16>(p, sub_matches),
                     ProgEnum::Bls12Program(p) => cli_generate_proof::<_, G16>(p, sub_matches),
+                    // the dimensions only allow pairing-friendly curves
+                    _ => unreachable!(),
                 },
                 #[cfg(feature = "libsnark")]
                 Dimensions(Backend::Libsnark, Curve::Bn128, ProvingScheme::GM17) => match prog {
@@ -957,10 +2600,14 @@ fn cli() -> Result<(), String> {
                 Dimensions(Backend::Libsnark, Curve::Bn128, ProvingScheme::PGHR13) => {
                     cli_verify::<Bn128Field, PGHR13>(sub_matches)
                 }
-                #[cfg(feature = "libsnark")]
                 _ => unreachable!(),
             }?
         }
+        ("test", Some(sub_matches)) => cli_test(sub_matches)?,
+        ("conformance", Some(sub_matches)) => cli_conformance(sub_matches)?,
+        ("new", Some(sub_matches)) => cli_new(sub_matches)?,
+        ("doc", Some(sub_matches)) => cli_doc(sub_matches)?,
+        ("explain", Some(sub_matches)) => cli_explain(sub_matches)?,
         _ => unreachable!(),
     }
     Ok(())
@@ -969,7 +2616,9 @@ fn cli() -> Result<(), String> {
 #[cfg(test)]
 mod tests {
     extern crate glob;
+    extern crate tempdir;
     use self::glob::glob;
+    use self::tempdir::TempDir;
     use super::*;
 
     #[test]
@@ -1001,7 +2650,7 @@ mod tests {
 
             let resolver = FileSystemResolver::new();
             let _: CompilationArtifacts<Bn128Field> =
-                compile(source, path, Some(&resolver)).unwrap();
+                compile(source, path, Some(&resolver), &CompileConfig::default()).unwrap();
         }
     }
 
@@ -1023,7 +2672,7 @@ mod tests {
 
             let resolver = FileSystemResolver::new();
             let artifacts: CompilationArtifacts<Bn128Field> =
-                compile(source, path, Some(&resolver)).unwrap();
+                compile(source, path, Some(&resolver), &CompileConfig::default()).unwrap();
 
             let interpreter = ir::Interpreter::default();
 
@@ -1033,6 +2682,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn templates() {
+        let tmp_dir = TempDir::new("templates").unwrap();
+
+        for name in TEMPLATES {
+            println!("Testing {}", name);
+
+            let path = tmp_dir.path().join(name);
+            new_project(&path, name).unwrap();
+
+            let root = path.join("root.zok");
+            let source = fs::read_to_string(&root).unwrap();
+
+            let resolver = FileSystemResolver::new();
+            let _: CompilationArtifacts<Bn128Field> =
+                compile(source, root, Some(&resolver), &CompileConfig::default()).unwrap();
+        }
+    }
+
     #[test]
     #[should_panic]
     fn examples_with_input_failure() {
@@ -1052,7 +2720,7 @@ mod tests {
 
             let resolver = FileSystemResolver::new();
             let artifacts: CompilationArtifacts<Bn128Field> =
-                compile(source, path, Some(&resolver)).unwrap();
+                compile(source, path, Some(&resolver), &CompileConfig::default()).unwrap();
 
             let interpreter = ir::Interpreter::default();
 
@@ -0,0 +1,115 @@
+use crate::helpers::sha256_file;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PROVING_KEY_FILE: &str = "proving.key";
+const VERIFICATION_KEY_FILE: &str = "verification.key";
+
+/// The keys of a compiled program stored in a cache directory
+pub struct CachedKeys {
+    dir: PathBuf,
+}
+
+impl CachedKeys {
+    /// Locates the keys of the program at `program_path` in `cache_dir`.
+    /// Keys are stored in a directory named after the SHA256 hash of the compiled program, the backend and the
+    /// proving scheme, so that recompiling a program to the same binary finds its keys again.
+    pub fn new(
+        cache_dir: &Path,
+        program_path: &Path,
+        backend: &str,
+        proving_scheme: &str,
+    ) -> Result<Self, String> {
+        let hash = sha256_file(program_path)?;
+
+        Ok(CachedKeys {
+            dir: cache_dir.join(format!("{}-{}-{}", hash, backend, proving_scheme)),
+        })
+    }
+
+    pub fn proving_key_path(&self) -> PathBuf {
+        self.dir.join(PROVING_KEY_FILE)
+    }
+
+    pub fn verification_key_path(&self) -> PathBuf {
+        self.dir.join(VERIFICATION_KEY_FILE)
+    }
+
+    pub fn exist(&self) -> bool {
+        self.proving_key_path().is_file() && self.verification_key_path().is_file()
+    }
+
+    /// Copies the cached keys to `pk_path` and `vk_path`
+    pub fn load(&self, pk_path: &Path, vk_path: &Path) -> Result<(), String> {
+        copy(&self.proving_key_path(), pk_path)?;
+        copy(&self.verification_key_path(), vk_path)
+    }
+
+    /// Copies the keys at `pk_path` and `vk_path` to the cache
+    pub fn store(&self, pk_path: &Path, vk_path: &Path) -> Result<(), String> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|why| format!("Couldn't create {}: {}", self.dir.display(), why))?;
+        copy(pk_path, &self.proving_key_path())?;
+        copy(vk_path, &self.verification_key_path())
+    }
+}
+
+fn copy(from: &Path, to: &Path) -> Result<(), String> {
+    fs::copy(from, to).map(|_| ()).map_err(|why| {
+        format!(
+            "Couldn't copy {} to {}: {}",
+            from.display(),
+            to.display(),
+            why
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::Write;
+    use tempdir::TempDir;
+
+    #[test]
+    fn store_and_load() {
+        let tmp = TempDir::new("cache").unwrap();
+        let program_path = tmp.path().join("out");
+        File::create(&program_path)
+            .unwrap()
+            .write_all(b"program")
+            .unwrap();
+
+        let pk_path = tmp.path().join("proving.key");
+        let vk_path = tmp.path().join("verification.key");
+        File::create(&pk_path).unwrap().write_all(b"pk").unwrap();
+        File::create(&vk_path).unwrap().write_all(b"vk").unwrap();
+
+        let cache_dir = tmp.path().join("cache");
+        let keys = CachedKeys::new(&cache_dir, &program_path, "bellman", "g16").unwrap();
+        assert!(!keys.exist());
+
+        keys.store(&pk_path, &vk_path).unwrap();
+        assert!(keys.exist());
+
+        // the same program finds the keys, a different one does not
+        let keys = CachedKeys::new(&cache_dir, &program_path, "bellman", "g16").unwrap();
+        assert!(keys.exist());
+        File::create(&program_path)
+            .unwrap()
+            .write_all(b"other program")
+            .unwrap();
+        assert!(
+            !CachedKeys::new(&cache_dir, &program_path, "bellman", "g16")
+                .unwrap()
+                .exist()
+        );
+
+        let loaded_pk_path = tmp.path().join("loaded.key");
+        let loaded_vk_path = tmp.path().join("loaded.json");
+        keys.load(&loaded_pk_path, &loaded_vk_path).unwrap();
+        assert_eq!(fs::read(loaded_pk_path).unwrap(), b"pk");
+        assert_eq!(fs::read(loaded_vk_path).unwrap(), b"vk");
+    }
+}
@@ -8,7 +8,11 @@ pub const BACKENDS: &[&str] = &[BELLMAN];
 
 pub const BN128: &str = "bn128";
 pub const BLS12_381: &str = "bls12_381";
-pub const CURVES: &[&str] = &[BN128, BLS12_381];
+pub const PALLAS: &str = "pallas";
+pub const VESTA: &str = "vesta";
+pub const GOLDILOCKS: &str = "goldilocks";
+pub const CUSTOM: &str = "custom";
+pub const CURVES: &[&str] = &[BN128, BLS12_381, PALLAS, VESTA, GOLDILOCKS, CUSTOM];
 
 pub const G16: &str = "g16";
 #[cfg(feature = "libsnark")]
@@ -19,3 +23,8 @@ pub const GM17: &str = "gm17";
 pub const SCHEMES: &[&str] = &[G16, PGHR13, GM17];
 #[cfg(not(feature = "libsnark"))]
 pub const SCHEMES: &[&str] = &[G16];
+
+pub const MIXER: &str = "mixer";
+pub const VOTING: &str = "voting";
+pub const ROLLUP: &str = "rollup";
+pub const TEMPLATES: &[&str] = &[MIXER, VOTING, ROLLUP];
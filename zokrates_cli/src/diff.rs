@@ -0,0 +1,398 @@
+use std::collections::BTreeSet;
+use zokrates_core::ir::{self, SourceMap, Statement};
+use zokrates_core::typed_absy::abi::Abi;
+use zokrates_core::typed_absy::Type;
+use zokrates_field::Field;
+
+/// Returns the differences in size and public layout between two compiled programs, one per line
+pub fn programs<T: Field>(old: &ir::Prog<T>, new: &ir::Prog<T>) -> Vec<String> {
+    let mut res = vec![];
+
+    let directive_count = |p: &ir::Prog<T>| {
+        p.main
+            .statements
+            .iter()
+            .filter(|s| match s {
+                Statement::Directive(..) => true,
+                _ => false,
+            })
+            .count()
+    };
+
+    compare(
+        &mut res,
+        "constraints",
+        old.constraint_count(),
+        new.constraint_count(),
+    );
+    compare(
+        &mut res,
+        "directives",
+        directive_count(old),
+        directive_count(new),
+    );
+    compare(
+        &mut res,
+        "public inputs",
+        old.private.iter().filter(|p| !**p).count(),
+        new.private.iter().filter(|p| !**p).count(),
+    );
+    compare(
+        &mut res,
+        "private inputs",
+        old.private.iter().filter(|p| **p).count(),
+        new.private.iter().filter(|p| **p).count(),
+    );
+    compare(
+        &mut res,
+        "outputs",
+        old.main.returns.len(),
+        new.main.returns.len(),
+    );
+
+    // the order of public inputs is what verifiers rely on
+    for (index, (o, n)) in old.private.iter().zip(new.private.iter()).enumerate() {
+        if o != n {
+            res.push(format!(
+                "input {} changed from {} to {}",
+                index,
+                visibility(*o),
+                visibility(*n)
+            ));
+        }
+    }
+
+    if old.private_outputs != new.private_outputs {
+        res.push(format!(
+            "private outputs changed from {:?} to {:?}",
+            old.private_outputs, new.private_outputs
+        ));
+    }
+
+    // programs of the same size can still have different constraints
+    if res.is_empty() && (old.main != new.main || old.functions != new.functions) {
+        res.push(String::from(
+            "constraints changed, although their number did not",
+        ));
+    }
+
+    res
+}
+
+/// Returns the differences between the ABIs of two programs, one per line
+pub fn abis(old: &Abi, new: &Abi) -> Vec<String> {
+    let mut res = vec![];
+
+    compare(&mut res, "ABI inputs", old.inputs.len(), new.inputs.len());
+
+    for (index, (o, n)) in old.inputs.iter().zip(new.inputs.iter()).enumerate() {
+        if o != n {
+            res.push(format!(
+                "ABI input {} changed from {} {} {} to {} {} {}",
+                index,
+                visibility(!o.public),
+                o.ty,
+                o.name,
+                visibility(!n.public),
+                n.ty,
+                n.name
+            ));
+        }
+    }
+
+    compare(
+        &mut res,
+        "ABI outputs",
+        old.outputs.len(),
+        new.outputs.len(),
+    );
+
+    for (index, (o, n)) in old.outputs.iter().zip(new.outputs.iter()).enumerate() {
+        if o != n {
+            res.push(format!("ABI output {} changed from {} to {}", index, o, n));
+        }
+    }
+
+    res
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Compatibility {
+    /// The change does not affect the public inputs of verifiers
+    Compatible,
+    /// Verifiers or their callers must be updated
+    Breaking,
+}
+
+/// Classifies the changes between two ABIs. Verifiers take the public inputs followed by the public outputs, so
+/// changes to their number, order or types are breaking. Renaming them and changing private inputs or outputs is
+/// compatible, although such changes still require a new setup.
+pub fn compatibility(old: &Abi, new: &Abi) -> Vec<(Compatibility, String)> {
+    let mut res = vec![];
+
+    for &public in &[true, false] {
+        let inputs = |abi: &Abi| {
+            abi.inputs
+                .iter()
+                .filter(|i| i.public == public)
+                .map(|i| (i.name.clone(), i.ty.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        classify(
+            &mut res,
+            &format!("{} input", visibility(!public)),
+            public,
+            inputs(old),
+            inputs(new),
+        );
+    }
+
+    for &public in &[true, false] {
+        // outputs are named after their index
+        let outputs = |abi: &Abi| {
+            abi.outputs
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| abi.private_outputs.contains(index) != public)
+                .map(|(index, ty)| (index.to_string(), ty.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        classify(
+            &mut res,
+            &format!("{} output", visibility(!public)),
+            public,
+            outputs(old),
+            outputs(new),
+        );
+    }
+
+    res
+}
+
+fn classify(
+    res: &mut Vec<(Compatibility, String)>,
+    what: &str,
+    public: bool,
+    old: Vec<(String, Type)>,
+    new: Vec<(String, Type)>,
+) {
+    let severity = match public {
+        true => Compatibility::Breaking,
+        false => Compatibility::Compatible,
+    };
+
+    for (index, ((old_name, old_ty), (new_name, new_ty))) in old.iter().zip(new.iter()).enumerate()
+    {
+        if old_ty != new_ty {
+            res.push((
+                severity.clone(),
+                format!(
+                    "{} {} changed type from {} to {}",
+                    what, index, old_ty, new_ty
+                ),
+            ));
+        } else if old_name != new_name {
+            res.push((
+                Compatibility::Compatible,
+                format!(
+                    "{} {} renamed from {} to {}",
+                    what, index, old_name, new_name
+                ),
+            ));
+        }
+    }
+
+    for (name, ty) in old.iter().skip(new.len()) {
+        res.push((
+            severity.clone(),
+            format!("{} {} {} removed", what, ty, name),
+        ));
+    }
+
+    for (name, ty) in new.iter().skip(old.len()) {
+        res.push((severity.clone(), format!("{} {} {} added", what, ty, name)));
+    }
+}
+
+/// Returns the variables of `main` which were added, removed or assigned a different number of times, one per line.
+/// Functions other than `main` are inlined before the source map is built, so their changes are only visible through
+/// the number of constraints.
+pub fn source_maps<T: Field>(old: &SourceMap<T>, new: &SourceMap<T>) -> Vec<String> {
+    let names: BTreeSet<_> = old.names().chain(new.names()).collect();
+
+    names
+        .into_iter()
+        .filter_map(
+            |name| match (old.last_occurrence(name), new.last_occurrence(name)) {
+                (Some(_), None) => Some(format!("variable {} removed", name)),
+                (None, Some(_)) => Some(format!("variable {} added", name)),
+                (Some(o), Some(n)) if o != n => Some(format!(
+                    "variable {} has {} assignments instead of {}",
+                    name, n, o
+                )),
+                _ => None,
+            },
+        )
+        .collect()
+}
+
+fn compare(res: &mut Vec<String>, what: &str, old: usize, new: usize) {
+    if old != new {
+        res.push(format!(
+            "{} changed from {} to {} ({:+})",
+            what,
+            old,
+            new,
+            new as isize - old as isize
+        ));
+    }
+}
+
+fn visibility(private: bool) -> &'static str {
+    match private {
+        true => "private",
+        false => "public",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zokrates_core::flat_absy::FlatVariable;
+    use zokrates_core::ir::{Function, LinComb, Prog};
+    use zokrates_field::Bn128Field;
+
+    fn program(private: Vec<bool>, constraints: usize) -> Prog<Bn128Field> {
+        Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: (0..private.len()).map(FlatVariable::new).collect(),
+                returns: vec![],
+                statements: (0..constraints)
+                    .map(|i| Statement::constraint(FlatVariable::new(i), FlatVariable::new(i + 1)))
+                    .collect(),
+            },
+            functions: vec![],
+            private,
+            private_outputs: vec![],
+        }
+    }
+
+    #[test]
+    fn programs() {
+        let old = program(vec![false, true], 2);
+
+        assert!(super::programs(&old, &old).is_empty());
+        assert_eq!(
+            super::programs(&old, &program(vec![true, false], 3)),
+            vec![
+                "constraints changed from 2 to 3 (+1)",
+                "input 0 changed from public to private",
+                "input 1 changed from private to public"
+            ]
+        );
+
+        let mut new = old.clone();
+        new.main.statements.reverse();
+        assert_eq!(
+            super::programs(&old, &new),
+            vec!["constraints changed, although their number did not"]
+        );
+    }
+
+    #[test]
+    fn compatibility() {
+        use zokrates_core::typed_absy::abi::AbiInput;
+
+        let input = |name: &str, public, ty| AbiInput {
+            name: String::from(name),
+            public,
+            ty,
+        };
+
+        let old = Abi {
+            inputs: vec![
+                input("a", true, Type::FieldElement),
+                input("b", false, Type::FieldElement),
+            ],
+            outputs: vec![Type::FieldElement],
+            private_outputs: vec![],
+        };
+
+        assert!(super::compatibility(&old, &old).is_empty());
+
+        // renaming public inputs and changing private ones is compatible
+        let new = Abi {
+            inputs: vec![
+                input("c", true, Type::FieldElement),
+                input("b", false, Type::Boolean),
+                input("d", false, Type::FieldElement),
+            ],
+            outputs: vec![Type::FieldElement],
+            private_outputs: vec![],
+        };
+        assert_eq!(
+            super::compatibility(&old, &new),
+            vec![
+                (
+                    Compatibility::Compatible,
+                    String::from("public input 0 renamed from a to c")
+                ),
+                (
+                    Compatibility::Compatible,
+                    String::from("private input 0 changed type from field to bool")
+                ),
+                (
+                    Compatibility::Compatible,
+                    String::from("private input field d added")
+                ),
+            ]
+        );
+
+        // making an output private removes it from the public inputs of the verifier
+        let old = Abi {
+            inputs: vec![],
+            outputs: vec![Type::FieldElement],
+            private_outputs: vec![],
+        };
+        let new = Abi {
+            inputs: vec![],
+            outputs: vec![Type::FieldElement],
+            private_outputs: vec![0],
+        };
+        assert_eq!(
+            super::compatibility(&old, &new),
+            vec![
+                (
+                    Compatibility::Breaking,
+                    String::from("public output field 0 removed")
+                ),
+                (
+                    Compatibility::Compatible,
+                    String::from("private output field 0 added")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn source_maps() {
+        let mut old = SourceMap::<Bn128Field>::default();
+        old.insert(String::from("a"), 0, LinComb::zero());
+        old.insert(String::from("b"), 0, LinComb::zero());
+
+        let mut new = old.clone();
+        new.insert(String::from("b"), 1, LinComb::zero());
+        new.insert(String::from("c"), 0, LinComb::zero());
+
+        assert_eq!(
+            super::source_maps(&old, &new),
+            vec![
+                "variable b has 1 assignments instead of 0",
+                "variable c added"
+            ]
+        );
+    }
+}
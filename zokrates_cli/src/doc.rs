@@ -0,0 +1,363 @@
+//! Generation of browsable documentation from the doc comments of modules
+//!
+//! Each module gets a page, named after its path relative to the documented directory or to the standard library,
+//! and an index lists all pages. Imported modules are documented as well, and imports link to their pages.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use zokrates_common::Resolver;
+use zokrates_core::doc::{documentation, Documentation, Item, ItemKind};
+use zokrates_fs_resolver::FileSystemResolver;
+
+pub const MARKDOWN: &str = "markdown";
+pub const HTML: &str = "html";
+pub const FORMATS: &[&str] = &[MARKDOWN, HTML];
+
+/// The sections of a page, with the kind of their items
+const SECTIONS: &[(ItemKind, &str)] = &[
+    (ItemKind::Struct, "Structs"),
+    (ItemKind::Function, "Functions"),
+];
+
+/// The documentation of a module
+#[derive(Debug, PartialEq)]
+pub struct Page {
+    pub name: String,
+    pub documentation: Documentation,
+    /// The names of the pages of the imported modules, in the order of `documentation.imports`, or `None` for embeds
+    pub imports: Vec<Option<String>>,
+}
+
+/// Returns the modules in `dir` and its subdirectories, sorted by path
+pub fn modules(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut res = vec![];
+
+    let entries =
+        fs::read_dir(dir).map_err(|why| format!("Couldn't read {}: {}", dir.display(), why))?;
+
+    for entry in entries {
+        let path = entry
+            .map_err(|why| format!("Couldn't read {}: {}", dir.display(), why))?
+            .path();
+
+        if path.is_dir() {
+            res.extend(modules(&path)?);
+        } else if path.extension().map(|e| e == "zok").unwrap_or(false) {
+            res.push(path);
+        }
+    }
+
+    res.sort();
+    Ok(res)
+}
+
+/// Returns the pages of the modules in `inputs` and of the modules they import, sorted by name. `root` is the
+/// directory the names of the pages are relative to.
+pub fn pages(inputs: Vec<PathBuf>, root: &Path) -> Result<Vec<Page>, String> {
+    let resolver = FileSystemResolver::new();
+
+    let mut pages = BTreeMap::new();
+    let mut queue = inputs
+        .into_iter()
+        .map(|location| {
+            fs::read_to_string(&location)
+                .map(|source| (location.clone(), source))
+                .map_err(|why| format!("Couldn't read {}: {}", location.display(), why))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    while let Some((location, source)) = queue.pop() {
+        let location = location.canonicalize().unwrap_or(location);
+        let name = page_name(&location, root);
+
+        if pages.contains_key(&name) {
+            continue;
+        }
+
+        let documentation = documentation(&source)
+            .map_err(|e| format!("Couldn't parse {}: {}", location.display(), e))?;
+
+        let mut imports = vec![];
+        for import in &documentation.imports {
+            if import.starts_with("EMBED") {
+                imports.push(None);
+                continue;
+            }
+
+            let (source, imported) = resolver
+                .resolve(location.clone(), PathBuf::from(import))
+                .map_err(|why| {
+                    format!(
+                        "Couldn't resolve import {} in {}: {}",
+                        import,
+                        location.display(),
+                        why
+                    )
+                })?;
+
+            let imported = imported.canonicalize().unwrap_or(imported);
+            imports.push(Some(page_name(&imported, root)));
+            queue.push((imported, source));
+        }
+
+        pages.insert(
+            name.clone(),
+            Page {
+                name,
+                documentation,
+                imports,
+            },
+        );
+    }
+
+    Ok(pages.into_iter().map(|(_, page)| page).collect())
+}
+
+/// Returns the path of a module without its extension, relative to `root` or else to the standard library
+fn page_name(location: &Path, root: &Path) -> String {
+    let stdlib = env::var("ZOKRATES_HOME")
+        .ok()
+        .and_then(|home| PathBuf::from(home).canonicalize().ok());
+
+    let relative = location
+        .strip_prefix(root)
+        .ok()
+        .or_else(|| {
+            stdlib
+                .as_ref()
+                .and_then(|home| location.strip_prefix(home).ok())
+        })
+        .unwrap_or(location);
+
+    relative
+        .with_extension("")
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(c) => Some(c.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Returns the name of the file of a page, which is flat so that pages can link to each other by name
+pub fn file_name(name: &str, format: &str) -> String {
+    let extension = match format {
+        MARKDOWN => "md",
+        _ => "html",
+    };
+    format!("{}.{}", name.replace('/', "."), extension)
+}
+
+/// Writes the pages and their index to `output`
+pub fn write(pages: &[Page], output: &Path, format: &str) -> Result<(), String> {
+    fs::create_dir_all(output)
+        .map_err(|why| format!("Couldn't create {}: {}", output.display(), why))?;
+
+    let (index, render): (_, fn(&Page) -> String) = match format {
+        MARKDOWN => (markdown_index(pages), markdown),
+        _ => (html_index(pages), html),
+    };
+
+    let files = pages
+        .iter()
+        .map(|page| (file_name(&page.name, format), render(page)))
+        .chain(std::iter::once((file_name("index", format), index)));
+
+    for (file, content) in files {
+        let path = output.join(file);
+        fs::write(&path, content)
+            .map_err(|why| format!("Couldn't write {}: {}", path.display(), why))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the items of a page of the given kind
+fn items(page: &Page, kind: ItemKind) -> Vec<&Item> {
+    page.documentation
+        .items
+        .iter()
+        .filter(|item| item.kind == kind)
+        .collect()
+}
+
+fn markdown(page: &Page) -> String {
+    let mut res = format!("# `{}`\n", page.name);
+
+    if !page.imports.is_empty() {
+        res.push_str("\n## Imports\n\n");
+        for (import, name) in page.documentation.imports.iter().zip(&page.imports) {
+            match name {
+                Some(name) => res.push_str(&format!(
+                    "- [`{}`]({})\n",
+                    import,
+                    file_name(name, MARKDOWN)
+                )),
+                None => res.push_str(&format!("- `{}`\n", import)),
+            }
+        }
+    }
+
+    for (kind, title) in SECTIONS {
+        let items = items(page, *kind);
+        if items.is_empty() {
+            continue;
+        }
+
+        res.push_str(&format!("\n## {}\n", title));
+        for item in items {
+            res.push_str(&format!(
+                "\n### `{}`\n\n```zokrates\n{}\n```\n",
+                item.name, item.signature
+            ));
+            if !item.doc.is_empty() {
+                res.push_str(&format!("\n{}\n", item.doc));
+            }
+        }
+    }
+
+    res
+}
+
+fn markdown_index(pages: &[Page]) -> String {
+    let mut res = String::from("# Modules\n\n");
+    for page in pages {
+        res.push_str(&format!(
+            "- [`{}`]({})\n",
+            page.name,
+            file_name(&page.name, MARKDOWN)
+        ));
+    }
+    res
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn html_document(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(title),
+        body
+    )
+}
+
+fn html(page: &Page) -> String {
+    let mut body = format!(
+        "<p><a href=\"{}\">Modules</a></p>\n<h1><code>{}</code></h1>\n",
+        file_name("index", HTML),
+        escape(&page.name)
+    );
+
+    if !page.imports.is_empty() {
+        body.push_str("<h2>Imports</h2>\n<ul>\n");
+        for (import, name) in page.documentation.imports.iter().zip(&page.imports) {
+            match name {
+                Some(name) => body.push_str(&format!(
+                    "<li><a href=\"{}\"><code>{}</code></a></li>\n",
+                    escape(&file_name(name, HTML)),
+                    escape(import)
+                )),
+                None => body.push_str(&format!("<li><code>{}</code></li>\n", escape(import))),
+            }
+        }
+        body.push_str("</ul>\n");
+    }
+
+    for (kind, title) in SECTIONS {
+        let items = items(page, *kind);
+        if items.is_empty() {
+            continue;
+        }
+
+        body.push_str(&format!("<h2>{}</h2>\n", title));
+        for item in items {
+            body.push_str(&format!(
+                "<h3 id=\"{}\"><code>{}</code></h3>\n<pre><code>{}</code></pre>\n",
+                escape(&item.name),
+                escape(&item.name),
+                escape(&item.signature)
+            ));
+            // paragraphs are separated by empty lines, as in markdown
+            for paragraph in item.doc.split("\n\n").filter(|p| !p.trim().is_empty()) {
+                body.push_str(&format!("<p>{}</p>\n", escape(paragraph)));
+            }
+        }
+    }
+
+    html_document(&page.name, &body)
+}
+
+fn html_index(pages: &[Page]) -> String {
+    let mut body = String::from("<h1>Modules</h1>\n<ul>\n");
+    for page in pages {
+        body.push_str(&format!(
+            "<li><a href=\"{}\"><code>{}</code></a></li>\n",
+            escape(&file_name(&page.name, HTML)),
+            escape(&page.name)
+        ));
+    }
+    body.push_str("</ul>\n");
+
+    html_document("Modules", &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn document_project() {
+        let dir = TempDir::new("doc").unwrap();
+        let root = dir.path().canonicalize().unwrap();
+
+        fs::create_dir(root.join("utils")).unwrap();
+        fs::write(
+            root.join("main.zok"),
+            "import \"EMBED/unpack\" as unpack\nfrom \"./utils/point\" import Point\n\n/// Entry point\ndef main(Point p) -> field:\n    return p.x\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("utils/point.zok"),
+            "/// A point\n/// of the plane\nstruct Point {\n    field x\n    field y\n}\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            modules(&root).unwrap(),
+            vec![root.join("main.zok"), root.join("utils/point.zok")]
+        );
+
+        let pages = pages(vec![root.join("main.zok")], &root).unwrap();
+
+        assert_eq!(
+            pages.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(),
+            vec!["main", "utils/point"]
+        );
+        assert_eq!(pages[0].imports, vec![None, Some("utils/point".into())]);
+
+        let main = markdown(&pages[0]);
+        assert!(main.contains("- `EMBED/unpack`\n"));
+        assert!(main.contains("- [`./utils/point`](utils.point.md)\n"));
+        assert!(main.contains("```zokrates\ndef main(Point p) -> field\n```\n\nEntry point\n"));
+
+        let point = html(&pages[1]);
+        assert!(
+            point.contains("<pre><code>struct Point {\n    field x\n    field y\n}</code></pre>")
+        );
+        assert!(point.contains("<p>A point\nof the plane</p>"));
+
+        let output = root.join("doc");
+        write(&pages, &output, HTML).unwrap();
+        assert!(output.join("index.html").is_file());
+        assert!(output.join("utils.point.html").is_file());
+    }
+}
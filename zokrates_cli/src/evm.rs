@@ -0,0 +1,121 @@
+use crate::helpers::run;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// The files of the node.js harness deploying a verifier and calling `verifyTx`, shared with the integration tests
+const HARNESS: &[(&str, &str)] = &[
+    ("test.js", include_str!("../tests/contract/test.js")),
+    (
+        "package.json",
+        include_str!("../tests/contract/package.json"),
+    ),
+];
+
+/// The gas used by a verifier on the EVM
+#[derive(Debug)]
+pub struct Gas {
+    /// gas used to deploy the verifier, excluding the libraries it links to
+    pub deployment: u64,
+    /// gas used by a transaction calling `verifyTx` on a valid proof
+    pub verification: u64,
+}
+
+/// Writes the harness to `harness_dir` and installs its dependencies, which requires `node` and `npm` to be available
+fn install(harness_dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(harness_dir)
+        .map_err(|why| format!("Couldn't create {}: {}", harness_dir.display(), why))?;
+
+    for (file, content) in HARNESS {
+        let path = harness_dir.join(file);
+        fs::write(&path, content)
+            .map_err(|why| format!("Couldn't write {}: {}", path.display(), why))?;
+    }
+
+    // dependencies are only installed once per harness directory
+    if !harness_dir.join("node_modules").exists() {
+        run(Command::new("npm")
+            .args(&["install", "--silent"])
+            .current_dir(harness_dir))?;
+    }
+
+    Ok(())
+}
+
+fn harness(
+    harness_dir: &Path,
+    contract_path: &Path,
+    proof_path: &Path,
+    scheme: &str,
+    abi: &str,
+) -> Command {
+    let mut command = Command::new("node");
+    command
+        .arg("test.js")
+        .arg(contract_path)
+        .arg(proof_path)
+        .arg(scheme)
+        .arg(abi)
+        .current_dir(harness_dir);
+    command
+}
+
+/// Deploys the verifier at `contract_path` to an in-process EVM and checks that `verifyTx` accepts the proof at
+/// `proof_path` and rejects a tampered version of it.
+/// The harness is installed in `harness_dir`, which requires `node` and `npm` to be available.
+pub fn verify(
+    harness_dir: &Path,
+    contract_path: &Path,
+    proof_path: &Path,
+    scheme: &str,
+    abi: &str,
+) -> Result<(), String> {
+    install(harness_dir)?;
+
+    run(&mut harness(
+        harness_dir,
+        contract_path,
+        proof_path,
+        scheme,
+        abi,
+    ))
+}
+
+/// Verifies the proof at `proof_path` with the verifier at `contract_path` as `verify` does, and returns the gas used
+/// to deploy the verifier and to verify the proof
+pub fn measure_gas(
+    harness_dir: &Path,
+    contract_path: &Path,
+    proof_path: &Path,
+    scheme: &str,
+    abi: &str,
+) -> Result<Gas, String> {
+    install(harness_dir)?;
+
+    let report_path = harness_dir.join("gas.json");
+    // a report left by a previous run must not be mistaken for the one of this run
+    if report_path.exists() {
+        fs::remove_file(&report_path)
+            .map_err(|why| format!("Couldn't remove {}: {}", report_path.display(), why))?;
+    }
+
+    run(harness(harness_dir, contract_path, proof_path, scheme, abi)
+        .env("ZOKRATES_GAS_REPORT", &report_path))?;
+
+    let report = fs::read_to_string(&report_path)
+        .map_err(|why| format!("Couldn't read {}: {}", report_path.display(), why))?;
+
+    let report: Value =
+        serde_json::from_str(&report).map_err(|why| format!("Invalid gas report: {}", why))?;
+    let gas = |key: &str| {
+        report[key]
+            .as_u64()
+            .ok_or_else(|| format!("The gas report has no `{}` gas", key))
+    };
+
+    Ok(Gas {
+        deployment: gas("deployment")?,
+        verification: gas("verification")?,
+    })
+}
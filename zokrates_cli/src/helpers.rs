@@ -1,10 +1,18 @@
 use core::convert::TryFrom;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
 
 use crate::constants::*;
 
 pub enum Curve {
     Bn128,
     Bls12,
+    Pallas,
+    Vesta,
+    Goldilocks,
+    Custom,
 }
 
 pub enum Backend {
@@ -21,6 +29,12 @@ pub enum ProvingScheme {
     PGHR13,
 }
 
+pub enum Template {
+    Mixer,
+    Voting,
+    Rollup,
+}
+
 impl TryFrom<&str> for Curve {
     type Error = String;
 
@@ -28,6 +42,10 @@ impl TryFrom<&str> for Curve {
         match s {
             BN128 => Ok(Curve::Bn128),
             BLS12_381 => Ok(Curve::Bls12),
+            PALLAS => Ok(Curve::Pallas),
+            VESTA => Ok(Curve::Vesta),
+            GOLDILOCKS => Ok(Curve::Goldilocks),
+            CUSTOM => Ok(Curve::Custom),
             _ => Err(format!("Unknown curve {}", s)),
         }
     }
@@ -61,6 +79,19 @@ impl TryFrom<&str> for ProvingScheme {
     }
 }
 
+impl TryFrom<&str> for Template {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            MIXER => Ok(Template::Mixer),
+            VOTING => Ok(Template::Voting),
+            ROLLUP => Ok(Template::Rollup),
+            _ => Err(format!("Unknown template {}", s)),
+        }
+    }
+}
+
 pub struct Dimensions(pub Backend, pub Curve, pub ProvingScheme);
 
 impl TryFrom<(&str, &str, &str)> for Dimensions {
@@ -86,7 +117,6 @@ impl TryFrom<(&str, &str, &str)> for Dimensions {
             (Backend::Libsnark, Curve::Bn128, ProvingScheme::PGHR13) => {
                 Ok(Dimensions(backend, curve, proving_scheme))
             }
-            #[cfg(feature = "libsnark")]
             _ => Err(format!(
                 "Unsupported combination of dimensions (backend: {}, curve: {}, proving scheme: {})",
                 s.0, s.1, s.2
@@ -94,3 +124,22 @@ impl TryFrom<(&str, &str, &str)> for Dimensions {
         }
     }
 }
+
+/// Runs `command` to completion, failing if it cannot be started or exits unsuccessfully
+pub fn run(command: &mut Command) -> Result<(), String> {
+    let status = command
+        .status()
+        .map_err(|why| format!("Couldn't run {:?}: {}", command, why))?;
+
+    match status.success() {
+        true => Ok(()),
+        false => Err(format!("{:?} failed with {}", command, status)),
+    }
+}
+
+/// Returns the hex encoded SHA256 hash of the file at `path`
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let content =
+        fs::read(path).map_err(|why| format!("Couldn't read {}: {}", path.display(), why))?;
+    Ok(hex::encode(Sha256::digest(&content)))
+}
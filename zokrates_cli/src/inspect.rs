@@ -0,0 +1,193 @@
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// Fields added by `setup` to the verification key, which are not part of the key itself
+const CURVE: &str = "curve";
+const SCHEME: &str = "scheme";
+const PROGRAM_HASH: &str = "program_hash";
+
+/// Fields holding one point per public input, plus one, depending on the proving scheme
+const INPUT_POINTS: &[(&str, &str)] = &[("gamma_abc", "g16"), ("query", "gm17"), ("ic", "pghr13")];
+
+/// Records the curve, the proving scheme and the hash of the program a verification key was generated for
+pub fn annotate(vk: Value, curve: &str, scheme: &str, program_hash: &str) -> Value {
+    match vk {
+        Value::Object(mut fields) => {
+            fields.insert(CURVE.into(), curve.into());
+            fields.insert(SCHEME.into(), scheme.into());
+            fields.insert(PROGRAM_HASH.into(), program_hash.into());
+            Value::Object(fields)
+        }
+        vk => vk,
+    }
+}
+
+/// Returns a short fingerprint of a verification key: the first 8 bytes of the SHA256 hash of its points, serialized
+/// as JSON with sorted keys. The metadata added by `annotate` is ignored, so that the fingerprint only depends on
+/// the key material.
+pub fn fingerprint(vk: &Value) -> String {
+    let key: Map<String, Value> = vk
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(name, _)| ![CURVE, SCHEME, PROGRAM_HASH].contains(&name.as_str()))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+
+    let hash = Sha256::digest(Value::Object(key).to_string().as_bytes());
+    format!("0x{}", hex::encode(&hash[..8]))
+}
+
+/// Declares the fingerprint of the verification key as a constant of an exported verifier, so that contracts relying
+/// on it can check which key it embeds
+pub fn embed_fingerprint(verifier: &str, fingerprint: &str) -> String {
+    verifier.replacen(
+        "contract Verifier {",
+        &format!(
+            "contract Verifier {{\n    bytes8 constant public VK_FINGERPRINT = {};",
+            fingerprint
+        ),
+        1,
+    )
+}
+
+/// Declares a function of an exported verifier returning the hash of public inputs, which identifies them uniquely as
+/// it rejects the inputs which are not reduced, as the verifier does. `public_inputs` is the number of public inputs,
+/// or `None` if the verifier takes them as an array of any length.
+pub fn embed_input_hash(verifier: &str, public_inputs: Option<usize>) -> String {
+    let input_type = match public_inputs {
+        Some(n) => format!("uint[{}]", n),
+        None => String::from("uint[]"),
+    };
+
+    verifier.replacen(
+        "contract Verifier {",
+        &format!(
+            "contract Verifier {{{}",
+            INPUT_HASH_FUNCTION.replace("<%input_type%>", &input_type)
+        ),
+        1,
+    )
+}
+
+/// Returns the number of public inputs of a verification key, given by its points
+pub fn public_inputs(vk: &Value) -> Option<usize> {
+    Summary::from(vk).public_inputs
+}
+
+const INPUT_HASH_FUNCTION: &str = r#"
+    /// @return h the hash of `input`, which identifies public inputs uniquely as they must be reduced modulo the scalar
+    /// field, both here and by the verifier
+    function inputHash(<%input_type%> memory input) public pure returns (bytes32 h) {
+        uint256 snark_scalar_field = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
+        for (uint i = 0; i < input.length; i++) {
+            require(input[i] < snark_scalar_field);
+        }
+        return keccak256(abi.encodePacked(input));
+    }"#;
+
+/// A human-readable summary of a verification key
+pub struct Summary {
+    fingerprint: String,
+    curve: Option<String>,
+    scheme: Option<String>,
+    public_inputs: Option<usize>,
+    program_hash: Option<String>,
+}
+
+impl From<&Value> for Summary {
+    fn from(vk: &Value) -> Self {
+        let field = |name: &str| vk.get(name).and_then(Value::as_str).map(String::from);
+
+        let points = INPUT_POINTS
+            .iter()
+            .find_map(|(name, scheme)| Some((vk.get(name)?.as_array()?, scheme)));
+
+        Summary {
+            fingerprint: fingerprint(vk),
+            curve: field(CURVE),
+            // keys generated before the scheme was recorded are recognized by their points
+            scheme: field(SCHEME).or_else(|| points.map(|(_, scheme)| scheme.to_string())),
+            public_inputs: points.map(|(points, _)| points.len().saturating_sub(1)),
+            program_hash: field(PROGRAM_HASH),
+        }
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let or_unknown = |v: Option<String>| v.unwrap_or_else(|| String::from("unknown"));
+
+        writeln!(f, "fingerprint:   {}", self.fingerprint)?;
+        writeln!(f, "curve:         {}", or_unknown(self.curve.clone()))?;
+        writeln!(f, "scheme:        {}", or_unknown(self.scheme.clone()))?;
+        writeln!(
+            f,
+            "public inputs: {}",
+            or_unknown(self.public_inputs.map(|n| n.to_string()))
+        )?;
+        write!(
+            f,
+            "program hash:  {}",
+            or_unknown(self.program_hash.clone())
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn vk() -> Value {
+        json!({
+            "alpha": ["0x01", "0x02"],
+            "gamma_abc": [["0x03", "0x04"], ["0x05", "0x06"], ["0x07", "0x08"]]
+        })
+    }
+
+    #[test]
+    fn fingerprint_ignores_metadata() {
+        let annotated = annotate(vk(), "bn128", "g16", "abcd");
+        assert_eq!(fingerprint(&vk()), fingerprint(&annotated));
+        assert_eq!(fingerprint(&vk()).len(), 18);
+
+        let other = json!({
+            "alpha": ["0x01", "0x03"],
+            "gamma_abc": [["0x03", "0x04"], ["0x05", "0x06"], ["0x07", "0x08"]]
+        });
+        assert_ne!(fingerprint(&vk()), fingerprint(&other));
+    }
+
+    #[test]
+    fn summary() {
+        let summary = Summary::from(&annotate(vk(), "bn128", "g16", "abcd"));
+        assert_eq!(summary.curve, Some(String::from("bn128")));
+        assert_eq!(summary.scheme, Some(String::from("g16")));
+        assert_eq!(summary.public_inputs, Some(2));
+        assert_eq!(summary.program_hash, Some(String::from("abcd")));
+
+        // keys without metadata
+        let summary = Summary::from(&vk());
+        assert_eq!(summary.curve, None);
+        assert_eq!(summary.scheme, Some(String::from("g16")));
+        assert_eq!(summary.program_hash, None);
+    }
+
+    #[test]
+    fn embed() {
+        let verifier = "pragma solidity ^0.6.1;\ncontract Verifier {\n    using Pairing for *;";
+        assert_eq!(
+            embed_fingerprint(verifier, "0x0011223344556677"),
+            "pragma solidity ^0.6.1;\ncontract Verifier {\n    bytes8 constant public VK_FINGERPRINT = 0x0011223344556677;\n    using Pairing for *;"
+        );
+
+        let verifier = embed_input_hash(verifier, Some(2));
+        assert!(verifier.contains("contract Verifier {\n    /// @return h"));
+        assert!(verifier.contains("function inputHash(uint[2] memory input)"));
+
+        let verifier = embed_input_hash("contract Verifier {", None);
+        assert!(verifier.contains("function inputHash(uint[] memory input)"));
+    }
+}
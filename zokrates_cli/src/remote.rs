@@ -0,0 +1,211 @@
+use crate::helpers::{run, sha256_file};
+use std::convert::TryFrom;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const PROVING_KEY_FILE: &str = "proving.key";
+const VERIFICATION_KEY_FILE: &str = "verification.key";
+const MANIFEST_FILE: &str = "keys.sha256";
+
+/// A remote location where artifacts are stored.
+/// Transfers are delegated to the command line tool of each storage, which must be installed and authenticated:
+/// `aws` for S3, `gsutil` for GCS, `ipfs` for IPFS and `curl` for HTTP.
+#[derive(Debug, PartialEq)]
+pub enum Remote {
+    File(PathBuf),
+    S3(String),
+    Gcs(String),
+    Ipfs(String),
+    Http(String),
+}
+
+impl TryFrom<&str> for Remote {
+    type Error = String;
+
+    fn try_from(uri: &str) -> Result<Self, Self::Error> {
+        let uri = uri.trim_end_matches('/');
+        match uri.splitn(2, "://").collect::<Vec<_>>()[..] {
+            ["file", path] => Ok(Remote::File(PathBuf::from(path))),
+            ["s3", _] => Ok(Remote::S3(uri.to_string())),
+            ["gs", _] => Ok(Remote::Gcs(uri.to_string())),
+            ["ipfs", path] => Ok(Remote::Ipfs(path.to_string())),
+            ["http", _] | ["https", _] => Ok(Remote::Http(uri.to_string())),
+            _ => Err(format!(
+                "Unsupported URI {}, expected one of file://, s3://, gs://, ipfs://, http:// or https://",
+                uri
+            )),
+        }
+    }
+}
+
+impl Remote {
+    fn join(&self, name: &str) -> Remote {
+        match self {
+            Remote::File(path) => Remote::File(path.join(name)),
+            Remote::S3(uri) => Remote::S3(format!("{}/{}", uri, name)),
+            Remote::Gcs(uri) => Remote::Gcs(format!("{}/{}", uri, name)),
+            Remote::Ipfs(path) => Remote::Ipfs(format!("{}/{}", path, name)),
+            Remote::Http(uri) => Remote::Http(format!("{}/{}", uri, name)),
+        }
+    }
+
+    fn download(&self, to: &Path) -> Result<(), String> {
+        match self {
+            Remote::File(path) => fs::copy(path, to).map(|_| ()).map_err(|why| {
+                format!(
+                    "Couldn't copy {} to {}: {}",
+                    path.display(),
+                    to.display(),
+                    why
+                )
+            }),
+            Remote::S3(uri) => run(Command::new("aws").args(&["s3", "cp"]).arg(uri).arg(to)),
+            Remote::Gcs(uri) => run(Command::new("gsutil").arg("cp").arg(uri).arg(to)),
+            Remote::Ipfs(path) => run(Command::new("ipfs").arg("get").arg(path).arg("-o").arg(to)),
+            Remote::Http(uri) => run(Command::new("curl").arg("-fsSL").arg(uri).arg("-o").arg(to)),
+        }
+    }
+
+    fn upload(&self, from: &Path) -> Result<(), String> {
+        match self {
+            Remote::File(path) => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)
+                        .map_err(|why| format!("Couldn't create {}: {}", parent.display(), why))?;
+                }
+                fs::copy(from, path).map(|_| ()).map_err(|why| {
+                    format!("Couldn't copy {} to {}: {}", from.display(), path.display(), why)
+                })
+            }
+            Remote::S3(uri) => run(Command::new("aws").args(&["s3", "cp"]).arg(from).arg(uri)),
+            Remote::Gcs(uri) => run(Command::new("gsutil").arg("cp").arg(from).arg(uri)),
+            // IPFS is content-addressed, so artifacts cannot be uploaded to a given path
+            Remote::Ipfs(_) => Err(String::from(
+                "Cannot upload to IPFS: add the keys with `ipfs add -r` and use the resulting CID to download them",
+            )),
+            Remote::Http(uri) => run(Command::new("curl").args(&["-fsS", "-T"]).arg(from).arg(uri)),
+        }
+    }
+}
+
+fn manifest_path() -> PathBuf {
+    env::temp_dir().join(format!("zokrates-{}-{}", std::process::id(), MANIFEST_FILE))
+}
+
+/// Uploads the keys at `pk_path` and `vk_path` to `remote`, along with a manifest of their SHA256 hashes
+pub fn push_keys(remote: &Remote, pk_path: &Path, vk_path: &Path) -> Result<(), String> {
+    let manifest = format!(
+        "{}  {}\n{}  {}\n",
+        sha256_file(pk_path)?,
+        PROVING_KEY_FILE,
+        sha256_file(vk_path)?,
+        VERIFICATION_KEY_FILE
+    );
+    let manifest_path = manifest_path();
+    fs::write(&manifest_path, manifest)
+        .map_err(|why| format!("Couldn't write {}: {}", manifest_path.display(), why))?;
+
+    let res = remote
+        .join(PROVING_KEY_FILE)
+        .upload(pk_path)
+        .and_then(|_| remote.join(VERIFICATION_KEY_FILE).upload(vk_path))
+        .and_then(|_| remote.join(MANIFEST_FILE).upload(&manifest_path));
+
+    let _ = fs::remove_file(&manifest_path);
+    res
+}
+
+/// Downloads the keys stored at `remote` to `pk_path` and `vk_path`, and checks them against the manifest of their
+/// hashes. This detects corrupted transfers, but not keys replaced along with the manifest: the verification key
+/// should still be compared with the one of the deployed verifier.
+pub fn pull_keys(remote: &Remote, pk_path: &Path, vk_path: &Path) -> Result<(), String> {
+    for path in &[pk_path, vk_path] {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|why| format!("Couldn't create {}: {}", parent.display(), why))?;
+        }
+    }
+
+    let manifest_path = manifest_path();
+    remote.join(MANIFEST_FILE).download(&manifest_path)?;
+    let manifest = fs::read_to_string(&manifest_path)
+        .map_err(|why| format!("Couldn't read {}: {}", manifest_path.display(), why));
+    let _ = fs::remove_file(&manifest_path);
+    let manifest = manifest?;
+
+    for (name, path) in &[
+        (PROVING_KEY_FILE, pk_path),
+        (VERIFICATION_KEY_FILE, vk_path),
+    ] {
+        remote.join(name).download(path)?;
+
+        let expected = manifest
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                match (parts.next(), parts.next()) {
+                    (Some(hash), Some(file)) if file == *name => Some(hash),
+                    _ => None,
+                }
+            })
+            .next()
+            .ok_or_else(|| format!("No hash found for {} in the manifest", name))?;
+
+        if sha256_file(path)? != expected {
+            let _ = fs::remove_file(path);
+            return Err(format!(
+                "Hash of the downloaded {} does not match the manifest",
+                name
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn parse_uri() {
+        assert_eq!(
+            Remote::try_from("s3://bucket/keys/"),
+            Ok(Remote::S3(String::from("s3://bucket/keys")))
+        );
+        assert_eq!(
+            Remote::try_from("ipfs://QmHash"),
+            Ok(Remote::Ipfs(String::from("QmHash")))
+        );
+        assert_eq!(
+            Remote::try_from("file:///tmp/keys"),
+            Ok(Remote::File(PathBuf::from("/tmp/keys")))
+        );
+        assert!(Remote::try_from("/tmp/keys").is_err());
+    }
+
+    #[test]
+    fn push_and_pull_file() {
+        let tmp = TempDir::new("remote").unwrap();
+        let pk_path = tmp.path().join("proving.key");
+        let vk_path = tmp.path().join("verification.key");
+        fs::write(&pk_path, b"pk").unwrap();
+        fs::write(&vk_path, b"vk").unwrap();
+
+        let remote = Remote::File(tmp.path().join("remote"));
+        push_keys(&remote, &pk_path, &vk_path).unwrap();
+
+        let pulled_pk_path = tmp.path().join("pulled").join("proving.key");
+        let pulled_vk_path = tmp.path().join("pulled").join("verification.key");
+        pull_keys(&remote, &pulled_pk_path, &pulled_vk_path).unwrap();
+        assert_eq!(fs::read(&pulled_pk_path).unwrap(), b"pk");
+        assert_eq!(fs::read(&pulled_vk_path).unwrap(), b"vk");
+
+        // tampering with a key is detected
+        fs::write(tmp.path().join("remote").join("proving.key"), b"other").unwrap();
+        assert!(pull_keys(&remote, &pulled_pk_path, &pulled_vk_path).is_err());
+    }
+}
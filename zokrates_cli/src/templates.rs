@@ -0,0 +1,61 @@
+use crate::helpers::Template;
+
+/// Files shared by all templates, as pairs of a path relative to the project and its content
+const COMMON: &[(&str, &str)] = &[
+    ("README.md", include_str!("../templates/common/README.md")),
+    (
+        "package.json",
+        include_str!("../templates/common/package.json"),
+    ),
+    (
+        "merkleRoot.zok",
+        include_str!("../templates/common/merkleRoot.zok"),
+    ),
+    (
+        "zokrates.js",
+        include_str!("../templates/common/zokrates.js"),
+    ),
+    ("deploy.js", include_str!("../templates/common/deploy.js")),
+];
+
+const MIXER: &[(&str, &str)] = &[
+    ("root.zok", include_str!("../templates/mixer/root.zok")),
+    ("index.js", include_str!("../templates/mixer/index.js")),
+];
+
+const VOTING: &[(&str, &str)] = &[
+    ("root.zok", include_str!("../templates/voting/root.zok")),
+    ("index.js", include_str!("../templates/voting/index.js")),
+];
+
+const ROLLUP: &[(&str, &str)] = &[
+    ("root.zok", include_str!("../templates/rollup/root.zok")),
+    (
+        "account.zok",
+        include_str!("../templates/rollup/account.zok"),
+    ),
+    ("index.js", include_str!("../templates/rollup/index.js")),
+];
+
+/// Helpers wrapping proof submission in an EIP-712 message signed by the prover, exported by `export-envelope`
+pub const ENVELOPE: &[(&str, &str)] = &[
+    (
+        "ProofEnvelope.sol",
+        include_str!("../templates/envelope/ProofEnvelope.sol"),
+    ),
+    (
+        "proofEnvelope.ts",
+        include_str!("../templates/envelope/proofEnvelope.ts"),
+    ),
+];
+
+/// Returns the files of a project generated from `template`
+pub fn files(template: &Template) -> impl Iterator<Item = &'static (&'static str, &'static str)> {
+    let files = match template {
+        Template::Mixer => MIXER,
+        Template::Voting => VOTING,
+        Template::Rollup => ROLLUP,
+    };
+
+    COMMON.iter().chain(files.iter())
+}
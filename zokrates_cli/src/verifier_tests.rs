@@ -0,0 +1,251 @@
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+/// Test file checking a verifier with Hardhat, ethers and chai
+const HARDHAT: &str = include_str!("../templates/tests/verifier.test.js");
+/// Test file checking a verifier with Foundry and forge-std
+const FOUNDRY: &str = include_str!("../templates/tests/verifier.t.sol");
+
+/// A framework running the integration tests of an exported verifier
+pub enum Framework {
+    Hardhat,
+    Foundry,
+}
+
+impl Framework {
+    pub fn from(s: &str) -> Result<Self, String> {
+        match s {
+            "hardhat" => Ok(Framework::Hardhat),
+            "foundry" => Ok(Framework::Foundry),
+            _ => Err(format!("Unknown test framework {}", s)),
+        }
+    }
+
+    /// Returns the path of the tests of the verifier at `verifier`, following the conventions of the framework
+    pub fn test_path(&self, verifier: &Path) -> PathBuf {
+        let stem = verifier.file_stem().unwrap().to_string_lossy();
+
+        let file = match self {
+            Framework::Hardhat => format!("{}.test.js", stem),
+            Framework::Foundry => format!("{}.t.sol", stem),
+        };
+
+        verifier.with_file_name(file)
+    }
+}
+
+/// Returns a test file checking that the verifier at `verifier` accepts `proof`, as written by `generate-proof`, and
+/// rejects it once falsified. `abi` is the Solidity ABI exposed by the verifier.
+pub fn generate(
+    framework: &Framework,
+    verifier: &Path,
+    proof: &Value,
+    scheme: &str,
+    abi: &str,
+) -> Result<String, String> {
+    let invalid =
+        || String::from("Invalid proof file: expected an object with `proof` and `inputs`");
+
+    let points = proof["proof"].as_object().ok_or_else(invalid)?;
+    let inputs = proof["inputs"].as_array().ok_or_else(invalid)?;
+
+    match framework {
+        // the points of the proof, in the order of the proof file as well as sorted by name, are in the order of the
+        // parameters of `verifyTx` in the v1 ABI
+        Framework::Hardhat => Ok(HARDHAT
+            .replace("{{scheme}}", scheme)
+            .replace("{{abi}}", abi)
+            .replace(
+                "{{proof}}",
+                &serde_json::to_string_pretty(&json_proof(points, inputs)).unwrap(),
+            )),
+        Framework::Foundry => {
+            let (mut statements, mut arguments, falsify) = match abi {
+                "v1" => solidity_v1_proof(points)?,
+                _ => solidity_v2_proof(points)?,
+            };
+
+            if !inputs.is_empty() {
+                statements.push(format!(
+                    "uint[{}] memory input = {};",
+                    inputs.len(),
+                    solidity_array(inputs)?
+                ));
+                arguments.push(String::from("input"));
+            }
+
+            Ok(FOUNDRY
+                .replace(
+                    "{{verifier}}",
+                    &verifier.file_name().unwrap().to_string_lossy(),
+                )
+                .replace(
+                    "{{proof}}",
+                    &statements
+                        .iter()
+                        .map(|s| format!("        {}", s))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                )
+                .replace("{{falsify}}", &falsify)
+                .replace("{{arguments}}", &arguments.join(", ")))
+        }
+    }
+}
+
+fn json_proof(points: &Map<String, Value>, inputs: &[Value]) -> Value {
+    let mut proof = Map::new();
+    proof.insert(String::from("proof"), Value::Object(points.clone()));
+    proof.insert(String::from("inputs"), Value::Array(inputs.to_vec()));
+    Value::Object(proof)
+}
+
+/// Returns the Solidity array literal of an array of hexadecimal numbers, whose type is set by its first element
+fn solidity_array(values: &[Value]) -> Result<String, String> {
+    let values = values
+        .iter()
+        .map(|v| {
+            v.as_str()
+                .map(String::from)
+                .ok_or_else(|| format!("Invalid proof file: expected a number, found {}", v))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(format!(
+        "[{}]",
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| match i {
+                0 => format!("uint({})", v),
+                _ => v.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+}
+
+/// A point of a proof, given by its coordinates as Solidity array literals
+enum Point {
+    G1(String, String),
+    G2(String, String),
+}
+
+impl Point {
+    fn from(value: &Value) -> Result<Self, String> {
+        let invalid = || format!("Invalid proof file: expected a point, found {}", value);
+
+        let coordinates = value
+            .as_array()
+            .filter(|c| c.len() == 2)
+            .ok_or_else(invalid)?;
+
+        match (&coordinates[0], &coordinates[1]) {
+            (Value::String(x), Value::String(y)) => Ok(Point::G1(x.clone(), y.clone())),
+            (Value::Array(x), Value::Array(y)) => {
+                Ok(Point::G2(solidity_array(x)?, solidity_array(y)?))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Returns the statements defining the proof as the arrays taken by `verifyTx` in the v1 ABI, the arguments of
+/// `verifyTx`, and the statement negating the point `a`
+fn solidity_v1_proof(
+    points: &Map<String, Value>,
+) -> Result<(Vec<String>, Vec<String>, String), String> {
+    // the points of the proof are in the order of the parameters of `verifyTx`, see `generate`
+    let statements = points
+        .iter()
+        .map(|(name, point)| {
+            Point::from(point).map(|point| match point {
+                Point::G1(x, y) => format!("uint[2] memory {} = [uint({}), {}];", name, x, y),
+                Point::G2(x, y) => format!("uint[2][2] memory {} = [{}, {}];", name, x, y),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((
+        statements,
+        points.keys().cloned().collect(),
+        String::from("a[1] = (Q - a[1]) % Q;"),
+    ))
+}
+
+/// Returns the statements defining the proof as the `Proof` struct taken by `verifyTx` in the v2 ABI, the arguments
+/// of `verifyTx`, and the statement negating the point `a`
+fn solidity_v2_proof(
+    points: &Map<String, Value>,
+) -> Result<(Vec<String>, Vec<String>, String), String> {
+    let statements = std::iter::once(Ok(String::from("Verifier.Proof memory proof;")))
+        .chain(points.iter().map(|(name, point)| {
+            Point::from(point).map(|point| match point {
+                Point::G1(x, y) => format!("proof.{} = Pairing.G1Point({}, {});", name, x, y),
+                Point::G2(x, y) => format!("proof.{} = Pairing.G2Point({}, {});", name, x, y),
+            })
+        }))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((
+        statements,
+        vec![String::from("proof")],
+        String::from("proof.a.Y = (Q - proof.a.Y) % Q;"),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proof() -> Value {
+        serde_json::from_str(
+            r#"{
+                "proof": {
+                    "a": ["0x1", "0x2"],
+                    "b": [["0x3", "0x4"], ["0x5", "0x6"]],
+                    "c": ["0x7", "0x8"]
+                },
+                "inputs": ["0x9"]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn foundry() {
+        let verifier = Path::new("out/verifier.sol");
+
+        assert_eq!(
+            Framework::Foundry.test_path(verifier),
+            PathBuf::from("out/verifier.t.sol")
+        );
+
+        let v1 = generate(&Framework::Foundry, verifier, &proof(), "g16", "v1").unwrap();
+
+        assert!(v1.contains("import \"./verifier.sol\";"));
+        assert!(v1.contains("uint[2][2] memory b = [[uint(0x3), 0x4], [uint(0x5), 0x6]];"));
+        assert!(v1.contains("uint[1] memory input = [uint(0x9)];"));
+        assert!(v1.contains("verifier.verifyTx(a, b, c, input)"));
+
+        let v2 = generate(&Framework::Foundry, verifier, &proof(), "g16", "v2").unwrap();
+
+        assert!(v2.contains("proof.b = Pairing.G2Point([uint(0x3), 0x4], [uint(0x5), 0x6]);"));
+        assert!(v2.contains("verifier.verifyTx(proof, input)"));
+    }
+
+    #[test]
+    fn hardhat() {
+        let verifier = Path::new("verifier.sol");
+
+        assert_eq!(
+            Framework::Hardhat.test_path(verifier),
+            PathBuf::from("verifier.test.js")
+        );
+
+        let test = generate(&Framework::Hardhat, verifier, &proof(), "gm17", "v2").unwrap();
+
+        assert!(test.contains("const SCHEME = \"gm17\";"));
+        assert!(test.contains("\"inputs\": [\n    \"0x9\"\n  ]"));
+    }
+}
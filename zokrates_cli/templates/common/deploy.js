@@ -0,0 +1,31 @@
+const fs = require('fs');
+const path = require('path');
+const solc = require('solc');
+const Web3 = require('web3');
+
+// Deploys the verifier contract exported by index.js and checks proof.json against it
+const web3 = new Web3(new Web3.providers.HttpProvider(process.env.ETH_RPC_URL || 'http://localhost:8545'));
+
+const source = fs.readFileSync(path.join(__dirname, 'verifier.sol')).toString();
+const output = JSON.parse(solc.compile(JSON.stringify({
+    language: 'Solidity',
+    sources: { 'verifier.sol': { content: source } },
+    settings: { outputSelection: { '*': { '*': ['abi', 'evm.bytecode'] } } }
+})));
+const { abi, evm } = output.contracts['verifier.sol']['Verifier'];
+
+(async () => {
+    const [account] = await web3.eth.getAccounts();
+
+    const contract = await new web3.eth.Contract(abi)
+        .deploy({ data: '0x' + evm.bytecode.object })
+        .send({ from: account, gas: 2000000 });
+    console.log('Verifier deployed at ' + contract.options.address);
+
+    const { proof, inputs } = JSON.parse(fs.readFileSync(path.join(__dirname, 'proof.json')));
+    const verified = await contract.methods.verifyTx(proof.a, proof.b, proof.c, inputs).call({ from: account });
+    console.log('Verification result: ' + verified);
+})().catch((err) => {
+    console.error(err);
+    process.exit(1);
+});
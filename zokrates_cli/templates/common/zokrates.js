@@ -0,0 +1,62 @@
+const fs = require('fs');
+const path = require('path');
+const { initialize } = require('zokrates-js/node');
+
+// Resolves the imports of the modules of this project, the standard library being resolved by zokrates-js
+const resolveCallback = (currentLocation, importLocation) => {
+    const location = path.resolve(path.dirname(currentLocation), importLocation + '.zok');
+    return { source: fs.readFileSync(location).toString(), location };
+};
+
+// Initializes zokrates-js and returns helpers to compile programs located in this project and run them
+module.exports = () => initialize().then((zokrates) => {
+    const compile = (source) => zokrates.compile(source, {
+        location: path.join(__dirname, 'main.zok'),
+        resolveCallback
+    });
+
+    const run = (artifacts, args) => JSON.parse(zokrates.computeWitness(artifacts, args).output);
+
+    // Computes the root of a tree where `leaf` is at position `index` and all other leaves are 0,
+    // returning it along with the authentication path of the leaf, as expected by ./merkleRoot.zok
+    const merkleTree = (leaf, index) => {
+        const hashPair = compile(`
+            from "./merkleRoot" import hashPair
+            def main(field left, field right) -> field:
+                return hashPair(left, right)
+        `);
+
+        let node = leaf;
+        let empty = '0';
+        const path = [];
+        const directions = [];
+        for (let i = 0; i < 4; i++) {
+            const isRight = ((index >> i) & 1) === 1;
+            path.push(empty);
+            directions.push(isRight);
+            [node] = run(hashPair, isRight ? [empty, node] : [node, empty]);
+            [empty] = run(hashPair, [empty, empty]);
+        }
+        return { root: node, path, directions };
+    };
+
+    // Compiles ./root.zok, generates a proof for the given arguments and exports the verifier contract
+    const prove = (args) => {
+        const source = fs.readFileSync(path.join(__dirname, 'root.zok')).toString();
+        const artifacts = zokrates.compile(source, {
+            location: path.join(__dirname, 'root.zok'),
+            resolveCallback
+        });
+        const { witness } = zokrates.computeWitness(artifacts, args);
+
+        // The setup is run on each call for demonstration purposes, a real deployment runs it once
+        const keypair = zokrates.setup(artifacts.program);
+        const proof = zokrates.generateProof(artifacts.program, witness, keypair.pk);
+
+        fs.writeFileSync(path.join(__dirname, 'verifier.sol'), zokrates.exportSolidityVerifier(keypair.vk, 'v1'));
+        fs.writeFileSync(path.join(__dirname, 'proof.json'), JSON.stringify(proof, null, 2));
+        console.log('Proof written to proof.json, verifier contract to verifier.sol');
+    };
+
+    return { compile, run, merkleTree, prove };
+});
@@ -0,0 +1,80 @@
+// This file is MIT Licensed.
+pragma solidity ^0.6.1;
+
+/// Checks proofs wrapped in an EIP-712 message signed by the prover. The signature binds the prover and a nonce to the
+/// public inputs, so that a proof observed in the mempool cannot be submitted by someone else or replayed.
+///
+/// Usage, with `input` the public inputs passed to the verifier:
+///
+///     mapping(address => uint256) public nonces;
+///
+///     function submit(address prover, uint[] memory input, bytes memory signature, ...) public {
+///         ProofEnvelope.check(nonces, ProofEnvelope.domainSeparator("MyApp", "1"), prover, input, signature);
+///         require(verifier.verifyTx(..., input));
+///         ...
+///     }
+library ProofEnvelope {
+    bytes32 constant EIP712_DOMAIN_TYPEHASH = keccak256(
+        "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
+    );
+    bytes32 constant PROOF_SUBMISSION_TYPEHASH = keccak256(
+        "ProofSubmission(address prover,uint256 nonce,uint256[] inputs)"
+    );
+
+    /// @return the EIP-712 domain separator of the calling contract on the current chain
+    function domainSeparator(string memory name, string memory version) internal view returns (bytes32) {
+        uint256 chainId;
+        assembly {
+            chainId := chainid()
+        }
+        return keccak256(abi.encode(
+            EIP712_DOMAIN_TYPEHASH,
+            keccak256(bytes(name)),
+            keccak256(bytes(version)),
+            chainId,
+            address(this)
+        ));
+    }
+
+    /// @return the EIP-712 digest signed by `prover` to submit a proof of `inputs` with `nonce`
+    function digest(bytes32 domain, address prover, uint256 nonce, uint[] memory inputs) internal pure returns (bytes32) {
+        bytes32 structHash = keccak256(abi.encode(
+            PROOF_SUBMISSION_TYPEHASH,
+            prover,
+            nonce,
+            keccak256(abi.encodePacked(inputs))
+        ));
+        return keccak256(abi.encodePacked("\x19\x01", domain, structHash));
+    }
+
+    /// @return the signer of `hash`, rejecting malleable and invalid signatures
+    function recover(bytes32 hash, bytes memory signature) internal pure returns (address) {
+        require(signature.length == 65, "invalid signature length");
+        bytes32 r;
+        bytes32 s;
+        uint8 v;
+        assembly {
+            r := mload(add(signature, 0x20))
+            s := mload(add(signature, 0x40))
+            v := byte(0, mload(add(signature, 0x60)))
+        }
+        require(uint256(s) <= 0x7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF5D576E7357A4501DDFE92F46681B20A0, "invalid signature s");
+        require(v == 27 || v == 28, "invalid signature v");
+        address signer = ecrecover(hash, v, r, s);
+        require(signer != address(0), "invalid signature");
+        return signer;
+    }
+
+    /// Checks that `prover` signed the submission of `inputs` with its current nonce, and increments the nonce
+    function check(
+        mapping(address => uint256) storage nonces,
+        bytes32 domain,
+        address prover,
+        uint[] memory inputs,
+        bytes memory signature
+    ) internal {
+        bytes32 hash = digest(domain, prover, nonces[prover], inputs);
+        require(recover(hash, signature) == prover, "envelope not signed by the prover");
+        nonces[prover]++;
+    }
+}
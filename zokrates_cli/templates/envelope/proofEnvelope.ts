@@ -0,0 +1,54 @@
+// Builds and signs the EIP-712 messages checked by ProofEnvelope.sol
+
+export interface Domain {
+    name: string;
+    version: string;
+    chainId: number;
+    // the contract calling ProofEnvelope.check
+    verifyingContract: string;
+}
+
+export interface Envelope {
+    prover: string;
+    nonce: string;
+    // the public inputs of the proof, as found in proof.json
+    inputs: string[];
+}
+
+/// Any EIP-1193 provider, for example `window.ethereum`
+export interface Provider {
+    request(args: { method: string; params: unknown[] }): Promise<unknown>;
+}
+
+export const types = {
+    EIP712Domain: [
+        { name: 'name', type: 'string' },
+        { name: 'version', type: 'string' },
+        { name: 'chainId', type: 'uint256' },
+        { name: 'verifyingContract', type: 'address' },
+    ],
+    ProofSubmission: [
+        { name: 'prover', type: 'address' },
+        { name: 'nonce', type: 'uint256' },
+        { name: 'inputs', type: 'uint256[]' },
+    ],
+};
+
+/// Returns the typed data of an envelope, as expected by `eth_signTypedData_v4`
+export function typedData(domain: Domain, envelope: Envelope) {
+    return {
+        types,
+        primaryType: 'ProofSubmission',
+        domain,
+        message: envelope,
+    };
+}
+
+/// Asks the prover to sign an envelope, returning the signature to pass to the contract with the proof
+export async function signEnvelope(provider: Provider, domain: Domain, envelope: Envelope): Promise<string> {
+    const signature = await provider.request({
+        method: 'eth_signTypedData_v4',
+        params: [envelope.prover, JSON.stringify(typedData(domain, envelope))],
+    });
+    return signature as string;
+}
@@ -0,0 +1,25 @@
+const zokrates = require('./zokrates');
+
+// Deposits a commitment in the mixer and proves its withdrawal
+// The secret and the nonce must be chosen at random and kept private by the depositor
+const secret = process.argv[2] || '1337';
+const nonce = process.argv[3] || '42';
+const index = 2;
+
+zokrates().then(({ compile, run, merkleTree, prove }) => {
+    const hashes = compile(`
+        import "commitments/commitment" as commitment
+        import "commitments/nullifier" as nullifier
+        def main(field secret, field nonce, field index) -> (field, field):
+            return commitment(secret, nonce), nullifier(secret, index)
+    `);
+    const [leaf, nullifierHash] = run(hashes, [secret, nonce, index.toString()]);
+
+    // The tree is usually maintained by the mixer contract, which inserts commitments on deposit
+    const { root, path, directions } = merkleTree(leaf, index);
+    console.log('Commitment: ' + leaf);
+    console.log('Root: ' + root);
+    console.log('Nullifier: ' + nullifierHash);
+
+    prove([root, nullifierHash, secret, nonce, path, directions]);
+});
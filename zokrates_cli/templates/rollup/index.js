@@ -0,0 +1,58 @@
+const zokrates = require('./zokrates');
+
+// Proves a transfer between two accounts of the rollup
+// The secret and the nonce of the sender must be chosen at random and kept private
+const secret = process.argv[2] || '1337';
+const nonce = process.argv[3] || '42';
+const amount = process.argv[4] || '30';
+
+// The two accounts of the rollup, all other leaves of the state tree being 0
+const fromIndex = 0;
+const fromBalance = '100';
+const toIndex = 1;
+const recipient = '7';
+const toBalance = '50';
+
+zokrates().then(({ compile, run, prove }) => {
+    const hashes = compile(`
+        import "commitments/commitment" as commitment
+        import "./account" as account
+        def main(field secret, field nonce, field fromBalance, field recipient, field toBalance) -> (field, field):
+            return account(commitment(secret, nonce), fromBalance), account(recipient, toBalance)
+    `);
+    const hashPair = compile(`
+        from "./merkleRoot" import hashPair
+        def main(field left, field right) -> field:
+            return hashPair(left, right)
+    `);
+    const hash = (left, right) => run(hashPair, [left, right])[0];
+
+    // Computes the root of a state tree holding the two accounts in its first two leaves,
+    // along with the paths of both accounts
+    const state = (fromBalance, toBalance) => {
+        const [from, to] = run(hashes, [secret, nonce, fromBalance, recipient, toBalance]);
+
+        let node = hash(from, to);
+        let empty = hash('0', '0');
+        const paths = [[to], [from]];
+        for (let i = 1; i < 4; i++) {
+            paths.forEach((path) => path.push(empty));
+            node = hash(node, empty);
+            empty = hash(empty, empty);
+        }
+        return { root: node, paths };
+    };
+    const directions = (index) => [0, 1, 2, 3].map((i) => ((index >> i) & 1) === 1);
+
+    const before = state(fromBalance, toBalance);
+    const intermediate = state((BigInt(fromBalance) - BigInt(amount)).toString(), toBalance);
+    const after = state((BigInt(fromBalance) - BigInt(amount)).toString(), (BigInt(toBalance) + BigInt(amount)).toString());
+    console.log('Old root: ' + before.root);
+    console.log('New root: ' + after.root);
+
+    prove([
+        before.root, after.root, secret, nonce, amount,
+        fromBalance, before.paths[fromIndex], directions(fromIndex),
+        recipient, toBalance, intermediate.paths[toIndex], directions(toIndex)
+    ]);
+});
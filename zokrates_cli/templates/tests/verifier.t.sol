@@ -0,0 +1,42 @@
+// Generated by `zokrates export-verifier --tests foundry`: checks that the verifier accepts a valid proof, and rejects
+// it once falsified. Run it with `forge test` from a project with `forge-std` installed.
+pragma solidity ^0.6.1;
+pragma experimental ABIEncoderV2;
+
+import "forge-std/Test.sol";
+import "./{{verifier}}";
+
+contract VerifierTest is Test {
+    // the base field of the points of the proof
+    uint256 constant Q = 21888242871839275222246405745257275088696311157297823662689037894645226208583;
+
+    Verifier verifier;
+
+    function setUp() public {
+        verifier = new Verifier();
+    }
+
+    // verifies the proof, after negating its point `a` if `falsify` is set, which keeps it on the curve but no longer
+    // satisfies the verification
+    function verify(bool falsify) internal view returns (bool) {
+{{proof}}
+        if (falsify) {
+            {{falsify}}
+        }
+
+        // an invalid proof may also make the verifier revert
+        try verifier.verifyTx({{arguments}}) returns (bool r) {
+            return r;
+        } catch {
+            return false;
+        }
+    }
+
+    function testAcceptsValidProof() public {
+        assertTrue(verify(false));
+    }
+
+    function testRejectsInvalidProof() public {
+        assertTrue(!verify(true));
+    }
+}
@@ -0,0 +1,49 @@
+// Generated by `zokrates export-verifier --tests hardhat`: checks that the verifier accepts a valid proof, and rejects
+// it once falsified. Run it with `npx hardhat test` after copying the verifier to the contracts of the project.
+const { expect } = require("chai");
+const { ethers } = require("hardhat");
+
+const SCHEME = "{{scheme}}";
+const ABI = "{{abi}}";
+
+// the base field of the points of the proof
+const Q = BigInt("21888242871839275222246405745257275088696311157297823662689037894645226208583");
+
+const { proof, inputs } = {{proof}};
+
+// the arguments of `verifyTx`: the v1 ABI takes the points of the proof separately, in the order of their names
+function args(proof) {
+    const points = ABI == "v1" ? Object.values(proof) : [proof];
+    return inputs.length > 0 ? [...points, inputs] : points;
+}
+
+// negates the point `a`, which stays on the curve but no longer satisfies the verification
+function falsify(proof) {
+    const [x, y] = proof.a;
+    return { ...proof, a: [x, "0x" + ((Q - BigInt(y)) % Q).toString(16)] };
+}
+
+describe("Verifier", function () {
+    let verifier;
+
+    before(async function () {
+        // the gm17 verifier links to the BN256G2 library
+        const libraries = {};
+        if (SCHEME == "gm17") {
+            const library = await (await ethers.getContractFactory("BN256G2")).deploy();
+            libraries.BN256G2 = library.address;
+        }
+
+        verifier = await (await ethers.getContractFactory("Verifier", { libraries })).deploy();
+    });
+
+    it("accepts a valid proof", async function () {
+        expect(await verifier.verifyTx(...args(proof))).to.equal(true);
+    });
+
+    it("rejects an invalid proof", async function () {
+        // an invalid proof may also make the verifier revert
+        const verified = await verifier.verifyTx(...args(falsify(proof))).catch(() => false);
+        expect(verified).to.equal(false);
+    });
+});
@@ -0,0 +1,27 @@
+const zokrates = require('./zokrates');
+
+// Registers a voter and proves a vote in an election
+// The secret and the nonce must be chosen at random and kept private by the voter
+const secret = process.argv[2] || '1337';
+const nonce = process.argv[3] || '42';
+const electionId = process.argv[4] || '1';
+const vote = process.argv[5] || '1';
+const index = 5;
+
+zokrates().then(({ compile, run, merkleTree, prove }) => {
+    const hashes = compile(`
+        import "commitments/commitment" as commitment
+        import "commitments/nullifier" as nullifier
+        def main(field secret, field nonce, field electionId) -> (field, field):
+            return commitment(secret, nonce), nullifier(secret, electionId)
+    `);
+    const [leaf, nullifierHash] = run(hashes, [secret, nonce, electionId]);
+
+    // The tree of eligible voters is usually maintained by the voting contract, which inserts commitments on registration
+    const { root, path, directions } = merkleTree(leaf, index);
+    console.log('Commitment: ' + leaf);
+    console.log('Root: ' + root);
+    console.log('Nullifier: ' + nullifierHash);
+
+    prove([root, electionId, nullifierHash, vote, secret, nonce, path, directions]);
+});
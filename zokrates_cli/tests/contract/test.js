@@ -5,7 +5,14 @@ const contractPath = process.argv[2]
 const proofPath = process.argv[3]
 const format = process.argv[4]
 const abiVersion = process.argv[5];
-const web3 = new Web3(new Web3.providers.HttpProvider('http://localhost:8545'));
+// use the node given in ETH_RPC_URL if any, otherwise an in-process EVM
+const provider = process.env.ETH_RPC_URL
+    ? new Web3.providers.HttpProvider(process.env.ETH_RPC_URL)
+    : require('ganache-core').provider();
+const web3 = new Web3(provider);
+// the gas used by the deployment and by the verification of the correct proof is written to ZOKRATES_GAS_REPORT if set
+const gasReportPath = process.env.ZOKRATES_GAS_REPORT
+let gas = {}
 
 // -----Compile contract-----
 const source = fs.readFileSync(contractPath, 'UTF-8');
@@ -46,16 +53,28 @@ let jsonInterface = JSON.parse(solc.compile(jsonContractSource));
         })
         .send({
             from: accounts[0],
-            gas: '2000000'
+            gas: '4000000'
         })
         .on('receipt', (tx) => {
             if (tx.status == true) {
                 console.log("Contract Deployed! Gas used: " + tx.gasUsed)
+                gas.deployment = tx.gasUsed
             }
         })
         .then(newContractInstance => {
             contract = newContractInstance;
-            Promise.all([makeTransaction(accounts[0], true), makeTransaction(accounts[0], false)])
+            let transactions = [makeTransaction(accounts[0], true), makeTransaction(accounts[0], false)]
+            // only the g16 verifier supports batches
+            if (format == "g16") {
+                transactions.push(makeBatchTransaction(accounts[0], true), makeBatchTransaction(accounts[0], false))
+            }
+            return Promise.all(transactions)
+        })
+        .then(() => {
+            if (gasReportPath) {
+                fs.writeFileSync(gasReportPath, JSON.stringify(gas))
+            }
+            process.exit(0)
         })
         .catch(err => {
             console.log(err);
@@ -73,6 +92,7 @@ let jsonInterface = JSON.parse(solc.compile(jsonContractSource));
 
             if (tx.status == true) {
                 console.log("Correct proof works! Gas used: " + tx.gasUsed)
+                gas.verification = tx.gasUsed
             }
         }
 
@@ -87,10 +107,48 @@ let jsonInterface = JSON.parse(solc.compile(jsonContractSource));
 
         return abiVersion == "v1" ? 
             verifyTx_ABIV1(proof, account, correct).on('receipt', handleReceipt)
-                .catch(handleError)
+                .catch(err => handleError(err, correct))
             :
             verifyTx_ABIV2(proof, account, correct).on('receipt', handleReceipt)
-                .catch(handleError)
+                .catch(err => handleError(err, correct))
+    }
+
+    // verifies a batch of two proofs, the second one being falsified if `correct` is false
+    function makeBatchTransaction(account, correct) {
+        let proofs = [getProof(true), getProof(correct)];
+        let args;
+
+        if (abiVersion == "v1") {
+            args = [0, 1, 2].map(i => proofs.map(p => p[0][i]));
+        } else {
+            args = [proofs.map(p => p[0])];
+        }
+        if (proofs[0][1].length > 0) {
+            args.push(proofs.map(p => p[1]));
+        }
+
+        return contract.methods.verifyBatch(...args).send({
+            from: account,
+            gas: 5000000
+        })
+            .on('receipt', tx => {
+                if (tx.status == true && !correct) {
+                    console.log("Batch verification has been successful with invalid proof data! THIS IS A BUG")
+                    process.exit(1)
+                }
+
+                if (tx.status == true) {
+                    console.log("Correct batch works! Gas used: " + tx.gasUsed)
+                }
+            })
+            .catch(err => {
+                if (!correct) {
+                    console.log("Batch with false proof not verified! Success")
+                } else {
+                    console.log(err);
+                    process.exit(1)
+                }
+            })
     }
 
     function verifyTx_ABIV2(proof, account, correct) {
@@ -98,7 +156,7 @@ let jsonInterface = JSON.parse(solc.compile(jsonContractSource));
         var arguments = proof[0]
         arguments = proof[1].length > 0 ? [arguments[0], proof[1]] : arguments
 
-        contract.methods.verifyTx(...arguments).send({
+        return contract.methods.verifyTx(...arguments).send({
             from: account,
             gas: 5000000
         })
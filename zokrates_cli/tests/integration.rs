@@ -52,6 +52,50 @@ mod integration {
         }
     }
 
+    #[test]
+    fn test_compute_witness_without_trace() {
+        let tmp_dir = TempDir::new(".tmp").unwrap();
+        let tmp_base = tmp_dir.path();
+        let flattened_path = tmp_base.join("out");
+        let abi_spec_path = tmp_base.join("abi.json");
+        let source_map_path = tmp_base.join("source_map.json");
+        let witness_path = tmp_base.join("witness");
+
+        assert_cli::Assert::command(&[
+            env!("CARGO_BIN_EXE_zokrates"),
+            "compile",
+            "-i",
+            "./tests/code/arithmetics.zok",
+            "-s",
+            abi_spec_path.to_str().unwrap(),
+            "-m",
+            source_map_path.to_str().unwrap(),
+            "-o",
+            flattened_path.to_str().unwrap(),
+            "--light",
+        ])
+        .succeeds()
+        .unwrap();
+
+        // the trace options must not be required when no trace is requested
+        assert_cli::Assert::command(&[
+            env!("CARGO_BIN_EXE_zokrates"),
+            "compute-witness",
+            "-i",
+            flattened_path.to_str().unwrap(),
+            "-o",
+            witness_path.to_str().unwrap(),
+            "-a",
+            "1",
+            "2",
+        ])
+        .succeeds()
+        .unwrap();
+
+        let witness = fs::read_to_string(&witness_path).unwrap();
+        assert!(witness.contains("~out_0 12"));
+    }
+
     fn install_nodejs_deps() {
         let out_dir = concat!(env!("OUT_DIR"), "/contract");
 
@@ -72,6 +116,7 @@ mod integration {
         let test_case_path = tmp_base.join(program_name);
         let flattened_path = tmp_base.join(program_name).join("out");
         let abi_spec_path = tmp_base.join(program_name).join("abi.json");
+        let source_map_path = tmp_base.join(program_name).join("source_map.json");
         let witness_path = tmp_base.join(program_name).join("witness");
         let inline_witness_path = tmp_base.join(program_name).join("inline_witness");
         let proof_path = tmp_base.join(program_name).join("proof.json");
@@ -99,6 +144,8 @@ mod integration {
             program_path.to_str().unwrap(),
             "-s",
             abi_spec_path.to_str().unwrap(),
+            "-m",
+            source_map_path.to_str().unwrap(),
             "-o",
             flattened_path.to_str().unwrap(),
             "--light",
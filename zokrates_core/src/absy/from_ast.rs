@@ -103,13 +103,28 @@ impl<'ast, T: Field> From<pest::Function<'ast>> for absy::SymbolDeclarationNode<
                     .returns
                     .clone()
                     .into_iter()
-                    .map(|r| absy::UnresolvedTypeNode::from(r))
+                    .map(|r| absy::UnresolvedTypeNode::from(r.ty))
                     .collect(),
             );
 
+        let private_outputs = function
+            .returns
+            .iter()
+            .enumerate()
+            .filter_map(|(index, r)| match r.visibility {
+                Some(pest::Visibility::Private(_)) => Some(index),
+                _ => None,
+            })
+            .collect();
+
         let id = function.id.span.as_str();
 
         let function = absy::Function::<T> {
+            attributes: function
+                .attributes
+                .into_iter()
+                .map(|a| absy::AttributeNode::from(a))
+                .collect(),
             arguments: function
                 .parameters
                 .into_iter()
@@ -121,6 +136,7 @@ impl<'ast, T: Field> From<pest::Function<'ast>> for absy::SymbolDeclarationNode<
                 .flat_map(|s| statements_from_statement(s))
                 .collect(),
             signature,
+            private_outputs,
         }
         .span(span.clone());
 
@@ -132,6 +148,18 @@ impl<'ast, T: Field> From<pest::Function<'ast>> for absy::SymbolDeclarationNode<
     }
 }
 
+impl<'ast> From<pest::Attribute<'ast>> for absy::AttributeNode<'ast> {
+    fn from(attribute: pest::Attribute<'ast>) -> absy::AttributeNode<'ast> {
+        use absy::NodeValue;
+
+        absy::Attribute {
+            id: attribute.id.span.as_str(),
+            argument: attribute.argument.map(|a| a.span.as_str()),
+        }
+        .span(attribute.span)
+    }
+}
+
 impl<'ast> From<pest::Parameter<'ast>> for absy::ParameterNode<'ast> {
     fn from(param: pest::Parameter<'ast>) -> absy::ParameterNode {
         use absy::NodeValue;
@@ -272,6 +300,7 @@ impl<'ast, T: Field> From<pest::IterationStatement<'ast>> for absy::StatementNod
         use absy::NodeValue;
         let from = absy::ExpressionNode::from(statement.from);
         let to = absy::ExpressionNode::from(statement.to);
+        let max = statement.max.map(|max| absy::ExpressionNode::from(max.0));
         let index = statement.index.span.as_str();
         let ty = absy::UnresolvedTypeNode::from(statement.ty);
         let statements: Vec<absy::StatementNode<T>> = statement
@@ -282,7 +311,7 @@ impl<'ast, T: Field> From<pest::IterationStatement<'ast>> for absy::StatementNod
 
         let var = absy::Variable::new(index, ty).span(statement.index.span);
 
-        absy::Statement::For(var, from, to, statements).span(statement.span)
+        absy::Statement::For(var, from, to, max, statements).span(statement.span)
     }
 }
 
@@ -322,6 +351,10 @@ impl<'ast, T: Field> From<pest::BinaryExpression<'ast>> for absy::ExpressionNode
                 box absy::ExpressionNode::from(*expression.left),
                 box absy::ExpressionNode::from(*expression.right),
             ),
+            pest::BinaryOperator::Rem => absy::Expression::Rem(
+                box absy::ExpressionNode::from(*expression.left),
+                box absy::ExpressionNode::from(*expression.right),
+            ),
             pest::BinaryOperator::Eq => absy::Expression::Eq(
                 box absy::ExpressionNode::from(*expression.left),
                 box absy::ExpressionNode::from(*expression.right),
@@ -570,6 +603,14 @@ impl<'ast, T: Field> From<pest::ConstantExpression<'ast>> for absy::ExpressionNo
                 u32::from_str_radix(&n.value.trim_start_matches("0x"), 16).unwrap(),
             )
             .span(n.span),
+            pest::ConstantExpression::U64(n) => absy::Expression::U64Constant(
+                u64::from_str_radix(&n.value.trim_start_matches("0x"), 16).unwrap(),
+            )
+            .span(n.span),
+            pest::ConstantExpression::U128(n) => absy::Expression::U128Constant(
+                u128::from_str_radix(&n.value.trim_start_matches("0x"), 16).unwrap(),
+            )
+            .span(n.span),
         }
     }
 }
@@ -621,6 +662,8 @@ impl<'ast> From<pest::Type<'ast>> for absy::UnresolvedTypeNode {
                 pest::BasicType::U8(t) => absy::UnresolvedType::Uint(8).span(t.span),
                 pest::BasicType::U16(t) => absy::UnresolvedType::Uint(16).span(t.span),
                 pest::BasicType::U32(t) => absy::UnresolvedType::Uint(32).span(t.span),
+                pest::BasicType::U64(t) => absy::UnresolvedType::Uint(64).span(t.span),
+                pest::BasicType::U128(t) => absy::UnresolvedType::Uint(128).span(t.span),
             },
             pest::Type::Array(t) => {
                 let inner_type = match t.ty {
@@ -632,6 +675,8 @@ impl<'ast> From<pest::Type<'ast>> for absy::UnresolvedTypeNode {
                         pest::BasicType::U8(t) => absy::UnresolvedType::Uint(8).span(t.span),
                         pest::BasicType::U16(t) => absy::UnresolvedType::Uint(16).span(t.span),
                         pest::BasicType::U32(t) => absy::UnresolvedType::Uint(32).span(t.span),
+                        pest::BasicType::U64(t) => absy::UnresolvedType::Uint(64).span(t.span),
+                        pest::BasicType::U128(t) => absy::UnresolvedType::Uint(128).span(t.span),
                     },
                     pest::BasicOrStructType::Struct(t) => {
                         absy::UnresolvedType::User(t.span.as_str().to_string()).span(t.span)
@@ -687,6 +732,7 @@ mod tests {
                 id: &source[4..8],
                 symbol: absy::Symbol::HereFunction(
                     absy::Function {
+                        attributes: vec![],
                         arguments: vec![],
                         statements: vec![absy::Statement::Return(
                             absy::ExpressionList {
@@ -701,6 +747,7 @@ mod tests {
                         signature: absy::UnresolvedSignature::new()
                             .inputs(vec![])
                             .outputs(vec![absy::UnresolvedType::FieldElement.mock()]),
+                        private_outputs: vec![],
                     }
                     .into(),
                 ),
@@ -720,6 +767,7 @@ mod tests {
                 id: &source[4..8],
                 symbol: absy::Symbol::HereFunction(
                     absy::Function {
+                        attributes: vec![],
                         arguments: vec![],
                         statements: vec![absy::Statement::Return(
                             absy::ExpressionList {
@@ -731,6 +779,7 @@ mod tests {
                         signature: absy::UnresolvedSignature::new()
                             .inputs(vec![])
                             .outputs(vec![absy::UnresolvedType::Boolean.mock()]),
+                        private_outputs: vec![],
                     }
                     .into(),
                 ),
@@ -751,6 +800,7 @@ mod tests {
                 id: &source[4..8],
                 symbol: absy::Symbol::HereFunction(
                     absy::Function {
+                        attributes: vec![],
                         arguments: vec![
                             absy::Parameter::private(
                                 absy::Variable::new(
@@ -785,6 +835,7 @@ mod tests {
                                 absy::UnresolvedType::Boolean.mock(),
                             ])
                             .outputs(vec![absy::UnresolvedType::FieldElement.mock()]),
+                        private_outputs: vec![],
                     }
                     .into(),
                 ),
@@ -806,6 +857,7 @@ mod tests {
                     id: "main",
                     symbol: absy::Symbol::HereFunction(
                         absy::Function {
+                            attributes: vec![],
                             arguments: vec![absy::Parameter::private(
                                 absy::Variable::new("a", ty.clone().mock()).into(),
                             )
@@ -818,6 +870,7 @@ mod tests {
                             )
                             .into()],
                             signature: absy::UnresolvedSignature::new().inputs(vec![ty.mock()]),
+                            private_outputs: vec![],
                         }
                         .into(),
                     ),
@@ -877,6 +930,7 @@ mod tests {
                     id: "main",
                     symbol: absy::Symbol::HereFunction(
                         absy::Function {
+                            attributes: vec![],
                             arguments: vec![],
                             statements: vec![absy::Statement::Return(
                                 absy::ExpressionList {
@@ -886,6 +940,7 @@ mod tests {
                             )
                             .into()],
                             signature: absy::UnresolvedSignature::new(),
+                            private_outputs: vec![],
                         }
                         .into(),
                     ),
@@ -216,15 +216,37 @@ impl<'ast, T: fmt::Debug> fmt::Debug for Module<'ast, T> {
     }
 }
 
+/// An attribute of a function, such as `#[inline(never)]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute<'ast> {
+    pub id: Identifier<'ast>,
+    pub argument: Option<&'ast str>,
+}
+
+pub type AttributeNode<'ast> = Node<Attribute<'ast>>;
+
+impl<'ast> fmt::Display for Attribute<'ast> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.argument {
+            Some(argument) => write!(f, "#[{}({})]", self.id, argument),
+            None => write!(f, "#[{}]", self.id),
+        }
+    }
+}
+
 /// A function defined locally
 #[derive(Clone, PartialEq)]
 pub struct Function<'ast, T> {
+    /// Attributes of the function
+    pub attributes: Vec<AttributeNode<'ast>>,
     /// Arguments of the function
     pub arguments: Vec<ParameterNode<'ast>>,
     /// Vector of statements that are executed when running the function
     pub statements: Vec<StatementNode<'ast, T>>,
     /// function signature
     pub signature: UnresolvedSignature,
+    /// indices of the outputs which are declared private
+    pub private_outputs: Vec<usize>,
 }
 
 pub type FunctionNode<'ast, T> = Node<Function<'ast, T>>;
@@ -300,10 +322,12 @@ pub enum Statement<'ast, T> {
     Declaration(VariableNode<'ast>),
     Definition(AssigneeNode<'ast, T>, ExpressionNode<'ast, T>),
     Assertion(ExpressionNode<'ast, T>),
+    /// A loop over `from..to`, whose upper bound `to` can be a runtime value if a compile-time maximum is given
     For(
         VariableNode<'ast>,
         ExpressionNode<'ast, T>,
         ExpressionNode<'ast, T>,
+        Option<ExpressionNode<'ast, T>>,
         Vec<StatementNode<'ast, T>>,
     ),
     MultipleDefinition(Vec<AssigneeNode<'ast, T>>, ExpressionNode<'ast, T>),
@@ -318,8 +342,12 @@ impl<'ast, T: fmt::Display> fmt::Display for Statement<'ast, T> {
             Statement::Declaration(ref var) => write!(f, "{}", var),
             Statement::Definition(ref lhs, ref rhs) => write!(f, "{} = {}", lhs, rhs),
             Statement::Assertion(ref e) => write!(f, "assert({})", e),
-            Statement::For(ref var, ref start, ref stop, ref list) => {
-                write!(f, "for {} in {}..{} do\n", var, start, stop)?;
+            Statement::For(ref var, ref start, ref stop, ref max, ref list) => {
+                write!(f, "for {} in {}..{} ", var, start, stop)?;
+                if let Some(max) = max {
+                    write!(f, "max {} ", max)?;
+                }
+                write!(f, "do\n")?;
                 for l in list {
                     write!(f, "\t\t{}\n", l)?;
                 }
@@ -347,8 +375,12 @@ impl<'ast, T: fmt::Debug> fmt::Debug for Statement<'ast, T> {
                 write!(f, "Definition({:?}, {:?})", lhs, rhs)
             }
             Statement::Assertion(ref e) => write!(f, "Assertion({:?})", e),
-            Statement::For(ref var, ref start, ref stop, ref list) => {
-                write!(f, "for {:?} in {:?}..{:?} do\n", var, start, stop)?;
+            Statement::For(ref var, ref start, ref stop, ref max, ref list) => {
+                write!(f, "for {:?} in {:?}..{:?} ", var, start, stop)?;
+                if let Some(max) = max {
+                    write!(f, "max {:?} ", max)?;
+                }
+                write!(f, "do\n")?;
                 for l in list {
                     write!(f, "\t\t{:?}\n", l)?;
                 }
@@ -477,11 +509,14 @@ pub enum Expression<'ast, T> {
     U8Constant(u8),
     U16Constant(u16),
     U32Constant(u32),
+    U64Constant(u64),
+    U128Constant(u128),
     Identifier(Identifier<'ast>),
     Add(Box<ExpressionNode<'ast, T>>, Box<ExpressionNode<'ast, T>>),
     Sub(Box<ExpressionNode<'ast, T>>, Box<ExpressionNode<'ast, T>>),
     Mult(Box<ExpressionNode<'ast, T>>, Box<ExpressionNode<'ast, T>>),
     Div(Box<ExpressionNode<'ast, T>>, Box<ExpressionNode<'ast, T>>),
+    Rem(Box<ExpressionNode<'ast, T>>, Box<ExpressionNode<'ast, T>>),
     Pow(Box<ExpressionNode<'ast, T>>, Box<ExpressionNode<'ast, T>>),
     IfElse(
         Box<ExpressionNode<'ast, T>>,
@@ -520,11 +555,14 @@ impl<'ast, T: fmt::Display> fmt::Display for Expression<'ast, T> {
             Expression::U8Constant(ref i) => write!(f, "{}", i),
             Expression::U16Constant(ref i) => write!(f, "{}", i),
             Expression::U32Constant(ref i) => write!(f, "{}", i),
+            Expression::U64Constant(ref i) => write!(f, "{}", i),
+            Expression::U128Constant(ref i) => write!(f, "{}", i),
             Expression::Identifier(ref var) => write!(f, "{}", var),
             Expression::Add(ref lhs, ref rhs) => write!(f, "({} + {})", lhs, rhs),
             Expression::Sub(ref lhs, ref rhs) => write!(f, "({} - {})", lhs, rhs),
             Expression::Mult(ref lhs, ref rhs) => write!(f, "({} * {})", lhs, rhs),
             Expression::Div(ref lhs, ref rhs) => write!(f, "({} / {})", lhs, rhs),
+            Expression::Rem(ref lhs, ref rhs) => write!(f, "({} % {})", lhs, rhs),
             Expression::Pow(ref lhs, ref rhs) => write!(f, "({}**{})", lhs, rhs),
             Expression::BooleanConstant(b) => write!(f, "{}", b),
             Expression::IfElse(ref condition, ref consequent, ref alternative) => write!(
@@ -587,12 +625,15 @@ impl<'ast, T: fmt::Debug> fmt::Debug for Expression<'ast, T> {
             Expression::U8Constant(ref i) => write!(f, "{:x}", i),
             Expression::U16Constant(ref i) => write!(f, "{:x}", i),
             Expression::U32Constant(ref i) => write!(f, "{:x}", i),
+            Expression::U64Constant(ref i) => write!(f, "{:x}", i),
+            Expression::U128Constant(ref i) => write!(f, "{:x}", i),
             Expression::FieldConstant(ref i) => write!(f, "Num({:?})", i),
             Expression::Identifier(ref var) => write!(f, "Ide({})", var),
             Expression::Add(ref lhs, ref rhs) => write!(f, "Add({:?}, {:?})", lhs, rhs),
             Expression::Sub(ref lhs, ref rhs) => write!(f, "Sub({:?}, {:?})", lhs, rhs),
             Expression::Mult(ref lhs, ref rhs) => write!(f, "Mult({:?}, {:?})", lhs, rhs),
             Expression::Div(ref lhs, ref rhs) => write!(f, "Div({:?}, {:?})", lhs, rhs),
+            Expression::Rem(ref lhs, ref rhs) => write!(f, "Rem({:?}, {:?})", lhs, rhs),
             Expression::Pow(ref lhs, ref rhs) => write!(f, "Pow({:?}, {:?})", lhs, rhs),
             Expression::BooleanConstant(b) => write!(f, "{}", b),
             Expression::IfElse(ref condition, ref consequent, ref alternative) => write!(
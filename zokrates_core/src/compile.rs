@@ -4,20 +4,29 @@
 //! @author Thibaut Schaeffer <thibaut@schaeff.fr>
 //! @date 2018
 use absy::{Module, ModuleId, Program};
+use deprecation::Deprecation;
 use flatten::Flattener;
 use imports::{self, Importer};
 use ir;
+use limits::{self, Budget, Limits};
 use macros;
 use macros::process_macros;
+use max_constraints::{self, MaxConstraints};
+use parser::Position;
+use public_inputs::{self, PublicInputLimits};
 use semantics::{self, Checker};
-use static_analysis::Analyse;
+use smt;
+use static_analysis::{self, Analyse, Pruner, UintError, UintRange, Unused};
 use std::collections::HashMap;
 use std::fmt;
 use std::io;
 use std::path::PathBuf;
+use taint::{self, Leak};
 use typed_absy::abi::Abi;
+use typed_absy::evaluator::{self, Mismatch};
+use typed_absy::TypedProgram;
 use typed_arena::Arena;
-use zir::ZirProgram;
+use zir::ranged::ZirProgram;
 use zokrates_common::Resolver;
 use zokrates_field::Field;
 use zokrates_pest_ast as pest;
@@ -26,6 +35,9 @@ use zokrates_pest_ast as pest;
 pub struct CompilationArtifacts<T: Field> {
     prog: ir::Prog<T>,
     abi: Abi,
+    source_map: ir::SourceMap<T>,
+    warnings: Vec<CompileWarning>,
+    uint_ranges: Vec<UintRange>,
 }
 
 impl<T: Field> CompilationArtifacts<T> {
@@ -36,6 +48,65 @@ impl<T: Field> CompilationArtifacts<T> {
     pub fn abi(&self) -> &Abi {
         &self.abi
     }
+
+    pub fn source_map(&self) -> &ir::SourceMap<T> {
+        &self.source_map
+    }
+
+    pub fn warnings(&self) -> &[CompileWarning] {
+        &self.warnings
+    }
+
+    /// Returns the ranges of the uint variables, if requested with `CompileConfig::uint_ranges`
+    pub fn uint_ranges(&self) -> &[UintRange] {
+        &self.uint_ranges
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompileConfig {
+    /// constrain public `bool` and `u*` inputs to be in range
+    pub validate_inputs: bool,
+    /// resource limits to compile untrusted programs, unlimited by default
+    pub limits: Limits,
+    /// report the ranges inferred for uint variables and the reductions they require
+    pub uint_ranges: bool,
+    /// maximum number of public inputs for each template of verifier, above which compilation warns
+    pub public_input_limits: PublicInputLimits,
+}
+
+impl Default for CompileConfig {
+    fn default() -> Self {
+        CompileConfig {
+            validate_inputs: true,
+            limits: Limits::default(),
+            uint_ranges: false,
+            public_input_limits: PublicInputLimits::default(),
+        }
+    }
+}
+
+impl CompileConfig {
+    pub fn validate_inputs(mut self, validate_inputs: bool) -> Self {
+        self.validate_inputs = validate_inputs;
+        self
+    }
+
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn uint_ranges(mut self, uint_ranges: bool) -> Self {
+        self.uint_ranges = uint_ranges;
+        self
+    }
+
+    pub fn public_input_limits(mut self, public_input_limits: PublicInputLimits) -> Self {
+        self.public_input_limits = public_input_limits;
+        self
+    }
 }
 
 #[derive(Debug)]
@@ -54,6 +125,11 @@ pub enum CompileErrorInner {
     MacroError(macros::Error),
     SemanticError(semantics::ErrorInner),
     ReadError(io::Error),
+    ResourceLimitExceeded(limits::Error),
+    SpecificationError(smt::Error),
+    TaintError(taint::Error),
+    UintError(UintError),
+    MaxConstraintsExceeded(max_constraints::Error),
 }
 
 impl CompileErrorInner {
@@ -95,6 +171,57 @@ impl CompileErrors {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompileWarningInner {
+    Deprecation(Deprecation),
+    Unused(Unused),
+    PublicInputs(public_inputs::Warning),
+}
+
+/// A warning, which does not prevent compilation
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompileWarning {
+    file: PathBuf,
+    pos: Option<(Position, Position)>,
+    value: CompileWarningInner,
+}
+
+impl CompileWarning {
+    pub fn new(
+        value: CompileWarningInner,
+        pos: Option<(Position, Position)>,
+        file: &PathBuf,
+    ) -> CompileWarning {
+        CompileWarning {
+            file: file.clone(),
+            pos,
+            value,
+        }
+    }
+
+    pub fn file(&self) -> &PathBuf {
+        &self.file
+    }
+
+    pub fn value(&self) -> &CompileWarningInner {
+        &self.value
+    }
+}
+
+impl fmt::Display for CompileWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let location = self
+            .pos
+            .map(|p| format!("{}", p.0))
+            .unwrap_or("?".to_string());
+        match self.value {
+            CompileWarningInner::Deprecation(ref d) => write!(f, "{}\n\t{}", location, d),
+            CompileWarningInner::Unused(ref u) => write!(f, "{}\n\t{}", location, u),
+            CompileWarningInner::PublicInputs(ref w) => write!(f, "{}\n\t{}", location, w),
+        }
+    }
+}
+
 impl From<pest::Error> for CompileErrorInner {
     fn from(error: pest::Error) -> Self {
         CompileErrorInner::ParserError(error)
@@ -113,6 +240,39 @@ impl From<io::Error> for CompileErrorInner {
     }
 }
 
+impl From<limits::Error> for CompileErrorInner {
+    fn from(error: limits::Error) -> Self {
+        CompileErrorInner::ResourceLimitExceeded(error)
+    }
+}
+
+impl From<smt::Error> for CompileErrorInner {
+    fn from(error: smt::Error) -> Self {
+        CompileErrorInner::SpecificationError(error)
+    }
+}
+
+impl From<taint::Error> for CompileErrorInner {
+    fn from(error: taint::Error) -> Self {
+        CompileErrorInner::TaintError(error)
+    }
+}
+
+impl From<static_analysis::Error> for CompileErrorInner {
+    fn from(error: static_analysis::Error) -> Self {
+        match error {
+            static_analysis::Error::ResourceLimit(e) => CompileErrorInner::ResourceLimitExceeded(e),
+            static_analysis::Error::Uint(e) => CompileErrorInner::UintError(e),
+        }
+    }
+}
+
+impl From<max_constraints::Error> for CompileErrorInner {
+    fn from(error: max_constraints::Error) -> Self {
+        CompileErrorInner::MaxConstraintsExceeded(error)
+    }
+}
+
 impl From<macros::Error> for CompileErrorInner {
     fn from(error: macros::Error) -> Self {
         CompileErrorInner::MacroError(error)
@@ -136,6 +296,11 @@ impl fmt::Display for CompileErrorInner {
             CompileErrorInner::SemanticError(ref e) => write!(f, "{}", e),
             CompileErrorInner::ReadError(ref e) => write!(f, "{}", e),
             CompileErrorInner::ImportError(ref e) => write!(f, "{}", e),
+            CompileErrorInner::ResourceLimitExceeded(ref e) => write!(f, "{}", e),
+            CompileErrorInner::SpecificationError(ref e) => write!(f, "{}", e),
+            CompileErrorInner::TaintError(ref e) => write!(f, "{}", e),
+            CompileErrorInner::UintError(ref e) => write!(f, "{}", e),
+            CompileErrorInner::MaxConstraintsExceeded(ref e) => write!(f, "{}", e),
         }
     }
 }
@@ -146,73 +311,271 @@ pub fn compile<T: Field, E: Into<imports::Error>>(
     source: String,
     location: FilePath,
     resolver: Option<&dyn Resolver<E>>,
+    config: &CompileConfig,
 ) -> Result<CompilationArtifacts<T>, CompileErrors> {
     let arena = Arena::new();
 
-    let (typed_ast, abi) = check_with_arena(source, location, resolver, &arena)?;
+    let budget = Budget::new(&config.limits);
+
+    let (typed_ast, max_constraints, abi, mut warnings) =
+        check_with_arena(source, location.clone(), resolver, &budget, &arena)?;
+
+    // report the ranges of uint variables before the program is flattened
+    let uint_ranges = match config.uint_ranges {
+        true => static_analysis::uint_ranges(&typed_ast),
+        false => vec![],
+    };
 
     // flatten input program
-    let program_flattened = Flattener::flatten(typed_ast);
+    let (program_flattened, mut source_map) = Flattener::flatten(typed_ast, config);
 
     // analyse (constant propagation after call resolution)
-    let program_flattened = program_flattened.analyse();
+    let program_flattened = program_flattened.propagate_with_source_map(&mut source_map);
 
     // convert to ir
-    let ir_prog = ir::Prog::from(program_flattened);
+    let ir_prog = ir::Prog {
+        private_outputs: abi.flattened_private_outputs(),
+        ..ir::Prog::from(program_flattened)
+    };
 
     // optimize
-    let optimized_ir_prog = ir_prog.optimize();
+    let optimized_ir_prog = ir_prog.optimize_with_source_map(&mut source_map);
 
     // analyse (check for unused constraints)
     let optimized_ir_prog = optimized_ir_prog.analyse();
 
+    // check the number of constraints of the functions annotated with `max_constraints`
+    max_constraints::check(&optimized_ir_prog, &max_constraints).map_err(|errors| {
+        CompileErrors(
+            errors
+                .into_iter()
+                .map(|e| CompileErrorInner::from(e).in_file(&location))
+                .collect(),
+        )
+    })?;
+
+    // warn about the verifiers which cannot practically verify the public inputs on chain
+    warnings.extend(
+        public_inputs::check(
+            optimized_ir_prog.public_inputs_count(),
+            &config.public_input_limits,
+        )
+        .into_iter()
+        .map(|w| CompileWarning::new(CompileWarningInner::PublicInputs(w), None, &location)),
+    );
+
     Ok(CompilationArtifacts {
         prog: optimized_ir_prog,
         abi,
+        source_map,
+        warnings,
+        uint_ranges,
     })
 }
 
+/// Compiles a program and compares the compiled program with a reference evaluation of its typed AST on `rounds`
+/// random inputs, returning the first inputs on which they disagree
+pub fn differential_test<T: Field, E: Into<imports::Error>>(
+    source: String,
+    location: FilePath,
+    resolver: Option<&dyn Resolver<E>>,
+    config: &CompileConfig,
+    rounds: usize,
+) -> Result<Option<Mismatch<T>>, CompileErrors> {
+    let arena = Arena::new();
+
+    let artifacts = compile::<T, E>(source.clone(), location.clone(), resolver, config)?;
+
+    let source = arena.alloc(source);
+    let compiled = compile_program::<T, E>(source, location, resolver, &mut vec![], &arena)?;
+    let typed_ast = Checker::check(compiled).map_err(|errors| {
+        CompileErrors(errors.into_iter().map(|e| CompileError::from(e)).collect())
+    })?;
+
+    Ok(evaluator::differential_test(
+        &typed_ast,
+        artifacts.prog(),
+        rounds,
+    ))
+}
+
+/// The version of the JSON format of the typed AST, to be increased on breaking changes
+pub const TYPED_AST_FORMAT_VERSION: usize = 1;
+
+#[derive(Serialize)]
+#[serde(bound = "T: Field")]
+struct TypedAstExport<'a, 'ast, T> {
+    version: usize,
+    program: &'a TypedProgram<'ast, T>,
+}
+
+/// Checks a program and returns its typed AST, serialized to JSON. The AST is the output of semantic checking: it
+/// is fully typed but not yet inlined, unrolled or optimized, so it still maps closely to the source.
+pub fn typed_ast<T: Field, E: Into<imports::Error>>(
+    source: String,
+    location: FilePath,
+    resolver: Option<&dyn Resolver<E>>,
+) -> Result<String, CompileErrors> {
+    let arena = Arena::new();
+
+    let source = arena.alloc(source);
+    let compiled = compile_program::<T, E>(source, location, resolver, &mut vec![], &arena)?;
+    let typed_ast = Checker::check(compiled).map_err(|errors| {
+        CompileErrors(errors.into_iter().map(|e| CompileError::from(e)).collect())
+    })?;
+
+    Ok(serde_json::to_string_pretty(&TypedAstExport {
+        version: TYPED_AST_FORMAT_VERSION,
+        program: &typed_ast,
+    })
+    .unwrap())
+}
+
+/// Checks a program and returns the leaks of its private inputs annotated with `@secret` to its public inputs and
+/// outputs, except through the functions annotated with `@declassify`
+pub fn taint_analysis<T: Field, E: Into<imports::Error>>(
+    source: String,
+    location: FilePath,
+    resolver: Option<&dyn Resolver<E>>,
+) -> Result<Vec<Leak>, CompileErrors> {
+    let arena = Arena::new();
+
+    let annotations = taint::annotations(&source);
+
+    let source = arena.alloc(source);
+    let compiled =
+        compile_program::<T, E>(source, location.clone(), resolver, &mut vec![], &arena)?;
+    let typed_ast = Checker::check(compiled).map_err(|errors| {
+        CompileErrors(errors.into_iter().map(|e| CompileError::from(e)).collect())
+    })?;
+
+    taint::analyse(&typed_ast, &annotations)
+        .map_err(|e| CompileErrors::from(CompileErrorInner::from(e).in_file(&location)))
+}
+
+/// Returns the verification conditions of the functions of the main module annotated with `@requires` and
+/// `@ensures`, as SMT-LIB scripts along with the names of the functions
+pub fn verification_conditions<T: Field, E: Into<imports::Error>>(
+    source: String,
+    location: FilePath,
+    resolver: Option<&dyn Resolver<E>>,
+    config: &CompileConfig,
+) -> Result<Vec<(String, String)>, CompileErrors> {
+    let specification_error =
+        |e: smt::Error| CompileErrors::from(CompileErrorInner::from(e).in_file(&location));
+
+    smt::specifications(&source)
+        .map_err(specification_error)?
+        .into_iter()
+        .map(|specification| -> Result<_, CompileErrors> {
+            let arena = Arena::new();
+
+            let budget = Budget::new(&config.limits);
+
+            let (harness, _, _, _) = check_with_arena::<T, _>(
+                specification.harness(&source),
+                location.clone(),
+                resolver,
+                &budget,
+                &arena,
+            )?;
+
+            let conditions = smt::verification_conditions(&harness, &specification)
+                .map_err(specification_error)?;
+
+            Ok((specification.function, conditions))
+        })
+        .collect()
+}
+
 pub fn check<'ast, T: Field, E: Into<imports::Error>>(
     source: String,
     location: FilePath,
     resolver: Option<&dyn Resolver<E>>,
-) -> Result<(), CompileErrors> {
+    config: &CompileConfig,
+) -> Result<Vec<CompileWarning>, CompileErrors> {
     let arena = Arena::new();
 
-    check_with_arena::<T, _>(source, location, resolver, &arena).map(|_| ())
+    let budget = Budget::new(&config.limits);
+
+    check_with_arena::<T, _>(source, location, resolver, &budget, &arena)
+        .map(|(_, _, _, warnings)| warnings)
 }
 
 fn check_with_arena<'ast, T: Field, E: Into<imports::Error>>(
     source: String,
     location: FilePath,
     resolver: Option<&dyn Resolver<E>>,
+    budget: &Budget,
     arena: &'ast Arena<String>,
-) -> Result<(ZirProgram<'ast, T>, Abi), CompileErrors> {
+) -> Result<
+    (
+        ZirProgram<'ast, T>,
+        Vec<MaxConstraints>,
+        Abi,
+        Vec<CompileWarning>,
+    ),
+    CompileErrors,
+> {
     let source = arena.alloc(source);
-    let compiled = compile_program(source, location.clone(), resolver, &arena)?;
+    let mut warnings = vec![];
+    let compiled = compile_program(source, location.clone(), resolver, &mut warnings, &arena)?;
+
+    // the typed AST has no positions, so those of the symbols of the main module are kept for the warnings
+    let mut positions = HashMap::new();
+    for symbol in &compiled.modules[&compiled.main].symbols {
+        positions.entry(symbol.value.id).or_insert(symbol.pos());
+    }
 
     // check semantics
     let typed_ast = Checker::check(compiled).map_err(|errors| {
         CompileErrors(errors.into_iter().map(|e| CompileError::from(e)).collect())
     })?;
 
+    // prune the functions which are never called, so that they are not unrolled
+    let (typed_ast, unused) = Pruner::prune(typed_ast);
+    warnings.extend(unused.into_iter().map(|u| {
+        let pos = match u {
+            Unused::Function(ref id) | Unused::Import(ref id) => {
+                positions.get(id.as_str()).cloned()
+            }
+        };
+        CompileWarning::new(CompileWarningInner::Unused(u), pos, &location)
+    }));
+
+    let limit_exceeded =
+        |e: limits::Error| CompileErrors::from(CompileErrorInner::from(e).in_file(&location));
+
+    let typed_ast = budget.check_ast_nodes(typed_ast).map_err(limit_exceeded)?;
+
     let abi = typed_ast.abi();
 
     // analyse (unroll and constant propagation)
-    let typed_ast = typed_ast.analyse();
+    let (typed_ast, max_constraints) = typed_ast
+        .analyse(budget)
+        .map_err(|e| CompileErrors::from(CompileErrorInner::from(e).in_file(&location)))?;
 
-    Ok((typed_ast, abi))
+    Ok((typed_ast, max_constraints, abi, warnings))
 }
 
+/// Parses a program and its imported modules, pushing to `warnings` the warnings found in the modules of the user
 pub fn compile_program<'ast, T: Field, E: Into<imports::Error>>(
     source: &'ast str,
     location: FilePath,
     resolver: Option<&dyn Resolver<E>>,
+    warnings: &mut Vec<CompileWarning>,
     arena: &'ast Arena<String>,
 ) -> Result<Program<'ast, T>, CompileErrors> {
     let mut modules = HashMap::new();
 
-    let main = compile_module(&source, location.clone(), resolver, &mut modules, &arena)?;
+    let main = compile_module(
+        &source,
+        location.clone(),
+        resolver,
+        &mut modules,
+        Some(warnings),
+        &arena,
+    )?;
 
     modules.insert(location.clone(), main);
 
@@ -222,11 +585,14 @@ pub fn compile_program<'ast, T: Field, E: Into<imports::Error>>(
     })
 }
 
+/// Parses a module and its imported modules. `warnings` is `None` if the module is not a module of the user, in which
+/// case no warning is reported for it
 pub fn compile_module<'ast, T: Field, E: Into<imports::Error>>(
     source: &'ast str,
     location: FilePath,
     resolver: Option<&dyn Resolver<E>>,
     modules: &mut HashMap<ModuleId, Module<'ast, T>>,
+    warnings: Option<&mut Vec<CompileWarning>>,
     arena: &'ast Arena<String>,
 ) -> Result<Module<'ast, T>, CompileErrors> {
     let ast = pest::generate_ast(&source)
@@ -242,6 +608,7 @@ pub fn compile_module<'ast, T: Field, E: Into<imports::Error>>(
         location.clone(),
         resolver,
         modules,
+        warnings,
         &arena,
     )
 }
@@ -263,6 +630,7 @@ mod test {
             source,
             "./path/to/file".into(),
             None::<&dyn Resolver<io::Error>>,
+            &CompileConfig::default(),
         );
         assert!(res.unwrap_err().0[0]
             .value()
@@ -281,10 +649,363 @@ mod test {
             source,
             "./path/to/file".into(),
             None::<&dyn Resolver<io::Error>>,
+            &CompileConfig::default(),
         );
         assert!(res.is_ok());
     }
 
+    #[test]
+    fn max_constraints() {
+        let source = |max: usize| {
+            format!(
+                r#"
+			#[max_constraints({})]
+			def cube(field a) -> field:
+			   return a * a * a
+
+			def main(field a) -> field:
+			   return cube(a)
+		"#,
+                max
+            )
+        };
+
+        let res: Result<CompilationArtifacts<Bn128Field>, CompileErrors> = compile(
+            source(100),
+            "./path/to/file".into(),
+            None::<&dyn Resolver<io::Error>>,
+            &CompileConfig::default(),
+        );
+        assert!(res.is_ok());
+
+        // `a * a * a` takes two multiplications
+        let res: Result<CompilationArtifacts<Bn128Field>, CompileErrors> = compile(
+            source(1),
+            "./path/to/file".into(),
+            None::<&dyn Resolver<io::Error>>,
+            &CompileConfig::default(),
+        );
+        let errors = res.unwrap_err().0;
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0]
+            .value()
+            .to_string()
+            .starts_with("Function `cube` compiles to"));
+    }
+
+    #[test]
+    fn verification_conditions() {
+        let source = r#"
+			// @requires a != 0
+			// @ensures result == a * a
+			def square(field a) -> field:
+			   return a * a
+
+			def main(field a) -> field:
+			   return square(a)
+		"#
+        .to_string();
+        let res = super::verification_conditions::<Bn128Field, io::Error>(
+            source,
+            "./path/to/file".into(),
+            None,
+            &CompileConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].0, "square");
+        assert!(res[0].1.contains("(declare-const |a| Int)"));
+        assert!(res[0].1.contains("; @ensures result == a * a"));
+        assert!(res[0].1.ends_with("(check-sat)\n"));
+    }
+
+    #[test]
+    fn typed_ast_json() {
+        use serde_json::{json, Value};
+
+        let source = r#"
+			def main(private field a) -> field:
+			   return a + 1
+		"#
+        .to_string();
+        let res =
+            typed_ast::<Bn128Field, io::Error>(source, "./path/to/file".into(), None).unwrap();
+        let res: Value = serde_json::from_str(&res).unwrap();
+
+        assert_eq!(res["version"], json!(TYPED_AST_FORMAT_VERSION));
+        assert_eq!(res["program"]["main"], json!("./path/to/file"));
+
+        let main = &res["program"]["modules"]["./path/to/file"]["functions"][0];
+        assert_eq!(main["key"]["id"], json!("main"));
+        assert_eq!(
+            main["symbol"]["Here"]["arguments"][0]["private"],
+            json!(true)
+        );
+        assert_eq!(
+            main["symbol"]["Here"]["statements"][0],
+            json!({
+                "Return": [{
+                    "FieldElement": {
+                        "Add": [{ "Identifier": "a" }, { "Number": "1" }]
+                    }
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn private_outputs() {
+        let source = r#"
+			def main() -> (private field[2], field, private bool):
+			   return [1, 2], 3, true
+		"#
+        .to_string();
+        let res: CompilationArtifacts<Bn128Field> = compile(
+            source,
+            "./path/to/file".into(),
+            None::<&dyn Resolver<io::Error>>,
+            &CompileConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(res.abi().private_outputs, vec![0, 2]);
+        assert_eq!(res.prog().private_outputs, vec![0, 1, 3]);
+    }
+
+    #[test]
+    fn source_map() {
+        let source = r#"
+			def main(field a) -> field:
+			   field b = a + 1
+			   b = b * a
+			   field[2] c = [a, b]
+			   field d = a * a
+			   return c[0] + c[1]
+		"#
+        .to_string();
+        let res: CompilationArtifacts<Bn128Field> = compile(
+            source,
+            "./path/to/file".into(),
+            None::<&dyn Resolver<io::Error>>,
+            &CompileConfig::default(),
+        )
+        .unwrap();
+
+        let witness = ir::Interpreter::default()
+            .execute(res.prog(), &vec![Bn128Field::from(3)])
+            .unwrap();
+
+        let evaluate = |name: &str, occurrence: Option<usize>| {
+            res.source_map()
+                .evaluate(name, occurrence, &witness)
+                .map(|(_, value)| value)
+        };
+
+        assert_eq!(evaluate("b", Some(0)), Ok(Bn128Field::from(4)));
+        assert_eq!(evaluate("b", None), Ok(Bn128Field::from(12)));
+        assert_eq!(evaluate("c[0]", None), Ok(Bn128Field::from(3)));
+        assert_eq!(evaluate("c[1]", None), Ok(Bn128Field::from(12)));
+        // `d` is never read, so it is eliminated along with its source map entries
+        assert_eq!(
+            evaluate("d", None),
+            Err(ir::source_map::Error::UnknownVariable("d".into()))
+        );
+    }
+
+    #[test]
+    fn uint_ranges() {
+        let source = r#"
+			def main(u32 a, u32 b) -> u32:
+			   u32 c = a + b
+			   return (c ^ a) + (c + b)
+		"#
+        .to_string();
+        let res: CompilationArtifacts<Bn128Field> = compile(
+            source,
+            "./path/to/file".into(),
+            None::<&dyn Resolver<io::Error>>,
+            &CompileConfig::default().uint_ranges(true),
+        )
+        .unwrap();
+
+        let ranges: Vec<_> = res
+            .uint_ranges()
+            .iter()
+            .map(|r| (r.name.as_str(), r.bits, r.reductions))
+            .collect();
+
+        assert_eq!(
+            ranges,
+            vec![
+                ("a", 32, 1),
+                ("b", 32, 0),
+                ("c", 33, 1),
+                ("#INTERNAL#_RETURN_0", 34, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn oracle() {
+        use num_bigint::BigUint;
+
+        let source = r#"
+			import "EMBED/oracle/1/1" as sqrt
+			def main(field a) -> field:
+			   field r = sqrt(a)
+			   assert(r * r == a)
+			   return r
+		"#
+        .to_string();
+        let res: CompilationArtifacts<Bn128Field> = compile(
+            source,
+            "./path/to/file".into(),
+            None::<&dyn Resolver<io::Error>>,
+            &CompileConfig::default(),
+        )
+        .unwrap();
+
+        let interpreter = ir::Interpreter::default().oracle("sqrt", |inputs| {
+            assert_eq!(inputs, &[BigUint::from(9u32)]);
+            Ok(vec![BigUint::from(3u32)])
+        });
+
+        assert_eq!(
+            interpreter
+                .execute(res.prog(), &vec![Bn128Field::from(9)])
+                .unwrap()
+                .return_values(),
+            vec![Bn128Field::from(3)]
+        );
+
+        assert!(ir::Interpreter::default()
+            .execute(res.prog(), &vec![Bn128Field::from(9)])
+            .is_err());
+    }
+
+    #[test]
+    fn validate_inputs() {
+        let source = r#"
+			def main(bool a, u8 b, private bool c) -> field:
+			   return 1
+		"#;
+
+        let compile_with = |config: CompileConfig| -> CompilationArtifacts<Bn128Field> {
+            compile(
+                source.to_string(),
+                "./path/to/file".into(),
+                None::<&dyn Resolver<io::Error>>,
+                &config,
+            )
+            .unwrap()
+        };
+
+        let validated = compile_with(CompileConfig::default());
+        let not_validated = compile_with(CompileConfig::default().validate_inputs(false));
+
+        // `a` takes one constraint, `b` takes 8 booleanity checks and one sum check
+        assert_eq!(
+            validated.prog().constraint_count(),
+            not_validated.prog().constraint_count() + 10
+        );
+    }
+
+    #[test]
+    fn resource_limits() {
+        let source = r#"
+			def main(field a) -> field:
+			   field b = 0
+			   for field i in 0..1000000000 do
+			      b = b + a
+			   endfor
+			   return b
+		"#;
+
+        let compile_with = |limits: Limits| {
+            compile::<Bn128Field, _>(
+                source.to_string(),
+                "./path/to/file".into(),
+                None::<&dyn Resolver<io::Error>>,
+                &CompileConfig::default().limits(limits),
+            )
+        };
+
+        let limit_exceeded = |limits: Limits| match compile_with(limits).unwrap_err().0[0].value() {
+            CompileErrorInner::ResourceLimitExceeded(e) => e.clone(),
+            e => panic!("expected a resource limit error, found {}", e),
+        };
+
+        assert_eq!(
+            limit_exceeded(Limits::default().max_ast_nodes(5)),
+            limits::Error::AstNodes(5)
+        );
+        assert_eq!(
+            limit_exceeded(Limits::default().max_unrolled_statements(1000)),
+            limits::Error::UnrolledStatements(1000)
+        );
+        assert_eq!(
+            limit_exceeded(Limits::default().timeout_ms(10)),
+            limits::Error::Timeout(10)
+        );
+    }
+
+    #[test]
+    fn small_field() {
+        use zokrates_field::GoldilocksField;
+
+        let compile_goldilocks = |source: &str| {
+            compile::<GoldilocksField, _>(
+                source.to_string(),
+                "./path/to/file".into(),
+                None::<&dyn Resolver<io::Error>>,
+                &CompileConfig::default(),
+            )
+        };
+
+        let error = |source: &str| {
+            compile_goldilocks(source).unwrap_err().0[0]
+                .value()
+                .to_string()
+        };
+
+        // two u16 can be multiplied in a 64 bit field, two u32 are multiplied on limbs of 16 bits
+        assert!(compile_goldilocks(
+            r#"
+			def main(u16 a, u16 b) -> u16:
+			   return a * b
+		"#
+        )
+        .is_ok());
+
+        assert!(compile_goldilocks(
+            r#"
+			def main(u32 a, u32 b) -> u32:
+			   return a * b
+		"#
+        )
+        .is_ok());
+
+        // even the sum of two u64 does not fit
+        assert!(error(
+            r#"
+			def main(u64 a) -> field:
+			   return 1
+		"#
+        )
+        .starts_with("Type u64 is not available"));
+
+        // unpacking to as many bits as the field has would not be unique
+        assert!(error(
+            r#"
+			import "EMBED/unpack64" as unpack
+			def main(field a) -> bool[64]:
+			   return unpack(a)
+		"#
+        )
+        .contains("not available in the goldilocks field"));
+    }
+
     mod abi {
         use super::*;
         use typed_absy::abi::*;
@@ -361,6 +1082,7 @@ struct Bar { field a }
                 main.to_string(),
                 "main".into(),
                 Some(&CustomResolver),
+                &CompileConfig::default(),
             )
             .unwrap();
 
@@ -386,9 +1108,249 @@ struct Bar { field a }
                             }]
                         })
                     }],
-                    outputs: vec![]
+                    outputs: vec![],
+                    private_outputs: vec![],
                 }
             );
         }
     }
+
+    #[test]
+    fn differential() {
+        let source = r#"
+			def add(u32 a, u32 b) -> u32:
+			   return a + b
+
+			def main(u32 a, u32 b, field c, bool[2] d) -> (u32, field, bool):
+			   u32[2] x = [a ^ b, add(a, b) << 3]
+			   x[1] = !x[1] & (x[0] >> 1)
+			   field e = if d[0] then c * c else c - 1 fi
+			   return x[1] - x[0], e, d[1] && !d[0]
+		"#
+        .to_string();
+        let res: Result<Option<Mismatch<Bn128Field>>, CompileErrors> = differential_test(
+            source,
+            "./path/to/file".into(),
+            None::<&dyn Resolver<io::Error>>,
+            &CompileConfig::default(),
+            10,
+        );
+        assert!(res.unwrap().is_none());
+    }
+
+    #[test]
+    fn deprecations() {
+        use deprecation::{Deprecation, Syntax};
+
+        // // main.zok
+        // import "./utils" as utils
+        // from "std" import sum
+        //
+        // // utils.zok
+        // from "std" import sum
+        //
+        // // std.zok
+        // import "./legacy" as legacy
+        // // @deprecated use `add` instead
+        // def sum(..)
+
+        struct CustomResolver;
+
+        impl<E> Resolver<E> for CustomResolver {
+            fn resolve(
+                &self,
+                _: PathBuf,
+                import_location: PathBuf,
+            ) -> Result<(String, PathBuf), E> {
+                let source = match import_location.display().to_string().as_str() {
+                    "./utils" => {
+                        r#"
+from "std" import sum
+def main(field a) -> field:
+    return sum(a, 1)
+"#
+                    }
+                    "std" => {
+                        r#"
+import "./legacy" as legacy
+// @deprecated use `add` instead
+def sum(field a, field b) -> field:
+    return legacy(a, b)
+
+def add(field a, field b) -> field:
+    return a + b
+"#
+                    }
+                    "./legacy" => {
+                        r#"
+def main(field a, field b) -> field:
+    return a + b
+"#
+                    }
+                    _ => unreachable!(),
+                };
+                Ok((source.into(), import_location))
+            }
+        }
+
+        let main = r#"
+import "./utils" as utils
+from "std" import sum
+from "std" import add
+def main(field a) -> field:
+    return utils(a) + sum(a, a) + add(a, a)
+"#;
+
+        let artifacts = compile::<Bn128Field, io::Error>(
+            main.to_string(),
+            "main".into(),
+            Some(&CustomResolver),
+            &CompileConfig::default(),
+        )
+        .unwrap();
+
+        let sum = CompileWarningInner::Deprecation(Deprecation::Function {
+            name: "sum".into(),
+            module: "std".into(),
+            hint: "use `add` instead".into(),
+        });
+
+        // the legacy import in the standard library is not reported
+        assert_eq!(
+            artifacts
+                .warnings()
+                .iter()
+                .map(|w| (w.file().display().to_string(), w.value().clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "main".to_string(),
+                    CompileWarningInner::Deprecation(Deprecation::Syntax(Syntax::MainImport))
+                ),
+                ("./utils".to_string(), sum.clone()),
+                ("main".to_string(), sum),
+            ]
+        );
+    }
+
+    #[test]
+    fn unused() {
+        use static_analysis::Unused;
+
+        let source = r#"
+import "EMBED/unpack" as unpack
+def helper(field a) -> field:
+    return a
+
+def unused(field a) -> field:
+    return helper(a)
+
+def main(field a) -> field:
+    return helper(a)
+"#;
+
+        let artifacts = compile::<Bn128Field, io::Error>(
+            source.to_string(),
+            "main".into(),
+            None,
+            &CompileConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            artifacts
+                .warnings()
+                .iter()
+                .map(|w| w.value().clone())
+                .collect::<Vec<_>>(),
+            vec![
+                CompileWarningInner::Unused(Unused::Function("unused".into())),
+                CompileWarningInner::Unused(Unused::Import("unpack".into())),
+            ]
+        );
+        assert_eq!(
+            artifacts.warnings()[0].to_string(),
+            "6:1\n\tFunction `unused` is never called"
+        );
+    }
+
+    #[test]
+    fn public_inputs() {
+        use public_inputs::{PublicInputLimits, Warning};
+
+        // two public arguments and a public output, the private output and argument are not counted
+        let source = r#"
+def main(field a, field b, private field c) -> (field, private field):
+    return a + b, c
+"#;
+
+        let compile_with = |limits: PublicInputLimits| {
+            compile::<Bn128Field, io::Error>(
+                source.to_string(),
+                "main".into(),
+                None,
+                &CompileConfig::default().public_input_limits(limits),
+            )
+            .unwrap()
+            .warnings()
+            .iter()
+            .map(|w| w.value().clone())
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(compile_with(PublicInputLimits::default()), vec![]);
+        assert_eq!(
+            compile_with(PublicInputLimits::none().set("yul", Some(2)).unwrap()),
+            vec![CompileWarningInner::PublicInputs(Warning {
+                template: "yul",
+                max: 2,
+                count: 3
+            })]
+        );
+    }
+
+    #[test]
+    fn calls() {
+        let source = r#"
+#[inline(never)]
+def square(field a) -> field:
+    return a * a
+
+def main(field a) -> field:
+    return square(square(a))
+"#;
+
+        let artifacts = compile::<Bn128Field, io::Error>(
+            source.to_string(),
+            "main".into(),
+            None,
+            &CompileConfig::default(),
+        )
+        .unwrap();
+
+        // `square` is compiled once and called twice
+        let prog = artifacts.prog();
+        assert_eq!(prog.functions.len(), 1);
+        assert_eq!(
+            prog.main
+                .statements
+                .iter()
+                .filter(|s| match s {
+                    ir::Statement::Call(..) => true,
+                    _ => false,
+                })
+                .count(),
+            2
+        );
+        assert_eq!(
+            prog.constraint_count(),
+            prog.clone().expand().constraint_count()
+        );
+
+        let witness = ir::Interpreter::default()
+            .execute(prog, &vec![Bn128Field::from(3)])
+            .unwrap();
+
+        assert_eq!(witness.return_values(), vec![Bn128Field::from(81)]);
+    }
 }
@@ -0,0 +1,128 @@
+//! Module containing the deprecations of the language and of the standard library
+//!
+//! A function is deprecated by a `// @deprecated <hint>` comment right above its definition, where the hint tells
+//! users what to use instead. Importing it, like using a deprecated syntax form, produces a warning.
+//!
+//! Warnings are only reported in the modules of the user, which are the main module and the modules it imports with
+//! relative paths, transitively: users cannot fix the modules of the standard library, which may keep using
+//! deprecated functions and syntax until they are removed.
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Returns the functions of a module annotated with `@deprecated`, along with their replacement hints
+pub fn annotations(source: &str) -> HashMap<String, String> {
+    let annotation = Regex::new(r"^\s*//\s*@deprecated\b\s*(.*?)\s*$").unwrap();
+    let definition = Regex::new(r"^\s*def\s+(\w+)").unwrap();
+
+    let mut res = HashMap::new();
+    let mut hint = None;
+
+    for line in source.lines() {
+        if let Some(captures) = annotation.captures(line) {
+            hint = Some(captures[1].to_string());
+            continue;
+        }
+
+        // other comments may be interleaved with the annotation
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+
+        if let Some(hint) = hint.take() {
+            if let Some(captures) = definition.captures(line) {
+                res.insert(captures[1].to_string(), hint);
+            }
+        }
+    }
+
+    res
+}
+
+/// The syntax forms which are deprecated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Syntax {
+    /// `import "./foo" as foo`, which imports the `main` function of a module
+    MainImport,
+}
+
+impl Syntax {
+    /// Returns how to replace this syntax
+    pub fn hint(&self) -> &'static str {
+        match *self {
+            Syntax::MainImport => {
+                "import it explicitly with `from \"<path>\" import main as <alias>`"
+            }
+        }
+    }
+}
+
+impl fmt::Display for Syntax {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Syntax::MainImport => write!(f, "Importing a module without `from` is deprecated"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Deprecation {
+    /// A function annotated with `@deprecated` is imported
+    Function {
+        name: String,
+        module: String,
+        hint: String,
+    },
+    Syntax(Syntax),
+}
+
+impl fmt::Display for Deprecation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Deprecation::Function {
+                ref name,
+                ref module,
+                ref hint,
+            } => match hint.as_str() {
+                "" => write!(f, "Function `{}` from {} is deprecated", name, module),
+                hint => write!(
+                    f,
+                    "Function `{}` from {} is deprecated: {}",
+                    name, module, hint
+                ),
+            },
+            Deprecation::Syntax(ref syntax) => write!(f, "{}: {}", syntax, syntax.hint()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn annotations() {
+        let source = r#"
+// @deprecated use `sha256` instead
+// a comment
+def sha256Legacy(field a) -> field:
+    return a
+
+// @deprecated
+def old() -> field:
+    return 1
+
+// @deprecated not attached
+field a = 1
+
+def main() -> field:
+    return 1
+"#;
+
+        let annotations = super::annotations(source);
+
+        assert_eq!(annotations.len(), 2);
+        assert_eq!(annotations["sha256Legacy"], "use `sha256` instead");
+        assert_eq!(annotations["old"], "");
+    }
+}
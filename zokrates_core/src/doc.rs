@@ -0,0 +1,180 @@
+//! Module extracting the documentation of a module from its doc comments
+//!
+//! Doc comments are lines starting with `///`, directly above the definition of a function or a struct. The
+//! signatures of the definitions are taken from the source, so that they read as written.
+
+use std::fmt;
+use zokrates_pest_ast as pest;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ItemKind {
+    Function,
+    Struct,
+}
+
+impl fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ItemKind::Function => write!(f, "function"),
+            ItemKind::Struct => write!(f, "struct"),
+        }
+    }
+}
+
+/// A documented definition of a module
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub kind: ItemKind,
+    pub name: String,
+    pub signature: String,
+    /// The text of the doc comments, without the leading `///`
+    pub doc: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Documentation {
+    /// The paths imported by the module, as written in its import directives
+    pub imports: Vec<String>,
+    /// The structs then the functions of the module, in the order of their definitions
+    pub items: Vec<Item>,
+}
+
+/// Returns the documentation of a module, or an error if it does not parse
+pub fn documentation(source: &str) -> Result<Documentation, pest::Error> {
+    let file = pest::generate_ast(source)?;
+
+    let lines: Vec<&str> = source.lines().collect();
+
+    let imports = file
+        .imports
+        .iter()
+        .map(|import| match import {
+            pest::ImportDirective::Main(import) => import.source.value.clone(),
+            pest::ImportDirective::From(import) => import.source.value.clone(),
+        })
+        .collect();
+
+    let structs = file.structs.iter().map(|s| Item {
+        kind: ItemKind::Struct,
+        name: s.id.value.clone(),
+        signature: match s.fields.len() {
+            0 => format!("struct {} {{}}", s.id.value),
+            _ => format!(
+                "struct {} {{\n{}\n}}",
+                s.id.value,
+                s.fields
+                    .iter()
+                    .map(|field| format!("    {}", field.span.as_str()))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ),
+        },
+        doc: doc_comment(&lines, s.span.start_pos().line_col().0),
+    });
+
+    let functions = file.functions.iter().map(|f| {
+        let returns: Vec<_> = f.returns.iter().map(|r| r.span.as_str()).collect();
+
+        Item {
+            kind: ItemKind::Function,
+            name: f.id.value.clone(),
+            signature: format!(
+                "def {}({}){}",
+                f.id.value,
+                f.parameters
+                    .iter()
+                    .map(|p| p.span.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                match returns.len() {
+                    0 => String::new(),
+                    1 => format!(" -> {}", returns[0]),
+                    _ => format!(" -> ({})", returns.join(", ")),
+                }
+            ),
+            doc: doc_comment(&lines, f.span.start_pos().line_col().0),
+        }
+    });
+
+    Ok(Documentation {
+        imports,
+        items: structs.chain(functions).collect(),
+    })
+}
+
+/// Returns the doc comment directly above the line `line`, counted from 1
+fn doc_comment(lines: &[&str], line: usize) -> String {
+    let comment: Vec<_> = lines[..line - 1]
+        .iter()
+        .rev()
+        .map(|line| line.trim())
+        .take_while(|line| line.starts_with("///"))
+        .map(|line| {
+            let line = &line[3..];
+            if line.starts_with(' ') {
+                &line[1..]
+            } else {
+                line
+            }
+        })
+        .collect();
+
+    comment.into_iter().rev().collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn documentation() {
+        let source = r#"import "hashes/sha256/512bit" as sha256
+from "./foo" import Foo
+
+/// A point of the curve
+struct Point {
+    field x
+    field y
+}
+
+// not a doc comment
+def helper() -> field:
+    return 1
+
+///   Adds two points
+///
+/// Assumes both points are on the curve
+def main(Point a, private Point b) -> (field, field):
+    return a.x + b.x, a.y + b.y
+"#;
+
+        let documentation = super::documentation(source).unwrap();
+
+        assert_eq!(
+            documentation,
+            Documentation {
+                imports: vec!["hashes/sha256/512bit".into(), "./foo".into()],
+                items: vec![
+                    Item {
+                        kind: ItemKind::Struct,
+                        name: "Point".into(),
+                        signature: "struct Point {\n    field x\n    field y\n}".into(),
+                        doc: "A point of the curve".into(),
+                    },
+                    Item {
+                        kind: ItemKind::Function,
+                        name: "helper".into(),
+                        signature: "def helper() -> field".into(),
+                        doc: "".into(),
+                    },
+                    Item {
+                        kind: ItemKind::Function,
+                        name: "main".into(),
+                        signature: "def main(Point a, private Point b) -> (field, field)".into(),
+                        doc: "  Adds two points\n\nAssumes both points are on the curve".into(),
+                    },
+                ]
+            }
+        );
+    }
+}
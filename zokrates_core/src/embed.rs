@@ -8,16 +8,72 @@ use typed_absy::types::{FunctionKey, Signature, Type};
 use zokrates_field::Field;
 
 /// A low level function that contains non-deterministic introduction of variables. It is carried out as is until
-/// the flattening step when it can be inlined.
-#[derive(Debug, Clone, PartialEq, Hash)]
+/// the flattening step when it can be inlined. Like other functions, it can return several values, which are bound
+/// with a multiple definition.
+#[derive(Debug, Clone, PartialEq, Hash, Serialize)]
 pub enum FlatEmbed {
     Unpack(usize),
     U8ToBits,
     U16ToBits,
     U32ToBits,
+    U64ToBits,
+    U128ToBits,
     U8FromBits,
     U16FromBits,
     U32FromBits,
+    U64FromBits,
+    U128FromBits,
+    U32ToBytesBe,
+    U32ToBytesLe,
+    U32FromBytesBe,
+    U32FromBytesLe,
+    /// The euclidean division of two unsigned integers of the given bitwidth, returning the quotient and the remainder
+    DivMod(usize),
+    /// The euclidean division of two field elements lower than `2**bound`, returning the quotient and the remainder
+    FieldDivMod(usize),
+    /// A value provided by the interpreter during witness computation, named after the alias of its import
+    Oracle {
+        name: String,
+        inputs: usize,
+        outputs: usize,
+    },
+}
+
+/// The bitwidths to which a field element can be unpacked with `EMBED/unpack<bitwidth>`, in addition to
+/// `EMBED/unpack` which unpacks to the full bitwidth of the field. Unpacking fails if the element does not fit.
+pub const UNPACK_BITWIDTHS: [usize; 5] = [8, 16, 32, 64, 128];
+
+/// The bitwidths of the unsigned integers which can be divided with `EMBED/u<bitwidth>_divmod`. The product of two
+/// `u128` does not fit in the fields, which the verification of the division requires.
+pub const DIVMOD_BITWIDTHS: [usize; 4] = [8, 16, 32, 64];
+
+/// The largest bound of `EMBED/field_divmod/<bound>` which has an id, enough for fields of up to 512 bits
+const MAX_FIELD_DIVMOD_BOUND: usize = 255;
+
+/// Builds the table of the ids `_FIELD_DIVMOD_<bound>`, with the bound written as two hexadecimal digits, so that
+/// the ids are static strings
+macro_rules! field_divmod_ids {
+    (@low $high:literal [$($low:literal)*]) => {
+        [$(concat!("_FIELD_DIVMOD_", $high, $low)),*]
+    };
+    ($($digit:literal)*) => {
+        field_divmod_ids!(@high [$($digit)*] $($digit)*)
+    };
+    (@high $digits:tt $($high:literal)*) => {
+        [$(field_divmod_ids!(@low $high $digits)),*]
+    };
+}
+
+/// The ids of the field divisions, indexed by the high and low hexadecimal digits of their bound, as divisions with
+/// different bounds have the same signature
+static FIELD_DIVMOD_IDS: [[&str; 16]; 16] = field_divmod_ids!(
+    "0" "1" "2" "3" "4" "5" "6" "7" "8" "9" "a" "b" "c" "d" "e" "f"
+);
+
+/// Returns whether `EMBED/field_divmod/<bound>` is available in the field `T`: the product of the quotient and the
+/// divisor must not overflow, see `field_divmod`, and the bound must have an id in `FIELD_DIVMOD_IDS`
+pub fn is_field_divmod_bound<T: Field>(bound: usize) -> bool {
+    bound > 0 && bound <= MAX_FIELD_DIVMOD_BOUND && 2 * bound < T::get_required_bits()
 }
 
 impl FlatEmbed {
@@ -35,6 +91,12 @@ impl FlatEmbed {
             FlatEmbed::U32ToBits => Signature::new()
                 .inputs(vec![Type::uint(32)])
                 .outputs(vec![Type::array(Type::Boolean, 32)]),
+            FlatEmbed::U64ToBits => Signature::new()
+                .inputs(vec![Type::uint(64)])
+                .outputs(vec![Type::array(Type::Boolean, 64)]),
+            FlatEmbed::U128ToBits => Signature::new()
+                .inputs(vec![Type::uint(128)])
+                .outputs(vec![Type::array(Type::Boolean, 128)]),
             FlatEmbed::U8FromBits => Signature::new()
                 .outputs(vec![Type::uint(8)])
                 .inputs(vec![Type::array(Type::Boolean, 8)]),
@@ -44,6 +106,29 @@ impl FlatEmbed {
             FlatEmbed::U32FromBits => Signature::new()
                 .outputs(vec![Type::uint(32)])
                 .inputs(vec![Type::array(Type::Boolean, 32)]),
+            FlatEmbed::U64FromBits => Signature::new()
+                .outputs(vec![Type::uint(64)])
+                .inputs(vec![Type::array(Type::Boolean, 64)]),
+            FlatEmbed::U128FromBits => Signature::new()
+                .outputs(vec![Type::uint(128)])
+                .inputs(vec![Type::array(Type::Boolean, 128)]),
+            FlatEmbed::U32ToBytesBe | FlatEmbed::U32ToBytesLe => Signature::new()
+                .inputs(vec![Type::uint(32)])
+                .outputs(vec![Type::array(Type::uint(8), 4)]),
+            FlatEmbed::U32FromBytesBe | FlatEmbed::U32FromBytesLe => Signature::new()
+                .outputs(vec![Type::uint(32)])
+                .inputs(vec![Type::array(Type::uint(8), 4)]),
+            FlatEmbed::DivMod(bitwidth) => Signature::new()
+                .inputs(vec![Type::uint(*bitwidth); 2])
+                .outputs(vec![Type::uint(*bitwidth); 2]),
+            FlatEmbed::FieldDivMod(_) => Signature::new()
+                .inputs(vec![Type::FieldElement; 2])
+                .outputs(vec![Type::FieldElement; 2]),
+            FlatEmbed::Oracle {
+                inputs, outputs, ..
+            } => Signature::new()
+                .inputs(vec![Type::FieldElement; *inputs])
+                .outputs(vec![Type::FieldElement; *outputs]),
         }
     }
 
@@ -57,9 +142,20 @@ impl FlatEmbed {
             FlatEmbed::U8ToBits => "_U8_TO_BITS",
             FlatEmbed::U16ToBits => "_U16_TO_BITS",
             FlatEmbed::U32ToBits => "_U32_TO_BITS",
+            FlatEmbed::U64ToBits => "_U64_TO_BITS",
+            FlatEmbed::U128ToBits => "_U128_TO_BITS",
             FlatEmbed::U8FromBits => "_U8_FROM_BITS",
             FlatEmbed::U16FromBits => "_U16_FROM_BITS",
             FlatEmbed::U32FromBits => "_U32_FROM_BITS",
+            FlatEmbed::U64FromBits => "_U64_FROM_BITS",
+            FlatEmbed::U128FromBits => "_U128_FROM_BITS",
+            FlatEmbed::U32ToBytesBe => "_U32_TO_BYTES_BE",
+            FlatEmbed::U32ToBytesLe => "_U32_TO_BYTES_LE",
+            FlatEmbed::U32FromBytesBe => "_U32_FROM_BYTES_BE",
+            FlatEmbed::U32FromBytesLe => "_U32_FROM_BYTES_LE",
+            FlatEmbed::DivMod(_) => "_DIVMOD",
+            FlatEmbed::FieldDivMod(bound) => FIELD_DIVMOD_IDS[bound >> 4][bound & 0xf],
+            FlatEmbed::Oracle { .. } => "_ORACLE",
         }
     }
 
@@ -67,6 +163,12 @@ impl FlatEmbed {
     pub fn synthetize<T: Field>(&self) -> FlatFunction<T> {
         match self {
             FlatEmbed::Unpack(bitwidth) => unpack_to_bitwidth(*bitwidth),
+            FlatEmbed::FieldDivMod(bound) => field_divmod(*bound),
+            FlatEmbed::Oracle {
+                name,
+                inputs,
+                outputs,
+            } => oracle(name, *inputs, *outputs),
             _ => unreachable!(),
         }
     }
@@ -177,6 +279,140 @@ pub fn unpack_to_bitwidth<T: Field>(bit_width: usize) -> FlatFunction<T> {
     }
 }
 
+/// A `FlatFunction` which returns the quotient and the remainder of the euclidean division of two field elements
+///
+/// # Inputs
+/// * bound the number of bits of the dividend and the divisor
+///
+/// # Remarks
+/// * the dividend, the divisor, the quotient, the remainder and `divisor - remainder - 1` are decomposed on `bound`
+///   bits, so that `quotient * divisor + remainder` cannot overflow and the remainder is lower than the divisor
+/// * the `FlatFunction` is not satisfiable if the divisor is zero or if an operand does not fit in `bound` bits
+pub fn field_divmod<T: Field>(bound: usize) -> FlatFunction<T> {
+    assert!(is_field_divmod_bound::<T>(bound));
+
+    let arguments = vec![
+        FlatParameter::private(FlatVariable::new(0)),
+        FlatParameter::private(FlatVariable::new(1)),
+    ];
+
+    let dividend = FlatExpression::Identifier(FlatVariable::new(0));
+    let divisor = FlatExpression::Identifier(FlatVariable::new(1));
+    let quotient = FlatVariable::new(2);
+    let remainder = FlatVariable::new(3);
+
+    let mut statements = vec![FlatStatement::Directive(FlatDirective::new(
+        vec![quotient, remainder],
+        Solver::EuclideanDiv,
+        vec![dividend.clone(), divisor.clone()],
+    ))];
+
+    let quotient = FlatExpression::Identifier(quotient);
+    let remainder = FlatExpression::Identifier(remainder);
+
+    let checked = vec![
+        dividend.clone(),
+        divisor.clone(),
+        quotient.clone(),
+        remainder.clone(),
+        FlatExpression::Sub(
+            box divisor.clone(),
+            box FlatExpression::Add(
+                box remainder.clone(),
+                box FlatExpression::Number(T::from(1)),
+            ),
+        ),
+    ];
+
+    for (index, e) in checked.into_iter().enumerate() {
+        let bits: Vec<_> = (0..bound)
+            .map(|i| FlatVariable::new(4 + index * bound + i))
+            .collect();
+
+        statements.push(FlatStatement::Directive(FlatDirective::new(
+            bits.clone(),
+            Solver::bits(bound),
+            vec![e.clone()],
+        )));
+
+        // bit checks
+        statements.extend(bits.iter().map(|bit| {
+            let bit = FlatExpression::Identifier(*bit);
+            FlatStatement::Condition(bit.clone(), FlatExpression::Mult(box bit.clone(), box bit))
+        }));
+
+        // sum check, the bits being ordered from the most significant one
+        let sum = bits.iter().rev().enumerate().fold(
+            FlatExpression::Number(T::from(0)),
+            |acc, (i, bit)| {
+                FlatExpression::Add(
+                    box acc,
+                    box FlatExpression::Mult(
+                        box FlatExpression::Identifier(*bit),
+                        box FlatExpression::Number(T::from(2).pow(i)),
+                    ),
+                )
+            },
+        );
+
+        statements.push(FlatStatement::Condition(e, sum));
+    }
+
+    // dividend - remainder == quotient * divisor
+    statements.push(FlatStatement::Condition(
+        FlatExpression::Sub(box dividend, box remainder.clone()),
+        FlatExpression::Mult(box quotient.clone(), box divisor),
+    ));
+
+    statements.push(FlatStatement::Return(FlatExpressionList {
+        expressions: vec![quotient, remainder],
+    }));
+
+    FlatFunction {
+        arguments,
+        statements,
+    }
+}
+
+/// A `FlatFunction` which returns the values provided by an oracle
+///
+/// # Inputs
+/// * name the name of the oracle
+/// * inputs the number of field elements passed to the oracle
+/// * outputs the number of field elements returned by the oracle
+///
+/// # Remarks
+/// * the return values of the `FlatFunction` are not constrained: they must be checked by the caller
+pub fn oracle<T: Field>(name: &str, inputs: usize, outputs: usize) -> FlatFunction<T> {
+    let arguments: Vec<_> = (0..inputs)
+        .map(|index| FlatParameter::private(FlatVariable::new(index)))
+        .collect();
+
+    let directive_outputs: Vec<_> = (inputs..inputs + outputs).map(FlatVariable::new).collect();
+
+    let statements = vec![
+        FlatStatement::Directive(FlatDirective {
+            inputs: arguments
+                .iter()
+                .map(|a| FlatExpression::Identifier(a.id))
+                .collect(),
+            outputs: directive_outputs.clone(),
+            solver: Solver::Oracle(name.to_string(), inputs, outputs),
+        }),
+        FlatStatement::Return(FlatExpressionList {
+            expressions: directive_outputs
+                .into_iter()
+                .map(FlatExpression::Identifier)
+                .collect(),
+        }),
+    ];
+
+    FlatFunction {
+        arguments,
+        statements,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -219,4 +455,39 @@ mod tests {
             );
         }
     }
+
+    #[cfg(test)]
+    mod field_divmod {
+        use super::*;
+
+        #[test]
+        fn field_divmod8() {
+            let divmod: FlatFunction<Bn128Field> = field_divmod(8);
+
+            assert_eq!(divmod.arguments.len(), 2);
+            assert_eq!(divmod.statements.len(), 1 + 5 * (1 + 8 + 1) + 1 + 1); // 1 directive, 5 decompositions, 1 product check, 1 return
+            assert_eq!(
+                divmod.statements[0],
+                FlatStatement::Directive(FlatDirective::new(
+                    vec![FlatVariable::new(2), FlatVariable::new(3)],
+                    Solver::EuclideanDiv,
+                    vec![FlatVariable::new(0), FlatVariable::new(1)]
+                ))
+            );
+        }
+
+        #[test]
+        fn bounds() {
+            assert!(!is_field_divmod_bound::<Bn128Field>(0));
+            assert!(is_field_divmod_bound::<Bn128Field>(126));
+            assert!(!is_field_divmod_bound::<Bn128Field>(127));
+        }
+
+        #[test]
+        fn ids() {
+            assert_eq!(FlatEmbed::FieldDivMod(0).id(), "_FIELD_DIVMOD_00");
+            assert_eq!(FlatEmbed::FieldDivMod(126).id(), "_FIELD_DIVMOD_7e");
+            assert_eq!(FlatEmbed::FieldDivMod(255).id(), "_FIELD_DIVMOD_ff");
+        }
+    }
 }
@@ -0,0 +1,165 @@
+//! Module compiling a function of a module other than `main` as a standalone circuit
+//!
+//! A `main` with the signature of the function is generated, which calls the function and returns its return values.
+//! The parameters and return types of the generated `main` are taken from the source, so that the inputs and outputs
+//! of the circuit read as written, including their visibility. The `main` of the module, if any, is renamed.
+
+use std::fmt;
+use zokrates_pest_ast as pest;
+
+/// Name given to the `main` of the module, as the generated `main` replaces it
+const RENAMED_MAIN: &str = "main_replaced";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// The module defines no function with the given name
+    NotFound(String),
+    /// The module defines several functions with the given name, which cannot be told apart
+    Overloaded(String),
+    /// The function has no return value, so it cannot be called from `main`
+    NoOutputs(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::NotFound(ref function) => {
+                write!(f, "Function `{}` is not defined in this module", function)
+            }
+            Error::Overloaded(ref function) => write!(
+                f,
+                "Function `{}` is defined several times in this module, it cannot be compiled on its own",
+                function
+            ),
+            Error::NoOutputs(ref function) => write!(
+                f,
+                "Function `{}` must return at least one value to be compiled on its own",
+                function
+            ),
+        }
+    }
+}
+
+/// Returns `source` with a `main` calling `function` in place of its own `main`. The source is returned unchanged if
+/// `function` is `main`, or if it does not parse, in which case the errors are reported when compiling it.
+pub fn with_entry_point(source: &str, function: &str) -> Result<String, Error> {
+    if function == "main" {
+        return Ok(source.to_string());
+    }
+
+    let file = match pest::generate_ast(source) {
+        Ok(file) => file,
+        Err(_) => return Ok(source.to_string()),
+    };
+
+    let definition = {
+        let mut definitions = file.functions.iter().filter(|f| f.id.value == function);
+
+        match (definitions.next(), definitions.next()) {
+            (None, _) => return Err(Error::NotFound(function.to_string())),
+            (Some(_), Some(_)) => return Err(Error::Overloaded(function.to_string())),
+            (Some(definition), None) => definition,
+        }
+    };
+
+    if definition.returns.is_empty() {
+        return Err(Error::NoOutputs(function.to_string()));
+    }
+
+    // rename the `main` functions of the module, starting from the end so that the positions stay valid
+    let mut renamed = source.to_string();
+    for main in file.functions.iter().rev().filter(|f| f.id.value == "main") {
+        renamed.replace_range(main.id.span.start()..main.id.span.end(), RENAMED_MAIN);
+    }
+
+    // the return values are named so that they do not shadow a parameter
+    let mut prefix = String::from("result");
+    while definition
+        .parameters
+        .iter()
+        .any(|p| p.id.value.starts_with(&prefix))
+    {
+        prefix.push('_');
+    }
+
+    let results: Vec<_> = (0..definition.returns.len())
+        .map(|i| format!("{}{}", prefix, i))
+        .collect();
+
+    Ok(format!(
+        "{}\n\ndef main({}) -> ({}):\n\t{} = {}({})\n\treturn {}\n",
+        renamed.trim_end(),
+        definition
+            .parameters
+            .iter()
+            .map(|p| p.span.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+        definition
+            .returns
+            .iter()
+            .map(|r| r.span.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+        definition
+            .returns
+            .iter()
+            .zip(results.iter())
+            .map(|(r, result)| format!("{} {}", r.ty.span().as_str(), result))
+            .collect::<Vec<_>>()
+            .join(", "),
+        function,
+        definition
+            .parameters
+            .iter()
+            .map(|p| p.id.value.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+        results.join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"def round(field a, private u32 b) -> (field, u32):
+    return a * a, b + 1
+
+def main(field a) -> (field):
+    field c, u32 d = round(a, 1)
+    return c
+"#;
+
+    #[test]
+    fn entry_point() {
+        let source = with_entry_point(SOURCE, "round").unwrap();
+
+        assert!(source.contains("def main_replaced(field a) -> (field):\n"));
+        assert!(source.ends_with(
+            "def main(field a, private u32 b) -> (field, u32):\n\
+             \tfield result0, u32 result1 = round(a, b)\n\
+             \treturn result0, result1\n"
+        ));
+    }
+
+    #[test]
+    fn unchanged_main() {
+        assert_eq!(with_entry_point(SOURCE, "main").unwrap(), SOURCE);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            with_entry_point(SOURCE, "foo"),
+            Err(Error::NotFound(String::from("foo")))
+        );
+
+        let source =
+            "def foo(field a) -> (field):\n\treturn a\n\ndef foo(u32 a) -> (u32):\n\treturn a\n";
+        assert_eq!(
+            with_entry_point(source, "foo"),
+            Err(Error::Overloaded(String::from("foo")))
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Catalogue of the errors reported by the compiler
+//!
+//! Each error has a stable code, printed with it and explained by `zokrates explain <code>`. Codes are never reused:
+//! an error which disappears keeps its entry, so that codes found in older logs can still be explained.
+
+use compile::CompileErrorInner;
+use limits;
+use smt;
+use taint;
+
+#[derive(Debug, PartialEq)]
+pub struct ErrorCode {
+    pub code: &'static str,
+    pub title: &'static str,
+    /// An extended description, with an example and how to fix it
+    pub explanation: &'static str,
+}
+
+pub const ERROR_CODES: &[ErrorCode] = &[
+    ErrorCode {
+        code: "E0001",
+        title: "Syntax error",
+        explanation: r#"The source does not follow the grammar of ZoKrates. The error points to the first token which
+could not be parsed, and lists the tokens which were expected instead.
+
+    def main(field a) -> field
+        return a
+
+The definition of `main` is missing a colon. Add it:
+
+    def main(field a) -> field:
+        return a"#,
+    },
+    ErrorCode {
+        code: "E0002",
+        title: "Cannot read a file",
+        explanation: r#"A file could not be read, for example because it was removed or is not readable by the current
+user. Check the path and permissions of the file named in the error."#,
+    },
+    ErrorCode {
+        code: "E0003",
+        title: "Import error",
+        explanation: r#"An import cannot be resolved: the module does not exist, it does not define the imported symbol,
+or the embed is not available in the field of the program.
+
+    import "./utils" as u
+
+If `utils.zok` is not in the directory of the importing module, fix the path. Imports from the standard library are
+written without `./`, for example `import "hashes/sha256/512bit" as sha256`."#,
+    },
+    ErrorCode {
+        code: "E0004",
+        title: "Incompatible curve",
+        explanation: r#"The module declares a curve with `#pragma curve` which is not the curve it is compiled for.
+
+    #pragma curve bls12_381
+
+Compile the program with `--curve bls12_381`, or remove the pragma if the module does not depend on the curve."#,
+    },
+    ErrorCode {
+        code: "E0005",
+        title: "Semantic error",
+        explanation: r#"The program is syntactically valid but not well-typed: for example, a variable is used before its
+declaration, an expression has the wrong type, or a function is called with the wrong number of arguments. The
+message describes the mismatch.
+
+    def main(field a) -> bool:
+        return a
+
+`main` returns a `field` where a `bool` is expected. Fix the return type, or the returned expression:
+
+    def main(field a) -> bool:
+        return a == 1"#,
+    },
+    ErrorCode {
+        code: "E0006",
+        title: "Too many AST nodes",
+        explanation: r#"The program, including its imported modules, has more statements and expressions than the limit
+set by the application compiling it. Split the program, or raise `max_ast_nodes` in the `Limits` of the compilation."#,
+    },
+    ErrorCode {
+        code: "E0007",
+        title: "Too many unrolled statements",
+        explanation: r#"Unrolling the loops of the program produces more statements than the limit set by the application
+compiling it. Nested loops multiply their bounds, so reduce them, or raise `max_unrolled_statements` in the `Limits` of
+the compilation."#,
+    },
+    ErrorCode {
+        code: "E0008",
+        title: "Compilation timeout",
+        explanation: r#"The compiler ran for longer than the timeout set by the application compiling it. Reduce the size
+of the program or the bounds of its loops, or raise `timeout_ms` in the `Limits` of the compilation."#,
+    },
+    ErrorCode {
+        code: "E0009",
+        title: "Unattached specification",
+        explanation: r#"`@requires` and `@ensures` annotations must be directly followed by a function definition on a
+single line.
+
+    // @ensures r == a * a
+    // a comment
+    def square(field a) -> field:
+
+Move the annotations right above the definition."#,
+    },
+    ErrorCode {
+        code: "E0010",
+        title: "Specified function without outputs",
+        explanation: r#"A function annotated with `@requires` or `@ensures` must return at least one value, which the
+verification conditions refer to. Remove the annotations, or return a value."#,
+    },
+    ErrorCode {
+        code: "E0011",
+        title: "Unsupported specification",
+        explanation: r#"An expression of an annotated function, or of its annotations, cannot be encoded in SMT-LIB, such
+as a bitwise operation on unsigned integers. Rewrite the expression with supported operations, or remove the
+annotations of the function."#,
+    },
+    ErrorCode {
+        code: "E0012",
+        title: "Unknown secret",
+        explanation: r#"A `@secret` annotation names a variable which is not an input of `main`.
+
+    // @secret key
+    def main(private field k) -> field:
+
+Name the input in the annotation: `// @secret k`."#,
+    },
+    ErrorCode {
+        code: "E0013",
+        title: "Public secret",
+        explanation: r#"A `@secret` annotation names a public input of `main`, which the verifier sees anyway. Make the
+input private:
+
+    // @secret k
+    def main(private field k) -> field:"#,
+    },
+    ErrorCode {
+        code: "E0014",
+        title: "Unsigned integer too large for the field",
+        explanation: r#"The sum of two unsigned integers, or their product even computed on limbs of half their bitwidth,
+does not fit in an element of the field the program is compiled for, so it cannot be reduced correctly. For example,
+`u64` is not available in the 64-bit Goldilocks field. Compile for a larger field, or use smaller integer types."#,
+    },
+    ErrorCode {
+        code: "E0015",
+        title: "Too many constraints",
+        explanation: r#"A function annotated with `#[max_constraints(n)]` compiles to more than `n` constraints, counting
+the constraints of the functions it calls.
+
+    #[max_constraints(10)]
+    def square_bits(u32 a) -> u32:
+        return a * a
+
+Multiplying unsigned integers decomposes the product into bits, which takes more than 10 constraints. If the increase
+is expected, raise the maximum in the attribute; otherwise, find the change which made the function more expensive."#,
+    },
+];
+
+/// Returns the entry of the catalogue for `code`, ignoring its case
+pub fn explain(code: &str) -> Option<&'static ErrorCode> {
+    ERROR_CODES
+        .iter()
+        .find(|e| e.code.eq_ignore_ascii_case(code))
+}
+
+impl CompileErrorInner {
+    /// Returns the code of this error in the catalogue
+    pub fn code(&self) -> &'static str {
+        match self {
+            CompileErrorInner::ParserError(_) => "E0001",
+            CompileErrorInner::ReadError(_) => "E0002",
+            CompileErrorInner::ImportError(_) => "E0003",
+            CompileErrorInner::MacroError(_) => "E0004",
+            CompileErrorInner::SemanticError(_) => "E0005",
+            CompileErrorInner::ResourceLimitExceeded(e) => match e {
+                limits::Error::AstNodes(_) => "E0006",
+                limits::Error::UnrolledStatements(_) => "E0007",
+                limits::Error::Timeout(_) => "E0008",
+            },
+            CompileErrorInner::SpecificationError(e) => match e {
+                smt::Error::Unattached(_) => "E0009",
+                smt::Error::NoOutputs(_) => "E0010",
+                smt::Error::Unsupported(_) => "E0011",
+            },
+            CompileErrorInner::TaintError(e) => match e {
+                taint::Error::UnknownInput(_) => "E0012",
+                taint::Error::PublicInput(_) => "E0013",
+            },
+            CompileErrorInner::UintError(_) => "E0014",
+            CompileErrorInner::MaxConstraintsExceeded(_) => "E0015",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use compile::{compile, CompilationArtifacts, CompileConfig, CompileErrors};
+    use std::collections::HashSet;
+    use std::io;
+    use zokrates_common::Resolver;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn catalogue() {
+        let codes: HashSet<_> = ERROR_CODES.iter().map(|e| e.code).collect();
+        assert_eq!(codes.len(), ERROR_CODES.len());
+
+        assert_eq!(explain("e0005").unwrap().title, "Semantic error");
+        assert_eq!(explain("E9999"), None);
+    }
+
+    #[test]
+    fn compile_error_code() {
+        let source = "def main(field a) -> bool:\n    return a".to_string();
+        let res: Result<CompilationArtifacts<Bn128Field>, CompileErrors> = compile(
+            source,
+            "./path/to/file".into(),
+            None::<&dyn Resolver<io::Error>>,
+            &CompileConfig::default(),
+        );
+
+        let code = res.unwrap_err().0[0].value().code();
+        assert_eq!(code, "E0005");
+        assert!(explain(code).is_some());
+    }
+}
@@ -12,7 +12,7 @@ pub use self::flat_parameter::FlatParameter;
 pub use self::flat_variable::FlatVariable;
 
 use solvers::Solver;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use zokrates_field::Field;
 
@@ -20,10 +20,15 @@ use zokrates_field::Field;
 pub struct FlatProg<T: Field> {
     /// FlatFunctions of the program
     pub main: FlatFunction<T>,
+    /// FlatFunctions called through `FlatStatement::Call`, by id
+    pub functions: BTreeMap<String, FlatFunction<T>>,
 }
 
 impl<T: Field> fmt::Display for FlatProg<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (id, function) in &self.functions {
+            writeln!(f, "// {}\n{}\n", id, function)?;
+        }
         write!(f, "{}", self.main)
     }
 }
@@ -92,6 +97,7 @@ pub enum FlatStatement<T: Field> {
     Condition(FlatExpression<T>, FlatExpression<T>),
     Definition(FlatVariable, FlatExpression<T>),
     Directive(FlatDirective<T>),
+    Call(FlatCall<T>),
 }
 
 impl<T: Field> fmt::Display for FlatStatement<T> {
@@ -101,6 +107,7 @@ impl<T: Field> fmt::Display for FlatStatement<T> {
             FlatStatement::Return(ref expr) => write!(f, "return {}", expr),
             FlatStatement::Condition(ref lhs, ref rhs) => write!(f, "{} == {}", lhs, rhs),
             FlatStatement::Directive(ref d) => write!(f, "{}", d),
+            FlatStatement::Call(ref c) => write!(f, "{}", c),
         }
     }
 }
@@ -114,6 +121,7 @@ impl<T: Field> fmt::Debug for FlatStatement<T> {
                 write!(f, "FlatCondition({:?}, {:?})", lhs, rhs)
             }
             FlatStatement::Directive(ref d) => write!(f, "{:?}", d),
+            FlatStatement::Call(ref c) => write!(f, "{:?}", c),
         }
     }
 }
@@ -151,10 +159,56 @@ impl<T: Field> FlatStatement<T> {
                     ..d
                 })
             }
+            FlatStatement::Call(c) => {
+                let outputs = c
+                    .outputs
+                    .into_iter()
+                    .map(|o| *o.apply_substitution(substitution))
+                    .collect();
+                let inputs = c
+                    .inputs
+                    .into_iter()
+                    .map(|i| i.apply_substitution(substitution))
+                    .collect();
+
+                FlatStatement::Call(FlatCall {
+                    outputs,
+                    inputs,
+                    ..c
+                })
+            }
         }
     }
 }
 
+/// A call to a `FlatFunction` of the program, whose returns are assigned to `outputs`
+#[derive(Clone, Hash, Debug, PartialEq, Eq)]
+pub struct FlatCall<T: Field> {
+    pub function: String,
+    pub inputs: Vec<FlatExpression<T>>,
+    pub outputs: Vec<FlatVariable>,
+}
+
+impl<T: Field> fmt::Display for FlatCall<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} = {}({})",
+            self.outputs
+                .iter()
+                .map(|o| o.to_string())
+                .collect::<Vec<String>>()
+                .join(", "),
+            self.function,
+            self.inputs
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
 #[derive(Clone, Hash, Debug)]
 pub struct FlatDirective<T: Field> {
     pub inputs: Vec<FlatExpression<T>>,
@@ -9,13 +9,18 @@ mod utils;
 
 use self::utils::flat_expression_from_bits;
 
+use crate::compile::CompileConfig;
 use crate::flat_absy::*;
 use crate::ir;
 use crate::solvers::Solver;
+use crate::zir::ranged::{
+    BooleanExpression, FieldElementExpression, UExpression, ZirExpression, ZirFunction,
+    ZirFunctionSymbols, ZirProgram, ZirStatement,
+};
 use crate::zir::types::{FunctionIdentifier, FunctionKey, Signature, Type, UBitwidth};
 use crate::zir::*;
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::convert::TryFrom;
 use zokrates_field::Field;
 
@@ -24,14 +29,22 @@ type FlatStatements<T> = Vec<FlatStatement<T>>;
 /// Flattener, computes flattened program.
 #[derive(Debug)]
 pub struct Flattener<'ast, T: Field> {
+    /// Compilation options
+    config: CompileConfig,
     /// Index of the next introduced variable while processing the program.
     next_var_idx: usize,
     /// `FlatVariable`s corresponding to each `Identifier`
     layout: HashMap<Identifier<'ast>, FlatVariable>,
-    /// Cached `FlatFunction`s to avoid re-flattening them
-    flat_cache: HashMap<FunctionKey<'ast>, FlatFunction<T>>,
-    /// Cached bit decompositions to avoid re-generating them
-    bits_cache: HashMap<FlatExpression<T>, Vec<FlatExpression<T>>>,
+    /// Flattened functions kept by the inliner, by id
+    functions: BTreeMap<String, FlatFunction<T>>,
+    /// Cached bit decompositions to avoid re-generating them, by decomposed expression and number of bits
+    bits_cache: HashMap<(FlatExpression<T>, usize), Vec<FlatExpression<T>>>,
+    /// Cached euclidean divisions, so that a quotient and a remainder of the same operands share their constraints, by
+    /// dividend, divisor and bitwidth
+    divisions_cache: HashMap<
+        (FlatExpression<T>, FlatExpression<T>, usize),
+        (FlatUExpression<T>, FlatUExpression<T>),
+    >,
 }
 
 trait FlattenOutput<T: Field>: Sized {
@@ -52,7 +65,7 @@ impl<T: Field> FlattenOutput<T> for FlatUExpression<T> {
 
 // We introduce a trait in order to make it possible to make flattening `e` generic over the type of `e`
 
-trait Flatten<'ast, T: Field>: TryFrom<ZirExpression<'ast, T>, Error = ()> + IfElse<'ast, T> {
+trait Flatten<'ast, T: Field>: TryFrom<ZirExpression<'ast, T>, Error = ()> {
     type Output: FlattenOutput<T>;
 
     fn flatten(
@@ -148,18 +161,26 @@ impl<T: Field> FlatUExpression<T> {
 }
 
 impl<'ast, T: Field> Flattener<'ast, T> {
-    pub fn flatten(p: ZirProgram<'ast, T>) -> FlatProg<T> {
-        Flattener::new().flatten_program(p)
+    /// Flattens a program, returning the source map of its flat variables
+    pub fn flatten(
+        p: ZirProgram<'ast, T>,
+        config: &CompileConfig,
+    ) -> (FlatProg<T>, ir::SourceMap<T>) {
+        let mut flattener = Flattener::new(config);
+        let p = flattener.flatten_program(p);
+        (p, flattener.source_map())
     }
 
     /// Returns a `Flattener` with fresh `layout`.
 
-    fn new() -> Flattener<'ast, T> {
+    fn new(config: &CompileConfig) -> Flattener<'ast, T> {
         Flattener {
+            config: config.clone(),
             next_var_idx: 0,
             layout: HashMap::new(),
-            flat_cache: HashMap::new(),
+            functions: BTreeMap::new(),
             bits_cache: HashMap::new(),
+            divisions_cache: HashMap::new(),
         }
     }
 
@@ -626,8 +647,8 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                 let name_y = self.use_sym();
                 let name_m = self.use_sym();
 
-                assert!(lhs.metadata.clone().unwrap().should_reduce.to_bool());
-                assert!(rhs.metadata.clone().unwrap().should_reduce.to_bool());
+                assert!(lhs.should_reduce());
+                assert!(rhs.should_reduce());
 
                 let lhs = self
                     .flatten_uint_expression(symbols, statements_flattened, lhs)
@@ -744,10 +765,10 @@ impl<'ast, T: Field> Flattener<'ast, T> {
         bitwidth: UBitwidth,
     ) -> Vec<FlatUExpression<T>> {
         let expression = UExpression::try_from(expression).unwrap();
-        let from = expression.metadata.clone().unwrap().bitwidth();
+        let from = expression.range().bits();
         let p = self.flatten_uint_expression(symbols, statements_flattened, expression);
         let bits = self
-            .get_bits(p, from as usize, bitwidth, statements_flattened)
+            .get_bits(p, from, bitwidth, statements_flattened)
             .into_iter()
             .map(|b| FlatUExpression::with_field(b))
             .collect();
@@ -773,6 +794,58 @@ impl<'ast, T: Field> Flattener<'ast, T> {
         FlatUExpression::with_bits(bits)
     }
 
+    fn flatten_u32_to_bytes(
+        &mut self,
+        symbols: &ZirFunctionSymbols<'ast, T>,
+        statements_flattened: &mut FlatStatements<T>,
+        expression: ZirExpression<'ast, T>,
+        little_endian: bool,
+    ) -> Vec<FlatUExpression<T>> {
+        // the bytes of a u32 are slices of its bits: no constraint is added beyond the decomposition of the input
+        let bits: Vec<_> = self
+            .flatten_u_to_bits(symbols, statements_flattened, expression, 32.into())
+            .into_iter()
+            .map(|b| b.get_field_unchecked())
+            .collect();
+
+        let mut bytes: Vec<_> = bits
+            .chunks(8)
+            .map(|byte| FlatUExpression::with_bits(byte.to_vec()))
+            .collect();
+
+        if little_endian {
+            bytes.reverse();
+        }
+
+        bytes
+    }
+
+    fn flatten_bytes_to_u32(
+        &mut self,
+        symbols: &ZirFunctionSymbols<'ast, T>,
+        statements_flattened: &mut FlatStatements<T>,
+        mut bytes: Vec<ZirExpression<'ast, T>>,
+        little_endian: bool,
+    ) -> FlatUExpression<T> {
+        assert_eq!(bytes.len(), 4);
+
+        if little_endian {
+            bytes.reverse();
+        }
+
+        let bits: Vec<_> = bytes
+            .into_iter()
+            .flat_map(|byte| {
+                self.flatten_u_to_bits(symbols, statements_flattened, byte, 8.into())
+                    .into_iter()
+                    .map(|b| b.get_field_unchecked())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        FlatUExpression::with_bits(bits)
+    }
+
     /// Flattens a function call
     ///
     /// # Arguments
@@ -796,9 +869,27 @@ impl<'ast, T: Field> Flattener<'ast, T> {
 
         let key = FunctionKey::with_id(id).signature(passed_signature);
 
+        // functions kept by the inliner are flattened once, then called
+        if let Some(ZirFunctionSymbol::Here(funct)) = symbols.get(&key) {
+            let id = self.flatten_shared_function(symbols, &key, funct.clone());
+            return self.flatten_call(symbols, statements_flattened, id, param_expressions);
+        }
+
         let funct = self.get_embed(&key, &symbols);
 
         match funct {
+            crate::embed::FlatEmbed::U128ToBits => self.flatten_u_to_bits(
+                symbols,
+                statements_flattened,
+                param_expressions[0].clone(),
+                128.into(),
+            ),
+            crate::embed::FlatEmbed::U64ToBits => self.flatten_u_to_bits(
+                symbols,
+                statements_flattened,
+                param_expressions[0].clone(),
+                64.into(),
+            ),
             crate::embed::FlatEmbed::U32ToBits => self.flatten_u_to_bits(
                 symbols,
                 statements_flattened,
@@ -817,6 +908,18 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                 param_expressions[0].clone(),
                 8.into(),
             ),
+            crate::embed::FlatEmbed::U128FromBits => vec![self.flatten_bits_to_u(
+                symbols,
+                statements_flattened,
+                param_expressions,
+                128.into(),
+            )],
+            crate::embed::FlatEmbed::U64FromBits => vec![self.flatten_bits_to_u(
+                symbols,
+                statements_flattened,
+                param_expressions,
+                64.into(),
+            )],
             crate::embed::FlatEmbed::U32FromBits => vec![self.flatten_bits_to_u(
                 symbols,
                 statements_flattened,
@@ -835,90 +938,250 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                 param_expressions,
                 8.into(),
             )],
-            funct => {
-                let funct = funct.synthetize();
+            crate::embed::FlatEmbed::U32ToBytesBe => self.flatten_u32_to_bytes(
+                symbols,
+                statements_flattened,
+                param_expressions[0].clone(),
+                false,
+            ),
+            crate::embed::FlatEmbed::U32ToBytesLe => self.flatten_u32_to_bytes(
+                symbols,
+                statements_flattened,
+                param_expressions[0].clone(),
+                true,
+            ),
+            crate::embed::FlatEmbed::U32FromBytesBe => vec![self.flatten_bytes_to_u32(
+                symbols,
+                statements_flattened,
+                param_expressions,
+                false,
+            )],
+            crate::embed::FlatEmbed::U32FromBytesLe => vec![self.flatten_bytes_to_u32(
+                symbols,
+                statements_flattened,
+                param_expressions,
+                true,
+            )],
+            crate::embed::FlatEmbed::DivMod(bitwidth) => {
+                let mut operands = param_expressions
+                    .into_iter()
+                    .map(|e| UExpression::try_from(e).unwrap());
+                let (quotient, remainder) = self.flatten_euclidean_division(
+                    symbols,
+                    statements_flattened,
+                    bitwidth.into(),
+                    operands.next().unwrap(),
+                    operands.next().unwrap(),
+                );
+                vec![quotient, remainder]
+            }
+            funct => self.instantiate_function(
+                symbols,
+                statements_flattened,
+                funct.synthetize(),
+                param_expressions,
+            ),
+        }
+    }
 
-                let mut replacement_map = HashMap::new();
+    /// Flattens a function kept by the inliner, unless it was already, returning its id
+    ///
+    /// # Arguments
+    ///
+    /// * `symbols` - Available functions in this context
+    /// * `key` - Key of the function
+    /// * `funct` - `ZirFunction` that will be flattened
+    fn flatten_shared_function(
+        &mut self,
+        symbols: &ZirFunctionSymbols<'ast, T>,
+        key: &FunctionKey<'ast>,
+        funct: ZirFunction<'ast, T>,
+    ) -> String {
+        let id = key.to_slug();
 
-                // Handle complex parameters and assign values:
-                // Rename Parameters, assign them to values in call. Resolve complex expressions with definitions
-                let params_flattened = param_expressions
-                    .into_iter()
-                    .map(|param_expr| {
-                        self.flatten_expression(symbols, statements_flattened, param_expr)
-                    })
-                    .into_iter()
-                    .map(|x| x.get_field_unchecked())
-                    .collect::<Vec<_>>();
+        if self.functions.contains_key(&id) {
+            return id;
+        }
 
-                for (concrete_argument, formal_argument) in
-                    params_flattened.into_iter().zip(funct.arguments)
-                {
-                    let new_var = self.define(concrete_argument, statements_flattened);
-                    replacement_map.insert(formal_argument.id, new_var);
-                }
+        // the function is flattened on its own, so the state of the function being flattened is put aside. Its
+        // variables do not clash with the ones of its callers, as each call is instantiated on fresh variables.
+        let layout = std::mem::replace(&mut self.layout, HashMap::new());
+        let next_var_idx = self.next_var_idx;
+        let bits_cache = std::mem::replace(&mut self.bits_cache, HashMap::new());
+        let divisions_cache = std::mem::replace(&mut self.divisions_cache, HashMap::new());
 
-                // Ensure renaming and correct returns:
-                // add all flattened statements, adapt return statements
+        let flattened = self.flatten_function(symbols, funct);
 
-                let (mut return_statements, statements): (Vec<_>, Vec<_>) =
-                    funct.statements.into_iter().partition(|s| match s {
-                        FlatStatement::Return(..) => true,
-                        _ => false,
-                    });
+        self.layout = layout;
+        self.next_var_idx = next_var_idx;
+        self.bits_cache = bits_cache;
+        self.divisions_cache = divisions_cache;
 
-                let statements: Vec<_> = statements
-                    .into_iter()
-                    .map(|stat| match stat {
-                        // set return statements as expression result
-                        FlatStatement::Return(..) => unreachable!(),
-                        FlatStatement::Definition(var, rhs) => {
-                            let new_var = self.use_sym();
-                            replacement_map.insert(var, new_var);
-                            let new_rhs = rhs.apply_substitution(&replacement_map);
-                            FlatStatement::Definition(new_var, new_rhs)
-                        }
-                        FlatStatement::Condition(lhs, rhs) => {
-                            let new_lhs = lhs.apply_substitution(&replacement_map);
-                            let new_rhs = rhs.apply_substitution(&replacement_map);
-                            FlatStatement::Condition(new_lhs, new_rhs)
-                        }
-                        FlatStatement::Directive(d) => {
-                            let new_outputs = d
-                                .outputs
-                                .into_iter()
-                                .map(|o| {
-                                    let new_o = self.use_sym();
-                                    replacement_map.insert(o, new_o);
-                                    new_o
-                                })
-                                .collect();
-                            let new_inputs = d
-                                .inputs
-                                .into_iter()
-                                .map(|i| i.apply_substitution(&replacement_map))
-                                .collect();
-                            FlatStatement::Directive(FlatDirective {
-                                outputs: new_outputs,
-                                solver: d.solver,
-                                inputs: new_inputs,
-                            })
-                        }
-                    })
-                    .collect();
+        self.functions.insert(id.clone(), flattened);
+        id
+    }
+
+    /// Flattens a call to a function kept by the inliner, assigning its returns to fresh variables
+    ///
+    /// # Arguments
+    ///
+    /// * `symbols` - Available functions in this context
+    /// * `statements_flattened` - Vector where new flattened statements can be added.
+    /// * `id` - Id of the flattened function
+    /// * `param_expressions` - Arguments of this call
+    fn flatten_call(
+        &mut self,
+        symbols: &ZirFunctionSymbols<'ast, T>,
+        statements_flattened: &mut FlatStatements<T>,
+        id: String,
+        param_expressions: Vec<ZirExpression<'ast, T>>,
+    ) -> Vec<FlatUExpression<T>> {
+        let return_count = self.functions[&id]
+            .statements
+            .iter()
+            .filter_map(|s| match s {
+                FlatStatement::Return(list) => Some(list.expressions.len()),
+                _ => None,
+            })
+            .next()
+            .unwrap();
+
+        let mut inputs = vec![];
+
+        for param_expr in param_expressions {
+            let e = self
+                .flatten_expression(symbols, statements_flattened, param_expr)
+                .get_field_unchecked();
+            inputs.push(self.define(e, statements_flattened).into());
+        }
 
-                statements_flattened.extend(statements);
+        let outputs: Vec<_> = (0..return_count).map(|_| self.use_sym()).collect();
 
-                match return_statements.pop().unwrap() {
-                    FlatStatement::Return(list) => list
-                        .expressions
+        statements_flattened.push(FlatStatement::Call(FlatCall {
+            function: id,
+            inputs,
+            outputs: outputs.clone(),
+        }));
+
+        outputs
+            .into_iter()
+            .map(|o| FlatUExpression::with_field(FlatExpression::Identifier(o)))
+            .collect()
+    }
+
+    /// Instantiates a flat function at a call, renaming its variables
+    ///
+    /// # Arguments
+    ///
+    /// * `symbols` - Available functions in this context
+    /// * `statements_flattened` - Vector where new flattened statements can be added.
+    /// * `funct` - `FlatFunction` that will be instantiated
+    /// * `param_expressions` - Arguments of this call
+    fn instantiate_function(
+        &mut self,
+        symbols: &ZirFunctionSymbols<'ast, T>,
+        statements_flattened: &mut FlatStatements<T>,
+        funct: FlatFunction<T>,
+        param_expressions: Vec<ZirExpression<'ast, T>>,
+    ) -> Vec<FlatUExpression<T>> {
+        let mut replacement_map = HashMap::new();
+
+        // Handle complex parameters and assign values:
+        // Rename Parameters, assign them to values in call. Resolve complex expressions with definitions
+        let params_flattened = param_expressions
+            .into_iter()
+            .map(|param_expr| self.flatten_expression(symbols, statements_flattened, param_expr))
+            .into_iter()
+            .map(|x| x.get_field_unchecked())
+            .collect::<Vec<_>>();
+
+        for (concrete_argument, formal_argument) in
+            params_flattened.into_iter().zip(funct.arguments)
+        {
+            let new_var = self.define(concrete_argument, statements_flattened);
+            replacement_map.insert(formal_argument.id, new_var);
+        }
+
+        // Ensure renaming and correct returns:
+        // add all flattened statements, adapt return statements
+
+        let (mut return_statements, statements): (Vec<_>, Vec<_>) =
+            funct.statements.into_iter().partition(|s| match s {
+                FlatStatement::Return(..) => true,
+                _ => false,
+            });
+
+        let statements: Vec<_> = statements
+            .into_iter()
+            .map(|stat| match stat {
+                // set return statements as expression result
+                FlatStatement::Return(..) => unreachable!(),
+                FlatStatement::Definition(var, rhs) => {
+                    let new_var = self.use_sym();
+                    replacement_map.insert(var, new_var);
+                    let new_rhs = rhs.apply_substitution(&replacement_map);
+                    FlatStatement::Definition(new_var, new_rhs)
+                }
+                FlatStatement::Condition(lhs, rhs) => {
+                    let new_lhs = lhs.apply_substitution(&replacement_map);
+                    let new_rhs = rhs.apply_substitution(&replacement_map);
+                    FlatStatement::Condition(new_lhs, new_rhs)
+                }
+                FlatStatement::Directive(d) => {
+                    let new_outputs = d
+                        .outputs
                         .into_iter()
-                        .map(|x| x.apply_substitution(&replacement_map))
-                        .map(|x| FlatUExpression::with_field(x))
-                        .collect(),
-                    _ => unreachable!(),
+                        .map(|o| {
+                            let new_o = self.use_sym();
+                            replacement_map.insert(o, new_o);
+                            new_o
+                        })
+                        .collect();
+                    let new_inputs = d
+                        .inputs
+                        .into_iter()
+                        .map(|i| i.apply_substitution(&replacement_map))
+                        .collect();
+                    FlatStatement::Directive(FlatDirective {
+                        outputs: new_outputs,
+                        solver: d.solver,
+                        inputs: new_inputs,
+                    })
                 }
-            }
+                FlatStatement::Call(c) => {
+                    let new_inputs = c
+                        .inputs
+                        .into_iter()
+                        .map(|i| i.apply_substitution(&replacement_map))
+                        .collect();
+                    let new_outputs = c
+                        .outputs
+                        .into_iter()
+                        .map(|o| {
+                            let new_o = self.use_sym();
+                            replacement_map.insert(o, new_o);
+                            new_o
+                        })
+                        .collect();
+                    FlatStatement::Call(FlatCall {
+                        function: c.function,
+                        inputs: new_inputs,
+                        outputs: new_outputs,
+                    })
+                }
+            })
+            .collect();
+
+        statements_flattened.extend(statements);
+
+        match return_statements.pop().unwrap() {
+            FlatStatement::Return(list) => list
+                .expressions
+                .into_iter()
+                .map(|x| x.apply_substitution(&replacement_map))
+                .map(|x| FlatUExpression::with_field(x))
+                .collect(),
+            _ => unreachable!(),
         }
     }
 
@@ -1025,29 +1288,25 @@ impl<'ast, T: Field> Flattener<'ast, T> {
         statements_flattened: &mut FlatStatements<T>,
         expr: UExpression<'ast, T>,
     ) -> FlatUExpression<T> {
-        // the bitwidth for this type of uint (8, 16 or 32)
+        // the bitwidth for this type of uint (8, 16, 32, 64 or 128)
         let target_bitwidth = expr.bitwidth;
 
-        let metadata = expr.metadata.clone().unwrap().clone();
-
         // the bitwidth on which this value is currently represented
-        let actual_bitwidth = metadata.bitwidth() as usize;
+        let actual_bitwidth = expr.range().bits();
 
         // whether this value should be reduced, for example if it is then used in a bitwidth operation
-        let should_reduce = metadata.should_reduce;
-
-        let should_reduce = should_reduce.to_bool();
+        let should_reduce = expr.should_reduce();
 
         let res = match expr.into_inner() {
             UExpressionInner::Value(x) => {
-                FlatUExpression::with_field(FlatExpression::Number(T::from(x as usize)))
+                FlatUExpression::with_field(FlatExpression::Number(T::from(x)))
             } // force to be a field element
             UExpressionInner::Identifier(x) => {
                 let field = FlatExpression::Identifier(self.layout.get(&x).unwrap().clone());
-                let bits = self.bits_cache.get(&field).map(|bits| {
-                    assert_eq!(bits.len(), target_bitwidth.to_usize());
-                    bits.clone()
-                });
+                let bits = self
+                    .bits_cache
+                    .get(&(field.clone(), target_bitwidth.to_usize()))
+                    .cloned();
                 FlatUExpression::with_field(field).bits(bits)
             }
             UExpressionInner::Not(box e) => {
@@ -1097,10 +1356,9 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                 FlatUExpression::with_field(FlatExpression::Add(box new_left, box new_right))
             }
             UExpressionInner::Sub(box left, box right) => {
-                // see uint optimizer for the reasoning here
-                let aux = FlatExpression::Number(
-                    T::from(2).pow(right.metadata.clone().unwrap().bitwidth() as usize),
-                );
+                // see range analysis for the reasoning here
+                let aux =
+                    FlatExpression::Number(right.range().sub_offset(target_bitwidth.to_usize()));
 
                 let left_flattened = self
                     .flatten_uint_expression(symbols, statements_flattened, left)
@@ -1177,6 +1435,26 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                         .collect::<Vec<_>>(),
                 )
             }
+            UExpressionInner::Mult(box left, box right)
+                if multiplies_on_limbs::<T>(target_bitwidth.to_usize())
+                    && left.should_reduce()
+                    && right.should_reduce() =>
+            {
+                let left_bits = self
+                    .flatten_uint_expression(symbols, statements_flattened, left)
+                    .bits
+                    .unwrap();
+                let right_bits = self
+                    .flatten_uint_expression(symbols, statements_flattened, right)
+                    .bits
+                    .unwrap();
+
+                FlatUExpression::with_field(self.flatten_limb_product(
+                    left_bits,
+                    right_bits,
+                    statements_flattened,
+                ))
+            }
             UExpressionInner::Mult(box left, box right) => {
                 let left_flattened = self
                     .flatten_uint_expression(symbols, statements_flattened, left)
@@ -1208,6 +1486,26 @@ impl<'ast, T: Field> Flattener<'ast, T> {
 
                 FlatUExpression::with_field(FlatExpression::Identifier(res))
             }
+            UExpressionInner::Div(box left, box right) => {
+                self.flatten_euclidean_division(
+                    symbols,
+                    statements_flattened,
+                    target_bitwidth,
+                    left,
+                    right,
+                )
+                .0
+            }
+            UExpressionInner::Rem(box left, box right) => {
+                self.flatten_euclidean_division(
+                    symbols,
+                    statements_flattened,
+                    target_bitwidth,
+                    left,
+                    right,
+                )
+                .1
+            }
             UExpressionInner::IfElse(box condition, box consequence, box alternative) => self
                 .flatten_if_else_expression(
                     symbols,
@@ -1218,8 +1516,8 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                 )
                 .clone(),
             UExpressionInner::Xor(box left, box right) => {
-                let left_metadata = left.metadata.clone().unwrap();
-                let right_metadata = right.metadata.clone().unwrap();
+                let left_metadata = left.metadata().clone();
+                let right_metadata = right.metadata().clone();
 
                 match (left.into_inner(), right.into_inner()) {
                     (UExpressionInner::And(box a, box b), UExpressionInner::And(box aa, box c)) => {
@@ -1268,17 +1566,15 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                                 symbols,
                                 statements_flattened,
                                 UExpressionInner::And(box a, box b)
-                                    .annotate(target_bitwidth)
-                                    .metadata(left_metadata),
+                                    .with_metadata(target_bitwidth, left_metadata),
                                 UExpressionInner::And(box aa, box c)
-                                    .annotate(target_bitwidth)
-                                    .metadata(right_metadata),
+                                    .with_metadata(target_bitwidth, right_metadata),
                             )
                         }
                     }
                     (UExpressionInner::Xor(box a, box b), c) => {
-                        let a_metadata = a.metadata.clone().unwrap();
-                        let b_metadata = b.metadata.clone().unwrap();
+                        let a_metadata = a.metadata().clone();
+                        let b_metadata = b.metadata().clone();
 
                         match (a.into_inner(), b.into_inner(), c) {
                             (
@@ -1359,17 +1655,13 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                                         statements_flattened,
                                         UExpressionInner::Xor(
                                             box UExpressionInner::And(box a, box b)
-                                                .annotate(target_bitwidth)
-                                                .metadata(a_metadata),
+                                                .with_metadata(target_bitwidth, a_metadata),
                                             box UExpressionInner::And(box aa, box c)
-                                                .annotate(target_bitwidth)
-                                                .metadata(b_metadata),
+                                                .with_metadata(target_bitwidth, b_metadata),
                                         )
-                                        .annotate(target_bitwidth)
-                                        .metadata(left_metadata),
+                                        .with_metadata(target_bitwidth, left_metadata),
                                         UExpressionInner::And(box bb, box cc)
-                                            .annotate(target_bitwidth)
-                                            .metadata(right_metadata),
+                                            .with_metadata(target_bitwidth, right_metadata),
                                     )
                                 }
                             }
@@ -1377,20 +1669,19 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                                 symbols,
                                 statements_flattened,
                                 UExpressionInner::Xor(
-                                    box a.annotate(target_bitwidth).metadata(a_metadata),
-                                    box b.annotate(target_bitwidth).metadata(b_metadata),
+                                    box a.with_metadata(target_bitwidth, a_metadata),
+                                    box b.with_metadata(target_bitwidth, b_metadata),
                                 )
-                                .annotate(target_bitwidth)
-                                .metadata(left_metadata),
-                                c.annotate(target_bitwidth).metadata(right_metadata),
+                                .with_metadata(target_bitwidth, left_metadata),
+                                c.with_metadata(target_bitwidth, right_metadata),
                             ),
                         }
                     }
                     (left_i, right_i) => self.default_xor(
                         symbols,
                         statements_flattened,
-                        left_i.annotate(target_bitwidth).metadata(left_metadata),
-                        right_i.annotate(target_bitwidth).metadata(right_metadata),
+                        left_i.with_metadata(target_bitwidth, left_metadata),
+                        right_i.with_metadata(target_bitwidth, right_metadata),
                     ),
                 }
             }
@@ -1520,6 +1811,140 @@ impl<'ast, T: Field> Flattener<'ast, T> {
         res
     }
 
+    /// Returns the product of two reduced unsigned integers given by their bits, up to a multiple of
+    /// `2**bitwidth`, for bitwidths whose full product does not fit in the field
+    ///
+    /// With `a = a_high * 2**half + a_low`, `a * b` is `a_low * b_low + (a_high * b_low + a_low * b_high) * 2**half`
+    /// modulo `2**bitwidth`, which only takes about three halves of the bitwidth.
+    fn flatten_limb_product(
+        &mut self,
+        left: Vec<FlatExpression<T>>,
+        right: Vec<FlatExpression<T>>,
+        statements_flattened: &mut FlatStatements<T>,
+    ) -> FlatExpression<T> {
+        let half = left.len() / 2;
+
+        let (left_high, left_low) = left.split_at(half);
+        let (right_high, right_low) = right.split_at(half);
+
+        let limb = |bits: &[FlatExpression<T>]| flat_expression_from_bits(bits.to_vec());
+
+        let left_high = limb(left_high);
+        let left_low = limb(left_low);
+        let right_high = limb(right_high);
+        let right_low = limb(right_low);
+
+        let mut product = |x: FlatExpression<T>, y: FlatExpression<T>| -> FlatExpression<T> {
+            self.define(FlatExpression::Mult(box x, box y), statements_flattened)
+                .into()
+        };
+
+        let low = product(left_low.clone(), right_low.clone());
+        let cross_left = product(left_high, right_low);
+        let cross_right = product(left_low, right_high);
+
+        let shift = FlatExpression::Number(T::from(2).pow(half));
+
+        FlatExpression::Add(
+            box low,
+            box FlatExpression::Add(
+                box FlatExpression::Mult(box shift.clone(), box cross_left),
+                box FlatExpression::Mult(box shift, box cross_right),
+            ),
+        )
+    }
+
+    /// Flattens the euclidean division of two reduced unsigned integers, returning the quotient and the remainder
+    ///
+    /// The quotient and the remainder are computed by a directive and decomposed on `bitwidth` bits, as is
+    /// `divisor - (remainder + 1)`, which enforces `remainder < divisor` and rejects a zero divisor. Range analysis
+    /// ensures that `quotient * divisor + remainder` does not overflow, so that checking it against the dividend is
+    /// enough. The number of constraints does not depend on the values of the operands.
+    fn flatten_euclidean_division(
+        &mut self,
+        symbols: &ZirFunctionSymbols<'ast, T>,
+        statements_flattened: &mut FlatStatements<T>,
+        bitwidth: UBitwidth,
+        left: UExpression<'ast, T>,
+        right: UExpression<'ast, T>,
+    ) -> (FlatUExpression<T>, FlatUExpression<T>) {
+        let left_flattened = self
+            .flatten_uint_expression(symbols, statements_flattened, left)
+            .get_field_unchecked();
+        let right_flattened = self
+            .flatten_uint_expression(symbols, statements_flattened, right)
+            .get_field_unchecked();
+
+        let dividend = if left_flattened.is_linear() {
+            left_flattened
+        } else {
+            FlatExpression::Identifier(self.define(left_flattened, statements_flattened))
+        };
+        let divisor = if right_flattened.is_linear() {
+            right_flattened
+        } else {
+            FlatExpression::Identifier(self.define(right_flattened, statements_flattened))
+        };
+
+        let key = (dividend.clone(), divisor.clone(), bitwidth.to_usize());
+
+        if let Some(res) = self.divisions_cache.get(&key) {
+            return res.clone();
+        }
+
+        let quotient = self.use_sym();
+        let remainder = self.use_sym();
+
+        statements_flattened.push(FlatStatement::Directive(FlatDirective::new(
+            vec![quotient, remainder],
+            Solver::EuclideanDiv,
+            vec![dividend.clone(), divisor.clone()],
+        )));
+
+        let quotient = FlatUExpression::with_field(FlatExpression::Identifier(quotient));
+        let remainder = FlatUExpression::with_field(FlatExpression::Identifier(remainder));
+
+        // range checks
+        let quotient_bits = self.get_bits(
+            quotient.clone(),
+            bitwidth.to_usize(),
+            bitwidth,
+            statements_flattened,
+        );
+        let remainder_bits = self.get_bits(
+            remainder.clone(),
+            bitwidth.to_usize(),
+            bitwidth,
+            statements_flattened,
+        );
+
+        // remainder < divisor
+        self.get_bits(
+            FlatUExpression::with_field(FlatExpression::Sub(
+                box divisor.clone(),
+                box FlatExpression::Add(
+                    box remainder.clone().get_field_unchecked(),
+                    box FlatExpression::Number(T::from(1)),
+                ),
+            )),
+            bitwidth.to_usize(),
+            bitwidth,
+            statements_flattened,
+        );
+
+        // dividend == quotient * divisor + remainder
+        statements_flattened.push(FlatStatement::Condition(
+            FlatExpression::Sub(box dividend, box remainder.clone().get_field_unchecked()),
+            FlatExpression::Mult(box quotient.clone().get_field_unchecked(), box divisor),
+        ));
+
+        let res = (quotient.bits(quotient_bits), remainder.bits(remainder_bits));
+
+        self.divisions_cache.insert(key, res.clone());
+
+        res
+    }
+
     fn get_bits(
         &mut self,
         e: FlatUExpression<T>,
@@ -1545,7 +1970,7 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                 assert_eq!(bits.len(), to);
 
                 self.bits_cache
-                    .insert(e.field.clone().unwrap(), bits.clone());
+                    .insert((e.field.clone().unwrap(), to), bits.clone());
                 return bits;
             }
             _ => {}
@@ -1557,13 +1982,9 @@ impl<'ast, T: Field> Flattener<'ast, T> {
             // constant
 
             let from = std::cmp::max(from, to);
-            match self.bits_cache.entry(e.field.clone().unwrap()) {
-                Entry::Occupied(entry) => {
-                    let res: Vec<_> = entry.get().clone().into_iter().map(|e| e.into()).collect();
-                    // if we already know a decomposition, it has to be of the size of the target bitwidth
-                    assert_eq!(res.len(), to);
-                    res
-                }
+            // the same value can be decomposed on several bitwidths, which give different bits
+            match self.bits_cache.entry((e.field.clone().unwrap(), to)) {
+                Entry::Occupied(entry) => entry.get().clone(),
                 Entry::Vacant(_) => {
                     let bits = (0..from).map(|_| self.use_sym()).collect::<Vec<_>>();
                     statements_flattened.push(FlatStatement::Directive(FlatDirective::new(
@@ -1603,10 +2024,10 @@ impl<'ast, T: Field> Flattener<'ast, T> {
 
                     assert_eq!(bits.len(), to);
 
-                    self.bits_cache.insert(e.field.unwrap(), bits.clone());
-                    self.bits_cache.insert(sum, bits.clone());
+                    self.bits_cache.insert((e.field.unwrap(), to), bits.clone());
+                    self.bits_cache.insert((sum, to), bits.clone());
 
-                    bits.into_iter().map(|v| v.into()).collect()
+                    bits
                 }
             }
         })
@@ -1890,7 +2311,7 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                 match bits {
                     Some(bits) => {
                         self.bits_cache
-                            .insert(FlatExpression::Identifier(var), bits);
+                            .insert((FlatExpression::Identifier(var), bits.len()), bits);
                     }
                     None => {}
                 }
@@ -1929,6 +2350,10 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                             exprs.clone(),
                         );
 
+                        // keep the bits of the outputs which are known, so that they are not decomposed again
+                        let rhs_bits: Vec<_> =
+                            rhs_flattened.iter().map(|r| r.bits.clone()).collect();
+
                         let rhs = rhs_flattened.into_iter();
 
                         let vars: Vec<_> = vars
@@ -1947,15 +2372,11 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                             })
                             .collect();
 
-                        if ["_U32_FROM_BITS", "_U16_FROM_BITS", "_U8_FROM_BITS"].contains(&key.id) {
-                            let bits = exprs
-                                .into_iter()
-                                .map(|e| {
-                                    self.flatten_expression(symbols, statements_flattened, e)
-                                        .get_field_unchecked()
-                                })
-                                .collect();
-                            self.bits_cache.insert(vars[0].clone().into(), bits);
+                        for (var, bits) in vars.iter().zip(rhs_bits) {
+                            if let Some(bits) = bits {
+                                self.bits_cache
+                                    .insert((var.clone().into(), bits.len()), bits);
+                            }
                         }
                     }
                 }
@@ -2021,9 +2442,25 @@ impl<'ast, T: Field> Flattener<'ast, T> {
 
         FlatProg {
             main: main_flattened,
+            functions: std::mem::replace(&mut self.functions, BTreeMap::new()),
         }
     }
 
+    /// Builds the source map of the variables of `main` from the layout, ignoring internal and inlined variables
+    fn source_map(&self) -> ir::SourceMap<T> {
+        let mut source_map = ir::SourceMap::default();
+
+        for (id, variable) in &self.layout {
+            if let Identifier::Source(id) = id {
+                if let Some((name, occurrence)) = id.source_name() {
+                    source_map.insert(name, occurrence, (*variable).into());
+                }
+            }
+        }
+
+        source_map
+    }
+
     /// Checks if the given name is a not used variable and returns a fresh variable.
     /// # Arguments
     ///
@@ -2058,8 +2495,11 @@ impl<'ast, T: Field> Flattener<'ast, T> {
     ) -> FlatParameter {
         let variable = self.use_variable(&parameter.id);
 
+        // public inputs are provided by the verifier, so checking their range can be disabled
+        let validate = parameter.private || self.config.validate_inputs;
+
         match parameter.id.get_type() {
-            Type::Uint(bitwidth) => {
+            Type::Uint(bitwidth) if validate => {
                 // to constrain unsigned integer inputs to be in range, we get their bit decomposition.
                 // it will be cached
                 self.get_bits(
@@ -2069,7 +2509,7 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                     statements_flattened,
                 );
             }
-            Type::Boolean => {
+            Type::Boolean if validate => {
                 statements_flattened.push(FlatStatement::Condition(
                     variable.into(),
                     FlatExpression::Mult(box variable.into(), box variable.into()),
@@ -2150,7 +2590,7 @@ mod tests {
             },
         };
 
-        let mut flattener = Flattener::new();
+        let mut flattener = Flattener::new(&CompileConfig::default());
 
         let expected = FlatFunction {
             arguments: vec![],
@@ -2209,7 +2649,7 @@ mod tests {
             },
         };
 
-        let mut flattener = Flattener::new();
+        let mut flattener = Flattener::new(&CompileConfig::default());
 
         let expected = FlatFunction {
             arguments: vec![],
@@ -2288,7 +2728,7 @@ mod tests {
             },
         };
 
-        let mut flattener = Flattener::new();
+        let mut flattener = Flattener::new(&CompileConfig::default());
 
         let expected = FlatFunction {
             arguments: vec![],
@@ -2361,14 +2801,14 @@ mod tests {
             box FieldElementExpression::Number(Bn128Field::from(51)),
         );
 
-        let mut flattener = Flattener::new();
+        let mut flattener = Flattener::new(&CompileConfig::default());
 
         flattener.flatten_field_expression(&HashMap::new(), &mut FlatStatements::new(), expression);
     }
 
     #[test]
     fn geq_leq() {
-        let mut flattener = Flattener::new();
+        let mut flattener = Flattener::new(&CompileConfig::default());
         let expression_le = BooleanExpression::Le(
             box FieldElementExpression::Number(Bn128Field::from(32)),
             box FieldElementExpression::Number(Bn128Field::from(4)),
@@ -2379,7 +2819,7 @@ mod tests {
             expression_le,
         );
 
-        let mut flattener = Flattener::new();
+        let mut flattener = Flattener::new(&CompileConfig::default());
         let expression_ge = BooleanExpression::Ge(
             box FieldElementExpression::Number(Bn128Field::from(32)),
             box FieldElementExpression::Number(Bn128Field::from(4)),
@@ -2393,7 +2833,7 @@ mod tests {
 
     #[test]
     fn bool_and() {
-        let mut flattener = Flattener::new();
+        let mut flattener = Flattener::new(&CompileConfig::default());
 
         let expression = FieldElementExpression::IfElse(
             box BooleanExpression::And(
@@ -2417,7 +2857,7 @@ mod tests {
     fn div() {
         // a = 5 / b / b
 
-        let mut flattener = Flattener::new();
+        let mut flattener = Flattener::new(&CompileConfig::default());
         let mut statements_flattened = FlatStatements::new();
 
         let definition = ZirStatement::Definition(
@@ -2513,10 +2953,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn bits_cache() {
+        let mut flattener: Flattener<Bn128Field> = Flattener::new(&CompileConfig::default());
+        let mut statements_flattened = FlatStatements::new();
+
+        let x = flattener.use_sym();
+        let x = || FlatUExpression::with_field(FlatExpression::Identifier(x));
+
+        let mut get_bits =
+            |bitwidth| flattener.get_bits(x(), 32, bitwidth, &mut statements_flattened);
+
+        let low = get_bits(UBitwidth::B16);
+        let all = get_bits(UBitwidth::B32);
+
+        assert_eq!(low.len(), 16);
+        assert_eq!(all.len(), 32);
+
+        // reducing `x` again reuses the decompositions
+        assert_eq!(get_bits(UBitwidth::B16), low);
+        assert_eq!(get_bits(UBitwidth::B32), all);
+
+        let decompositions = statements_flattened
+            .iter()
+            .filter(|s| match s {
+                FlatStatement::Directive(..) => true,
+                _ => false,
+            })
+            .count();
+
+        assert_eq!(decompositions, 2);
+    }
+
     #[test]
     #[should_panic]
     fn next_variable() {
-        let mut flattener: Flattener<Bn128Field> = Flattener::new();
+        let mut flattener: Flattener<Bn128Field> = Flattener::new(&CompileConfig::default());
         assert_eq!(
             FlatVariable::new(0),
             flattener.use_variable(&Variable::field_element("a"))
@@ -0,0 +1,102 @@
+//! Entry points for fuzzing the compiler, used by the targets in the `fuzz` directory at the root of the repository.
+//!
+//! Each entry point accepts arbitrary bytes and discards compilation errors: only panics are failures.
+//! Inputs which are not valid UTF-8 or longer than `MAX_SOURCE_LENGTH` are ignored, imports cannot be
+//! resolved, so that the fuzzer never touches the file system, and compilation runs under resource limits.
+
+use compile::{check, compile, CompileConfig};
+use ir::Interpreter;
+use limits::Limits;
+use std::io;
+use std::path::PathBuf;
+use zokrates_common::Resolver;
+use zokrates_field::{Bn128Field, Field};
+
+pub const MAX_SOURCE_LENGTH: usize = 1 << 14;
+
+fn source(data: &[u8]) -> Option<String> {
+    if data.len() > MAX_SOURCE_LENGTH {
+        return None;
+    }
+    std::str::from_utf8(data).ok().map(String::from)
+}
+
+fn location() -> PathBuf {
+    PathBuf::from("fuzz.zok")
+}
+
+fn config() -> CompileConfig {
+    CompileConfig::default().limits(
+        Limits::default()
+            .max_ast_nodes(1 << 16)
+            .max_unrolled_statements(1 << 16)
+            .timeout_ms(1000),
+    )
+}
+
+/// Parses the input
+pub fn parse(data: &[u8]) {
+    if let Some(source) = source(data) {
+        let _ = zokrates_pest_ast::generate_ast(&source);
+    }
+}
+
+/// Parses and checks the input, then runs the static analysis passes on the typed program, down to the
+/// optimization of unsigned integers on ZIR
+pub fn analyse(data: &[u8]) {
+    if let Some(source) = source(data) {
+        let _ = check::<Bn128Field, io::Error>(
+            source,
+            location(),
+            None::<&dyn Resolver<io::Error>>,
+            &config(),
+        );
+    }
+}
+
+/// Compiles the input
+pub fn compile_from_string(data: &[u8]) {
+    if let Some(source) = source(data) {
+        let _ = compile::<Bn128Field, io::Error>(
+            source,
+            location(),
+            None::<&dyn Resolver<io::Error>>,
+            &config(),
+        );
+    }
+}
+
+/// Compiles the part of the input before the first zero byte and runs the compiled program on arguments read from
+/// the rest of the input, eight bytes per argument
+pub fn compile_and_run(data: &[u8]) {
+    let split = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+    let (code, arguments) = data.split_at(split);
+
+    let source = match source(code) {
+        Some(source) => source,
+        None => return,
+    };
+
+    let artifacts = match compile::<Bn128Field, io::Error>(
+        source,
+        location(),
+        None::<&dyn Resolver<io::Error>>,
+        &config(),
+    ) {
+        Ok(artifacts) => artifacts,
+        Err(_) => return,
+    };
+
+    let program = artifacts.prog();
+
+    let mut arguments: Vec<Bn128Field> = arguments
+        .iter()
+        .skip(1)
+        .collect::<Vec<_>>()
+        .chunks(8)
+        .map(|chunk| Bn128Field::from_byte_vector(chunk.iter().map(|b| **b).collect()))
+        .collect();
+    arguments.resize(program.arguments_count(), Bn128Field::from(0));
+
+    let _ = Interpreter::default().execute(program, &arguments);
+}
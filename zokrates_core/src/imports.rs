@@ -6,13 +6,14 @@
 
 use crate::absy::*;
 use crate::compile::compile_module;
-use crate::compile::{CompileErrorInner, CompileErrors};
-use crate::embed::FlatEmbed;
+use crate::compile::{CompileErrorInner, CompileErrors, CompileWarning, CompileWarningInner};
+use crate::deprecation::{self, Deprecation, Syntax};
+use crate::embed::{is_field_divmod_bound, FlatEmbed, DIVMOD_BITWIDTHS, UNPACK_BITWIDTHS};
 use crate::parser::Position;
 use std::collections::HashMap;
 use std::fmt;
 use std::io;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 use typed_arena::Arena;
 use zokrates_common::Resolver;
@@ -138,6 +139,7 @@ impl Importer {
         location: PathBuf,
         resolver: Option<&dyn Resolver<E>>,
         modules: &mut HashMap<ModuleId, Module<'ast, T>>,
+        mut warnings: Option<&mut Vec<CompileWarning>>,
         arena: &'ast Arena<String>,
     ) -> Result<Module<'ast, T>, CompileErrors> {
         let mut symbols: Vec<_> = vec![];
@@ -160,6 +162,59 @@ impl Importer {
                             .start_end(pos.0, pos.1),
                         );
                     }
+                    s if UNPACK_BITWIDTHS
+                        .iter()
+                        .any(|bitwidth| s == format!("EMBED/unpack{}", bitwidth)) =>
+                    {
+                        let bitwidth = s["EMBED/unpack".len()..].parse().unwrap();
+
+                        // unpacking is only unique to fewer bits than the field has
+                        if bitwidth >= T::get_required_bits() {
+                            return Err(CompileErrorInner::ImportError(
+                                Error::new(format!(
+                                    "Embed {} is not available in the {} field, whose elements have {} bits",
+                                    s,
+                                    T::name(),
+                                    T::get_required_bits()
+                                ))
+                                .with_pos(Some(pos)),
+                            )
+                            .in_file(&location)
+                            .into());
+                        }
+
+                        let alias = alias.unwrap_or(&s["EMBED/".len()..]);
+
+                        symbols.push(
+                            SymbolDeclaration {
+                                id: &alias,
+                                symbol: Symbol::Flat(FlatEmbed::Unpack(bitwidth)),
+                            }
+                            .start_end(pos.0, pos.1),
+                        );
+                    }
+                    "EMBED/u128_to_bits" => {
+                        let alias = alias.unwrap_or("u128_to_bits");
+
+                        symbols.push(
+                            SymbolDeclaration {
+                                id: &alias,
+                                symbol: Symbol::Flat(FlatEmbed::U128ToBits),
+                            }
+                            .start_end(pos.0, pos.1),
+                        );
+                    }
+                    "EMBED/u64_to_bits" => {
+                        let alias = alias.unwrap_or("u64_to_bits");
+
+                        symbols.push(
+                            SymbolDeclaration {
+                                id: &alias,
+                                symbol: Symbol::Flat(FlatEmbed::U64ToBits),
+                            }
+                            .start_end(pos.0, pos.1),
+                        );
+                    }
                     "EMBED/u32_to_bits" => {
                         let alias = alias.unwrap_or("u32_to_bits");
 
@@ -193,6 +248,28 @@ impl Importer {
                             .start_end(pos.0, pos.1),
                         );
                     }
+                    "EMBED/u128_from_bits" => {
+                        let alias = alias.unwrap_or("u128_from_bits");
+
+                        symbols.push(
+                            SymbolDeclaration {
+                                id: &alias,
+                                symbol: Symbol::Flat(FlatEmbed::U128FromBits),
+                            }
+                            .start_end(pos.0, pos.1),
+                        );
+                    }
+                    "EMBED/u64_from_bits" => {
+                        let alias = alias.unwrap_or("u64_from_bits");
+
+                        symbols.push(
+                            SymbolDeclaration {
+                                id: &alias,
+                                symbol: Symbol::Flat(FlatEmbed::U64FromBits),
+                            }
+                            .start_end(pos.0, pos.1),
+                        );
+                    }
                     "EMBED/u32_from_bits" => {
                         let alias = alias.unwrap_or("u32_from_bits");
 
@@ -226,6 +303,144 @@ impl Importer {
                             .start_end(pos.0, pos.1),
                         );
                     }
+                    "EMBED/u32_to_bytes_be" => {
+                        let alias = alias.unwrap_or("u32_to_bytes_be");
+
+                        symbols.push(
+                            SymbolDeclaration {
+                                id: &alias,
+                                symbol: Symbol::Flat(FlatEmbed::U32ToBytesBe),
+                            }
+                            .start_end(pos.0, pos.1),
+                        );
+                    }
+                    "EMBED/u32_to_bytes_le" => {
+                        let alias = alias.unwrap_or("u32_to_bytes_le");
+
+                        symbols.push(
+                            SymbolDeclaration {
+                                id: &alias,
+                                symbol: Symbol::Flat(FlatEmbed::U32ToBytesLe),
+                            }
+                            .start_end(pos.0, pos.1),
+                        );
+                    }
+                    "EMBED/u32_from_bytes_be" => {
+                        let alias = alias.unwrap_or("u32_from_bytes_be");
+
+                        symbols.push(
+                            SymbolDeclaration {
+                                id: &alias,
+                                symbol: Symbol::Flat(FlatEmbed::U32FromBytesBe),
+                            }
+                            .start_end(pos.0, pos.1),
+                        );
+                    }
+                    "EMBED/u32_from_bytes_le" => {
+                        let alias = alias.unwrap_or("u32_from_bytes_le");
+
+                        symbols.push(
+                            SymbolDeclaration {
+                                id: &alias,
+                                symbol: Symbol::Flat(FlatEmbed::U32FromBytesLe),
+                            }
+                            .start_end(pos.0, pos.1),
+                        );
+                    }
+                    s if DIVMOD_BITWIDTHS
+                        .iter()
+                        .any(|bitwidth| s == format!("EMBED/u{}_divmod", bitwidth)) =>
+                    {
+                        let bitwidth = s["EMBED/u".len()..s.len() - "_divmod".len()]
+                            .parse()
+                            .unwrap();
+
+                        let alias = alias.unwrap_or(&s["EMBED/".len()..]);
+
+                        symbols.push(
+                            SymbolDeclaration {
+                                id: &alias,
+                                symbol: Symbol::Flat(FlatEmbed::DivMod(bitwidth)),
+                            }
+                            .start_end(pos.0, pos.1),
+                        );
+                    }
+                    s if s.starts_with("EMBED/field_divmod/") => {
+                        let bound = match s["EMBED/field_divmod/".len()..].parse::<usize>() {
+                            Ok(bound) => bound,
+                            Err(_) => {
+                                return Err(CompileErrorInner::ImportError(
+                                    Error::new(format!(
+                                        "Invalid field division {}, expected EMBED/field_divmod/<bound>",
+                                        s
+                                    ))
+                                    .with_pos(Some(pos)),
+                                )
+                                .in_file(&location)
+                                .into());
+                            }
+                        };
+
+                        // the product of the quotient and the divisor must not overflow
+                        if !is_field_divmod_bound::<T>(bound) {
+                            return Err(CompileErrorInner::ImportError(
+                                Error::new(format!(
+                                    "Embed {} is not available in the {} field, whose elements have {} bits: the bound must be positive and lower than half of it",
+                                    s,
+                                    T::name(),
+                                    T::get_required_bits()
+                                ))
+                                .with_pos(Some(pos)),
+                            )
+                            .in_file(&location)
+                            .into());
+                        }
+
+                        let alias = alias.unwrap_or("field_divmod");
+
+                        symbols.push(
+                            SymbolDeclaration {
+                                id: &alias,
+                                symbol: Symbol::Flat(FlatEmbed::FieldDivMod(bound)),
+                            }
+                            .start_end(pos.0, pos.1),
+                        );
+                    }
+                    s if s.starts_with("EMBED/oracle/") => {
+                        let counts = s["EMBED/oracle/".len()..]
+                            .split('/')
+                            .map(|count| count.parse::<usize>())
+                            .collect::<Result<Vec<_>, _>>();
+
+                        let (inputs, outputs) = match counts.as_ref().map(|c| c.as_slice()) {
+                            Ok([inputs, outputs]) => (*inputs, *outputs),
+                            _ => {
+                                return Err(CompileErrorInner::ImportError(
+                                    Error::new(format!(
+                                        "Invalid oracle {}, expected EMBED/oracle/<inputs>/<outputs>",
+                                        s
+                                    ))
+                                    .with_pos(Some(pos)),
+                                )
+                                .in_file(&location)
+                                .into());
+                            }
+                        };
+
+                        let alias = alias.unwrap_or("oracle");
+
+                        symbols.push(
+                            SymbolDeclaration {
+                                id: &alias,
+                                symbol: Symbol::Flat(FlatEmbed::Oracle {
+                                    name: alias.to_string(),
+                                    inputs,
+                                    outputs,
+                                }),
+                            }
+                            .start_end(pos.0, pos.1),
+                        );
+                    }
                     s => {
                         return Err(CompileErrorInner::ImportError(
                             Error::new(format!("Embed {} not found", s)).with_pos(Some(pos)),
@@ -239,6 +454,30 @@ impl Importer {
                 match resolver {
                     Some(res) => match res.resolve(location.clone(), import.source.to_path_buf()) {
                         Ok((source, new_location)) => {
+                            let symbol = import.symbol.unwrap_or("main");
+
+                            if let Some(warnings) = warnings.as_mut() {
+                                let mut deprecations = vec![];
+                                if import.symbol.is_none() {
+                                    deprecations.push(Deprecation::Syntax(Syntax::MainImport));
+                                }
+                                if let Some(hint) = deprecation::annotations(&source).remove(symbol)
+                                {
+                                    deprecations.push(Deprecation::Function {
+                                        name: symbol.to_string(),
+                                        module: import.source.display().to_string(),
+                                        hint,
+                                    });
+                                }
+                                warnings.extend(deprecations.into_iter().map(|d| {
+                                    CompileWarning::new(
+                                        CompileWarningInner::Deprecation(d),
+                                        Some(pos),
+                                        &location,
+                                    )
+                                }));
+                            }
+
                             // generate an alias from the imported path if none was given explicitely
                             let alias = import.alias.unwrap_or(
                                 std::path::Path::new(import.source)
@@ -259,11 +498,25 @@ impl Importer {
                                 None => {
                                     let source = arena.alloc(source);
 
+                                    // modules imported with an absolute path are in the standard library, and so
+                                    // are the modules they import
+                                    let relative = match import.source.components().next() {
+                                        Some(Component::CurDir) | Some(Component::ParentDir) => {
+                                            true
+                                        }
+                                        _ => false,
+                                    };
+
                                     let compiled = compile_module(
                                         source,
                                         new_location.clone(),
                                         resolver,
                                         modules,
+                                        if relative {
+                                            warnings.as_mut().map(|w| &mut **w)
+                                        } else {
+                                            None
+                                        },
                                         &arena,
                                     )?;
 
@@ -278,7 +531,7 @@ impl Importer {
                                     id: &alias,
                                     symbol: Symbol::There(
                                         SymbolImport::with_id_in_module(
-                                            import.symbol.unwrap_or("main"),
+                                            symbol,
                                             new_location.display().to_string(),
                                         )
                                         .start_end(pos.0, pos.1),
@@ -0,0 +1,281 @@
+//! Module containing the expansion of calls into the constraints of the functions they call
+//!
+//! Each function is expanded once, then instantiated at each call by shifting its variables: the variables of a
+//! call are laid out after the variables of the caller and of the calls which precede it. The expanded program,
+//! and therefore the witnesses computed for it, only depend on the program.
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::folder::Folder;
+use crate::ir::{Function, Prog, Statement};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use zokrates_field::Field;
+
+impl<T: Field> Prog<T> {
+    /// Replaces each call by the constraints of the function it calls, returning a program without functions
+    pub fn expand(self) -> Prog<T> {
+        if self.functions.is_empty() {
+            return self;
+        }
+
+        let Prog {
+            main,
+            functions,
+            private,
+            private_outputs,
+        } = self;
+
+        let mut expander = Expander {
+            functions: functions.iter().map(|f| (f.id.as_str(), f)).collect(),
+            expanded: HashMap::new(),
+        };
+
+        let (main, _) = expander.expand_function(main);
+
+        Prog {
+            main,
+            functions: vec![],
+            private,
+            private_outputs,
+        }
+    }
+
+    /// Returns this program without functions, borrowing it unless it has calls to expand
+    pub fn expanded(&self) -> Cow<'_, Prog<T>> {
+        match self.functions.is_empty() {
+            true => Cow::Borrowed(self),
+            false => Cow::Owned(self.clone().expand()),
+        }
+    }
+}
+
+struct Expander<'a, T> {
+    /// The functions of the program, by id
+    functions: HashMap<&'a str, &'a Function<T>>,
+    /// The functions which were already expanded, with the number of variables they use
+    expanded: HashMap<String, (Function<T>, usize)>,
+}
+
+impl<'a, T: Field> Expander<'a, T> {
+    /// Expands the calls of a function, returning the expanded function and the number of variables it uses
+    fn expand_function(&mut self, function: Function<T>) -> (Function<T>, usize) {
+        let mut next = variable_count(&function);
+        let mut statements = vec![];
+
+        for statement in function.statements {
+            match statement {
+                Statement::Call(call) => {
+                    if !self.expanded.contains_key(&call.function) {
+                        let callee = self
+                            .functions
+                            .get(call.function.as_str())
+                            .map(|f| (*f).clone())
+                            .unwrap_or_else(|| {
+                                panic!("Function `{}` is called but not defined", call.function)
+                            });
+                        let expanded = self.expand_function(callee);
+                        self.expanded.insert(call.function.clone(), expanded);
+                    }
+
+                    let (callee, width) = &self.expanded[&call.function];
+
+                    let mut instance = Instance {
+                        offset: next,
+                        returns: callee
+                            .returns
+                            .iter()
+                            .cloned()
+                            .zip(call.outputs.iter().cloned())
+                            .collect(),
+                    };
+
+                    // the arguments of the callee are defined as the inputs of the call
+                    for (argument, input) in callee.arguments.iter().zip(call.inputs) {
+                        statements.push(Statement::definition(instance.rename(*argument), input));
+                    }
+
+                    statements.extend(
+                        callee
+                            .statements
+                            .iter()
+                            .cloned()
+                            .flat_map(|s| instance.fold_statement(s)),
+                    );
+
+                    next += width;
+                }
+                s => statements.push(s),
+            }
+        }
+
+        (
+            Function {
+                statements,
+                ..function
+            },
+            next,
+        )
+    }
+}
+
+/// Renames the variables of an expanded function to instantiate it at a call
+struct Instance {
+    /// The index of the first variable of the call
+    offset: usize,
+    /// The outputs of the call, by return of the callee
+    returns: HashMap<FlatVariable, FlatVariable>,
+}
+
+impl Instance {
+    fn rename(&self, v: FlatVariable) -> FlatVariable {
+        match v {
+            v if v == FlatVariable::one() => v,
+            v if v.is_output() => self.returns[&v],
+            v => FlatVariable::new(self.offset + v.id()),
+        }
+    }
+}
+
+impl<T: Field> Folder<T> for Instance {
+    fn fold_variable(&mut self, v: FlatVariable) -> FlatVariable {
+        self.rename(v)
+    }
+}
+
+/// Returns the number of variables of a function, that is one more than the highest index it uses
+fn variable_count<T: Field>(function: &Function<T>) -> usize {
+    let variables = function.statements.iter().flat_map(|s| match s {
+        Statement::Constraint(quad, lin) => quad
+            .left
+            .0
+            .iter()
+            .chain(quad.right.0.iter())
+            .chain(lin.0.iter())
+            .map(|(v, _)| *v)
+            .collect::<Vec<_>>(),
+        Statement::Directive(d) => d
+            .inputs
+            .iter()
+            .flat_map(|i| i.left.0.iter().chain(i.right.0.iter()))
+            .map(|(v, _)| *v)
+            .chain(d.outputs.iter().cloned())
+            .collect(),
+        Statement::Call(c) => c
+            .inputs
+            .iter()
+            .flat_map(|i| i.0.iter())
+            .map(|(v, _)| *v)
+            .chain(c.outputs.iter().cloned())
+            .collect(),
+    });
+
+    function
+        .arguments
+        .iter()
+        .cloned()
+        .chain(variables)
+        .filter(|v| *v != FlatVariable::one() && !v.is_output())
+        .map(|v| v.id() + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Call, LinComb, QuadComb};
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn expand() {
+        // def square(_0) -> (1):
+        //     (1 * _0) * (1 * _0) == 1 * _1
+        //     (1 * _1) * (1 * ~one) == 1 * ~out_0
+        //
+        // def main(_0) -> (1):
+        //     _1 = square(1 * _0)
+        //     _2 = square(1 * _1)
+        //     (1 * _2) * (1 * ~one) == 1 * ~out_0
+
+        let square: Function<Bn128Field> = Function {
+            id: String::from("square"),
+            arguments: vec![FlatVariable::new(0)],
+            statements: vec![
+                Statement::constraint(
+                    QuadComb::from_linear_combinations(
+                        FlatVariable::new(0).into(),
+                        FlatVariable::new(0).into(),
+                    ),
+                    FlatVariable::new(1),
+                ),
+                Statement::definition(FlatVariable::public(0), FlatVariable::new(1)),
+            ],
+            returns: vec![FlatVariable::public(0)],
+        };
+
+        let call = |input: usize, output: usize| {
+            Statement::Call(Call {
+                function: String::from("square"),
+                inputs: vec![LinComb::from(FlatVariable::new(input))],
+                outputs: vec![FlatVariable::new(output)],
+            })
+        };
+
+        let p = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                statements: vec![
+                    call(0, 1),
+                    call(1, 2),
+                    Statement::definition(FlatVariable::public(0), FlatVariable::new(2)),
+                ],
+                returns: vec![FlatVariable::public(0)],
+            },
+            functions: vec![square],
+            private: vec![true],
+            private_outputs: vec![],
+        };
+
+        // each call counts the constraints of `square` and the one binding its argument
+        assert_eq!(p.constraint_count(), 7);
+
+        // `main` uses 3 variables, so the calls use `_3, _4` and `_5, _6`
+        let expected = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                statements: vec![
+                    Statement::definition(FlatVariable::new(3), FlatVariable::new(0)),
+                    Statement::constraint(
+                        QuadComb::from_linear_combinations(
+                            FlatVariable::new(3).into(),
+                            FlatVariable::new(3).into(),
+                        ),
+                        FlatVariable::new(4),
+                    ),
+                    Statement::definition(FlatVariable::new(1), FlatVariable::new(4)),
+                    Statement::definition(FlatVariable::new(5), FlatVariable::new(1)),
+                    Statement::constraint(
+                        QuadComb::from_linear_combinations(
+                            FlatVariable::new(5).into(),
+                            FlatVariable::new(5).into(),
+                        ),
+                        FlatVariable::new(6),
+                    ),
+                    Statement::definition(FlatVariable::new(2), FlatVariable::new(6)),
+                    Statement::definition(FlatVariable::public(0), FlatVariable::new(2)),
+                ],
+                returns: vec![FlatVariable::public(0)],
+            },
+            functions: vec![],
+            private: vec![true],
+            private_outputs: vec![],
+        };
+
+        let expanded = p.expand();
+
+        assert_eq!(expanded, expected);
+        assert_eq!(expanded.constraint_count(), 7);
+    }
+}
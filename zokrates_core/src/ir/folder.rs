@@ -36,10 +36,20 @@ pub trait Folder<T: Field>: Sized {
     fn fold_directive(&mut self, d: Directive<T>) -> Directive<T> {
         fold_directive(self, d)
     }
+
+    fn fold_call(&mut self, c: Call<T>) -> Call<T> {
+        fold_call(self, c)
+    }
 }
 
 pub fn fold_module<T: Field, F: Folder<T>>(f: &mut F, p: Prog<T>) -> Prog<T> {
+    // `main` is folded last, so that the state of the folder is the one of `main` when folding is done
     Prog {
+        functions: p
+            .functions
+            .into_iter()
+            .map(|fun| f.fold_function(fun))
+            .collect(),
         main: f.fold_function(p.main),
         ..p
     }
@@ -52,6 +62,7 @@ pub fn fold_statement<T: Field, F: Folder<T>>(f: &mut F, s: Statement<T>) -> Vec
             f.fold_linear_combination(lin),
         )],
         Statement::Directive(dir) => vec![Statement::Directive(f.fold_directive(dir))],
+        Statement::Call(call) => vec![Statement::Call(f.fold_call(call))],
     }
 }
 
@@ -85,6 +96,18 @@ pub fn fold_directive<T: Field, F: Folder<T>>(f: &mut F, ds: Directive<T>) -> Di
     }
 }
 
+pub fn fold_call<T: Field, F: Folder<T>>(f: &mut F, c: Call<T>) -> Call<T> {
+    Call {
+        inputs: c
+            .inputs
+            .into_iter()
+            .map(|i| f.fold_linear_combination(i))
+            .collect(),
+        outputs: c.outputs.into_iter().map(|o| f.fold_variable(o)).collect(),
+        ..c
+    }
+}
+
 pub fn fold_function<T: Field, F: Folder<T>>(f: &mut F, fun: Function<T>) -> Function<T> {
     Function {
         arguments: fun
@@ -1,7 +1,7 @@
 use crate::flat_absy::{
-    FlatDirective, FlatExpression, FlatFunction, FlatProg, FlatStatement, FlatVariable,
+    FlatCall, FlatDirective, FlatExpression, FlatFunction, FlatProg, FlatStatement, FlatVariable,
 };
-use crate::ir::{Directive, Function, LinComb, Prog, QuadComb, Statement};
+use crate::ir::{Call, Directive, Function, LinComb, Prog, QuadComb, Statement};
 use zokrates_field::Field;
 
 impl<T: Field> From<FlatFunction<T>> for Function<T> {
@@ -71,7 +71,21 @@ impl<T: Field> From<FlatProg<T>> for Prog<T> {
 
         let main = main.into();
 
-        Prog { private, main }
+        let functions = flat_prog
+            .functions
+            .into_iter()
+            .map(|(id, function)| Function {
+                id,
+                ..Function::from(function)
+            })
+            .collect();
+
+        Prog {
+            private,
+            main,
+            functions,
+            private_outputs: vec![],
+        }
     }
 }
 
@@ -114,6 +128,7 @@ impl<T: Field> From<FlatStatement<T>> for Statement<T> {
                 e => Statement::Constraint(LinComb::from(e).into(), var.into()),
             },
             FlatStatement::Directive(ds) => Statement::Directive(ds.into()),
+            FlatStatement::Call(c) => Statement::Call(c.into()),
             _ => panic!("return should be handled at the function level"),
         }
     }
@@ -133,6 +148,16 @@ impl<T: Field> From<FlatDirective<T>> for Directive<T> {
     }
 }
 
+impl<T: Field> From<FlatCall<T>> for Call<T> {
+    fn from(c: FlatCall<T>) -> Call<T> {
+        Call {
+            function: c.function,
+            inputs: c.inputs.into_iter().map(|i| i.into()).collect(),
+            outputs: c.outputs,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
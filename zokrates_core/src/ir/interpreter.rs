@@ -1,8 +1,10 @@
 use crate::flat_absy::flat_variable::FlatVariable;
-use crate::ir::{LinComb, Prog, QuadComb, Statement, Witness};
+use crate::ir::{Function, LinComb, PartialWitness, Prog, QuadComb, Statement, Witness};
 use ir::Directive;
+use num_bigint::BigUint;
 use solvers::Solver;
-use std::collections::BTreeMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use zokrates_field::Field;
 
@@ -10,16 +12,22 @@ pub type ExecutionResult<T> = Result<Witness<T>, Error>;
 
 impl<T: Field> Prog<T> {}
 
+/// A function returning the outputs of an oracle given its inputs, field elements being passed as integers
+pub type Oracle = Box<dyn Fn(&[BigUint]) -> Result<Vec<BigUint>, String>>;
+
 pub struct Interpreter {
     /// Whether we should try to give out-of-range bit decompositions when the input is not a single summand.
     /// Used to do targetted testing of `<` flattening, making sure the bit decomposition we base the result on is unique.
     should_try_out_of_range: bool,
+    /// The functions called to execute the oracles of the program, by name
+    oracles: HashMap<String, Oracle>,
 }
 
 impl Default for Interpreter {
     fn default() -> Interpreter {
         Interpreter {
             should_try_out_of_range: false,
+            oracles: HashMap::new(),
         }
     }
 }
@@ -28,30 +36,414 @@ impl Interpreter {
     pub fn try_out_of_range() -> Interpreter {
         Interpreter {
             should_try_out_of_range: true,
+            ..Interpreter::default()
         }
     }
+
+    /// Registers the function called when executing the oracle `name`. Its outputs are not checked by the
+    /// interpreter beyond their count: the program is expected to constrain them.
+    pub fn oracle<F: Fn(&[BigUint]) -> Result<Vec<BigUint>, String> + 'static>(
+        mut self,
+        name: &str,
+        f: F,
+    ) -> Self {
+        self.oracles.insert(name.to_string(), Box::new(f));
+        self
+    }
 }
 
 impl Interpreter {
     pub fn execute<T: Field>(&self, program: &Prog<T>, inputs: &Vec<T>) -> ExecutionResult<T> {
-        let main = &program.main;
+        self.execute_traced(program, inputs, None)
+    }
+
+    /// Executes a program like `execute`, recording the statements it executes and the values they operate on in
+    /// `trace`. If execution fails, the trace ends with the error and the statement which caused it.
+    pub fn execute_with_trace<T: Field>(
+        &self,
+        program: &Prog<T>,
+        inputs: &Vec<T>,
+        trace: &mut Trace,
+    ) -> ExecutionResult<T> {
+        self.execute_traced(program, inputs, Some(trace))
+    }
+
+    fn execute_traced<T: Field>(
+        &self,
+        program: &Prog<T>,
+        inputs: &Vec<T>,
+        trace: Option<&mut Trace>,
+    ) -> ExecutionResult<T> {
         self.check_inputs(&program, &inputs)?;
+        let program = Self::expand(program);
+        let main = &program.main;
         let mut witness = BTreeMap::new();
         witness.insert(FlatVariable::one(), T::one());
         for (arg, value) in main.arguments.iter().zip(inputs.iter()) {
             witness.insert(arg.clone(), value.clone().into());
         }
 
-        for statement in main.statements.iter() {
+        // statements are only executed in order when they are traced
+        let trace = match trace {
+            Some(trace) => trace,
+            None => {
+                self.execute_batched(main, &mut witness)?;
+                return Ok(Witness(witness));
+            }
+        };
+
+        for (index, statement) in main.statements.iter().enumerate() {
+            let executed = match self.try_execute_statement(statement, &mut witness) {
+                Ok(executed) => executed,
+                Err(e) => {
+                    trace.error = Some(TraceError {
+                        index,
+                        statement: statement.to_string(),
+                        message: e.to_string(),
+                    });
+                    return Err(e);
+                }
+            };
+            assert!(executed, "Statement `{}` uses unknown variables", statement);
+
+            trace.record(index, statement, &witness);
+        }
+
+        Ok(Witness(witness))
+    }
+
+    /// Executes the statements of a function, inverting the divisors of its `Div` and `ConditionEq` directives in
+    /// batches. Statements are executed level by level, the level of a statement being the number of inversions
+    /// its inputs depend on in sequence, so they may not run in order.
+    fn execute_batched<T: Field>(
+        &self,
+        function: &Function<T>,
+        witness: &mut BTreeMap<FlatVariable, T>,
+    ) -> Result<(), Error> {
+        let levels = Self::inversion_levels(function, witness);
+
+        // the sort is stable, so statements of the same level run in order
+        let mut order: Vec<usize> = (0..function.statements.len()).collect();
+        order.sort_by_key(|index| levels[*index]);
+
+        let mut level = 0;
+        let mut inversions = vec![];
+
+        for index in order {
+            if levels[index] != level {
+                self.execute_inversions(&inversions, witness)?;
+                inversions.clear();
+                level = levels[index];
+            }
+
+            let statement = &function.statements[index];
+
             match statement {
-                Statement::Constraint(quad, lin) => match lin.is_assignee(&witness) {
+                Statement::Directive(d) if Self::is_inversion(&d.solver) => {
+                    let inputs = d
+                        .inputs
+                        .iter()
+                        .map(|i| i.evaluate(&witness))
+                        .collect::<Result<Vec<_>, _>>()
+                        .unwrap_or_else(|_| {
+                            panic!("Statement `{}` uses unknown variables", statement)
+                        });
+                    inversions.push((d, inputs));
+                }
+                _ => {
+                    let executed = self.try_execute_statement(statement, witness)?;
+                    assert!(executed, "Statement `{}` uses unknown variables", statement);
+                }
+            }
+        }
+
+        self.execute_inversions(&inversions, witness)
+    }
+
+    fn is_inversion(solver: &Solver) -> bool {
+        match solver {
+            Solver::Div | Solver::ConditionEq => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the level of each statement of a function, given its known variables
+    fn inversion_levels<T: Field>(
+        function: &Function<T>,
+        known: &BTreeMap<FlatVariable, T>,
+    ) -> Vec<usize> {
+        let mut levels: BTreeMap<FlatVariable, usize> = known.keys().map(|v| (*v, 0)).collect();
+
+        let level = |levels: &BTreeMap<FlatVariable, usize>, l: &LinComb<T>| {
+            l.0.iter()
+                .filter_map(|(variable, _)| levels.get(variable))
+                .max()
+                .cloned()
+                .unwrap_or(0)
+        };
+
+        function
+            .statements
+            .iter()
+            .map(|statement| match statement {
+                Statement::Constraint(quad, lin) => {
+                    let quad_level =
+                        std::cmp::max(level(&levels, &quad.left), level(&levels, &quad.right));
+
+                    match lin.is_assignee(&levels) {
+                        true => {
+                            levels.insert(lin.0.iter().next().unwrap().0, quad_level);
+                            quad_level
+                        }
+                        false => std::cmp::max(quad_level, level(&levels, lin)),
+                    }
+                }
+                Statement::Directive(d) => {
+                    // outputs which were already assigned are overwritten after they are read
+                    let directive_level = d
+                        .inputs
+                        .iter()
+                        .flat_map(|i| vec![level(&levels, &i.left), level(&levels, &i.right)])
+                        .chain(d.outputs.iter().filter_map(|o| levels.get(o).cloned()))
+                        .max()
+                        .unwrap_or(0);
+
+                    let output_level = match Self::is_inversion(&d.solver) {
+                        true => directive_level + 1,
+                        false => directive_level,
+                    };
+
+                    for output in &d.outputs {
+                        levels.insert(*output, output_level);
+                    }
+
+                    directive_level
+                }
+                Statement::Call(..) => unreachable!("calls should be expanded before execution"),
+            })
+            .collect()
+    }
+
+    /// Executes `Div` and `ConditionEq` directives whose inputs are known, inverting their non-zero divisors at once
+    /// with Montgomery's trick, which costs a single inversion and three multiplications per divisor
+    fn execute_inversions<T: Field>(
+        &self,
+        inversions: &[(&Directive<T>, Vec<T>)],
+        witness: &mut BTreeMap<FlatVariable, T>,
+    ) -> Result<(), Error> {
+        let divisors: Vec<T> = inversions
+            .iter()
+            .map(|(d, inputs)| match d.solver {
+                Solver::Div => inputs[1].clone(),
+                _ => inputs[0].clone(),
+            })
+            .collect();
+
+        // `products[i]` is the product of the non-zero divisors up to `i`
+        let mut products = Vec::with_capacity(divisors.len());
+        let mut product = T::one();
+        for divisor in divisors.iter().filter(|d| !d.is_zero()) {
+            product = product * divisor;
+            products.push(product.clone());
+        }
+
+        // going backwards, `inverse` is the inverse of the product of the non-zero divisors up to `i`
+        let mut inverse = product.inverse_mul();
+        let mut inverses = vec![None; divisors.len()];
+        let mut remaining = products.len();
+        for (i, divisor) in divisors.iter().enumerate().rev() {
+            if divisor.is_zero() {
+                continue;
+            }
+            remaining -= 1;
+            let previous = match remaining {
+                0 => T::one(),
+                _ => products[remaining - 1].clone(),
+            };
+            inverses[i] = Some(inverse.clone() * previous);
+            inverse = inverse * divisor;
+        }
+
+        for ((d, inputs), inverse) in inversions.iter().zip(inverses) {
+            let outputs = match (&d.solver, inverse) {
+                (Solver::Div, Some(inverse)) => vec![inputs[0].clone() * inverse],
+                (Solver::ConditionEq, Some(inverse)) => vec![T::one(), inverse],
+                // zero divisors are left to the solver
+                _ => self
+                    .execute_solver(&d.solver, inputs)
+                    .map_err(|_| Error::Solver)?,
+            };
+
+            for (o, value) in d.outputs.iter().zip(outputs) {
+                witness.insert(*o, value);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Executes the statements of a program which only depend on its public inputs, leaving the others pending
+    /// until the private inputs are passed to `resume`
+    pub fn execute_partial<T: Field>(
+        &self,
+        program: &Prog<T>,
+        public_inputs: &Vec<T>,
+    ) -> Result<PartialWitness<T>, Error> {
+        let public_arguments = Self::arguments(program, false);
+
+        if public_arguments.len() != public_inputs.len() {
+            return Err(Error::WrongInputCount {
+                expected: public_arguments.len(),
+                received: public_inputs.len(),
+            });
+        }
+
+        let mut public_inputs = public_inputs.iter();
+
+        let inputs = program
+            .private
+            .iter()
+            .map(|private| match private {
+                true => None,
+                false => public_inputs.next().cloned(),
+            })
+            .collect();
+
+        self.execute_fragment(program, &inputs)
+    }
+
+    /// Executes the statements of a program which only depend on the known inputs, `None` standing for the
+    /// inputs held by other parties. Fragments computed by each party are then merged and passed to `complete`
+    pub fn execute_fragment<T: Field>(
+        &self,
+        program: &Prog<T>,
+        inputs: &Vec<Option<T>>,
+    ) -> Result<PartialWitness<T>, Error> {
+        self.check_inputs(&program, &inputs)?;
+
+        let program = Self::expand(program);
+
+        let mut witness = BTreeMap::new();
+        witness.insert(FlatVariable::one(), T::one());
+        for (arg, value) in program.main.arguments.iter().zip(inputs.iter()) {
+            if let Some(value) = value {
+                witness.insert(*arg, value.clone());
+            }
+        }
+
+        let mut pending = vec![];
+
+        for (index, statement) in program.main.statements.iter().enumerate() {
+            if !self.try_execute_statement(statement, &mut witness)? {
+                pending.push(index);
+            }
+        }
+
+        Ok(PartialWitness {
+            values: witness,
+            pending,
+        })
+    }
+
+    /// Completes a witness computation started with `execute_partial`, given the private inputs of the program
+    pub fn resume<T: Field>(
+        &self,
+        program: &Prog<T>,
+        mut partial: PartialWitness<T>,
+        private_inputs: &Vec<T>,
+    ) -> ExecutionResult<T> {
+        let private_arguments = Self::arguments(program, true);
+
+        if private_arguments.len() != private_inputs.len() {
+            return Err(Error::WrongInputCount {
+                expected: private_arguments.len(),
+                received: private_inputs.len(),
+            });
+        }
+
+        for (arg, value) in private_arguments.into_iter().zip(private_inputs.iter()) {
+            partial.values.insert(arg, value.clone());
+        }
+
+        self.complete(program, partial)
+    }
+
+    /// Completes a partial witness in which all inputs of the program are known
+    pub fn complete<T: Field>(
+        &self,
+        program: &Prog<T>,
+        partial: PartialWitness<T>,
+    ) -> ExecutionResult<T> {
+        let program = Self::expand(program);
+
+        if !program
+            .main
+            .arguments
+            .iter()
+            .all(|arg| partial.values.contains_key(arg))
+        {
+            return Err(Error::InvalidPartialWitness);
+        }
+
+        let mut witness = partial.values;
+
+        for index in partial.pending {
+            let statement = program
+                .main
+                .statements
+                .get(index)
+                .ok_or(Error::InvalidPartialWitness)?;
+
+            if !self.try_execute_statement(statement, &mut witness)? {
+                return Err(Error::InvalidPartialWitness);
+            }
+        }
+
+        Ok(Witness(witness))
+    }
+
+    /// Returns the program with its calls expanded, as witnesses are computed for the expanded program
+    fn expand<T: Field>(program: &Prog<T>) -> Cow<Prog<T>> {
+        match program.functions.is_empty() {
+            true => Cow::Borrowed(program),
+            false => Cow::Owned(program.clone().expand()),
+        }
+    }
+
+    /// Returns the public or private arguments of the program
+    fn arguments<T: Field>(program: &Prog<T>, private: bool) -> Vec<FlatVariable> {
+        program
+            .main
+            .arguments
+            .iter()
+            .zip(program.private.iter())
+            .filter(|(_, p)| **p == private)
+            .map(|(arg, _)| *arg)
+            .collect()
+    }
+
+    /// Executes a statement, returning `false` without updating the witness if some of its inputs are unknown
+    fn try_execute_statement<T: Field>(
+        &self,
+        statement: &Statement<T>,
+        witness: &mut BTreeMap<FlatVariable, T>,
+    ) -> Result<bool, Error> {
+        match statement {
+            Statement::Constraint(quad, lin) => {
+                let lhs_value = match quad.evaluate(&witness) {
+                    Ok(value) => value,
+                    Err(()) => return Ok(false),
+                };
+
+                match lin.is_assignee(&witness) {
                     true => {
-                        let val = quad.evaluate(&witness).unwrap();
-                        witness.insert(lin.0.iter().next().unwrap().0.clone(), val);
+                        witness.insert(lin.0.iter().next().unwrap().0.clone(), lhs_value);
                     }
                     false => {
-                        let lhs_value = quad.evaluate(&witness).unwrap();
-                        let rhs_value = lin.evaluate(&witness).unwrap();
+                        let rhs_value = match lin.evaluate(&witness) {
+                            Ok(value) => value,
+                            Err(()) => return Ok(false),
+                        };
                         if lhs_value != rhs_value {
                             return Err(Error::UnsatisfiedConstraint {
                                 left: lhs_value.to_dec_string(),
@@ -59,38 +451,46 @@ impl Interpreter {
                             });
                         }
                     }
-                },
-                Statement::Directive(ref d) => {
-                    match (&d.solver, &d.inputs, self.should_try_out_of_range) {
-                        (Solver::Bits(bitwidth), inputs, true)
-                            if inputs[0].left.0.len() > 1
-                                || inputs[0].right.0.len() > 1
-                                    && *bitwidth == T::get_required_bits() =>
-                        {
-                            Self::try_solve_out_of_range(&d, &mut witness)
+                }
+            }
+            Statement::Directive(ref d) => {
+                let inputs = match d
+                    .inputs
+                    .iter()
+                    .map(|i| i.evaluate(&witness))
+                    .collect::<Result<Vec<_>, _>>()
+                {
+                    Ok(inputs) => inputs,
+                    Err(()) => return Ok(false),
+                };
+
+                match (&d.solver, &d.inputs, self.should_try_out_of_range) {
+                    (Solver::Bits(bitwidth), inputs, true)
+                        if inputs[0].left.0.len() > 1
+                            || inputs[0].right.0.len() > 1
+                                && *bitwidth == T::get_required_bits() =>
+                    {
+                        Self::try_solve_out_of_range(&d, witness)
+                    }
+                    _ => match self.execute_solver(&d.solver, &inputs) {
+                        Ok(res) => {
+                            for (i, o) in d.outputs.iter().enumerate() {
+                                witness.insert(o.clone(), res[i].clone());
+                            }
                         }
-                        _ => {
-                            let inputs: Vec<_> = d
-                                .inputs
-                                .iter()
-                                .map(|i| i.evaluate(&witness).unwrap())
-                                .collect();
-                            match self.execute_solver(&d.solver, &inputs) {
-                                Ok(res) => {
-                                    for (i, o) in d.outputs.iter().enumerate() {
-                                        witness.insert(o.clone(), res[i].clone());
-                                    }
-                                    continue;
-                                }
-                                Err(_) => return Err(Error::Solver),
-                            };
+                        Err(e) => {
+                            return Err(match d.solver {
+                                Solver::Oracle(..) => Error::Oracle(e),
+                                _ => Error::Solver,
+                            })
                         }
-                    }
+                    },
                 }
             }
+            Statement::Call(..) => unreachable!("calls should be expanded before execution"),
         }
 
-        Ok(Witness(witness))
+        Ok(true)
     }
 
     fn try_solve_out_of_range<T: Field>(d: &Directive<T>, witness: &mut BTreeMap<FlatVariable, T>) {
@@ -155,7 +555,13 @@ impl Interpreter {
                         res.push(T::zero());
                     }
                 }
-                assert_eq!(num, T::zero());
+                if num != T::zero() {
+                    return Err(format!(
+                        "{} does not fit in {} bits",
+                        inputs[0].to_dec_string(),
+                        bit_width
+                    ));
+                }
                 res
             }
             Solver::Xor => {
@@ -185,6 +591,36 @@ impl Interpreter {
                 vec![a * (b - c.clone()) + c]
             }
             Solver::Div => vec![inputs[0].clone() / inputs[1].clone()],
+            Solver::EuclideanDiv => {
+                if inputs[1].is_zero() {
+                    return Err(String::from("Division by zero"));
+                }
+
+                let dividend = inputs[0].to_biguint();
+                let divisor = inputs[1].to_biguint();
+
+                vec![T::from(&dividend / &divisor), T::from(&dividend % &divisor)]
+            }
+            Solver::Oracle(name, _, _) => {
+                let oracle = self
+                    .oracles
+                    .get(name)
+                    .ok_or_else(|| format!("Oracle `{}` is not defined", name))?;
+
+                let res = oracle(&inputs.iter().map(|i| i.to_biguint()).collect::<Vec<_>>())
+                    .map_err(|e| format!("Oracle `{}` failed: {}", name, e))?;
+
+                if res.len() != expected_output_count {
+                    return Err(format!(
+                        "Oracle `{}` returned {} values, expected {}",
+                        name,
+                        res.len(),
+                        expected_output_count
+                    ));
+                }
+
+                res.into_iter().map(T::from).collect()
+            }
         };
 
         assert_eq!(res.len(), expected_output_count);
@@ -217,11 +653,104 @@ impl<T: Field> QuadComb<T> {
     }
 }
 
+/// A record of the statements executed by the interpreter, for auditors to check how a witness was formed. Values
+/// are decimal strings, and statements are in the human-readable format of `.ztf` files. Statements are indexed in
+/// `main` once its calls are expanded.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct Trace {
+    /// Only the statements whose index is a multiple of `sampling` are recorded
+    pub sampling: usize,
+    pub steps: Vec<Step>,
+    pub error: Option<TraceError>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Step {
+    /// A constraint `left * right == output`, which assigns `output` if it was unknown
+    Constraint {
+        index: usize,
+        statement: String,
+        left: String,
+        right: String,
+        output: String,
+    },
+    /// A directive, with the values of its inputs and of the outputs computed by its solver
+    Directive {
+        index: usize,
+        statement: String,
+        inputs: Vec<String>,
+        outputs: Vec<String>,
+    },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct TraceError {
+    pub index: usize,
+    pub statement: String,
+    pub message: String,
+}
+
+impl Trace {
+    /// Creates an empty trace recording one statement out of `sampling`
+    pub fn new(sampling: usize) -> Self {
+        assert!(sampling > 0, "Trace sampling must be positive");
+
+        Trace {
+            sampling,
+            steps: vec![],
+            error: None,
+        }
+    }
+
+    fn record<T: Field>(
+        &mut self,
+        index: usize,
+        statement: &Statement<T>,
+        witness: &BTreeMap<FlatVariable, T>,
+    ) {
+        if index % self.sampling != 0 {
+            return;
+        }
+
+        // the statement was executed, so all its variables are known
+        let value = |l: &LinComb<T>| l.evaluate(witness).unwrap().to_dec_string();
+
+        self.steps.push(match statement {
+            Statement::Constraint(quad, lin) => Step::Constraint {
+                index,
+                statement: statement.to_string(),
+                left: value(&quad.left),
+                right: value(&quad.right),
+                output: value(lin),
+            },
+            Statement::Directive(d) => Step::Directive {
+                index,
+                statement: statement.to_string(),
+                inputs: d
+                    .inputs
+                    .iter()
+                    .map(|i| i.evaluate(witness).unwrap().to_dec_string())
+                    .collect(),
+                outputs: d
+                    .outputs
+                    .iter()
+                    .map(|o| witness.get(o).unwrap().to_dec_string())
+                    .collect(),
+            },
+            Statement::Call(..) => unreachable!("calls should be expanded before execution"),
+        });
+    }
+}
+
 #[derive(PartialEq, Serialize, Deserialize, Clone)]
 pub enum Error {
     UnsatisfiedConstraint { left: String, right: String },
     Solver,
     WrongInputCount { expected: usize, received: usize },
+    InvalidPartialWitness,
+    InconsistentFragments(String),
+    Oracle(String),
 }
 
 impl fmt::Display for Error {
@@ -240,6 +769,16 @@ impl fmt::Display for Error {
                 received,
                 if received == 1 { "" } else { "s" }
             ),
+            Error::InvalidPartialWitness => {
+                write!(
+                    f,
+                    "Partial witness is incomplete or does not match the program"
+                )
+            }
+            Error::InconsistentFragments(ref variable) => {
+                write!(f, "Witness fragments disagree on the value of {}", variable)
+            }
+            Error::Oracle(ref e) => write!(f, "{}", e),
         }
     }
 }
@@ -294,6 +833,361 @@ mod tests {
         }
     }
 
+    #[test]
+    fn partial_execution() {
+        use crate::ir::Function;
+
+        // def main(x, private y):
+        //    a = x * x
+        //    b = a * y
+        //    return b
+        let x = FlatVariable::new(0);
+        let y = FlatVariable::new(1);
+        let a = FlatVariable::new(2);
+        let b = FlatVariable::new(3);
+
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: "main".to_string(),
+                arguments: vec![x, y],
+                statements: vec![
+                    Statement::definition(
+                        a,
+                        QuadComb::from_linear_combinations(x.into(), x.into()),
+                    ),
+                    Statement::definition(
+                        b,
+                        QuadComb::from_linear_combinations(a.into(), y.into()),
+                    ),
+                    Statement::definition(FlatVariable::public(0), b),
+                ],
+                returns: vec![FlatVariable::public(0)],
+            },
+            functions: vec![],
+            private: vec![false, true],
+            private_outputs: vec![],
+        };
+
+        let interpreter = Interpreter::default();
+
+        let partial = interpreter
+            .execute_partial(&program, &vec![Bn128Field::from(3)])
+            .unwrap();
+
+        assert_eq!(partial.values.get(&a), Some(&Bn128Field::from(9)));
+        assert_eq!(partial.pending, vec![1, 2]);
+
+        let witness = interpreter
+            .resume(&program, partial, &vec![Bn128Field::from(2)])
+            .unwrap();
+
+        assert_eq!(
+            witness,
+            interpreter
+                .execute(&program, &vec![Bn128Field::from(3), Bn128Field::from(2)])
+                .unwrap()
+        );
+        assert_eq!(witness.return_values(), vec![Bn128Field::from(18)]);
+    }
+
+    #[test]
+    fn trace() {
+        use crate::ir::Function;
+
+        // def main(x):
+        //    a = x * x
+        //    a == 4
+        //    return a
+        let x = FlatVariable::new(0);
+        let a = FlatVariable::new(1);
+
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: "main".to_string(),
+                arguments: vec![x],
+                statements: vec![
+                    Statement::definition(
+                        a,
+                        QuadComb::from_linear_combinations(x.into(), x.into()),
+                    ),
+                    Statement::constraint(a, LinComb::summand(4, FlatVariable::one())),
+                    Statement::definition(FlatVariable::public(0), a),
+                ],
+                returns: vec![FlatVariable::public(0)],
+            },
+            functions: vec![],
+            private: vec![false],
+            private_outputs: vec![],
+        };
+
+        let interpreter = Interpreter::default();
+
+        let mut trace = Trace::new(2);
+        interpreter
+            .execute_with_trace(&program, &vec![Bn128Field::from(2)], &mut trace)
+            .unwrap();
+
+        assert_eq!(
+            trace.steps,
+            vec![
+                Step::Constraint {
+                    index: 0,
+                    statement: program.main.statements[0].to_string(),
+                    left: String::from("2"),
+                    right: String::from("2"),
+                    output: String::from("4"),
+                },
+                Step::Constraint {
+                    index: 2,
+                    statement: program.main.statements[2].to_string(),
+                    left: String::from("1"),
+                    right: String::from("4"),
+                    output: String::from("4"),
+                }
+            ]
+        );
+        assert_eq!(trace.error, None);
+
+        let mut trace = Trace::new(1);
+        assert!(interpreter
+            .execute_with_trace(&program, &vec![Bn128Field::from(3)], &mut trace)
+            .is_err());
+
+        assert_eq!(trace.steps.len(), 1);
+        assert_eq!(
+            trace.error,
+            Some(TraceError {
+                index: 1,
+                statement: program.main.statements[1].to_string(),
+                message: String::from("Expected 9 to equal 4"),
+            })
+        );
+    }
+
+    #[test]
+    fn batched_inversions() {
+        use crate::ir::Function;
+
+        // def main(x, y):
+        //    # a = 1 / x
+        //    # b = 1 / y
+        //    # e, m = ConditionEq(0)
+        //    # d = a / b
+        //    d * x == y
+        //    return d
+        let (x, y, a, b, e, m, d) = (
+            FlatVariable::new(0),
+            FlatVariable::new(1),
+            FlatVariable::new(2),
+            FlatVariable::new(3),
+            FlatVariable::new(4),
+            FlatVariable::new(5),
+            FlatVariable::new(6),
+        );
+
+        let directive = |inputs: Vec<QuadComb<Bn128Field>>, outputs, solver| {
+            Statement::Directive(Directive {
+                inputs,
+                outputs,
+                solver,
+            })
+        };
+
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: "main".to_string(),
+                arguments: vec![x, y],
+                statements: vec![
+                    directive(
+                        vec![FlatVariable::one().into(), x.into()],
+                        vec![a],
+                        Solver::Div,
+                    ),
+                    directive(
+                        vec![FlatVariable::one().into(), y.into()],
+                        vec![b],
+                        Solver::Div,
+                    ),
+                    directive(
+                        vec![QuadComb::from_linear_combinations(
+                            LinComb::zero(),
+                            LinComb::zero(),
+                        )],
+                        vec![e, m],
+                        Solver::ConditionEq,
+                    ),
+                    directive(vec![a.into(), b.into()], vec![d], Solver::Div),
+                    Statement::constraint(
+                        QuadComb::from_linear_combinations(d.into(), x.into()),
+                        y,
+                    ),
+                    Statement::definition(FlatVariable::public(0), d),
+                ],
+                returns: vec![FlatVariable::public(0)],
+            },
+            functions: vec![],
+            private: vec![false, false],
+            private_outputs: vec![],
+        };
+
+        let known = vec![FlatVariable::one(), x, y]
+            .into_iter()
+            .map(|v| (v, Bn128Field::from(1)))
+            .collect();
+        assert_eq!(
+            Interpreter::inversion_levels(&program.main, &known),
+            vec![0, 0, 0, 1, 2, 2]
+        );
+
+        let interpreter = Interpreter::default();
+        let inputs = vec![Bn128Field::from(3), Bn128Field::from(7)];
+
+        let batched = interpreter.execute(&program, &inputs).unwrap();
+        let sequential = interpreter
+            .execute_with_trace(&program, &inputs, &mut Trace::new(1))
+            .unwrap();
+
+        assert_eq!(batched, sequential);
+        assert_eq!(
+            batched.return_values(),
+            vec![Bn128Field::from(7) / Bn128Field::from(3)]
+        );
+        assert_eq!(batched.0[&m], Bn128Field::from(1));
+    }
+
+    #[test]
+    fn multi_party_execution() {
+        use crate::ir::Function;
+
+        // def main(private x, private y):
+        //    a = x * x
+        //    b = a * y
+        //    return b
+        let x = FlatVariable::new(0);
+        let y = FlatVariable::new(1);
+        let a = FlatVariable::new(2);
+        let b = FlatVariable::new(3);
+
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: "main".to_string(),
+                arguments: vec![x, y],
+                statements: vec![
+                    Statement::definition(
+                        a,
+                        QuadComb::from_linear_combinations(x.into(), x.into()),
+                    ),
+                    Statement::definition(
+                        b,
+                        QuadComb::from_linear_combinations(a.into(), y.into()),
+                    ),
+                    Statement::definition(FlatVariable::public(0), b),
+                ],
+                returns: vec![FlatVariable::public(0)],
+            },
+            functions: vec![],
+            private: vec![true, true],
+            private_outputs: vec![],
+        };
+
+        let interpreter = Interpreter::default();
+
+        let first = interpreter
+            .execute_fragment(&program, &vec![Some(Bn128Field::from(3)), None])
+            .unwrap();
+        let second = interpreter
+            .execute_fragment(&program, &vec![None, Some(Bn128Field::from(2))])
+            .unwrap();
+
+        assert_eq!(first.pending, vec![1, 2]);
+        assert_eq!(second.pending, vec![0, 1, 2]);
+
+        // fragments cannot be completed on their own
+        assert_eq!(
+            interpreter.complete(&program, first.clone()),
+            Err(Error::InvalidPartialWitness)
+        );
+
+        let witness = interpreter
+            .complete(&program, first.clone().merge(second).unwrap())
+            .unwrap();
+
+        assert_eq!(witness.return_values(), vec![Bn128Field::from(18)]);
+
+        // fragments must agree on the values they share
+        let conflicting = interpreter
+            .execute_fragment(&program, &vec![Some(Bn128Field::from(4)), None])
+            .unwrap();
+
+        assert_eq!(
+            first.merge(conflicting),
+            Err(Error::InconsistentFragments(x.to_string()))
+        );
+    }
+
+    #[test]
+    fn calls() {
+        use crate::ir::{Call, Function};
+
+        // def square(x):
+        //    return x * x
+        //
+        // def main(x):
+        //    return square(square(x))
+        let x = FlatVariable::new(0);
+        let a = FlatVariable::new(1);
+        let b = FlatVariable::new(2);
+
+        let square = Function {
+            id: "square".to_string(),
+            arguments: vec![x],
+            statements: vec![Statement::definition(
+                FlatVariable::public(0),
+                QuadComb::from_linear_combinations(x.into(), x.into()),
+            )],
+            returns: vec![FlatVariable::public(0)],
+        };
+
+        let call = |input: FlatVariable, output: FlatVariable| {
+            Statement::Call(Call {
+                function: "square".to_string(),
+                inputs: vec![input.into()],
+                outputs: vec![output],
+            })
+        };
+
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: "main".to_string(),
+                arguments: vec![x],
+                statements: vec![
+                    call(x, a),
+                    call(a, b),
+                    Statement::definition(FlatVariable::public(0), b),
+                ],
+                returns: vec![FlatVariable::public(0)],
+            },
+            functions: vec![square],
+            private: vec![true],
+            private_outputs: vec![],
+        };
+
+        let interpreter = Interpreter::default();
+        let inputs = vec![Bn128Field::from(3)];
+
+        let witness = interpreter.execute(&program, &inputs).unwrap();
+
+        assert_eq!(witness.return_values(), vec![Bn128Field::from(81)]);
+        // the witness is computed for the expanded program, in which the argument of the first call is `_3`
+        assert_eq!(
+            witness,
+            interpreter
+                .execute(&program.clone().expand(), &inputs)
+                .unwrap()
+        );
+        assert_eq!(witness.0[&FlatVariable::new(3)], Bn128Field::from(3));
+    }
+
     #[test]
     fn bits_of_one() {
         let inputs = vec![Bn128Field::from(1)];
@@ -322,4 +1216,23 @@ mod tests {
         assert_eq!(res[248], Bn128Field::from(1));
         assert_eq!(res[247], Bn128Field::from(0));
     }
+
+    #[test]
+    fn euclidean_div() {
+        let interpreter = Interpreter::default();
+        let res = interpreter
+            .execute_solver(
+                &Solver::EuclideanDiv,
+                &vec![Bn128Field::from(200), Bn128Field::from(7)],
+            )
+            .unwrap();
+        assert_eq!(res, vec![Bn128Field::from(28), Bn128Field::from(4)]);
+
+        assert!(interpreter
+            .execute_solver(
+                &Solver::EuclideanDiv,
+                &vec![Bn128Field::from(200), Bn128Field::from(0)],
+            )
+            .is_err());
+    }
 }
@@ -0,0 +1,361 @@
+//! Module containing lints on compiled programs, reporting constraints and variables which are likely mistakes
+//!
+//! Constraints do not record the position of the code they were compiled from, so findings are located by the
+//! variables of `main` they involve, as recorded in the source map.
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::{Prog, SourceMap, Statement};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use zokrates_field::Field;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Lint<T> {
+    /// The constraint at this index holds for any witness, such as `0 == 0`
+    TriviallySatisfied(usize),
+    /// The constraint at this index fixes a variable to a constant, which could be propagated
+    Constant(usize, FlatVariable, T),
+    /// The public input at this index is not used by any constraint, so proofs hold for any value of it
+    UnusedPublicInput(usize),
+    /// The public output at this index is not related to any private input by the constraints, so anyone can compute
+    /// it without a proof
+    VacuousOutput(usize),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Warning<T> {
+    pub lint: Lint<T>,
+    /// The variables of `main` involved, with their occurrence
+    pub locations: Vec<(String, usize)>,
+}
+
+impl<T: Field> fmt::Display for Lint<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Lint::TriviallySatisfied(index) => {
+                write!(f, "Constraint {} is trivially satisfied", index)
+            }
+            Lint::Constant(index, variable, value) => write!(
+                f,
+                "Constraint {} fixes {} to {}, which could be propagated",
+                index, variable, value
+            ),
+            Lint::UnusedPublicInput(index) => {
+                write!(f, "Public input {} is not used by any constraint", index)
+            }
+            Lint::VacuousOutput(index) => write!(
+                f,
+                "Public output {} does not depend on any private input",
+                index
+            ),
+        }
+    }
+}
+
+impl<T: Field> fmt::Display for Warning<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.lint)?;
+
+        if !self.locations.is_empty() {
+            write!(
+                f,
+                ", involving {}",
+                self.locations
+                    .iter()
+                    .map(|(name, occurrence)| format!("`{}` (occurrence {})", name, occurrence))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Partition of the variables into sets related by constraints, as a union-find structure
+#[derive(Default)]
+struct Components {
+    parents: HashMap<FlatVariable, FlatVariable>,
+}
+
+impl Components {
+    fn find(&mut self, variable: FlatVariable) -> FlatVariable {
+        let mut root = variable;
+        while let Some(parent) = self.parents.get(&root) {
+            root = *parent;
+        }
+
+        // point the whole path to the root, so that later lookups are short
+        let mut current = variable;
+        while current != root {
+            current = self.parents.insert(current, root).unwrap();
+        }
+
+        root
+    }
+
+    fn join(&mut self, variables: &BTreeSet<FlatVariable>) {
+        let mut variables = variables.iter();
+
+        if let Some(first) = variables.next() {
+            let root = self.find(*first);
+            for variable in variables {
+                let other = self.find(*variable);
+                if other != root {
+                    self.parents.insert(other, root);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the warnings of a program: constraints first, in order, then unused public inputs, then vacuous outputs.
+/// Constraints are indexed among constraints only, ignoring directives.
+pub fn lint<T: Field>(program: &Prog<T>, source_map: &SourceMap<T>) -> Vec<Warning<T>> {
+    let mut occurrences: HashMap<FlatVariable, BTreeSet<(String, usize)>> = HashMap::new();
+    for (name, occurrence, value) in source_map.occurrences() {
+        for variable in value.clone().into_canonical().0.keys() {
+            occurrences
+                .entry(*variable)
+                .or_default()
+                .insert((name.clone(), occurrence));
+        }
+    }
+
+    let locate = |variables: &BTreeSet<FlatVariable>| -> Vec<(String, usize)> {
+        variables
+            .iter()
+            .filter_map(|variable| occurrences.get(variable))
+            .flatten()
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    };
+
+    let mut warnings = vec![];
+    let mut used = HashSet::new();
+    let mut components = Components::default();
+
+    // a call relates its inputs to its outputs through the constraints of the function it calls
+    for call in program.main.statements.iter().filter_map(|s| match s {
+        Statement::Call(c) => Some(c),
+        _ => None,
+    }) {
+        let variables: BTreeSet<_> = call
+            .inputs
+            .iter()
+            .flat_map(|i| i.0.iter().map(|(variable, _)| *variable))
+            .chain(call.outputs.iter().cloned())
+            .filter(|variable| *variable != FlatVariable::one())
+            .collect();
+
+        used.extend(variables.iter().cloned());
+        components.join(&variables);
+    }
+
+    let constraints = program.main.statements.iter().filter_map(|s| match s {
+        Statement::Constraint(quad, lin) => Some((quad.clone().reduce(), lin.clone().reduce())),
+        _ => None,
+    });
+
+    for (index, (quad, lin)) in constraints.enumerate() {
+        let variables: BTreeSet<_> = quad
+            .left
+            .0
+            .iter()
+            .chain(quad.right.0.iter())
+            .chain(lin.0.iter())
+            .map(|(variable, _)| *variable)
+            .filter(|variable| *variable != FlatVariable::one())
+            .collect();
+
+        used.extend(variables.iter().cloned());
+        components.join(&variables);
+
+        // a linear constraint reads `difference == 0`, the constant term being the coefficient of `~one`
+        let difference = match quad.try_linear() {
+            Some(linear) => (linear - lin).into_canonical().0,
+            None => continue,
+        };
+
+        let constant = difference
+            .get(&FlatVariable::one())
+            .cloned()
+            .unwrap_or_else(T::zero);
+        let mut terms = difference
+            .iter()
+            .filter(|(variable, _)| **variable != FlatVariable::one());
+
+        let lint = match (terms.next(), terms.next()) {
+            (None, _) if constant == T::zero() => Lint::TriviallySatisfied(index),
+            // outputs are fixed to constants when `main` returns one, which `VacuousOutput` reports
+            (Some((variable, coefficient)), None) if !variable.is_output() => {
+                Lint::Constant(index, *variable, (T::zero() - constant) / coefficient)
+            }
+            _ => continue,
+        };
+
+        warnings.push(Warning {
+            lint,
+            locations: locate(&variables),
+        });
+    }
+
+    let parameters = program.parameters();
+
+    for (index, parameter) in parameters.iter().enumerate() {
+        if !parameter.private && !used.contains(&parameter.id) {
+            warnings.push(Warning {
+                lint: Lint::UnusedPublicInput(index),
+                locations: locate(&vec![parameter.id].into_iter().collect()),
+            });
+        }
+    }
+
+    let private: HashSet<_> = parameters
+        .iter()
+        .filter(|parameter| parameter.private)
+        .map(|parameter| components.find(parameter.id))
+        .collect();
+
+    for (index, output) in program.main.returns.iter().enumerate() {
+        if !program.is_private_output(index) && !private.contains(&components.find(*output)) {
+            warnings.push(Warning {
+                lint: Lint::VacuousOutput(index),
+                locations: locate(&vec![*output].into_iter().collect()),
+            });
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ir::{Function, LinComb, QuadComb};
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn lint() {
+        // def main(_0, _1, private _2) -> (2):
+        //     (1 * ~one) * (1 * ~one) == 1 * ~one
+        //     (1 * ~one) * (5 * ~one) == 1 * _3
+        //     (1 * _0) * (1 * _3) == 1 * ~out_0
+        //     (1 * _2) * (1 * _2) == 1 * ~out_1
+        //     return ~out_0, ~out_1
+
+        let one = FlatVariable::one();
+
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![
+                    FlatVariable::new(0),
+                    FlatVariable::new(1),
+                    FlatVariable::new(2),
+                ],
+                statements: vec![
+                    Statement::constraint(
+                        QuadComb::from_linear_combinations(one.into(), one.into()),
+                        one,
+                    ),
+                    Statement::constraint(
+                        QuadComb::from_linear_combinations(one.into(), LinComb::summand(5, one)),
+                        FlatVariable::new(3),
+                    ),
+                    Statement::constraint(
+                        QuadComb::from_linear_combinations(
+                            FlatVariable::new(0).into(),
+                            FlatVariable::new(3).into(),
+                        ),
+                        FlatVariable::public(0),
+                    ),
+                    Statement::constraint(
+                        QuadComb::from_linear_combinations(
+                            FlatVariable::new(2).into(),
+                            FlatVariable::new(2).into(),
+                        ),
+                        FlatVariable::public(1),
+                    ),
+                ],
+                returns: vec![FlatVariable::public(0), FlatVariable::public(1)],
+            },
+            functions: vec![],
+            private: vec![false, false, true],
+            private_outputs: vec![],
+        };
+
+        let mut source_map = SourceMap::default();
+        source_map.insert(String::from("a"), 0, FlatVariable::new(0).into());
+        source_map.insert(String::from("b"), 0, FlatVariable::new(1).into());
+        source_map.insert(String::from("c"), 0, FlatVariable::new(3).into());
+
+        let warnings = super::lint(&program, &source_map);
+
+        assert_eq!(
+            warnings,
+            vec![
+                Warning {
+                    lint: Lint::TriviallySatisfied(0),
+                    locations: vec![]
+                },
+                Warning {
+                    lint: Lint::Constant(1, FlatVariable::new(3), Bn128Field::from(5)),
+                    locations: vec![(String::from("c"), 0)]
+                },
+                Warning {
+                    lint: Lint::UnusedPublicInput(1),
+                    locations: vec![(String::from("b"), 0)]
+                },
+                Warning {
+                    lint: Lint::VacuousOutput(0),
+                    locations: vec![]
+                },
+            ]
+        );
+
+        assert_eq!(
+            warnings[1].to_string(),
+            "Constraint 1 fixes _3 to 5, which could be propagated, involving `c` (occurrence 0)"
+        );
+    }
+
+    #[test]
+    fn private_outputs_are_not_vacuous() {
+        // def main(_0) -> (1):
+        //     (1 * ~one) * (1 * _0) == 1 * ~out_0
+        //     return ~out_0
+
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                statements: vec![Statement::constraint(
+                    FlatVariable::new(0),
+                    FlatVariable::public(0),
+                )],
+                returns: vec![FlatVariable::public(0)],
+            },
+            functions: vec![],
+            private: vec![false],
+            private_outputs: vec![],
+        };
+
+        assert_eq!(
+            super::lint(&program, &SourceMap::default()),
+            vec![Warning {
+                lint: Lint::VacuousOutput(0),
+                locations: vec![]
+            }]
+        );
+
+        let program = Prog {
+            private_outputs: vec![0],
+            ..program
+        };
+
+        assert!(super::lint(&program, &SourceMap::default()).is_empty());
+    }
+}
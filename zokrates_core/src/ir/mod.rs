@@ -1,27 +1,33 @@
 use crate::flat_absy::flat_parameter::FlatParameter;
 use crate::flat_absy::FlatVariable;
 use crate::solvers::Solver;
+use std::collections::HashMap;
 use std::fmt;
 use zokrates_field::Field;
 
+mod expand;
 mod expression;
 pub mod folder;
 mod from_flat;
 mod interpreter;
+pub mod lint;
 mod serialize;
+pub mod source_map;
 mod witness;
 
 pub use self::expression::QuadComb;
 pub use self::expression::{CanonicalLinComb, LinComb};
 pub use self::serialize::ProgEnum;
+pub use self::source_map::SourceMap;
 
-pub use self::interpreter::{Error, ExecutionResult, Interpreter};
-pub use self::witness::Witness;
+pub use self::interpreter::{Error, ExecutionResult, Interpreter, Oracle, Step, Trace, TraceError};
+pub use self::witness::{PartialWitness, Witness};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Hash)]
 pub enum Statement<T> {
     Constraint(QuadComb<T>, LinComb<T>),
     Directive(Directive<T>),
+    Call(Call<T>),
 }
 
 impl<T: Field> PartialEq for Statement<T> {
@@ -31,6 +37,7 @@ impl<T: Field> PartialEq for Statement<T> {
                 l1.eq(l2) && r1.eq(r2)
             }
             (Statement::Directive(d1), Statement::Directive(d2)) => d1.eq(d2),
+            (Statement::Call(c1), Statement::Call(c2)) => c1.eq(c2),
             _ => false,
         }
     }
@@ -85,11 +92,51 @@ impl<T: Field> fmt::Display for Directive<T> {
     }
 }
 
+/// A call to a function of the program, whose constraints are instantiated on the variables of the caller: its
+/// arguments are bound to `inputs`, its returns to `outputs`, and its other variables are fresh
+#[derive(Clone, Debug, Serialize, Deserialize, Hash)]
+pub struct Call<T> {
+    pub function: String,
+    pub inputs: Vec<LinComb<T>>,
+    pub outputs: Vec<FlatVariable>,
+}
+
+impl<T: Field> PartialEq for Call<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.function.eq(&other.function)
+            && self.inputs.eq(&other.inputs)
+            && self.outputs.eq(&other.outputs)
+    }
+}
+
+impl<T: Field> Eq for Call<T> {}
+
+impl<T: Field> fmt::Display for Call<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} = {}({})",
+            self.outputs
+                .iter()
+                .map(|o| format!("{}", o))
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.function,
+            self.inputs
+                .iter()
+                .map(|i| format!("{}", i))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
 impl<T: Field> fmt::Display for Statement<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             Statement::Constraint(ref quad, ref lin) => write!(f, "{} == {}", quad, lin),
             Statement::Directive(ref s) => write!(f, "{}", s),
+            Statement::Call(ref c) => write!(f, "{}", c),
         }
     }
 }
@@ -140,31 +187,89 @@ impl<T: Field> fmt::Display for Function<T> {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Prog<T> {
     pub main: Function<T>,
+    /// Functions called by `main`, or by one another, through `Statement::Call`
+    pub functions: Vec<Function<T>>,
     pub private: Vec<bool>,
+    pub private_outputs: Vec<usize>,
 }
 
 impl<T: Field> PartialEq for Prog<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.main.eq(&other.main) && self.private.eq(&other.private)
+        self.main.eq(&other.main)
+            && self.functions.eq(&other.functions)
+            && self.private.eq(&other.private)
+            && self.private_outputs.eq(&other.private_outputs)
     }
 }
 
 impl<T: Field> Prog<T> {
+    /// Returns the number of constraints of the program, counting the constraints of a function at each call, as well
+    /// as the constraints binding its arguments to the inputs of the call once expanded
     pub fn constraint_count(&self) -> usize {
-        self.main
+        self.count_constraints(&self.main, &mut HashMap::new())
+    }
+
+    /// Returns the number of constraints of the function `id`, or of `main`, if the program defines it
+    pub fn function_constraint_count(&self, id: &str) -> Option<usize> {
+        std::iter::once(&self.main)
+            .chain(self.functions.iter())
+            .find(|f| f.id == id)
+            .map(|f| self.count_constraints(f, &mut HashMap::new()))
+    }
+
+    fn count_constraints(
+        &self,
+        function: &Function<T>,
+        counts: &mut HashMap<String, usize>,
+    ) -> usize {
+        function
             .statements
             .iter()
-            .filter(|s| match s {
-                Statement::Constraint(..) => true,
-                _ => false,
+            .map(|s| match s {
+                Statement::Constraint(..) => 1,
+                Statement::Directive(..) => 0,
+                Statement::Call(c) => {
+                    c.inputs.len()
+                        + match counts.get(&c.function) {
+                            Some(count) => *count,
+                            None => {
+                                let count =
+                                    self.count_constraints(self.function(&c.function), counts);
+                                counts.insert(c.function.clone(), count);
+                                count
+                            }
+                        }
+                }
             })
-            .count()
+            .sum()
+    }
+
+    /// Returns the function `id` called by the program
+    ///
+    /// # Panics
+    ///
+    /// Panics if the program has no such function
+    pub fn function(&self, id: &str) -> &Function<T> {
+        self.functions
+            .iter()
+            .find(|f| f.id == id)
+            .unwrap_or_else(|| panic!("Function `{}` is called but not defined", id))
     }
 
     pub fn arguments_count(&self) -> usize {
         self.private.len()
     }
 
+    /// Returns the number of public inputs of the proofs of the program: its public arguments and its public outputs
+    pub fn public_inputs_count(&self) -> usize {
+        self.private.iter().filter(|private| !**private).count() + self.main.returns.len()
+            - self.private_outputs.len()
+    }
+
+    pub fn is_private_output(&self, index: usize) -> bool {
+        self.private_outputs.contains(&index)
+    }
+
     pub fn parameters(&self) -> Vec<FlatParameter> {
         self.main
             .arguments
@@ -180,6 +285,9 @@ impl<T: Field> Prog<T> {
 
 impl<T: Field> fmt::Display for Prog<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for function in &self.functions {
+            writeln!(f, "{}\n", function)?;
+        }
         write!(f, "{}", self.main)
     }
 }
@@ -203,5 +311,15 @@ mod tests {
             );
             assert_eq!(format!("{}", c), "(1 * _42) * (1 * _42) == 1 * _42")
         }
+
+        #[test]
+        fn print_call() {
+            let c: Statement<Bn128Field> = Statement::Call(Call {
+                function: String::from("foo"),
+                inputs: vec![FlatVariable::new(42).into(), FlatVariable::one().into()],
+                outputs: vec![FlatVariable::new(43), FlatVariable::new(44)],
+            });
+            assert_eq!(format!("{}", c), "_43, _44 = foo(1 * _42, 1 * ~one)")
+        }
     }
 }
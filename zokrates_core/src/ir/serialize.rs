@@ -4,18 +4,24 @@ use std::io::{Read, Write};
 use zokrates_field::*;
 
 const ZOKRATES_MAGIC: &[u8; 4] = &[0x5a, 0x4f, 0x4b, 0];
-const ZOKRATES_VERSION_1: &[u8; 4] = &[0, 0, 0, 1];
+// version 2 added private outputs
+// version 3 added functions and calls
+const ZOKRATES_VERSION_3: &[u8; 4] = &[0, 0, 0, 3];
 
 #[derive(PartialEq, Debug)]
 pub enum ProgEnum {
     Bls12Program(Prog<Bls12Field>),
     Bn128Program(Prog<Bn128Field>),
+    PallasProgram(Prog<PallasField>),
+    VestaProgram(Prog<VestaField>),
+    GoldilocksProgram(Prog<GoldilocksField>),
+    CustomProgram(Prog<CustomField>),
 }
 
 impl<T: Field> Prog<T> {
     pub fn serialize<W: Write>(&self, mut w: W) {
         w.write(ZOKRATES_MAGIC).unwrap();
-        w.write(ZOKRATES_VERSION_1).unwrap();
+        w.write(ZOKRATES_VERSION_3).unwrap();
         w.write(&T::id()).unwrap();
 
         serialize_into(&mut w, self, Infinite).unwrap();
@@ -30,12 +36,12 @@ impl ProgEnum {
             .map_err(|_| String::from("Cannot read magic number"))?;
 
         if &magic == ZOKRATES_MAGIC {
-            // Check the version, 1
+            // Check the version, 3
             let mut version = [0; 4];
             r.read_exact(&mut version)
                 .map_err(|_| String::from("Cannot read version"))?;
 
-            if &version == ZOKRATES_VERSION_1 {
+            if &version == ZOKRATES_VERSION_3 {
                 // Check the curve identifier, deserializing accordingly
                 let mut curve = [0; 4];
                 r.read_exact(&mut curve)
@@ -48,6 +54,19 @@ impl ProgEnum {
                     m if m == Bn128Field::id() => Ok(ProgEnum::Bn128Program(
                         deserialize_from(&mut r, Infinite).unwrap(),
                     )),
+                    m if m == PallasField::id() => Ok(ProgEnum::PallasProgram(
+                        deserialize_from(&mut r, Infinite).unwrap(),
+                    )),
+                    m if m == VestaField::id() => Ok(ProgEnum::VestaProgram(
+                        deserialize_from(&mut r, Infinite).unwrap(),
+                    )),
+                    m if m == GoldilocksField::id() => Ok(ProgEnum::GoldilocksProgram(
+                        deserialize_from(&mut r, Infinite).unwrap(),
+                    )),
+                    // programs compiled for a custom field are only recognized once its modulus is set
+                    m if custom::modulus().is_some() && m == CustomField::id() => Ok(
+                        ProgEnum::CustomProgram(deserialize_from(&mut r, Infinite).unwrap()),
+                    ),
                     _ => Err(String::from("Unknown curve identifier")),
                 }
             } else {
@@ -67,7 +86,7 @@ mod tests {
     use zokrates_field::{Bls12Field, Bn128Field};
 
     #[test]
-    fn ser_deser_v1() {
+    fn ser_deser_v3() {
         let p: ir::Prog<Bn128Field> = ir::Prog {
             main: ir::Function {
                 arguments: vec![],
@@ -75,7 +94,9 @@ mod tests {
                 returns: vec![],
                 statements: vec![],
             },
+            functions: vec![],
             private: vec![],
+            private_outputs: vec![],
         };
 
         let mut buffer = Cursor::new(vec![]);
@@ -96,7 +117,9 @@ mod tests {
                 returns: vec![],
                 statements: vec![],
             },
+            functions: vec![],
             private: vec![],
+            private_outputs: vec![],
         };
 
         let mut buffer = Cursor::new(vec![]);
@@ -0,0 +1,186 @@
+//! Module containing the source map of a program, locating the variables of `main` in the witness
+//!
+//! Each variable is mapped to one linear combination of witness variables per occurrence. The occurrence `n` of
+//! a variable is its value after `n` assignments, the declaration being occurrence `0`. Array elements and struct
+//! members are named `a[0]` and `s.member` respectively.
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::{LinComb, Witness};
+use std::collections::BTreeMap;
+use std::fmt;
+use zokrates_field::Field;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SourceMap<T> {
+    variables: BTreeMap<String, BTreeMap<usize, LinComb<T>>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    UnknownVariable(String),
+    UnknownOccurrence(String, usize),
+    MissingValue(FlatVariable),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UnknownVariable(name) => write!(
+                f,
+                "Variable `{}` was not found in `main`, or was optimized out",
+                name
+            ),
+            Error::UnknownOccurrence(name, occurrence) => write!(
+                f,
+                "Occurrence {} of variable `{}` was not found, or was optimized out",
+                occurrence, name
+            ),
+            Error::MissingValue(v) => write!(f, "Variable {} is missing in the witness", v),
+        }
+    }
+}
+
+impl<T> Default for SourceMap<T> {
+    fn default() -> Self {
+        SourceMap {
+            variables: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T: Field> SourceMap<T> {
+    pub fn insert(&mut self, name: String, occurrence: usize, value: LinComb<T>) {
+        self.variables
+            .entry(name)
+            .or_default()
+            .insert(occurrence, value);
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.variables.keys()
+    }
+
+    /// Returns all occurrences of all variables, ordered by name and occurrence
+    pub fn occurrences(&self) -> impl Iterator<Item = (&String, usize, &LinComb<T>)> {
+        self.variables.iter().flat_map(|(name, occurrences)| {
+            occurrences
+                .iter()
+                .map(move |(occurrence, value)| (name, *occurrence, value))
+        })
+    }
+
+    /// Returns the last occurrence of a variable, which is its number of assignments
+    pub fn last_occurrence(&self, name: &str) -> Option<usize> {
+        self.variables
+            .get(name)
+            .and_then(|occurrences| occurrences.keys().next_back().cloned())
+    }
+
+    /// Returns the given occurrence of a variable, or its last occurrence if none is given
+    pub fn get(
+        &self,
+        name: &str,
+        occurrence: Option<usize>,
+    ) -> Result<(usize, &LinComb<T>), Error> {
+        let occurrences = self
+            .variables
+            .get(name)
+            .ok_or_else(|| Error::UnknownVariable(name.to_string()))?;
+
+        match occurrence {
+            Some(occurrence) => occurrences
+                .get(&occurrence)
+                .map(|value| (occurrence, value))
+                .ok_or_else(|| Error::UnknownOccurrence(name.to_string(), occurrence)),
+            None => Ok(occurrences
+                .iter()
+                .next_back()
+                .map(|(occurrence, value)| (*occurrence, value))
+                .unwrap()),
+        }
+    }
+
+    /// Returns the value of the given occurrence of a variable in a witness, or of its last occurrence if none is given
+    pub fn evaluate(
+        &self,
+        name: &str,
+        occurrence: Option<usize>,
+        witness: &Witness<T>,
+    ) -> Result<(usize, T), Error> {
+        let (occurrence, value) = self.get(name, occurrence)?;
+
+        value
+            .0
+            .iter()
+            .try_fold(T::from(0), |acc, (variable, coefficient)| {
+                witness
+                    .0
+                    .get(variable)
+                    .map(|v| acc + v.clone() * coefficient)
+                    .ok_or(Error::MissingValue(*variable))
+            })
+            .map(|value| (occurrence, value))
+    }
+
+    /// Removes the occurrences whose value is not kept by `f`, for example because its variables were optimized out
+    pub fn retain<F: FnMut(&LinComb<T>) -> bool>(&mut self, mut f: F) {
+        for occurrences in self.variables.values_mut() {
+            occurrences.retain(|_, value| f(value));
+        }
+        self.variables
+            .retain(|_, occurrences| !occurrences.is_empty());
+    }
+
+    /// Rewrites the linear combinations of the source map, to follow a substitution of variables in the program
+    pub fn substitute<F: FnMut(LinComb<T>) -> LinComb<T>>(&mut self, mut f: F) {
+        for occurrences in self.variables.values_mut() {
+            for value in occurrences.values_mut() {
+                *value = f(std::mem::replace(value, LinComb::zero()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn evaluate() {
+        let mut source_map = SourceMap::default();
+        source_map.insert(String::from("a"), 0, FlatVariable::new(0).into());
+        source_map.insert(
+            String::from("a"),
+            2,
+            LinComb::summand(2, FlatVariable::new(1)) + LinComb::from(Bn128Field::from(3)),
+        );
+
+        let witness = Witness(
+            vec![
+                (FlatVariable::one(), Bn128Field::from(1)),
+                (FlatVariable::new(0), Bn128Field::from(42)),
+                (FlatVariable::new(1), Bn128Field::from(5)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        assert_eq!(
+            source_map.evaluate("a", Some(0), &witness),
+            Ok((0, Bn128Field::from(42)))
+        );
+        assert_eq!(
+            source_map.evaluate("a", None, &witness),
+            Ok((2, Bn128Field::from(13)))
+        );
+        assert_eq!(
+            source_map.evaluate("a", Some(1), &witness),
+            Err(Error::UnknownOccurrence(String::from("a"), 1))
+        );
+        assert_eq!(
+            source_map.evaluate("b", None, &witness),
+            Err(Error::UnknownVariable(String::from("b")))
+        );
+    }
+}
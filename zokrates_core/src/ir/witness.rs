@@ -1,4 +1,7 @@
 use crate::flat_absy::FlatVariable;
+use crate::ir::Error;
+use bincode::{deserialize_from, serialize_into, Infinite};
+use std::collections::btree_map::Entry;
 use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::io;
@@ -89,6 +92,59 @@ impl<T: Field> Witness<T> {
     }
 }
 
+/// The state of a witness computation which was started before the private inputs were known
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PartialWitness<T> {
+    /// the values computed so far
+    pub values: BTreeMap<FlatVariable, T>,
+    /// the indices of the statements which remain to be executed
+    pub pending: Vec<usize>,
+}
+
+impl<T: Field> PartialWitness<T> {
+    /// Merges witness fragments computed by different parties, checking that they agree on the values they share.
+    /// A statement remains pending if it is pending in both fragments
+    pub fn merge(self, other: Self) -> Result<Self, Error> {
+        let PartialWitness {
+            values: other_values,
+            pending: other_pending,
+        } = other;
+
+        let mut values = self.values;
+
+        for (variable, value) in other_values {
+            match values.entry(variable) {
+                Entry::Occupied(e) => {
+                    if *e.get() != value {
+                        return Err(Error::InconsistentFragments(variable.to_string()));
+                    }
+                }
+                Entry::Vacant(e) => {
+                    e.insert(value);
+                }
+            }
+        }
+
+        let pending = self
+            .pending
+            .into_iter()
+            .filter(|index| other_pending.binary_search(index).is_ok())
+            .collect();
+
+        Ok(PartialWitness { values, pending })
+    }
+
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        serialize_into(&mut writer, self, Infinite)
+            .map_err(|why| io::Error::new(io::ErrorKind::Other, why.to_string()))
+    }
+
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        deserialize_from(&mut reader, Infinite)
+            .map_err(|why| io::Error::new(io::ErrorKind::Other, why.to_string()))
+    }
+}
+
 impl<T: Field> fmt::Display for Witness<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
@@ -154,6 +210,28 @@ mod tests {
             assert!(Witness::<Bn128Field>::read(buff).is_err());
         }
 
+        #[test]
+        fn serialize_deserialize_partial() {
+            let w = PartialWitness {
+                values: vec![
+                    (FlatVariable::new(42), Bn128Field::from(42)),
+                    (FlatVariable::one(), Bn128Field::from(1)),
+                ]
+                .into_iter()
+                .collect(),
+                pending: vec![1, 3],
+            };
+
+            let mut buff = Cursor::new(vec![]);
+
+            w.write(&mut buff).unwrap();
+            buff.set_position(0);
+
+            let r = PartialWitness::read(buff).unwrap();
+
+            assert_eq!(w, r);
+        }
+
         #[test]
         fn not_csv() {
             let mut buff = Cursor::new(vec![]);
@@ -33,7 +33,17 @@ mod zir;
 
 pub mod absy;
 pub mod compile;
+pub mod deprecation;
+pub mod doc;
+pub mod entry_point;
+pub mod error_codes;
 pub mod flat_absy;
+pub mod fuzz;
 pub mod ir;
+pub mod limits;
+pub mod max_constraints;
 pub mod proof_system;
+pub mod public_inputs;
+pub mod smt;
+pub mod taint;
 pub mod typed_absy;
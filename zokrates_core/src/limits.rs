@@ -0,0 +1,183 @@
+//! Module containing resource limits for the compilation of untrusted programs
+//!
+//! Limits are enforced cooperatively: the compiler checks them at given points and stops with an `Error` as soon
+//! as one of them is exceeded.
+
+use crate::typed_absy::folder::*;
+use crate::typed_absy::*;
+use std::fmt;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant};
+use zokrates_field::Field;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Limits {
+    /// maximum number of statements and expressions in the checked program, including imported modules
+    pub max_ast_nodes: Option<usize>,
+    /// maximum number of statements visited while unrolling loops
+    pub max_unrolled_statements: Option<usize>,
+    /// maximum time spent in the compiler before flattening, in milliseconds. Ignored when targeting wasm
+    pub timeout_ms: Option<u64>,
+}
+
+impl Limits {
+    pub fn max_ast_nodes(mut self, max_ast_nodes: usize) -> Self {
+        self.max_ast_nodes = Some(max_ast_nodes);
+        self
+    }
+
+    pub fn max_unrolled_statements(mut self, max_unrolled_statements: usize) -> Self {
+        self.max_unrolled_statements = Some(max_unrolled_statements);
+        self
+    }
+
+    pub fn timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.timeout_ms = Some(timeout_ms);
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    AstNodes(usize),
+    UnrolledStatements(usize),
+    Timeout(u64),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::AstNodes(max) => write!(
+                f,
+                "Resource limit exceeded: the program has more than {} nodes",
+                max
+            ),
+            Error::UnrolledStatements(max) => write!(
+                f,
+                "Resource limit exceeded: unrolling loops produces more than {} statements",
+                max
+            ),
+            Error::Timeout(ms) => write!(
+                f,
+                "Resource limit exceeded: compilation took more than {}ms",
+                ms
+            ),
+        }
+    }
+}
+
+/// The limits of a single compilation, starting the clock for the timeout on creation
+#[derive(Debug, Clone)]
+pub struct Budget {
+    limits: Limits,
+    #[cfg(not(target_arch = "wasm32"))]
+    deadline: Option<Instant>,
+}
+
+impl Budget {
+    pub fn new(limits: &Limits) -> Self {
+        Budget {
+            limits: limits.clone(),
+            #[cfg(not(target_arch = "wasm32"))]
+            deadline: limits
+                .timeout_ms
+                .map(|ms| Instant::now() + Duration::from_millis(ms)),
+        }
+    }
+
+    pub fn unlimited() -> Self {
+        Self::new(&Limits::default())
+    }
+
+    pub fn check_time(&self) -> Result<(), Error> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(deadline) = self.deadline {
+                if Instant::now() > deadline {
+                    return Err(Error::Timeout(self.limits.timeout_ms.unwrap()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn check_unrolled_statements(&self, count: usize) -> Result<(), Error> {
+        match self.limits.max_unrolled_statements {
+            Some(max) if count > max => Err(Error::UnrolledStatements(max)),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn check_ast_nodes<'ast, T: Field>(
+        &self,
+        p: TypedProgram<'ast, T>,
+    ) -> Result<TypedProgram<'ast, T>, Error> {
+        match self.limits.max_ast_nodes {
+            Some(max) => {
+                let mut counter = NodeCounter { count: 0 };
+                let p = counter.fold_program(p);
+                match counter.count > max {
+                    true => Err(Error::AstNodes(max)),
+                    false => Ok(p),
+                }
+            }
+            None => Ok(p),
+        }
+    }
+}
+
+struct NodeCounter {
+    count: usize,
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for NodeCounter {
+    fn fold_statement(&mut self, s: TypedStatement<'ast, T>) -> Vec<TypedStatement<'ast, T>> {
+        self.count += 1;
+        fold_statement(self, s)
+    }
+
+    fn fold_field_expression(
+        &mut self,
+        e: FieldElementExpression<'ast, T>,
+    ) -> FieldElementExpression<'ast, T> {
+        self.count += 1;
+        fold_field_expression(self, e)
+    }
+
+    fn fold_boolean_expression(
+        &mut self,
+        e: BooleanExpression<'ast, T>,
+    ) -> BooleanExpression<'ast, T> {
+        self.count += 1;
+        fold_boolean_expression(self, e)
+    }
+
+    fn fold_uint_expression_inner(
+        &mut self,
+        bitwidth: UBitwidth,
+        e: UExpressionInner<'ast, T>,
+    ) -> UExpressionInner<'ast, T> {
+        self.count += 1;
+        fold_uint_expression_inner(self, bitwidth, e)
+    }
+
+    fn fold_array_expression_inner(
+        &mut self,
+        ty: &Type,
+        size: usize,
+        e: ArrayExpressionInner<'ast, T>,
+    ) -> ArrayExpressionInner<'ast, T> {
+        self.count += 1;
+        fold_array_expression_inner(self, ty, size, e)
+    }
+
+    fn fold_struct_expression_inner(
+        &mut self,
+        ty: &StructType,
+        e: StructExpressionInner<'ast, T>,
+    ) -> StructExpressionInner<'ast, T> {
+        self.count += 1;
+        fold_struct_expression_inner(self, ty, e)
+    }
+}
@@ -0,0 +1,83 @@
+//! Module checking the number of constraints of the functions annotated with `#[max_constraints(n)]`
+//!
+//! Annotated functions are kept as sub-circuits by the inliner, so that their constraints are found in a single
+//! function of the compiled program. Once the program is optimized, the constraints of each annotated function are
+//! counted, those of its calls included, and compared to its maximum. Functions which are never called are not checked.
+
+use crate::ir::Prog;
+use crate::typed_absy::{TypedFunctionSymbol, TypedProgram};
+use crate::zir::types::FunctionKey;
+use std::fmt;
+use zokrates_field::Field;
+
+/// The maximum number of constraints of a function
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaxConstraints {
+    /// the name of the function in the source
+    function: String,
+    /// the id of the function in the compiled program
+    id: String,
+    max: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    pub function: String,
+    pub max: usize,
+    pub count: usize,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Function `{}` compiles to {} constraints, more than the {} allowed by its `max_constraints` attribute",
+            self.function, self.count, self.max
+        )
+    }
+}
+
+/// Returns the maximum number of constraints of the functions of an inlined program, where the functions kept as
+/// sub-circuits are defined in the main module
+pub fn collect<'ast, T: Field>(p: &TypedProgram<'ast, T>) -> Vec<MaxConstraints> {
+    p.modules[&p.main]
+        .functions
+        .iter()
+        .filter_map(|(key, symbol)| match symbol {
+            TypedFunctionSymbol::Here(f) => f.attributes.max_constraints.map(|max| {
+                let id = match key.id {
+                    "main" => String::from("main"),
+                    _ => FunctionKey::from(key.clone()).to_slug(),
+                };
+
+                MaxConstraints {
+                    function: key.id.to_string(),
+                    id,
+                    max,
+                }
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Checks the number of constraints of the functions of a compiled program against their maximum
+pub fn check<T: Field>(p: &Prog<T>, max_constraints: &[MaxConstraints]) -> Result<(), Vec<Error>> {
+    let errors: Vec<_> = max_constraints
+        .iter()
+        .filter_map(|m| {
+            p.function_constraint_count(&m.id)
+                .filter(|count| *count > m.max)
+                .map(|count| Error {
+                    function: m.function.clone(),
+                    max: m.max,
+                    count,
+                })
+        })
+        .collect();
+
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(errors),
+    }
+}
@@ -0,0 +1,227 @@
+//! Module containing the `DeadCodeOptimizer` to remove the statements whose variables are never read
+//!
+//! A constraint `q == c * v` where `c` is not zero and `v` appears in no other statement only defines `v`: any
+//! assignment of the other variables satisfies it for some value of `v`, so it can be removed, as can the directives
+//! whose outputs are not used anymore. Statements are visited in reverse, so that removing a statement can make the
+//! ones defining its inputs dead in turn. Calls are kept, as the constraints of the called function can fail, and so
+//! are the statements which are the last to read an argument, which would otherwise be left unconstrained.
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::folder::Folder;
+use crate::ir::*;
+use std::collections::{HashMap, HashSet};
+use zokrates_field::Field;
+
+pub struct DeadCodeOptimizer {
+    /// The variables which were removed from the last function
+    removed: HashSet<FlatVariable>,
+}
+
+impl DeadCodeOptimizer {
+    fn new() -> DeadCodeOptimizer {
+        DeadCodeOptimizer {
+            removed: HashSet::new(),
+        }
+    }
+
+    pub fn optimize<T: Field>(p: Prog<T>, source_map: &mut SourceMap<T>) -> Prog<T> {
+        let mut optimizer = DeadCodeOptimizer::new();
+        let p = optimizer.fold_module(p);
+
+        // `main` is folded last, so the removed variables are the ones of `main`
+        source_map.retain(|value| value.0.iter().all(|(v, _)| !optimizer.removed.contains(v)));
+
+        p
+    }
+}
+
+/// Returns the occurrences of the variables read by a statement, the outputs of a directive not being read by it
+fn reads<T: Field>(s: &Statement<T>) -> Vec<FlatVariable> {
+    match s {
+        Statement::Constraint(quad, lin) => quad
+            .left
+            .0
+            .iter()
+            .chain(quad.right.0.iter())
+            .chain(lin.0.iter())
+            .map(|(v, _)| *v)
+            .collect(),
+        Statement::Directive(d) => d
+            .inputs
+            .iter()
+            .flat_map(|i| i.left.0.iter().chain(i.right.0.iter()))
+            .map(|(v, _)| *v)
+            .collect(),
+        Statement::Call(c) => c
+            .inputs
+            .iter()
+            .flat_map(|i| i.0.iter())
+            .map(|(v, _)| *v)
+            .chain(c.outputs.iter().cloned())
+            .collect(),
+    }
+}
+
+/// Returns the variables defined by a statement if it can be removed when they are not read, given the number of
+/// occurrences of each variable
+fn defined<T: Field>(
+    s: &Statement<T>,
+    uses: &HashMap<FlatVariable, usize>,
+) -> Option<Vec<FlatVariable>> {
+    match s {
+        Statement::Constraint(quad, lin) => {
+            let (v, coefficient) = lin.try_summand()?;
+
+            let in_quad = quad
+                .left
+                .0
+                .iter()
+                .chain(quad.right.0.iter())
+                .any(|(w, _)| *w == v);
+
+            match coefficient != T::zero() && !in_quad && uses[&v] == lin.0.len() {
+                true => Some(vec![v]),
+                false => None,
+            }
+        }
+        Statement::Directive(d) => match d
+            .outputs
+            .iter()
+            .all(|o| uses.get(o).cloned().unwrap_or(0) == 0)
+        {
+            true => Some(d.outputs.clone()),
+            false => None,
+        },
+        Statement::Call(..) => None,
+    }
+}
+
+impl<T: Field> Folder<T> for DeadCodeOptimizer {
+    fn fold_function(&mut self, f: Function<T>) -> Function<T> {
+        let mut uses = HashMap::new();
+        for v in f.statements.iter().flat_map(reads) {
+            *uses.entry(v).or_insert(0) += 1;
+        }
+
+        let arguments: HashSet<_> = f.arguments.iter().cloned().collect();
+        let returns: HashSet<_> = f.returns.iter().cloned().collect();
+        let removed = &mut self.removed;
+        removed.clear();
+
+        let mut statements: Vec<_> = f
+            .statements
+            .into_iter()
+            .rev()
+            .filter(|s| {
+                let defined = match defined(s, &uses) {
+                    Some(defined) => defined,
+                    None => return true,
+                };
+
+                if defined.iter().any(|v| {
+                    *v == FlatVariable::one()
+                        || v.is_output()
+                        || arguments.contains(v)
+                        || returns.contains(v)
+                }) {
+                    return true;
+                }
+
+                let read = reads(s);
+
+                // keep the last statement reading an argument
+                if read.iter().any(|v| {
+                    arguments.contains(v) && uses[v] == read.iter().filter(|w| *w == v).count()
+                }) {
+                    return true;
+                }
+
+                for v in read {
+                    *uses.get_mut(&v).unwrap() -= 1;
+                }
+                removed.extend(defined);
+
+                false
+            })
+            .collect();
+
+        statements.reverse();
+
+        Function { statements, ..f }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solvers::Solver;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn remove_unused_definitions() {
+        // def main(_0, _1) -> (1):
+        //     # _2 = Div(1 * _0, 1 * ~one)
+        //     (1 * _0) * (1 * _0) == 1 * _2
+        //     (1 * _2) * (1 * ~one) == 2 * _3
+        //     (1 * _1) * (1 * _1) == 1 * _4
+        //     (1 * _0) * (1 * ~one) == 1 * ~out_0
+        //
+        // becomes
+        //
+        // def main(_0, _1) -> (1):
+        //     (1 * _1) * (1 * _1) == 1 * _4
+        //     (1 * _0) * (1 * ~one) == 1 * ~out_0
+        //
+        // as `_3` is never read, which makes `_2` unused, and the constraint on `_1` is the only one to read it
+
+        let square = |v: usize, res: usize| {
+            Statement::constraint(
+                QuadComb::from_linear_combinations(
+                    FlatVariable::new(v).into(),
+                    FlatVariable::new(v).into(),
+                ),
+                FlatVariable::new(res),
+            )
+        };
+
+        let ret = Statement::definition(FlatVariable::public(0), FlatVariable::new(0));
+
+        let p: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0), FlatVariable::new(1)],
+                statements: vec![
+                    Statement::Directive(Directive {
+                        inputs: vec![FlatVariable::new(0).into(), FlatVariable::one().into()],
+                        outputs: vec![FlatVariable::new(2)],
+                        solver: Solver::Div,
+                    }),
+                    square(0, 2),
+                    Statement::constraint(
+                        FlatVariable::new(2),
+                        LinComb::summand(2, FlatVariable::new(3)),
+                    ),
+                    square(1, 4),
+                    ret.clone(),
+                ],
+                returns: vec![FlatVariable::public(0)],
+            },
+            functions: vec![],
+            private: vec![true, true],
+            private_outputs: vec![],
+        };
+
+        let expected = Prog {
+            main: Function {
+                statements: vec![square(1, 4), ret],
+                ..p.main.clone()
+            },
+            ..p.clone()
+        };
+
+        assert_eq!(
+            DeadCodeOptimizer::optimize(p, &mut SourceMap::default()),
+            expected
+        );
+    }
+}
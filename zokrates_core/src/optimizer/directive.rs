@@ -31,8 +31,12 @@ impl<T: Field> DirectiveOptimizer<T> {
         }
     }
 
-    pub fn optimize(p: Prog<T>) -> Prog<T> {
-        DirectiveOptimizer::new().fold_module(p)
+    /// Optimizes a program, applying the resulting substitution to `source_map`
+    pub fn optimize(p: Prog<T>, source_map: &mut SourceMap<T>) -> Prog<T> {
+        let mut optimizer = DirectiveOptimizer::new();
+        let p = optimizer.fold_module(p);
+        source_map.substitute(|l| optimizer.fold_linear_combination(l));
+        p
     }
 }
 
@@ -61,6 +65,13 @@ impl<T: Field> Folder<T> for DirectiveOptimizer<T> {
     fn fold_variable(&mut self, v: FlatVariable) -> FlatVariable {
         *self.substitution.get(&v).unwrap_or(&v)
     }
+
+    fn fold_function(&mut self, f: Function<T>) -> Function<T> {
+        // functions do not share variables, so directives are only deduplicated within a function
+        self.calls.clear();
+        self.substitution.clear();
+        fold_function(self, f)
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,6 @@
 //! Module containing the `DuplicateOptimizer` to remove duplicate constraints
 
-use crate::ir::folder::Folder;
+use crate::ir::folder::{fold_function, Folder};
 use crate::ir::*;
 use std::collections::{hash_map::DefaultHasher, HashSet};
 use zokrates_field::Field;
@@ -43,6 +43,12 @@ impl<T: Field> Folder<T> for DuplicateOptimizer {
         self.seen.insert(hashed);
         result
     }
+
+    fn fold_function(&mut self, f: Function<T>) -> Function<T> {
+        // functions do not share variables, so constraints are only deduplicated within a function
+        self.seen.clear();
+        fold_function(self, f)
+    }
 }
 
 #[cfg(test)]
@@ -54,6 +60,7 @@ mod tests {
     #[test]
     fn identity() {
         let p: Prog<Bn128Field> = Prog {
+            functions: vec![],
             private: vec![],
             main: Function {
                 id: "main".to_string(),
@@ -76,6 +83,7 @@ mod tests {
                 returns: vec![],
                 arguments: vec![],
             },
+            private_outputs: vec![],
         };
 
         let expected = p.clone();
@@ -94,6 +102,7 @@ mod tests {
         );
 
         let p: Prog<Bn128Field> = Prog {
+            functions: vec![],
             private: vec![],
             main: Function {
                 id: "main".to_string(),
@@ -113,9 +122,11 @@ mod tests {
                 returns: vec![],
                 arguments: vec![],
             },
+            private_outputs: vec![],
         };
 
         let expected = Prog {
+            functions: vec![],
             private: vec![],
             main: Function {
                 id: "main".to_string(),
@@ -132,6 +143,7 @@ mod tests {
                 returns: vec![],
                 arguments: vec![],
             },
+            private_outputs: vec![],
         };
 
         assert_eq!(DuplicateOptimizer::optimize(p), expected);
@@ -4,29 +4,38 @@
 //! @author Thibaut Schaeffer <thibaut@schaeff.fr>
 //! @date 2018
 
+mod dead_code;
 mod directive;
 mod duplicate;
 mod redefinition;
 mod tautology;
 
+use self::dead_code::DeadCodeOptimizer;
 use self::directive::DirectiveOptimizer;
 use self::duplicate::DuplicateOptimizer;
 use self::redefinition::RedefinitionOptimizer;
 use self::tautology::TautologyOptimizer;
 
-use crate::ir::Prog;
+use crate::ir::{Prog, SourceMap};
 use zokrates_field::Field;
 
 impl<T: Field> Prog<T> {
     pub fn optimize(self) -> Self {
+        self.optimize_with_source_map(&mut SourceMap::default())
+    }
+
+    /// Optimizes the program, updating `source_map` to follow the variables which are substituted away
+    pub fn optimize_with_source_map(self, source_map: &mut SourceMap<T>) -> Self {
         // remove redefinitions
-        let r = RedefinitionOptimizer::optimize(self);
+        let r = RedefinitionOptimizer::optimize(self, source_map);
         // remove constraints that are always satisfied
         let r = TautologyOptimizer::optimize(r);
         // // deduplicate directives which take the same input
-        let r = DirectiveOptimizer::optimize(r);
+        let r = DirectiveOptimizer::optimize(r, source_map);
         // remove duplicate constraints
         let r = DuplicateOptimizer::optimize(r);
+        // remove statements whose variables are never read
+        let r = DeadCodeOptimizer::optimize(r, source_map);
         r
     }
 }
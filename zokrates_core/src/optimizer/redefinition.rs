@@ -28,9 +28,10 @@
 // - input variables are inserted into `i`
 // - the `~one` variable is inserted into `i`
 // - For each directive
-//      - if all directive inputs are of the form as `coeff * ~one`, execute the solver with all `coeff` as inputs, introducing `res`
+//      - if all directive inputs are of the form as `coeff * ~one` and the solver is not an oracle, execute the solver with all `coeff` as inputs, introducing `res`
 //        as the output, and insert `(v, o)` into `s` for `(v, o)` in `(d.outputs, res)`
 //      - else, for each variable `v` introduced, insert `v` into `i`
+// - For each call, we replace its inputs by their value in `s` and insert its outputs into `i`
 // - For each constraint `c`, we replace all variables by their value in `s` if any, otherwise leave them unchanged. Let's call `c_0` the resulting constraint. We either return `c_0` or nothing based on the form of `c_0`:
 //     - `~one * lin == k * v if v isn't in i`: insert `(v, lin / k)` into `s` and return nothing
 //     - `q == k * v if v isn't in i`: insert `v` into `i` and return `c_0`
@@ -40,6 +41,7 @@ use crate::flat_absy::flat_variable::FlatVariable;
 use crate::ir::folder::{fold_function, Folder};
 use crate::ir::LinComb;
 use crate::ir::*;
+use crate::solvers::Solver;
 use std::collections::{HashMap, HashSet};
 use zokrates_field::Field;
 
@@ -59,8 +61,12 @@ impl<T: Field> RedefinitionOptimizer<T> {
         }
     }
 
-    pub fn optimize(p: Prog<T>) -> Prog<T> {
-        RedefinitionOptimizer::new().fold_module(p)
+    /// Optimizes a program, applying the resulting substitution to `source_map`
+    pub fn optimize(p: Prog<T>, source_map: &mut SourceMap<T>) -> Prog<T> {
+        let mut optimizer = RedefinitionOptimizer::new();
+        let p = optimizer.fold_module(p);
+        source_map.substitute(|l| optimizer.fold_linear_combination(l));
+        p
     }
 }
 
@@ -142,15 +148,24 @@ impl<T: Field> Folder<T> for RedefinitionOptimizer<T> {
                     })
                     .collect::<Vec<Result<T, QuadComb<T>>>>();
 
-                match inputs.iter().all(|r| r.is_ok()) {
-                    true => {
-                        // unwrap inputs to their constant value
-                        let inputs = inputs.into_iter().map(|i| i.unwrap()).collect();
-                        // run the interpereter
-                        let outputs = Interpreter::default()
-                            .execute_solver(&d.solver, &inputs)
-                            .unwrap();
+                // oracles are only available during witness computation, and the solvers failing on constant
+                // inputs, such as a division by zero, are kept so that witness computation fails
+                let solver = &d.solver;
+                let outputs = match solver {
+                    Solver::Oracle(..) => None,
+                    _ => inputs
+                        .iter()
+                        .cloned()
+                        .collect::<Result<Vec<_>, _>>()
+                        .ok()
+                        // run the interpreter
+                        .and_then(|inputs| {
+                            Interpreter::default().execute_solver(solver, &inputs).ok()
+                        }),
+                };
 
+                match outputs {
+                    Some(outputs) => {
                         assert_eq!(outputs.len(), d.outputs.len());
 
                         // insert the results in the substitution
@@ -160,7 +175,7 @@ impl<T: Field> Folder<T> for RedefinitionOptimizer<T> {
                         }
                         vec![]
                     }
-                    false => {
+                    None => {
                         // reconstruct the input expressions
                         let inputs = inputs
                             .into_iter()
@@ -177,6 +192,14 @@ impl<T: Field> Folder<T> for RedefinitionOptimizer<T> {
                     }
                 }
             }
+            Statement::Call(c) => {
+                let c = self.fold_call(c);
+                // the outputs of a call are defined by the called function, so they must not be replaced
+                for o in c.outputs.iter().cloned() {
+                    self.ignore.insert(o);
+                }
+                vec![Statement::Call(c)]
+            }
         }
     }
 
@@ -490,4 +513,51 @@ mod tests {
         let mut optimizer = RedefinitionOptimizer::new();
         assert_eq!(optimizer.fold_function(f), optimized);
     }
+
+    #[test]
+    fn keep_call_outputs() {
+        // def main(x):
+        //     y = x
+        //     z = foo(y)
+        //     z == x
+
+        // ->
+
+        // def main(x):
+        //     z = foo(x)
+        //     z == x
+
+        let x = FlatVariable::new(0);
+        let y = FlatVariable::new(1);
+        let z = FlatVariable::new(2);
+
+        let call = |input: FlatVariable| {
+            Statement::Call(Call {
+                function: "foo".to_string(),
+                inputs: vec![input.into()],
+                outputs: vec![z],
+            })
+        };
+
+        let f: Function<Bn128Field> = Function {
+            id: "main".to_string(),
+            arguments: vec![x],
+            statements: vec![
+                Statement::definition(y, x),
+                call(y),
+                Statement::definition(z, x),
+            ],
+            returns: vec![],
+        };
+
+        let optimized: Function<Bn128Field> = Function {
+            id: "main".to_string(),
+            arguments: vec![x],
+            statements: vec![call(x), Statement::definition(z, x)],
+            returns: vec![],
+        };
+
+        let mut optimizer = RedefinitionOptimizer::new();
+        assert_eq!(optimizer.fold_function(f), optimized);
+    }
 }
@@ -5,7 +5,7 @@ use bellman::groth16::{
 use pairing::{CurveAffine, Engine};
 use regex::Regex;
 
-use zokrates_field::Field;
+use zokrates_field::{BellmanFieldExtensions, Field};
 
 use crate::ir;
 use crate::proof_system::bellman::Computation;
@@ -27,7 +27,7 @@ pub struct ProofPoints {
 }
 
 impl ProofPoints {
-    fn into_bellman<T: Field>(self) -> BellmanProof<T::BellmanEngine> {
+    fn into_bellman<T: Field + BellmanFieldExtensions>(self) -> BellmanProof<T::BellmanEngine> {
         BellmanProof {
             a: serialization::to_g1::<T>(self.a),
             b: serialization::to_g2::<T>(self.b),
@@ -46,7 +46,7 @@ pub struct VerificationKey {
 }
 
 impl VerificationKey {
-    fn into_bellman<T: Field>(self) -> VerifyingKey<T::BellmanEngine> {
+    fn into_bellman<T: Field + BellmanFieldExtensions>(self) -> VerifyingKey<T::BellmanEngine> {
         VerifyingKey {
             alpha_g1: serialization::to_g1::<T>(self.alpha),
             beta_g1: <T::BellmanEngine as Engine>::G1Affine::one(), // not used during verification
@@ -63,7 +63,7 @@ impl VerificationKey {
     }
 }
 
-impl<T: Field> ProofSystem<T> for G16 {
+impl<T: Field + BellmanFieldExtensions> ProofSystem<T> for G16 {
     type VerificationKey = VerificationKey;
     type ProofPoints = ProofPoints;
 
@@ -72,7 +72,7 @@ impl<T: Field> ProofSystem<T> for G16 {
         std::env::set_var("BELLMAN_VERBOSE", "0");
         println!("{}", G16_WARNING);
 
-        let parameters = Computation::without_witness(program).setup();
+        let parameters = Computation::without_witness(&program).setup();
 
         let mut pk: Vec<u8> = Vec::new();
 
@@ -95,9 +95,9 @@ impl<T: Field> ProofSystem<T> for G16 {
     }
 
     fn generate_proof(
-        program: ir::Prog<T>,
+        program: &ir::Prog<T>,
         witness: ir::Witness<T>,
-        proving_key: Vec<u8>,
+        proving_key: &[u8],
     ) -> Proof<ProofPoints> {
         #[cfg(not(target_arch = "wasm32"))]
         std::env::set_var("BELLMAN_VERBOSE", "0");
@@ -105,7 +105,7 @@ impl<T: Field> ProofSystem<T> for G16 {
         println!("{}", G16_WARNING);
 
         let computation = Computation::with_witness(program, witness);
-        let params = Parameters::read(proving_key.as_slice(), true).unwrap();
+        let params = Parameters::read(proving_key, true).unwrap();
 
         let proof = computation.clone().prove(&params);
 
@@ -128,106 +128,67 @@ impl<T: Field> ProofSystem<T> for G16 {
     }
 
     fn export_solidity_verifier(vk: VerificationKey, abi: SolidityAbi) -> String {
-        let (mut template_text, solidity_pairing_lib) = match abi {
-            SolidityAbi::V1 => (
-                String::from(CONTRACT_TEMPLATE),
-                String::from(SOLIDITY_PAIRING_LIB),
-            ),
-            SolidityAbi::V2 => (
-                String::from(CONTRACT_TEMPLATE_V2),
-                String::from(SOLIDITY_PAIRING_LIB_V2),
-            ),
+        solidity_verifier(vk, abi, false)
+    }
+
+    fn export_hardened_solidity_verifier(vk: VerificationKey, abi: SolidityAbi) -> Option<String> {
+        Some(solidity_verifier(vk, abi, true))
+    }
+
+    fn export_solidity_dynamic_verifier() -> Option<String> {
+        Some(format!(
+            "{}{}{}",
+            SOLIDITY_G2_ADDITION_LIB, SOLIDITY_PAIRING_LIB_V2, DYNAMIC_CONTRACT_TEMPLATE
+        ))
+    }
+
+    fn export_yul_verifier(vk: VerificationKey) -> Option<String> {
+        let input_count = vk.gamma_abc.len() - 1;
+
+        let input_argument = match input_count {
+            0 => String::new(),
+            n => format!(",\n            uint[{}] calldata input", n),
         };
 
-        let vk_regex = Regex::new(r#"(<%vk_[^i%]*%>)"#).unwrap();
-        let vk_gamma_abc_len_regex = Regex::new(r#"(<%vk_gamma_abc_length%>)"#).unwrap();
-        let vk_gamma_abc_repeat_regex = Regex::new(r#"(<%vk_gamma_abc_pts%>)"#).unwrap();
-        let vk_input_len_regex = Regex::new(r#"(<%vk_input_length%>)"#).unwrap();
-        let input_loop = Regex::new(r#"(<%input_loop%>)"#).unwrap();
-        let input_argument = Regex::new(r#"(<%input_argument%>)"#).unwrap();
-
-        template_text = vk_regex
-            .replace(template_text.as_str(), vk.alpha.to_string().as_str())
-            .into_owned();
-
-        template_text = vk_regex
-            .replace(template_text.as_str(), vk.beta.to_string().as_str())
-            .into_owned();
-
-        template_text = vk_regex
-            .replace(template_text.as_str(), vk.gamma.to_string().as_str())
-            .into_owned();
-
-        template_text = vk_regex
-            .replace(template_text.as_str(), vk.delta.to_string().as_str())
-            .into_owned();
-
-        let gamma_abc_count: usize = vk.gamma_abc.len();
-        template_text = vk_gamma_abc_len_regex
-            .replace(
-                template_text.as_str(),
-                format!("{}", gamma_abc_count).as_str(),
-            )
-            .into_owned();
+        // the inputs follow the 8 words of the proof in the calldata
+        let vk_x = vk
+            .gamma_abc
+            .iter()
+            .skip(1)
+            .enumerate()
+            .map(|(i, g1)| {
+                YUL_INPUT_TEMPLATE
+                    .replace("<%index%>", &i.to_string())
+                    .replace("<%offset%>", &format!("{:#x}", 0x104 + 0x20 * i))
+                    .replace("<%x%>", &g1.0)
+                    .replace("<%y%>", &g1.1)
+            })
+            .collect::<Vec<_>>()
+            .join("");
 
-        template_text = vk_input_len_regex
-            .replace(
-                template_text.as_str(),
-                format!("{}", gamma_abc_count - 1).as_str(),
-            )
-            .into_owned();
-
-        // feed input values only if there are any
-        template_text = if gamma_abc_count > 1 {
-            input_loop.replace(
-                template_text.as_str(),
-                r#"
-        for(uint i = 0; i < input.length; i++){
-            inputValues[i] = input[i];
-        }"#,
-            )
-        } else {
-            input_loop.replace(template_text.as_str(), "")
+        fn g2<'a>(p: &'a G2Affine) -> [&'a String; 4] {
+            [&(p.0).0, &(p.0).1, &(p.1).0, &(p.1).1]
         }
-        .to_string();
 
-        // take input values as argument only if there are any
-        template_text = if gamma_abc_count > 1 {
-            input_argument.replace(
-                template_text.as_str(),
-                format!(", uint[{}] memory input", gamma_abc_count - 1).as_str(),
-            )
-        } else {
-            input_argument.replace(template_text.as_str(), "")
-        }
-        .to_string();
-
-        let mut gamma_abc_repeat_text = String::new();
-        for (i, g1) in vk.gamma_abc.iter().enumerate() {
-            gamma_abc_repeat_text.push_str(
-                format!(
-                    "vk.gamma_abc[{}] = Pairing.G1Point({});",
-                    i,
-                    g1.to_string().as_str()
-                )
-                .as_str(),
-            );
-            if i < gamma_abc_count - 1 {
-                gamma_abc_repeat_text.push_str("\n        ");
+        let mut template_text = YUL_CONTRACT_TEMPLATE
+            .replace("<%input_argument%>", &input_argument)
+            .replace("<%vk_x%>", &vk_x)
+            .replace("<%gamma_abc_x%>", &vk.gamma_abc[0].0)
+            .replace("<%gamma_abc_y%>", &vk.gamma_abc[0].1)
+            .replace("<%alpha_x%>", &vk.alpha.0)
+            .replace("<%alpha_y%>", &vk.alpha.1);
+
+        for (name, point) in &[
+            ("gamma", &vk.gamma),
+            ("delta", &vk.delta),
+            ("beta", &vk.beta),
+        ] {
+            for (i, coordinate) in g2(*point).iter().enumerate() {
+                template_text = template_text.replace(&format!("<%{}_{}%>", name, i), coordinate);
             }
         }
 
-        template_text = vk_gamma_abc_repeat_regex
-            .replace(template_text.as_str(), gamma_abc_repeat_text.as_str())
-            .into_owned();
-
-        let re = Regex::new(r"(?P<v>0[xX][0-9a-fA-F]{64})").unwrap();
-        template_text = re.replace_all(&template_text, "uint256($v)").to_string();
-
-        format!(
-            "{}{}{}",
-            SOLIDITY_G2_ADDITION_LIB, solidity_pairing_lib, template_text
-        )
+        Some(format!("pragma solidity ^0.6.1;\n{}", template_text))
     }
 
     fn verify(vk: VerificationKey, proof: Proof<ProofPoints>) -> bool {
@@ -241,7 +202,7 @@ impl<T: Field> ProofSystem<T> for G16 {
             .inputs
             .iter()
             .map(|s| {
-                T::try_from_str(s.trim_start_matches("0x"), 16)
+                T::try_from_hex_str(s)
                     .expect(format!("Invalid {} value: {}", T::name(), s).as_str())
                     .into_bellman()
             })
@@ -249,21 +210,255 @@ impl<T: Field> ProofSystem<T> for G16 {
 
         verify_proof(&pvk, &bellman_proof, &public_inputs).unwrap()
     }
+
+    fn verify_hardened(
+        vk: VerificationKey,
+        proof: Proof<ProofPoints>,
+    ) -> Option<Result<bool, String>> {
+        Some(verify_hardened::<T>(vk, proof))
+    }
+}
+
+/// Verifies `proof` after checking that its points are in the subgroup of prime order and are not the point at
+/// infinity, and that its inputs are reduced, returning an error otherwise
+fn verify_hardened<T: Field + BellmanFieldExtensions>(
+    vk: VerificationKey,
+    proof: Proof<ProofPoints>,
+) -> Result<bool, String> {
+    let point = |name: &str, e: String| format!("Invalid point {} in the proof: {}", name, e);
+
+    let bellman_proof: BellmanProof<T::BellmanEngine> = BellmanProof {
+        a: serialization::try_to_g1::<T>(&proof.proof.a).map_err(|e| point("a", e))?,
+        b: serialization::try_to_g2::<T>(&proof.proof.b).map_err(|e| point("b", e))?,
+        c: serialization::try_to_g1::<T>(&proof.proof.c).map_err(|e| point("c", e))?,
+    };
+
+    let public_inputs = proof
+        .inputs
+        .iter()
+        .map(|s| {
+            T::try_from_hex_str(s)
+                .map(|v| v.into_bellman())
+                .map_err(|_| format!("Invalid {} value: {}", T::name(), s))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let pvk = prepare_verifying_key(&vk.into_bellman::<T>());
+
+    verify_proof(&pvk, &bellman_proof, &public_inputs).map_err(|e| e.to_string())
 }
 
+/// Exports the Solidity verifier of `vk`, which also rejects malleable encodings of the proof if `hardened` is set
+fn solidity_verifier(vk: VerificationKey, abi: SolidityAbi, hardened: bool) -> String {
+    let (mut template_text, solidity_pairing_lib) = match abi {
+        SolidityAbi::V1 => (
+            String::from(CONTRACT_TEMPLATE),
+            String::from(SOLIDITY_PAIRING_LIB),
+        ),
+        SolidityAbi::V2 => (
+            String::from(CONTRACT_TEMPLATE_V2),
+            String::from(SOLIDITY_PAIRING_LIB_V2),
+        ),
+    };
+
+    let vk_regex = Regex::new(r#"(<%vk_[^i%]*%>)"#).unwrap();
+    let vk_gamma_abc_len_regex = Regex::new(r#"(<%vk_gamma_abc_length%>)"#).unwrap();
+    let vk_gamma_abc_repeat_regex = Regex::new(r#"(<%vk_gamma_abc_pts%>)"#).unwrap();
+    let vk_input_len_regex = Regex::new(r#"(<%vk_input_length%>)"#).unwrap();
+    let input_loop = Regex::new(r#"(<%input_loop%>)"#).unwrap();
+    let input_argument = Regex::new(r#"(<%input_argument%>)"#).unwrap();
+    let batch_input_loop = Regex::new(r#"(<%batch_input_loop%>)"#).unwrap();
+    let batch_input_argument = Regex::new(r#"(<%batch_input_argument%>)"#).unwrap();
+
+    template_text = vk_regex
+        .replace(template_text.as_str(), vk.alpha.to_string().as_str())
+        .into_owned();
+
+    template_text = vk_regex
+        .replace(template_text.as_str(), vk.beta.to_string().as_str())
+        .into_owned();
+
+    template_text = vk_regex
+        .replace(template_text.as_str(), vk.gamma.to_string().as_str())
+        .into_owned();
+
+    template_text = vk_regex
+        .replace(template_text.as_str(), vk.delta.to_string().as_str())
+        .into_owned();
+
+    let gamma_abc_count: usize = vk.gamma_abc.len();
+    template_text = vk_gamma_abc_len_regex
+        .replace(
+            template_text.as_str(),
+            format!("{}", gamma_abc_count).as_str(),
+        )
+        .into_owned();
+
+    template_text = vk_input_len_regex
+        .replace(
+            template_text.as_str(),
+            format!("{}", gamma_abc_count - 1).as_str(),
+        )
+        .into_owned();
+
+    // feed input values only if there are any
+    template_text = if gamma_abc_count > 1 {
+        input_loop.replace(
+            template_text.as_str(),
+            r#"
+    for(uint i = 0; i < input.length; i++){
+        inputValues[i] = input[i];
+    }"#,
+        )
+    } else {
+        input_loop.replace(template_text.as_str(), "")
+    }
+    .to_string();
+
+    // take input values as argument only if there are any
+    template_text = if gamma_abc_count > 1 {
+        input_argument.replace(
+            template_text.as_str(),
+            format!(", uint[{}] memory input", gamma_abc_count - 1).as_str(),
+        )
+    } else {
+        input_argument.replace(template_text.as_str(), "")
+    }
+    .to_string();
+
+    // same for the inputs of each proof of a batch
+    template_text = if gamma_abc_count > 1 {
+        batch_input_loop.replace(
+            template_text.as_str(),
+            r#"
+    require(input.length == proofs.length);
+    for(uint i = 0; i < input.length; i++){
+        inputValues[i] = new uint[](input[i].length);
+        for(uint j = 0; j < input[i].length; j++){
+            inputValues[i][j] = input[i][j];
+        }
+    }"#,
+        )
+    } else {
+        batch_input_loop.replace(template_text.as_str(), "")
+    }
+    .to_string();
+
+    template_text = if gamma_abc_count > 1 {
+        batch_input_argument.replace(
+            template_text.as_str(),
+            format!(", uint[{}][] memory input", gamma_abc_count - 1).as_str(),
+        )
+    } else {
+        batch_input_argument.replace(template_text.as_str(), "")
+    }
+    .to_string();
+
+    let mut gamma_abc_repeat_text = String::new();
+    for (i, g1) in vk.gamma_abc.iter().enumerate() {
+        gamma_abc_repeat_text.push_str(
+            format!(
+                "vk.gamma_abc[{}] = Pairing.G1Point({});",
+                i,
+                g1.to_string().as_str()
+            )
+            .as_str(),
+        );
+        if i < gamma_abc_count - 1 {
+            gamma_abc_repeat_text.push_str("\n        ");
+        }
+    }
+
+    template_text = vk_gamma_abc_repeat_regex
+        .replace(template_text.as_str(), gamma_abc_repeat_text.as_str())
+        .into_owned();
+
+    // check the encoding of the proofs before verifying them only if hardened
+    let (proof_check_function, proof_check, batch_proof_check) = match hardened {
+        true => (
+            HARDENED_PROOF_CHECK_TEMPLATE,
+            "\n        checkProof(proof);",
+            r#"
+        for (uint i = 0; i < proofs.length; i++) {
+            checkProof(proofs[i]);
+        }"#,
+        ),
+        false => ("", "", ""),
+    };
+
+    template_text = template_text
+        .replace("<%proof_check_function%>", proof_check_function)
+        .replace("<%proof_check%>", proof_check)
+        .replace("<%batch_proof_check%>", batch_proof_check);
+
+    let re = Regex::new(r"(?P<v>0[xX][0-9a-fA-F]{64})").unwrap();
+    template_text = re.replace_all(&template_text, "uint256($v)").to_string();
+
+    format!(
+        "{}{}{}",
+        SOLIDITY_G2_ADDITION_LIB, solidity_pairing_lib, template_text
+    )
+}
+
+/// Conversion of the points of the proofs and verification keys to bellman
+///
+/// `to_g1` and `to_g2` panic unless the coordinates are reduced and the point is on the curve, which accepts the point
+/// at infinity, encoded as `(0, 0)`, and points of G2 outside of the subgroup of prime order. `try_to_g1` and
+/// `try_to_g2` reject all of them with an error instead.
 mod serialization {
-    use pairing::{from_hex, CurveAffine, Engine};
+    use pairing::ff::PrimeField;
+    use pairing::{from_hex, CurveAffine, CurveProjective, Engine};
     use proof_system::{G1Affine, G2Affine};
-    use zokrates_field::Field;
+    use zokrates_field::{BellmanFieldExtensions, Field};
+
+    pub fn try_to_g1<T: Field + BellmanFieldExtensions>(
+        g1: &G1Affine,
+    ) -> Result<<T::BellmanEngine as Engine>::G1Affine, String> {
+        let point = <T::BellmanEngine as Engine>::G1Affine::from_xy_checked(
+            from_hex(&g1.0)?,
+            from_hex(&g1.1)?,
+        )
+        .map_err(|e| e.to_string())?;
+        check_subgroup(point)
+    }
+
+    pub fn try_to_g2<T: Field + BellmanFieldExtensions>(
+        g2: &G2Affine,
+    ) -> Result<<T::BellmanEngine as Engine>::G2Affine, String> {
+        // `new_fq2` panics on coordinates which are not reduced
+        for coordinate in &[&(g2.0).0, &(g2.0).1, &(g2.1).0, &(g2.1).1] {
+            from_hex::<<T::BellmanEngine as Engine>::Fq>(coordinate)?;
+        }
+        let x = T::new_fq2(&(g2.0).1, &(g2.0).0);
+        let y = T::new_fq2(&(g2.1).1, &(g2.1).0);
+        let point = <T::BellmanEngine as Engine>::G2Affine::from_xy_checked(x, y)
+            .map_err(|e| e.to_string())?;
+        check_subgroup(point)
+    }
+
+    /// Checks that a point of the curve is in the subgroup of prime order and is not the point at infinity
+    fn check_subgroup<G: CurveAffine>(point: G) -> Result<G, String> {
+        if point.is_zero() {
+            return Err(String::from("the point at infinity is not allowed"));
+        }
+        match point.mul(<G::Scalar as PrimeField>::char()).is_zero() {
+            true => Ok(point),
+            false => Err(String::from("not in the subgroup of prime order")),
+        }
+    }
 
-    pub fn to_g1<T: Field>(g1: G1Affine) -> <T::BellmanEngine as Engine>::G1Affine {
+    pub fn to_g1<T: Field + BellmanFieldExtensions>(
+        g1: G1Affine,
+    ) -> <T::BellmanEngine as Engine>::G1Affine {
         <T::BellmanEngine as Engine>::G1Affine::from_xy_checked(
             from_hex(&g1.0).unwrap(),
             from_hex(&g1.1).unwrap(),
         )
         .unwrap()
     }
-    pub fn to_g2<T: Field>(g2: G2Affine) -> <T::BellmanEngine as Engine>::G2Affine {
+    pub fn to_g2<T: Field + BellmanFieldExtensions>(
+        g2: G2Affine,
+    ) -> <T::BellmanEngine as Engine>::G2Affine {
         // apparently the order is reversed
         let x = T::new_fq2(&(g2.0).1, &(g2.0).0);
         let y = T::new_fq2(&(g2.1).1, &(g2.1).0);
@@ -293,11 +488,11 @@ contract Verifier {
         vk.delta = Pairing.G2Point(<%vk_delta%>);
         vk.gamma_abc = new Pairing.G1Point[](<%vk_gamma_abc_length%>);
         <%vk_gamma_abc_pts%>
-    }
+    }<%proof_check_function%>
     function verify(uint[] memory input, Proof memory proof) internal view returns (uint) {
         uint256 snark_scalar_field = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
         VerifyingKey memory vk = verifyingKey();
-        require(input.length + 1 == vk.gamma_abc.length);
+        require(input.length + 1 == vk.gamma_abc.length);<%proof_check%>
         // Compute the linear combination vk_x
         Pairing.G1Point memory vk_x = Pairing.G1Point(0, 0);
         for (uint i = 0; i < input.length; i++) {
@@ -312,6 +507,65 @@ contract Verifier {
              Pairing.negate(vk.alpha), vk.beta)) return 1;
         return 0;
     }
+    /// Derives one random coefficient per proof from all proofs and inputs of a batch, so that invalid proofs cannot
+    /// be chosen to cancel out
+    function batchCoefficients(uint[][] memory inputs, Proof[] memory proofs) internal pure returns (uint[] memory r) {
+        uint256 snark_scalar_field = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
+        uint256 seed = 0;
+        for (uint i = 0; i < proofs.length; i++) {
+            seed = uint256(keccak256(abi.encodePacked(
+                seed,
+                proofs[i].a.X, proofs[i].a.Y,
+                proofs[i].b.X, proofs[i].b.Y,
+                proofs[i].c.X, proofs[i].c.Y,
+                inputs[i])));
+        }
+        r = new uint[](proofs.length);
+        for (uint i = 0; i < proofs.length; i++) {
+            r[i] = uint256(keccak256(abi.encodePacked(seed, i))) % snark_scalar_field;
+        }
+    }
+    /// Accumulates the scalars of each point of gamma_abc over the batch, so that they are multiplied only once
+    function batchScalars(uint[][] memory inputs, uint[] memory r, uint length) internal pure returns (uint[] memory scalars) {
+        uint256 snark_scalar_field = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
+        scalars = new uint[](length);
+        for (uint i = 0; i < inputs.length; i++) {
+            require(inputs[i].length + 1 == length);
+            scalars[0] = addmod(scalars[0], r[i], snark_scalar_field);
+            for (uint j = 0; j < inputs[i].length; j++) {
+                require(inputs[i][j] < snark_scalar_field);
+                scalars[j + 1] = addmod(scalars[j + 1], mulmod(r[i], inputs[i][j], snark_scalar_field), snark_scalar_field);
+            }
+        }
+    }
+    function batchVerify(uint[][] memory inputs, Proof[] memory proofs) internal view returns (uint) {
+        VerifyingKey memory vk = verifyingKey();
+        require(proofs.length > 0 && inputs.length == proofs.length);<%batch_proof_check%>
+        uint[] memory r = batchCoefficients(inputs, proofs);
+        uint[] memory scalars = batchScalars(inputs, r, vk.gamma_abc.length);
+        // Check that the product of e(r_i * a_i, b_i) equals
+        // e(sum(r_i) * alpha, beta) * e(sum(r_i * vk_x_i), gamma) * e(sum(r_i * c_i), delta),
+        // using one pairing per proof and three shared ones
+        uint n = proofs.length;
+        Pairing.G1Point[] memory p1 = new Pairing.G1Point[](n + 3);
+        Pairing.G2Point[] memory p2 = new Pairing.G2Point[](n + 3);
+        for (uint i = 0; i < n; i++) {
+            p1[i] = Pairing.scalar_mul(proofs[i].a, r[i]);
+            p2[i] = proofs[i].b;
+            p1[n + 1] = Pairing.addition(p1[n + 1], Pairing.scalar_mul(proofs[i].c, r[i]));
+        }
+        for (uint j = 0; j < vk.gamma_abc.length; j++) {
+            p1[n] = Pairing.addition(p1[n], Pairing.scalar_mul(vk.gamma_abc[j], scalars[j]));
+        }
+        p1[n] = Pairing.negate(p1[n]);
+        p2[n] = vk.gamma;
+        p1[n + 1] = Pairing.negate(p1[n + 1]);
+        p2[n + 1] = vk.delta;
+        p1[n + 2] = Pairing.negate(Pairing.scalar_mul(vk.alpha, scalars[0]));
+        p2[n + 2] = vk.beta;
+        if(!Pairing.pairing(p1, p2)) return 1;
+        return 0;
+    }
     function verifyTx(
             Proof memory proof<%input_argument%>
         ) public view returns (bool r) {
@@ -323,9 +577,166 @@ contract Verifier {
             return false;
         }
     }
+    function verifyBatch(
+            Proof[] memory proofs<%batch_input_argument%>
+        ) public view returns (bool r) {
+        uint[][] memory inputValues = new uint[][](proofs.length);
+        <%batch_input_loop%>
+        if (batchVerify(inputValues, proofs) == 0) {
+            return true;
+        } else {
+            return false;
+        }
+    }
 }
 "#;
 
+const DYNAMIC_CONTRACT_TEMPLATE: &str = r#"
+contract Verifier {
+    using Pairing for *;
+    struct VerifyingKey {
+        Pairing.G1Point alpha;
+        Pairing.G2Point beta;
+        Pairing.G2Point gamma;
+        Pairing.G2Point delta;
+        Pairing.G1Point[] gamma_abc;
+    }
+    struct Proof {
+        Pairing.G1Point a;
+        Pairing.G2Point b;
+        Pairing.G1Point c;
+    }
+    function verify(uint[] memory input, Proof memory proof, VerifyingKey memory vk) internal view returns (uint) {
+        uint256 snark_scalar_field = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
+        require(input.length + 1 == vk.gamma_abc.length);
+        // Compute the linear combination vk_x
+        Pairing.G1Point memory vk_x = Pairing.G1Point(0, 0);
+        for (uint i = 0; i < input.length; i++) {
+            require(input[i] < snark_scalar_field);
+            vk_x = Pairing.addition(vk_x, Pairing.scalar_mul(vk.gamma_abc[i + 1], input[i]));
+        }
+        vk_x = Pairing.addition(vk_x, vk.gamma_abc[0]);
+        if(!Pairing.pairingProd4(
+             proof.a, proof.b,
+             Pairing.negate(vk_x), vk.gamma,
+             Pairing.negate(proof.c), vk.delta,
+             Pairing.negate(vk.alpha), vk.beta)) return 1;
+        return 0;
+    }
+    /// @return r whether the proof is valid for the verification key passed as calldata
+    function verifyTx(
+            Proof memory proof,
+            uint[] memory input,
+            VerifyingKey memory vk
+        ) public view returns (bool r) {
+        return verify(input, proof, vk) == 0;
+    }
+    /// @return r whether the proof is valid for the verification key stored under `id` in `registry`
+    function verifyTxWithRegistry(
+            VerificationKeyRegistry registry,
+            bytes32 id,
+            Proof memory proof,
+            uint[] memory input
+        ) public view returns (bool r) {
+        return verify(input, proof, registry.getKey(id)) == 0;
+    }
+}
+/// Stores verification keys by id. Only the owner can set them, which lets it upgrade circuits without redeploying
+/// the verifier.
+contract VerificationKeyRegistry {
+    address public owner;
+    // keys are stored ABI encoded, as arrays of structs cannot be copied to storage
+    mapping(bytes32 => bytes) keys;
+    event KeySet(bytes32 indexed id);
+    constructor() public {
+        owner = msg.sender;
+    }
+    function setKey(bytes32 id, Verifier.VerifyingKey memory vk) public {
+        require(msg.sender == owner);
+        keys[id] = abi.encode(vk);
+        emit KeySet(id);
+    }
+    function getKey(bytes32 id) public view returns (Verifier.VerifyingKey memory) {
+        require(keys[id].length > 0);
+        return abi.decode(keys[id], (Verifier.VerifyingKey));
+    }
+}
+"#;
+
+const HARDENED_PROOF_CHECK_TEMPLATE: &str = r#"
+    /// Rejects the proofs whose coordinates are not reduced, as they are reduced by the precompiles and by `negate`
+    /// into the ones of another proof, and the proofs containing the point at infinity. The precompiles check that
+    /// the points are on the curve, and that `b` is in the subgroup of prime order, which for `a` and `c` is the whole
+    /// curve.
+    function checkProof(Proof memory proof) internal pure {
+        uint256 p = 21888242871839275222246405745257275088696311157297823662689037894645226208583;
+        require(proof.a.X < p && proof.a.Y < p && proof.c.X < p && proof.c.Y < p);
+        require(proof.b.X[0] < p && proof.b.X[1] < p && proof.b.Y[0] < p && proof.b.Y[1] < p);
+        require(proof.a.X != 0 || proof.a.Y != 0);
+        require(proof.b.X[0] != 0 || proof.b.X[1] != 0 || proof.b.Y[0] != 0 || proof.b.Y[1] != 0);
+        require(proof.c.X != 0 || proof.c.Y != 0);
+    }"#;
+
+const YUL_CONTRACT_TEMPLATE: &str = r#"
+contract Verifier {
+    /// Verifies a proof against the verification key inlined below. The arguments are read directly from the calldata,
+    /// where the ABI places them at fixed offsets: a at 0x04, b at 0x44, c at 0xc4 and the inputs from 0x104.
+    function verifyTx(
+            uint[2] calldata a,
+            uint[2][2] calldata b,
+            uint[2] calldata c<%input_argument%>
+        ) external view returns (bool r) {
+        assembly {
+            // the scalar field of the inputs and the base field of the points
+            let q := 21888242871839275222246405745257275088548364400416034343698204186575808495617
+            let p := 21888242871839275222246405745257275088696311157297823662689037894645226208583
+            let m := mload(0x40)
+            let success := 1
+            // vk_x = gamma_abc[0] + sum(input[i] * gamma_abc[i + 1]) is accumulated at m
+            mstore(m, <%gamma_abc_x%>)
+            mstore(add(m, 0x20), <%gamma_abc_y%>)<%vk_x%>
+            // check e(a, b) * e(-vk_x, gamma) * e(-c, delta) * e(-alpha, beta) == 1
+            let e := add(m, 0xa0)
+            calldatacopy(e, 0x04, 0xc0)
+            mstore(add(e, 0xc0), mload(m))
+            mstore(add(e, 0xe0), mod(sub(p, mload(add(m, 0x20))), p))
+            mstore(add(e, 0x100), <%gamma_0%>)
+            mstore(add(e, 0x120), <%gamma_1%>)
+            mstore(add(e, 0x140), <%gamma_2%>)
+            mstore(add(e, 0x160), <%gamma_3%>)
+            if iszero(lt(calldataload(0xe4), p)) { revert(0, 0) }
+            mstore(add(e, 0x180), calldataload(0xc4))
+            mstore(add(e, 0x1a0), mod(sub(p, calldataload(0xe4)), p))
+            mstore(add(e, 0x1c0), <%delta_0%>)
+            mstore(add(e, 0x1e0), <%delta_1%>)
+            mstore(add(e, 0x200), <%delta_2%>)
+            mstore(add(e, 0x220), <%delta_3%>)
+            mstore(add(e, 0x240), <%alpha_x%>)
+            mstore(add(e, 0x260), mod(sub(p, <%alpha_y%>), p))
+            mstore(add(e, 0x280), <%beta_0%>)
+            mstore(add(e, 0x2a0), <%beta_1%>)
+            mstore(add(e, 0x2c0), <%beta_2%>)
+            mstore(add(e, 0x2e0), <%beta_3%>)
+            success := and(success, staticcall(gas(), 8, e, 0x300, e, 0x20))
+            if iszero(success) { revert(0, 0) }
+            r := mload(e)
+        }
+    }
+}
+"#;
+
+const YUL_INPUT_TEMPLATE: &str = r#"
+            // input[<%index%>]
+            {
+                let s := calldataload(<%offset%>)
+                if iszero(lt(s, q)) { revert(0, 0) }
+                mstore(add(m, 0x40), <%x%>)
+                mstore(add(m, 0x60), <%y%>)
+                mstore(add(m, 0x80), s)
+                success := and(success, staticcall(gas(), 7, add(m, 0x40), 0x60, add(m, 0x40), 0x40))
+                success := and(success, staticcall(gas(), 6, m, 0x80, m, 0x40))
+            }"#;
+
 const CONTRACT_TEMPLATE: &str = r#"
 contract Verifier {
     using Pairing for *;
@@ -348,11 +759,11 @@ contract Verifier {
         vk.delta = Pairing.G2Point(<%vk_delta%>);
         vk.gamma_abc = new Pairing.G1Point[](<%vk_gamma_abc_length%>);
         <%vk_gamma_abc_pts%>
-    }
+    }<%proof_check_function%>
     function verify(uint[] memory input, Proof memory proof) internal view returns (uint) {
         uint256 snark_scalar_field = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
         VerifyingKey memory vk = verifyingKey();
-        require(input.length + 1 == vk.gamma_abc.length);
+        require(input.length + 1 == vk.gamma_abc.length);<%proof_check%>
         // Compute the linear combination vk_x
         Pairing.G1Point memory vk_x = Pairing.G1Point(0, 0);
         for (uint i = 0; i < input.length; i++) {
@@ -367,6 +778,65 @@ contract Verifier {
              Pairing.negate(vk.alpha), vk.beta)) return 1;
         return 0;
     }
+    /// Derives one random coefficient per proof from all proofs and inputs of a batch, so that invalid proofs cannot
+    /// be chosen to cancel out
+    function batchCoefficients(uint[][] memory inputs, Proof[] memory proofs) internal pure returns (uint[] memory r) {
+        uint256 snark_scalar_field = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
+        uint256 seed = 0;
+        for (uint i = 0; i < proofs.length; i++) {
+            seed = uint256(keccak256(abi.encodePacked(
+                seed,
+                proofs[i].a.X, proofs[i].a.Y,
+                proofs[i].b.X, proofs[i].b.Y,
+                proofs[i].c.X, proofs[i].c.Y,
+                inputs[i])));
+        }
+        r = new uint[](proofs.length);
+        for (uint i = 0; i < proofs.length; i++) {
+            r[i] = uint256(keccak256(abi.encodePacked(seed, i))) % snark_scalar_field;
+        }
+    }
+    /// Accumulates the scalars of each point of gamma_abc over the batch, so that they are multiplied only once
+    function batchScalars(uint[][] memory inputs, uint[] memory r, uint length) internal pure returns (uint[] memory scalars) {
+        uint256 snark_scalar_field = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
+        scalars = new uint[](length);
+        for (uint i = 0; i < inputs.length; i++) {
+            require(inputs[i].length + 1 == length);
+            scalars[0] = addmod(scalars[0], r[i], snark_scalar_field);
+            for (uint j = 0; j < inputs[i].length; j++) {
+                require(inputs[i][j] < snark_scalar_field);
+                scalars[j + 1] = addmod(scalars[j + 1], mulmod(r[i], inputs[i][j], snark_scalar_field), snark_scalar_field);
+            }
+        }
+    }
+    function batchVerify(uint[][] memory inputs, Proof[] memory proofs) internal view returns (uint) {
+        VerifyingKey memory vk = verifyingKey();
+        require(proofs.length > 0 && inputs.length == proofs.length);<%batch_proof_check%>
+        uint[] memory r = batchCoefficients(inputs, proofs);
+        uint[] memory scalars = batchScalars(inputs, r, vk.gamma_abc.length);
+        // Check that the product of e(r_i * a_i, b_i) equals
+        // e(sum(r_i) * alpha, beta) * e(sum(r_i * vk_x_i), gamma) * e(sum(r_i * c_i), delta),
+        // using one pairing per proof and three shared ones
+        uint n = proofs.length;
+        Pairing.G1Point[] memory p1 = new Pairing.G1Point[](n + 3);
+        Pairing.G2Point[] memory p2 = new Pairing.G2Point[](n + 3);
+        for (uint i = 0; i < n; i++) {
+            p1[i] = Pairing.scalar_mul(proofs[i].a, r[i]);
+            p2[i] = proofs[i].b;
+            p1[n + 1] = Pairing.addition(p1[n + 1], Pairing.scalar_mul(proofs[i].c, r[i]));
+        }
+        for (uint j = 0; j < vk.gamma_abc.length; j++) {
+            p1[n] = Pairing.addition(p1[n], Pairing.scalar_mul(vk.gamma_abc[j], scalars[j]));
+        }
+        p1[n] = Pairing.negate(p1[n]);
+        p2[n] = vk.gamma;
+        p1[n + 1] = Pairing.negate(p1[n + 1]);
+        p2[n + 1] = vk.delta;
+        p1[n + 2] = Pairing.negate(Pairing.scalar_mul(vk.alpha, scalars[0]));
+        p2[n + 2] = vk.beta;
+        if(!Pairing.pairing(p1, p2)) return 1;
+        return 0;
+    }
     function verifyTx(
             uint[2] memory a,
             uint[2][2] memory b,
@@ -384,6 +854,26 @@ contract Verifier {
             return false;
         }
     }
+    function verifyBatch(
+            uint[2][] memory a,
+            uint[2][2][] memory b,
+            uint[2][] memory c<%batch_input_argument%>
+        ) public view returns (bool r) {
+        require(b.length == a.length && c.length == a.length);
+        Proof[] memory proofs = new Proof[](a.length);
+        for (uint i = 0; i < a.length; i++) {
+            proofs[i].a = Pairing.G1Point(a[i][0], a[i][1]);
+            proofs[i].b = Pairing.G2Point([b[i][0][0], b[i][0][1]], [b[i][1][0], b[i][1][1]]);
+            proofs[i].c = Pairing.G1Point(c[i][0], c[i][1]);
+        }
+        uint[][] memory inputValues = new uint[][](proofs.length);
+        <%batch_input_loop%>
+        if (batchVerify(inputValues, proofs) == 0) {
+            return true;
+        } else {
+            return false;
+        }
+    }
 }
 "#;
 
@@ -407,7 +897,9 @@ mod tests {
                     FlatVariable::public(0).into(),
                 )],
             },
+            functions: vec![],
             private: vec![false],
+            private_outputs: vec![],
         };
 
         let keypair = G16::setup(program.clone());
@@ -418,9 +910,120 @@ mod tests {
             .execute(&program, &vec![Bn128Field::from(42)])
             .unwrap();
 
-        let proof = G16::generate_proof(program, witness, keypair.pk);
+        let proof = G16::generate_proof(&program, witness, &keypair.pk);
         let ans = <G16 as ProofSystem<Bn128Field>>::verify(keypair.vk, proof);
 
         assert!(ans);
     }
+
+    #[test]
+    fn verify_hardened() {
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                returns: vec![FlatVariable::public(0)],
+                statements: vec![Statement::Constraint(
+                    FlatVariable::new(0).into(),
+                    FlatVariable::public(0).into(),
+                )],
+            },
+            functions: vec![],
+            private: vec![false],
+            private_outputs: vec![],
+        };
+
+        let keypair = G16::setup(program.clone());
+
+        let witness = Interpreter::default()
+            .execute(&program, &vec![Bn128Field::from(42)])
+            .unwrap();
+
+        // the keys and proofs are not `Clone`
+        let vk = serde_json::to_string(&keypair.vk).unwrap();
+        let proof = G16::generate_proof(&program, witness, &keypair.pk);
+        let proof = serde_json::to_string(&proof).unwrap();
+
+        let vk = || -> VerificationKey { serde_json::from_str(&vk).unwrap() };
+        let proof = || -> Proof<ProofPoints> { serde_json::from_str(&proof).unwrap() };
+
+        assert_eq!(
+            <G16 as ProofSystem<Bn128Field>>::verify_hardened(vk(), proof()),
+            Some(Ok(true))
+        );
+
+        // the point at infinity is rejected
+        let mut identity = proof();
+        identity.proof.a = G1Affine(String::from("0x00"), String::from("0x00"));
+        assert!(
+            <G16 as ProofSystem<Bn128Field>>::verify_hardened(vk(), identity)
+                .unwrap()
+                .is_err()
+        );
+
+        // the input plus the modulus is rejected instead of reduced
+        let mut unreduced = proof();
+        unreduced.inputs = vec![String::from(
+            "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f000002b",
+        )];
+        assert!(
+            <G16 as ProofSystem<Bn128Field>>::verify_hardened(vk(), unreduced)
+                .unwrap()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn export_verifiers() {
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                returns: vec![FlatVariable::public(0)],
+                statements: vec![Statement::Constraint(
+                    FlatVariable::new(0).into(),
+                    FlatVariable::public(0).into(),
+                )],
+            },
+            functions: vec![],
+            private: vec![false],
+            private_outputs: vec![],
+        };
+
+        for abi in vec![SolidityAbi::V1, SolidityAbi::V2] {
+            let keypair = G16::setup(program.clone());
+            let verifier =
+                <G16 as ProofSystem<Bn128Field>>::export_solidity_verifier(keypair.vk, abi);
+
+            assert!(!verifier.contains("<%"));
+            assert!(verifier.contains("function verifyBatch("));
+            assert!(!verifier.contains("checkProof"));
+            // inputs which are not reduced are rejected, so that each input has a single encoding
+            assert!(verifier.contains("require(input[i] < snark_scalar_field);"));
+            assert!(verifier.contains("require(inputs[i][j] < snark_scalar_field);"));
+        }
+
+        for abi in vec![SolidityAbi::V1, SolidityAbi::V2] {
+            let keypair = G16::setup(program.clone());
+            let verifier = <G16 as ProofSystem<Bn128Field>>::export_hardened_solidity_verifier(
+                keypair.vk, abi,
+            )
+            .unwrap();
+
+            assert!(!verifier.contains("<%"));
+            assert!(verifier.contains("checkProof(proof);"));
+            assert!(verifier.contains("checkProof(proofs[i]);"));
+        }
+
+        let verifier =
+            <G16 as ProofSystem<Bn128Field>>::export_solidity_dynamic_verifier().unwrap();
+        assert!(verifier.contains("contract VerificationKeyRegistry"));
+        assert!(verifier.contains("require(input[i] < snark_scalar_field);"));
+
+        let keypair = G16::setup(program);
+        let verifier = <G16 as ProofSystem<Bn128Field>>::export_yul_verifier(keypair.vk).unwrap();
+        assert!(!verifier.contains("<%"));
+        assert!(verifier.contains("calldataload(0x104)"));
+        assert!(verifier.contains("if iszero(lt(s, q)) { revert(0, 0) }"));
+    }
 }
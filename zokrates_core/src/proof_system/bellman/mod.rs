@@ -11,7 +11,7 @@ use bellman::groth16::{
 use bellman::pairing::ff::ScalarEngine;
 use bellman::{Circuit, ConstraintSystem, LinearCombination, SynthesisError, Variable};
 use std::collections::BTreeMap;
-use zokrates_field::Field;
+use zokrates_field::{BellmanFieldExtensions, Field};
 
 use self::rand::ChaChaRng;
 use crate::flat_absy::FlatVariable;
@@ -19,20 +19,20 @@ use crate::flat_absy::FlatVariable;
 pub use self::parse::*;
 
 #[derive(Clone)]
-pub struct Computation<T> {
-    program: Prog<T>,
+pub struct Computation<'a, T> {
+    program: &'a Prog<T>,
     witness: Option<Witness<T>>,
 }
 
-impl<T: Field> Computation<T> {
-    pub fn with_witness(program: Prog<T>, witness: Witness<T>) -> Self {
+impl<'a, T: Field + BellmanFieldExtensions> Computation<'a, T> {
+    pub fn with_witness(program: &'a Prog<T>, witness: Witness<T>) -> Self {
         Computation {
             program,
             witness: Some(witness),
         }
     }
 
-    pub fn without_witness(program: Prog<T>) -> Self {
+    pub fn without_witness(program: &'a Prog<T>) -> Self {
         Computation {
             program,
             witness: None,
@@ -40,7 +40,10 @@ impl<T: Field> Computation<T> {
     }
 }
 
-fn bellman_combination<T: Field, CS: ConstraintSystem<T::BellmanEngine>>(
+fn bellman_combination<
+    T: Field + BellmanFieldExtensions,
+    CS: ConstraintSystem<T::BellmanEngine>,
+>(
     l: CanonicalLinComb<T>,
     cs: &mut CS,
     symbols: &mut BTreeMap<FlatVariable, Variable>,
@@ -83,12 +86,15 @@ fn bellman_combination<T: Field, CS: ConstraintSystem<T::BellmanEngine>>(
         .fold(LinearCombination::zero(), |acc, e| acc + e)
 }
 
-impl<T: Field> Prog<T> {
+impl<T: Field + BellmanFieldExtensions> Prog<T> {
     pub fn synthesize<CS: ConstraintSystem<T::BellmanEngine>>(
-        self,
+        &self,
         cs: &mut CS,
         witness: Option<Witness<T>>,
     ) -> Result<(), SynthesisError> {
+        // the constraints of called functions are synthesized at each call
+        let program = self.expanded();
+
         // mapping from IR variables
         let mut symbols = BTreeMap::new();
 
@@ -97,13 +103,14 @@ impl<T: Field> Prog<T> {
         assert!(symbols.insert(FlatVariable::one(), CS::one()).is_none());
 
         symbols.extend(
-            self.main
+            program
+                .main
                 .arguments
                 .iter()
-                .zip(self.private)
+                .zip(program.private.iter())
                 .enumerate()
                 .map(|(index, (var, private))| {
-                    let wire = match private {
+                    let wire = match *private {
                         true => cs.alloc(
                             || format!("PRIVATE_INPUT_{}", index),
                             || {
@@ -130,25 +137,46 @@ impl<T: Field> Prog<T> {
                 }),
         );
 
-        let main = self.main;
+        // private outputs are not part of the public inputs, so we allocate them as auxiliary variables
+        // public outputs are allocated lazily when first encountered
+        symbols.extend(program.private_outputs.iter().map(|index| {
+            let var = FlatVariable::public(*index);
+            let wire = cs
+                .alloc(
+                    || format!("{}", var),
+                    || {
+                        Ok(witness
+                            .0
+                            .remove(&var)
+                            .ok_or(SynthesisError::AssignmentMissing)?
+                            .into_bellman())
+                    },
+                )
+                .unwrap();
+            (var, wire)
+        }));
 
-        for statement in main.statements {
+        for statement in &program.main.statements {
             match statement {
                 Statement::Constraint(quad, lin) => {
                     let a = &bellman_combination(
-                        quad.left.into_canonical(),
+                        quad.left.clone().into_canonical(),
                         cs,
                         &mut symbols,
                         &mut witness,
                     );
                     let b = &bellman_combination(
-                        quad.right.into_canonical(),
+                        quad.right.clone().into_canonical(),
+                        cs,
+                        &mut symbols,
+                        &mut witness,
+                    );
+                    let c = &bellman_combination(
+                        lin.clone().into_canonical(),
                         cs,
                         &mut symbols,
                         &mut witness,
                     );
-                    let c =
-                        &bellman_combination(lin.into_canonical(), cs, &mut symbols, &mut witness);
 
                     cs.enforce(|| "Constraint", |lc| lc + a, |lc| lc + b, |lc| lc + c);
                 }
@@ -160,7 +188,7 @@ impl<T: Field> Prog<T> {
     }
 }
 
-impl<T: Field> Computation<T> {
+impl<'a, T: Field + BellmanFieldExtensions> Computation<'a, T> {
     pub fn prove(self, params: &Parameters<T::BellmanEngine>) -> Proof<T::BellmanEngine> {
         let rng = &mut ChaChaRng::new_unseeded();
 
@@ -186,7 +214,16 @@ impl<T: Field> Computation<T> {
             .filter(|(_, p)| !p)
             .map(|(a, _)| a)
             .map(|v| self.witness.clone().unwrap().0.get(v).unwrap().clone())
-            .chain(self.witness.clone().unwrap().return_values())
+            .chain(
+                self.witness
+                    .clone()
+                    .unwrap()
+                    .return_values()
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(index, _)| !self.program.is_private_output(*index))
+                    .map(|(_, v)| v),
+            )
             .map(|v| v.clone().into_bellman())
             .collect()
     }
@@ -198,7 +235,7 @@ impl<T: Field> Computation<T> {
     }
 }
 
-impl<T: Field> Circuit<T::BellmanEngine> for Computation<T> {
+impl<'a, T: Field + BellmanFieldExtensions> Circuit<T::BellmanEngine> for Computation<'a, T> {
     fn synthesize<CS: ConstraintSystem<T::BellmanEngine>>(
         self,
         cs: &mut CS,
@@ -228,7 +265,7 @@ mod parse {
         static ref FR_REGEX: Regex = Regex::new(r"Fr\((?P<x>0[xX][0-9a-fA-F]*)\)").unwrap();
     }
 
-    pub fn parse_g1<T: Field>(
+    pub fn parse_g1<T: Field + BellmanFieldExtensions>(
         e: &<T::BellmanEngine as bellman::pairing::Engine>::G1Affine,
     ) -> G1Affine {
         let raw_e = e.to_string();
@@ -239,7 +276,7 @@ mod parse {
         )
     }
 
-    pub fn parse_g2<T: Field>(
+    pub fn parse_g2<T: Field + BellmanFieldExtensions>(
         e: &<T::BellmanEngine as bellman::pairing::Engine>::G2Affine,
     ) -> G2Affine {
         let raw_e = e.to_string();
@@ -256,7 +293,9 @@ mod parse {
         )
     }
 
-    pub fn parse_fr<T: Field>(e: &<T::BellmanEngine as ScalarEngine>::Fr) -> String {
+    pub fn parse_fr<T: Field + BellmanFieldExtensions>(
+        e: &<T::BellmanEngine as ScalarEngine>::Fr,
+    ) -> String {
         let raw_e = e.to_string();
         let captures = FR_REGEX.captures(&raw_e).unwrap();
         captures.name(&"x").unwrap().as_str().to_string()
@@ -282,13 +321,15 @@ mod tests {
                     returns: vec![],
                     statements: vec![],
                 },
+                functions: vec![],
                 private: vec![],
+                private_outputs: vec![],
             };
 
             let interpreter = Interpreter::default();
 
             let witness = interpreter.execute(&program, &vec![]).unwrap();
-            let computation = Computation::with_witness(program, witness);
+            let computation = Computation::with_witness(&program, witness);
 
             let params = computation.clone().setup();
             let _proof = computation.prove(&params);
@@ -306,7 +347,9 @@ mod tests {
                         FlatVariable::public(0).into(),
                     )],
                 },
+                functions: vec![],
                 private: vec![true],
+                private_outputs: vec![],
             };
 
             let interpreter = Interpreter::default();
@@ -315,7 +358,45 @@ mod tests {
                 .execute(&program, &vec![Bn128Field::from(0)])
                 .unwrap();
 
-            let computation = Computation::with_witness(program, witness);
+            let computation = Computation::with_witness(&program, witness);
+
+            let params = computation.clone().setup();
+            let _proof = computation.prove(&params);
+        }
+
+        #[test]
+        fn private_output() {
+            let program: Prog<Bn128Field> = Prog {
+                main: Function {
+                    id: String::from("main"),
+                    arguments: vec![FlatVariable::new(0)],
+                    returns: vec![FlatVariable::public(0), FlatVariable::public(1)],
+                    statements: vec![
+                        Statement::Constraint(
+                            FlatVariable::new(0).into(),
+                            FlatVariable::public(0).into(),
+                        ),
+                        Statement::Constraint(
+                            FlatVariable::new(0).into(),
+                            FlatVariable::public(1).into(),
+                        ),
+                    ],
+                },
+                functions: vec![],
+                private: vec![false],
+                private_outputs: vec![0],
+            };
+
+            let interpreter = Interpreter::default();
+
+            let witness = interpreter
+                .execute(&program, &vec![Bn128Field::from(42)])
+                .unwrap();
+
+            let computation = Computation::with_witness(&program, witness);
+
+            // the public argument and the public output
+            assert_eq!(computation.public_inputs_values().len(), 2);
 
             let params = computation.clone().setup();
             let _proof = computation.prove(&params);
@@ -333,7 +414,9 @@ mod tests {
                         FlatVariable::public(0).into(),
                     )],
                 },
+                functions: vec![],
                 private: vec![false],
+                private_outputs: vec![],
             };
 
             let interpreter = Interpreter::default();
@@ -342,7 +425,7 @@ mod tests {
                 .execute(&program, &vec![Bn128Field::from(0)])
                 .unwrap();
 
-            let computation = Computation::with_witness(program, witness);
+            let computation = Computation::with_witness(&program, witness);
 
             let params = computation.clone().setup();
             let _proof = computation.prove(&params);
@@ -360,13 +443,15 @@ mod tests {
                         FlatVariable::public(0).into(),
                     )],
                 },
+                functions: vec![],
                 private: vec![],
+                private_outputs: vec![],
             };
 
             let interpreter = Interpreter::default();
 
             let witness = interpreter.execute(&program, &vec![]).unwrap();
-            let computation = Computation::with_witness(program, witness);
+            let computation = Computation::with_witness(&program, witness);
 
             let params = computation.clone().setup();
             let _proof = computation.prove(&params);
@@ -396,7 +481,9 @@ mod tests {
                         ),
                     ],
                 },
+                functions: vec![],
                 private: vec![true, false],
+                private_outputs: vec![],
             };
 
             let interpreter = Interpreter::default();
@@ -404,7 +491,7 @@ mod tests {
             let witness = interpreter
                 .execute(&program, &vec![Bn128Field::from(3), Bn128Field::from(4)])
                 .unwrap();
-            let computation = Computation::with_witness(program, witness);
+            let computation = Computation::with_witness(&program, witness);
 
             let params = computation.clone().setup();
             let _proof = computation.prove(&params);
@@ -422,7 +509,9 @@ mod tests {
                         FlatVariable::public(0).into(),
                     )],
                 },
+                functions: vec![],
                 private: vec![false],
+                private_outputs: vec![],
             };
 
             let interpreter = Interpreter::default();
@@ -431,7 +520,7 @@ mod tests {
                 .execute(&program, &vec![Bn128Field::from(3)])
                 .unwrap();
 
-            let computation = Computation::with_witness(program, witness);
+            let computation = Computation::with_witness(&program, witness);
 
             let params = computation.clone().setup();
             let _proof = computation.prove(&params);
@@ -451,7 +540,9 @@ mod tests {
                         FlatVariable::public(0).into(),
                     )],
                 },
+                functions: vec![],
                 private: vec![true, false],
+                private_outputs: vec![],
             };
 
             let interpreter = Interpreter::default();
@@ -459,7 +550,7 @@ mod tests {
             let witness = interpreter
                 .execute(&program, &vec![Bn128Field::from(3), Bn128Field::from(4)])
                 .unwrap();
-            let computation = Computation::with_witness(program, witness);
+            let computation = Computation::with_witness(&program, witness);
 
             let params = computation.clone().setup();
             let _proof = computation.prove(&params);
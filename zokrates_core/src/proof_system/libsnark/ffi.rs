@@ -20,9 +20,9 @@ extern "C" {
 }
 
 impl Buffer {
-    pub fn from_vec(v: &Vec<u8>) -> Buffer {
+    pub fn from_vec(v: &[u8]) -> Buffer {
         let mut buf = vec![0; v.len()].into_boxed_slice();
-        buf.copy_from_slice(v.as_slice());
+        buf.copy_from_slice(v);
 
         let data = buf.as_mut_ptr();
         let len = buf.len();
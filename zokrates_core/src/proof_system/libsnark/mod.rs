@@ -44,7 +44,7 @@ pub fn prepare_setup<T: Field>(
     usize,
 ) {
     // transform to R1CS
-    let (variables, public_variables_count, a, b, c) = r1cs_program(program);
+    let (variables, public_variables_count, a, b, c) = r1cs_program(&program);
 
     let num_inputs = public_variables_count - 1;
 
@@ -154,7 +154,7 @@ pub fn prepare_setup<T: Field>(
 
 // proof-system-independent preparation for proof generation
 pub fn prepare_generate_proof<T: Field>(
-    program: ir::Prog<T>,
+    program: &ir::Prog<T>,
     witness: ir::Witness<T>,
 ) -> (Vec<[u8; 32]>, usize, Vec<[u8; 32]>, usize) {
     // recover variable order from the program
@@ -211,7 +211,7 @@ pub fn provide_variable_idx(
 ///
 /// * `prog` - The program the representation is calculated for.
 pub fn r1cs_program<T: Field>(
-    prog: ir::Prog<T>,
+    prog: &ir::Prog<T>,
 ) -> (
     Vec<FlatVariable>,
     usize,
@@ -219,6 +219,9 @@ pub fn r1cs_program<T: Field>(
     Vec<Vec<(usize, T)>>,
     Vec<Vec<(usize, T)>>,
 ) {
+    // the constraints of called functions are represented at each call
+    let prog = prog.expanded();
+
     let mut variables: HashMap<FlatVariable, usize> = HashMap::new();
     provide_variable_idx(&mut variables, &FlatVariable::one());
 
@@ -232,14 +235,14 @@ pub fn r1cs_program<T: Field>(
         provide_variable_idx(&mut variables, &x.1);
     }
 
-    //Only the main function is relevant in this step, since all calls to other functions were resolved during flattening
-    let main = prog.main;
+    //Only the main function is relevant in this step, since all calls to other functions were expanded
+    let main = &prog.main;
 
     //~out are added after main's arguments, since we want variables (columns)
     //in the r1cs to be aligned like "public inputs | private inputs"
     let main_return_count = main.returns.len();
 
-    for i in 0..main_return_count {
+    for i in (0..main_return_count).filter(|i| !prog.private_outputs.contains(i)) {
         provide_variable_idx(&mut variables, &FlatVariable::public(i));
     }
 
@@ -249,7 +252,7 @@ pub fn r1cs_program<T: Field>(
     // first pass through statements to populate `variables`
     for (quad, lin) in main.statements.iter().filter_map(|s| match s {
         Statement::Constraint(quad, lin) => Some((quad, lin)),
-        Statement::Directive(..) => None,
+        _ => None,
     }) {
         for (k, _) in &quad.left.0 {
             provide_variable_idx(&mut variables, &k);
@@ -267,28 +270,28 @@ pub fn r1cs_program<T: Field>(
     let mut c = vec![];
 
     // second pass to convert program to raw sparse vectors
-    for (quad, lin) in main.statements.into_iter().filter_map(|s| match s {
+    for (quad, lin) in main.statements.iter().filter_map(|s| match s {
         Statement::Constraint(quad, lin) => Some((quad, lin)),
-        Statement::Directive(..) => None,
+        _ => None,
     }) {
         a.push(
             quad.left
                 .0
-                .into_iter()
-                .map(|(k, v)| (variables.get(&k).unwrap().clone(), v))
+                .iter()
+                .map(|(k, v)| (variables.get(k).unwrap().clone(), v.clone()))
                 .collect(),
         );
         b.push(
             quad.right
                 .0
-                .into_iter()
-                .map(|(k, v)| (variables.get(&k).unwrap().clone(), v))
+                .iter()
+                .map(|(k, v)| (variables.get(k).unwrap().clone(), v.clone()))
                 .collect(),
         );
         c.push(
             lin.0
-                .into_iter()
-                .map(|(k, v)| (variables.get(&k).unwrap().clone(), v))
+                .iter()
+                .map(|(k, v)| (variables.get(k).unwrap().clone(), v.clone()))
                 .collect(),
         );
     }
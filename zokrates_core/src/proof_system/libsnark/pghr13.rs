@@ -105,14 +105,14 @@ impl ProofSystem<Bn128Field> for PGHR13 {
     }
 
     fn generate_proof(
-        program: ir::Prog<Bn128Field>,
+        program: &ir::Prog<Bn128Field>,
         witness: ir::Witness<Bn128Field>,
-        proving_key: Vec<u8>,
+        proving_key: &[u8],
     ) -> Proof<ProofPoints> {
         let (public_inputs_arr, public_inputs_length, private_inputs_arr, private_inputs_length) =
             prepare_generate_proof(program, witness);
 
-        let mut pk_buf = Buffer::from_vec(&proving_key);
+        let mut pk_buf = Buffer::from_vec(proving_key);
 
         let proof = unsafe {
             let result = pghr13_bn128_generate_proof(
@@ -257,7 +257,7 @@ impl ProofSystem<Bn128Field> for PGHR13 {
             .inputs
             .iter()
             .map(|v| {
-                Bn128Field::try_from_str(v.as_str().trim_start_matches("0x"), 16)
+                Bn128Field::try_from_hex_str(v.as_str())
                     .expect(format!("Invalid bn128 value: {}", v.as_str()).as_str())
             })
             .collect();
@@ -1,6 +1,8 @@
 pub mod bellman;
 #[cfg(feature = "libsnark")]
 pub mod libsnark;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod pipeline;
 
 mod solidity;
 
@@ -80,12 +82,45 @@ where
     fn setup(program: ir::Prog<T>) -> SetupKeypair<Self::VerificationKey>;
 
     fn generate_proof(
-        program: ir::Prog<T>,
+        program: &ir::Prog<T>,
         witness: ir::Witness<T>,
-        proving_key: Vec<u8>,
+        proving_key: &[u8],
     ) -> Proof<Self::ProofPoints>;
 
     fn export_solidity_verifier(vk: Self::VerificationKey, abi: SolidityAbi) -> String;
 
+    /// Exports a Solidity verifier which also rejects the proofs whose coordinates are not reduced or which contain the
+    /// point at infinity, so that a valid proof cannot be submitted again under another encoding. Returns `None` if the
+    /// proving scheme does not support it.
+    fn export_hardened_solidity_verifier(
+        _vk: Self::VerificationKey,
+        _abi: SolidityAbi,
+    ) -> Option<String> {
+        None
+    }
+
+    /// Exports a Solidity verifier which takes the verification key as an argument or from a registry contract
+    /// instead of embedding it, so that keys can be replaced without redeploying the verifier. Returns `None` if the
+    /// proving scheme does not support it.
+    fn export_solidity_dynamic_verifier() -> Option<String> {
+        None
+    }
+
+    /// Exports a verifier written in inline assembly, which calls the precompiles directly on the calldata to
+    /// minimize gas costs. Returns `None` if the proving scheme does not support it.
+    fn export_yul_verifier(_vk: Self::VerificationKey) -> Option<String> {
+        None
+    }
+
     fn verify(vk: Self::VerificationKey, proof: Proof<Self::ProofPoints>) -> bool;
+
+    /// Verifies a proof after checking that its points are in the subgroup of prime order and are not the point at
+    /// infinity, and that its inputs are reduced, returning an error instead of panicking on invalid proofs. Returns
+    /// `None` if the proving scheme does not support it.
+    fn verify_hardened(
+        _vk: Self::VerificationKey,
+        _proof: Proof<Self::ProofPoints>,
+    ) -> Option<Result<bool, String>> {
+        None
+    }
 }
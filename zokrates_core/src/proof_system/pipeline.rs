@@ -0,0 +1,733 @@
+//! Proving of a stream of inputs against the same program and proving key.
+//!
+//! Witness computation and proof generation run on two separate threads connected by a
+//! bounded channel, so that the witness of the next input is computed while the proof of the
+//! current one is being generated. The program and the proving key are loaded once and shared
+//! by all jobs, which makes this suitable for long-running proving servers.
+
+use crate::ir::{self, Interpreter, Prog};
+use proof_system::{Proof, ProofSystem};
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::mpsc::{channel, sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use zokrates_field::Field;
+
+/// The result of a single proving job: the proof, or the error encountered while computing
+/// the witness
+pub type JobResult<P> = Result<Proof<P>, ir::Error>;
+
+pub struct ProvingPipeline<T, P> {
+    program: Arc<Prog<T>>,
+    proving_key: Arc<Vec<u8>>,
+    proof_system: PhantomData<P>,
+}
+
+impl<T, P> ProvingPipeline<T, P>
+where
+    T: Field + Send + Sync + 'static,
+    P: ProofSystem<T> + 'static,
+    P::ProofPoints: Send + 'static,
+{
+    pub fn new(program: Prog<T>, proving_key: Vec<u8>) -> Self {
+        ProvingPipeline {
+            program: Arc::new(program),
+            proving_key: Arc::new(proving_key),
+            proof_system: PhantomData,
+        }
+    }
+
+    /// Proves each element of `inputs` and returns a receiver yielding the results in the order
+    /// of the inputs. `inputs` can itself be a `Receiver`, in which case jobs are processed as
+    /// they are submitted until the sending side is dropped.
+    pub fn prove<I>(&self, inputs: I) -> Receiver<JobResult<P::ProofPoints>>
+    where
+        I: IntoIterator<Item = Vec<T>> + Send + 'static,
+    {
+        // at most one witness waits for the prover, which bounds memory usage
+        let (witness_sender, witness_receiver) = sync_channel(1);
+        let (proof_sender, proof_receiver) = channel();
+
+        let program = self.program.clone();
+        thread::spawn(move || {
+            let interpreter = Interpreter::default();
+            for arguments in inputs {
+                let witness = interpreter.execute(&program, &arguments);
+                if witness_sender.send(witness).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let program = self.program.clone();
+        let proving_key = self.proving_key.clone();
+        thread::spawn(move || {
+            for witness in witness_receiver {
+                let result =
+                    witness.map(|witness| P::generate_proof(&program, witness, &proving_key));
+                if proof_sender.send(result).is_err() {
+                    break;
+                }
+            }
+        });
+
+        proof_receiver
+    }
+}
+
+pub type JobId = usize;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed(ir::Error),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum QueueError {
+    /// The queue is at capacity, the job should be submitted again later
+    Full,
+    /// The pipeline processing the queue stopped
+    Closed,
+}
+
+impl fmt::Display for QueueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QueueError::Full => write!(f, "Job queue is full"),
+            QueueError::Closed => write!(f, "Job queue is closed"),
+        }
+    }
+}
+
+struct Job<P> {
+    status: JobStatus,
+    started: Option<Instant>,
+    result: Option<JobResult<P>>,
+}
+
+#[derive(Default)]
+struct Metrics {
+    succeeded: usize,
+    failed: usize,
+    latency: Duration,
+}
+
+struct State<P> {
+    jobs: HashMap<JobId, Job<P>>,
+    next_id: JobId,
+    metrics: Metrics,
+}
+
+impl<P> State<P> {
+    fn start(&mut self, id: JobId) {
+        if let Some(job) = self.jobs.get_mut(&id) {
+            job.status = JobStatus::Running;
+            job.started = Some(Instant::now());
+        }
+    }
+
+    fn finish(&mut self, id: JobId, result: JobResult<P>) {
+        if let Some(job) = self.jobs.get_mut(&id) {
+            match result {
+                Ok(proof) => {
+                    job.status = JobStatus::Succeeded;
+                    job.result = Some(Ok(proof));
+                    self.metrics.succeeded += 1;
+                    if let Some(started) = job.started {
+                        self.metrics.latency += started.elapsed();
+                    }
+                }
+                Err(e) => {
+                    job.status = JobStatus::Failed(e.clone());
+                    job.result = Some(Err(e));
+                    self.metrics.failed += 1;
+                }
+            }
+        }
+    }
+}
+
+/// A bounded queue of proving jobs processed by a `ProvingPipeline`.
+///
+/// Submissions are rejected with `QueueError::Full` once `capacity` jobs are waiting, so that
+/// callers can apply backpressure instead of buffering an unbounded amount of inputs.
+pub struct JobQueue<T, P: ProofSystem<T>>
+where
+    T: Field,
+{
+    sender: SyncSender<(JobId, Vec<T>)>,
+    state: Arc<Mutex<State<P::ProofPoints>>>,
+    collector: JoinHandle<()>,
+}
+
+impl<T, P> JobQueue<T, P>
+where
+    T: Field + Send + Sync + 'static,
+    P: ProofSystem<T> + 'static,
+    P::ProofPoints: Send + 'static,
+{
+    pub fn new(pipeline: &ProvingPipeline<T, P>, capacity: usize) -> Self {
+        let (sender, receiver) = sync_channel::<(JobId, Vec<T>)>(capacity);
+        let (id_sender, id_receiver) = channel();
+
+        let state = Arc::new(Mutex::new(State {
+            jobs: HashMap::new(),
+            next_id: 0,
+            metrics: Metrics::default(),
+        }));
+
+        let feeder_state = state.clone();
+        let inputs = receiver.into_iter().map(move |(id, arguments)| {
+            feeder_state.lock().unwrap().start(id);
+            // the pipeline yields results in order, so the collector can match them to ids
+            let _ = id_sender.send(id);
+            arguments
+        });
+
+        let results = pipeline.prove(inputs);
+
+        let collector_state = state.clone();
+        let collector = thread::spawn(move || {
+            for (id, result) in id_receiver.into_iter().zip(results) {
+                collector_state.lock().unwrap().finish(id, result);
+            }
+        });
+
+        JobQueue {
+            sender,
+            state,
+            collector,
+        }
+    }
+
+    pub fn submit(&self, arguments: Vec<T>) -> Result<JobId, QueueError> {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id;
+
+        // register the job before sending it, so that the pipeline always finds it
+        state.jobs.insert(
+            id,
+            Job {
+                status: JobStatus::Queued,
+                started: None,
+                result: None,
+            },
+        );
+
+        match self.sender.try_send((id, arguments)) {
+            Ok(()) => {
+                state.next_id += 1;
+                Ok(id)
+            }
+            Err(e) => {
+                state.jobs.remove(&id);
+                Err(match e {
+                    TrySendError::Full(_) => QueueError::Full,
+                    TrySendError::Disconnected(_) => QueueError::Closed,
+                })
+            }
+        }
+    }
+
+    pub fn status(&self, id: JobId) -> Option<JobStatus> {
+        let state = self.state.lock().unwrap();
+        state.jobs.get(&id).map(|job| job.status.clone())
+    }
+
+    /// Removes a finished job from the queue and returns its result, or `None` if the job is
+    /// unknown or not finished yet
+    pub fn take(&self, id: JobId) -> Option<JobResult<P::ProofPoints>> {
+        let mut state = self.state.lock().unwrap();
+        match state.jobs.get(&id).map(|job| job.result.is_some()) {
+            Some(true) => state.jobs.remove(&id).and_then(|job| job.result),
+            _ => None,
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state
+            .jobs
+            .values()
+            .filter(|job| job.status == JobStatus::Queued)
+            .count()
+    }
+
+    /// Returns the metrics of the queue in the Prometheus text exposition format
+    pub fn metrics(&self) -> String {
+        exposition(&[(vec![], self.snapshot())])
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        let depth = self.depth();
+        let state = self.state.lock().unwrap();
+
+        Snapshot {
+            depth,
+            succeeded: state.metrics.succeeded,
+            failed: state.metrics.failed,
+            latency: state.metrics.latency,
+        }
+    }
+
+    /// Stops accepting jobs, waits for all submitted jobs to finish and returns the results
+    /// which were not taken yet
+    pub fn shutdown(self) -> HashMap<JobId, JobResult<P::ProofPoints>> {
+        drop(self.sender);
+        self.collector.join().unwrap();
+
+        let mut state = self.state.lock().unwrap();
+        state
+            .jobs
+            .drain()
+            .filter_map(|(id, job)| job.result.map(|result| (id, result)))
+            .collect()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RoutingError {
+    /// The circuit does not exist or the API key is not allowed to use it. Both cases are
+    /// reported the same way so that clients cannot discover circuits they have no access to.
+    Unauthorized,
+    /// A circuit is already registered under this name
+    AlreadyRegistered(String),
+    Queue(QueueError),
+}
+
+impl fmt::Display for RoutingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RoutingError::Unauthorized => write!(f, "Unauthorized"),
+            RoutingError::AlreadyRegistered(name) => {
+                write!(f, "Circuit `{}` is already registered", name)
+            }
+            RoutingError::Queue(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<QueueError> for RoutingError {
+    fn from(e: QueueError) -> Self {
+        RoutingError::Queue(e)
+    }
+}
+
+struct Circuit<T: Field, P: ProofSystem<T>> {
+    queue: JobQueue<T, P>,
+    api_keys: Vec<String>,
+}
+
+/// A set of circuits served side by side, each with its own job queue and API keys
+pub struct CircuitRegistry<T: Field, P: ProofSystem<T>> {
+    circuits: HashMap<String, Circuit<T, P>>,
+}
+
+impl<T, P> CircuitRegistry<T, P>
+where
+    T: Field + Send + Sync + 'static,
+    P: ProofSystem<T> + 'static,
+    P::ProofPoints: Send + 'static,
+{
+    pub fn new() -> Self {
+        CircuitRegistry {
+            circuits: HashMap::new(),
+        }
+    }
+
+    /// Registers a circuit under `name`, which only the given `api_keys` are allowed to use.
+    /// Registration fails if the name is taken, as replacing the circuit would drop its jobs.
+    pub fn register(
+        &mut self,
+        name: String,
+        pipeline: &ProvingPipeline<T, P>,
+        capacity: usize,
+        api_keys: Vec<String>,
+    ) -> Result<(), RoutingError> {
+        if self.circuits.contains_key(&name) {
+            return Err(RoutingError::AlreadyRegistered(name));
+        }
+
+        self.circuits.insert(
+            name,
+            Circuit {
+                queue: JobQueue::new(pipeline, capacity),
+                api_keys,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn queue(&self, name: &str, api_key: &str) -> Result<&JobQueue<T, P>, RoutingError> {
+        self.circuits
+            .get(name)
+            .filter(|circuit| {
+                circuit
+                    .api_keys
+                    .iter()
+                    .any(|k| constant_time_eq(k, api_key))
+            })
+            .map(|circuit| &circuit.queue)
+            .ok_or(RoutingError::Unauthorized)
+    }
+
+    pub fn submit(
+        &self,
+        name: &str,
+        api_key: &str,
+        arguments: Vec<T>,
+    ) -> Result<JobId, RoutingError> {
+        Ok(self.queue(name, api_key)?.submit(arguments)?)
+    }
+
+    pub fn status(
+        &self,
+        name: &str,
+        api_key: &str,
+        id: JobId,
+    ) -> Result<Option<JobStatus>, RoutingError> {
+        Ok(self.queue(name, api_key)?.status(id))
+    }
+
+    pub fn take(
+        &self,
+        name: &str,
+        api_key: &str,
+        id: JobId,
+    ) -> Result<Option<JobResult<P::ProofPoints>>, RoutingError> {
+        Ok(self.queue(name, api_key)?.take(id))
+    }
+
+    /// Returns the metrics of all circuits in the Prometheus text exposition format, each
+    /// labelled with the name of its circuit
+    pub fn metrics(&self) -> String {
+        let mut names: Vec<_> = self.circuits.keys().collect();
+        names.sort();
+
+        let queues: Vec<_> = names
+            .into_iter()
+            .map(|name| {
+                (
+                    vec![("circuit", name.as_str())],
+                    self.circuits[name].queue.snapshot(),
+                )
+            })
+            .collect();
+
+        exposition(&queues)
+    }
+
+    /// Shuts down all circuits, waiting for their submitted jobs to finish
+    pub fn shutdown(self) -> HashMap<String, HashMap<JobId, JobResult<P::ProofPoints>>> {
+        self.circuits
+            .into_iter()
+            .map(|(name, circuit)| (name, circuit.queue.shutdown()))
+            .collect()
+    }
+}
+
+/// The metrics of a job queue at a point in time
+struct Snapshot {
+    depth: usize,
+    succeeded: usize,
+    failed: usize,
+    latency: Duration,
+}
+
+/// Renders the metrics of queues, each identified by its labels, in the Prometheus text
+/// exposition format. Each metric family is described once, followed by the samples of all
+/// queues.
+fn exposition(queues: &[(Vec<(&str, &str)>, Snapshot)]) -> String {
+    let mut res = String::new();
+
+    describe(
+        &mut res,
+        "zokrates_queue_depth",
+        "gauge",
+        "Number of jobs waiting to be proven",
+    );
+    for (labels, snapshot) in queues {
+        sample(&mut res, "zokrates_queue_depth", labels, snapshot.depth);
+    }
+
+    describe(
+        &mut res,
+        "zokrates_jobs_total",
+        "counter",
+        "Number of finished jobs",
+    );
+    for (labels, snapshot) in queues {
+        for (status, count) in vec![
+            ("succeeded", snapshot.succeeded),
+            ("failed", snapshot.failed),
+        ] {
+            let mut labels = labels.clone();
+            labels.push(("status", status));
+            sample(&mut res, "zokrates_jobs_total", &labels, count);
+        }
+    }
+
+    describe(
+        &mut res,
+        "zokrates_proof_latency_seconds",
+        "summary",
+        "Time from the start of witness computation to the proof",
+    );
+    for (labels, snapshot) in queues {
+        let latency = snapshot.latency;
+        sample(
+            &mut res,
+            "zokrates_proof_latency_seconds_sum",
+            labels,
+            latency.as_secs() as f64 + f64::from(latency.subsec_nanos()) * 1e-9,
+        );
+        sample(
+            &mut res,
+            "zokrates_proof_latency_seconds_count",
+            labels,
+            snapshot.succeeded,
+        );
+    }
+
+    if let Some(bytes) = resident_memory() {
+        describe(
+            &mut res,
+            "process_resident_memory_bytes",
+            "gauge",
+            "Resident memory of the proving process",
+        );
+        sample(&mut res, "process_resident_memory_bytes", &[], bytes);
+    }
+
+    res
+}
+
+/// Returns the resident memory of the process in bytes, on platforms which report it in `/proc`
+fn resident_memory() -> Option<u64> {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()?
+        .lines()
+        .find(|line| line.starts_with("VmRSS:"))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|kilobytes| kilobytes.parse::<u64>().ok())
+        .map(|kilobytes| kilobytes * 1024)
+}
+
+fn describe(res: &mut String, metric: &str, kind: &str, help: &str) {
+    res.push_str(&format!(
+        "# HELP {} {}\n# TYPE {} {}\n",
+        metric, help, metric, kind
+    ));
+}
+
+fn sample<V: fmt::Display>(res: &mut String, metric: &str, labels: &[(&str, &str)], value: V) {
+    let labels = match labels.len() {
+        0 => String::new(),
+        _ => format!(
+            "{{{}}}",
+            labels
+                .iter()
+                .map(|(name, value)| format!("{}=\"{}\"", name, escape(value)))
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+    };
+
+    res.push_str(&format!("{}{} {}\n", metric, labels, value));
+}
+
+/// Escapes a label value as required by the Prometheus text exposition format
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Compares two strings in time independent of the position of the first difference
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flat_absy::FlatVariable;
+    use crate::ir::{Function, Statement};
+    use proof_system::bellman::groth16::G16;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn prove_stream() {
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                returns: vec![FlatVariable::public(0)],
+                statements: vec![Statement::Constraint(
+                    FlatVariable::new(0).into(),
+                    FlatVariable::public(0).into(),
+                )],
+            },
+            functions: vec![],
+            private: vec![false],
+            private_outputs: vec![],
+        };
+
+        let keypair = G16::setup(program.clone());
+        let pipeline = ProvingPipeline::<_, G16>::new(program, keypair.pk);
+
+        let inputs = vec![
+            vec![Bn128Field::from(1)],
+            vec![Bn128Field::from(2), Bn128Field::from(3)],
+            vec![Bn128Field::from(4)],
+        ];
+
+        let mut results = pipeline.prove(inputs).into_iter();
+
+        let first = results.next().unwrap().unwrap();
+        assert_eq!(
+            results.next().unwrap().err(),
+            Some(ir::Error::WrongInputCount {
+                expected: 1,
+                received: 2
+            })
+        );
+        assert!(results.next().unwrap().is_ok());
+        assert!(results.next().is_none());
+
+        assert!(<G16 as ProofSystem<Bn128Field>>::verify(keypair.vk, first));
+    }
+
+    #[test]
+    fn job_queue() {
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                returns: vec![FlatVariable::public(0)],
+                statements: vec![Statement::Constraint(
+                    FlatVariable::new(0).into(),
+                    FlatVariable::public(0).into(),
+                )],
+            },
+            functions: vec![],
+            private: vec![false],
+            private_outputs: vec![],
+        };
+
+        let keypair = G16::setup(program.clone());
+        let pipeline = ProvingPipeline::<_, G16>::new(program, keypair.pk);
+        let queue = JobQueue::new(&pipeline, 4);
+
+        let valid = queue.submit(vec![Bn128Field::from(42)]).unwrap();
+        let invalid = queue.submit(vec![]).unwrap();
+
+        assert!(queue.status(valid).is_some());
+        assert_eq!(queue.status(valid + 2), None);
+
+        let metrics_before_shutdown = queue.metrics();
+        assert!(metrics_before_shutdown.contains("# TYPE zokrates_queue_depth gauge"));
+        #[cfg(target_os = "linux")]
+        assert!(metrics_before_shutdown.contains("process_resident_memory_bytes "));
+
+        let mut results = queue.shutdown();
+
+        assert!(<G16 as ProofSystem<Bn128Field>>::verify(
+            keypair.vk,
+            results.remove(&valid).unwrap().unwrap()
+        ));
+        assert_eq!(
+            results.remove(&invalid).unwrap().err(),
+            Some(ir::Error::WrongInputCount {
+                expected: 1,
+                received: 0
+            })
+        );
+    }
+
+    #[test]
+    fn registry() {
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                returns: vec![FlatVariable::public(0)],
+                statements: vec![Statement::Constraint(
+                    FlatVariable::new(0).into(),
+                    FlatVariable::public(0).into(),
+                )],
+            },
+            functions: vec![],
+            private: vec![false],
+            private_outputs: vec![],
+        };
+
+        let keypair = G16::setup(program.clone());
+        let pipeline = ProvingPipeline::<_, G16>::new(program, keypair.pk);
+
+        let mut registry = CircuitRegistry::new();
+        registry
+            .register(
+                String::from("id"),
+                &pipeline,
+                4,
+                vec![String::from("secret")],
+            )
+            .unwrap();
+        assert_eq!(
+            registry.register(String::from("id"), &pipeline, 4, vec![]),
+            Err(RoutingError::AlreadyRegistered(String::from("id")))
+        );
+        registry
+            .register(String::from("other"), &pipeline, 4, vec![])
+            .unwrap();
+
+        assert_eq!(
+            registry.submit("id", "public", vec![Bn128Field::from(1)]),
+            Err(RoutingError::Unauthorized)
+        );
+        assert_eq!(
+            registry.submit("other", "secret", vec![Bn128Field::from(1)]),
+            Err(RoutingError::Unauthorized)
+        );
+
+        let id = registry
+            .submit("id", "secret", vec![Bn128Field::from(1)])
+            .unwrap();
+        assert_eq!(
+            registry.status("id", "public", id),
+            Err(RoutingError::Unauthorized)
+        );
+
+        let metrics = registry.metrics();
+        assert!(metrics.contains("zokrates_jobs_total{circuit=\"id\",status=\"failed\"} 0"));
+        assert!(metrics.contains("zokrates_queue_depth{circuit=\"other\"} 0"));
+        // each metric family is described once for all circuits
+        assert_eq!(
+            metrics.matches("# TYPE zokrates_queue_depth gauge").count(),
+            1
+        );
+
+        let mut results = registry.shutdown();
+        assert!(results.remove("id").unwrap().remove(&id).unwrap().is_ok());
+    }
+
+    #[test]
+    fn escape_labels() {
+        let mut res = String::new();
+        sample(&mut res, "depth", &[("circuit", "a\"b\\c\nd")], 3);
+        assert_eq!(res, "depth{circuit=\"a\\\"b\\\\c\\nd\"} 3\n");
+    }
+}
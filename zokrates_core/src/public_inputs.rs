@@ -0,0 +1,122 @@
+//! Module checking the number of public inputs of a program against the number which can be verified on chain
+//!
+//! A verifier multiplies a point of its verification key by each public input and adds the products, so that the gas
+//! used by a verification grows linearly with the number of public inputs. Past a threshold depending on the template of
+//! the verifier and on the gas limit of the target chain, verifying proofs on chain is impractical. Compilation warns
+//! about it, so that users learn about it before running a setup.
+
+use std::fmt;
+
+/// The gas which a transaction verifying a proof can practically use on Ethereum mainnet, a sixth of a block
+const GAS_BUDGET: usize = 5_000_000;
+/// The gas used by a verification independently of its public inputs, most of it by the pairing check
+const BASE_GAS: usize = 300_000;
+/// The gas used for each public input by the `solidity` verifier: a scalar multiplication, an addition, the calldata
+/// and the calls to the precompiles through the `Pairing` library
+const SOLIDITY_GAS_PER_INPUT: usize = 8_000;
+/// The gas used for each public input by the `yul` verifier, which calls the precompiles directly on the calldata
+const YUL_GAS_PER_INPUT: usize = 6_700;
+
+/// The maximum number of public inputs of a program, for each template of verifier. A template without maximum is not
+/// checked. The defaults target Ethereum mainnet; chains with other gas limits or costs need other maximums.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PublicInputLimits {
+    /// maximum number of public inputs verified by the `solidity` verifier
+    pub solidity: Option<usize>,
+    /// maximum number of public inputs verified by the `yul` verifier
+    pub yul: Option<usize>,
+}
+
+impl Default for PublicInputLimits {
+    fn default() -> Self {
+        PublicInputLimits {
+            solidity: Some((GAS_BUDGET - BASE_GAS) / SOLIDITY_GAS_PER_INPUT),
+            yul: Some((GAS_BUDGET - BASE_GAS) / YUL_GAS_PER_INPUT),
+        }
+    }
+}
+
+impl PublicInputLimits {
+    /// Returns limits which check no template
+    pub fn none() -> Self {
+        PublicInputLimits {
+            solidity: None,
+            yul: None,
+        }
+    }
+
+    /// Sets the maximum of the template `template`, or removes it if `max` is `None`
+    pub fn set(mut self, template: &str, max: Option<usize>) -> Result<Self, String> {
+        match template {
+            "solidity" => self.solidity = max,
+            "yul" => self.yul = max,
+            _ => return Err(format!("Unknown verifier template `{}`", template)),
+        };
+        Ok(self)
+    }
+}
+
+/// A template of verifier which cannot practically verify the public inputs of a program
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    pub template: &'static str,
+    pub max: usize,
+    pub count: usize,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The program has {} public inputs, more than the {} which the `{}` verifier can practically verify on chain",
+            self.count, self.max, self.template
+        )
+    }
+}
+
+/// Returns a warning for each template whose maximum is below `count` public inputs
+pub fn check(count: usize, limits: &PublicInputLimits) -> Vec<Warning> {
+    vec![("solidity", limits.solidity), ("yul", limits.yul)]
+        .into_iter()
+        .filter_map(|(template, max)| match max {
+            Some(max) if count > max => Some(Warning {
+                template,
+                max,
+                count,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_templates() {
+        let limits = PublicInputLimits::none()
+            .set("solidity", Some(2))
+            .unwrap()
+            .set("yul", Some(3))
+            .unwrap();
+
+        assert_eq!(check(2, &limits), vec![]);
+        assert_eq!(
+            check(3, &limits),
+            vec![Warning {
+                template: "solidity",
+                max: 2,
+                count: 3
+            }]
+        );
+        assert_eq!(check(4, &limits).len(), 2);
+        assert_eq!(check(1000, &PublicInputLimits::none()), vec![]);
+    }
+
+    #[test]
+    fn unknown_template() {
+        assert!(PublicInputLimits::default().set("vyper", Some(1)).is_err());
+    }
+}
@@ -630,6 +630,80 @@ impl<'ast> Checker<'ast> {
         }
     }
 
+    /// Build a conditional expression, expecting `consequence` and `alternative` to have the same type
+    fn if_else<T: Field>(
+        condition: BooleanExpression<'ast, T>,
+        consequence: TypedExpression<'ast, T>,
+        alternative: TypedExpression<'ast, T>,
+    ) -> TypedExpression<'ast, T> {
+        match (consequence, alternative) {
+            (
+                TypedExpression::FieldElement(consequence),
+                TypedExpression::FieldElement(alternative),
+            ) => FieldElementExpression::IfElse(box condition, box consequence, box alternative)
+                .into(),
+            (TypedExpression::Boolean(consequence), TypedExpression::Boolean(alternative)) => {
+                BooleanExpression::IfElse(box condition, box consequence, box alternative).into()
+            }
+            (TypedExpression::Array(consequence), TypedExpression::Array(alternative)) => {
+                let inner_type = consequence.inner_type().clone();
+                let size = consequence.size();
+                ArrayExpressionInner::IfElse(box condition, box consequence, box alternative)
+                    .annotate(inner_type, size)
+                    .into()
+            }
+            (TypedExpression::Struct(consequence), TypedExpression::Struct(alternative)) => {
+                let ty = consequence.ty().clone();
+                StructExpressionInner::IfElse(box condition, box consequence, box alternative)
+                    .annotate(ty)
+                    .into()
+            }
+            (TypedExpression::Uint(consequence), TypedExpression::Uint(alternative)) => {
+                let bitwidth = consequence.bitwidth();
+                UExpressionInner::IfElse(box condition, box consequence, box alternative)
+                    .annotate(bitwidth)
+                    .into()
+            }
+            _ => unreachable!("types should match here as we checked them explicitly"),
+        }
+    }
+
+    /// Check a call to the `select(condition, consequence, alternative)` intrinsic, which is used when no
+    /// function called `select` with a matching signature is in scope
+    fn check_select<T: Field>(
+        arguments: Vec<TypedExpression<'ast, T>>,
+        pos: (Position, Position),
+    ) -> Result<TypedExpression<'ast, T>, ErrorInner> {
+        let mut arguments = arguments.into_iter();
+
+        match (arguments.next(), arguments.next(), arguments.next(), arguments.next()) {
+            (Some(TypedExpression::Boolean(condition)), Some(consequence), Some(alternative), None) => {
+                match consequence.get_type() == alternative.get_type() {
+                    true => Ok(Self::if_else(condition, consequence, alternative)),
+                    false => Err(ErrorInner {
+                        pos: Some(pos),
+                        message: format!(
+                            "{{consequence}} and {{alternative}} in `select` should have the same type, found {}, {}",
+                            consequence.get_type(),
+                            alternative.get_type()
+                        ),
+                    }),
+                }
+            }
+            (Some(c), Some(_), Some(_), None) => Err(ErrorInner {
+                pos: Some(pos),
+                message: format!(
+                    "{{condition}} in `select` should be a boolean, found {}",
+                    c.get_type()
+                ),
+            }),
+            _ => Err(ErrorInner {
+                pos: Some(pos),
+                message: format!("`select` expects 3 arguments"),
+            }),
+        }
+    }
+
     fn check_for_var(&self, var: &VariableNode) -> Result<(), ErrorInner> {
         match var.value.get_type() {
             UnresolvedType::FieldElement => Ok(()),
@@ -655,6 +729,14 @@ impl<'ast> Checker<'ast> {
 
         assert_eq!(funct.arguments.len(), funct.signature.inputs.len());
 
+        let attributes = match self.check_attributes(funct.attributes) {
+            Ok(a) => a,
+            Err(e) => {
+                errors.extend(e);
+                FunctionAttributes::default()
+            }
+        };
+
         for arg in funct.arguments {
             match self.check_parameter(arg, module_id, types) {
                 Ok(a) => {
@@ -721,12 +803,100 @@ impl<'ast> Checker<'ast> {
         self.exit_scope();
 
         Ok(TypedFunction {
+            attributes,
             arguments: arguments_checked,
             statements: statements_checked,
             signature: signature.unwrap(),
+            private_outputs: funct.private_outputs,
         })
     }
 
+    fn check_attributes(
+        &self,
+        attributes: Vec<AttributeNode<'ast>>,
+    ) -> Result<FunctionAttributes, Vec<ErrorInner>> {
+        let mut errors = vec![];
+        let mut res = FunctionAttributes::default();
+        let mut max_constraints_pos = None;
+
+        for attribute in attributes {
+            let pos = attribute.pos();
+
+            match (attribute.value.id, attribute.value.argument) {
+                ("inline", argument) => {
+                    let inline = match argument {
+                        Some("always") => Inline::Always,
+                        Some("never") => Inline::Never,
+                        _ => {
+                            errors.push(ErrorInner {
+                                pos: Some(pos),
+                                message: format!(
+                                    "Expected `inline(always)` or `inline(never)`, found `{}`",
+                                    attribute.value
+                                ),
+                            });
+                            continue;
+                        }
+                    };
+
+                    match res.inline {
+                        Some(_) => errors.push(ErrorInner {
+                            pos: Some(pos),
+                            message: format!("Duplicate attribute `{}`", attribute.value),
+                        }),
+                        None => res.inline = Some(inline),
+                    }
+                }
+                ("max_constraints", argument) => {
+                    let max = match argument.and_then(|a| a.parse::<usize>().ok()) {
+                        Some(max) => max,
+                        None => {
+                            errors.push(ErrorInner {
+                                pos: Some(pos),
+                                message: format!(
+                                    "Expected `max_constraints(n)` where `n` is a number, found `{}`",
+                                    attribute.value
+                                ),
+                            });
+                            continue;
+                        }
+                    };
+
+                    match res.max_constraints {
+                        Some(_) => errors.push(ErrorInner {
+                            pos: Some(pos),
+                            message: format!("Duplicate attribute `{}`", attribute.value),
+                        }),
+                        None => {
+                            res.max_constraints = Some(max);
+                            max_constraints_pos = Some(pos);
+                        }
+                    }
+                }
+                _ => errors.push(ErrorInner {
+                    pos: Some(pos),
+                    message: format!("Unknown attribute `{}`", attribute.value),
+                }),
+            }
+        }
+
+        // the constraints of a function are only counted if it is kept as a sub-circuit
+        if let (Some(Inline::Always), Some(_)) = (res.inline, res.max_constraints) {
+            errors.push(ErrorInner {
+                pos: max_constraints_pos,
+                message:
+                    "`max_constraints` cannot be used on a function annotated with `inline(always)`"
+                        .into(),
+            });
+        }
+
+        if errors.len() > 0 {
+            return Err(errors);
+        }
+
+        Ok(res)
+    }
+
     fn check_parameter(
         &self,
         p: ParameterNode<'ast>,
@@ -908,7 +1078,7 @@ impl<'ast> Checker<'ast> {
                 }
                 .map_err(|e| vec![e])
             }
-            Statement::For(var, from, to, statements) => {
+            Statement::For(var, from, to, max, statements) => {
                 self.enter_scope();
 
                 self.check_for_var(&var).map_err(|e| vec![e])?;
@@ -946,6 +1116,24 @@ impl<'ast> Checker<'ast> {
                 }
                 .map_err(|e| vec![e])?;
 
+                let max = match max {
+                    Some(max) => match self
+                        .check_expression(max, module_id, &types)
+                        .map_err(|e| vec![e])?
+                    {
+                        TypedExpression::FieldElement(e) => Ok(Some(e)),
+                        e => Err(ErrorInner {
+                            pos: Some(pos),
+                            message: format!(
+                                "Expected maximum loop bound to be of type field, found {}",
+                                e.get_type()
+                            ),
+                        }),
+                    },
+                    None => Ok(None),
+                }
+                .map_err(|e| vec![e])?;
+
                 self.insert_into_scope(var.clone());
 
                 let mut checked_statements = vec![];
@@ -956,7 +1144,7 @@ impl<'ast> Checker<'ast> {
                 }
 
                 self.exit_scope();
-                Ok(TypedStatement::For(var, from, to, checked_statements))
+                Ok(TypedStatement::For(var, from, to, max, checked_statements))
             }
             Statement::MultipleDefinition(assignees, rhs) => {
                 match rhs.value {
@@ -1009,6 +1197,18 @@ impl<'ast> Checker<'ast> {
 
                                 Ok(TypedStatement::MultipleDefinition(variables, call))
                     		},
+                    		// `select` is an intrinsic returning a single value
+                    		None if fun_id == "select" && variables.len() == 1 => {
+                    		    let var = variables.into_iter().next().unwrap();
+
+                    		    Self::check_select(arguments_checked, pos).and_then(|e| match e.get_type() == var.get_type() {
+                    		        true => Ok(TypedStatement::Definition(TypedAssignee::Identifier(var), e)),
+                    		        false => Err(ErrorInner {
+                    		            pos: Some(pos),
+                    		            message: format!("Expression {} of type {} cannot be assigned to {} of type {}", e, e.get_type(), var, var.get_type()),
+                    		        }),
+                    		    })
+                    		},
                     		None => Err(ErrorInner {                         pos: Some(pos),
  message: format!("Function definition for function {} with signature {} not found.", fun_id, query) }),
                     	}
@@ -1334,11 +1534,57 @@ impl<'ast> Checker<'ast> {
                     (TypedExpression::FieldElement(e1), TypedExpression::FieldElement(e2)) => {
                         Ok(FieldElementExpression::Div(box e1, box e2).into())
                     }
+                    (TypedExpression::Uint(e1), TypedExpression::Uint(e2)) => {
+                        if e1.get_type() == e2.get_type() {
+                            Ok(UExpression::div(e1, e2).into())
+                        } else {
+                            Err(ErrorInner {
+                                pos: Some(pos),
+
+                                message: format!(
+                                    "Cannot apply `/` to {:?}, {:?}",
+                                    e1.get_type(),
+                                    e2.get_type()
+                                ),
+                            })
+                        }
+                    }
                     (t1, t2) => Err(ErrorInner {
                         pos: Some(pos),
 
                         message: format!(
-                            "Expected only field elements, found {:?}, {:?}",
+                            "Cannot apply `/` to {:?}, {:?}",
+                            t1.get_type(),
+                            t2.get_type()
+                        ),
+                    }),
+                }
+            }
+            Expression::Rem(box e1, box e2) => {
+                let e1_checked = self.check_expression(e1, module_id, &types)?;
+                let e2_checked = self.check_expression(e2, module_id, &types)?;
+
+                match (e1_checked, e2_checked) {
+                    (TypedExpression::Uint(e1), TypedExpression::Uint(e2)) => {
+                        if e1.get_type() == e2.get_type() {
+                            Ok(UExpression::rem(e1, e2).into())
+                        } else {
+                            Err(ErrorInner {
+                                pos: Some(pos),
+
+                                message: format!(
+                                    "Cannot apply `%` to {:?}, {:?}",
+                                    e1.get_type(),
+                                    e2.get_type()
+                                ),
+                            })
+                        }
+                    }
+                    (t1, t2) => Err(ErrorInner {
+                        pos: Some(pos),
+
+                        message: format!(
+                            "Cannot apply `%` to {:?}, {:?}",
                             t1.get_type(),
                             t2.get_type()
                         ),
@@ -1374,28 +1620,7 @@ impl<'ast> Checker<'ast> {
                         let consequence_type = consequence_checked.get_type();
                         let alternative_type = alternative_checked.get_type();
                         match consequence_type == alternative_type {
-                            true => match (consequence_checked, alternative_checked) {
-                                (TypedExpression::FieldElement(consequence), TypedExpression::FieldElement(alternative)) => {
-                                    Ok(FieldElementExpression::IfElse(box condition, box consequence, box alternative).into())
-                                },
-                                (TypedExpression::Boolean(consequence), TypedExpression::Boolean(alternative)) => {
-                                    Ok(BooleanExpression::IfElse(box condition, box consequence, box alternative).into())
-                                },
-                                (TypedExpression::Array(consequence), TypedExpression::Array(alternative)) => {
-                                    let inner_type = consequence.inner_type().clone();
-                                    let size = consequence.size();
-                                    Ok(ArrayExpressionInner::IfElse(box condition, box consequence, box alternative).annotate(inner_type, size).into())
-                                },
-                                (TypedExpression::Struct(consequence), TypedExpression::Struct(alternative)) => {
-                                    let ty = consequence.ty().clone();
-                                    Ok(StructExpressionInner::IfElse(box condition, box consequence, box alternative).annotate(ty).into())
-                                },
-                                (TypedExpression::Uint(consequence), TypedExpression::Uint(alternative)) => {
-                                    let bitwidth = consequence.bitwidth();
-                                    Ok(UExpressionInner::IfElse(box condition, box consequence, box alternative).annotate(bitwidth).into())
-                                },
-                                _ => unreachable!("types should match here as we checked them explicitly")
-                            }
+                            true => Ok(Self::if_else(condition, consequence_checked, alternative_checked)),
                             false => Err(ErrorInner {
                                 pos: Some(pos),
                                 message: format!("{{consequence}} and {{alternative}} in `if/else` expression should have the same type, found {}, {}", consequence_type, alternative_type)
@@ -1415,6 +1640,8 @@ impl<'ast> Checker<'ast> {
             Expression::U8Constant(n) => Ok(UExpressionInner::Value(n.into()).annotate(8).into()),
             Expression::U16Constant(n) => Ok(UExpressionInner::Value(n.into()).annotate(16).into()),
             Expression::U32Constant(n) => Ok(UExpressionInner::Value(n.into()).annotate(32).into()),
+            Expression::U64Constant(n) => Ok(UExpressionInner::Value(n.into()).annotate(64).into()),
+            Expression::U128Constant(n) => Ok(UExpressionInner::Value(n).annotate(128).into()),
             Expression::FunctionCall(fun_id, arguments) => {
                 // check the arguments
                 let mut arguments_checked = vec![];
@@ -1494,6 +1721,7 @@ impl<'ast> Checker<'ast> {
                             }),
                         }
                     }
+                    None if fun_id == "select" => Self::check_select(arguments_checked, pos),
                     None => Err(ErrorInner {
                         pos: Some(pos),
 
@@ -2397,9 +2625,11 @@ mod tests {
             let signature = UnresolvedSignature::new();
 
             Function {
+                attributes: vec![],
                 arguments,
                 statements,
                 signature,
+                private_outputs: vec![],
             }
             .mock()
         }
@@ -2424,9 +2654,11 @@ mod tests {
                 UnresolvedSignature::new().inputs(vec![UnresolvedType::FieldElement.mock()]);
 
             Function {
+                attributes: vec![],
                 arguments,
                 statements,
                 signature,
+                private_outputs: vec![],
             }
             .mock()
         }
@@ -2878,12 +3110,14 @@ mod tests {
             .mock(),
         ];
         let foo = Function {
+            attributes: vec![],
             arguments: foo_args,
             statements: foo_statements,
             signature: UnresolvedSignature {
                 inputs: vec![],
                 outputs: vec![UnresolvedType::FieldElement.mock()],
             },
+            private_outputs: vec![],
         }
         .mock();
 
@@ -2897,12 +3131,14 @@ mod tests {
         .mock()];
 
         let bar = Function {
+            attributes: vec![],
             arguments: bar_args,
             statements: bar_statements,
             signature: UnresolvedSignature {
                 inputs: vec![],
                 outputs: vec![UnresolvedType::FieldElement.mock()],
             },
+            private_outputs: vec![],
         }
         .mock();
 
@@ -2962,12 +3198,14 @@ mod tests {
         ];
 
         let foo = Function {
+            attributes: vec![],
             arguments: foo_args,
             statements: foo_statements,
             signature: UnresolvedSignature {
                 inputs: vec![],
                 outputs: vec![UnresolvedType::FieldElement.mock()],
             },
+            private_outputs: vec![],
         }
         .mock();
 
@@ -2991,12 +3229,14 @@ mod tests {
             .mock(),
         ];
         let bar = Function {
+            attributes: vec![],
             arguments: bar_args,
             statements: bar_statements,
             signature: UnresolvedSignature {
                 inputs: vec![],
                 outputs: vec![UnresolvedType::FieldElement.mock()],
             },
+            private_outputs: vec![],
         }
         .mock();
 
@@ -3010,12 +3250,14 @@ mod tests {
         .mock()];
 
         let main = Function {
+            attributes: vec![],
             arguments: main_args,
             statements: main_statements,
             signature: UnresolvedSignature {
                 inputs: vec![],
                 outputs: vec![UnresolvedType::FieldElement.mock()],
             },
+            private_outputs: vec![],
         }
         .mock();
 
@@ -3059,6 +3301,7 @@ mod tests {
                 absy::Variable::new("i", UnresolvedType::FieldElement.mock()).mock(),
                 Expression::FieldConstant(Bn128Field::from(0)).mock(),
                 Expression::FieldConstant(Bn128Field::from(10)).mock(),
+                None,
                 vec![],
             )
             .mock(),
@@ -3071,12 +3314,14 @@ mod tests {
             .mock(),
         ];
         let foo = Function {
+            attributes: vec![],
             arguments: vec![],
             statements: foo_statements,
             signature: UnresolvedSignature {
                 inputs: vec![],
                 outputs: vec![UnresolvedType::FieldElement.mock()],
             },
+            private_outputs: vec![],
         }
         .mock();
 
@@ -3117,6 +3362,7 @@ mod tests {
             absy::Variable::new("i", UnresolvedType::FieldElement.mock()).mock(),
             Expression::FieldConstant(Bn128Field::from(0)).mock(),
             Expression::FieldConstant(Bn128Field::from(10)).mock(),
+            None,
             for_statements,
         )
         .mock()];
@@ -3133,26 +3379,31 @@ mod tests {
             typed_absy::Variable::field_element("i"),
             FieldElementExpression::Number(Bn128Field::from(0)),
             FieldElementExpression::Number(Bn128Field::from(10)),
+            None,
             for_statements_checked,
         )];
 
         let foo = Function {
+            attributes: vec![],
             arguments: vec![],
             statements: foo_statements,
             signature: UnresolvedSignature {
                 inputs: vec![],
                 outputs: vec![UnresolvedType::FieldElement.mock()],
             },
+            private_outputs: vec![],
         }
         .mock();
 
         let foo_checked = TypedFunction {
+            attributes: FunctionAttributes::default(),
             arguments: Vec::<Parameter>::new(),
             statements: foo_statements_checked,
             signature: Signature {
                 inputs: vec![],
                 outputs: vec![Type::FieldElement],
             },
+            private_outputs: vec![],
         };
 
         let types = HashMap::new();
@@ -3165,6 +3416,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn for_max_not_field() {
+        // def foo(field n):
+        //   for field i in 0..n max true do
+        //   endfor
+        //   return
+        // should fail
+        let foo_statements = vec![
+            Statement::For(
+                absy::Variable::new("i", UnresolvedType::FieldElement.mock()).mock(),
+                Expression::FieldConstant(Bn128Field::from(0)).mock(),
+                Expression::Identifier("n").mock(),
+                Some(Expression::BooleanConstant(true).mock()),
+                vec![],
+            )
+            .mock(),
+            Statement::Return(
+                ExpressionList {
+                    expressions: vec![],
+                }
+                .mock(),
+            )
+            .mock(),
+        ];
+        let foo = Function {
+            attributes: vec![],
+            arguments: vec![absy::Parameter::private(
+                absy::Variable::new("n", UnresolvedType::FieldElement.mock()).mock(),
+            )
+            .mock()],
+            statements: foo_statements,
+            signature: UnresolvedSignature {
+                inputs: vec![UnresolvedType::FieldElement.mock()],
+                outputs: vec![],
+            },
+            private_outputs: vec![],
+        }
+        .mock();
+
+        let types = HashMap::new();
+        let module_id = "".into();
+
+        let mut checker = Checker::new();
+        assert_eq!(
+            checker.check_function(foo, &module_id, &types),
+            Err(vec![ErrorInner {
+                pos: Some((Position::mock(), Position::mock())),
+                message: "Expected maximum loop bound to be of type field, found bool".into()
+            }])
+        );
+    }
+
     #[test]
     fn arity_mismatch() {
         // def foo():
@@ -3195,12 +3498,14 @@ mod tests {
         let functions = vec![foo].into_iter().collect();
 
         let bar = Function {
+            attributes: vec![],
             arguments: vec![],
             statements: bar_statements,
             signature: UnresolvedSignature {
                 inputs: vec![],
                 outputs: vec![UnresolvedType::FieldElement.mock()],
             },
+            private_outputs: vec![],
         }
         .mock();
 
@@ -3246,12 +3551,14 @@ mod tests {
         let functions = vec![foo].into_iter().collect();
 
         let bar = Function {
+            attributes: vec![],
             arguments: vec![],
             statements: bar_statements,
             signature: UnresolvedSignature {
                 inputs: vec![],
                 outputs: vec![UnresolvedType::FieldElement.mock()],
             },
+            private_outputs: vec![],
         }
         .mock();
 
@@ -3287,12 +3594,14 @@ mod tests {
         ];
 
         let bar = Function {
+            attributes: vec![],
             arguments: vec![],
             statements: bar_statements,
             signature: UnresolvedSignature {
                 inputs: vec![],
                 outputs: vec![UnresolvedType::FieldElement.mock()],
             },
+            private_outputs: vec![],
         }
         .mock();
 
@@ -3333,6 +3642,7 @@ mod tests {
         .mock()];
 
         let foo = Function {
+            attributes: vec![],
             arguments: vec![crate::absy::Parameter {
                 id: absy::Variable::new("x", UnresolvedType::FieldElement.mock()).mock(),
                 private: false,
@@ -3346,6 +3656,7 @@ mod tests {
                     UnresolvedType::FieldElement.mock(),
                 ],
             },
+            private_outputs: vec![],
         }
         .mock();
 
@@ -3376,12 +3687,14 @@ mod tests {
         ];
 
         let main = Function {
+            attributes: vec![],
             arguments: vec![],
             statements: main_statements,
             signature: UnresolvedSignature {
                 inputs: vec![],
                 outputs: vec![UnresolvedType::FieldElement.mock()],
             },
+            private_outputs: vec![],
         }
         .mock();
 
@@ -3437,6 +3750,7 @@ mod tests {
         .mock()];
 
         let foo = Function {
+            attributes: vec![],
             arguments: vec![],
             statements: foo_statements,
             signature: UnresolvedSignature {
@@ -3446,6 +3760,7 @@ mod tests {
                     UnresolvedType::FieldElement.mock(),
                 ],
             },
+            private_outputs: vec![],
         }
         .mock();
 
@@ -3468,12 +3783,14 @@ mod tests {
         ];
 
         let main = Function {
+            attributes: vec![],
             arguments: vec![],
             statements: main_statements,
             signature: UnresolvedSignature {
                 inputs: vec![],
                 outputs: vec![],
             },
+            private_outputs: vec![],
         }
         .mock();
 
@@ -3536,12 +3853,14 @@ mod tests {
         .mock()];
 
         let foo = Function {
+            attributes: vec![],
             arguments: vec![],
             statements: foo_statements,
             signature: UnresolvedSignature {
                 inputs: vec![],
                 outputs: vec![UnresolvedType::FieldElement.mock()],
             },
+            private_outputs: vec![],
         }
         .mock();
 
@@ -3583,12 +3902,14 @@ mod tests {
         ];
 
         let main = Function {
+            attributes: vec![],
             arguments: vec![],
             statements: main_statements,
             signature: UnresolvedSignature {
                 inputs: vec![],
                 outputs: vec![],
             },
+            private_outputs: vec![],
         }
         .mock();
 
@@ -3638,12 +3959,14 @@ mod tests {
         .mock()];
 
         let bar = Function {
+            attributes: vec![],
             arguments: vec![],
             statements: bar_statements,
             signature: UnresolvedSignature {
                 inputs: vec![],
                 outputs: vec![UnresolvedType::FieldElement.mock()],
             },
+            private_outputs: vec![],
         }
         .mock();
 
@@ -3662,6 +3985,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn select_type_mismatch() {
+        // def bar() -> field:
+        //   return select(true, 1, false)
+        // should fail
+        let bar_statements: Vec<StatementNode<Bn128Field>> = vec![Statement::Return(
+            ExpressionList {
+                expressions: vec![Expression::FunctionCall(
+                    "select",
+                    vec![
+                        Expression::BooleanConstant(true).mock(),
+                        Expression::FieldConstant(Bn128Field::from(1)).mock(),
+                        Expression::BooleanConstant(false).mock(),
+                    ],
+                )
+                .mock()],
+            }
+            .mock(),
+        )
+        .mock()];
+
+        let bar = Function {
+            attributes: vec![],
+            arguments: vec![],
+            statements: bar_statements,
+            signature: UnresolvedSignature {
+                inputs: vec![],
+                outputs: vec![UnresolvedType::FieldElement.mock()],
+            },
+            private_outputs: vec![],
+        }
+        .mock();
+
+        let types = HashMap::new();
+        let module_id = "".into();
+
+        let mut checker = new_with_args(HashSet::new(), 0, HashSet::new());
+        assert_eq!(
+            checker.check_function(bar, &module_id, &types),
+            Err(vec![ErrorInner {
+                pos: Some((Position::mock(), Position::mock())),
+
+                message: "{consequence} and {alternative} in `select` should have the same type, found field, bool"
+                    .into()
+            }])
+        );
+    }
+
     #[test]
     fn return_undefined() {
         // def bar():
@@ -3679,6 +4050,7 @@ mod tests {
         .mock()];
 
         let bar = Function {
+            attributes: vec![],
             arguments: vec![],
             statements: bar_statements,
             signature: UnresolvedSignature {
@@ -3688,6 +4060,7 @@ mod tests {
                     UnresolvedType::FieldElement.mock(),
                 ],
             },
+            private_outputs: vec![],
         }
         .mock();
 
@@ -3704,6 +4077,131 @@ mod tests {
         );
     }
 
+    #[test]
+    fn attributes() {
+        // #[inline(never)]
+        // #[max_constraints(5000)]
+        // def bar():
+        //   return
+        // should succeed
+        //
+        // #[inline(always)]
+        // #[max_constraints(many)]
+        // #[max_constraints(10)]
+        // def bar():
+        //   return
+        // should fail
+        //
+        // #[inline(never)]
+        // #[foo]
+        // #[inline(sometimes)]
+        // def bar():
+        //   return
+        // should fail
+        let bar = |attributes: Vec<Attribute<'static>>| {
+            Function {
+                attributes: attributes.into_iter().map(|a| a.mock()).collect(),
+                arguments: vec![],
+                statements: vec![Statement::Return(
+                    ExpressionList::<Bn128Field> {
+                        expressions: vec![],
+                    }
+                    .mock(),
+                )
+                .mock()],
+                signature: UnresolvedSignature::new(),
+                private_outputs: vec![],
+            }
+            .mock()
+        };
+
+        let never = Attribute {
+            id: "inline",
+            argument: Some("never"),
+        };
+
+        let max_constraints = |argument| Attribute {
+            id: "max_constraints",
+            argument: Some(argument),
+        };
+
+        let types = HashMap::new();
+        let module_id = "".into();
+
+        let mut checker = new_with_args(HashSet::new(), 0, HashSet::new());
+        assert_eq!(
+            checker
+                .check_function(
+                    bar(vec![never.clone(), max_constraints("5000")]),
+                    &module_id,
+                    &types
+                )
+                .unwrap()
+                .attributes,
+            FunctionAttributes {
+                inline: Some(Inline::Never),
+                max_constraints: Some(5000)
+            }
+        );
+
+        let mut checker = new_with_args(HashSet::new(), 0, HashSet::new());
+        assert_eq!(
+            checker.check_function(
+                bar(vec![
+                    Attribute {
+                        id: "inline",
+                        argument: Some("always"),
+                    },
+                    max_constraints("many"),
+                    max_constraints("10"),
+                ]),
+                &module_id,
+                &types
+            ),
+            Err(vec![
+                ErrorInner {
+                    pos: Some((Position::mock(), Position::mock())),
+                    message: "Expected `max_constraints(n)` where `n` is a number, found `#[max_constraints(many)]`".into()
+                },
+                ErrorInner {
+                    pos: Some((Position::mock(), Position::mock())),
+                    message: "`max_constraints` cannot be used on a function annotated with `inline(always)`".into()
+                }
+            ])
+        );
+
+        let mut checker = new_with_args(HashSet::new(), 0, HashSet::new());
+        assert_eq!(
+            checker.check_function(
+                bar(vec![
+                    never,
+                    Attribute {
+                        id: "foo",
+                        argument: None,
+                    },
+                    Attribute {
+                        id: "inline",
+                        argument: Some("sometimes"),
+                    },
+                ]),
+                &module_id,
+                &types
+            ),
+            Err(vec![
+                ErrorInner {
+                    pos: Some((Position::mock(), Position::mock())),
+                    message: "Unknown attribute `#[foo]`".into()
+                },
+                ErrorInner {
+                    pos: Some((Position::mock(), Position::mock())),
+                    message:
+                        "Expected `inline(always)` or `inline(never)`, found `#[inline(sometimes)]`"
+                            .into()
+                }
+            ])
+        );
+    }
+
     #[test]
     fn multi_def() {
         // def foo():
@@ -3778,22 +4276,26 @@ mod tests {
         functions.insert(foo);
 
         let bar = Function {
+            attributes: vec![],
             arguments: vec![],
             statements: bar_statements,
             signature: UnresolvedSignature {
                 inputs: vec![],
                 outputs: vec![UnresolvedType::FieldElement.mock()],
             },
+            private_outputs: vec![],
         }
         .mock();
 
         let bar_checked = TypedFunction {
+            attributes: FunctionAttributes::default(),
             arguments: vec![],
             statements: bar_statements_checked,
             signature: Signature {
                 inputs: vec![],
                 outputs: vec![Type::FieldElement],
             },
+            private_outputs: vec![],
         };
 
         let types = HashMap::new();
@@ -3839,22 +4341,26 @@ mod tests {
         let main2_arguments = vec![];
 
         let main1 = Function {
+            attributes: vec![],
             arguments: main1_arguments,
             statements: main1_statements,
             signature: UnresolvedSignature {
                 inputs: vec![UnresolvedType::FieldElement.mock()],
                 outputs: vec![UnresolvedType::FieldElement.mock()],
             },
+            private_outputs: vec![],
         }
         .mock();
 
         let main2 = Function {
+            attributes: vec![],
             arguments: main2_arguments,
             statements: main2_statements,
             signature: UnresolvedSignature {
                 inputs: vec![],
                 outputs: vec![UnresolvedType::FieldElement.mock()],
             },
+            private_outputs: vec![],
         }
         .mock();
 
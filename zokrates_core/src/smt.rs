@@ -0,0 +1,565 @@
+//! Module exporting the verification conditions of annotated functions to SMT-LIB
+//!
+//! Functions can be annotated with `// @requires <condition>` and `// @ensures <condition>` comments, where the
+//! conditions are boolean ZoKrates expressions over the parameters of the function and, for `@ensures`, its return
+//! values. For each annotated function, a harness calling it is compiled to ZIR, whose semantics are then encoded in
+//! SMT-LIB so that the conditions can be checked by a solver such as Z3 or CVC5.
+
+use num_bigint::BigUint;
+use regex::Regex;
+use std::collections::HashSet;
+use std::fmt;
+use zir::ranged::{
+    BooleanExpression, FieldElementExpression, UExpression, ZirExpression, ZirProgram,
+};
+use zir::*;
+use zokrates_field::Field;
+
+/// Name given to `main` in harnesses, which define their own `main`
+const RENAMED_MAIN: &str = "main_unverified";
+
+/// The annotations of a function, along with the parts of its definition needed to call it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Specification {
+    pub function: String,
+    /// parameters as they appear in the source, for example `private field a`
+    pub parameters: Vec<String>,
+    /// return types as they appear in the source
+    pub outputs: Vec<String>,
+    pub requires: Vec<String>,
+    pub ensures: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// Annotations starting at the given line are not followed by a function definition on a single line
+    Unattached(usize),
+    /// An annotated function has no return value
+    NoOutputs(String),
+    /// An expression cannot be encoded in SMT-LIB
+    Unsupported(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Unattached(line) => write!(
+                f,
+                "Annotations on line {} must be followed by a function definition on a single line",
+                line
+            ),
+            Error::NoOutputs(ref function) => write!(
+                f,
+                "Annotated function `{}` must return at least one value",
+                function
+            ),
+            Error::Unsupported(ref e) => write!(
+                f,
+                "Cannot export verification conditions: {} is not supported",
+                e
+            ),
+        }
+    }
+}
+
+/// Returns the specifications of the annotated functions of a module, in the order of their definitions
+pub fn specifications(source: &str) -> Result<Vec<Specification>, Error> {
+    let annotation = Regex::new(r"^\s*//\s*@(requires|ensures)\s+(.+?)\s*$").unwrap();
+    let definition =
+        Regex::new(r"^\s*def\s+(\w+)\s*\(([^)]*)\)\s*(?:->\s*(.+?))?\s*:\s*(?://.*)?$").unwrap();
+
+    let mut res = vec![];
+    let mut requires = vec![];
+    let mut ensures = vec![];
+    // the line of the first pending annotation
+    let mut first = None;
+
+    for (index, line) in source.lines().enumerate() {
+        if let Some(captures) = annotation.captures(line) {
+            first.get_or_insert(index + 1);
+            match &captures[1] {
+                "requires" => requires.push(captures[2].to_string()),
+                _ => ensures.push(captures[2].to_string()),
+            }
+            continue;
+        }
+
+        let line_number = match first {
+            Some(line_number) => line_number,
+            None => continue,
+        };
+
+        // other comments may be interleaved with annotations
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+
+        let captures = definition
+            .captures(line)
+            .ok_or(Error::Unattached(line_number))?;
+
+        let function = captures[1].to_string();
+
+        let outputs = captures
+            .get(3)
+            .map(|outputs| {
+                outputs
+                    .as_str()
+                    .trim_start_matches('(')
+                    .trim_end_matches(')')
+                    .split(',')
+                    .map(|ty| ty.trim().to_string())
+                    .filter(|ty| !ty.is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        if outputs.is_empty() {
+            return Err(Error::NoOutputs(function));
+        }
+
+        res.push(Specification {
+            function,
+            parameters: captures[2]
+                .split(',')
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect(),
+            outputs,
+            requires: requires.drain(..).collect(),
+            ensures: ensures.drain(..).collect(),
+        });
+
+        first = None;
+    }
+
+    match first {
+        Some(line_number) => Err(Error::Unattached(line_number)),
+        None => Ok(res),
+    }
+}
+
+impl Specification {
+    fn results(&self) -> Vec<String> {
+        match self.outputs.len() {
+            1 => vec![String::from("result")],
+            n => (0..n).map(|i| format!("result{}", i)).collect(),
+        }
+    }
+
+    /// Returns `source` with its `main` replaced by a function calling the specified function, and returning its
+    /// return values followed by the values of the `@requires` and `@ensures` conditions.
+    /// Return values are named `result`, or `result0`, `result1`, ... if the function returns more than one value.
+    pub fn harness(&self, source: &str) -> String {
+        let main = Regex::new(r"(?m)^(\s*def\s+)main(\s*\()").unwrap();
+        let source = main.replace(source, format!("${{1}}{}${{2}}", RENAMED_MAIN).as_str());
+
+        let callee = match self.function.as_str() {
+            "main" => RENAMED_MAIN,
+            function => function,
+        };
+
+        let arguments: Vec<_> = self
+            .parameters
+            .iter()
+            .map(|p| p.split_whitespace().last().unwrap())
+            .collect();
+
+        let conditions = self.requires.iter().chain(self.ensures.iter());
+
+        format!(
+            "{}\n\ndef main({}) -> ({}):\n\t{} = {}({})\n\treturn {}\n",
+            source.trim_end(),
+            self.parameters.join(", "),
+            self.outputs
+                .iter()
+                .cloned()
+                .chain(conditions.clone().map(|_| String::from("bool")))
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.outputs
+                .iter()
+                .zip(self.results())
+                .map(|(ty, result)| format!("{} {}", ty, result))
+                .collect::<Vec<_>>()
+                .join(", "),
+            callee,
+            arguments.join(", "),
+            self.results()
+                .into_iter()
+                .chain(conditions.cloned())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+/// Encodes the `main` function of a compiled harness in SMT-LIB, such that `unsat` proves that the `@ensures`
+/// conditions hold for all inputs satisfying the `@requires` conditions and the assertions of the function.
+/// Field elements are integers modulo the field size and unsigned integers are bit vectors. The return values of
+/// built-in functions such as `unpack` are left unconstrained, so a model found by the solver may not be reachable.
+pub fn verification_conditions<T: Field>(
+    program: &ZirProgram<T>,
+    specification: &Specification,
+) -> Result<String, Error> {
+    let main = program.modules[&program.main]
+        .functions
+        .iter()
+        .find(|(key, _)| key.id == "main")
+        .map(|(_, symbol)| match symbol {
+            ZirFunctionSymbol::Here(main) => main,
+            _ => unreachable!("main should be a function of the harness"),
+        })
+        .unwrap();
+
+    let mut encoder = Encoder::<T>::default();
+
+    encoder.lines.push(format!(
+        "; verification conditions of `{}`: `unsat` means that they hold",
+        specification.function
+    ));
+    encoder.lines.push(String::from("(set-logic ALL)"));
+
+    for parameter in &main.arguments {
+        encoder.declare(&parameter.id);
+    }
+
+    let mut returned = vec![];
+
+    for statement in &main.statements {
+        match statement {
+            ZirStatement::Declaration(v) => encoder.declare(v),
+            ZirStatement::Definition(v, e) => {
+                let e = encoder.expression(e)?;
+                encoder.declare(v);
+                encoder
+                    .lines
+                    .push(format!("(assert (= {} {}))", name(&v.id), e));
+            }
+            ZirStatement::Assertion(e) => {
+                let e = encoder.boolean(e)?;
+                encoder.lines.push(format!("(assert {})", e));
+            }
+            ZirStatement::MultipleDefinition(
+                variables,
+                ZirExpressionList::FunctionCall(key, ..),
+            ) => {
+                encoder
+                    .lines
+                    .push(format!("; the outputs of `{}` are unconstrained", key.id));
+                for v in variables {
+                    encoder.declare(v);
+                }
+            }
+            ZirStatement::Return(expressions) => {
+                returned = expressions
+                    .iter()
+                    .map(|e| encoder.expression(e))
+                    .collect::<Result<Vec<_>, _>>()?;
+            }
+        }
+    }
+
+    // the conditions are the last return values of the harness
+    let ensures = returned.split_off(returned.len() - specification.ensures.len());
+    let requires = returned.split_off(returned.len() - specification.requires.len());
+
+    for (condition, e) in specification.requires.iter().zip(requires) {
+        encoder.lines.push(format!("; @requires {}", condition));
+        encoder.lines.push(format!("(assert {})", e));
+    }
+
+    for condition in &specification.ensures {
+        encoder.lines.push(format!("; @ensures {}", condition));
+    }
+    encoder
+        .lines
+        .push(format!("(assert (not {}))", conjunction(ensures)));
+    encoder.lines.push(String::from("(check-sat)"));
+
+    Ok(encoder.lines.join("\n") + "\n")
+}
+
+fn name(id: &Identifier) -> String {
+    format!("|{}|", id)
+}
+
+fn conjunction(mut conditions: Vec<String>) -> String {
+    match conditions.len() {
+        0 => String::from("true"),
+        1 => conditions.pop().unwrap(),
+        _ => format!("(and {})", conditions.join(" ")),
+    }
+}
+
+/// Returns the constant value of an expression, such as the exponent of a power or the amount of a shift
+fn constant<T: Field>(e: &FieldElementExpression<T>, what: &str) -> Result<usize, Error> {
+    match e {
+        FieldElementExpression::Number(n) => n
+            .to_dec_string()
+            .parse()
+            .map_err(|_| Error::Unsupported(format!("{} {}", what, n))),
+        _ => Err(Error::Unsupported(format!("non-constant {}", what))),
+    }
+}
+
+struct Encoder<T> {
+    lines: Vec<String>,
+    declared: HashSet<String>,
+    /// number of variables introduced by the encoding
+    fresh: usize,
+    modulus: BigUint,
+    _field: std::marker::PhantomData<T>,
+}
+
+impl<T: Field> Default for Encoder<T> {
+    fn default() -> Self {
+        Encoder {
+            lines: vec![],
+            declared: HashSet::new(),
+            fresh: 0,
+            modulus: T::max_value().to_biguint() + 1u32,
+            _field: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T: Field> Encoder<T> {
+    fn declare(&mut self, v: &Variable) {
+        self.declare_name(name(&v.id), &v._type);
+    }
+
+    fn declare_name(&mut self, name: String, ty: &Type) {
+        if !self.declared.insert(name.clone()) {
+            return;
+        }
+
+        let sort = match ty {
+            Type::FieldElement => String::from("Int"),
+            Type::Boolean => String::from("Bool"),
+            Type::Uint(bitwidth) => format!("(_ BitVec {})", bitwidth.to_usize()),
+        };
+
+        self.lines
+            .push(format!("(declare-const {} {})", name, sort));
+
+        if *ty == Type::FieldElement {
+            self.lines.push(format!(
+                "(assert (and (<= 0 {}) (< {} {})))",
+                name, name, self.modulus
+            ));
+        }
+    }
+
+    fn reduce(&self, e: String) -> String {
+        format!("(mod {} {})", e, self.modulus)
+    }
+
+    fn expression(&mut self, e: &ZirExpression<T>) -> Result<String, Error> {
+        match e {
+            ZirExpression::FieldElement(e) => self.field(e),
+            ZirExpression::Boolean(e) => self.boolean(e),
+            ZirExpression::Uint(e) => self.uint(e),
+        }
+    }
+
+    fn field(&mut self, e: &FieldElementExpression<T>) -> Result<String, Error> {
+        Ok(match e {
+            FieldElementExpression::Number(n) => n.to_dec_string(),
+            FieldElementExpression::Identifier(id) => name(id),
+            FieldElementExpression::Add(left, right) => {
+                let e = format!("(+ {} {})", self.field(left)?, self.field(right)?);
+                self.reduce(e)
+            }
+            FieldElementExpression::Sub(left, right) => {
+                let e = format!("(- {} {})", self.field(left)?, self.field(right)?);
+                self.reduce(e)
+            }
+            FieldElementExpression::Mult(left, right) => {
+                let e = format!("(* {} {})", self.field(left)?, self.field(right)?);
+                self.reduce(e)
+            }
+            FieldElementExpression::Div(left, right) => {
+                // like the compiled program, introduce the quotient and constrain its product with the divisor
+                let (left, right) = (self.field(left)?, self.field(right)?);
+                let quotient = format!("|#smt_div_{}|", self.fresh);
+                self.fresh += 1;
+                self.declare_name(quotient.clone(), &Type::FieldElement);
+                let product = self.reduce(format!("(* {} {})", quotient, right));
+                self.lines
+                    .push(format!("(assert (= {} {}))", product, left));
+                quotient
+            }
+            FieldElementExpression::Pow(base, exponent) => {
+                let exponent = constant(exponent, "exponent")?;
+                let base = self.field(base)?;
+                (0..exponent).fold(String::from("1"), |acc, _| {
+                    self.reduce(format!("(* {} {})", acc, base))
+                })
+            }
+            FieldElementExpression::IfElse(condition, consequence, alternative) => format!(
+                "(ite {} {} {})",
+                self.boolean(condition)?,
+                self.field(consequence)?,
+                self.field(alternative)?
+            ),
+        })
+    }
+
+    fn boolean(&mut self, e: &BooleanExpression<T>) -> Result<String, Error> {
+        Ok(match e {
+            BooleanExpression::Identifier(id) => name(id),
+            BooleanExpression::Value(v) => v.to_string(),
+            BooleanExpression::Lt(left, right) => {
+                format!("(< {} {})", self.field(left)?, self.field(right)?)
+            }
+            BooleanExpression::Le(left, right) => {
+                format!("(<= {} {})", self.field(left)?, self.field(right)?)
+            }
+            BooleanExpression::Ge(left, right) => {
+                format!("(>= {} {})", self.field(left)?, self.field(right)?)
+            }
+            BooleanExpression::Gt(left, right) => {
+                format!("(> {} {})", self.field(left)?, self.field(right)?)
+            }
+            BooleanExpression::FieldEq(left, right) => {
+                format!("(= {} {})", self.field(left)?, self.field(right)?)
+            }
+            BooleanExpression::BoolEq(left, right) => {
+                format!("(= {} {})", self.boolean(left)?, self.boolean(right)?)
+            }
+            BooleanExpression::UintEq(left, right) => {
+                format!("(= {} {})", self.uint(left)?, self.uint(right)?)
+            }
+            BooleanExpression::Or(left, right) => {
+                format!("(or {} {})", self.boolean(left)?, self.boolean(right)?)
+            }
+            BooleanExpression::And(left, right) => {
+                format!("(and {} {})", self.boolean(left)?, self.boolean(right)?)
+            }
+            BooleanExpression::Not(e) => format!("(not {})", self.boolean(e)?),
+            BooleanExpression::IfElse(condition, consequence, alternative) => format!(
+                "(ite {} {} {})",
+                self.boolean(condition)?,
+                self.boolean(consequence)?,
+                self.boolean(alternative)?
+            ),
+        })
+    }
+
+    fn uint(&mut self, e: &UExpression<T>) -> Result<String, Error> {
+        let bitwidth = e.bitwidth.to_usize();
+
+        Ok(match &e.inner {
+            UExpressionInner::Value(v) => format!("(_ bv{} {})", v, bitwidth),
+            UExpressionInner::Identifier(id) => name(id),
+            UExpressionInner::Add(left, right) => self.uint_binary("bvadd", left, right)?,
+            UExpressionInner::Sub(left, right) => self.uint_binary("bvsub", left, right)?,
+            UExpressionInner::Mult(left, right) => self.uint_binary("bvmul", left, right)?,
+            UExpressionInner::Div(left, right) => self.uint_division("bvudiv", left, right)?,
+            UExpressionInner::Rem(left, right) => self.uint_division("bvurem", left, right)?,
+            UExpressionInner::Xor(left, right) => self.uint_binary("bvxor", left, right)?,
+            UExpressionInner::And(left, right) => self.uint_binary("bvand", left, right)?,
+            UExpressionInner::Or(left, right) => self.uint_binary("bvor", left, right)?,
+            UExpressionInner::Not(e) => format!("(bvnot {})", self.uint(e)?),
+            UExpressionInner::LeftShift(e, by) => format!(
+                "(bvshl {} (_ bv{} {}))",
+                self.uint(e)?,
+                constant(by, "shift")?,
+                bitwidth
+            ),
+            UExpressionInner::RightShift(e, by) => format!(
+                "(bvlshr {} (_ bv{} {}))",
+                self.uint(e)?,
+                constant(by, "shift")?,
+                bitwidth
+            ),
+            UExpressionInner::IfElse(condition, consequence, alternative) => format!(
+                "(ite {} {} {})",
+                self.boolean(condition)?,
+                self.uint(consequence)?,
+                self.uint(alternative)?
+            ),
+        })
+    }
+
+    fn uint_binary(
+        &mut self,
+        op: &str,
+        left: &UExpression<T>,
+        right: &UExpression<T>,
+    ) -> Result<String, Error> {
+        Ok(format!(
+            "({} {} {})",
+            op,
+            self.uint(left)?,
+            self.uint(right)?
+        ))
+    }
+
+    /// Like the compiled program, which cannot be satisfied with a zero divisor, asserts that the divisor is not zero
+    fn uint_division(
+        &mut self,
+        op: &str,
+        left: &UExpression<T>,
+        right: &UExpression<T>,
+    ) -> Result<String, Error> {
+        let bitwidth = right.bitwidth.to_usize();
+        let (left, right) = (self.uint(left)?, self.uint(right)?);
+        self.lines
+            .push(format!("(assert (not (= {} (_ bv0 {}))))", right, bitwidth));
+        Ok(format!("({} {} {})", op, left, right))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+// @requires b != 0
+// multiplies `a` by the inverse of `b`
+// @ensures result * b == a
+def div(field a, private field b) -> field:
+	return a / b
+
+def main(field a) -> (field, bool):
+	return div(a, 2), true
+"#;
+
+    #[test]
+    fn specifications() {
+        assert_eq!(
+            super::specifications(SOURCE),
+            Ok(vec![Specification {
+                function: String::from("div"),
+                parameters: vec![String::from("field a"), String::from("private field b")],
+                outputs: vec![String::from("field")],
+                requires: vec![String::from("b != 0")],
+                ensures: vec![String::from("result * b == a")],
+            }])
+        );
+
+        assert_eq!(
+            super::specifications("// @ensures true\nfield a = 1"),
+            Err(Error::Unattached(1))
+        );
+        assert_eq!(
+            super::specifications("// @ensures true\ndef main():\n\treturn"),
+            Err(Error::NoOutputs(String::from("main")))
+        );
+    }
+
+    #[test]
+    fn harness() {
+        let specification = &super::specifications(SOURCE).unwrap()[0];
+
+        assert!(specification.harness(SOURCE).ends_with(
+            "def main_unverified(field a) -> (field, bool):\n\treturn div(a, 2), true\n\n\
+             def main(field a, private field b) -> (field, bool, bool):\n\
+             \tfield result = div(a, b)\n\
+             \treturn result, b != 0, result * b == a\n"
+        ));
+    }
+}
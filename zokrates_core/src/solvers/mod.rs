@@ -6,10 +6,14 @@ pub enum Solver {
     ConditionEq,
     Bits(usize),
     Div,
+    /// The quotient and the remainder of the euclidean division of two integers
+    EuclideanDiv,
     Xor,
     Or,
     ShaAndXorAndXorAnd,
     ShaCh,
+    /// A call to the oracle with the given name, taking and returning the given numbers of field elements
+    Oracle(String, usize, usize),
 }
 
 impl fmt::Display for Solver {
@@ -24,10 +28,12 @@ impl Solver {
             Solver::ConditionEq => (1, 2),
             Solver::Bits(bit_width) => (1, *bit_width),
             Solver::Div => (2, 1),
+            Solver::EuclideanDiv => (2, 2),
             Solver::Xor => (2, 1),
             Solver::Or => (2, 1),
             Solver::ShaAndXorAndXorAnd => (3, 1),
             Solver::ShaCh => (3, 1),
+            Solver::Oracle(_, inputs, outputs) => (*inputs, *outputs),
         }
     }
 }
@@ -0,0 +1,251 @@
+//! Module containing dead code elimination for the zir AST
+//!
+//! A definition is removed when its variable is never read, which saves the constraints it would be flattened to.
+//! Statements are visited in reverse, so that removing a definition can make the ones it reads dead in turn.
+//! Definitions are kept if their expression can fail, such as a division by zero, or if they are the last statement
+//! to read an argument, which would otherwise be left unconstrained.
+
+use crate::zir::folder::*;
+use crate::zir::*;
+use std::collections::{HashMap, HashSet};
+use zokrates_field::Field;
+
+pub struct DeadCodeEliminator;
+
+impl DeadCodeEliminator {
+    pub fn eliminate<'ast, T: Field>(p: ZirProgram<'ast, T>) -> ZirProgram<'ast, T> {
+        DeadCodeEliminator.fold_program(p)
+    }
+}
+
+/// The variables read by a statement, with their number of occurrences
+#[derive(Default)]
+struct Reads<'ast> {
+    identifiers: HashMap<Identifier<'ast>, usize>,
+    // whether evaluating the statement can fail
+    fallible: bool,
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for Reads<'ast> {
+    fn fold_name(&mut self, n: Identifier<'ast>) -> Identifier<'ast> {
+        *self.identifiers.entry(n.clone()).or_insert(0) += 1;
+        n
+    }
+
+    // variables are only found in definitions and declarations, which do not read them
+    fn fold_variable(&mut self, v: Variable<'ast>) -> Variable<'ast> {
+        v
+    }
+
+    fn fold_field_expression(
+        &mut self,
+        e: FieldElementExpression<'ast, T>,
+    ) -> FieldElementExpression<'ast, T> {
+        if let FieldElementExpression::Div(..) = e {
+            self.fallible = true;
+        }
+        fold_field_expression(self, e)
+    }
+
+    fn fold_uint_expression(&mut self, e: UExpression<'ast, T>) -> UExpression<'ast, T> {
+        match e.inner {
+            UExpressionInner::Div(..) | UExpressionInner::Rem(..) => self.fallible = true,
+            _ => {}
+        }
+        fold_uint_expression(self, e)
+    }
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for DeadCodeEliminator {
+    fn fold_function(&mut self, f: ZirFunction<'ast, T>) -> ZirFunction<'ast, T> {
+        let statements: Vec<_> = f
+            .statements
+            .into_iter()
+            .map(|s| {
+                let mut reads = Reads::default();
+                let s = reads.fold_statement(s);
+                (s, reads)
+            })
+            .collect();
+
+        let mut uses: HashMap<Identifier<'ast>, usize> = HashMap::new();
+        for (_, reads) in &statements {
+            for (id, count) in &reads.identifiers {
+                *uses.entry(id.clone()).or_insert(0) += count;
+            }
+        }
+
+        let arguments: HashSet<_> = f.arguments.iter().map(|a| a.id.id.clone()).collect();
+        let mut removed = HashSet::new();
+
+        let mut statements: Vec<_> = statements
+            .into_iter()
+            .rev()
+            .filter_map(|(s, reads)| {
+                let dead = s.iter().all(|s| match s {
+                    ZirStatement::Definition(v, _) => {
+                        !reads.fallible
+                            && uses.get(&v.id).cloned().unwrap_or(0) == 0
+                            && reads
+                                .identifiers
+                                .iter()
+                                .all(|(id, count)| !arguments.contains(id) || uses[id] > *count)
+                    }
+                    ZirStatement::Declaration(v) => removed.contains(&v.id),
+                    _ => false,
+                });
+
+                match dead {
+                    true => {
+                        for (id, count) in reads.identifiers {
+                            *uses.get_mut(&id).unwrap() -= count;
+                        }
+                        for s in s {
+                            if let ZirStatement::Definition(v, _) = s {
+                                removed.insert(v.id);
+                            }
+                        }
+                        None
+                    }
+                    false => Some(s),
+                }
+            })
+            .flatten()
+            .collect();
+
+        statements.reverse();
+
+        ZirFunction { statements, ..f }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zir::types::Signature;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn unused_definitions() {
+        // def main(field a, field b) -> (field):
+        //     field c = a * a
+        //     field d = c + 1
+        //     field e = a / a
+        //     field f = b * 2
+        //     return a
+        //
+        // becomes
+        //
+        // def main(field a, field b) -> (field):
+        //     field e = a / a
+        //     field f = b * 2
+        //     return a
+        //
+        // as `e` can fail and `f` is the only statement to read `b`
+
+        let division = ZirStatement::Definition(
+            Variable::field_element("e"),
+            FieldElementExpression::Div(
+                box FieldElementExpression::Identifier("a".into()),
+                box FieldElementExpression::Identifier("a".into()),
+            )
+            .into(),
+        );
+
+        let argument = ZirStatement::Definition(
+            Variable::field_element("f"),
+            FieldElementExpression::Mult(
+                box FieldElementExpression::Identifier("b".into()),
+                box FieldElementExpression::Number(Bn128Field::from(2)),
+            )
+            .into(),
+        );
+
+        let ret = ZirStatement::Return(vec![FieldElementExpression::Identifier("a".into()).into()]);
+
+        let f: ZirFunction<Bn128Field> = ZirFunction {
+            arguments: vec![
+                Parameter::private(Variable::field_element("a")),
+                Parameter::private(Variable::field_element("b")),
+            ],
+            statements: vec![
+                ZirStatement::Definition(
+                    Variable::field_element("c"),
+                    FieldElementExpression::Mult(
+                        box FieldElementExpression::Identifier("a".into()),
+                        box FieldElementExpression::Identifier("a".into()),
+                    )
+                    .into(),
+                ),
+                ZirStatement::Definition(
+                    Variable::field_element("d"),
+                    FieldElementExpression::Add(
+                        box FieldElementExpression::Identifier("c".into()),
+                        box FieldElementExpression::Number(Bn128Field::from(1)),
+                    )
+                    .into(),
+                ),
+                division.clone(),
+                argument.clone(),
+                ret.clone(),
+            ],
+            signature: Signature::new()
+                .inputs(vec![Type::FieldElement, Type::FieldElement])
+                .outputs(vec![Type::FieldElement]),
+        };
+
+        let expected = ZirFunction {
+            statements: vec![division, argument, ret],
+            ..f.clone()
+        };
+
+        assert_eq!(DeadCodeEliminator.fold_function(f), expected);
+    }
+
+    #[test]
+    fn unused_uint_divisions() {
+        // def main(u32 a) -> (u32):
+        //     u32 x = a / 0
+        //     u32 y = a % 0
+        //     u32 z = a * a
+        //     return a
+        //
+        // becomes
+        //
+        // def main(u32 a) -> (u32):
+        //     u32 x = a / 0
+        //     u32 y = a % 0
+        //     return a
+        //
+        // as `x` and `y` can fail
+
+        let a = || UExpressionInner::Identifier("a".into()).annotate(32);
+        let zero = || UExpressionInner::Value(0).annotate(32);
+
+        let division =
+            ZirStatement::Definition(Variable::uint("x".into(), 32), a().div(zero()).into());
+        let remainder =
+            ZirStatement::Definition(Variable::uint("y".into(), 32), a().rem(zero()).into());
+        let ret = ZirStatement::Return(vec![a().into()]);
+
+        let f: ZirFunction<Bn128Field> = ZirFunction {
+            arguments: vec![Parameter::private(Variable::uint("a".into(), 32))],
+            statements: vec![
+                division.clone(),
+                remainder.clone(),
+                ZirStatement::Definition(Variable::uint("z".into(), 32), a().mult(a()).into()),
+                ret.clone(),
+            ],
+            signature: Signature::new()
+                .inputs(vec![Type::uint(32)])
+                .outputs(vec![Type::uint(32)]),
+        };
+
+        let expected = ZirFunction {
+            statements: vec![division, remainder, ret],
+            ..f.clone()
+        };
+
+        assert_eq!(DeadCodeEliminator.fold_function(f), expected);
+    }
+}
@@ -5,6 +5,7 @@
 //! @date 2018
 
 use crate::flat_absy::*;
+use crate::ir::{LinComb, SourceMap};
 use std::collections::HashMap;
 use zokrates_field::Field;
 
@@ -81,19 +82,31 @@ impl<T: Field> FlatStatement<T> {
                     .collect(),
                 ..d
             })),
+            FlatStatement::Call(c) => Some(FlatStatement::Call(FlatCall {
+                inputs: c
+                    .inputs
+                    .into_iter()
+                    .map(|i| i.propagate(constants))
+                    .collect(),
+                ..c
+            })),
         }
     }
 }
 
 impl<T: Field> Propagate<T> for FlatFunction<T> {
     fn propagate(self) -> FlatFunction<T> {
-        let mut constants = HashMap::new();
+        self.propagate_with_constants(&mut HashMap::new())
+    }
+}
 
+impl<T: Field> FlatFunction<T> {
+    fn propagate_with_constants(self, constants: &mut HashMap<FlatVariable, T>) -> FlatFunction<T> {
         FlatFunction {
             statements: self
                 .statements
                 .into_iter()
-                .filter_map(|s| s.propagate(&mut constants))
+                .filter_map(|s| s.propagate(constants))
                 .collect(),
             ..self
         }
@@ -102,9 +115,34 @@ impl<T: Field> Propagate<T> for FlatFunction<T> {
 
 impl<T: Field> FlatProg<T> {
     pub fn propagate(self) -> FlatProg<T> {
-        let main = self.main.propagate();
+        self.propagate_with_source_map(&mut SourceMap::default())
+    }
+
+    /// Propagates constants, replacing the variables of `source_map` which were found to be constant by their value
+    pub fn propagate_with_source_map(self, source_map: &mut SourceMap<T>) -> FlatProg<T> {
+        let mut constants = HashMap::new();
+
+        let main = self.main.propagate_with_constants(&mut constants);
+
+        // called functions do not share variables with `main`, so they are propagated on their own
+        let functions = self
+            .functions
+            .into_iter()
+            .map(|(id, function)| (id, function.propagate()))
+            .collect();
+
+        source_map.substitute(|l| {
+            LinComb(
+                l.0.into_iter()
+                    .map(|(variable, coefficient)| match constants.get(&variable) {
+                        Some(c) => (FlatVariable::one(), c.clone() * coefficient),
+                        None => (variable, coefficient),
+                    })
+                    .collect(),
+            )
+        });
 
-        FlatProg { main }
+        FlatProg { main, functions }
     }
 }
 
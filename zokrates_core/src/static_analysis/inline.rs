@@ -16,11 +16,19 @@
 
 //! where any call in `main` must be to `_SHA_256_ROUND` or `_UNPACK`
 
+//! Functions annotated with `#[inline(never)]` or `#[max_constraints(n)]`, and large functions called several times
+//! unless annotated with `#[inline(always)]`, are not inlined either: they are defined in the resulting module under the key they were called
+//! with, their own calls inlined, and called from `main` like embeds. Flattening then processes them once for all calls.
+
+use static_analysis::prune::calls;
 use std::collections::HashMap;
 use typed_absy::types::{FunctionKey, Type, UBitwidth};
 use typed_absy::{folder::*, *};
 use zokrates_field::Field;
 
+/// The size, in statements once inlined, from which a function called several times is kept as a shared sub-circuit
+const SHARED_FUNCTION_SIZE: usize = 1000;
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 struct Location<'ast> {
     module: TypedModuleId,
@@ -56,6 +64,18 @@ pub struct Inliner<'ast, T: Field> {
     call_count: HashMap<(TypedModuleId, FunctionKey<'ast>), usize>,
     /// the cache for memoization: for each function body, tracks function calls
     call_cache: CallCache<'ast, T>,
+    /// the oracles called, defined in the resulting module under the key they were imported with
+    oracles: HashMap<FunctionKey<'ast>, crate::embed::FlatEmbed>,
+    /// the field divisions called, defined in the resulting module under their key, which depends on their bound
+    field_divisions: HashMap<FunctionKey<'ast>, crate::embed::FlatEmbed>,
+    /// the number of times each function is called when running `main`
+    executions: HashMap<Location<'ast>, usize>,
+    /// the size of each function once inlined, in statements
+    sizes: HashMap<Location<'ast>, usize>,
+    /// the key under which each function kept as a shared sub-circuit is defined in the resulting module
+    shared_keys: HashMap<Location<'ast>, FunctionKey<'ast>>,
+    /// the functions kept as shared sub-circuits, with their calls inlined
+    shared: Vec<(FunctionKey<'ast>, TypedFunction<'ast, T>)>,
 }
 
 impl<'ast, T: Field> Inliner<'ast, T> {
@@ -74,6 +94,12 @@ impl<'ast, T: Field> Inliner<'ast, T> {
             stack: vec![],
             call_count: HashMap::new(),
             call_cache: HashMap::new(),
+            oracles: HashMap::new(),
+            field_divisions: HashMap::new(),
+            executions: HashMap::new(),
+            sizes: HashMap::new(),
+            shared_keys: HashMap::new(),
+            shared: vec![],
         }
     }
 
@@ -93,10 +119,16 @@ impl<'ast, T: Field> Inliner<'ast, T> {
         // initialize an inliner over all modules, starting from the main module
         let mut inliner = Inliner::with_modules_and_module_id_and_key(
             p.modules,
-            main_module_id,
+            main_module_id.clone(),
             main_key.clone(),
         );
 
+        // measure the functions, to decide which ones to keep as shared sub-circuits
+        inliner.profile(Location {
+            module: main_module_id,
+            key: main_key.clone(),
+        });
+
         // inline all calls in the main function, recursively
         let main = inliner.fold_function_symbol(main);
 
@@ -104,6 +136,26 @@ impl<'ast, T: Field> Inliner<'ast, T> {
         let unpack = crate::embed::FlatEmbed::Unpack(T::get_required_bits());
         let unpack_key = unpack.key::<T>();
 
+        // define functions in the main module for the `unpack` embeds to smaller bitwidths
+        let unpack_bitwidths = crate::embed::UNPACK_BITWIDTHS.iter().map(|bitwidth| {
+            let unpack = crate::embed::FlatEmbed::Unpack(*bitwidth);
+            (unpack.key::<T>(), TypedFunctionSymbol::Flat(unpack))
+        });
+
+        // define functions in the main module for the `divmod` embeds
+        let divmod_bitwidths = crate::embed::DIVMOD_BITWIDTHS.iter().map(|bitwidth| {
+            let divmod = crate::embed::FlatEmbed::DivMod(*bitwidth);
+            (divmod.key::<T>(), TypedFunctionSymbol::Flat(divmod))
+        });
+
+        // define a function in the main module for the `u128_to_bits` embed
+        let u128_to_bits = crate::embed::FlatEmbed::U128ToBits;
+        let u128_to_bits_key = u128_to_bits.key::<T>();
+
+        // define a function in the main module for the `u64_to_bits` embed
+        let u64_to_bits = crate::embed::FlatEmbed::U64ToBits;
+        let u64_to_bits_key = u64_to_bits.key::<T>();
+
         // define a function in the main module for the `u32_to_bits` embed
         let u32_to_bits = crate::embed::FlatEmbed::U32ToBits;
         let u32_to_bits_key = u32_to_bits.key::<T>();
@@ -116,6 +168,14 @@ impl<'ast, T: Field> Inliner<'ast, T> {
         let u8_to_bits = crate::embed::FlatEmbed::U8ToBits;
         let u8_to_bits_key = u8_to_bits.key::<T>();
 
+        // define a function in the main module for the `u128_from_bits` embed
+        let u128_from_bits = crate::embed::FlatEmbed::U128FromBits;
+        let u128_from_bits_key = u128_from_bits.key::<T>();
+
+        // define a function in the main module for the `u64_from_bits` embed
+        let u64_from_bits = crate::embed::FlatEmbed::U64FromBits;
+        let u64_from_bits_key = u64_from_bits.key::<T>();
+
         // define a function in the main module for the `u32_from_bits` embed
         let u32_from_bits = crate::embed::FlatEmbed::U32FromBits;
         let u32_from_bits_key = u32_from_bits.key::<T>();
@@ -128,6 +188,22 @@ impl<'ast, T: Field> Inliner<'ast, T> {
         let u8_from_bits = crate::embed::FlatEmbed::U8FromBits;
         let u8_from_bits_key = u8_from_bits.key::<T>();
 
+        // define a function in the main module for the `u32_to_bytes_be` embed
+        let u32_to_bytes_be = crate::embed::FlatEmbed::U32ToBytesBe;
+        let u32_to_bytes_be_key = u32_to_bytes_be.key::<T>();
+
+        // define a function in the main module for the `u32_to_bytes_le` embed
+        let u32_to_bytes_le = crate::embed::FlatEmbed::U32ToBytesLe;
+        let u32_to_bytes_le_key = u32_to_bytes_le.key::<T>();
+
+        // define a function in the main module for the `u32_from_bytes_be` embed
+        let u32_from_bytes_be = crate::embed::FlatEmbed::U32FromBytesBe;
+        let u32_from_bytes_be_key = u32_from_bytes_be.key::<T>();
+
+        // define a function in the main module for the `u32_from_bytes_le` embed
+        let u32_from_bytes_le = crate::embed::FlatEmbed::U32FromBytesLe;
+        let u32_from_bytes_le_key = u32_from_bytes_le.key::<T>();
+
         // return a program with a single module containing `main`, `_UNPACK`, and `_SHA256_ROUND
         TypedProgram {
             main: "main".into(),
@@ -136,15 +212,58 @@ impl<'ast, T: Field> Inliner<'ast, T> {
                 TypedModule {
                     functions: vec![
                         (unpack_key, TypedFunctionSymbol::Flat(unpack)),
+                        (
+                            u128_from_bits_key,
+                            TypedFunctionSymbol::Flat(u128_from_bits),
+                        ),
+                        (u64_from_bits_key, TypedFunctionSymbol::Flat(u64_from_bits)),
                         (u32_from_bits_key, TypedFunctionSymbol::Flat(u32_from_bits)),
                         (u16_from_bits_key, TypedFunctionSymbol::Flat(u16_from_bits)),
                         (u8_from_bits_key, TypedFunctionSymbol::Flat(u8_from_bits)),
+                        (u128_to_bits_key, TypedFunctionSymbol::Flat(u128_to_bits)),
+                        (u64_to_bits_key, TypedFunctionSymbol::Flat(u64_to_bits)),
                         (u32_to_bits_key, TypedFunctionSymbol::Flat(u32_to_bits)),
                         (u16_to_bits_key, TypedFunctionSymbol::Flat(u16_to_bits)),
                         (u8_to_bits_key, TypedFunctionSymbol::Flat(u8_to_bits)),
+                        (
+                            u32_to_bytes_be_key,
+                            TypedFunctionSymbol::Flat(u32_to_bytes_be),
+                        ),
+                        (
+                            u32_to_bytes_le_key,
+                            TypedFunctionSymbol::Flat(u32_to_bytes_le),
+                        ),
+                        (
+                            u32_from_bytes_be_key,
+                            TypedFunctionSymbol::Flat(u32_from_bytes_be),
+                        ),
+                        (
+                            u32_from_bytes_le_key,
+                            TypedFunctionSymbol::Flat(u32_from_bytes_le),
+                        ),
                         (main_key, main),
                     ]
                     .into_iter()
+                    .chain(unpack_bitwidths)
+                    .chain(divmod_bitwidths)
+                    .chain(
+                        inliner
+                            .oracles
+                            .into_iter()
+                            .map(|(key, oracle)| (key, TypedFunctionSymbol::Flat(oracle))),
+                    )
+                    .chain(
+                        inliner
+                            .field_divisions
+                            .into_iter()
+                            .map(|(key, division)| (key, TypedFunctionSymbol::Flat(division))),
+                    )
+                    .chain(
+                        inliner
+                            .shared
+                            .into_iter()
+                            .map(|(key, function)| (key, TypedFunctionSymbol::Here(function))),
+                    )
                     .collect(),
                 },
             )]
@@ -167,6 +286,18 @@ impl<'ast, T: Field> Inliner<'ast, T> {
             _ => {}
         };
 
+        // if the function is kept as a shared sub-circuit, replace the call with a call to the function we define in
+        // the resulting module
+        if let Some(shared_key) = self.resolve(key).and_then(|l| self.share(key, l)) {
+            // increase the number of calls for this function by one
+            let _ = self
+                .call_count
+                .entry((self.module_id().clone(), shared_key.clone()))
+                .and_modify(|i| *i += 1)
+                .or_insert(1);
+            return Err((shared_key, expressions));
+        }
+
         // here we clone a function symbol, which is cheap except when it contains the function body, in which case we'd clone anyways
         let res = match self.module().functions.get(&key).unwrap().clone() {
             // if the function called is in the same module, we can go ahead and inline in this module
@@ -228,13 +359,30 @@ impl<'ast, T: Field> Inliner<'ast, T> {
                 let (current_module, current_key) =
                     self.change_context(module_id, function_key.clone());
                 // inline the call there
-                let res = self.try_inline_call(&function_key, expressions.clone())?;
+                let res = self.try_inline_call(&function_key, expressions.clone());
                 // switch back focus
                 self.change_context(current_module, current_key);
-                Ok(res)
+                // calls which are not inlined are also counted here, where their results are named
+                if let Err((ref key, _)) = res {
+                    let _ = self
+                        .call_count
+                        .entry((self.module_id().clone(), key.clone()))
+                        .and_modify(|i| *i += 1)
+                        .or_insert(1);
+                }
+                res
+            }
+            // if the function is an oracle, keep its key as its name is not known statically
+            TypedFunctionSymbol::Flat(oracle @ crate::embed::FlatEmbed::Oracle { .. }) => {
+                self.oracles.insert(key.clone(), oracle);
+                Err((key.clone(), expressions.clone()))
             }
             // if the function is a flat symbol, replace the call with a call to the local function we provide so it can be inlined in flattening
             TypedFunctionSymbol::Flat(embed) => {
+                // field divisions are only provided for the bounds which are used
+                if let crate::embed::FlatEmbed::FieldDivMod(_) = embed {
+                    self.field_divisions.insert(embed.key::<T>(), embed.clone());
+                }
                 // increase the number of calls for this function by one
                 let _ = self
                     .call_count
@@ -254,6 +402,151 @@ impl<'ast, T: Field> Inliner<'ast, T> {
         })
     }
 
+    /// Measures the functions called when running the function at `main`, recursively: how many times each of them
+    /// is called, and its size once inlined
+    fn profile(&mut self, main: Location<'ast>) {
+        // the functions called, with callees before their callers
+        let mut order = vec![];
+        let mut callees = HashMap::new();
+        self.visit(main.clone(), &mut callees, &mut order);
+
+        for location in &order {
+            let size = callees[location]
+                .iter()
+                .fold(self.function(location).statements.len(), |size, callee| {
+                    size.saturating_add(self.sizes[callee])
+                });
+            self.sizes.insert(location.clone(), size);
+        }
+
+        self.executions.insert(main, 1);
+        for location in order.iter().rev() {
+            let executions = self.executions.get(location).cloned().unwrap_or(0);
+            for callee in &callees[location] {
+                let count = self.executions.entry(callee.clone()).or_insert(0);
+                *count = count.saturating_add(executions);
+            }
+        }
+    }
+
+    /// Visits the functions called by the function at `location` in post-order, collecting their callees
+    fn visit(
+        &self,
+        location: Location<'ast>,
+        callees: &mut HashMap<Location<'ast>, Vec<Location<'ast>>>,
+        order: &mut Vec<Location<'ast>>,
+    ) {
+        if callees.contains_key(&location) {
+            return;
+        }
+
+        let called: Vec<_> = calls(self.function(&location))
+            .into_iter()
+            .filter_map(|key| self.resolve_in(&location.module, &key))
+            .collect();
+
+        callees.insert(location.clone(), called.clone());
+
+        for callee in called {
+            self.visit(callee, callees, order);
+        }
+
+        order.push(location);
+    }
+
+    /// Returns the function defined at `location`
+    fn function(&self, location: &Location<'ast>) -> &TypedFunction<'ast, T> {
+        match &self.modules[&location.module].functions[&location.key] {
+            TypedFunctionSymbol::Here(function) => function,
+            _ => unreachable!("only functions defined in a module have a location"),
+        }
+    }
+
+    /// Returns the location of the function called with `key` in the current module
+    fn resolve(&self, key: &FunctionKey<'ast>) -> Option<Location<'ast>> {
+        self.resolve_in(self.module_id(), key)
+    }
+
+    /// Returns the location of the function called with `key` in the module `module_id`, following imports, or `None`
+    /// if it is an embed
+    fn resolve_in(
+        &self,
+        module_id: &TypedModuleId,
+        key: &FunctionKey<'ast>,
+    ) -> Option<Location<'ast>> {
+        let mut location = Location {
+            module: module_id.clone(),
+            key: key.clone(),
+        };
+
+        loop {
+            location = match &self.modules[&location.module].functions[&location.key] {
+                TypedFunctionSymbol::Here(_) => return Some(location),
+                TypedFunctionSymbol::There(key, module) => Location {
+                    module: module.clone(),
+                    key: key.clone(),
+                },
+                TypedFunctionSymbol::Flat(_) => return None,
+            };
+        }
+    }
+
+    /// Decides whether the function at `location`, called with `key`, is kept as a shared sub-circuit. If so, defines
+    /// it in the resulting module unless it already is, and returns the key it is defined under.
+    fn share(
+        &mut self,
+        key: &FunctionKey<'ast>,
+        location: Location<'ast>,
+    ) -> Option<FunctionKey<'ast>> {
+        if let Some(shared_key) = self.shared_keys.get(&location) {
+            return Some(shared_key.clone());
+        }
+
+        // functions with a maximum number of constraints are kept, so that their constraints can be counted
+        let attributes = &self.function(&location).attributes;
+        let keep = match attributes.inline {
+            Some(Inline::Always) => false,
+            Some(Inline::Never) => true,
+            None => {
+                attributes.max_constraints.is_some()
+                    || self.sizes[&location] >= SHARED_FUNCTION_SIZE
+                        && self.executions[&location] >= 2
+            }
+        };
+
+        // the function is defined under the key it is called with, unless that key is taken, in which case it is
+        // inlined
+        let available = key.id != "main"
+            && !self.oracles.contains_key(key)
+            && self.shared_keys.values().all(|k| k != key);
+
+        if !keep || !available {
+            return None;
+        }
+
+        self.shared_keys.insert(location.clone(), key.clone());
+
+        let function = self.function(&location).clone();
+
+        // inline the calls of the function in its own context, with its own variables, and without reusing the results
+        // of calls memoized in its callers, which are not defined in its body
+        let (current_module, current_key) = self.change_context(location.module, location.key);
+        let stack = std::mem::replace(&mut self.stack, vec![]);
+        let statement_buffer = std::mem::replace(&mut self.statement_buffer, vec![]);
+        let call_cache = std::mem::replace(&mut self.call_cache, HashMap::new());
+
+        let function = self.fold_function(function);
+
+        self.stack = stack;
+        self.statement_buffer = statement_buffer;
+        self.call_cache = call_cache;
+        self.change_context(current_module, current_key);
+
+        self.shared.push((key.clone(), function));
+
+        Some(key.clone())
+    }
+
     // Focus the inliner on another module with id `module_id` and return the current `module_id`
     fn change_context(
         &mut self,
@@ -594,6 +887,7 @@ mod tests {
                     FunctionKey::with_id("main")
                         .signature(Signature::new().outputs(vec![Type::FieldElement])),
                     TypedFunctionSymbol::Here(TypedFunction {
+                        attributes: FunctionAttributes::default(),
                         arguments: vec![],
                         statements: vec![TypedStatement::Return(vec![
                             FieldElementExpression::FunctionCall(
@@ -604,6 +898,7 @@ mod tests {
                             .into(),
                         ])],
                         signature: Signature::new().outputs(vec![Type::FieldElement]),
+                        private_outputs: vec![],
                     }),
                 ),
                 (
@@ -625,11 +920,13 @@ mod tests {
                 FunctionKey::with_id("foo")
                     .signature(Signature::new().outputs(vec![Type::FieldElement])),
                 TypedFunctionSymbol::Here(TypedFunction {
+                    attributes: FunctionAttributes::default(),
                     arguments: vec![],
                     statements: vec![TypedStatement::Return(vec![
                         FieldElementExpression::Number(Bn128Field::from(42)).into(),
                     ])],
                     signature: Signature::new().outputs(vec![Type::FieldElement]),
+                    private_outputs: vec![],
                 }),
             )]
             .into_iter()
@@ -660,11 +957,13 @@ mod tests {
                 )
                 .unwrap(),
             &TypedFunctionSymbol::Here(TypedFunction {
+                attributes: FunctionAttributes::default(),
                 arguments: vec![],
                 statements: vec![TypedStatement::Return(vec![
                     FieldElementExpression::Number(Bn128Field::from(42)).into(),
                 ])],
                 signature: Signature::new().outputs(vec![Type::FieldElement]),
+                private_outputs: vec![],
             })
         );
     }
@@ -695,6 +994,7 @@ mod tests {
                             .outputs(vec![Type::FieldElement]),
                     ),
                     TypedFunctionSymbol::Here(TypedFunction {
+                        attributes: FunctionAttributes::default(),
                         arguments: vec![Parameter::private(Variable::field_element("a"))],
                         statements: vec![TypedStatement::Return(vec![
                             FieldElementExpression::Mult(
@@ -713,6 +1013,7 @@ mod tests {
                         signature: Signature::new()
                             .inputs(vec![Type::FieldElement])
                             .outputs(vec![Type::FieldElement]),
+                        private_outputs: vec![],
                     }),
                 ),
                 (
@@ -743,6 +1044,7 @@ mod tests {
                         .outputs(vec![Type::FieldElement]),
                 ),
                 TypedFunctionSymbol::Here(TypedFunction {
+                    attributes: FunctionAttributes::default(),
                     arguments: vec![Parameter::private(Variable::field_element("a"))],
                     statements: vec![TypedStatement::Return(vec![FieldElementExpression::Mult(
                         box FieldElementExpression::Identifier("a".into()),
@@ -752,6 +1054,7 @@ mod tests {
                     signature: Signature::new()
                         .inputs(vec![Type::FieldElement])
                         .outputs(vec![Type::FieldElement]),
+                    private_outputs: vec![],
                 }),
             )]
             .into_iter()
@@ -796,6 +1099,7 @@ mod tests {
                 )
                 .unwrap(),
             &TypedFunctionSymbol::Here(TypedFunction {
+                attributes: FunctionAttributes::default(),
                 arguments: vec![Parameter::private(Variable::field_element("a"))],
                 statements: vec![
                     TypedStatement::Definition(
@@ -820,6 +1124,7 @@ mod tests {
                 signature: Signature::new()
                     .inputs(vec![Type::FieldElement])
                     .outputs(vec![Type::FieldElement]),
+                private_outputs: vec![],
             })
         );
     }
@@ -849,6 +1154,7 @@ mod tests {
                 (
                     FunctionKey::with_id("main").signature(signature.clone()),
                     TypedFunctionSymbol::Here(TypedFunction {
+                        attributes: FunctionAttributes::default(),
                         arguments: vec![Parameter {
                             id: Variable::field_element("a"),
                             private: true,
@@ -874,6 +1180,7 @@ mod tests {
                             .into()]),
                         ],
                         signature: signature.clone(),
+                        private_outputs: vec![],
                     }),
                 ),
                 (
@@ -892,6 +1199,7 @@ mod tests {
             functions: vec![(
                 FunctionKey::with_id("foo").signature(signature.clone()),
                 TypedFunctionSymbol::Here(TypedFunction {
+                    attributes: FunctionAttributes::default(),
                     arguments: vec![Parameter {
                         id: Variable::field_element("a"),
                         private: true,
@@ -900,6 +1208,7 @@ mod tests {
                         FieldElementExpression::Identifier("a".into()).into(),
                     ])],
                     signature: signature.clone(),
+                    private_outputs: vec![],
                 }),
             )]
             .into_iter()
@@ -927,6 +1236,7 @@ mod tests {
                 .get(&FunctionKey::with_id("main").signature(signature.clone()))
                 .unwrap(),
             &TypedFunctionSymbol::Here(TypedFunction {
+                attributes: FunctionAttributes::default(),
                 arguments: vec![Parameter {
                     id: Variable::field_element("a"),
                     private: true,
@@ -967,6 +1277,7 @@ mod tests {
                     ])
                 ],
                 signature: signature.clone(),
+                private_outputs: vec![],
             })
         );
     }
@@ -1003,6 +1314,7 @@ mod tests {
                             .inputs(vec![Type::FieldElement]),
                     ),
                     TypedFunctionSymbol::Here(TypedFunction {
+                        attributes: FunctionAttributes::default(),
                         arguments: vec![Parameter {
                             id: Variable::field_element("a"),
                             private: true,
@@ -1028,11 +1340,13 @@ mod tests {
                             .into()]),
                         ],
                         signature: signature.clone(),
+                        private_outputs: vec![],
                     }),
                 ),
                 (
                     FunctionKey::with_id("bar").signature(signature.clone()),
                     TypedFunctionSymbol::Here(TypedFunction {
+                        attributes: FunctionAttributes::default(),
                         arguments: vec![Parameter {
                             id: Variable::field_element("a"),
                             private: true,
@@ -1045,6 +1359,7 @@ mod tests {
                             .into(),
                         ])],
                         signature: signature.clone(),
+                        private_outputs: vec![],
                     }),
                 ),
                 (
@@ -1063,6 +1378,7 @@ mod tests {
             functions: vec![(
                 FunctionKey::with_id("foo").signature(signature.clone()),
                 TypedFunctionSymbol::Here(TypedFunction {
+                    attributes: FunctionAttributes::default(),
                     arguments: vec![Parameter {
                         id: Variable::field_element("a"),
                         private: true,
@@ -1071,6 +1387,7 @@ mod tests {
                         FieldElementExpression::Identifier("a".into()).into(),
                     ])],
                     signature: signature.clone(),
+                    private_outputs: vec![],
                 }),
             )]
             .into_iter()
@@ -1098,6 +1415,7 @@ mod tests {
                 .get(&FunctionKey::with_id("main").signature(signature.clone()))
                 .unwrap(),
             &TypedFunctionSymbol::Here(TypedFunction {
+                attributes: FunctionAttributes::default(),
                 arguments: vec![Parameter {
                     id: Variable::field_element("a"),
                     private: true,
@@ -1177,6 +1495,7 @@ mod tests {
                     ])
                 ],
                 signature: signature.clone(),
+                private_outputs: vec![],
             })
         );
     }
@@ -1203,6 +1522,7 @@ mod tests {
                     FunctionKey::with_id("main")
                         .signature(Signature::new().outputs(vec![Type::FieldElement])),
                     TypedFunctionSymbol::Here(TypedFunction {
+                        attributes: FunctionAttributes::default(),
                         arguments: vec![],
                         statements: vec![
                             TypedStatement::MultipleDefinition(
@@ -1221,6 +1541,7 @@ mod tests {
                             .into()]),
                         ],
                         signature: Signature::new().outputs(vec![Type::FieldElement]),
+                        private_outputs: vec![],
                     }),
                 ),
                 (
@@ -1242,11 +1563,13 @@ mod tests {
                 FunctionKey::with_id("foo")
                     .signature(Signature::new().outputs(vec![Type::FieldElement])),
                 TypedFunctionSymbol::Here(TypedFunction {
+                    attributes: FunctionAttributes::default(),
                     arguments: vec![],
                     statements: vec![TypedStatement::Return(vec![
                         FieldElementExpression::Number(Bn128Field::from(42)).into(),
                     ])],
                     signature: Signature::new().outputs(vec![Type::FieldElement]),
+                    private_outputs: vec![],
                 }),
             )]
             .into_iter()
@@ -1277,6 +1600,7 @@ mod tests {
                 )
                 .unwrap(),
             &TypedFunctionSymbol::Here(TypedFunction {
+                attributes: FunctionAttributes::default(),
                 arguments: vec![],
                 statements: vec![
                     TypedStatement::Definition(
@@ -1288,6 +1612,7 @@ mod tests {
                     ])
                 ],
                 signature: Signature::new().outputs(vec![Type::FieldElement]),
+                private_outputs: vec![],
             })
         );
     }
@@ -1312,6 +1637,7 @@ mod tests {
                     FunctionKey::with_id("main")
                         .signature(Signature::new().outputs(vec![Type::FieldElement])),
                     TypedFunctionSymbol::Here(TypedFunction {
+                        attributes: FunctionAttributes::default(),
                         arguments: vec![],
                         statements: vec![
                             TypedStatement::MultipleDefinition(
@@ -1330,17 +1656,20 @@ mod tests {
                             .into()]),
                         ],
                         signature: Signature::new().outputs(vec![Type::FieldElement]),
+                        private_outputs: vec![],
                     }),
                 ),
                 (
                     FunctionKey::with_id("foo")
                         .signature(Signature::new().outputs(vec![Type::FieldElement])),
                     TypedFunctionSymbol::Here(TypedFunction {
+                        attributes: FunctionAttributes::default(),
                         arguments: vec![],
                         statements: vec![TypedStatement::Return(vec![
                             FieldElementExpression::Number(Bn128Field::from(42)).into(),
                         ])],
                         signature: Signature::new().outputs(vec![Type::FieldElement]),
+                        private_outputs: vec![],
                     }),
                 ),
             ]
@@ -1370,6 +1699,7 @@ mod tests {
                 )
                 .unwrap(),
             &TypedFunctionSymbol::Here(TypedFunction {
+                attributes: FunctionAttributes::default(),
                 arguments: vec![],
                 statements: vec![
                     TypedStatement::Definition(
@@ -1381,6 +1711,7 @@ mod tests {
                     ])
                 ],
                 signature: Signature::new().outputs(vec![Type::FieldElement]),
+                private_outputs: vec![],
             })
         );
     }
@@ -1410,6 +1741,7 @@ mod tests {
                             .outputs(vec![Type::FieldElement]),
                     ),
                     TypedFunctionSymbol::Here(TypedFunction {
+                        attributes: FunctionAttributes::default(),
                         arguments: vec![Parameter::private(Variable::field_element("a"))],
                         statements: vec![TypedStatement::Return(vec![
                             FieldElementExpression::FunctionCall(
@@ -1433,6 +1765,7 @@ mod tests {
                         signature: Signature::new()
                             .inputs(vec![Type::FieldElement])
                             .outputs(vec![Type::FieldElement]),
+                        private_outputs: vec![],
                     }),
                 ),
                 (
@@ -1463,6 +1796,7 @@ mod tests {
                         .outputs(vec![Type::FieldElement]),
                 ),
                 TypedFunctionSymbol::Here(TypedFunction {
+                    attributes: FunctionAttributes::default(),
                     arguments: vec![Parameter::private(Variable::field_element("a"))],
                     statements: vec![TypedStatement::Return(vec![
                         FieldElementExpression::Identifier("a".into()).into(),
@@ -1470,6 +1804,7 @@ mod tests {
                     signature: Signature::new()
                         .inputs(vec![Type::FieldElement])
                         .outputs(vec![Type::FieldElement]),
+                    private_outputs: vec![],
                 }),
             )]
             .into_iter()
@@ -1522,6 +1857,7 @@ mod tests {
                 )
                 .unwrap(),
             &TypedFunctionSymbol::Here(TypedFunction {
+                attributes: FunctionAttributes::default(),
                 arguments: vec![Parameter::private(Variable::field_element("a"))],
                 statements: vec![
                     TypedStatement::Definition(
@@ -1547,6 +1883,148 @@ mod tests {
                 signature: Signature::new()
                     .inputs(vec![Type::FieldElement])
                     .outputs(vec![Type::FieldElement]),
+                private_outputs: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn keep_function_inlined_never() {
+        // // main
+        // from "foo" import foo
+        // def main(field a) -> field:
+        //    return a * foo(a)
+        //
+        // // foo
+        // #[inline(never)]
+        // def foo(field a) -> field:
+        //    return a * a
+        //
+        //
+        // // inlined
+        // def foo(field a) -> field:
+        //    return a * a
+        //
+        // def main(field a) -> field:
+        //    field foo_1 = foo(a)
+        //    return a * foo_1
+
+        let foo_key = FunctionKey::with_id("foo").signature(
+            Signature::new()
+                .inputs(vec![Type::FieldElement])
+                .outputs(vec![Type::FieldElement]),
+        );
+
+        let main_key = FunctionKey::with_id("main").signature(
+            Signature::new()
+                .inputs(vec![Type::FieldElement])
+                .outputs(vec![Type::FieldElement]),
+        );
+
+        let main = TypedModule {
+            functions: vec![
+                (
+                    main_key.clone(),
+                    TypedFunctionSymbol::Here(TypedFunction {
+                        attributes: FunctionAttributes::default(),
+                        arguments: vec![Parameter::private(Variable::field_element("a"))],
+                        statements: vec![TypedStatement::Return(vec![
+                            FieldElementExpression::Mult(
+                                box FieldElementExpression::Identifier("a".into()),
+                                box FieldElementExpression::FunctionCall(
+                                    foo_key.clone(),
+                                    vec![FieldElementExpression::Identifier("a".into()).into()],
+                                ),
+                            )
+                            .into(),
+                        ])],
+                        signature: main_key.signature.clone(),
+                        private_outputs: vec![],
+                    }),
+                ),
+                (
+                    foo_key.clone(),
+                    TypedFunctionSymbol::There(foo_key.clone(), "foo".into()),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        let foo_function = TypedFunction {
+            attributes: FunctionAttributes {
+                inline: Some(Inline::Never),
+                ..FunctionAttributes::default()
+            },
+            arguments: vec![Parameter::private(Variable::field_element("a"))],
+            statements: vec![TypedStatement::Return(vec![FieldElementExpression::Mult(
+                box FieldElementExpression::Identifier("a".into()),
+                box FieldElementExpression::Identifier("a".into()),
+            )
+            .into()])],
+            signature: foo_key.signature.clone(),
+            private_outputs: vec![],
+        };
+
+        let foo = TypedModule {
+            functions: vec![(
+                foo_key.clone(),
+                TypedFunctionSymbol::Here(foo_function.clone()),
+            )]
+            .into_iter()
+            .collect(),
+        };
+
+        let modules: HashMap<_, _> = vec![("main".into(), main), ("foo".into(), foo)]
+            .into_iter()
+            .collect();
+
+        let program: TypedProgram<Bn128Field> = TypedProgram {
+            main: "main".into(),
+            modules,
+        };
+
+        let program = Inliner::inline(program);
+
+        let functions = &program
+            .modules
+            .get(&PathBuf::from("main"))
+            .unwrap()
+            .functions;
+
+        assert_eq!(
+            functions.get(&foo_key).unwrap(),
+            &TypedFunctionSymbol::Here(foo_function)
+        );
+
+        let call = Identifier {
+            id: CoreIdentifier::Call(foo_key.clone()),
+            version: 1,
+            stack: vec![],
+        };
+
+        assert_eq!(
+            functions.get(&main_key).unwrap(),
+            &TypedFunctionSymbol::Here(TypedFunction {
+                attributes: FunctionAttributes::default(),
+                arguments: vec![Parameter::private(Variable::field_element("a"))],
+                statements: vec![
+                    TypedStatement::MultipleDefinition(
+                        vec![Variable::field_element(call.clone())],
+                        TypedExpressionList::FunctionCall(
+                            foo_key.clone(),
+                            vec![FieldElementExpression::Identifier("a".into()).into()],
+                            vec![Type::FieldElement],
+                        ),
+                    ),
+                    TypedStatement::Return(vec![FieldElementExpression::Mult(
+                        box FieldElementExpression::Identifier("a".into()),
+                        box FieldElementExpression::Identifier(call),
+                    )
+                    .into()])
+                ],
+                signature: main_key.signature.clone(),
+                private_outputs: vec![],
             })
         );
     }
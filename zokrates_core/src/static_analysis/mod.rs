@@ -4,47 +4,102 @@
 //! @author Thibaut Schaeffer <thibaut@schaeff.fr>
 //! @date 2018
 
+mod dead_code;
 mod flat_propagation;
 mod flatten_complex_types;
 mod inline;
 mod propagate_unroll;
 mod propagation;
+mod prune;
+mod range_analysis;
 mod redefinition;
 mod return_binder;
-mod uint_optimizer;
+mod simplify;
+mod strength_reduction;
+mod uint_cse;
+mod uint_ranges;
 mod unconstrained_vars;
 mod unroll;
 mod variable_access_remover;
+mod zir_propagation;
 
+use self::dead_code::DeadCodeEliminator;
 use self::flatten_complex_types::Flattener;
 use self::inline::Inliner;
 use self::propagate_unroll::PropagatedUnroller;
 use self::propagation::Propagator;
+use self::range_analysis::RangeAnalysis;
 use self::redefinition::RedefinitionOptimizer;
 use self::return_binder::ReturnBinder;
-use self::uint_optimizer::UintOptimizer;
+use self::simplify::Simplifier;
+use self::strength_reduction::StrengthReducer;
+use self::uint_cse::UintCse;
 use self::unconstrained_vars::UnconstrainedVariableDetector;
 use self::variable_access_remover::VariableAccessRemover;
+use self::zir_propagation::ZirPropagator;
 use crate::flat_absy::FlatProg;
 use crate::ir::Prog;
+use crate::limits::{self, Budget};
+use crate::max_constraints::{self, MaxConstraints};
 use crate::typed_absy::TypedProgram;
-use zir::ZirProgram;
+use std::fmt;
+use zir::ranged::ZirProgram;
 use zokrates_field::Field;
 
+pub use self::prune::{Pruner, Unused};
+pub use self::range_analysis::Error as UintError;
+pub use self::range_analysis::Range;
+pub use self::uint_ranges::{uint_ranges, UintRange};
+
+#[derive(Debug)]
+pub enum Error {
+    ResourceLimit(limits::Error),
+    Uint(UintError),
+}
+
+impl From<limits::Error> for Error {
+    fn from(e: limits::Error) -> Self {
+        Error::ResourceLimit(e)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::ResourceLimit(e) => write!(f, "{}", e),
+            Error::Uint(e) => write!(f, "{}", e),
+        }
+    }
+}
+
 pub trait Analyse {
     fn analyse(self) -> Self;
 }
 
 impl<'ast, T: Field> TypedProgram<'ast, T> {
-    pub fn analyse(self) -> ZirProgram<'ast, T> {
+    /// Returns the program in zir, along with the maximum number of constraints of its functions
+    pub fn analyse(
+        self,
+        budget: &Budget,
+    ) -> Result<(ZirProgram<'ast, T>, Vec<MaxConstraints>), Error> {
         // propagated unrolling
-        let r = PropagatedUnroller::unroll(self).unwrap_or_else(|e| panic!(e));
+        let r = PropagatedUnroller::unroll(self, budget).map_err(|e| match e {
+            propagate_unroll::Error::ResourceLimit(e) => e,
+            propagate_unroll::Error::NonConstantBound => {
+                panic!("Loop unrolling failed. This happened because a loop bound is not constant")
+            }
+        })?;
         // return binding
         let r = ReturnBinder::bind(r);
 
         // inline
         let r = Inliner::inline(r);
 
+        // find the functions with a maximum number of constraints, now that they are kept as sub-circuits
+        let max_constraints = max_constraints::collect(&r);
+
+        budget.check_time()?;
+
         // propagate
         let r = Propagator::propagate(r);
 
@@ -57,10 +112,27 @@ impl<'ast, T: Field> TypedProgram<'ast, T> {
         // convert to zir, removing complex types
         let zir = Flattener::flatten(r);
 
-        // optimize uint expressions
-        let zir = UintOptimizer::optimize(zir);
+        // propagate the constants exposed by removing complex types
+        let zir = ZirPropagator::propagate(zir);
+
+        // replace the operations with a neutral element, and the double negations, by their operand
+        let zir = Simplifier::simplify(zir);
+
+        // replace the products by constants with shifts and sums
+        let zir = StrengthReducer::reduce(zir);
+
+        // define once the uint expressions which occur several times
+        let zir = UintCse::share(zir);
+
+        // remove the definitions whose variables are never read
+        let zir = DeadCodeEliminator::eliminate(zir);
+
+        // analyse the ranges of uint expressions, deciding where to reduce them
+        let zir = RangeAnalysis::analyse(zir).map_err(Error::Uint)?;
+
+        budget.check_time()?;
 
-        zir
+        Ok((zir, max_constraints))
     }
 }
 
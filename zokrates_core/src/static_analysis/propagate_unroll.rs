@@ -19,6 +19,7 @@
 //! In the case that a loop bound cannot be reduced to a constant, we detect it by noticing that the unroll does
 //! not make progress anymore.
 
+use limits::{self, Budget};
 use static_analysis::propagation::Propagator;
 use static_analysis::unroll::{Output, Unroller};
 use typed_absy::TypedProgram;
@@ -26,14 +27,27 @@ use zokrates_field::Field;
 
 pub struct PropagatedUnroller;
 
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    NonConstantBound,
+    ResourceLimit(limits::Error),
+}
+
+impl From<limits::Error> for Error {
+    fn from(e: limits::Error) -> Self {
+        Error::ResourceLimit(e)
+    }
+}
+
 impl PropagatedUnroller {
     pub fn unroll<'ast, T: Field>(
         p: TypedProgram<'ast, T>,
-    ) -> Result<TypedProgram<'ast, T>, &'static str> {
+        budget: &Budget,
+    ) -> Result<TypedProgram<'ast, T>, Error> {
         let mut blocked_at = None;
 
         // unroll a first time, retrieving whether the unroll is complete
-        let mut unrolled = Unroller::unroll(p);
+        let mut unrolled = Unroller::unroll(p, budget)?;
 
         loop {
             // conditions to exit the loop
@@ -41,7 +55,7 @@ impl PropagatedUnroller {
                 Output::Complete(p) => return Ok(p),
                 Output::Incomplete(next, index) => {
                     if Some(index) == blocked_at {
-                        return Err("Loop unrolling failed. This happened because a loop bound is not constant");
+                        return Err(Error::NonConstantBound);
                     } else {
                         // update the index where we blocked
                         blocked_at = Some(index);
@@ -49,8 +63,10 @@ impl PropagatedUnroller {
                         // propagate
                         let propagated = Propagator::propagate_verbose(next);
 
+                        budget.check_time()?;
+
                         // unroll
-                        Unroller::unroll(propagated)
+                        Unroller::unroll(propagated, budget)?
                     }
                 }
             };
@@ -71,6 +87,7 @@ mod tests {
             Variable::field_element("i"),
             FieldElementExpression::Identifier("i".into()),
             FieldElementExpression::Number(Bn128Field::from(2)),
+            None,
             vec![],
         )];
 
@@ -83,9 +100,11 @@ mod tests {
                     functions: vec![(
                         FunctionKey::with_id("main"),
                         TypedFunctionSymbol::Here(TypedFunction {
+                            attributes: FunctionAttributes::default(),
                             arguments: vec![],
                             signature: Signature::new(),
                             statements,
+                            private_outputs: vec![],
                         }),
                     )]
                     .into_iter()
@@ -97,7 +116,10 @@ mod tests {
             main: "main".into(),
         };
 
-        assert!(PropagatedUnroller::unroll(p).is_err());
+        assert_eq!(
+            PropagatedUnroller::unroll(p, &Budget::unlimited()),
+            Err(Error::NonConstantBound)
+        );
     }
 
     #[test]
@@ -109,21 +131,23 @@ mod tests {
         // should be unrolled to
         // i_0 = 0
         // j_0 = 0
-        // foo_0 = i_0 + j_0
+        // foo_0 = 0
         // j_1 = 1
-        // foo_1 = i_0 + j_1
+        // foo_1 = 1
         // i_1 = 1
         // j_2 = 1
-        // foo_2 = i_1 + j_1
+        // foo_2 = 2
 
         let s = TypedStatement::For(
             Variable::field_element("i"),
             FieldElementExpression::Number(Bn128Field::from(0)),
             FieldElementExpression::Number(Bn128Field::from(2)),
+            None,
             vec![TypedStatement::For(
                 Variable::field_element("j"),
                 FieldElementExpression::Identifier("i".into()),
                 FieldElementExpression::Number(Bn128Field::from(2)),
+                None,
                 vec![
                     TypedStatement::Declaration(Variable::field_element("foo")),
                     TypedStatement::Definition(
@@ -155,11 +179,7 @@ mod tests {
                 TypedAssignee::Identifier(Variable::field_element(
                     Identifier::from("foo").version(0),
                 )),
-                FieldElementExpression::Add(
-                    box FieldElementExpression::Identifier(Identifier::from("i").version(0)),
-                    box FieldElementExpression::Identifier(Identifier::from("j").version(0)),
-                )
-                .into(),
+                FieldElementExpression::Number(Bn128Field::from(0)).into(),
             ),
             TypedStatement::Definition(
                 TypedAssignee::Identifier(Variable::field_element(
@@ -171,11 +191,7 @@ mod tests {
                 TypedAssignee::Identifier(Variable::field_element(
                     Identifier::from("foo").version(1),
                 )),
-                FieldElementExpression::Add(
-                    box FieldElementExpression::Identifier(Identifier::from("i").version(0)),
-                    box FieldElementExpression::Identifier(Identifier::from("j").version(1)),
-                )
-                .into(),
+                FieldElementExpression::Number(Bn128Field::from(1)).into(),
             ),
             TypedStatement::Definition(
                 TypedAssignee::Identifier(Variable::field_element(
@@ -193,11 +209,7 @@ mod tests {
                 TypedAssignee::Identifier(Variable::field_element(
                     Identifier::from("foo").version(2),
                 )),
-                FieldElementExpression::Add(
-                    box FieldElementExpression::Identifier(Identifier::from("i").version(1)),
-                    box FieldElementExpression::Identifier(Identifier::from("j").version(2)),
-                )
-                .into(),
+                FieldElementExpression::Number(Bn128Field::from(2)).into(),
             ),
         ];
 
@@ -208,9 +220,11 @@ mod tests {
                     functions: vec![(
                         FunctionKey::with_id("main"),
                         TypedFunctionSymbol::Here(TypedFunction {
+                            attributes: FunctionAttributes::default(),
                             arguments: vec![],
                             signature: Signature::new(),
                             statements: vec![s],
+                            private_outputs: vec![],
                         }),
                     )]
                     .into_iter()
@@ -222,8 +236,9 @@ mod tests {
             main: "main".into(),
         };
 
-        let statements = match PropagatedUnroller::unroll(p).unwrap().modules
-            [std::path::Path::new("main")]
+        let statements = match PropagatedUnroller::unroll(p, &Budget::unlimited())
+            .unwrap()
+            .modules[std::path::Path::new("main")]
         .functions[&FunctionKey::with_id("main")]
             .clone()
         {
@@ -70,6 +70,11 @@ fn is_constant<'ast, T: Field>(e: &TypedExpression<'ast, T>) -> bool {
     }
 }
 
+/// Returns the mask of the values of `bitwidth` bits, to which arithmetic on constants wraps around
+fn mask(bitwidth: UBitwidth) -> u128 {
+    u128::max_value() >> (128 - bitwidth.to_usize())
+}
+
 impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
     fn fold_function(&mut self, f: TypedFunction<'ast, T>) -> TypedFunction<'ast, T> {
         self.constants = HashMap::new();
@@ -120,14 +125,15 @@ impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
             // only loops with variable bounds are expected here
             // we stop propagation here as constants maybe be modified inside the loop body
             // which we do not visit
-            TypedStatement::For(v, from, to, statements) => {
+            TypedStatement::For(v, from, to, max, statements) => {
                 let from = self.fold_field_expression(from);
                 let to = self.fold_field_expression(to);
+                let max = max.map(|max| self.fold_field_expression(max));
 
                 // invalidate the constants map as any constant could be modified inside the loop body, which we don't visit
                 self.constants.clear();
 
-                Some(TypedStatement::For(v, from, to, statements))
+                Some(TypedStatement::For(v, from, to, max, statements))
             }
             TypedStatement::MultipleDefinition(variables, expression_list) => {
                 let expression_list = self.fold_expression_list(expression_list);
@@ -220,39 +226,81 @@ impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
                             }
                         }
 
+                        fn process_divmod<'ast, T: Field>(
+                            arguments: Vec<TypedExpression<'ast, T>>,
+                        ) -> Option<Vec<TypedExpression<'ast, T>>> {
+                            assert_eq!(arguments.len(), 2);
+
+                            let dividend = UExpression::try_from(arguments[0].clone()).unwrap();
+                            let divisor = UExpression::try_from(arguments[1].clone()).unwrap();
+                            let bitwidth = dividend.bitwidth;
+
+                            match (dividend.into_inner(), divisor.into_inner()) {
+                                // division by zero is left to the compiled program, which cannot satisfy it
+                                (UExpressionInner::Value(_), UExpressionInner::Value(0)) => None,
+                                (UExpressionInner::Value(n), UExpressionInner::Value(d)) => {
+                                    Some(vec![
+                                        UExpressionInner::Value(n / d).annotate(bitwidth).into(),
+                                        UExpressionInner::Value(n % d).annotate(bitwidth).into(),
+                                    ])
+                                }
+                                _ => unreachable!("should be uint values"),
+                            }
+                        }
+
                         match arguments.iter().all(|a| is_constant(a)) {
                             true => {
-                                let r: Option<TypedExpression<'ast, T>> = match key.id {
-                                    "_U32_FROM_BITS" => Some(process_u_from_bits(
+                                let r: Option<Vec<TypedExpression<'ast, T>>> = match key.id {
+                                    "_U128_FROM_BITS" => Some(vec![process_u_from_bits(
+                                        variables.clone(),
+                                        arguments.clone(),
+                                        UBitwidth::B128,
+                                    )]),
+                                    "_U64_FROM_BITS" => Some(vec![process_u_from_bits(
+                                        variables.clone(),
+                                        arguments.clone(),
+                                        UBitwidth::B64,
+                                    )]),
+                                    "_U32_FROM_BITS" => Some(vec![process_u_from_bits(
                                         variables.clone(),
                                         arguments.clone(),
                                         UBitwidth::B32,
-                                    )),
-                                    "_U16_FROM_BITS" => Some(process_u_from_bits(
+                                    )]),
+                                    "_U16_FROM_BITS" => Some(vec![process_u_from_bits(
                                         variables.clone(),
                                         arguments.clone(),
                                         UBitwidth::B16,
-                                    )),
-                                    "_U8_FROM_BITS" => Some(process_u_from_bits(
+                                    )]),
+                                    "_U8_FROM_BITS" => Some(vec![process_u_from_bits(
                                         variables.clone(),
                                         arguments.clone(),
                                         UBitwidth::B8,
-                                    )),
-                                    "_U32_TO_BITS" => Some(process_u_to_bits(
+                                    )]),
+                                    "_U128_TO_BITS" => Some(vec![process_u_to_bits(
+                                        variables.clone(),
+                                        arguments.clone(),
+                                        UBitwidth::B128,
+                                    )]),
+                                    "_U64_TO_BITS" => Some(vec![process_u_to_bits(
+                                        variables.clone(),
+                                        arguments.clone(),
+                                        UBitwidth::B64,
+                                    )]),
+                                    "_U32_TO_BITS" => Some(vec![process_u_to_bits(
                                         variables.clone(),
                                         arguments.clone(),
                                         UBitwidth::B32,
-                                    )),
-                                    "_U16_TO_BITS" => Some(process_u_to_bits(
+                                    )]),
+                                    "_U16_TO_BITS" => Some(vec![process_u_to_bits(
                                         variables.clone(),
                                         arguments.clone(),
                                         UBitwidth::B16,
-                                    )),
-                                    "_U8_TO_BITS" => Some(process_u_to_bits(
+                                    )]),
+                                    "_U8_TO_BITS" => Some(vec![process_u_to_bits(
                                         variables.clone(),
                                         arguments.clone(),
                                         UBitwidth::B8,
-                                    )),
+                                    )]),
                                     "_UNPACK" => {
                                         assert_eq!(variables.len(), 1);
                                         assert_eq!(arguments.len(), 1);
@@ -274,31 +322,29 @@ impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
                                                 }
                                                 assert_eq!(num, T::zero());
 
-                                                Some(
-                                                    ArrayExpressionInner::Value(
-                                                        res.into_iter()
-                                                            .map(|v| {
-                                                                BooleanExpression::Value(v).into()
-                                                            })
-                                                            .collect(),
-                                                    )
-                                                    .annotate(Type::Boolean, T::get_required_bits())
-                                                    .into(),
+                                                Some(vec![ArrayExpressionInner::Value(
+                                                    res.into_iter()
+                                                        .map(|v| BooleanExpression::Value(v).into())
+                                                        .collect(),
                                                 )
+                                                .annotate(Type::Boolean, T::get_required_bits())
+                                                .into()])
                                             }
                                             _ => unreachable!("should be a field value"),
                                         }
                                     }
+                                    "_DIVMOD" => process_divmod(arguments.clone()),
                                     "_SHA256_ROUND" => None,
                                     _ => None,
                                 };
 
                                 match r {
-                                    Some(expr) => {
-                                        self.constants.insert(
-                                            TypedAssignee::Identifier(variables[0].clone()),
-                                            expr.clone(),
-                                        );
+                                    Some(exprs) => {
+                                        assert_eq!(exprs.len(), variables.len());
+                                        for (v, e) in variables.iter().zip(exprs) {
+                                            self.constants
+                                                .insert(TypedAssignee::Identifier(v.clone()), e);
+                                        }
                                         match self.verbose {
                                             true => Some(TypedStatement::MultipleDefinition(
                                                 variables,
@@ -361,10 +407,7 @@ impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
                 self.fold_uint_expression(e2).into_inner(),
             ) {
                 (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
-                    use std::convert::TryInto;
-                    UExpressionInner::Value(
-                        (v1 + v2) % 2_u128.pow(bitwidth.to_usize().try_into().unwrap()),
-                    )
+                    UExpressionInner::Value(v1.wrapping_add(v2) & mask(bitwidth))
                 }
                 (e, UExpressionInner::Value(v)) | (UExpressionInner::Value(v), e) => match v {
                     0 => e,
@@ -382,18 +425,10 @@ impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
                 self.fold_uint_expression(e2).into_inner(),
             ) {
                 (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
-                    use std::convert::TryInto;
-                    UExpressionInner::Value(
-                        (v1.wrapping_sub(v2)) % 2_u128.pow(bitwidth.to_usize().try_into().unwrap()),
-                    )
+                    UExpressionInner::Value(v1.wrapping_sub(v2) & mask(bitwidth))
                 }
-                (e, UExpressionInner::Value(v)) | (UExpressionInner::Value(v), e) => match v {
-                    0 => e,
-                    _ => UExpressionInner::Sub(
-                        box e.annotate(bitwidth),
-                        box UExpressionInner::Value(v).annotate(bitwidth),
-                    ),
-                },
+                // subtraction is not commutative: only a zero on the right can be dropped
+                (e, UExpressionInner::Value(0)) => e,
                 (e1, e2) => {
                     UExpressionInner::Sub(box e1.annotate(bitwidth), box e2.annotate(bitwidth))
                 }
@@ -403,10 +438,7 @@ impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
                 self.fold_uint_expression(e2).into_inner(),
             ) {
                 (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
-                    use std::convert::TryInto;
-                    UExpressionInner::Value(
-                        (v1 * v2) % 2_u128.pow(bitwidth.to_usize().try_into().unwrap()),
-                    )
+                    UExpressionInner::Value(v1.wrapping_mul(v2) & mask(bitwidth))
                 }
                 (e, UExpressionInner::Value(v)) | (UExpressionInner::Value(v), e) => match v {
                     0 => UExpressionInner::Value(0),
@@ -420,13 +452,38 @@ impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
                     UExpressionInner::Mult(box e1.annotate(bitwidth), box e2.annotate(bitwidth))
                 }
             },
+            // division by zero is left to the compiled program, which cannot satisfy it
+            UExpressionInner::Div(box e1, box e2) => match (
+                self.fold_uint_expression(e1).into_inner(),
+                self.fold_uint_expression(e2).into_inner(),
+            ) {
+                (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) if v2 != 0 => {
+                    UExpressionInner::Value(v1 / v2)
+                }
+                (e, UExpressionInner::Value(1)) => e,
+                (e1, e2) => {
+                    UExpressionInner::Div(box e1.annotate(bitwidth), box e2.annotate(bitwidth))
+                }
+            },
+            UExpressionInner::Rem(box e1, box e2) => match (
+                self.fold_uint_expression(e1).into_inner(),
+                self.fold_uint_expression(e2).into_inner(),
+            ) {
+                (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) if v2 != 0 => {
+                    UExpressionInner::Value(v1 % v2)
+                }
+                (_, UExpressionInner::Value(1)) => UExpressionInner::Value(0),
+                (e1, e2) => {
+                    UExpressionInner::Rem(box e1.annotate(bitwidth), box e2.annotate(bitwidth))
+                }
+            },
             UExpressionInner::RightShift(box e, box by) => {
                 let e = self.fold_uint_expression(e);
                 let by = self.fold_field_expression(by);
                 match (e.into_inner(), by) {
                     (UExpressionInner::Value(v), FieldElementExpression::Number(by)) => {
                         let by_as_usize = by.to_dec_string().parse::<usize>().unwrap();
-                        UExpressionInner::Value(v >> by_as_usize)
+                        UExpressionInner::Value(v.checked_shr(by_as_usize as u32).unwrap_or(0))
                     }
                     (e, FieldElementExpression::Number(by)) => UExpressionInner::RightShift(
                         box e.annotate(bitwidth),
@@ -444,7 +501,9 @@ impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
                 match (e.into_inner(), by) {
                     (UExpressionInner::Value(v), FieldElementExpression::Number(by)) => {
                         let by_as_usize = by.to_dec_string().parse::<usize>().unwrap();
-                        UExpressionInner::Value((v << by_as_usize) & 0xffffffff)
+                        UExpressionInner::Value(
+                            v.checked_shl(by_as_usize as u32).unwrap_or(0) & mask(bitwidth),
+                        )
                     }
                     (e, FieldElementExpression::Number(by)) => UExpressionInner::LeftShift(
                         box e.annotate(bitwidth),
@@ -499,7 +558,7 @@ impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
             UExpressionInner::Not(box e) => {
                 let e = self.fold_uint_expression(e).into_inner();
                 match e {
-                    UExpressionInner::Value(v) => UExpressionInner::Value((!v) & 0xffffffff),
+                    UExpressionInner::Value(v) => UExpressionInner::Value(!v & mask(bitwidth)),
                     e => UExpressionInner::Not(box e.annotate(bitwidth)),
                 }
             }
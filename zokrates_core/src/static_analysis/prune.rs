@@ -0,0 +1,305 @@
+//! Module containing the pruning of the functions which are not reachable from `main`
+//!
+//! Unrolling processes every function of every module, so removing the functions which are never called beforehand
+//! saves compilation time, in particular when a large module is imported for a single helper.
+//!
+//! The functions and imports of the main module which are never used are returned, to be reported to the user.
+//! Those of other modules are not: modules are libraries, which commonly define more than a given program uses.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use typed_absy::folder::*;
+use typed_absy::types::FunctionKey;
+use typed_absy::*;
+use zokrates_field::Field;
+
+/// A symbol of the main module which is never used, by name
+#[derive(Debug, Clone, PartialEq)]
+pub enum Unused {
+    Function(String),
+    Import(String),
+}
+
+impl fmt::Display for Unused {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Unused::Function(ref id) => write!(f, "Function `{}` is never called", id),
+            Unused::Import(ref id) => write!(f, "Imported symbol `{}` is never used", id),
+        }
+    }
+}
+
+pub struct Pruner;
+
+impl Pruner {
+    pub fn prune<'ast, T: Field>(p: TypedProgram<'ast, T>) -> (TypedProgram<'ast, T>, Vec<Unused>) {
+        let main_key = p.modules[&p.main]
+            .functions
+            .keys()
+            .find(|k| k.id == "main")
+            .unwrap()
+            .clone();
+
+        let mut reachable = HashSet::new();
+        let mut stack = vec![(p.main.clone(), main_key)];
+
+        while let Some((module, key)) = stack.pop() {
+            if !reachable.insert((module.clone(), key.clone())) {
+                continue;
+            }
+
+            match &p.modules[&module].functions[&key] {
+                TypedFunctionSymbol::Here(f) => {
+                    stack.extend(calls(f).into_iter().map(|key| (module.clone(), key)))
+                }
+                TypedFunctionSymbol::There(key, module) => {
+                    stack.push((module.clone(), key.clone()))
+                }
+                TypedFunctionSymbol::Flat(_) => {}
+            }
+        }
+
+        // symbols are reported by name, as overloads of a used function or import are not worth a warning
+        let mut used = HashSet::new();
+        let mut unused = HashMap::new();
+
+        for (key, symbol) in &p.modules[&p.main].functions {
+            if reachable.contains(&(p.main.clone(), key.clone())) {
+                used.insert(key.id);
+            } else {
+                unused.entry(key.id).or_insert(match symbol {
+                    TypedFunctionSymbol::Here(_) => Unused::Function(key.id.to_string()),
+                    _ => Unused::Import(key.id.to_string()),
+                });
+            }
+        }
+
+        let mut unused: Vec<_> = unused
+            .into_iter()
+            .filter(|(id, _)| !used.contains(id))
+            .map(|(_, unused)| unused)
+            .collect();
+        unused.sort_by_key(|u| u.to_string());
+
+        let main = p.main;
+        let modules = p
+            .modules
+            .into_iter()
+            .filter_map(|(module_id, module)| {
+                let functions: TypedFunctionSymbols<'ast, T> = module
+                    .functions
+                    .into_iter()
+                    .filter(|(key, _)| reachable.contains(&(module_id.clone(), key.clone())))
+                    .collect();
+
+                match functions.len() {
+                    0 => None,
+                    _ => Some((module_id, TypedModule { functions })),
+                }
+            })
+            .collect();
+
+        (TypedProgram { main, modules }, unused)
+    }
+}
+
+/// Returns the keys of the functions called in a function, which are in the module of the function, once per call
+pub fn calls<'ast, T: Field>(f: &TypedFunction<'ast, T>) -> Vec<FunctionKey<'ast>> {
+    let mut collector = CallCollector { calls: vec![] };
+    collector.fold_function(f.clone());
+    collector.calls
+}
+
+/// Collects the keys of the functions called in a function, which are in the module of the function
+struct CallCollector<'ast> {
+    calls: Vec<FunctionKey<'ast>>,
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for CallCollector<'ast> {
+    fn fold_expression_list(
+        &mut self,
+        es: TypedExpressionList<'ast, T>,
+    ) -> TypedExpressionList<'ast, T> {
+        match es {
+            TypedExpressionList::FunctionCall(key, arguments, types) => {
+                self.calls.push(key.clone());
+                TypedExpressionList::FunctionCall(
+                    key,
+                    arguments
+                        .into_iter()
+                        .map(|a| self.fold_expression(a))
+                        .collect(),
+                    types,
+                )
+            }
+        }
+    }
+
+    fn fold_field_expression(
+        &mut self,
+        e: FieldElementExpression<'ast, T>,
+    ) -> FieldElementExpression<'ast, T> {
+        if let FieldElementExpression::FunctionCall(ref key, _) = e {
+            self.calls.push(key.clone());
+        }
+        fold_field_expression(self, e)
+    }
+
+    fn fold_boolean_expression(
+        &mut self,
+        e: BooleanExpression<'ast, T>,
+    ) -> BooleanExpression<'ast, T> {
+        if let BooleanExpression::FunctionCall(ref key, _) = e {
+            self.calls.push(key.clone());
+        }
+        fold_boolean_expression(self, e)
+    }
+
+    fn fold_uint_expression_inner(
+        &mut self,
+        bitwidth: UBitwidth,
+        e: UExpressionInner<'ast, T>,
+    ) -> UExpressionInner<'ast, T> {
+        if let UExpressionInner::FunctionCall(ref key, _) = e {
+            self.calls.push(key.clone());
+        }
+        fold_uint_expression_inner(self, bitwidth, e)
+    }
+
+    fn fold_array_expression_inner(
+        &mut self,
+        ty: &Type,
+        size: usize,
+        e: ArrayExpressionInner<'ast, T>,
+    ) -> ArrayExpressionInner<'ast, T> {
+        if let ArrayExpressionInner::FunctionCall(ref key, _) = e {
+            self.calls.push(key.clone());
+        }
+        fold_array_expression_inner(self, ty, size, e)
+    }
+
+    fn fold_struct_expression_inner(
+        &mut self,
+        ty: &StructType,
+        e: StructExpressionInner<'ast, T>,
+    ) -> StructExpressionInner<'ast, T> {
+        if let StructExpressionInner::FunctionCall(ref key, _) = e {
+            self.calls.push(key.clone());
+        }
+        fold_struct_expression_inner(self, ty, e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use typed_absy::types::Signature;
+    use zokrates_field::Bn128Field;
+
+    fn function<'ast>(
+        statements: Vec<TypedStatement<'ast, Bn128Field>>,
+    ) -> TypedFunctionSymbol<'ast, Bn128Field> {
+        TypedFunctionSymbol::Here(TypedFunction {
+            attributes: FunctionAttributes::default(),
+            arguments: vec![],
+            statements,
+            signature: Signature::new().outputs(vec![Type::FieldElement]),
+            private_outputs: vec![],
+        })
+    }
+
+    fn call(id: &str) -> TypedStatement<Bn128Field> {
+        TypedStatement::Return(vec![
+            FieldElementExpression::FunctionCall(key(id), vec![]).into()
+        ])
+    }
+
+    fn key(id: &str) -> FunctionKey {
+        FunctionKey::with_id(id).signature(Signature::new().outputs(vec![Type::FieldElement]))
+    }
+
+    #[test]
+    fn prune() {
+        // // main
+        // from "foo" import used
+        // from "foo" import unusedImport
+        // def helper() -> field:
+        //     return used()
+        // def unusedFunction() -> field:
+        //     return unusedImport()
+        // def main() -> field:
+        //     return helper()
+        //
+        // // foo
+        // def used() -> field:
+        //     return 1
+        // def unusedImport() -> field:
+        //     return 2
+
+        let main: TypedModule<Bn128Field> = TypedModule {
+            functions: vec![
+                (
+                    key("used"),
+                    TypedFunctionSymbol::There(key("used"), "foo".into()),
+                ),
+                (
+                    key("unusedImport"),
+                    TypedFunctionSymbol::There(key("unusedImport"), "foo".into()),
+                ),
+                (key("helper"), function(vec![call("used")])),
+                (key("unusedFunction"), function(vec![call("unusedImport")])),
+                (key("main"), function(vec![call("helper")])),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        let constant = |value: u32| {
+            function(vec![TypedStatement::Return(vec![
+                FieldElementExpression::Number(Bn128Field::from(value)).into(),
+            ])])
+        };
+
+        let foo: TypedModule<Bn128Field> = TypedModule {
+            functions: vec![
+                (key("used"), constant(1)),
+                (key("unusedImport"), constant(2)),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        let p = TypedProgram {
+            main: "main".into(),
+            modules: vec![("main".into(), main), ("foo".into(), foo)]
+                .into_iter()
+                .collect(),
+        };
+
+        let (p, unused) = Pruner::prune(p);
+
+        assert_eq!(
+            unused,
+            vec![
+                Unused::Function("unusedFunction".into()),
+                Unused::Import("unusedImport".into()),
+            ]
+        );
+
+        let mut main_keys: Vec<_> = p.modules[&PathBuf::from("main")]
+            .functions
+            .keys()
+            .map(|k| k.id)
+            .collect();
+        main_keys.sort();
+        assert_eq!(main_keys, vec!["helper", "main", "used"]);
+
+        let foo_keys: Vec<_> = p.modules[&PathBuf::from("foo")]
+            .functions
+            .keys()
+            .map(|k| k.id)
+            .collect();
+        assert_eq!(foo_keys, vec!["used"]);
+    }
+}
@@ -0,0 +1,1273 @@
+//! Module containing the range analysis of uint expressions in the zir AST
+//!
+//! Uint expressions are represented by field elements which may exceed the bitwidth of their type: the sum of two
+//! `u32` is only reduced to 32 bits, by decomposing it, if an operation requires it or if it does not fit in the field
+//! otherwise. This analysis computes the range of each uint expression and decides where to reduce them, returning the
+//! program in the `zir::ranged` AST, in which every uint expression carries its `UMetadata`.
+//!
+//! The functions kept as shared sub-circuits by the inliner are analysed before their callers. They return their uints
+//! without reducing them, and their callers register the ranges of the returned values, so that a call is only reduced
+//! where the inlined function would have been. Their arguments are reduced, as they decompose their parameters.
+
+use crate::zir::ranged;
+use crate::zir::types::FunctionKey;
+use crate::zir::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use zokrates_field::Field;
+
+/// An unsigned integer computation which does not fit in the field. Bit decompositions are only unique below
+/// `2^(T::get_required_bits() - 1)`, which small fields such as Goldilocks do not reach for the sum of two `u64`.
+/// Products which do not fit, such as that of two `u128` in BN128, are computed on limbs of half the bitwidth.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Error {
+    /// A multiplication whose product does not fit, even computed on limbs, or a division whose quotient multiplied by
+    /// the divisor does not fit
+    Product {
+        expression: String,
+        bitwidth: usize,
+        field: &'static str,
+    },
+    /// A type whose values cannot be added without overflowing
+    Type {
+        bitwidth: usize,
+        field: &'static str,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Product {
+                expression,
+                bitwidth,
+                field,
+            } => write!(
+                f,
+                "Cannot compute `{}` in the {} field, as the product of two u{} does not fit in its elements",
+                expression, field, bitwidth
+            ),
+            Error::Type { bitwidth, field } => write!(
+                f,
+                "Type u{} is not available in the {} field, as the sum of two u{} does not fit in its elements",
+                bitwidth, field, bitwidth
+            ),
+        }
+    }
+}
+
+/// The values a uint expression can take as a field element, from `0` to `max` included
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Range<T> {
+    pub max: T,
+}
+
+impl<T: Field> Range<T> {
+    pub fn new<U: Into<T>>(max: U) -> Self {
+        Range { max: max.into() }
+    }
+
+    /// Returns the range of a value reduced to `bitwidth` bits
+    pub fn reduced(bitwidth: usize) -> Self {
+        Range {
+            max: T::from(2).pow(bitwidth) - T::from(1),
+        }
+    }
+
+    /// Returns the number of bits on which the values of this range are represented
+    pub fn bits(&self) -> usize {
+        self.max.bits() as usize
+    }
+
+    pub fn add(&self, other: &Self) -> Option<Self> {
+        self.max.checked_add(&other.max).map(|max| Range { max })
+    }
+
+    pub fn mul(&self, other: &Self) -> Option<Self> {
+        self.max.checked_mul(&other.max).map(|max| Range { max })
+    }
+
+    /// Returns the offset added when subtracting a value of this range from a `bitwidth` bits uint: `a - b` is computed
+    /// as `a - b + offset` so that it does not underflow, and the offset is a multiple of `2**bitwidth` so that it
+    /// vanishes when the result is reduced
+    pub fn sub_offset(&self, bitwidth: usize) -> T {
+        T::from(2).pow(std::cmp::max(self.bits(), bitwidth))
+    }
+
+    /// Returns the range of `self - other` on `bitwidth` bits, see `sub_offset`
+    pub fn sub(&self, other: &Self, bitwidth: usize) -> Option<Self> {
+        // the offset itself must be below `2**(T::get_required_bits() - 1)`
+        if std::cmp::max(other.bits(), bitwidth) >= T::get_required_bits() - 1 {
+            return None;
+        }
+
+        self.max
+            .checked_add(&other.sub_offset(bitwidth))
+            .map(|max| Range { max })
+    }
+
+    /// Returns the range of a value of this range reduced to `bitwidth` bits
+    pub fn truncate(&self, bitwidth: usize) -> Self {
+        match self.bits() > bitwidth {
+            true => Self::reduced(bitwidth),
+            false => self.clone(),
+        }
+    }
+
+    /// Returns the range of the quotient of a value of this range by the constant `divisor`, which is not zero
+    pub fn div(&self, divisor: u128) -> Self {
+        Range {
+            max: T::from(self.max.to_biguint() / divisor),
+        }
+    }
+
+    /// Returns the range of the remainder of a value of this range by a value of `divisor`, which is below the
+    /// divisor and at most the dividend
+    pub fn rem(&self, divisor: &Self) -> Self {
+        match divisor.max == T::from(0) {
+            // the division by zero cannot be satisfied
+            true => divisor.clone(),
+            false => Range {
+                max: std::cmp::min(self.max.clone(), divisor.max.clone() - T::from(1)),
+            },
+        }
+    }
+
+    /// Returns the smallest range containing both ranges
+    pub fn union(&self, other: &Self) -> Self {
+        Range {
+            max: std::cmp::max(&self.max, &other.max).clone(),
+        }
+    }
+
+    /// Returns the range of a value of this range shifted left by `by`, truncated to `bitwidth` bits
+    pub fn left_shift(&self, by: usize, bitwidth: usize) -> Self {
+        Self::reduced(std::cmp::min(self.bits() + by, bitwidth))
+    }
+
+    /// Returns the range of a value of this range shifted right by `by`
+    pub fn right_shift(&self, by: usize) -> Self {
+        Self::reduced(self.bits() - std::cmp::min(by, self.bits()))
+    }
+}
+
+/// Returns the range of the product of two values reduced to `bitwidth` bits, which is computed on limbs when it does
+/// not fit otherwise, or `None` if it does not fit either way
+fn reduced_product<T: Field>(bitwidth: usize) -> Option<Range<T>> {
+    let reduced = Range::reduced(bitwidth);
+
+    match multiplies_on_limbs::<T>(bitwidth) {
+        false => reduced.mul(&reduced),
+        true => {
+            // `a_low * b_low + (a_high * b_low + a_low * b_high) * 2**half`
+            let limb = Range::<T>::reduced(bitwidth / 2);
+            let square = limb.mul(&limb)?;
+
+            square
+                .mul(&Range::new(T::from(2).pow(bitwidth / 2 + 1)))
+                .and_then(|cross| cross.add(&square))
+        }
+    }
+}
+
+/// Returns whether to reduce each operand of a binary operation on `bitwidth` bits, and the range of its result if it
+/// fits in the field: operands are only reduced if the result does not fit otherwise, the left one first
+fn reduce_operands<T: Field, F: Fn(&Range<T>, &Range<T>) -> Option<Range<T>>>(
+    left: &Range<T>,
+    right: &Range<T>,
+    bitwidth: usize,
+    op: F,
+) -> (bool, bool, Option<Range<T>>) {
+    let reduced = Range::reduced(bitwidth);
+
+    vec![(false, false), (true, false), (false, true)]
+        .into_iter()
+        .filter_map(|(reduce_left, reduce_right)| {
+            let left = if reduce_left { &reduced } else { left };
+            let right = if reduce_right { &reduced } else { right };
+
+            op(left, right).map(|range| (reduce_left, reduce_right, Some(range)))
+        })
+        .next()
+        .unwrap_or_else(|| (true, true, op(&reduced, &reduced)))
+}
+
+/// Sets whether `e` should be reduced, keeping a reduction which was already required
+fn reduce<'ast, T: Field>(
+    e: ranged::UExpression<'ast, T>,
+    should_reduce: bool,
+) -> ranged::UExpression<'ast, T> {
+    let metadata = e.metadata().clone();
+
+    e.with_metadata(UMetadata {
+        should_reduce: metadata.should_reduce || should_reduce,
+        ..metadata
+    })
+}
+
+fn force_reduce<'ast, T: Field>(e: ranged::UExpression<'ast, T>) -> ranged::UExpression<'ast, T> {
+    reduce(e, true)
+}
+
+fn force_no_reduce<'ast, T: Field>(
+    e: ranged::UExpression<'ast, T>,
+) -> ranged::UExpression<'ast, T> {
+    reduce(e, false)
+}
+
+#[derive(Default)]
+pub struct RangeAnalysis<'ast, T: Field> {
+    ids: HashMap<ZirAssignee<'ast>, UMetadata<T>>,
+    /// the functions defined in the program, which the inliner kept as sub-circuits shared by their calls
+    shared: HashSet<FunctionKey<'ast>>,
+    /// the ranges of the uints returned by the shared functions analysed so far
+    returns: HashMap<FunctionKey<'ast>, Vec<Option<Range<T>>>>,
+    /// whether the function being analysed returns its uints without reducing them, see `returned_ranges`
+    defers_returns: bool,
+    error: Option<Error>,
+}
+
+impl<'ast, T: Field> RangeAnalysis<'ast, T> {
+    pub fn new() -> Self {
+        RangeAnalysis {
+            ids: HashMap::new(),
+            shared: HashSet::new(),
+            returns: HashMap::new(),
+            defers_returns: false,
+            error: None,
+        }
+    }
+
+    pub fn analyse(p: ZirProgram<'ast, T>) -> Result<ranged::ZirProgram<'ast, T>, Error> {
+        let mut analysis = RangeAnalysis::new();
+        let p = analysis.fold_program(p);
+
+        match analysis.error {
+            Some(e) => Err(e),
+            None => Ok(p),
+        }
+    }
+
+    /// Analyses the function under `key` among the `pending` ones after the shared functions it calls, moving it to
+    /// `analysed`
+    fn analyse_function(
+        &mut self,
+        key: FunctionKey<'ast>,
+        pending: &mut ZirFunctionSymbols<'ast, T>,
+        analysed: &mut ranged::ZirFunctionSymbols<'ast, T>,
+    ) {
+        let symbol = match pending.remove(&key) {
+            Some(symbol) => symbol,
+            None => return,
+        };
+
+        let symbol = match symbol {
+            ZirFunctionSymbol::Here(f) => {
+                for callee in callees(&f) {
+                    self.analyse_function(callee, pending, analysed);
+                }
+
+                // the outputs of the program are reduced
+                self.defers_returns = key.id != "main";
+                let f = self.fold_function(f);
+                self.returns.insert(key.clone(), returned_ranges(&f));
+
+                ZirFunctionSymbol::Here(f)
+            }
+            ZirFunctionSymbol::There(key, module_id) => ZirFunctionSymbol::There(key, module_id),
+            ZirFunctionSymbol::Flat(embed) => ZirFunctionSymbol::Flat(embed),
+        };
+
+        analysed.insert(key, symbol);
+    }
+
+    fn register(&mut self, a: ZirAssignee<'ast>, m: UMetadata<T>) {
+        self.ids.insert(a, m);
+    }
+
+    /// Records an error if the sum of two reduced values of `bitwidth` bits does not fit, returning whether it does
+    fn check_bitwidth(&mut self, bitwidth: usize) -> bool {
+        let fits = bitwidth < T::get_required_bits() - 1;
+
+        if !fits {
+            self.error.get_or_insert_with(|| Error::Type {
+                bitwidth,
+                field: T::name(),
+            });
+        }
+
+        fits
+    }
+
+    /// Analyses the operands of a binary operation on `bitwidth` bits, reducing them as required for the result of
+    /// `op` to fit, see `reduce_operands`
+    fn fold_operands<F: Fn(&Range<T>, &Range<T>) -> Option<Range<T>>>(
+        &mut self,
+        left: UExpression<'ast, T>,
+        right: UExpression<'ast, T>,
+        bitwidth: usize,
+        op: F,
+    ) -> (
+        ranged::UExpression<'ast, T>,
+        ranged::UExpression<'ast, T>,
+        Option<Range<T>>,
+    ) {
+        let left = self.fold_uint_expression(left);
+        let right = self.fold_uint_expression(right);
+
+        let (reduce_left, reduce_right, range) =
+            reduce_operands(left.range(), right.range(), bitwidth, op);
+
+        (
+            reduce(left, reduce_left),
+            reduce(right, reduce_right),
+            range,
+        )
+    }
+
+    /// Analyses and reduces the operands of a division on `bitwidth` bits, recording an error if the product of the
+    /// quotient and the divisor added to the remainder does not fit
+    fn fold_division_operands(
+        &mut self,
+        left: UExpression<'ast, T>,
+        right: UExpression<'ast, T>,
+        bitwidth: usize,
+        operator: &str,
+    ) -> (ranged::UExpression<'ast, T>, ranged::UExpression<'ast, T>) {
+        let left = force_reduce(self.fold_uint_expression(left));
+        let right = force_reduce(self.fold_uint_expression(right));
+
+        let reduced = Range::<T>::reduced(bitwidth);
+        let fits = reduced
+            .mul(&reduced)
+            .and_then(|product| product.add(&reduced))
+            .is_some();
+
+        if !fits {
+            self.error.get_or_insert_with(|| Error::Product {
+                expression: format!("({} {} {})", left, operator, right),
+                bitwidth,
+                field: T::name(),
+            });
+        }
+
+        (left, right)
+    }
+
+    fn fold_program(&mut self, p: ZirProgram<'ast, T>) -> ranged::ZirProgram<'ast, T> {
+        ZirProgram {
+            modules: p
+                .modules
+                .into_iter()
+                .map(|(module_id, module)| (module_id, self.fold_module(module)))
+                .collect(),
+            main: p.main,
+        }
+    }
+
+    fn fold_module(&mut self, m: ZirModule<'ast, T>) -> ranged::ZirModule<'ast, T> {
+        self.shared = m
+            .functions
+            .iter()
+            .filter_map(|(key, symbol)| match symbol {
+                ZirFunctionSymbol::Here(_) => Some(key.clone()),
+                _ => None,
+            })
+            .collect();
+
+        let mut pending = m.functions;
+        let mut analysed = HashMap::new();
+
+        let keys: Vec<_> = pending.keys().cloned().collect();
+        for key in keys {
+            self.analyse_function(key, &mut pending, &mut analysed);
+        }
+
+        ZirModule {
+            functions: analysed,
+        }
+    }
+
+    fn fold_function(&mut self, f: ZirFunction<'ast, T>) -> ranged::ZirFunction<'ast, T> {
+        self.ids = HashMap::new();
+
+        ZirFunction {
+            arguments: f
+                .arguments
+                .into_iter()
+                .map(|a| self.fold_parameter(a))
+                .collect(),
+            statements: f
+                .statements
+                .into_iter()
+                .map(|s| self.fold_statement(s))
+                .collect(),
+            signature: f.signature,
+        }
+    }
+
+    fn fold_parameter(&mut self, p: Parameter<'ast>) -> Parameter<'ast> {
+        if let Type::Uint(bitwidth) = p.id.get_type() {
+            // parameters are decomposed even if they are not used
+            self.check_bitwidth(bitwidth.to_usize());
+            self.register(p.id.clone(), UMetadata::parameter(bitwidth));
+        }
+
+        p
+    }
+
+    fn fold_statement(&mut self, s: ZirStatement<'ast, T>) -> ranged::ZirStatement<'ast, T> {
+        match s {
+            ZirStatement::Definition(a, e) => {
+                let e = match e {
+                    ZirExpression::Uint(i) => {
+                        let i = force_no_reduce(self.fold_uint_expression(i));
+                        self.register(a.clone(), i.metadata().clone());
+                        ZirExpression::Uint(i)
+                    }
+                    e => self.fold_expression(e),
+                };
+                ZirStatement::Definition(a, e)
+            }
+            // we need to put back in range to return, unless the callers know the range of the returned values
+            ZirStatement::Return(expressions) => ZirStatement::Return(
+                expressions
+                    .into_iter()
+                    .map(|e| match e {
+                        ZirExpression::Uint(e) => {
+                            let e = self.fold_uint_expression(e);
+
+                            let e = reduce(e, !self.defers_returns);
+
+                            ZirExpression::Uint(e)
+                        }
+                        e => self.fold_expression(e),
+                    })
+                    .collect(),
+            ),
+            ZirStatement::MultipleDefinition(
+                lhs,
+                ZirExpressionList::FunctionCall(key, arguments, ty),
+            ) => {
+                // shared functions decompose their uint parameters, which must therefore be in range, as does the
+                // division embed, see flattening, while other embeds reduce their uint arguments themselves when
+                // decomposing them
+                let reduced_arguments = self.shared.contains(&key) || key.id == "_DIVMOD";
+
+                // the embeds building uints from bits or bytes, the ones slicing a u32 into bytes and the division
+                // embed return reduced values
+                let reduced_returns = match key.id {
+                    "_U8_FROM_BITS" | "_U16_FROM_BITS" | "_U32_FROM_BITS" | "_U64_FROM_BITS"
+                    | "_U128_FROM_BITS" | "_U32_TO_BYTES_BE" | "_U32_TO_BYTES_LE"
+                    | "_U32_FROM_BYTES_BE" | "_U32_FROM_BYTES_LE" | "_DIVMOD" => true,
+                    _ => false,
+                };
+
+                let arguments = arguments
+                    .into_iter()
+                    .map(|e| match e {
+                        ZirExpression::Uint(e) => ZirExpression::Uint(reduce(
+                            self.fold_uint_expression(e),
+                            reduced_arguments,
+                        )),
+                        e => self.fold_expression(e),
+                    })
+                    .collect();
+
+                // shared functions are analysed before their callers, see `analyse_function`
+                match self.returns.get(&key).cloned() {
+                    Some(ranges) => {
+                        for (v, range) in lhs.iter().zip(ranges) {
+                            if let Some(range) = range {
+                                self.register(v.clone(), UMetadata::with_range(range));
+                            }
+                        }
+                    }
+                    None if reduced_returns => {
+                        for v in &lhs {
+                            if let Type::Uint(bitwidth) = v.get_type() {
+                                self.register(v.clone(), UMetadata::parameter(bitwidth));
+                            }
+                        }
+                    }
+                    None => {}
+                }
+
+                ZirStatement::MultipleDefinition(
+                    lhs,
+                    ZirExpressionList::FunctionCall(key, arguments, ty),
+                )
+            }
+            // we can only compare two unsigned integers if they are in range, see `fold_boolean_expression`
+            ZirStatement::Assertion(e) => ZirStatement::Assertion(self.fold_boolean_expression(e)),
+            ZirStatement::Declaration(v) => ZirStatement::Declaration(v),
+        }
+    }
+
+    fn fold_expression(&mut self, e: ZirExpression<'ast, T>) -> ranged::ZirExpression<'ast, T> {
+        match e {
+            ZirExpression::FieldElement(e) => self.fold_field_expression(e).into(),
+            ZirExpression::Boolean(e) => self.fold_boolean_expression(e).into(),
+            ZirExpression::Uint(e) => self.fold_uint_expression(e).into(),
+        }
+    }
+
+    fn fold_field_expression(
+        &mut self,
+        e: FieldElementExpression<'ast, T>,
+    ) -> ranged::FieldElementExpression<'ast, T> {
+        use self::FieldElementExpression::*;
+
+        match e {
+            Number(n) => Number(n),
+            Identifier(id) => Identifier(id),
+            Add(box left, box right) => Add(
+                box self.fold_field_expression(left),
+                box self.fold_field_expression(right),
+            ),
+            Sub(box left, box right) => Sub(
+                box self.fold_field_expression(left),
+                box self.fold_field_expression(right),
+            ),
+            Mult(box left, box right) => Mult(
+                box self.fold_field_expression(left),
+                box self.fold_field_expression(right),
+            ),
+            Div(box left, box right) => Div(
+                box self.fold_field_expression(left),
+                box self.fold_field_expression(right),
+            ),
+            Pow(box left, box right) => Pow(
+                box self.fold_field_expression(left),
+                box self.fold_field_expression(right),
+            ),
+            IfElse(box condition, box consequence, box alternative) => IfElse(
+                box self.fold_boolean_expression(condition),
+                box self.fold_field_expression(consequence),
+                box self.fold_field_expression(alternative),
+            ),
+        }
+    }
+
+    fn fold_boolean_expression(
+        &mut self,
+        e: BooleanExpression<'ast, T>,
+    ) -> ranged::BooleanExpression<'ast, T> {
+        use self::BooleanExpression::*;
+
+        match e {
+            Identifier(id) => Identifier(id),
+            Value(v) => Value(v),
+            UintEq(box left, box right) => {
+                let left = self.fold_uint_expression(left);
+                let right = self.fold_uint_expression(right);
+
+                // we can only compare two unsigned integers if they are in range
+                let left = force_reduce(left);
+                let right = force_reduce(right);
+
+                UintEq(box left, box right)
+            }
+            FieldEq(box left, box right) => FieldEq(
+                box self.fold_field_expression(left),
+                box self.fold_field_expression(right),
+            ),
+            Lt(box left, box right) => Lt(
+                box self.fold_field_expression(left),
+                box self.fold_field_expression(right),
+            ),
+            Le(box left, box right) => Le(
+                box self.fold_field_expression(left),
+                box self.fold_field_expression(right),
+            ),
+            Ge(box left, box right) => Ge(
+                box self.fold_field_expression(left),
+                box self.fold_field_expression(right),
+            ),
+            Gt(box left, box right) => Gt(
+                box self.fold_field_expression(left),
+                box self.fold_field_expression(right),
+            ),
+            BoolEq(box left, box right) => BoolEq(
+                box self.fold_boolean_expression(left),
+                box self.fold_boolean_expression(right),
+            ),
+            Or(box left, box right) => Or(
+                box self.fold_boolean_expression(left),
+                box self.fold_boolean_expression(right),
+            ),
+            And(box left, box right) => And(
+                box self.fold_boolean_expression(left),
+                box self.fold_boolean_expression(right),
+            ),
+            Not(box e) => Not(box self.fold_boolean_expression(e)),
+            IfElse(box condition, box consequence, box alternative) => IfElse(
+                box self.fold_boolean_expression(condition),
+                box self.fold_boolean_expression(consequence),
+                box self.fold_boolean_expression(alternative),
+            ),
+        }
+    }
+
+    fn fold_uint_expression(&mut self, e: UExpression<'ast, T>) -> ranged::UExpression<'ast, T> {
+        let bitwidth = e.bitwidth.to_usize();
+
+        // the sum of two reduced values must fit, products are checked below
+        if !self.check_bitwidth(bitwidth) {
+            // the program is rejected, so the expression does not matter
+            return UExpressionInner::Value(0).with_max(bitwidth, T::from(0));
+        }
+
+        use self::UExpressionInner::*;
+
+        match e.inner {
+            Value(v) => Value(v).with_max(bitwidth, v),
+            Identifier(id) => Identifier(id.clone()).with_metadata(
+                bitwidth,
+                self.ids
+                    .get(&Variable::uint(id.clone(), bitwidth))
+                    .cloned()
+                    .expect(&format!("identifier should have been defined: {}", id)),
+            ),
+            Add(box left, box right) => {
+                let (left, right, range) = self.fold_operands(left, right, bitwidth, Range::add);
+
+                Add(box left, box right).with_range(
+                    bitwidth,
+                    range.expect("the sum of two reduced values should fit, see `check_bitwidth`"),
+                )
+            }
+            Sub(box left, box right) => {
+                // `left - right` is computed as `left - right + offset`, where the offset is the smallest power of two
+                // which is at least `2**bitwidth` and above `right`, see `Range::sub_offset`
+                // if both operands are reduced, the result is below `2**(bitwidth + 1)`, which fits
+                let (left, right, range) =
+                    self.fold_operands(left, right, bitwidth, |left, right| {
+                        left.sub(right, bitwidth)
+                    });
+
+                Sub(box left, box right).with_range(
+                    bitwidth,
+                    range.expect("the difference of two reduced values should fit"),
+                )
+            }
+            Mult(box left, box right) => {
+                let (left, right, range) = self.fold_operands(left, right, bitwidth, Range::mul);
+
+                // when even the product of two reduced values does not fit, it is computed on limbs, see flattening
+                let range = range
+                    .or_else(|| reduced_product(bitwidth))
+                    .unwrap_or_else(|| {
+                        self.error.get_or_insert_with(|| Error::Product {
+                            expression: format!("({} * {})", left, right),
+                            bitwidth,
+                            field: T::name(),
+                        });
+                        // the program is rejected, so the metadata does not matter
+                        Range::new(T::from(0))
+                    });
+
+                Mult(box left, box right).with_range(bitwidth, range)
+            }
+            // both operands are reduced and the quotient and remainder are decomposed on the bitwidth, see flattening,
+            // which verifies `quotient * divisor + remainder == dividend` without overflow
+            Div(box left, box right) => {
+                let (left, right) = self.fold_division_operands(left, right, bitwidth, "/");
+
+                let dividend = left.range().truncate(bitwidth);
+                let range = match right.as_inner() {
+                    Value(c) if *c > 0 => dividend.div(*c),
+                    _ => dividend,
+                };
+
+                Div(box left, box right).with_range(bitwidth, range)
+            }
+            Rem(box left, box right) => {
+                let (left, right) = self.fold_division_operands(left, right, bitwidth, "%");
+
+                let range = left
+                    .range()
+                    .truncate(bitwidth)
+                    .rem(&right.range().truncate(bitwidth));
+
+                Rem(box left, box right).with_range(bitwidth, range)
+            }
+            Xor(box left, box right) => {
+                let left = self.fold_uint_expression(left);
+                let right = self.fold_uint_expression(right);
+
+                Xor(box force_reduce(left), box force_reduce(right))
+                    .with_range(bitwidth, Range::reduced(bitwidth))
+            }
+            And(box left, box right) => {
+                let left = self.fold_uint_expression(left);
+                let right = self.fold_uint_expression(right);
+
+                And(box force_reduce(left), box force_reduce(right))
+                    .with_range(bitwidth, Range::reduced(bitwidth))
+            }
+            Or(box left, box right) => {
+                let left = self.fold_uint_expression(left);
+                let right = self.fold_uint_expression(right);
+
+                Or(box force_reduce(left), box force_reduce(right))
+                    .with_range(bitwidth, Range::reduced(bitwidth))
+            }
+            Not(box e) => {
+                let e = self.fold_uint_expression(e);
+
+                Not(box force_reduce(e)).with_range(bitwidth, Range::reduced(bitwidth))
+            }
+            LeftShift(box e, box by) => {
+                let e = self.fold_uint_expression(e);
+                let by = self.fold_field_expression(by);
+
+                let range = e.range().left_shift(shift(&by), bitwidth);
+
+                LeftShift(box force_reduce(e), box by).with_range(bitwidth, range)
+            }
+            RightShift(box e, box by) => {
+                let e = self.fold_uint_expression(e);
+                let by = self.fold_field_expression(by);
+
+                let range = e.range().right_shift(shift(&by));
+
+                RightShift(box force_reduce(e), box by).with_range(bitwidth, range)
+            }
+            // the branches are not reduced, so that the masked iterations of bounded loops, such as
+            // `a = if running then a + b else a`, defer their reductions as unmasked iterations do
+            IfElse(box condition, box consequence, box alternative) => {
+                let condition = self.fold_boolean_expression(condition);
+                let consequence = self.fold_uint_expression(consequence);
+                let alternative = self.fold_uint_expression(alternative);
+
+                let range = consequence.range().union(alternative.range());
+
+                IfElse(
+                    box condition,
+                    box force_no_reduce(consequence),
+                    box force_no_reduce(alternative),
+                )
+                .with_range(bitwidth, range)
+            }
+        }
+    }
+}
+
+/// Returns the keys of the functions called by `f`
+fn callees<'ast, T>(f: &ZirFunction<'ast, T>) -> Vec<FunctionKey<'ast>> {
+    f.statements
+        .iter()
+        .filter_map(|s| match s {
+            ZirStatement::MultipleDefinition(_, ZirExpressionList::FunctionCall(key, ..)) => {
+                Some(key.clone())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the ranges of the values returned by an analysed function, `None` for the values which are not uints
+pub fn returned_ranges<'ast, T: Field>(f: &ranged::ZirFunction<'ast, T>) -> Vec<Option<Range<T>>> {
+    f.statements
+        .iter()
+        .filter_map(|s| match s {
+            ZirStatement::Return(expressions) => Some(expressions),
+            _ => None,
+        })
+        .last()
+        .map(|expressions| {
+            expressions
+                .iter()
+                .map(|e| match e {
+                    ZirExpression::Uint(e) => Some(match e.should_reduce() {
+                        true => Range::reduced(e.bitwidth.to_usize()),
+                        false => e.range().clone(),
+                    }),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the constant amount of a shift
+fn shift<'ast, T: Field, M>(by: &FieldElementExpression<'ast, T, M>) -> usize {
+    match by {
+        FieldElementExpression::Number(by) => by.to_dec_string().parse::<usize>().unwrap(),
+        _ => unreachable!("shifts should be by a constant amount"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zokrates_field::{Bn128Field, Pow};
+
+    extern crate pretty_assertions;
+    use self::pretty_assertions::assert_eq;
+
+    macro_rules! uint_test {
+        ( $left_max:expr, $left_reduce:expr, $right_max:expr, $right_reduce:expr, $method:ident, $res_max:expr  ) => {{
+            let mut analysis: RangeAnalysis<Bn128Field> = RangeAnalysis::new();
+
+            let (left, left_analysed) = define(&mut analysis, "left", 32, $left_max);
+            let (right, right_analysed) = define(&mut analysis, "right", 32, $right_max);
+
+            let left_expected = reduce(left_analysed, $left_reduce);
+            let right_expected = reduce(right_analysed, $right_reduce);
+
+            let res = analysis.fold_uint_expression(UExpression::$method(left, right));
+
+            assert_eq!(res.range(), &Range::new($res_max));
+            assert_eq!(operands(res), (left_expected, right_expected));
+        }};
+    }
+
+    /// Returns the operands of an analysed binary operation
+    fn operands<'a, T: Field>(
+        e: ranged::UExpression<'a, T>,
+    ) -> (ranged::UExpression<'a, T>, ranged::UExpression<'a, T>) {
+        use self::UExpressionInner::*;
+
+        match e.into_inner() {
+            Add(box left, box right)
+            | Sub(box left, box right)
+            | Mult(box left, box right)
+            | Div(box left, box right)
+            | Rem(box left, box right)
+            | Xor(box left, box right)
+            | And(box left, box right)
+            | Or(box left, box right) => (left, right),
+            e => panic!("expected a binary operation, found {:?}", e),
+        }
+    }
+
+    /// Defines the identifier `id` of `bitwidth` bits with values up to `max`, returning it before and after analysis
+    fn define<'a, T: Field, U: Into<T>>(
+        analysis: &mut RangeAnalysis<'a, T>,
+        id: &'a str,
+        bitwidth: usize,
+        max: U,
+    ) -> (UExpression<'a, T>, ranged::UExpression<'a, T>) {
+        let metadata = UMetadata::with_range(Range::new(max));
+        analysis.register(Variable::uint(id.into(), bitwidth), metadata.clone());
+
+        (
+            UExpressionInner::Identifier(id.into()).annotate(bitwidth),
+            UExpressionInner::Identifier(id.into()).with_metadata(bitwidth, metadata),
+        )
+    }
+
+    #[test]
+    fn add() {
+        // no reduction
+        uint_test!(42, false, 33, false, add, 75);
+        // left reduction
+        uint_test!(
+            Bn128Field::max_unique_value(),
+            true,
+            1,
+            false,
+            add,
+            0x100000000_u128
+        );
+        // right reduction
+        uint_test!(
+            1,
+            false,
+            Bn128Field::max_unique_value(),
+            true,
+            add,
+            0x100000000_u128
+        );
+        // right and left reductions
+        uint_test!(
+            Bn128Field::max_unique_value(),
+            true,
+            Bn128Field::max_unique_value(),
+            true,
+            add,
+            0x1fffffffe_u128
+        );
+    }
+
+    #[test]
+    fn sub() {
+        // no reduction
+        uint_test!(42, false, 33, false, sub, 0x100000000_u128 + 42);
+        // left reduction
+        uint_test!(
+            Bn128Field::max_unique_value(),
+            true,
+            1,
+            false,
+            sub,
+            0x1ffffffff_u128
+        );
+        // right reduction
+        uint_test!(
+            1,
+            false,
+            Bn128Field::max_unique_value(),
+            true,
+            sub,
+            0x100000001_u128
+        );
+        // right and left reductions
+        uint_test!(
+            Bn128Field::max_unique_value(),
+            true,
+            Bn128Field::max_unique_value(),
+            true,
+            sub,
+            0x1ffffffff_u128
+        );
+    }
+
+    #[test]
+    fn mult() {
+        // no reduction
+        uint_test!(42, false, 33, false, mult, 1386);
+        // left reduction
+        uint_test!(
+            Bn128Field::max_unique_value(),
+            true,
+            2,
+            false,
+            mult,
+            0x1fffffffe_u128
+        );
+        // right reduction
+        uint_test!(
+            2,
+            false,
+            Bn128Field::max_unique_value(),
+            true,
+            mult,
+            0x1fffffffe_u128
+        );
+        // right and left reductions
+        uint_test!(
+            Bn128Field::max_unique_value(),
+            true,
+            Bn128Field::max_unique_value(),
+            true,
+            mult,
+            0xfffffffe00000001_u128
+        );
+    }
+
+    #[test]
+    fn mult_limbs() {
+        // the product of two reduced u128 does not fit in BN128, so it is computed on limbs of 64 bits
+        let mut analysis = RangeAnalysis::<Bn128Field>::new();
+        let (left, _) = define(&mut analysis, "left", 128, Bn128Field::max_unique_value());
+        let (right, _) = define(&mut analysis, "right", 128, Bn128Field::max_unique_value());
+
+        let res = analysis.fold_uint_expression(UExpression::mult(left, right));
+
+        let limb_max = Bn128Field::from(2).pow(64) - Bn128Field::from(1);
+        assert_eq!(
+            res.range().max,
+            limb_max.clone() * limb_max * (Bn128Field::from(2).pow(65) + Bn128Field::from(1))
+        );
+        assert_eq!(res.range().bits(), 193);
+
+        match res.into_inner() {
+            UExpressionInner::Mult(box left, box right) => {
+                assert!(left.should_reduce());
+                assert!(right.should_reduce());
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(analysis.error, None);
+    }
+
+    #[test]
+    fn mult_small_field() {
+        use zokrates_field::GoldilocksField;
+
+        fn mult<'a>(
+            analysis: &mut RangeAnalysis<'a, GoldilocksField>,
+            bitwidth: usize,
+        ) -> ranged::UExpression<'a, GoldilocksField> {
+            let max = GoldilocksField::max_unique_value();
+            let (left, _) = define(analysis, "left", bitwidth, max.clone());
+            let (right, _) = define(analysis, "right", bitwidth, max);
+
+            analysis.fold_uint_expression(UExpression::mult(left, right))
+        }
+
+        // the product of two reduced u16 fits in 63 bits
+        let mut analysis = RangeAnalysis::<GoldilocksField>::new();
+        let res = mult(&mut analysis, 16);
+        assert_eq!(res.range().max, GoldilocksField::from(0xfffe0001_u32));
+        assert_eq!(analysis.error, None);
+
+        // the product of two reduced u32 does not, so it is computed on limbs of 16 bits
+        let mut analysis = RangeAnalysis::<GoldilocksField>::new();
+        let res = mult(&mut analysis, 32);
+        assert_eq!(res.range().max, GoldilocksField::from(0x1fffd00000001_u128));
+        assert_eq!(analysis.error, None);
+
+        // the sum of two u64 does not fit either
+        let mut analysis = RangeAnalysis::<GoldilocksField>::new();
+        let (left, _) = define(&mut analysis, "left", 64, 1);
+        let (right, _) = define(&mut analysis, "right", 64, 1);
+        analysis.fold_uint_expression(UExpression::add(left, right));
+        assert_eq!(
+            analysis.error,
+            Some(Error::Type {
+                bitwidth: 64,
+                field: GoldilocksField::name(),
+            })
+        );
+    }
+
+    #[test]
+    fn bitwise() {
+        // xor
+        uint_test!(42, true, 33, true, xor, 0xffffffff_u32);
+        // or
+        uint_test!(42, true, 33, true, or, 0xffffffff_u32);
+        // and
+        uint_test!(42, true, 33, true, and, 0xffffffff_u32);
+        // not
+        let mut analysis = RangeAnalysis::<Bn128Field>::new();
+        let (e, e_analysed) = define(&mut analysis, "e", 32, 255);
+
+        assert_eq!(
+            analysis.fold_uint_expression(UExpression::not(e)),
+            UExpressionInner::Not(box force_reduce(e_analysed)).with_max(32, 0xffffffff_u32)
+        );
+    }
+
+    #[test]
+    fn right_shift() {
+        let test = |max: u32, res_max: u32| {
+            let mut analysis = RangeAnalysis::<Bn128Field>::new();
+            let (e, e_analysed) = define(&mut analysis, "e", 32, max);
+            let by = FieldElementExpression::Number(Bn128Field::from(2));
+
+            assert_eq!(
+                analysis.fold_uint_expression(UExpression::right_shift(e, by)),
+                UExpressionInner::RightShift(
+                    box force_reduce(e_analysed),
+                    box FieldElementExpression::Number(Bn128Field::from(2))
+                )
+                .with_max(32, res_max)
+            );
+        };
+
+        test(255, 63);
+        test(2, 0);
+    }
+
+    #[test]
+    fn left_shift() {
+        let test = |max: u32, res_max: u32| {
+            let mut analysis = RangeAnalysis::<Bn128Field>::new();
+            let (e, e_analysed) = define(&mut analysis, "e", 32, max);
+            let by = FieldElementExpression::Number(Bn128Field::from(2));
+
+            assert_eq!(
+                analysis.fold_uint_expression(UExpression::left_shift(e, by)),
+                UExpressionInner::LeftShift(
+                    box force_reduce(e_analysed),
+                    box FieldElementExpression::Number(Bn128Field::from(2))
+                )
+                .with_max(32, res_max)
+            );
+        };
+
+        test(255, 1023);
+        test(0xffffffff, 0xffffffff);
+    }
+
+    #[test]
+    fn if_else() {
+        // `left` and `right` are smaller than the target
+        let mut analysis = RangeAnalysis::<Bn128Field>::new();
+        let (consequence, _) = define(&mut analysis, "a", 32, 42);
+        let (alternative, _) = define(&mut analysis, "b", 32, 33);
+
+        assert_eq!(
+            analysis
+                .fold_uint_expression(UExpression::if_else(
+                    BooleanExpression::Value(true),
+                    consequence,
+                    alternative
+                ))
+                .range(),
+            &Range::new(42)
+        );
+    }
+
+    #[test]
+    fn masked() {
+        // the iterations of bounded loops are masked as `a = if running then a + b else a`, which reduces neither `a`
+        // nor `a + b`, as in the unmasked `a = a + b`
+        let mut analysis = RangeAnalysis::<Bn128Field>::new();
+        let (a, _) = define(&mut analysis, "a", 32, 0xffffffff_u128);
+        let (b, _) = define(&mut analysis, "b", 32, 0xffffffff_u128);
+
+        let e = analysis.fold_uint_expression(UExpression::if_else(
+            BooleanExpression::Identifier("running".into()),
+            a.clone().add(b),
+            a,
+        ));
+
+        assert_eq!(e.range(), &Range::new(0x1fffffffe_u128));
+
+        match e.into_inner() {
+            UExpressionInner::IfElse(_, box consequence, box alternative) => {
+                assert!(!alternative.should_reduce());
+                assert!(!consequence.should_reduce());
+                match consequence.into_inner() {
+                    UExpressionInner::Add(box left, box right) => {
+                        assert!(!left.should_reduce());
+                        assert!(!right.should_reduce());
+                    }
+                    e => panic!("expected a sum, found {:?}", e),
+                }
+            }
+            e => panic!("expected a condition, found {:?}", e),
+        }
+    }
+
+    #[test]
+    fn division() {
+        // both operands are reduced, the quotient is at most the dividend and the remainder below the divisor
+        uint_test!(0x1fffffffe_u128, true, 42, true, div, 0xffffffff_u128);
+        uint_test!(0x1fffffffe_u128, true, 42, true, rem, 41);
+        uint_test!(7, true, 42, true, rem, 7);
+
+        // a constant divisor bounds the quotient
+        let mut analysis = RangeAnalysis::<Bn128Field>::new();
+        let (a, _) = define(&mut analysis, "a", 32, 0xffffffff_u128);
+        let ten = || UExpressionInner::Value(10).annotate(32);
+
+        let e = analysis.fold_uint_expression(UExpression::div(a.clone(), ten()));
+        assert_eq!(e.range(), &Range::new(0x19999999_u128));
+        let e = analysis.fold_uint_expression(UExpression::rem(a, ten()));
+        assert_eq!(e.range(), &Range::new(9));
+
+        // the product of the quotient and the divisor of two u128 does not fit
+        let mut analysis = RangeAnalysis::<Bn128Field>::new();
+        let (left, _) = define(&mut analysis, "left", 128, 1);
+        let (right, _) = define(&mut analysis, "right", 128, 1);
+        analysis.fold_uint_expression(UExpression::div(left, right));
+        assert_eq!(
+            analysis.error,
+            Some(Error::Product {
+                expression: String::from("(left / right)"),
+                bitwidth: 128,
+                field: "bn128",
+            })
+        );
+    }
+
+    #[test]
+    fn shared_function() {
+        // def add(u32 a, u32 b) -> u32:
+        //     return a + b
+        //
+        // def main(u32 x, u32 y) -> u32:
+        //     u32 c = add(x, y)
+        //     return c
+
+        use crate::zir::types::Signature;
+
+        let id = |id: &'static str| UExpressionInner::Identifier(id.into()).annotate(32);
+
+        let signature = Signature::new()
+            .inputs(vec![Type::uint(32), Type::uint(32)])
+            .outputs(vec![Type::uint(32)]);
+        let add_key = FunctionKey::with_id("add").signature(signature.clone());
+
+        let add: ZirFunction<Bn128Field> = ZirFunction {
+            arguments: vec![
+                Parameter::private(Variable::uint("a".into(), 32)),
+                Parameter::private(Variable::uint("b".into(), 32)),
+            ],
+            statements: vec![ZirStatement::Return(vec![id("a").add(id("b")).into()])],
+            signature: signature.clone(),
+        };
+
+        let main: ZirFunction<Bn128Field> = ZirFunction {
+            arguments: vec![
+                Parameter::private(Variable::uint("x".into(), 32)),
+                Parameter::private(Variable::uint("y".into(), 32)),
+            ],
+            statements: vec![
+                ZirStatement::MultipleDefinition(
+                    vec![Variable::uint("c".into(), 32)],
+                    ZirExpressionList::FunctionCall(
+                        add_key.clone(),
+                        vec![id("x").into(), id("y").into()],
+                        vec![Type::uint(32)],
+                    ),
+                ),
+                ZirStatement::Return(vec![id("c").into()]),
+            ],
+            signature: signature.clone(),
+        };
+
+        let main_key = FunctionKey::with_id("main").signature(signature);
+
+        let p = ZirProgram {
+            main: "main".into(),
+            modules: vec![(
+                "main".into(),
+                ZirModule {
+                    functions: vec![
+                        (main_key.clone(), ZirFunctionSymbol::Here(main)),
+                        (add_key.clone(), ZirFunctionSymbol::Here(add)),
+                    ]
+                    .into_iter()
+                    .collect(),
+                },
+            )]
+            .into_iter()
+            .collect(),
+        };
+
+        let p = RangeAnalysis::analyse(p).unwrap();
+        let functions = &p.modules[&p.main].functions;
+
+        let returned = |key: &FunctionKey| match &functions[key] {
+            ZirFunctionSymbol::Here(f) => match f.statements.last() {
+                Some(ZirStatement::Return(expressions)) => match &expressions[0] {
+                    ZirExpression::Uint(e) => e.clone(),
+                    _ => unreachable!(),
+                },
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+
+        // `add` returns the sum without reducing it
+        let sum = returned(&add_key);
+        assert!(!sum.should_reduce());
+        assert_eq!(sum.range(), &Range::new(0x1fffffffe_u128));
+
+        // `main` knows the range of `c`, and reduces it to return it
+        let c = returned(&main_key);
+        assert!(c.should_reduce());
+        assert_eq!(c.range(), &Range::new(0x1fffffffe_u128));
+    }
+
+    #[test]
+    fn sub_offset() {
+        // the offset is a multiple of `2**bitwidth`, even if the subtracted value has fewer bits
+        assert_eq!(
+            Range::<Bn128Field>::new(1).sub_offset(32),
+            Bn128Field::from(0x100000000_u128)
+        );
+        assert_eq!(
+            Range::<Bn128Field>::new(0x1ffffffff_u128).sub_offset(32),
+            Bn128Field::from(0x200000000_u128)
+        );
+    }
+}
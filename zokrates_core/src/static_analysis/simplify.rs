@@ -0,0 +1,253 @@
+//! Module containing algebraic simplification for the zir AST
+//!
+//! Macro-expanded and generic code is full of operations with a neutral element, such as `x ^ 0` or `x & 0xffffffff` on
+//! a `u32`, and of double negations. Each of them is flattened to constraints, and for uints to a decomposition into
+//! bits, although its value is the one of its operand. They are replaced by their operand before the range analysis.
+//!
+//! Only identities which keep all the operands of the expression are applied: `x * 0` is not simplified to `0`, as `x`
+//! could contain a division by zero which would then not fail anymore.
+
+use crate::zir::folder::*;
+use crate::zir::types::UBitwidth;
+use crate::zir::*;
+use zokrates_field::Field;
+
+pub struct Simplifier;
+
+impl Simplifier {
+    pub fn simplify<'ast, T: Field>(p: ZirProgram<'ast, T>) -> ZirProgram<'ast, T> {
+        Simplifier.fold_program(p)
+    }
+}
+
+fn mask(bitwidth: UBitwidth) -> u128 {
+    u128::max_value() >> (128 - bitwidth.to_usize())
+}
+
+fn is_number<'ast, T: Field>(e: &FieldElementExpression<'ast, T>, n: u32) -> bool {
+    match e {
+        FieldElementExpression::Number(v) => *v == T::from(n),
+        _ => false,
+    }
+}
+
+fn is_value<'ast, T: Field>(e: &UExpression<'ast, T>, v: u128) -> bool {
+    match e.as_inner() {
+        UExpressionInner::Value(value) => *value == v,
+        _ => false,
+    }
+}
+
+fn is_bool<'ast, T: Field>(e: &BooleanExpression<'ast, T>, b: bool) -> bool {
+    match e {
+        BooleanExpression::Value(value) => *value == b,
+        _ => false,
+    }
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for Simplifier {
+    fn fold_field_expression(
+        &mut self,
+        e: FieldElementExpression<'ast, T>,
+    ) -> FieldElementExpression<'ast, T> {
+        match fold_field_expression(self, e) {
+            // x + 0 = 0 + x = x
+            FieldElementExpression::Add(box e1, box e2) => {
+                match (is_number(&e1, 0), is_number(&e2, 0)) {
+                    (true, _) => e2,
+                    (_, true) => e1,
+                    _ => FieldElementExpression::Add(box e1, box e2),
+                }
+            }
+            // x - 0 = x
+            FieldElementExpression::Sub(box e1, box e2) => match is_number(&e2, 0) {
+                true => e1,
+                false => FieldElementExpression::Sub(box e1, box e2),
+            },
+            // x * 1 = 1 * x = x
+            FieldElementExpression::Mult(box e1, box e2) => {
+                match (is_number(&e1, 1), is_number(&e2, 1)) {
+                    (true, _) => e2,
+                    (_, true) => e1,
+                    _ => FieldElementExpression::Mult(box e1, box e2),
+                }
+            }
+            // x / 1 = x
+            FieldElementExpression::Div(box e1, box e2) => match is_number(&e2, 1) {
+                true => e1,
+                false => FieldElementExpression::Div(box e1, box e2),
+            },
+            // x ** 1 = x
+            FieldElementExpression::Pow(box e1, box e2) => match is_number(&e2, 1) {
+                true => e1,
+                false => FieldElementExpression::Pow(box e1, box e2),
+            },
+            e => e,
+        }
+    }
+
+    fn fold_boolean_expression(
+        &mut self,
+        e: BooleanExpression<'ast, T>,
+    ) -> BooleanExpression<'ast, T> {
+        match fold_boolean_expression(self, e) {
+            // !!x = x
+            BooleanExpression::Not(box BooleanExpression::Not(box e)) => e,
+            // x && true = true && x = x && x = x
+            BooleanExpression::And(box e1, box e2) => {
+                match (is_bool(&e1, true), is_bool(&e2, true) || e1 == e2) {
+                    (true, _) => e2,
+                    (_, true) => e1,
+                    _ => BooleanExpression::And(box e1, box e2),
+                }
+            }
+            // x || false = false || x = x || x = x
+            BooleanExpression::Or(box e1, box e2) => {
+                match (is_bool(&e1, false), is_bool(&e2, false) || e1 == e2) {
+                    (true, _) => e2,
+                    (_, true) => e1,
+                    _ => BooleanExpression::Or(box e1, box e2),
+                }
+            }
+            e => e,
+        }
+    }
+
+    fn fold_uint_expression_inner(
+        &mut self,
+        bitwidth: UBitwidth,
+        e: UExpressionInner<'ast, T>,
+    ) -> UExpressionInner<'ast, T> {
+        match fold_uint_expression_inner(self, bitwidth, e) {
+            // x + 0 = 0 + x = x
+            UExpressionInner::Add(box e1, box e2) => match (is_value(&e1, 0), is_value(&e2, 0)) {
+                (true, _) => e2.into_inner(),
+                (_, true) => e1.into_inner(),
+                _ => UExpressionInner::Add(box e1, box e2),
+            },
+            // x - 0 = x
+            UExpressionInner::Sub(box e1, box e2) => match is_value(&e2, 0) {
+                true => e1.into_inner(),
+                false => UExpressionInner::Sub(box e1, box e2),
+            },
+            // x * 1 = 1 * x = x
+            UExpressionInner::Mult(box e1, box e2) => match (is_value(&e1, 1), is_value(&e2, 1)) {
+                (true, _) => e2.into_inner(),
+                (_, true) => e1.into_inner(),
+                _ => UExpressionInner::Mult(box e1, box e2),
+            },
+            // x / 1 = x
+            UExpressionInner::Div(box e1, box e2) => match is_value(&e2, 1) {
+                true => e1.into_inner(),
+                false => UExpressionInner::Div(box e1, box e2),
+            },
+            // x ^ 0 = 0 ^ x = x
+            UExpressionInner::Xor(box e1, box e2) => match (is_value(&e1, 0), is_value(&e2, 0)) {
+                (true, _) => e2.into_inner(),
+                (_, true) => e1.into_inner(),
+                _ => UExpressionInner::Xor(box e1, box e2),
+            },
+            // x & 0b1...1 = 0b1...1 & x = x & x = x
+            UExpressionInner::And(box e1, box e2) => {
+                let ones = mask(bitwidth);
+                match (is_value(&e1, ones), is_value(&e2, ones) || e1 == e2) {
+                    (true, _) => e2.into_inner(),
+                    (_, true) => e1.into_inner(),
+                    _ => UExpressionInner::And(box e1, box e2),
+                }
+            }
+            // x | 0 = 0 | x = x | x = x
+            UExpressionInner::Or(box e1, box e2) => {
+                match (is_value(&e1, 0), is_value(&e2, 0) || e1 == e2) {
+                    (true, _) => e2.into_inner(),
+                    (_, true) => e1.into_inner(),
+                    _ => UExpressionInner::Or(box e1, box e2),
+                }
+            }
+            // !!x = x
+            UExpressionInner::Not(box e) => match e.into_inner() {
+                UExpressionInner::Not(box e) => e.into_inner(),
+                e => UExpressionInner::Not(box e.annotate(bitwidth)),
+            },
+            // x << 0 = x >> 0 = x
+            UExpressionInner::LeftShift(box e, box by) => match is_number(&by, 0) {
+                true => e.into_inner(),
+                false => UExpressionInner::LeftShift(box e, box by),
+            },
+            UExpressionInner::RightShift(box e, box by) => match is_number(&by, 0) {
+                true => e.into_inner(),
+                false => UExpressionInner::RightShift(box e, box by),
+            },
+            e => e,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zokrates_field::Bn128Field;
+
+    fn identifier<'ast>(id: &'ast str) -> UExpression<'ast, Bn128Field> {
+        UExpressionInner::Identifier(id.into()).annotate(32)
+    }
+
+    fn value<'ast>(v: u128) -> UExpression<'ast, Bn128Field> {
+        UExpressionInner::Value(v).annotate(32)
+    }
+
+    #[test]
+    fn uint_identities() {
+        // !!((a ^ 0) & 0xffffffff) | (b * 1 + 0) == a | b
+        let e = identifier("a")
+            .xor(value(0))
+            .and(value(0xffffffff))
+            .not()
+            .not()
+            .or(identifier("b").mult(value(1)).add(value(0)));
+
+        assert_eq!(
+            Simplifier.fold_uint_expression(e),
+            identifier("a").or(identifier("b"))
+        );
+
+        // a & 0xffff is not simplified on a u32
+        let e = identifier("a").and(value(0xffff));
+
+        assert_eq!(Simplifier.fold_uint_expression(e.clone()), e);
+    }
+
+    #[test]
+    fn field_and_boolean_identities() {
+        // !!(a * 1 - 0 == b) && true
+        let a = FieldElementExpression::Identifier("a".into());
+        let b = FieldElementExpression::Identifier("b".into());
+
+        let e = BooleanExpression::And(
+            box BooleanExpression::Not(box BooleanExpression::Not(box BooleanExpression::FieldEq(
+                box FieldElementExpression::Sub(
+                    box FieldElementExpression::Mult(
+                        box a.clone(),
+                        box FieldElementExpression::Number(Bn128Field::from(1)),
+                    ),
+                    box FieldElementExpression::Number(Bn128Field::from(0)),
+                ),
+                box b.clone(),
+            ))),
+            box BooleanExpression::Value(true),
+        );
+
+        assert_eq!(
+            Simplifier.fold_boolean_expression(e),
+            BooleanExpression::FieldEq(box a.clone(), box b.clone())
+        );
+
+        // a * 0 is kept, as `a` could fail
+        let e = FieldElementExpression::Mult(
+            box a,
+            box FieldElementExpression::Number(Bn128Field::from(0)),
+        );
+
+        assert_eq!(Simplifier.fold_field_expression(e.clone()), e);
+    }
+}
@@ -0,0 +1,192 @@
+//! Module containing strength reduction for the zir AST
+//!
+//! A uint product is flattened to a multiplication whose result has as many bits as both operands together, so that
+//! reducing it decomposes more bits than its bitwidth, and when it does not fit in the field both operands are reduced
+//! and multiplied on limbs. A product by a constant is cheaper to compute with other operations:
+//!
+//! - `x * 2**k` is replaced by `x << k`, which only decomposes `x` on its bitwidth and whose result is already reduced
+//! - `x * c`, for a small constant `c`, is replaced by `x + ... + x`, a linear combination which only reduces `x` if the
+//! sum does not fit
+//!
+//! A division or a remainder is flattened to a directive whose quotient and remainder are decomposed, which a division
+//! by a power of two does not need:
+//!
+//! - `x / 2**k` is replaced by `x >> k`
+//! - `x % 2**k` is replaced by `x & (2**k - 1)`
+//!
+//! The operands repeated by the sums are defined once by the uint common subexpression elimination which follows.
+
+use crate::zir::folder::*;
+use crate::zir::types::UBitwidth;
+use crate::zir::*;
+use zokrates_field::Field;
+
+/// The largest constant by which a product is replaced by a sum
+const MAX_SUMMED_CONSTANT: u128 = 7;
+
+pub struct StrengthReducer;
+
+impl StrengthReducer {
+    pub fn reduce<'ast, T: Field>(p: ZirProgram<'ast, T>) -> ZirProgram<'ast, T> {
+        StrengthReducer.fold_program(p)
+    }
+}
+
+/// Returns `x * c` as a cheaper operation, if there is one
+fn reduce_product<'ast, T: Field>(
+    x: &UExpression<'ast, T>,
+    c: u128,
+) -> Option<UExpression<'ast, T>> {
+    match c {
+        // `x * 0` is kept, as `x` could fail, and `x * 1` is simplified beforehand
+        0 | 1 => None,
+        c if c.is_power_of_two() => Some(
+            x.clone()
+                .left_shift(FieldElementExpression::Number(T::from(c.trailing_zeros()))),
+        ),
+        c if c <= MAX_SUMMED_CONSTANT => Some((1..c).fold(x.clone(), |sum, _| sum.add(x.clone()))),
+        _ => None,
+    }
+}
+
+/// Returns `x / c`, or `x % c` if `remainder` is set, as a cheaper operation if `c` is a power of two
+fn reduce_division<'ast, T: Field>(
+    x: &UExpression<'ast, T>,
+    c: u128,
+    remainder: bool,
+) -> Option<UExpression<'ast, T>> {
+    match (c.is_power_of_two(), remainder) {
+        (false, _) => None,
+        (true, false) => Some(
+            x.clone()
+                .right_shift(FieldElementExpression::Number(T::from(c.trailing_zeros()))),
+        ),
+        (true, true) => Some(
+            x.clone()
+                .and(UExpressionInner::Value(c - 1).annotate(x.bitwidth)),
+        ),
+    }
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for StrengthReducer {
+    fn fold_uint_expression_inner(
+        &mut self,
+        bitwidth: UBitwidth,
+        e: UExpressionInner<'ast, T>,
+    ) -> UExpressionInner<'ast, T> {
+        match fold_uint_expression_inner(self, bitwidth, e) {
+            UExpressionInner::Mult(box e1, box e2) => {
+                let reduced = match (e1.as_inner(), e2.as_inner()) {
+                    (_, UExpressionInner::Value(c)) => reduce_product(&e1, *c),
+                    (UExpressionInner::Value(c), _) => reduce_product(&e2, *c),
+                    _ => None,
+                };
+
+                reduced
+                    .map(|e| e.into_inner())
+                    .unwrap_or_else(|| UExpressionInner::Mult(box e1, box e2))
+            }
+            UExpressionInner::Div(box e1, box e2) => match e2.as_inner() {
+                UExpressionInner::Value(c) => reduce_division(&e1, *c, false),
+                _ => None,
+            }
+            .map(|e| e.into_inner())
+            .unwrap_or_else(|| UExpressionInner::Div(box e1, box e2)),
+            UExpressionInner::Rem(box e1, box e2) => match e2.as_inner() {
+                UExpressionInner::Value(c) => reduce_division(&e1, *c, true),
+                _ => None,
+            }
+            .map(|e| e.into_inner())
+            .unwrap_or_else(|| UExpressionInner::Rem(box e1, box e2)),
+            e => e,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zokrates_field::Bn128Field;
+
+    fn identifier<'ast>(id: &'ast str) -> UExpression<'ast, Bn128Field> {
+        UExpressionInner::Identifier(id.into()).annotate(32)
+    }
+
+    fn value<'ast>(v: u128) -> UExpression<'ast, Bn128Field> {
+        UExpressionInner::Value(v).annotate(32)
+    }
+
+    fn reduce<'ast>(e: UExpression<'ast, Bn128Field>) -> UExpression<'ast, Bn128Field> {
+        StrengthReducer.fold_uint_expression(e)
+    }
+
+    #[test]
+    fn power_of_two() {
+        // a * 8 == a << 3
+        assert_eq!(
+            reduce(identifier("a").mult(value(8))),
+            identifier("a").left_shift(FieldElementExpression::Number(Bn128Field::from(3)))
+        );
+
+        // 0x80000000 * (a ^ b) == (a ^ b) << 31
+        assert_eq!(
+            reduce(value(0x80000000).mult(identifier("a").xor(identifier("b")))),
+            identifier("a")
+                .xor(identifier("b"))
+                .left_shift(FieldElementExpression::Number(Bn128Field::from(31)))
+        );
+    }
+
+    #[test]
+    fn small_constant() {
+        // a * 3 == a + a + a
+        assert_eq!(
+            reduce(identifier("a").mult(value(3))),
+            identifier("a").add(identifier("a")).add(identifier("a"))
+        );
+
+        // the operands are rewritten first: 5 * (b * 2) == (b << 1) + ... + (b << 1)
+        let b = || identifier("b").left_shift(FieldElementExpression::Number(Bn128Field::from(1)));
+        assert_eq!(
+            reduce(value(5).mult(identifier("b").mult(value(2)))),
+            b().add(b()).add(b()).add(b()).add(b())
+        );
+    }
+
+    #[test]
+    fn division_by_power_of_two() {
+        // a / 16 == a >> 4
+        assert_eq!(
+            reduce(identifier("a").div(value(16))),
+            identifier("a").right_shift(FieldElementExpression::Number(Bn128Field::from(4)))
+        );
+
+        // a % 16 == a & 15
+        assert_eq!(
+            reduce(identifier("a").rem(value(16))),
+            identifier("a").and(value(15))
+        );
+
+        // divisions by other constants, and by zero, are kept
+        for e in vec![
+            identifier("a").div(value(10)),
+            identifier("a").rem(value(0)),
+            value(16).div(identifier("a")),
+        ] {
+            assert_eq!(reduce(e.clone()), e);
+        }
+    }
+
+    #[test]
+    fn kept() {
+        // products by 0, by large constants, and of variables are kept
+        for e in vec![
+            identifier("a").mult(value(0)),
+            identifier("a").mult(value(10)),
+            value(0xffffffff).mult(identifier("a")),
+            identifier("a").mult(identifier("b")),
+        ] {
+            assert_eq!(reduce(e.clone()), e);
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! Module containing common subexpression elimination for uint expressions
+//!
+//! Each uint expression is flattened on its own, so a subexpression such as `a ^ b` which occurs in several statements
+//! is decomposed into bits as many times. Expressions which occur more than once in a function are defined once, before
+//! the first statement using them, and replaced by the defined variable. ZIR functions have no control flow and their
+//! variables are only assigned once, so equal expressions always have the same value.
+//!
+//! The operands of commutative operators are first ordered by hash, so that `a ^ b` and `b ^ a` are shared as well.
+
+use crate::zir::folder::*;
+use crate::zir::types::UBitwidth;
+use crate::zir::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use zokrates_field::Field;
+
+pub struct UintCse<'ast, T: Field> {
+    // the variables defined for the expressions of the current function which occur more than once
+    shared: HashMap<UExpression<'ast, T>, Identifier<'ast>>,
+    // the number of occurrences of the expressions of the current function
+    occurrences: HashMap<UExpression<'ast, T>, usize>,
+    // the definitions to insert before the statement being folded
+    definitions: Vec<ZirStatement<'ast, T>>,
+}
+
+impl<'ast, T: Field> UintCse<'ast, T> {
+    pub fn new() -> Self {
+        UintCse {
+            shared: HashMap::new(),
+            occurrences: HashMap::new(),
+            definitions: vec![],
+        }
+    }
+
+    pub fn share(p: ZirProgram<'ast, T>) -> ZirProgram<'ast, T> {
+        UintCse::new().fold_program(p)
+    }
+}
+
+/// Orders the operands of commutative operators and counts the occurrences of the expressions
+struct Occurrences<'ast, T> {
+    occurrences: HashMap<UExpression<'ast, T>, usize>,
+}
+
+impl<'ast, T: Field> Occurrences<'ast, T> {
+    fn new() -> Self {
+        Occurrences {
+            occurrences: HashMap::new(),
+        }
+    }
+}
+
+fn hash<'ast, T: Field>(e: &UExpression<'ast, T>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    e.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn ordered<'ast, T: Field>(
+    left: UExpression<'ast, T>,
+    right: UExpression<'ast, T>,
+) -> (Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>) {
+    match hash(&left) <= hash(&right) {
+        true => (box left, box right),
+        false => (box right, box left),
+    }
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for Occurrences<'ast, T> {
+    fn fold_uint_expression(&mut self, e: UExpression<'ast, T>) -> UExpression<'ast, T> {
+        let e = fold_uint_expression(self, e);
+
+        match e.as_inner() {
+            UExpressionInner::Identifier(..) | UExpressionInner::Value(..) => {}
+            _ => *self.occurrences.entry(e.clone()).or_insert(0) += 1,
+        }
+
+        e
+    }
+
+    fn fold_uint_expression_inner(
+        &mut self,
+        bitwidth: UBitwidth,
+        e: UExpressionInner<'ast, T>,
+    ) -> UExpressionInner<'ast, T> {
+        match fold_uint_expression_inner(self, bitwidth, e) {
+            UExpressionInner::Add(box left, box right) => {
+                let (left, right) = ordered(left, right);
+                UExpressionInner::Add(left, right)
+            }
+            UExpressionInner::Mult(box left, box right) => {
+                let (left, right) = ordered(left, right);
+                UExpressionInner::Mult(left, right)
+            }
+            UExpressionInner::Xor(box left, box right) => {
+                let (left, right) = ordered(left, right);
+                UExpressionInner::Xor(left, right)
+            }
+            UExpressionInner::And(box left, box right) => {
+                let (left, right) = ordered(left, right);
+                UExpressionInner::And(left, right)
+            }
+            UExpressionInner::Or(box left, box right) => {
+                let (left, right) = ordered(left, right);
+                UExpressionInner::Or(left, right)
+            }
+            e => e,
+        }
+    }
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for UintCse<'ast, T> {
+    fn fold_function(&mut self, f: ZirFunction<'ast, T>) -> ZirFunction<'ast, T> {
+        let mut occurrences = Occurrences::new();
+        let f = occurrences.fold_function(f);
+
+        self.occurrences = occurrences.occurrences;
+        self.shared = HashMap::new();
+
+        fold_function(self, f)
+    }
+
+    fn fold_statement(&mut self, s: ZirStatement<'ast, T>) -> Vec<ZirStatement<'ast, T>> {
+        let statements = fold_statement(self, s);
+        self.definitions.drain(..).chain(statements).collect()
+    }
+
+    fn fold_uint_expression(&mut self, e: UExpression<'ast, T>) -> UExpression<'ast, T> {
+        if self.occurrences.get(&e).cloned().unwrap_or(0) < 2 {
+            return fold_uint_expression(self, e);
+        }
+
+        let bitwidth = e.bitwidth;
+
+        let id = match self.shared.get(&e) {
+            Some(id) => id.clone(),
+            None => {
+                let key = e.clone();
+                let e = fold_uint_expression(self, e);
+
+                let id = Identifier::Internal("CSE", self.shared.len());
+                self.definitions.push(ZirStatement::Definition(
+                    Variable::uint(id.clone(), bitwidth),
+                    e.into(),
+                ));
+                self.shared.insert(key, id.clone());
+                id
+            }
+        };
+
+        UExpressionInner::Identifier(id).annotate(bitwidth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zir::types::Signature;
+    use zokrates_field::Bn128Field;
+
+    fn identifier<'ast>(id: &'ast str) -> UExpression<'ast, Bn128Field> {
+        UExpressionInner::Identifier(id.into()).annotate(32)
+    }
+
+    #[test]
+    fn share_commuted_expressions() {
+        // def main(u32 a, u32 b) -> (u32, u32):
+        //     return (a ^ b) + 1, (b ^ a) & 1
+        //
+        // becomes
+        //
+        // def main(u32 a, u32 b) -> (u32, u32):
+        //     u32 cse_0 = a ^ b
+        //     return cse_0 + 1, cse_0 & 1
+
+        let value = || UExpressionInner::Value(1).annotate(32);
+
+        let f: ZirFunction<Bn128Field> = ZirFunction {
+            arguments: vec![
+                Parameter::private(Variable::uint("a".into(), 32)),
+                Parameter::private(Variable::uint("b".into(), 32)),
+            ],
+            statements: vec![ZirStatement::Return(vec![
+                identifier("a").xor(identifier("b")).add(value()).into(),
+                identifier("b").xor(identifier("a")).and(value()).into(),
+            ])],
+            signature: Signature::new()
+                .inputs(vec![Type::uint(32), Type::uint(32)])
+                .outputs(vec![Type::uint(32), Type::uint(32)]),
+        };
+
+        let statements = UintCse::new().fold_function(f).statements;
+
+        assert_eq!(statements.len(), 2);
+
+        let shared = Identifier::Internal("CSE", 0);
+
+        match &statements[0] {
+            ZirStatement::Definition(v, ZirExpression::Uint(e)) => {
+                assert_eq!(v, &Variable::uint(shared.clone(), 32));
+                assert!(
+                    *e == identifier("a").xor(identifier("b"))
+                        || *e == identifier("b").xor(identifier("a"))
+                );
+            }
+            s => panic!(
+                "expected a definition of the shared expression, found {:?}",
+                s
+            ),
+        }
+
+        let shared = || UExpressionInner::Identifier(shared.clone()).annotate(32);
+
+        // the operands of the returned expressions are ordered by hash
+        match &statements[1] {
+            ZirStatement::Return(expressions) => {
+                assert!(
+                    expressions[0] == ZirExpression::Uint(shared().add(value()))
+                        || expressions[0] == ZirExpression::Uint(value().add(shared()))
+                );
+                assert!(
+                    expressions[1] == ZirExpression::Uint(shared().and(value()))
+                        || expressions[1] == ZirExpression::Uint(value().and(shared()))
+                );
+            }
+            s => panic!("expected a return, found {:?}", s),
+        }
+    }
+
+    #[test]
+    fn single_occurrences_unchanged() {
+        // def main(u32 a) -> (u32):
+        //     u32 b = a ^ a
+        //     return b + 1
+
+        let f: ZirFunction<Bn128Field> = ZirFunction {
+            arguments: vec![Parameter::private(Variable::uint("a".into(), 32))],
+            statements: vec![
+                ZirStatement::Definition(
+                    Variable::uint("b".into(), 32),
+                    identifier("a").xor(identifier("a")).into(),
+                ),
+                ZirStatement::Return(vec![identifier("b")
+                    .add(UExpressionInner::Value(1).annotate(32))
+                    .into()]),
+            ],
+            signature: Signature::new()
+                .inputs(vec![Type::uint(32)])
+                .outputs(vec![Type::uint(32)]),
+        };
+
+        let expected = Occurrences::new().fold_function(f.clone());
+
+        assert_eq!(UintCse::new().fold_function(f), expected);
+    }
+}
@@ -0,0 +1,308 @@
+//! Module reporting the ranges inferred by the range analysis
+//!
+//! Each uint variable is reported with the number of bits of the values it can take as a field element,
+//! which exceeds its bitwidth when its reduction is deferred, and the number of its uses which reduce it to its
+//! bitwidth. Reducing a value decomposes it into bits, which is what makes uint operations expensive. Expressions which
+//! are not variables, such as `a + b`, are reported when they are reduced from more bits than their bitwidth, as the
+//! results of operations such as `a ^ b` are already known as bits. Variables which are not in the source, such as the
+//! returned values or the variables inlined from calls, are reported under their internal name.
+
+use super::range_analysis::returned_ranges;
+use crate::zir::folder::*;
+use crate::zir::ranged;
+use crate::zir::types::FunctionKey;
+use crate::zir::*;
+use std::collections::HashMap;
+use std::fmt;
+use zokrates_field::Field;
+
+/// The range of a uint variable or expression
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UintRange {
+    /// the function of the variable or expression
+    pub function: String,
+    /// the name of the variable, or the expression
+    pub name: String,
+    /// the number of the assignment of a variable of the source, see the source map
+    pub occurrence: Option<usize>,
+    pub bitwidth: usize,
+    /// the number of bits of the values as field elements
+    pub bits: usize,
+    /// the number of uses which reduce the value to `bitwidth` bits
+    pub reductions: usize,
+}
+
+impl fmt::Display for UintRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.occurrence {
+            Some(occurrence) if occurrence > 0 => {
+                write!(f, "{}: {}#{}", self.function, self.name, occurrence)?
+            }
+            _ => write!(f, "{}: {}", self.function, self.name)?,
+        };
+
+        write!(f, " (u{}) on {} bits, ", self.bitwidth, self.bits)?;
+
+        match self.reductions {
+            0 => write!(f, "never reduced"),
+            1 => write!(f, "reduced once"),
+            n => write!(f, "reduced {} times", n),
+        }
+    }
+}
+
+/// Returns the ranges of the uint variables and reduced expressions of a program annotated by the range analysis, by
+/// function, in the order of their definition
+pub fn uint_ranges<'ast, T: Field>(p: &ranged::ZirProgram<'ast, T>) -> Vec<UintRange> {
+    // the number of bits of the uints returned by each function kept as a shared sub-circuit
+    let returns: HashMap<_, _> = p.modules[&p.main]
+        .functions
+        .iter()
+        .filter_map(|(key, symbol)| match symbol {
+            ZirFunctionSymbol::Here(f) => Some((
+                key.clone(),
+                returned_ranges(f)
+                    .into_iter()
+                    .map(|range| range.map(|range| range.bits()))
+                    .collect(),
+            )),
+            _ => None,
+        })
+        .collect();
+
+    let mut functions: Vec<_> = p.modules[&p.main]
+        .functions
+        .iter()
+        .filter_map(|(key, symbol)| match symbol {
+            ZirFunctionSymbol::Here(f) => {
+                let name = match key.id {
+                    "main" => String::from("main"),
+                    _ => key.to_slug(),
+                };
+                Some((name, f))
+            }
+            _ => None,
+        })
+        .collect();
+
+    // report `main` first, then the other functions by name
+    functions.sort_by_key(|(name, _)| (name.as_str() != "main", name.clone()));
+
+    functions
+        .into_iter()
+        .flat_map(|(name, f)| {
+            let mut collector = RangeCollector::new(name, &returns);
+            collector.fold_function(f.clone());
+            collector.ranges
+        })
+        .collect()
+}
+
+struct RangeCollector<'a, 'ast> {
+    function: String,
+    /// the number of bits of the uints returned by the functions called, see `uint_ranges`
+    returns: &'a HashMap<FunctionKey<'ast>, Vec<Option<usize>>>,
+    ranges: Vec<UintRange>,
+    /// the index of the range of each variable or expression in `ranges`
+    indices: HashMap<(String, Option<usize>), usize>,
+}
+
+impl<'a, 'ast> RangeCollector<'a, 'ast> {
+    fn new(function: String, returns: &'a HashMap<FunctionKey<'ast>, Vec<Option<usize>>>) -> Self {
+        RangeCollector {
+            function,
+            returns,
+            ranges: vec![],
+            indices: HashMap::new(),
+        }
+    }
+
+    /// Returns the range of a variable or expression, recording it on `bits` bits if it was not recorded yet
+    fn range(
+        &mut self,
+        name: String,
+        occurrence: Option<usize>,
+        bitwidth: usize,
+        bits: usize,
+    ) -> &mut UintRange {
+        let key = (name.clone(), occurrence);
+
+        let index = match self.indices.get(&key) {
+            Some(index) => *index,
+            None => {
+                self.ranges.push(UintRange {
+                    function: self.function.clone(),
+                    name,
+                    occurrence,
+                    bitwidth,
+                    bits,
+                    reductions: 0,
+                });
+                self.indices.insert(key, self.ranges.len() - 1);
+                self.ranges.len() - 1
+            }
+        };
+
+        &mut self.ranges[index]
+    }
+
+    /// Records the definition of a variable on `bits` bits
+    fn define(&mut self, v: &Variable, bits: usize) {
+        if let Type::Uint(bitwidth) = v.get_type() {
+            let (name, occurrence) = variable_name(&v.id);
+            self.range(name, occurrence, bitwidth.to_usize(), bits);
+        }
+    }
+}
+
+/// Returns the name of a variable in the source and the number of its assignment, or its internal name
+fn variable_name(id: &Identifier) -> (String, Option<usize>) {
+    match id {
+        Identifier::Source(source) => source
+            .source_name()
+            .map(|(name, occurrence)| (name, Some(occurrence)))
+            .unwrap_or_else(|| (id.to_string(), None)),
+        Identifier::Internal(..) => (id.to_string(), None),
+    }
+}
+
+impl<'a, 'ast, T: Field> Folder<'ast, T, UMetadata<T>> for RangeCollector<'a, 'ast> {
+    fn fold_parameter(&mut self, p: Parameter<'ast>) -> Parameter<'ast> {
+        if let Type::Uint(bitwidth) = p.id.get_type() {
+            self.define(&p.id, bitwidth.to_usize());
+        }
+
+        p
+    }
+
+    fn fold_statement(
+        &mut self,
+        s: ranged::ZirStatement<'ast, T>,
+    ) -> Vec<ranged::ZirStatement<'ast, T>> {
+        match s {
+            ZirStatement::Definition(ref v, ZirExpression::Uint(ref e)) => {
+                self.define(v, e.range().bits())
+            }
+            // the uints returned by embeds are reduced
+            ZirStatement::MultipleDefinition(
+                ref variables,
+                ZirExpressionList::FunctionCall(ref key, ..),
+            ) => {
+                let returns = self.returns.get(key);
+
+                for (i, v) in variables.iter().enumerate() {
+                    if let Type::Uint(bitwidth) = v.get_type() {
+                        let bits = returns
+                            .and_then(|returns| returns[i])
+                            .unwrap_or_else(|| bitwidth.to_usize());
+                        self.define(v, bits);
+                    }
+                }
+            }
+            _ => {}
+        };
+
+        fold_statement(self, s)
+    }
+
+    fn fold_uint_expression(
+        &mut self,
+        e: ranged::UExpression<'ast, T>,
+    ) -> ranged::UExpression<'ast, T> {
+        if e.should_reduce() {
+            let bitwidth = e.bitwidth.to_usize();
+            let bits = e.range().bits();
+
+            let range = match e.as_inner() {
+                // constants are decomposed for free
+                UExpressionInner::Value(_) => None,
+                UExpressionInner::Identifier(id) => {
+                    let (name, occurrence) = variable_name(id);
+                    Some(self.range(name, occurrence, bitwidth, bits))
+                }
+                _ if bits > bitwidth => Some(self.range(e.to_string(), None, bitwidth, bits)),
+                _ => None,
+            };
+
+            if let Some(range) = range {
+                range.reductions += 1;
+            }
+        }
+
+        fold_uint_expression(self, e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::static_analysis::range_analysis::RangeAnalysis;
+    use crate::zir::types::{FunctionKey, Signature};
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn ranges() {
+        // def main(u32 a, u32 b) -> u32:
+        //     u32 c = a + b
+        //     return (c ^ a) + (c + b)
+
+        let a = || UExpressionInner::Identifier("a".into()).annotate(32);
+        let b = || UExpressionInner::Identifier("b".into()).annotate(32);
+        let c = || UExpressionInner::Identifier("c".into()).annotate(32);
+
+        let main: ZirFunction<Bn128Field> = ZirFunction {
+            arguments: vec![
+                Parameter::private(Variable::uint("a".into(), 32)),
+                Parameter::private(Variable::uint("b".into(), 32)),
+            ],
+            statements: vec![
+                ZirStatement::Definition(Variable::uint("c".into(), 32), a().add(b()).into()),
+                ZirStatement::Return(vec![c().xor(a()).add(c().add(b())).into()]),
+            ],
+            signature: Signature::new()
+                .inputs(vec![Type::uint(32), Type::uint(32)])
+                .outputs(vec![Type::uint(32)]),
+        };
+
+        let p = ZirProgram {
+            main: "main".into(),
+            modules: vec![(
+                "main".into(),
+                ZirModule {
+                    functions: vec![(
+                        FunctionKey::with_id("main").signature(main.signature.clone()),
+                        ZirFunctionSymbol::Here(main),
+                    )]
+                    .into_iter()
+                    .collect(),
+                },
+            )]
+            .into_iter()
+            .collect(),
+        };
+
+        let p = RangeAnalysis::analyse(p).unwrap();
+
+        let range = |name: &str, occurrence, bits, reductions| UintRange {
+            function: String::from("main"),
+            name: String::from(name),
+            occurrence,
+            bitwidth: 32,
+            bits,
+            reductions,
+        };
+
+        assert_eq!(
+            uint_ranges(&p),
+            vec![
+                // `a` is reduced by the xor
+                range("a", Some(0), 32, 1),
+                range("b", Some(0), 32, 0),
+                // `c` is reduced by the xor, but not in `c + b`
+                range("c", Some(0), 33, 1),
+                // the returned value is reduced
+                range("((c ^ a) + (c + b))", None, 34, 1),
+            ]
+        );
+    }
+}
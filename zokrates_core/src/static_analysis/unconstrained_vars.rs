@@ -38,6 +38,14 @@ impl UnconstrainedVariableDetector {
 }
 
 impl<T: Field> Folder<T> for UnconstrainedVariableDetector {
+    fn fold_module(&mut self, p: Prog<T>) -> Prog<T> {
+        // called functions have their own variables, so only `main` is checked, the inputs of a call counting as used
+        Prog {
+            main: self.fold_function(p.main),
+            ..p
+        }
+    }
+
     fn fold_argument(&mut self, p: FlatVariable) -> FlatVariable {
         p
     }
@@ -85,8 +93,10 @@ mod tests {
         };
 
         let p: Prog<Bn128Field> = Prog {
+            functions: vec![],
             private: vec![true],
             main,
+            private_outputs: vec![],
         };
 
         UnconstrainedVariableDetector::detect(p);
@@ -109,8 +119,10 @@ mod tests {
         };
 
         let p: Prog<Bn128Field> = Prog {
+            functions: vec![],
             private: vec![true],
             main,
+            private_outputs: vec![],
         };
 
         UnconstrainedVariableDetector::detect(p);
@@ -167,8 +179,10 @@ mod tests {
         };
 
         let p: Prog<Bn128Field> = Prog {
+            functions: vec![],
             private: vec![true],
             main,
+            private_outputs: vec![],
         };
 
         UnconstrainedVariableDetector::detect(p);
@@ -1,9 +1,13 @@
 //! Module containing SSA reduction, including for-loop unrolling
 //!
+//! Loops whose upper bound is a runtime value below a constant maximum are unrolled up to the maximum, their iterations
+//! being masked by a condition, see `bounded_loop`.
+//!
 //! @file unroll.rs
 //! @author Thibaut Schaeffer <thibaut@schaeff.fr>
 //! @date 2018
 
+use crate::limits::{self, Budget};
 use crate::typed_absy::folder::*;
 use crate::typed_absy::types::{MemberId, Type};
 use crate::typed_absy::*;
@@ -17,20 +21,28 @@ pub enum Output<'ast, T: Field> {
     Incomplete(TypedProgram<'ast, T>, usize),
 }
 
-pub struct Unroller<'ast> {
+pub struct Unroller<'ast, T: Field> {
     // version index for any variable name
     substitution: HashMap<CoreIdentifier<'ast>, usize>,
+    // the value of the loop variables in the iteration being unrolled
+    induction_variables: HashMap<Identifier<'ast>, T>,
     // whether all statements could be unrolled so far. Loops with variable bounds cannot.
     complete: bool,
     statement_count: usize,
+    budget: Budget,
+    // the first resource limit which was exceeded. Once set, no more statements are produced
+    exceeded: Option<limits::Error>,
 }
 
-impl<'ast> Unroller<'ast> {
+impl<'ast, T: Field> Unroller<'ast, T> {
     fn new() -> Self {
         Unroller {
             substitution: HashMap::new(),
+            induction_variables: HashMap::new(),
             complete: true,
             statement_count: 0,
+            budget: Budget::unlimited(),
+            exceeded: None,
         }
     }
 
@@ -54,17 +66,38 @@ impl<'ast> Unroller<'ast> {
         res
     }
 
-    pub fn unroll<T: Field>(p: TypedProgram<T>) -> Output<T> {
-        let mut unroller = Unroller::new();
+    pub fn unroll(
+        p: TypedProgram<'ast, T>,
+        budget: &Budget,
+    ) -> Result<Output<'ast, T>, limits::Error> {
+        let mut unroller = Unroller {
+            budget: budget.clone(),
+            ..Unroller::new()
+        };
         let p = unroller.fold_program(p);
 
-        match unroller.complete {
+        if let Some(e) = unroller.exceeded {
+            return Err(e);
+        }
+
+        Ok(match unroller.complete {
             true => Output::Complete(p),
             false => Output::Incomplete(p, unroller.statement_count),
+        })
+    }
+
+    fn check_budget(&mut self, pending: usize) -> bool {
+        if self.exceeded.is_none() {
+            self.exceeded = self
+                .budget
+                .check_unrolled_statements(self.statement_count + pending)
+                .and_then(|_| self.budget.check_time())
+                .err();
         }
+        self.exceeded.is_none()
     }
 
-    fn choose_many<T: Field>(
+    fn choose_many(
         base: TypedExpression<'ast, T>,
         indices: Vec<Access<'ast, T>>,
         new_expression: TypedExpression<'ast, T>,
@@ -379,9 +412,250 @@ fn linear<'ast, T: Field>(a: TypedAssignee<'ast, T>) -> (Variable, Vec<Access<'a
     }
 }
 
-impl<'ast, T: Field> Folder<'ast, T> for Unroller<'ast> {
+/// Returns a variable as an expression
+fn identifier<'ast, T: Field>(v: &Variable<'ast>) -> TypedExpression<'ast, T> {
+    match v.get_type() {
+        Type::FieldElement => FieldElementExpression::Identifier(v.id.clone()).into(),
+        Type::Boolean => BooleanExpression::Identifier(v.id.clone()).into(),
+        Type::Uint(bitwidth) => UExpressionInner::Identifier(v.id.clone())
+            .annotate(bitwidth)
+            .into(),
+        Type::Array(array_type) => ArrayExpressionInner::Identifier(v.id.clone())
+            .annotate(*array_type.ty, array_type.size)
+            .into(),
+        Type::Struct(members) => StructExpressionInner::Identifier(v.id.clone())
+            .annotate(members)
+            .into(),
+    }
+}
+
+/// Returns the value of an assignee as an expression
+/// a[2].b -> a[2].b
+fn assignee<'ast, T: Field>(a: &TypedAssignee<'ast, T>) -> TypedExpression<'ast, T> {
+    match a {
+        TypedAssignee::Identifier(v) => identifier(v),
+        TypedAssignee::Select(array, index) => {
+            let array = match assignee(array) {
+                TypedExpression::Array(array) => array,
+                e => unreachable!(
+                    "an element should only be selected in an array, found {}",
+                    e
+                ),
+            };
+            let index = (**index).clone();
+
+            match a.get_type() {
+                Type::FieldElement => FieldElementExpression::select(array, index).into(),
+                Type::Boolean => BooleanExpression::select(array, index).into(),
+                Type::Uint(..) => UExpression::select(array, index).into(),
+                Type::Array(..) => ArrayExpression::select(array, index).into(),
+                Type::Struct(..) => StructExpression::select(array, index).into(),
+            }
+        }
+        TypedAssignee::Member(s, id) => {
+            let s = match assignee(s) {
+                TypedExpression::Struct(s) => s,
+                e => unreachable!("a member should only be accessed in a struct, found {}", e),
+            };
+            let id = id.clone();
+
+            match a.get_type() {
+                Type::FieldElement => FieldElementExpression::member(s, id).into(),
+                Type::Boolean => BooleanExpression::member(s, id).into(),
+                Type::Uint(..) => UExpression::member(s, id).into(),
+                Type::Array(..) => ArrayExpression::member(s, id).into(),
+                Type::Struct(..) => StructExpression::member(s, id).into(),
+            }
+        }
+    }
+}
+
+/// Returns `if condition then consequence else alternative`, for branches of any type
+fn if_else<'ast, T: Field>(
+    condition: BooleanExpression<'ast, T>,
+    consequence: TypedExpression<'ast, T>,
+    alternative: TypedExpression<'ast, T>,
+) -> TypedExpression<'ast, T> {
+    match (consequence, alternative) {
+        (TypedExpression::FieldElement(c), TypedExpression::FieldElement(a)) => {
+            FieldElementExpression::if_else(condition, c, a).into()
+        }
+        (TypedExpression::Boolean(c), TypedExpression::Boolean(a)) => {
+            BooleanExpression::if_else(condition, c, a).into()
+        }
+        (TypedExpression::Uint(c), TypedExpression::Uint(a)) => {
+            UExpression::if_else(condition, c, a).into()
+        }
+        (TypedExpression::Array(c), TypedExpression::Array(a)) => {
+            ArrayExpression::if_else(condition, c, a).into()
+        }
+        (TypedExpression::Struct(c), TypedExpression::Struct(a)) => {
+            StructExpression::if_else(condition, c, a).into()
+        }
+        (c, a) => unreachable!(
+            "the branches of a condition should have the same type, found {} and {}",
+            c.get_type(),
+            a.get_type()
+        ),
+    }
+}
+
+/// Returns the loop `for v in from..to max max`, whose bound `to` is at most `max`, as a loop over `from..max` whose
+/// iterations only have effects before `to`, so that it can be unrolled when `to` is not a constant.
+///
+/// The iterations are chained by a boolean telling whether the loop is still running, which each iteration sets to
+/// false if its index is `to`: comparing the index to `to` would decompose `to` in every iteration, whereas an
+/// equality is a few constraints. The loop is followed by an assertion that it stopped, or that `to` is `max`, so
+/// that `to` cannot be out of the range of the iterations.
+///
+/// `mask` is the condition under which the enclosing bounded loops are running, and `depth` their number, which tells
+/// their variables apart.
+fn bounded_loop<'ast, T: Field>(
+    mask: Option<BooleanExpression<'ast, T>>,
+    depth: usize,
+    v: Variable<'ast>,
+    from: FieldElementExpression<'ast, T>,
+    to: FieldElementExpression<'ast, T>,
+    max: FieldElementExpression<'ast, T>,
+    statements: Vec<TypedStatement<'ast, T>>,
+) -> Vec<TypedStatement<'ast, T>> {
+    let bound = Variable::field_element(CoreIdentifier::Internal("BOUND", depth));
+    let running = Variable::boolean(CoreIdentifier::Internal("RUNNING", depth));
+
+    let bound_expression = FieldElementExpression::Identifier(bound.id.clone());
+    let running_expression = BooleanExpression::Identifier(running.id.clone());
+
+    let mut locals = HashSet::new();
+    locals.insert(v.id.id.clone());
+
+    let stop = TypedStatement::Definition(
+        TypedAssignee::Identifier(running.clone()),
+        BooleanExpression::And(
+            box running_expression.clone(),
+            box BooleanExpression::Not(box BooleanExpression::FieldEq(
+                box FieldElementExpression::Identifier(v.id.clone()),
+                box bound_expression.clone(),
+            )),
+        )
+        .into(),
+    );
+
+    let body = std::iter::once(stop)
+        .chain(mask_statements(
+            &running_expression,
+            depth,
+            &mut locals,
+            statements,
+        ))
+        .collect();
+
+    vec![
+        TypedStatement::Declaration(bound.clone()),
+        TypedStatement::Definition(TypedAssignee::Identifier(bound), to.into()),
+        TypedStatement::Declaration(running.clone()),
+        TypedStatement::Definition(
+            TypedAssignee::Identifier(running),
+            mask.unwrap_or(BooleanExpression::Value(true)).into(),
+        ),
+        TypedStatement::For(v, from, max.clone(), None, body),
+        TypedStatement::Assertion(BooleanExpression::Or(
+            box BooleanExpression::Not(box running_expression),
+            box BooleanExpression::FieldEq(box bound_expression, box max),
+        )),
+    ]
+}
+
+/// Returns `statements` such that they only have effects if `condition` holds: otherwise, the variables which are not
+/// in `locals` keep their values and the assertions are not enforced. The variables declared in `statements` are
+/// added to `locals`, as they cannot be used after the iteration.
+fn mask_statements<'ast, T: Field>(
+    condition: &BooleanExpression<'ast, T>,
+    depth: usize,
+    locals: &mut HashSet<CoreIdentifier<'ast>>,
+    statements: Vec<TypedStatement<'ast, T>>,
+) -> Vec<TypedStatement<'ast, T>> {
+    let mut res = vec![];
+
+    for s in statements {
+        match s {
+            TypedStatement::Declaration(v) => {
+                locals.insert(v.id.id.clone());
+                res.push(TypedStatement::Declaration(v));
+            }
+            TypedStatement::Definition(a, e) => {
+                let e = match locals.contains(&linear(a.clone()).0.id.id) {
+                    true => e,
+                    false => if_else(condition.clone(), e, assignee(&a)),
+                };
+                res.push(TypedStatement::Definition(a, e));
+            }
+            TypedStatement::Assertion(e) => {
+                res.push(TypedStatement::Assertion(BooleanExpression::Or(
+                    box BooleanExpression::Not(box condition.clone()),
+                    box e,
+                )));
+            }
+            TypedStatement::MultipleDefinition(variables, e) => {
+                match variables.iter().all(|v| locals.contains(&v.id.id)) {
+                    true => res.push(TypedStatement::MultipleDefinition(variables, e)),
+                    false => {
+                        // the values are returned in temporary variables, which are then assigned as definitions
+                        let temporaries: Vec<_> = variables
+                            .iter()
+                            .enumerate()
+                            .map(|(i, v)| {
+                                Variable::with_id_and_type(
+                                    CoreIdentifier::Internal("MASKED", i),
+                                    v.get_type(),
+                                )
+                            })
+                            .collect();
+
+                        res.extend(temporaries.iter().cloned().map(TypedStatement::Declaration));
+                        res.push(TypedStatement::MultipleDefinition(temporaries.clone(), e));
+
+                        let definitions = variables
+                            .into_iter()
+                            .zip(temporaries.iter())
+                            .map(|(v, t)| {
+                                TypedStatement::Definition(
+                                    TypedAssignee::Identifier(v),
+                                    identifier(t),
+                                )
+                            })
+                            .collect();
+                        res.extend(mask_statements(condition, depth, locals, definitions));
+                    }
+                }
+            }
+            TypedStatement::For(v, from, to, None, statements) => {
+                locals.insert(v.id.id.clone());
+                let statements = mask_statements(condition, depth, locals, statements);
+                res.push(TypedStatement::For(v, from, to, None, statements));
+            }
+            // the nested loop is running only if this one is, so that its statements are not masked again
+            TypedStatement::For(v, from, to, Some(max), statements) => res.extend(bounded_loop(
+                Some(condition.clone()),
+                depth + 1,
+                v,
+                from,
+                to,
+                max,
+                statements,
+            )),
+            s => res.push(s),
+        }
+    }
+
+    res
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for Unroller<'ast, T> {
     fn fold_statement(&mut self, s: TypedStatement<'ast, T>) -> Vec<TypedStatement<'ast, T>> {
         self.statement_count += 1;
+        if !self.check_budget(0) {
+            return vec![];
+        }
         match s {
             TypedStatement::Declaration(_) => vec![],
             TypedStatement::Definition(assignee, expr) => {
@@ -389,31 +663,7 @@ impl<'ast, T: Field> Folder<'ast, T> for Unroller<'ast> {
 
                 let (variable, indices) = linear(assignee);
 
-                let base = match variable.get_type() {
-                    Type::FieldElement => {
-                        FieldElementExpression::Identifier(variable.id.clone().into()).into()
-                    }
-                    Type::Boolean => {
-                        BooleanExpression::Identifier(variable.id.clone().into()).into()
-                    }
-                    Type::Uint(bitwidth) => {
-                        UExpressionInner::Identifier(variable.id.clone().into())
-                            .annotate(bitwidth)
-                            .into()
-                    }
-                    Type::Array(array_type) => {
-                        ArrayExpressionInner::Identifier(variable.id.clone().into())
-                            .annotate(*array_type.ty, array_type.size)
-                            .into()
-                    }
-                    Type::Struct(members) => {
-                        StructExpressionInner::Identifier(variable.id.clone().into())
-                            .annotate(members)
-                            .into()
-                    }
-                };
-
-                let base = self.fold_expression(base);
+                let base = self.fold_expression(identifier(&variable));
 
                 let indices = indices
                     .into_iter()
@@ -443,7 +693,13 @@ impl<'ast, T: Field> Folder<'ast, T> for Unroller<'ast> {
 
                 vec![TypedStatement::MultipleDefinition(variables, exprs)]
             }
-            TypedStatement::For(v, from, to, stats) => {
+            TypedStatement::For(v, from, to, Some(max), stats) => {
+                bounded_loop(None, 0, v, from, to, max, stats)
+                    .into_iter()
+                    .flat_map(|s| self.fold_statement(s))
+                    .collect()
+            }
+            TypedStatement::For(v, from, to, None, stats) => {
                 let from = self.fold_field_expression(from);
                 let to = self.fold_field_expression(to);
 
@@ -452,35 +708,37 @@ impl<'ast, T: Field> Folder<'ast, T> for Unroller<'ast> {
                         let mut values: Vec<T> = vec![];
                         let mut current = from;
                         while current < to {
+                            if !self.check_budget(values.len()) {
+                                return vec![];
+                            }
                             values.push(current.clone());
                             current = T::one() + &current;
                         }
 
-                        let res = values
-                            .into_iter()
-                            .map(|index| {
-                                vec![
-                                    vec![
-                                        TypedStatement::Declaration(v.clone()),
-                                        TypedStatement::Definition(
-                                            TypedAssignee::Identifier(v.clone()),
-                                            FieldElementExpression::Number(index).into(),
-                                        ),
-                                    ],
-                                    stats.clone(),
-                                ]
-                                .into_iter()
-                                .flat_map(|x| x)
-                            })
-                            .flat_map(|x| x)
-                            .flat_map(|x| self.fold_statement(x))
-                            .collect();
+                        let mut res = vec![];
+
+                        for index in values {
+                            res.extend(self.fold_statement(TypedStatement::Declaration(v.clone())));
+                            res.extend(self.fold_statement(TypedStatement::Definition(
+                                TypedAssignee::Identifier(v.clone()),
+                                FieldElementExpression::Number(index.clone()).into(),
+                            )));
+
+                            // the loop variable is constant in this iteration, so that expressions such as
+                            // `i * 32 + j` can be folded while unrolling, see `fold_field_expression`
+                            let id = self.fold_name(v.id.clone());
+                            self.induction_variables.insert(id, index);
+
+                            for s in stats.clone() {
+                                res.extend(self.fold_statement(s));
+                            }
+                        }
 
                         res
                     }
                     (from, to) => {
                         self.complete = false;
-                        vec![TypedStatement::For(v, from, to, stats)]
+                        vec![TypedStatement::For(v, from, to, None, stats)]
                     }
                 }
             }
@@ -490,6 +748,7 @@ impl<'ast, T: Field> Folder<'ast, T> for Unroller<'ast> {
 
     fn fold_function(&mut self, f: TypedFunction<'ast, T>) -> TypedFunction<'ast, T> {
         self.substitution = HashMap::new();
+        self.induction_variables = HashMap::new();
         for arg in &f.arguments {
             self.substitution.insert(arg.id.id.id.clone(), 0);
         }
@@ -503,6 +762,31 @@ impl<'ast, T: Field> Folder<'ast, T> for Unroller<'ast> {
             ..n
         }
     }
+
+    fn fold_field_expression(
+        &mut self,
+        e: FieldElementExpression<'ast, T>,
+    ) -> FieldElementExpression<'ast, T> {
+        match fold_field_expression(self, e) {
+            FieldElementExpression::Identifier(id) => match self.induction_variables.get(&id) {
+                Some(value) => FieldElementExpression::Number(value.clone()),
+                None => FieldElementExpression::Identifier(id),
+            },
+            FieldElementExpression::Add(
+                box FieldElementExpression::Number(n1),
+                box FieldElementExpression::Number(n2),
+            ) => FieldElementExpression::Number(n1 + n2),
+            FieldElementExpression::Sub(
+                box FieldElementExpression::Number(n1),
+                box FieldElementExpression::Number(n2),
+            ) => FieldElementExpression::Number(n1 - n2),
+            FieldElementExpression::Mult(
+                box FieldElementExpression::Number(n1),
+                box FieldElementExpression::Number(n2),
+            ) => FieldElementExpression::Number(n1 * n2),
+            e => e,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -762,16 +1046,17 @@ mod tests {
 
             // should be unrolled to
             // i_0 = 2
-            // foo_0 = i_0
+            // foo_0 = 2
             // i_1 = 3
-            // foo_1 = i_1
+            // foo_1 = 3
             // i_2 = 4
-            // foo_2 = i_2
+            // foo_2 = 4
 
             let s = TypedStatement::For(
                 Variable::field_element("i"),
                 FieldElementExpression::Number(Bn128Field::from(2)),
                 FieldElementExpression::Number(Bn128Field::from(5)),
+                None,
                 vec![
                     TypedStatement::Declaration(Variable::field_element("foo")),
                     TypedStatement::Definition(
@@ -792,7 +1077,7 @@ mod tests {
                     TypedAssignee::Identifier(Variable::field_element(
                         Identifier::from("foo").version(0),
                     )),
-                    FieldElementExpression::Identifier(Identifier::from("i").version(0)).into(),
+                    FieldElementExpression::Number(Bn128Field::from(2)).into(),
                 ),
                 TypedStatement::Definition(
                     TypedAssignee::Identifier(Variable::field_element(
@@ -804,7 +1089,7 @@ mod tests {
                     TypedAssignee::Identifier(Variable::field_element(
                         Identifier::from("foo").version(1),
                     )),
-                    FieldElementExpression::Identifier(Identifier::from("i").version(1)).into(),
+                    FieldElementExpression::Number(Bn128Field::from(3)).into(),
                 ),
                 TypedStatement::Definition(
                     TypedAssignee::Identifier(Variable::field_element(
@@ -816,7 +1101,7 @@ mod tests {
                     TypedAssignee::Identifier(Variable::field_element(
                         Identifier::from("foo").version(2),
                     )),
-                    FieldElementExpression::Identifier(Identifier::from("i").version(2)).into(),
+                    FieldElementExpression::Number(Bn128Field::from(4)).into(),
                 ),
             ];
 
@@ -825,6 +1110,216 @@ mod tests {
             assert_eq!(u.fold_statement(s), expected);
         }
 
+        #[test]
+        fn induction_variables() {
+            // for field i in 0..2
+            //		for field j in 0..2
+            //			field foo = i * 2 + j
+
+            // should be unrolled to
+            // i_0 = 0
+            // j_0 = 0
+            // foo_0 = 0
+            // j_1 = 1
+            // foo_1 = 1
+            // i_1 = 1
+            // j_2 = 0
+            // foo_2 = 2
+            // j_3 = 1
+            // foo_3 = 3
+
+            let s = TypedStatement::For(
+                Variable::field_element("i"),
+                FieldElementExpression::Number(Bn128Field::from(0)),
+                FieldElementExpression::Number(Bn128Field::from(2)),
+                None,
+                vec![TypedStatement::For(
+                    Variable::field_element("j"),
+                    FieldElementExpression::Number(Bn128Field::from(0)),
+                    FieldElementExpression::Number(Bn128Field::from(2)),
+                    None,
+                    vec![
+                        TypedStatement::Declaration(Variable::field_element("foo")),
+                        TypedStatement::Definition(
+                            TypedAssignee::Identifier(Variable::field_element("foo")),
+                            FieldElementExpression::Add(
+                                box FieldElementExpression::Mult(
+                                    box FieldElementExpression::Identifier("i".into()),
+                                    box FieldElementExpression::Number(Bn128Field::from(2)),
+                                ),
+                                box FieldElementExpression::Identifier("j".into()),
+                            )
+                            .into(),
+                        ),
+                    ],
+                )],
+            );
+
+            let definition = |name: &'static str, version: usize, value: usize| {
+                TypedStatement::Definition(
+                    TypedAssignee::Identifier(Variable::field_element(
+                        Identifier::from(name).version(version),
+                    )),
+                    FieldElementExpression::Number(Bn128Field::from(value)).into(),
+                )
+            };
+
+            let expected = vec![
+                definition("i", 0, 0),
+                definition("j", 0, 0),
+                definition("foo", 0, 0),
+                definition("j", 1, 1),
+                definition("foo", 1, 1),
+                definition("i", 1, 1),
+                definition("j", 2, 0),
+                definition("foo", 2, 2),
+                definition("j", 3, 1),
+                definition("foo", 3, 3),
+            ];
+
+            let mut u = Unroller::new();
+
+            assert_eq!(u.fold_statement(s), expected);
+        }
+
+        #[test]
+        fn bounded_loop() {
+            // field a = 0
+            // for field i in 0..n max 2
+            //		a = a + i
+            //		assert(a == 1)
+
+            // should be unrolled to
+            // a_0 = 0
+            // bound_0 = n
+            // running_0 = true
+            // i_0 = 0
+            // running_1 = running_0 && !(0 == bound_0)
+            // a_1 = if running_1 then a_0 + 0 else a_0
+            // assert(!running_1 || a_1 == 1)
+            // i_1 = 1
+            // running_2 = running_1 && !(1 == bound_0)
+            // a_2 = if running_2 then a_1 + 1 else a_1
+            // assert(!running_2 || a_2 == 1)
+            // assert(!running_2 || bound_0 == 2)
+
+            let a = Variable::field_element("a");
+
+            let statements = vec![
+                TypedStatement::Definition(
+                    TypedAssignee::Identifier(a.clone()),
+                    FieldElementExpression::Number(Bn128Field::from(0)).into(),
+                ),
+                TypedStatement::For(
+                    Variable::field_element("i"),
+                    FieldElementExpression::Number(Bn128Field::from(0)),
+                    FieldElementExpression::Identifier("n".into()),
+                    Some(FieldElementExpression::Number(Bn128Field::from(2))),
+                    vec![
+                        TypedStatement::Definition(
+                            TypedAssignee::Identifier(a.clone()),
+                            FieldElementExpression::Add(
+                                box FieldElementExpression::Identifier("a".into()),
+                                box FieldElementExpression::Identifier("i".into()),
+                            )
+                            .into(),
+                        ),
+                        TypedStatement::Assertion(BooleanExpression::FieldEq(
+                            box FieldElementExpression::Identifier("a".into()),
+                            box FieldElementExpression::Number(Bn128Field::from(1)),
+                        )),
+                    ],
+                ),
+            ];
+
+            let id = |id: CoreIdentifier<'static>, version| Identifier::from(id).version(version);
+            let a = |version| id(CoreIdentifier::Source("a"), version);
+            let bound = id(CoreIdentifier::Internal("BOUND", 0), 0);
+            let running = |version| id(CoreIdentifier::Internal("RUNNING", 0), version);
+            let number = |n: usize| FieldElementExpression::Number(Bn128Field::from(n));
+
+            let iteration = |index: usize| {
+                vec![
+                    TypedStatement::Definition(
+                        TypedAssignee::Identifier(Variable::field_element(
+                            Identifier::from("i").version(index),
+                        )),
+                        number(index).into(),
+                    ),
+                    TypedStatement::Definition(
+                        TypedAssignee::Identifier(Variable::boolean(running(index + 1))),
+                        BooleanExpression::And(
+                            box BooleanExpression::Identifier(running(index)),
+                            box BooleanExpression::Not(box BooleanExpression::FieldEq(
+                                box number(index),
+                                box FieldElementExpression::Identifier(bound.clone()),
+                            )),
+                        )
+                        .into(),
+                    ),
+                    TypedStatement::Definition(
+                        TypedAssignee::Identifier(Variable::field_element(a(index + 1))),
+                        FieldElementExpression::if_else(
+                            BooleanExpression::Identifier(running(index + 1)),
+                            FieldElementExpression::Add(
+                                box FieldElementExpression::Identifier(a(index)),
+                                box number(index),
+                            ),
+                            FieldElementExpression::Identifier(a(index)),
+                        )
+                        .into(),
+                    ),
+                    TypedStatement::Assertion(BooleanExpression::Or(
+                        box BooleanExpression::Not(box BooleanExpression::Identifier(running(
+                            index + 1,
+                        ))),
+                        box BooleanExpression::FieldEq(
+                            box FieldElementExpression::Identifier(a(index + 1)),
+                            box number(1),
+                        ),
+                    )),
+                ]
+            };
+
+            let expected: Vec<_> = vec![
+                TypedStatement::Definition(
+                    TypedAssignee::Identifier(Variable::field_element(a(0))),
+                    number(0).into(),
+                ),
+                TypedStatement::Definition(
+                    TypedAssignee::Identifier(Variable::field_element(bound.clone())),
+                    FieldElementExpression::Identifier("n".into()).into(),
+                ),
+                TypedStatement::Definition(
+                    TypedAssignee::Identifier(Variable::boolean(running(0))),
+                    BooleanExpression::Value(true).into(),
+                ),
+            ]
+            .into_iter()
+            .chain(iteration(0))
+            .chain(iteration(1))
+            .chain(std::iter::once(TypedStatement::Assertion(
+                BooleanExpression::Or(
+                    box BooleanExpression::Not(box BooleanExpression::Identifier(running(2))),
+                    box BooleanExpression::FieldEq(
+                        box FieldElementExpression::Identifier(bound.clone()),
+                        box number(2),
+                    ),
+                ),
+            )))
+            .collect();
+
+            let mut u = Unroller::new();
+
+            assert_eq!(
+                statements
+                    .into_iter()
+                    .flat_map(|s| u.fold_statement(s))
+                    .collect::<Vec<_>>(),
+                expected
+            );
+        }
+
         #[test]
         fn idempotence() {
             // an already unrolled program should not be modified by unrolling again
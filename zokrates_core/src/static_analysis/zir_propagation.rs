@@ -0,0 +1,550 @@
+//! Module containing constant propagation for the zir AST
+//!
+//! The typed AST is propagated before its complex types are removed, which hides constants behind array and struct
+//! accesses: `[1, 2][0] + 3` only becomes `1 + 3` once the array is flattened into its elements. Propagating these
+//! constants before the range analysis gives it the exact value of constant uint expressions rather than a bound on
+//! their operands, so that fewer reductions are required.
+
+use crate::zir::folder::*;
+use crate::zir::types::UBitwidth;
+use crate::zir::*;
+use std::collections::HashMap;
+use zokrates_field::Field;
+
+pub struct ZirPropagator<'ast, T: Field> {
+    // the constant expressions assigned to variables of the current function
+    constants: HashMap<ZirAssignee<'ast>, ZirExpression<'ast, T>>,
+}
+
+impl<'ast, T: Field> ZirPropagator<'ast, T> {
+    pub fn new() -> Self {
+        ZirPropagator {
+            constants: HashMap::new(),
+        }
+    }
+
+    pub fn propagate(p: ZirProgram<'ast, T>) -> ZirProgram<'ast, T> {
+        ZirPropagator::new().fold_program(p)
+    }
+}
+
+fn is_constant<'ast, T: Field>(e: &ZirExpression<'ast, T>) -> bool {
+    match e {
+        ZirExpression::FieldElement(FieldElementExpression::Number(..)) => true,
+        ZirExpression::Boolean(BooleanExpression::Value(..)) => true,
+        ZirExpression::Uint(e) => match e.as_inner() {
+            UExpressionInner::Value(..) => true,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Returns the mask of the values of `bitwidth` bits, to which arithmetic on constants wraps around
+fn mask(bitwidth: UBitwidth) -> u128 {
+    u128::max_value() >> (128 - bitwidth.to_usize())
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for ZirPropagator<'ast, T> {
+    fn fold_function(&mut self, f: ZirFunction<'ast, T>) -> ZirFunction<'ast, T> {
+        self.constants = HashMap::new();
+        fold_function(self, f)
+    }
+
+    fn fold_statement(&mut self, s: ZirStatement<'ast, T>) -> Vec<ZirStatement<'ast, T>> {
+        match s {
+            // propagation to the defined variable if rhs is a constant
+            ZirStatement::Definition(a, e) => {
+                let e = self.fold_expression(e);
+
+                if is_constant(&e) {
+                    self.constants.insert(a, e);
+                    vec![]
+                } else {
+                    self.constants.remove(&a);
+                    vec![ZirStatement::Definition(a, e)]
+                }
+            }
+            // assertions which always hold do not need to be checked
+            ZirStatement::Assertion(e) => match self.fold_boolean_expression(e) {
+                BooleanExpression::Value(true) => vec![],
+                e => vec![ZirStatement::Assertion(e)],
+            },
+            s => fold_statement(self, s),
+        }
+    }
+
+    fn fold_field_expression(
+        &mut self,
+        e: FieldElementExpression<'ast, T>,
+    ) -> FieldElementExpression<'ast, T> {
+        match e {
+            FieldElementExpression::Identifier(id) => {
+                match self.constants.get(&Variable::field_element(id.clone())) {
+                    Some(ZirExpression::FieldElement(e)) => e.clone(),
+                    Some(_) => unreachable!(
+                        "constant stored for a field element should be a field element"
+                    ),
+                    None => FieldElementExpression::Identifier(id),
+                }
+            }
+            FieldElementExpression::Add(box e1, box e2) => match (
+                self.fold_field_expression(e1),
+                self.fold_field_expression(e2),
+            ) {
+                (FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
+                    FieldElementExpression::Number(n1 + n2)
+                }
+                (e1, e2) => FieldElementExpression::Add(box e1, box e2),
+            },
+            FieldElementExpression::Sub(box e1, box e2) => match (
+                self.fold_field_expression(e1),
+                self.fold_field_expression(e2),
+            ) {
+                (FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
+                    FieldElementExpression::Number(n1 - n2)
+                }
+                (e1, e2) => FieldElementExpression::Sub(box e1, box e2),
+            },
+            FieldElementExpression::Mult(box e1, box e2) => match (
+                self.fold_field_expression(e1),
+                self.fold_field_expression(e2),
+            ) {
+                (FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
+                    FieldElementExpression::Number(n1 * n2)
+                }
+                (e1, e2) => FieldElementExpression::Mult(box e1, box e2),
+            },
+            FieldElementExpression::Div(box e1, box e2) => match (
+                self.fold_field_expression(e1),
+                self.fold_field_expression(e2),
+            ) {
+                // a division by zero is left to fail when the program runs
+                (
+                    FieldElementExpression::Number(ref n1),
+                    FieldElementExpression::Number(ref n2),
+                ) if *n2 != T::from(0) => FieldElementExpression::Number(n1.clone() / n2),
+                (e1, e2) => FieldElementExpression::Div(box e1, box e2),
+            },
+            FieldElementExpression::Pow(box e1, box e2) => match (
+                self.fold_field_expression(e1),
+                self.fold_field_expression(e2),
+            ) {
+                (_, FieldElementExpression::Number(ref n2)) if *n2 == T::from(0) => {
+                    FieldElementExpression::Number(T::from(1))
+                }
+                (FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
+                    FieldElementExpression::Number(n1.pow(n2))
+                }
+                (e1, e2) => FieldElementExpression::Pow(box e1, box e2),
+            },
+            FieldElementExpression::IfElse(box condition, box consequence, box alternative) => {
+                let consequence = self.fold_field_expression(consequence);
+                let alternative = self.fold_field_expression(alternative);
+                match self.fold_boolean_expression(condition) {
+                    BooleanExpression::Value(true) => consequence,
+                    BooleanExpression::Value(false) => alternative,
+                    c => FieldElementExpression::IfElse(box c, box consequence, box alternative),
+                }
+            }
+            e => fold_field_expression(self, e),
+        }
+    }
+
+    fn fold_boolean_expression(
+        &mut self,
+        e: BooleanExpression<'ast, T>,
+    ) -> BooleanExpression<'ast, T> {
+        // as in the propagation of the typed AST, we only compare constants, see `Propagator::fold_boolean_expression`
+        match e {
+            BooleanExpression::Identifier(id) => {
+                match self.constants.get(&Variable::boolean(id.clone())) {
+                    Some(ZirExpression::Boolean(e)) => e.clone(),
+                    Some(_) => unreachable!("constant stored for a boolean should be a boolean"),
+                    None => BooleanExpression::Identifier(id),
+                }
+            }
+            BooleanExpression::FieldEq(box e1, box e2) => match (
+                self.fold_field_expression(e1),
+                self.fold_field_expression(e2),
+            ) {
+                (FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
+                    BooleanExpression::Value(n1 == n2)
+                }
+                (e1, e2) => BooleanExpression::FieldEq(box e1, box e2),
+            },
+            BooleanExpression::UintEq(box e1, box e2) => {
+                let e1 = self.fold_uint_expression(e1);
+                let e2 = self.fold_uint_expression(e2);
+
+                match (e1.as_inner(), e2.as_inner()) {
+                    (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                        BooleanExpression::Value(v1 == v2)
+                    }
+                    _ => BooleanExpression::UintEq(box e1, box e2),
+                }
+            }
+            BooleanExpression::BoolEq(box e1, box e2) => match (
+                self.fold_boolean_expression(e1),
+                self.fold_boolean_expression(e2),
+            ) {
+                (BooleanExpression::Value(v1), BooleanExpression::Value(v2)) => {
+                    BooleanExpression::Value(v1 == v2)
+                }
+                (e1, e2) => BooleanExpression::BoolEq(box e1, box e2),
+            },
+            BooleanExpression::Lt(box e1, box e2) => match (
+                self.fold_field_expression(e1),
+                self.fold_field_expression(e2),
+            ) {
+                (FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
+                    BooleanExpression::Value(n1 < n2)
+                }
+                (e1, e2) => BooleanExpression::Lt(box e1, box e2),
+            },
+            BooleanExpression::Le(box e1, box e2) => match (
+                self.fold_field_expression(e1),
+                self.fold_field_expression(e2),
+            ) {
+                (FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
+                    BooleanExpression::Value(n1 <= n2)
+                }
+                (e1, e2) => BooleanExpression::Le(box e1, box e2),
+            },
+            BooleanExpression::Gt(box e1, box e2) => match (
+                self.fold_field_expression(e1),
+                self.fold_field_expression(e2),
+            ) {
+                (FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
+                    BooleanExpression::Value(n1 > n2)
+                }
+                (e1, e2) => BooleanExpression::Gt(box e1, box e2),
+            },
+            BooleanExpression::Ge(box e1, box e2) => match (
+                self.fold_field_expression(e1),
+                self.fold_field_expression(e2),
+            ) {
+                (FieldElementExpression::Number(n1), FieldElementExpression::Number(n2)) => {
+                    BooleanExpression::Value(n1 >= n2)
+                }
+                (e1, e2) => BooleanExpression::Ge(box e1, box e2),
+            },
+            BooleanExpression::Or(box e1, box e2) => match (
+                self.fold_boolean_expression(e1),
+                self.fold_boolean_expression(e2),
+            ) {
+                // x || true == true
+                (_, BooleanExpression::Value(true)) | (BooleanExpression::Value(true), _) => {
+                    BooleanExpression::Value(true)
+                }
+                // x || false == x
+                (e, BooleanExpression::Value(false)) | (BooleanExpression::Value(false), e) => e,
+                (e1, e2) => BooleanExpression::Or(box e1, box e2),
+            },
+            BooleanExpression::And(box e1, box e2) => match (
+                self.fold_boolean_expression(e1),
+                self.fold_boolean_expression(e2),
+            ) {
+                // x && false == false
+                (_, BooleanExpression::Value(false)) | (BooleanExpression::Value(false), _) => {
+                    BooleanExpression::Value(false)
+                }
+                // x && true == x
+                (e, BooleanExpression::Value(true)) | (BooleanExpression::Value(true), e) => e,
+                (e1, e2) => BooleanExpression::And(box e1, box e2),
+            },
+            BooleanExpression::Not(box e) => match self.fold_boolean_expression(e) {
+                BooleanExpression::Value(v) => BooleanExpression::Value(!v),
+                e => BooleanExpression::Not(box e),
+            },
+            BooleanExpression::IfElse(box condition, box consequence, box alternative) => {
+                let consequence = self.fold_boolean_expression(consequence);
+                let alternative = self.fold_boolean_expression(alternative);
+                match self.fold_boolean_expression(condition) {
+                    BooleanExpression::Value(true) => consequence,
+                    BooleanExpression::Value(false) => alternative,
+                    c => BooleanExpression::IfElse(box c, box consequence, box alternative),
+                }
+            }
+            e => fold_boolean_expression(self, e),
+        }
+    }
+
+    fn fold_uint_expression_inner(
+        &mut self,
+        bitwidth: UBitwidth,
+        e: UExpressionInner<'ast, T>,
+    ) -> UExpressionInner<'ast, T> {
+        match e {
+            UExpressionInner::Identifier(id) => {
+                match self.constants.get(&Variable::uint(id.clone(), bitwidth)) {
+                    Some(ZirExpression::Uint(e)) => e.as_inner().clone(),
+                    Some(_) => unreachable!("constant stored for a uint should be a uint"),
+                    None => UExpressionInner::Identifier(id),
+                }
+            }
+            UExpressionInner::Add(box e1, box e2) => match (
+                self.fold_uint_expression(e1).into_inner(),
+                self.fold_uint_expression(e2).into_inner(),
+            ) {
+                (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                    UExpressionInner::Value(v1.wrapping_add(v2) & mask(bitwidth))
+                }
+                (e, UExpressionInner::Value(0)) | (UExpressionInner::Value(0), e) => e,
+                (e1, e2) => {
+                    UExpressionInner::Add(box e1.annotate(bitwidth), box e2.annotate(bitwidth))
+                }
+            },
+            UExpressionInner::Sub(box e1, box e2) => match (
+                self.fold_uint_expression(e1).into_inner(),
+                self.fold_uint_expression(e2).into_inner(),
+            ) {
+                (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                    UExpressionInner::Value(v1.wrapping_sub(v2) & mask(bitwidth))
+                }
+                (e, UExpressionInner::Value(0)) => e,
+                (e1, e2) => {
+                    UExpressionInner::Sub(box e1.annotate(bitwidth), box e2.annotate(bitwidth))
+                }
+            },
+            UExpressionInner::Mult(box e1, box e2) => match (
+                self.fold_uint_expression(e1).into_inner(),
+                self.fold_uint_expression(e2).into_inner(),
+            ) {
+                (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                    UExpressionInner::Value(v1.wrapping_mul(v2) & mask(bitwidth))
+                }
+                (_, UExpressionInner::Value(0)) | (UExpressionInner::Value(0), _) => {
+                    UExpressionInner::Value(0)
+                }
+                (e, UExpressionInner::Value(1)) | (UExpressionInner::Value(1), e) => e,
+                (e1, e2) => {
+                    UExpressionInner::Mult(box e1.annotate(bitwidth), box e2.annotate(bitwidth))
+                }
+            },
+            // division by zero is left to the compiled program, which cannot satisfy it
+            UExpressionInner::Div(box e1, box e2) => match (
+                self.fold_uint_expression(e1).into_inner(),
+                self.fold_uint_expression(e2).into_inner(),
+            ) {
+                (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) if v2 != 0 => {
+                    UExpressionInner::Value(v1 / v2)
+                }
+                (e, UExpressionInner::Value(1)) => e,
+                (e1, e2) => {
+                    UExpressionInner::Div(box e1.annotate(bitwidth), box e2.annotate(bitwidth))
+                }
+            },
+            UExpressionInner::Rem(box e1, box e2) => match (
+                self.fold_uint_expression(e1).into_inner(),
+                self.fold_uint_expression(e2).into_inner(),
+            ) {
+                (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) if v2 != 0 => {
+                    UExpressionInner::Value(v1 % v2)
+                }
+                (_, UExpressionInner::Value(1)) => UExpressionInner::Value(0),
+                (e1, e2) => {
+                    UExpressionInner::Rem(box e1.annotate(bitwidth), box e2.annotate(bitwidth))
+                }
+            },
+            UExpressionInner::Xor(box e1, box e2) => match (
+                self.fold_uint_expression(e1).into_inner(),
+                self.fold_uint_expression(e2).into_inner(),
+            ) {
+                (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                    UExpressionInner::Value(v1 ^ v2)
+                }
+                (e, UExpressionInner::Value(0)) | (UExpressionInner::Value(0), e) => e,
+                (e1, e2) => {
+                    UExpressionInner::Xor(box e1.annotate(bitwidth), box e2.annotate(bitwidth))
+                }
+            },
+            UExpressionInner::And(box e1, box e2) => match (
+                self.fold_uint_expression(e1).into_inner(),
+                self.fold_uint_expression(e2).into_inner(),
+            ) {
+                (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                    UExpressionInner::Value(v1 & v2)
+                }
+                (_, UExpressionInner::Value(0)) | (UExpressionInner::Value(0), _) => {
+                    UExpressionInner::Value(0)
+                }
+                (e1, e2) => {
+                    UExpressionInner::And(box e1.annotate(bitwidth), box e2.annotate(bitwidth))
+                }
+            },
+            UExpressionInner::Or(box e1, box e2) => match (
+                self.fold_uint_expression(e1).into_inner(),
+                self.fold_uint_expression(e2).into_inner(),
+            ) {
+                (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                    UExpressionInner::Value(v1 | v2)
+                }
+                (e, UExpressionInner::Value(0)) | (UExpressionInner::Value(0), e) => e,
+                (e1, e2) => {
+                    UExpressionInner::Or(box e1.annotate(bitwidth), box e2.annotate(bitwidth))
+                }
+            },
+            UExpressionInner::Not(box e) => match self.fold_uint_expression(e).into_inner() {
+                UExpressionInner::Value(v) => UExpressionInner::Value(!v & mask(bitwidth)),
+                e => UExpressionInner::Not(box e.annotate(bitwidth)),
+            },
+            UExpressionInner::LeftShift(box e, box by) => {
+                let e = self.fold_uint_expression(e);
+                let by = self.fold_field_expression(by);
+                match (e.into_inner(), by) {
+                    (UExpressionInner::Value(v), FieldElementExpression::Number(by)) => {
+                        let by_as_usize = by.to_dec_string().parse::<usize>().unwrap();
+                        UExpressionInner::Value(
+                            v.checked_shl(by_as_usize as u32).unwrap_or(0) & mask(bitwidth),
+                        )
+                    }
+                    (e, by) => UExpressionInner::LeftShift(box e.annotate(bitwidth), box by),
+                }
+            }
+            UExpressionInner::RightShift(box e, box by) => {
+                let e = self.fold_uint_expression(e);
+                let by = self.fold_field_expression(by);
+                match (e.into_inner(), by) {
+                    (UExpressionInner::Value(v), FieldElementExpression::Number(by)) => {
+                        let by_as_usize = by.to_dec_string().parse::<usize>().unwrap();
+                        UExpressionInner::Value(v.checked_shr(by_as_usize as u32).unwrap_or(0))
+                    }
+                    (e, by) => UExpressionInner::RightShift(box e.annotate(bitwidth), box by),
+                }
+            }
+            UExpressionInner::IfElse(box condition, box consequence, box alternative) => {
+                let consequence = self.fold_uint_expression(consequence);
+                let alternative = self.fold_uint_expression(alternative);
+                match self.fold_boolean_expression(condition) {
+                    BooleanExpression::Value(true) => consequence.into_inner(),
+                    BooleanExpression::Value(false) => alternative.into_inner(),
+                    c => UExpressionInner::IfElse(box c, box consequence, box alternative),
+                }
+            }
+            e => fold_uint_expression_inner(self, bitwidth, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zir::types::Signature;
+    use zokrates_field::Bn128Field;
+
+    fn value<'ast>(v: u128, bitwidth: usize) -> UExpression<'ast, Bn128Field> {
+        UExpressionInner::Value(v).annotate(bitwidth)
+    }
+
+    #[test]
+    fn uint_wraps_around() {
+        let mut propagator = ZirPropagator::new();
+
+        assert_eq!(
+            propagator.fold_uint_expression(UExpression::add(value(250, 8), value(10, 8))),
+            value(4, 8)
+        );
+        assert_eq!(
+            propagator.fold_uint_expression(UExpression::sub(value(3, 8), value(5, 8))),
+            value(254, 8)
+        );
+        assert_eq!(
+            propagator.fold_uint_expression(UExpression::mult(value(16, 8), value(17, 8))),
+            value(16, 8)
+        );
+        assert_eq!(
+            propagator.fold_uint_expression(UExpression::not(value(0, 32))),
+            value(0xffffffff, 32)
+        );
+    }
+
+    #[test]
+    fn uint_keeps_operand_order() {
+        let a = || UExpressionInner::Identifier("a".into()).annotate(32);
+
+        // `0 - a` is not `a`
+        let e = UExpression::sub(value(0, 32), a());
+        assert_eq!(ZirPropagator::new().fold_uint_expression(e.clone()), e);
+
+        let e = UExpression::sub(a(), value(0, 32));
+        assert_eq!(ZirPropagator::new().fold_uint_expression(e), a());
+    }
+
+    #[test]
+    fn uint_division() {
+        let mut propagator = ZirPropagator::new();
+
+        assert_eq!(
+            propagator.fold_uint_expression(UExpression::div(value(200, 8), value(7, 8))),
+            value(28, 8)
+        );
+        assert_eq!(
+            propagator.fold_uint_expression(UExpression::rem(value(200, 8), value(7, 8))),
+            value(4, 8)
+        );
+
+        // the division by zero is kept, so that the compiled program fails
+        let e = UExpression::div(value(200, 8), value(0, 8));
+        assert_eq!(propagator.fold_uint_expression(e.clone()), e);
+    }
+
+    #[test]
+    fn function() {
+        // def main(u32 b) -> (u32):
+        //     u32 a = 5 + 3
+        //     field c = if 1 < 2 then 4 else 5 fi
+        //     assert(c == 4)
+        //     return a + b
+        //
+        // becomes
+        //
+        // def main(u32 b) -> (u32):
+        //     return 8 + b
+
+        let f: ZirFunction<Bn128Field> = ZirFunction {
+            arguments: vec![Parameter::private(Variable::uint("b".into(), 32))],
+            statements: vec![
+                ZirStatement::Definition(
+                    Variable::uint("a".into(), 32),
+                    UExpression::add(value(5, 32), value(3, 32)).into(),
+                ),
+                ZirStatement::Definition(
+                    Variable::field_element("c"),
+                    FieldElementExpression::IfElse(
+                        box BooleanExpression::Lt(
+                            box FieldElementExpression::Number(Bn128Field::from(1)),
+                            box FieldElementExpression::Number(Bn128Field::from(2)),
+                        ),
+                        box FieldElementExpression::Number(Bn128Field::from(4)),
+                        box FieldElementExpression::Number(Bn128Field::from(5)),
+                    )
+                    .into(),
+                ),
+                ZirStatement::Assertion(BooleanExpression::FieldEq(
+                    box FieldElementExpression::Identifier("c".into()),
+                    box FieldElementExpression::Number(Bn128Field::from(4)),
+                )),
+                ZirStatement::Return(vec![UExpression::add(
+                    UExpressionInner::Identifier("a".into()).annotate(32),
+                    UExpressionInner::Identifier("b".into()).annotate(32),
+                )
+                .into()]),
+            ],
+            signature: Signature::new()
+                .inputs(vec![Type::uint(32)])
+                .outputs(vec![Type::uint(32)]),
+        };
+
+        let expected = ZirFunction {
+            statements: vec![ZirStatement::Return(vec![UExpression::add(
+                value(8, 32),
+                UExpressionInner::Identifier("b".into()).annotate(32),
+            )
+            .into()])],
+            ..f.clone()
+        };
+
+        assert_eq!(ZirPropagator::new().fold_function(f), expected);
+    }
+}
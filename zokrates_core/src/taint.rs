@@ -0,0 +1,567 @@
+//! Module containing an information flow analysis of the typed AST
+//!
+//! Private inputs of `main` can be annotated with `// @secret <name>`, and functions which are allowed to reveal
+//! information about their inputs, such as hashes and commitments, with `// @declassify <function>`. The analysis
+//! then checks that no public output depends on a secret input, and that no assertion relates a public input to a
+//! secret input, except through a call to a declassified function.
+
+use crate::typed_absy::folder::*;
+use crate::typed_absy::types::FunctionKey;
+use crate::typed_absy::*;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use zokrates_field::Field;
+
+/// The parameters of a function a value depends on, by index
+type Labels = BTreeSet<usize>;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Annotations {
+    /// private inputs of `main` which must not leak
+    pub secrets: Vec<String>,
+    /// functions whose return values do not leak their arguments, as they are called
+    pub declassified: HashSet<String>,
+}
+
+/// Returns the `@secret` and `@declassify` annotations of a module
+pub fn annotations(source: &str) -> Annotations {
+    let annotation = Regex::new(r"^\s*//\s*@(secret|declassify)\s+(\w+)\s*$").unwrap();
+
+    let mut res = Annotations::default();
+
+    for captures in source.lines().filter_map(|line| annotation.captures(line)) {
+        match &captures[1] {
+            "secret" => res.secrets.push(captures[2].to_string()),
+            _ => {
+                res.declassified.insert(captures[2].to_string());
+            }
+        }
+    }
+
+    res
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// A secret is not an input of `main`
+    UnknownInput(String),
+    /// A secret is a public input of `main`
+    PublicInput(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::UnknownInput(ref name) => {
+                write!(f, "Secret `{}` is not an input of main", name)
+            }
+            Error::PublicInput(ref name) => write!(
+                f,
+                "Secret `{}` is a public input of main, hence not secret",
+                name
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Leak {
+    /// A public output of `main`, by index, depends on secret inputs
+    Output { index: usize, secrets: Vec<String> },
+    /// A public input of `main` is related to secret inputs by an assertion
+    Input { name: String, secrets: Vec<String> },
+}
+
+impl fmt::Display for Leak {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Leak::Output { index, secrets } => write!(
+                f,
+                "Public output {} depends on secret input {}",
+                index,
+                secrets.join(", ")
+            ),
+            Leak::Input { name, secrets } => write!(
+                f,
+                "Public input {} is related to secret input {} by an assertion",
+                name,
+                secrets.join(", ")
+            ),
+        }
+    }
+}
+
+/// Returns the leaks of the secret inputs of a program
+pub fn analyse<T: Field>(
+    program: &TypedProgram<T>,
+    annotations: &Annotations,
+) -> Result<Vec<Leak>, Error> {
+    let main = program.modules[&program.main]
+        .functions
+        .iter()
+        .find(|(key, _)| key.id == "main")
+        .map(|(_, symbol)| match symbol {
+            TypedFunctionSymbol::Here(main) => main,
+            _ => unreachable!("main should be a typed function locally"),
+        })
+        .unwrap();
+
+    let inputs: Vec<_> = main
+        .arguments
+        .iter()
+        .map(|p| (p.id.id.to_string(), p.private))
+        .collect();
+
+    let secrets = annotations
+        .secrets
+        .iter()
+        .map(
+            |secret| match inputs.iter().position(|(name, _)| name == secret) {
+                None => Err(Error::UnknownInput(secret.clone())),
+                Some(index) if !inputs[index].1 => Err(Error::PublicInput(secret.clone())),
+                Some(index) => Ok((index, secret.clone())),
+            },
+        )
+        .collect::<Result<BTreeMap<_, _>, _>>()?;
+
+    let secret_names = |labels: &Labels| -> Vec<String> {
+        labels
+            .iter()
+            .filter_map(|index| secrets.get(index).cloned())
+            .collect()
+    };
+
+    let mut analysis = Analysis {
+        program,
+        declassified: &annotations.declassified,
+        summaries: HashMap::new(),
+    };
+
+    let summary = analysis.function(&program.main, main);
+
+    let mut leaks = BTreeSet::new();
+
+    for (index, labels) in summary.outputs.iter().enumerate() {
+        let secrets = secret_names(labels);
+        if !main.private_outputs.contains(&index) && !secrets.is_empty() {
+            leaks.insert(Leak::Output { index, secrets });
+        }
+    }
+
+    for labels in &summary.assertions {
+        let secrets = secret_names(labels);
+        if secrets.is_empty() {
+            continue;
+        }
+        for index in labels.iter().filter(|index| !inputs[**index].1) {
+            leaks.insert(Leak::Input {
+                name: inputs[*index].0.clone(),
+                secrets: secrets.clone(),
+            });
+        }
+    }
+
+    Ok(leaks.into_iter().collect())
+}
+
+/// The dependencies of the return values and assertions of a function on its parameters
+#[derive(Debug, Clone, Default)]
+struct Summary {
+    outputs: Vec<Labels>,
+    assertions: BTreeSet<Labels>,
+}
+
+/// The state of the analysis of a function
+struct State<'ast> {
+    variables: HashMap<Identifier<'ast>, Labels>,
+    summary: Summary,
+}
+
+struct Analysis<'p, 'ast, T> {
+    program: &'p TypedProgram<'ast, T>,
+    declassified: &'p HashSet<String>,
+    summaries: HashMap<(TypedModuleId, FunctionKey<'ast>), Summary>,
+}
+
+impl<'p, 'ast, T: Field> Analysis<'p, 'ast, T> {
+    fn summary(&mut self, module: &TypedModuleId, key: &FunctionKey<'ast>) -> Summary {
+        if self.declassified.contains(key.id) {
+            return Summary {
+                outputs: vec![Labels::new(); key.signature.outputs.len()],
+                assertions: BTreeSet::new(),
+            };
+        }
+
+        let cache_key = (module.clone(), key.clone());
+
+        if let Some(summary) = self.summaries.get(&cache_key) {
+            return summary.clone();
+        }
+
+        let program = self.program;
+
+        let summary = match &program.modules[module].functions[key] {
+            TypedFunctionSymbol::Here(f) => self.function(module, f),
+            TypedFunctionSymbol::There(key, module) => self.summary(module, key),
+            // the outputs of embedded functions depend on all their inputs
+            TypedFunctionSymbol::Flat(_) => Summary {
+                outputs: vec![
+                    (0..key.signature.inputs.len()).collect();
+                    key.signature.outputs.len()
+                ],
+                assertions: BTreeSet::new(),
+            },
+        };
+
+        self.summaries.insert(cache_key, summary.clone());
+
+        summary
+    }
+
+    fn function(&mut self, module: &TypedModuleId, f: &TypedFunction<'ast, T>) -> Summary {
+        let mut state = State {
+            variables: f
+                .arguments
+                .iter()
+                .enumerate()
+                .map(|(index, p)| (p.id.id.clone(), std::iter::once(index).collect()))
+                .collect(),
+            summary: Summary::default(),
+        };
+
+        self.statements(module, &f.statements, &mut state);
+
+        state.summary
+    }
+
+    fn statements(
+        &mut self,
+        module: &TypedModuleId,
+        statements: &[TypedStatement<'ast, T>],
+        state: &mut State<'ast>,
+    ) {
+        for statement in statements {
+            match statement {
+                TypedStatement::Declaration(v) => {
+                    state.variables.insert(v.id.clone(), Labels::new());
+                }
+                TypedStatement::Definition(assignee, e) => {
+                    let mut labels = self.expression(module, e, state);
+                    match assignee {
+                        TypedAssignee::Identifier(v) => {
+                            state.variables.insert(v.id.clone(), labels);
+                        }
+                        // assigning part of a variable adds to the dependencies of the rest
+                        assignee => {
+                            let root = self.assignee(module, assignee, &mut labels, state);
+                            state.variables.entry(root).or_default().extend(labels);
+                        }
+                    }
+                }
+                TypedStatement::Assertion(e) => {
+                    let labels = self.expression(module, &e.clone().into(), state);
+                    state.summary.assertions.insert(labels);
+                }
+                TypedStatement::Return(expressions) => {
+                    let outputs = expressions
+                        .iter()
+                        .map(|e| self.expression(module, e, state))
+                        .collect();
+                    state.summary.outputs = outputs;
+                }
+                TypedStatement::For(v, from, to, max, body) => {
+                    let mut bounds = self.expression(module, &from.clone().into(), state);
+                    bounds.extend(self.expression(module, &to.clone().into(), state));
+                    if let Some(max) = max {
+                        bounds.extend(self.expression(module, &max.clone().into(), state));
+                    }
+
+                    // iterate until the dependencies carried from one iteration to the next are stable
+                    loop {
+                        let before = state.variables.clone();
+                        state.variables.insert(v.id.clone(), bounds.clone());
+                        self.statements(module, body, state);
+                        for (id, labels) in &before {
+                            state
+                                .variables
+                                .entry(id.clone())
+                                .or_default()
+                                .extend(labels.iter().cloned());
+                        }
+                        if state.variables == before {
+                            break;
+                        }
+                    }
+                }
+                TypedStatement::MultipleDefinition(variables, e) => {
+                    let mut dependencies = Dependencies::new(self, module, &state.variables);
+                    let outputs = match e {
+                        TypedExpressionList::FunctionCall(key, arguments, _) => {
+                            dependencies.call(key, arguments)
+                        }
+                    };
+                    state.summary.assertions.extend(dependencies.assertions);
+                    for (v, labels) in variables.iter().zip(outputs) {
+                        state.variables.insert(v.id.clone(), labels);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the variable an assignee is part of, adding the dependencies of its indices to `labels`
+    fn assignee(
+        &mut self,
+        module: &TypedModuleId,
+        assignee: &TypedAssignee<'ast, T>,
+        labels: &mut Labels,
+        state: &mut State<'ast>,
+    ) -> Identifier<'ast> {
+        match assignee {
+            TypedAssignee::Identifier(v) => v.id.clone(),
+            TypedAssignee::Select(a, index) => {
+                labels.extend(self.expression(module, &(**index).clone().into(), state));
+                self.assignee(module, a, labels, state)
+            }
+            TypedAssignee::Member(s, _) => self.assignee(module, s, labels, state),
+        }
+    }
+
+    /// Returns the dependencies of an expression, recording the assertions of the functions it calls
+    fn expression(
+        &mut self,
+        module: &TypedModuleId,
+        e: &TypedExpression<'ast, T>,
+        state: &mut State<'ast>,
+    ) -> Labels {
+        let mut dependencies = Dependencies::new(self, module, &state.variables);
+        dependencies.fold_expression(e.clone());
+        state.summary.assertions.extend(dependencies.assertions);
+        dependencies.labels
+    }
+}
+
+/// Collects the dependencies of an expression
+struct Dependencies<'a, 'p, 'ast, T> {
+    analysis: &'a mut Analysis<'p, 'ast, T>,
+    module: &'a TypedModuleId,
+    variables: &'a HashMap<Identifier<'ast>, Labels>,
+    labels: Labels,
+    /// the assertions of the called functions, in terms of the dependencies of their arguments
+    assertions: BTreeSet<Labels>,
+}
+
+impl<'a, 'p, 'ast, T: Field> Dependencies<'a, 'p, 'ast, T> {
+    fn new(
+        analysis: &'a mut Analysis<'p, 'ast, T>,
+        module: &'a TypedModuleId,
+        variables: &'a HashMap<Identifier<'ast>, Labels>,
+    ) -> Self {
+        Dependencies {
+            analysis,
+            module,
+            variables,
+            labels: Labels::new(),
+            assertions: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the dependencies of the return values of a call
+    fn call(
+        &mut self,
+        key: &FunctionKey<'ast>,
+        arguments: &[TypedExpression<'ast, T>],
+    ) -> Vec<Labels> {
+        let arguments: Vec<Labels> = arguments
+            .iter()
+            .map(|a| {
+                let labels = std::mem::replace(&mut self.labels, Labels::new());
+                self.fold_expression(a.clone());
+                std::mem::replace(&mut self.labels, labels)
+            })
+            .collect();
+
+        let summary = self.analysis.summary(self.module, key);
+
+        let substitute = |labels: &Labels| -> Labels {
+            labels
+                .iter()
+                .flat_map(|index| arguments[*index].iter().cloned())
+                .collect()
+        };
+
+        self.assertions
+            .extend(summary.assertions.iter().map(&substitute));
+
+        summary.outputs.iter().map(&substitute).collect()
+    }
+
+    fn single_output_call(
+        &mut self,
+        key: &FunctionKey<'ast>,
+        arguments: &[TypedExpression<'ast, T>],
+    ) {
+        let outputs = self.call(key, arguments);
+        self.labels.extend(outputs.into_iter().flatten());
+    }
+}
+
+impl<'a, 'p, 'ast, T: Field> Folder<'ast, T> for Dependencies<'a, 'p, 'ast, T> {
+    fn fold_name(&mut self, n: Identifier<'ast>) -> Identifier<'ast> {
+        if let Some(labels) = self.variables.get(&n) {
+            self.labels.extend(labels.iter().cloned());
+        }
+        n
+    }
+
+    fn fold_field_expression(
+        &mut self,
+        e: FieldElementExpression<'ast, T>,
+    ) -> FieldElementExpression<'ast, T> {
+        match e {
+            FieldElementExpression::FunctionCall(key, arguments) => {
+                self.single_output_call(&key, &arguments);
+                FieldElementExpression::FunctionCall(key, arguments)
+            }
+            e => fold_field_expression(self, e),
+        }
+    }
+
+    fn fold_boolean_expression(
+        &mut self,
+        e: BooleanExpression<'ast, T>,
+    ) -> BooleanExpression<'ast, T> {
+        match e {
+            BooleanExpression::FunctionCall(key, arguments) => {
+                self.single_output_call(&key, &arguments);
+                BooleanExpression::FunctionCall(key, arguments)
+            }
+            e => fold_boolean_expression(self, e),
+        }
+    }
+
+    fn fold_uint_expression_inner(
+        &mut self,
+        bitwidth: UBitwidth,
+        e: UExpressionInner<'ast, T>,
+    ) -> UExpressionInner<'ast, T> {
+        match e {
+            UExpressionInner::FunctionCall(key, arguments) => {
+                self.single_output_call(&key, &arguments);
+                UExpressionInner::FunctionCall(key, arguments)
+            }
+            e => fold_uint_expression_inner(self, bitwidth, e),
+        }
+    }
+
+    fn fold_array_expression_inner(
+        &mut self,
+        ty: &Type,
+        size: usize,
+        e: ArrayExpressionInner<'ast, T>,
+    ) -> ArrayExpressionInner<'ast, T> {
+        match e {
+            ArrayExpressionInner::FunctionCall(key, arguments) => {
+                self.single_output_call(&key, &arguments);
+                ArrayExpressionInner::FunctionCall(key, arguments)
+            }
+            e => fold_array_expression_inner(self, ty, size, e),
+        }
+    }
+
+    fn fold_struct_expression_inner(
+        &mut self,
+        ty: &StructType,
+        e: StructExpressionInner<'ast, T>,
+    ) -> StructExpressionInner<'ast, T> {
+        match e {
+            StructExpressionInner::FunctionCall(key, arguments) => {
+                self.single_output_call(&key, &arguments);
+                StructExpressionInner::FunctionCall(key, arguments)
+            }
+            e => fold_struct_expression_inner(self, ty, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use compile::{taint_analysis, CompileErrorInner};
+    use std::io;
+    use zokrates_field::Bn128Field;
+
+    fn leaks(source: &str) -> Vec<String> {
+        taint_analysis::<Bn128Field, io::Error>(source.to_string(), "./path/to/file".into(), None)
+            .unwrap()
+            .iter()
+            .map(|leak| leak.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn declassified_outputs() {
+        let source = r#"
+def hash(field a) -> field:
+	return a * a
+
+// @secret preimage
+// @declassify hash
+def main(private field preimage, field commitment, private field salt) -> (field, field):
+	assert(hash(preimage) == commitment)
+	return hash(preimage), preimage + salt
+"#;
+
+        assert_eq!(
+            leaks(source),
+            vec!["Public output 1 depends on secret input preimage"]
+        );
+    }
+
+    #[test]
+    fn assertions() {
+        // the assertion in `check` relates its arguments
+        let source = r#"
+def check(field a, field b) -> bool:
+	assert(a == b)
+	return true
+
+// @secret preimage
+def main(private field preimage, field commitment) -> (bool):
+	field[2] values = [0, 0]
+	for field i in 0..2 do
+		values[i] = preimage
+	endfor
+	return check(values[1], commitment)
+"#;
+
+        assert_eq!(
+            leaks(source),
+            vec!["Public input commitment is related to secret input preimage by an assertion"]
+        );
+    }
+
+    #[test]
+    fn public_secret() {
+        let source = r#"
+// @secret a
+def main(field a) -> (field):
+	return 1
+"#;
+
+        let errors = taint_analysis::<Bn128Field, io::Error>(
+            source.to_string(),
+            "./path/to/file".into(),
+            None,
+        )
+        .unwrap_err();
+
+        match errors.0[0].value() {
+            CompileErrorInner::TaintError(e) => {
+                assert_eq!(*e, Error::PublicInput(String::from("a")))
+            }
+            e => panic!("unexpected error {}", e),
+        }
+    }
+}
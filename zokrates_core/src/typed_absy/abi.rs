@@ -15,6 +15,8 @@ pub type AbiOutput = Type;
 pub struct Abi {
     pub inputs: Vec<AbiInput>,
     pub outputs: Vec<AbiOutput>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub private_outputs: Vec<usize>,
 }
 
 impl Abi {
@@ -24,6 +26,22 @@ impl Abi {
             outputs: self.outputs.clone(),
         }
     }
+
+    /// Returns the indices of the private outputs once flattened to field elements
+    pub fn flattened_private_outputs(&self) -> Vec<usize> {
+        let mut offset = 0;
+        let mut res = vec![];
+
+        for (index, output) in self.outputs.iter().enumerate() {
+            let count = output.get_primitive_count();
+            if self.private_outputs.contains(&index) {
+                res.extend(offset..offset + count);
+            }
+            offset += count;
+        }
+
+        res
+    }
 }
 
 #[cfg(test)]
@@ -32,7 +50,8 @@ mod tests {
     use std::collections::HashMap;
     use typed_absy::types::{ArrayType, FunctionKey, StructMember, StructType};
     use typed_absy::{
-        Parameter, Type, TypedFunction, TypedFunctionSymbol, TypedModule, TypedProgram, Variable,
+        FunctionAttributes, Parameter, Type, TypedFunction, TypedFunctionSymbol, TypedModule,
+        TypedProgram, Variable,
     };
     use zokrates_field::Bn128Field;
 
@@ -42,6 +61,7 @@ mod tests {
         functions.insert(
             FunctionKey::with_id("main"),
             TypedFunctionSymbol::Here(TypedFunction {
+                attributes: FunctionAttributes::default(),
                 arguments: vec![
                     Parameter {
                         id: Variable::field_element("a"),
@@ -56,6 +76,7 @@ mod tests {
                 signature: Signature::new()
                     .inputs(vec![Type::FieldElement, Type::Boolean])
                     .outputs(vec![Type::FieldElement]),
+                private_outputs: vec![],
             }),
         );
 
@@ -82,6 +103,7 @@ mod tests {
                 },
             ],
             outputs: vec![Type::FieldElement],
+            private_outputs: vec![],
         };
 
         assert_eq!(expected_abi, abi);
@@ -92,6 +114,7 @@ mod tests {
         let abi: Abi = Abi {
             inputs: vec![],
             outputs: vec![],
+            private_outputs: vec![],
         };
 
         let json = serde_json::to_string(&abi).unwrap();
@@ -114,6 +137,7 @@ mod tests {
                 },
             ],
             outputs: vec![Type::FieldElement],
+            private_outputs: vec![],
         };
 
         let json = serde_json::to_string_pretty(&abi).unwrap();
@@ -164,6 +188,7 @@ mod tests {
                     StructMember::new(String::from("b"), Type::Boolean),
                 ],
             ))],
+            private_outputs: vec![],
         };
 
         let json = serde_json::to_string_pretty(&abi).unwrap();
@@ -235,6 +260,7 @@ mod tests {
                 )),
             }],
             outputs: vec![],
+            private_outputs: vec![],
         };
 
         let json = serde_json::to_string_pretty(&abi).unwrap();
@@ -294,6 +320,7 @@ mod tests {
                 )),
             }],
             outputs: vec![Type::Boolean],
+            private_outputs: vec![],
         };
 
         let json = serde_json::to_string_pretty(&abi).unwrap();
@@ -345,6 +372,7 @@ mod tests {
                 )),
             }],
             outputs: vec![Type::FieldElement],
+            private_outputs: vec![],
         };
 
         let json = serde_json::to_string_pretty(&abi).unwrap();
@@ -0,0 +1,834 @@
+//! A reference evaluator for typed programs.
+//!
+//! It executes the typed AST as produced by the semantic checker, before any static analysis,
+//! with arbitrary precision integers reduced modulo the field size. It is slow but simple enough
+//! to be obviously correct, which makes it useful to detect miscompilations: its outputs must
+//! match those of the compiled program on all inputs.
+//!
+//! Like in compiled programs, both branches of conditional expressions are evaluated, so that a
+//! failure in either branch makes the evaluation fail.
+
+extern crate rand;
+
+use self::rand::Rng;
+use crate::embed::FlatEmbed;
+use crate::ir;
+use num_bigint::BigUint;
+use std::collections::HashMap;
+use std::fmt;
+use typed_absy::types::{FunctionKey, MemberId};
+use typed_absy::*;
+use zokrates_field::Field;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    AssertionFailed,
+    DivisionByZero,
+    OutOfBounds { index: BigUint, size: usize },
+    DoesNotFit { bitwidth: usize },
+    WrongInputCount { expected: usize, received: usize },
+    Oracle(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::AssertionFailed => write!(f, "Assertion failed"),
+            Error::DivisionByZero => write!(f, "Division by zero"),
+            Error::OutOfBounds { index, size } => write!(
+                f,
+                "Index {} out of bounds for array of size {}",
+                index, size
+            ),
+            Error::DoesNotFit { bitwidth } => write!(f, "Value does not fit in {} bits", bitwidth),
+            Error::WrongInputCount { expected, received } => write!(
+                f,
+                "Program takes {} inputs but was passed {} values",
+                expected, received
+            ),
+            Error::Oracle(name) => write!(f, "Oracle `{}` cannot be evaluated", name),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Field(BigUint),
+    Boolean(bool),
+    Uint(u128),
+    Array(Vec<Value>),
+    // members in the order of their declaration
+    Struct(Vec<Value>),
+}
+
+impl Value {
+    fn field(self) -> BigUint {
+        match self {
+            Value::Field(v) => v,
+            v => unreachable!("expected a field element, found {:?}", v),
+        }
+    }
+
+    fn boolean(self) -> bool {
+        match self {
+            Value::Boolean(v) => v,
+            v => unreachable!("expected a boolean, found {:?}", v),
+        }
+    }
+
+    fn uint(self) -> u128 {
+        match self {
+            Value::Uint(v) => v,
+            v => unreachable!("expected an unsigned integer, found {:?}", v),
+        }
+    }
+
+    fn array(self) -> Vec<Value> {
+        match self {
+            Value::Array(v) => v,
+            v => unreachable!("expected an array, found {:?}", v),
+        }
+    }
+
+    fn structure(self) -> Vec<Value> {
+        match self {
+            Value::Struct(v) => v,
+            v => unreachable!("expected a struct, found {:?}", v),
+        }
+    }
+
+    /// Builds a value of type `ty` from the flattened representation used for program inputs
+    fn unflatten<I: Iterator<Item = BigUint>>(ty: &Type, values: &mut I) -> Value {
+        match ty {
+            Type::FieldElement => Value::Field(values.next().unwrap()),
+            Type::Boolean => Value::Boolean(values.next().unwrap() == BigUint::from(1u32)),
+            Type::Uint(..) => Value::Uint(to_u128(&values.next().unwrap())),
+            Type::Array(array_type) => Value::Array(
+                (0..array_type.size)
+                    .map(|_| Value::unflatten(&array_type.ty, values))
+                    .collect(),
+            ),
+            Type::Struct(members) => Value::Struct(
+                members
+                    .iter()
+                    .map(|member| Value::unflatten(&member.ty, values))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn flatten(self) -> Vec<BigUint> {
+        match self {
+            Value::Field(v) => vec![v],
+            Value::Boolean(v) => vec![BigUint::from(v as u32)],
+            Value::Uint(v) => vec![BigUint::from(v)],
+            Value::Array(values) | Value::Struct(values) => {
+                values.into_iter().flat_map(|v| v.flatten()).collect()
+            }
+        }
+    }
+}
+
+fn to_u128(v: &BigUint) -> u128 {
+    v.to_bytes_le()
+        .iter()
+        .rev()
+        .fold(0, |acc, byte| (acc << 8) | *byte as u128)
+}
+
+fn to_bits(v: u128, bitwidth: usize) -> Value {
+    Value::Array(
+        (0..bitwidth)
+            .rev()
+            .map(|i| Value::Boolean((v >> i) & 1 == 1))
+            .collect(),
+    )
+}
+
+fn from_bits(bits: Vec<Value>) -> u128 {
+    bits.into_iter()
+        .fold(0, |acc, bit| (acc << 1) | bit.boolean() as u128)
+}
+
+/// The variables of a function call
+struct Frame<'a, 'ast> {
+    module: &'a TypedModuleId,
+    variables: HashMap<Identifier<'ast>, Value>,
+}
+
+pub struct ReferenceEvaluator<'a, 'ast, T> {
+    program: &'a TypedProgram<'ast, T>,
+    modulus: BigUint,
+}
+
+impl<'a, 'ast, T: Field> ReferenceEvaluator<'a, 'ast, T> {
+    pub fn new(program: &'a TypedProgram<'ast, T>) -> Self {
+        ReferenceEvaluator {
+            program,
+            modulus: T::max_value().to_biguint() + BigUint::from(1u32),
+        }
+    }
+
+    fn main(&self) -> &'a TypedFunction<'ast, T> {
+        let program = self.program;
+        program.modules[&program.main]
+            .functions
+            .iter()
+            .find_map(|(key, symbol)| match symbol {
+                TypedFunctionSymbol::Here(f) if key.id == "main" => Some(f),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    /// Executes the main function on `inputs`, flattened in the same way as the inputs of the
+    /// compiled program, and returns its flattened outputs
+    pub fn execute(&self, inputs: &[T]) -> Result<Vec<T>, Error> {
+        let main = self.main();
+
+        let expected = main
+            .arguments
+            .iter()
+            .map(|p| p.id._type.get_primitive_count())
+            .sum();
+        if inputs.len() != expected {
+            return Err(Error::WrongInputCount {
+                expected,
+                received: inputs.len(),
+            });
+        }
+
+        let mut inputs = inputs.iter().map(|i| i.to_biguint());
+        let arguments = main
+            .arguments
+            .iter()
+            .map(|p| Value::unflatten(&p.id._type, &mut inputs))
+            .collect();
+
+        let program = self.program;
+        Ok(self
+            .call_function(&program.main, main, arguments)?
+            .into_iter()
+            .flat_map(|v| v.flatten())
+            .map(T::from)
+            .collect())
+    }
+
+    /// Generates random inputs for the main function. Field elements are kept small so that
+    /// comparisons, which are only defined on a subset of the field, behave the same in the
+    /// compiled program
+    pub fn random_inputs<R: Rng>(&self, rng: &mut R) -> Vec<T> {
+        fn generate<R: Rng>(ty: &Type, rng: &mut R) -> Vec<BigUint> {
+            match ty {
+                Type::FieldElement => vec![match rng.gen_range(0, 4) {
+                    0 => BigUint::from(0u32),
+                    1 => BigUint::from(1u32),
+                    2 => BigUint::from(rng.gen::<u32>()),
+                    _ => BigUint::from(rng.gen::<u64>()),
+                }],
+                Type::Boolean => vec![BigUint::from(rng.gen::<bool>() as u32)],
+                Type::Uint(bitwidth) => {
+                    let v = (rng.gen::<u64>() as u128) << 64 | rng.gen::<u64>() as u128;
+                    vec![BigUint::from(v >> (128 - bitwidth.to_usize()))]
+                }
+                Type::Array(array_type) => (0..array_type.size)
+                    .flat_map(|_| generate(&array_type.ty, rng))
+                    .collect(),
+                Type::Struct(members) => members
+                    .iter()
+                    .flat_map(|member| generate(&member.ty, rng))
+                    .collect(),
+            }
+        }
+
+        self.main()
+            .arguments
+            .iter()
+            .flat_map(|p| generate(&p.id._type, rng))
+            .map(T::from)
+            .collect()
+    }
+
+    fn call(
+        &self,
+        module: &'a TypedModuleId,
+        key: &FunctionKey<'ast>,
+        arguments: Vec<Value>,
+    ) -> Result<Vec<Value>, Error> {
+        let program = self.program;
+        match &program.modules[module].functions[key] {
+            TypedFunctionSymbol::Here(f) => self.call_function(module, f, arguments),
+            TypedFunctionSymbol::There(key, module) => self.call(module, key, arguments),
+            TypedFunctionSymbol::Flat(embed) => self.call_embed(embed, arguments),
+        }
+    }
+
+    fn call_function(
+        &self,
+        module: &'a TypedModuleId,
+        function: &'a TypedFunction<'ast, T>,
+        arguments: Vec<Value>,
+    ) -> Result<Vec<Value>, Error> {
+        let mut frame = Frame {
+            module,
+            variables: function
+                .arguments
+                .iter()
+                .map(|p| p.id.id.clone())
+                .zip(arguments)
+                .collect(),
+        };
+
+        for statement in &function.statements {
+            if let Some(values) = self.execute_statement(&mut frame, statement)? {
+                return Ok(values);
+            }
+        }
+
+        Ok(vec![])
+    }
+
+    fn call_embed(&self, embed: &FlatEmbed, arguments: Vec<Value>) -> Result<Vec<Value>, Error> {
+        if let FlatEmbed::Oracle { name, .. } = embed {
+            return Err(Error::Oracle(name.clone()));
+        }
+
+        let mut arguments = arguments.into_iter();
+        let argument = arguments.next().unwrap();
+
+        let result = match embed {
+            FlatEmbed::Unpack(bitwidth) => {
+                let v = argument.field();
+                if v.bits() > *bitwidth {
+                    return Err(Error::DoesNotFit {
+                        bitwidth: *bitwidth,
+                    });
+                }
+                Value::Array(
+                    (0..*bitwidth)
+                        .rev()
+                        .map(|i| {
+                            Value::Boolean((&v >> i) & BigUint::from(1u32) == BigUint::from(1u32))
+                        })
+                        .collect(),
+                )
+            }
+            FlatEmbed::U8ToBits => to_bits(argument.uint(), 8),
+            FlatEmbed::U16ToBits => to_bits(argument.uint(), 16),
+            FlatEmbed::U32ToBits => to_bits(argument.uint(), 32),
+            FlatEmbed::U64ToBits => to_bits(argument.uint(), 64),
+            FlatEmbed::U128ToBits => to_bits(argument.uint(), 128),
+            FlatEmbed::U8FromBits
+            | FlatEmbed::U16FromBits
+            | FlatEmbed::U32FromBits
+            | FlatEmbed::U64FromBits
+            | FlatEmbed::U128FromBits => Value::Uint(from_bits(argument.array())),
+            FlatEmbed::U32ToBytesBe | FlatEmbed::U32ToBytesLe => {
+                let v = argument.uint();
+                let mut bytes: Vec<_> = (0..4)
+                    .rev()
+                    .map(|i| Value::Uint((v >> (8 * i)) & 0xff))
+                    .collect();
+                if *embed == FlatEmbed::U32ToBytesLe {
+                    bytes.reverse();
+                }
+                Value::Array(bytes)
+            }
+            FlatEmbed::U32FromBytesBe | FlatEmbed::U32FromBytesLe => {
+                let mut bytes = argument.array();
+                if *embed == FlatEmbed::U32FromBytesLe {
+                    bytes.reverse();
+                }
+                Value::Uint(
+                    bytes
+                        .into_iter()
+                        .fold(0, |acc, byte| (acc << 8) | byte.uint()),
+                )
+            }
+            FlatEmbed::DivMod(_) => {
+                let dividend = argument.uint();
+                let divisor = arguments.next().unwrap().uint();
+                if divisor == 0 {
+                    return Err(Error::DivisionByZero);
+                }
+                return Ok(vec![
+                    Value::Uint(dividend / divisor),
+                    Value::Uint(dividend % divisor),
+                ]);
+            }
+            FlatEmbed::FieldDivMod(bound) => {
+                let dividend = argument.field();
+                let divisor = arguments.next().unwrap().field();
+                if dividend.bits() > *bound || divisor.bits() > *bound {
+                    return Err(Error::DoesNotFit { bitwidth: *bound });
+                }
+                if divisor == BigUint::from(0u32) {
+                    return Err(Error::DivisionByZero);
+                }
+                return Ok(vec![
+                    Value::Field(&dividend / &divisor),
+                    Value::Field(&dividend % &divisor),
+                ]);
+            }
+            FlatEmbed::Oracle { .. } => unreachable!(),
+        };
+
+        Ok(vec![result])
+    }
+
+    fn execute_statement(
+        &self,
+        frame: &mut Frame<'a, 'ast>,
+        statement: &TypedStatement<'ast, T>,
+    ) -> Result<Option<Vec<Value>>, Error> {
+        match statement {
+            TypedStatement::Return(expressions) => Ok(Some(
+                expressions
+                    .iter()
+                    .map(|e| self.evaluate(&*frame, e))
+                    .collect::<Result<_, _>>()?,
+            )),
+            TypedStatement::Definition(assignee, expression) => {
+                let value = self.evaluate(frame, expression)?;
+                self.assign(frame, assignee, value)?;
+                Ok(None)
+            }
+            TypedStatement::Declaration(..) => Ok(None),
+            TypedStatement::Assertion(e) => match self.boolean(frame, e)? {
+                true => Ok(None),
+                false => Err(Error::AssertionFailed),
+            },
+            TypedStatement::For(variable, from, to, max, statements) => {
+                let from = self.field(frame, from)?;
+                let to = self.field(frame, to)?;
+
+                // the compiled loop asserts that the bound is reached by an iteration, or is the maximum
+                if let Some(max) = max {
+                    let max = self.field(frame, max)?;
+                    if to != max && (to < from || to > max) {
+                        return Err(Error::AssertionFailed);
+                    }
+                }
+
+                let from = to_u128(&from);
+                let to = to_u128(&to);
+
+                for i in from..to {
+                    frame
+                        .variables
+                        .insert(variable.id.clone(), Value::Field(BigUint::from(i)));
+                    for statement in statements {
+                        if let Some(values) = self.execute_statement(frame, statement)? {
+                            return Ok(Some(values));
+                        }
+                    }
+                }
+
+                Ok(None)
+            }
+            TypedStatement::MultipleDefinition(variables, rhs) => match rhs {
+                TypedExpressionList::FunctionCall(key, arguments, _) => {
+                    let values = self.call_with(frame, key, arguments)?;
+                    for (variable, value) in variables.iter().zip(values) {
+                        frame.variables.insert(variable.id.clone(), value);
+                    }
+                    Ok(None)
+                }
+            },
+        }
+    }
+
+    fn read(
+        &self,
+        frame: &Frame<'a, 'ast>,
+        assignee: &TypedAssignee<'ast, T>,
+    ) -> Result<Value, Error> {
+        match assignee {
+            TypedAssignee::Identifier(variable) => Ok(frame.variables[&variable.id].clone()),
+            TypedAssignee::Select(array, index) => {
+                let values = self.read(frame, array)?.array();
+                let index = self.index(frame, index, values.len())?;
+                Ok(values[index].clone())
+            }
+            TypedAssignee::Member(s, id) => {
+                let index = member_index(&s.get_type(), id);
+                Ok(self.read(frame, s)?.structure()[index].clone())
+            }
+        }
+    }
+
+    fn assign(
+        &self,
+        frame: &mut Frame<'a, 'ast>,
+        assignee: &TypedAssignee<'ast, T>,
+        value: Value,
+    ) -> Result<(), Error> {
+        match assignee {
+            TypedAssignee::Identifier(variable) => {
+                frame.variables.insert(variable.id.clone(), value);
+                Ok(())
+            }
+            TypedAssignee::Select(array, index) => {
+                let mut values = self.read(frame, array)?.array();
+                let index = self.index(frame, index, values.len())?;
+                values[index] = value;
+                self.assign(frame, array, Value::Array(values))
+            }
+            TypedAssignee::Member(s, id) => {
+                let index = member_index(&s.get_type(), id);
+                let mut values = self.read(frame, s)?.structure();
+                values[index] = value;
+                self.assign(frame, s, Value::Struct(values))
+            }
+        }
+    }
+
+    fn call_with(
+        &self,
+        frame: &Frame<'a, 'ast>,
+        key: &FunctionKey<'ast>,
+        arguments: &[TypedExpression<'ast, T>],
+    ) -> Result<Vec<Value>, Error> {
+        let arguments = arguments
+            .iter()
+            .map(|a| self.evaluate(frame, a))
+            .collect::<Result<_, _>>()?;
+        self.call(frame.module, key, arguments)
+    }
+
+    fn call_single(
+        &self,
+        frame: &Frame<'a, 'ast>,
+        key: &FunctionKey<'ast>,
+        arguments: &[TypedExpression<'ast, T>],
+    ) -> Result<Value, Error> {
+        Ok(self
+            .call_with(frame, key, arguments)?
+            .into_iter()
+            .next()
+            .unwrap())
+    }
+
+    fn index(
+        &self,
+        frame: &Frame<'a, 'ast>,
+        index: &FieldElementExpression<'ast, T>,
+        size: usize,
+    ) -> Result<usize, Error> {
+        let index = self.field(frame, index)?;
+        match index < BigUint::from(size) {
+            true => Ok(to_u128(&index) as usize),
+            false => Err(Error::OutOfBounds { index, size }),
+        }
+    }
+
+    fn select(
+        &self,
+        frame: &Frame<'a, 'ast>,
+        array: &ArrayExpression<'ast, T>,
+        index: &FieldElementExpression<'ast, T>,
+    ) -> Result<Value, Error> {
+        let values = self.array(frame, array)?;
+        let index = self.index(frame, index, values.len())?;
+        Ok(values[index].clone())
+    }
+
+    fn member(
+        &self,
+        frame: &Frame<'a, 'ast>,
+        s: &StructExpression<'ast, T>,
+        id: &MemberId,
+    ) -> Result<Value, Error> {
+        let index = member_index(&s.get_type(), id);
+        Ok(self.structure(frame, s)?[index].clone())
+    }
+
+    fn evaluate(
+        &self,
+        frame: &Frame<'a, 'ast>,
+        e: &TypedExpression<'ast, T>,
+    ) -> Result<Value, Error> {
+        match e {
+            TypedExpression::FieldElement(e) => self.field(frame, e).map(Value::Field),
+            TypedExpression::Boolean(e) => self.boolean(frame, e).map(Value::Boolean),
+            TypedExpression::Uint(e) => self.uint(frame, e).map(Value::Uint),
+            TypedExpression::Array(e) => self.array(frame, e).map(Value::Array),
+            TypedExpression::Struct(e) => self.structure(frame, e).map(Value::Struct),
+        }
+    }
+
+    fn field(
+        &self,
+        frame: &Frame<'a, 'ast>,
+        e: &FieldElementExpression<'ast, T>,
+    ) -> Result<BigUint, Error> {
+        let p = &self.modulus;
+
+        match e {
+            FieldElementExpression::Number(n) => Ok(n.to_biguint()),
+            FieldElementExpression::Identifier(id) => Ok(frame.variables[id].clone().field()),
+            FieldElementExpression::Add(a, b) => {
+                Ok((self.field(frame, a)? + self.field(frame, b)?) % p)
+            }
+            FieldElementExpression::Sub(a, b) => {
+                Ok((self.field(frame, a)? + p - self.field(frame, b)?) % p)
+            }
+            FieldElementExpression::Mult(a, b) => {
+                Ok((self.field(frame, a)? * self.field(frame, b)?) % p)
+            }
+            FieldElementExpression::Div(a, b) => {
+                let a = self.field(frame, a)?;
+                let b = self.field(frame, b)?;
+                if b == BigUint::from(0u32) {
+                    return Err(Error::DivisionByZero);
+                }
+                // Fermat's little theorem: b^(p-2) is the inverse of b
+                let inverse = b.modpow(&(p - BigUint::from(2u32)), p);
+                Ok((a * inverse) % p)
+            }
+            FieldElementExpression::Pow(a, b) => {
+                Ok(self.field(frame, a)?.modpow(&self.field(frame, b)?, p))
+            }
+            FieldElementExpression::IfElse(c, a, b) => {
+                let c = self.boolean(frame, c)?;
+                let a = self.field(frame, a)?;
+                let b = self.field(frame, b)?;
+                Ok(if c { a } else { b })
+            }
+            FieldElementExpression::FunctionCall(key, arguments) => {
+                Ok(self.call_single(frame, key, arguments)?.field())
+            }
+            FieldElementExpression::Member(s, id) => Ok(self.member(frame, s, id)?.field()),
+            FieldElementExpression::Select(a, i) => Ok(self.select(frame, a, i)?.field()),
+        }
+    }
+
+    fn boolean(
+        &self,
+        frame: &Frame<'a, 'ast>,
+        e: &BooleanExpression<'ast, T>,
+    ) -> Result<bool, Error> {
+        match e {
+            BooleanExpression::Identifier(id) => Ok(frame.variables[id].clone().boolean()),
+            BooleanExpression::Value(v) => Ok(*v),
+            BooleanExpression::Lt(a, b) => Ok(self.field(frame, a)? < self.field(frame, b)?),
+            BooleanExpression::Le(a, b) => Ok(self.field(frame, a)? <= self.field(frame, b)?),
+            BooleanExpression::Ge(a, b) => Ok(self.field(frame, a)? >= self.field(frame, b)?),
+            BooleanExpression::Gt(a, b) => Ok(self.field(frame, a)? > self.field(frame, b)?),
+            BooleanExpression::FieldEq(a, b) => Ok(self.field(frame, a)? == self.field(frame, b)?),
+            BooleanExpression::BoolEq(a, b) => {
+                Ok(self.boolean(frame, a)? == self.boolean(frame, b)?)
+            }
+            BooleanExpression::ArrayEq(a, b) => Ok(self.array(frame, a)? == self.array(frame, b)?),
+            BooleanExpression::StructEq(a, b) => {
+                Ok(self.structure(frame, a)? == self.structure(frame, b)?)
+            }
+            BooleanExpression::UintEq(a, b) => Ok(self.uint(frame, a)? == self.uint(frame, b)?),
+            BooleanExpression::Or(a, b) => {
+                let a = self.boolean(frame, a)?;
+                let b = self.boolean(frame, b)?;
+                Ok(a || b)
+            }
+            BooleanExpression::And(a, b) => {
+                let a = self.boolean(frame, a)?;
+                let b = self.boolean(frame, b)?;
+                Ok(a && b)
+            }
+            BooleanExpression::Not(a) => Ok(!self.boolean(frame, a)?),
+            BooleanExpression::IfElse(c, a, b) => {
+                let c = self.boolean(frame, c)?;
+                let a = self.boolean(frame, a)?;
+                let b = self.boolean(frame, b)?;
+                Ok(if c { a } else { b })
+            }
+            BooleanExpression::Member(s, id) => Ok(self.member(frame, s, id)?.boolean()),
+            BooleanExpression::FunctionCall(key, arguments) => {
+                Ok(self.call_single(frame, key, arguments)?.boolean())
+            }
+            BooleanExpression::Select(a, i) => Ok(self.select(frame, a, i)?.boolean()),
+        }
+    }
+
+    fn uint(&self, frame: &Frame<'a, 'ast>, e: &UExpression<'ast, T>) -> Result<u128, Error> {
+        let bitwidth = e.bitwidth.to_usize();
+        let mask = u128::max_value() >> (128 - bitwidth);
+
+        match &e.inner {
+            UExpressionInner::Value(v) => Ok(*v),
+            UExpressionInner::Identifier(id) => Ok(frame.variables[id].clone().uint()),
+            UExpressionInner::Add(a, b) => {
+                Ok(self.uint(frame, a)?.wrapping_add(self.uint(frame, b)?) & mask)
+            }
+            UExpressionInner::Sub(a, b) => {
+                Ok(self.uint(frame, a)?.wrapping_sub(self.uint(frame, b)?) & mask)
+            }
+            UExpressionInner::Mult(a, b) => {
+                Ok(self.uint(frame, a)?.wrapping_mul(self.uint(frame, b)?) & mask)
+            }
+            UExpressionInner::Div(a, b) => self
+                .uint(frame, a)?
+                .checked_div(self.uint(frame, b)?)
+                .ok_or(Error::DivisionByZero),
+            UExpressionInner::Rem(a, b) => self
+                .uint(frame, a)?
+                .checked_rem(self.uint(frame, b)?)
+                .ok_or(Error::DivisionByZero),
+            UExpressionInner::Xor(a, b) => Ok(self.uint(frame, a)? ^ self.uint(frame, b)?),
+            UExpressionInner::And(a, b) => Ok(self.uint(frame, a)? & self.uint(frame, b)?),
+            UExpressionInner::Or(a, b) => Ok(self.uint(frame, a)? | self.uint(frame, b)?),
+            UExpressionInner::Not(a) => Ok(!self.uint(frame, a)? & mask),
+            UExpressionInner::LeftShift(a, by) => {
+                let a = self.uint(frame, a)?;
+                let by = self.field(frame, by)?;
+                match by < BigUint::from(bitwidth) {
+                    true => Ok((a << to_u128(&by)) & mask),
+                    false => Ok(0),
+                }
+            }
+            UExpressionInner::RightShift(a, by) => {
+                let a = self.uint(frame, a)?;
+                let by = self.field(frame, by)?;
+                match by < BigUint::from(bitwidth) {
+                    true => Ok(a >> to_u128(&by)),
+                    false => Ok(0),
+                }
+            }
+            UExpressionInner::FunctionCall(key, arguments) => {
+                Ok(self.call_single(frame, key, arguments)?.uint())
+            }
+            UExpressionInner::IfElse(c, a, b) => {
+                let c = self.boolean(frame, c)?;
+                let a = self.uint(frame, a)?;
+                let b = self.uint(frame, b)?;
+                Ok(if c { a } else { b })
+            }
+            UExpressionInner::Member(s, id) => Ok(self.member(frame, s, id)?.uint()),
+            UExpressionInner::Select(a, i) => Ok(self.select(frame, a, i)?.uint()),
+        }
+    }
+
+    fn array(
+        &self,
+        frame: &Frame<'a, 'ast>,
+        e: &ArrayExpression<'ast, T>,
+    ) -> Result<Vec<Value>, Error> {
+        match e.as_inner() {
+            ArrayExpressionInner::Identifier(id) => Ok(frame.variables[id].clone().array()),
+            ArrayExpressionInner::Value(values) => {
+                values.iter().map(|v| self.evaluate(frame, v)).collect()
+            }
+            ArrayExpressionInner::FunctionCall(key, arguments) => {
+                Ok(self.call_single(frame, key, arguments)?.array())
+            }
+            ArrayExpressionInner::IfElse(c, a, b) => {
+                let c = self.boolean(frame, c)?;
+                let a = self.array(frame, a)?;
+                let b = self.array(frame, b)?;
+                Ok(if c { a } else { b })
+            }
+            ArrayExpressionInner::Member(s, id) => Ok(self.member(frame, s, id)?.array()),
+            ArrayExpressionInner::Select(a, i) => Ok(self.select(frame, a, i)?.array()),
+        }
+    }
+
+    fn structure(
+        &self,
+        frame: &Frame<'a, 'ast>,
+        e: &StructExpression<'ast, T>,
+    ) -> Result<Vec<Value>, Error> {
+        match e.as_inner() {
+            StructExpressionInner::Identifier(id) => Ok(frame.variables[id].clone().structure()),
+            StructExpressionInner::Value(values) => {
+                values.iter().map(|v| self.evaluate(frame, v)).collect()
+            }
+            StructExpressionInner::FunctionCall(key, arguments) => {
+                Ok(self.call_single(frame, key, arguments)?.structure())
+            }
+            StructExpressionInner::IfElse(c, a, b) => {
+                let c = self.boolean(frame, c)?;
+                let a = self.structure(frame, a)?;
+                let b = self.structure(frame, b)?;
+                Ok(if c { a } else { b })
+            }
+            StructExpressionInner::Member(s, id) => Ok(self.member(frame, s, id)?.structure()),
+            StructExpressionInner::Select(a, i) => Ok(self.select(frame, a, i)?.structure()),
+        }
+    }
+}
+
+fn member_index(ty: &Type, id: &MemberId) -> usize {
+    match ty {
+        Type::Struct(members) => members.iter().position(|m| m.id == *id).unwrap(),
+        _ => unreachable!("a member access should only be defined over structs"),
+    }
+}
+
+/// A disagreement between the reference evaluator and the compiled program
+#[derive(Debug)]
+pub struct Mismatch<T> {
+    pub inputs: Vec<T>,
+    pub expected: Result<Vec<T>, Error>,
+    pub found: Result<Vec<T>, ir::Error>,
+}
+
+impl<T: fmt::Display> fmt::Display for Mismatch<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn display<T: fmt::Display, E: fmt::Display>(r: &Result<Vec<T>, E>) -> String {
+            match r {
+                Ok(values) => format!(
+                    "[{}]",
+                    values
+                        .iter()
+                        .map(|v| v.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                Err(e) => format!("error ({})", e),
+            }
+        }
+
+        write!(
+            f,
+            "On inputs {}, the reference evaluator returned {} but the compiled program returned {}",
+            self.inputs
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(" "),
+            display(&self.expected),
+            display(&self.found)
+        )
+    }
+}
+
+/// Runs `program` and its compiled version `compiled` on `rounds` random inputs and returns the
+/// first inputs on which they disagree. Both failing counts as an agreement, as the reasons for
+/// failure are reported differently.
+pub fn differential_test<'ast, T: Field>(
+    program: &TypedProgram<'ast, T>,
+    compiled: &ir::Prog<T>,
+    rounds: usize,
+) -> Option<Mismatch<T>> {
+    let evaluator = ReferenceEvaluator::new(program);
+    let interpreter = ir::Interpreter::default();
+    let mut rng = rand::thread_rng();
+
+    (0..rounds).find_map(|_| {
+        let inputs = evaluator.random_inputs(&mut rng);
+
+        let expected = evaluator.execute(&inputs);
+        let found = interpreter
+            .execute(compiled, &inputs)
+            .map(|witness| witness.return_values());
+
+        match (&expected, &found) {
+            (Ok(e), Ok(f)) if e == f => None,
+            (Err(_), Err(_)) => None,
+            _ => Some(Mismatch {
+                inputs,
+                expected,
+                found,
+            }),
+        }
+    })
+}
@@ -166,10 +166,11 @@ pub fn fold_statement<'ast, T: Field, F: Folder<'ast, T>>(
         }
         TypedStatement::Declaration(v) => TypedStatement::Declaration(f.fold_variable(v)),
         TypedStatement::Assertion(e) => TypedStatement::Assertion(f.fold_boolean_expression(e)),
-        TypedStatement::For(v, from, to, statements) => TypedStatement::For(
+        TypedStatement::For(v, from, to, max, statements) => TypedStatement::For(
             f.fold_variable(v),
             from,
             to,
+            max,
             statements
                 .into_iter()
                 .flat_map(|s| f.fold_statement(s))
@@ -430,6 +431,18 @@ pub fn fold_uint_expression_inner<'ast, T: Field, F: Folder<'ast, T>>(
 
             UExpressionInner::Mult(box left, box right)
         }
+        UExpressionInner::Div(box left, box right) => {
+            let left = f.fold_uint_expression(left);
+            let right = f.fold_uint_expression(right);
+
+            UExpressionInner::Div(box left, box right)
+        }
+        UExpressionInner::Rem(box left, box right) => {
+            let left = f.fold_uint_expression(left);
+            let right = f.fold_uint_expression(right);
+
+            UExpressionInner::Rem(box left, box right)
+        }
         UExpressionInner::Xor(box left, box right) => {
             let left = f.fold_uint_expression(left);
             let right = f.fold_uint_expression(right);
@@ -1,3 +1,4 @@
+use serde::{Serialize, Serializer};
 use std::fmt;
 use typed_absy::types::FunctionKey;
 use typed_absy::TypedModuleId;
@@ -55,6 +56,13 @@ impl<'ast> fmt::Display for Identifier<'ast> {
     }
 }
 
+// identifiers are serialized as they are displayed
+impl<'ast> Serialize for Identifier<'ast> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(self)
+    }
+}
+
 impl<'ast> From<&'ast str> for Identifier<'ast> {
     fn from(id: &'ast str) -> Identifier<'ast> {
         Identifier::from(CoreIdentifier::Source(id))
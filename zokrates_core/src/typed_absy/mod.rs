@@ -6,6 +6,7 @@
 //! @date 2017
 
 pub mod abi;
+pub mod evaluator;
 pub mod folder;
 pub mod identifier;
 
@@ -23,7 +24,8 @@ pub use typed_absy::uint::{bitwidth, UExpression, UExpressionInner, UMetadata};
 
 use crate::typed_absy::types::{FunctionKey, MemberId};
 use embed::FlatEmbed;
-use std::collections::HashMap;
+use serde::Serializer;
+use std::collections::{BTreeMap, HashMap};
 use std::convert::TryFrom;
 use std::fmt;
 use zokrates_field::Field;
@@ -45,9 +47,46 @@ pub type TypedModules<'ast, T> = HashMap<TypedModuleId, TypedModule<'ast, T>>;
 ///   in a given `TypedModule`, hence the use of a HashMap
 pub type TypedFunctionSymbols<'ast, T> = HashMap<FunctionKey<'ast>, TypedFunctionSymbol<'ast, T>>;
 
+// Serialization of the typed AST, see the "Typed AST" section of the book for the resulting format
+
+fn serialize_modules<'ast, T: Field, S: Serializer>(
+    modules: &TypedModules<'ast, T>,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    // sort the modules so that the output is deterministic
+    s.collect_map(modules.iter().collect::<BTreeMap<_, _>>())
+}
+
+#[derive(Serialize)]
+#[serde(bound = "T: Field")]
+struct FunctionEntry<'a, 'ast, T> {
+    key: &'a FunctionKey<'ast>,
+    symbol: &'a TypedFunctionSymbol<'ast, T>,
+}
+
+fn serialize_functions<'ast, T: Field, S: Serializer>(
+    functions: &TypedFunctionSymbols<'ast, T>,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    // function keys are not strings, so functions are serialized as a list sorted by key
+    let mut functions: Vec<_> = functions.iter().collect();
+    functions.sort_by_key(|(key, _)| key.to_slug());
+    s.collect_seq(
+        functions
+            .into_iter()
+            .map(|(key, symbol)| FunctionEntry { key, symbol }),
+    )
+}
+
+fn serialize_field<T: Field, S: Serializer>(value: &T, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&value.to_dec_string())
+}
+
 /// A typed program as a collection of modules, one of them being the main
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize)]
+#[serde(bound = "T: Field")]
 pub struct TypedProgram<'ast, T> {
+    #[serde(serialize_with = "serialize_modules")]
     pub modules: TypedModules<'ast, T>,
     pub main: TypedModuleId,
 }
@@ -76,6 +115,7 @@ impl<'ast, T: Field> TypedProgram<'ast, T> {
                 })
                 .collect(),
             outputs: main.signature.outputs.clone(),
+            private_outputs: main.private_outputs.clone(),
         }
     }
 }
@@ -103,13 +143,16 @@ impl<'ast, T: fmt::Display> fmt::Display for TypedProgram<'ast, T> {
 }
 
 /// A typed program as a collection of functions. Types have been resolved during semantic checking.
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Clone, Serialize)]
+#[serde(bound = "T: Field")]
 pub struct TypedModule<'ast, T> {
     /// Functions of the program
+    #[serde(serialize_with = "serialize_functions")]
     pub functions: TypedFunctionSymbols<'ast, T>,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize)]
+#[serde(bound = "T: Field")]
 pub enum TypedFunctionSymbol<'ast, T> {
     Here(TypedFunction<'ast, T>),
     There(FunctionKey<'ast>, TypedModuleId),
@@ -181,15 +224,37 @@ impl<'ast, T: fmt::Debug> fmt::Debug for TypedModule<'ast, T> {
     }
 }
 
+/// How a function is to be inlined, as requested by an `inline` attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum Inline {
+    /// `#[inline(always)]`: the function is inlined at every call
+    Always,
+    /// `#[inline(never)]`: the function is kept as a sub-circuit shared by its calls
+    Never,
+}
+
+/// The checked attributes of a function
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+pub struct FunctionAttributes {
+    pub inline: Option<Inline>,
+    /// The maximum number of constraints of the function, as requested by a `max_constraints` attribute
+    pub max_constraints: Option<usize>,
+}
+
 /// A typed function
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize)]
+#[serde(bound = "T: Field")]
 pub struct TypedFunction<'ast, T> {
+    /// Attributes of the function
+    pub attributes: FunctionAttributes,
     /// Arguments of the function
     pub arguments: Vec<Parameter<'ast>>,
     /// Vector of statements that are executed when running the function
     pub statements: Vec<TypedStatement<'ast, T>>,
     /// function signature
     pub signature: Signature,
+    /// indices of the outputs which are declared private
+    pub private_outputs: Vec<usize>,
 }
 
 impl<'ast, T: fmt::Display> fmt::Display for TypedFunction<'ast, T> {
@@ -249,7 +314,8 @@ impl<'ast, T: fmt::Debug> fmt::Debug for TypedFunction<'ast, T> {
 }
 
 /// Something we can assign to.
-#[derive(Clone, PartialEq, Hash, Eq)]
+#[derive(Clone, PartialEq, Hash, Eq, Serialize)]
+#[serde(bound = "T: Field")]
 pub enum TypedAssignee<'ast, T> {
     Identifier(Variable<'ast>),
     Select(
@@ -307,16 +373,20 @@ impl<'ast, T: fmt::Display> fmt::Display for TypedAssignee<'ast, T> {
 }
 
 /// A statement in a `TypedFunction`
-#[derive(Clone, PartialEq, Hash, Eq)]
+#[derive(Clone, PartialEq, Hash, Eq, Serialize)]
+#[serde(bound = "T: Field")]
 pub enum TypedStatement<'ast, T> {
     Return(Vec<TypedExpression<'ast, T>>),
     Definition(TypedAssignee<'ast, T>, TypedExpression<'ast, T>),
     Declaration(Variable<'ast>),
     Assertion(BooleanExpression<'ast, T>),
+    /// A loop over `from..to`. If a maximum is given, `to` can be a runtime value between `from` and the maximum, and
+    /// the loop is unrolled up to the maximum, each iteration only having effects if it is before `to`
     For(
         Variable<'ast>,
         FieldElementExpression<'ast, T>,
         FieldElementExpression<'ast, T>,
+        Option<FieldElementExpression<'ast, T>>,
         Vec<TypedStatement<'ast, T>>,
     ),
     MultipleDefinition(Vec<Variable<'ast>>, TypedExpressionList<'ast, T>),
@@ -340,8 +410,12 @@ impl<'ast, T: fmt::Debug> fmt::Debug for TypedStatement<'ast, T> {
                 write!(f, "Definition({:?}, {:?})", lhs, rhs)
             }
             TypedStatement::Assertion(ref e) => write!(f, "Assertion({:?})", e),
-            TypedStatement::For(ref var, ref start, ref stop, ref list) => {
-                write!(f, "for {:?} in {:?}..{:?} do\n", var, start, stop)?;
+            TypedStatement::For(ref var, ref start, ref stop, ref max, ref list) => {
+                write!(f, "for {:?} in {:?}..{:?} ", var, start, stop)?;
+                if let Some(max) = max {
+                    write!(f, "max {:?} ", max)?;
+                }
+                write!(f, "do\n")?;
                 for l in list {
                     write!(f, "\t\t{:?}\n", l)?;
                 }
@@ -357,9 +431,13 @@ impl<'ast, T: fmt::Debug> fmt::Debug for TypedStatement<'ast, T> {
 impl<'ast, T: fmt::Display> TypedStatement<'ast, T> {
     fn fmt_indented(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
         match self {
-            TypedStatement::For(variable, from, to, statements) => {
+            TypedStatement::For(variable, from, to, max, statements) => {
                 write!(f, "{}", "\t".repeat(depth))?;
-                writeln!(f, "for {} in {}..{} do", variable, from, to)?;
+                write!(f, "for {} in {}..{} ", variable, from, to)?;
+                if let Some(max) = max {
+                    write!(f, "max {} ", max)?;
+                }
+                writeln!(f, "do")?;
                 for s in statements {
                     s.fmt_indented(f, depth + 1)?;
                     writeln!(f, "")?;
@@ -387,8 +465,12 @@ impl<'ast, T: fmt::Display> fmt::Display for TypedStatement<'ast, T> {
             TypedStatement::Declaration(ref var) => write!(f, "{}", var),
             TypedStatement::Definition(ref lhs, ref rhs) => write!(f, "{} = {}", lhs, rhs),
             TypedStatement::Assertion(ref e) => write!(f, "assert({})", e),
-            TypedStatement::For(ref var, ref start, ref stop, ref list) => {
-                write!(f, "for {} in {}..{} do\n", var, start, stop)?;
+            TypedStatement::For(ref var, ref start, ref stop, ref max, ref list) => {
+                write!(f, "for {} in {}..{} ", var, start, stop)?;
+                if let Some(max) = max {
+                    write!(f, "max {} ", max)?;
+                }
+                write!(f, "do\n")?;
                 for l in list {
                     write!(f, "\t\t{}\n", l)?;
                 }
@@ -412,7 +494,8 @@ pub trait Typed {
 }
 
 /// A typed expression
-#[derive(Clone, PartialEq, Hash, Eq)]
+#[derive(Clone, PartialEq, Hash, Eq, Serialize)]
+#[serde(bound = "T: Field")]
 pub enum TypedExpression<'ast, T> {
     Boolean(BooleanExpression<'ast, T>),
     FieldElement(FieldElementExpression<'ast, T>),
@@ -577,7 +660,8 @@ pub trait MultiTyped {
     fn get_types(&self) -> &Vec<Type>;
 }
 
-#[derive(Clone, PartialEq, Hash, Eq)]
+#[derive(Clone, PartialEq, Hash, Eq, Serialize)]
+#[serde(bound = "T: Field")]
 pub enum TypedExpressionList<'ast, T> {
     FunctionCall(FunctionKey<'ast>, Vec<TypedExpression<'ast, T>>, Vec<Type>),
 }
@@ -591,9 +675,10 @@ impl<'ast, T> MultiTyped for TypedExpressionList<'ast, T> {
 }
 
 /// An expression of type `field`
-#[derive(Clone, PartialEq, Hash, Eq)]
+#[derive(Clone, PartialEq, Hash, Eq, Serialize)]
+#[serde(bound = "T: Field")]
 pub enum FieldElementExpression<'ast, T> {
-    Number(T),
+    Number(#[serde(serialize_with = "serialize_field")] T),
     Identifier(Identifier<'ast>),
     Add(
         Box<FieldElementExpression<'ast, T>>,
@@ -629,7 +714,8 @@ pub enum FieldElementExpression<'ast, T> {
 }
 
 /// An expression of type `bool`
-#[derive(Clone, PartialEq, Hash, Eq)]
+#[derive(Clone, PartialEq, Hash, Eq, Serialize)]
+#[serde(bound = "T: Field")]
 pub enum BooleanExpression<'ast, T> {
     Identifier(Identifier<'ast>),
     Value(bool),
@@ -690,14 +776,16 @@ pub enum BooleanExpression<'ast, T> {
 /// * Contrary to basic types which are represented as enums, we wrap an enum `ArrayExpressionInner` in a struct in order to keep track of the type (content and size)
 /// of the array. Only using an enum would require generics, which would propagate up to TypedExpression which we want to keep simple, hence this "runtime"
 /// type checking
-#[derive(Clone, PartialEq, Hash, Eq)]
+#[derive(Clone, PartialEq, Hash, Eq, Serialize)]
+#[serde(bound = "T: Field")]
 pub struct ArrayExpression<'ast, T> {
     size: usize,
     ty: Type,
     inner: ArrayExpressionInner<'ast, T>,
 }
 
-#[derive(Clone, PartialEq, Hash, Eq)]
+#[derive(Clone, PartialEq, Hash, Eq, Serialize)]
+#[serde(bound = "T: Field")]
 pub enum ArrayExpressionInner<'ast, T> {
     Identifier(Identifier<'ast>),
     Value(Vec<TypedExpression<'ast, T>>),
@@ -742,7 +830,8 @@ impl<'ast, T> ArrayExpression<'ast, T> {
     }
 }
 
-#[derive(Clone, PartialEq, Hash, Eq)]
+#[derive(Clone, PartialEq, Hash, Eq, Serialize)]
+#[serde(bound = "T: Field")]
 pub struct StructExpression<'ast, T> {
     ty: StructType,
     inner: StructExpressionInner<'ast, T>,
@@ -762,7 +851,8 @@ impl<'ast, T> StructExpression<'ast, T> {
     }
 }
 
-#[derive(Clone, PartialEq, Hash, Eq)]
+#[derive(Clone, PartialEq, Hash, Eq, Serialize)]
+#[serde(bound = "T: Field")]
 pub enum StructExpressionInner<'ast, T> {
     Identifier(Identifier<'ast>),
     Value(Vec<TypedExpression<'ast, T>>),
@@ -890,6 +980,8 @@ impl<'ast, T: fmt::Display> fmt::Display for UExpression<'ast, T> {
             UExpressionInner::Xor(ref lhs, ref rhs) => write!(f, "({} ^ {})", lhs, rhs),
             UExpressionInner::Sub(ref lhs, ref rhs) => write!(f, "({} - {})", lhs, rhs),
             UExpressionInner::Mult(ref lhs, ref rhs) => write!(f, "({} * {})", lhs, rhs),
+            UExpressionInner::Div(ref lhs, ref rhs) => write!(f, "({} / {})", lhs, rhs),
+            UExpressionInner::Rem(ref lhs, ref rhs) => write!(f, "({} % {})", lhs, rhs),
             UExpressionInner::RightShift(ref e, ref by) => write!(f, "({} >> {})", e, by),
             UExpressionInner::LeftShift(ref e, ref by) => write!(f, "({} << {})", e, by),
             UExpressionInner::Not(ref e) => write!(f, "!{}", e),
@@ -1,7 +1,7 @@
 use crate::typed_absy::Variable;
 use std::fmt;
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize)]
 pub struct Parameter<'ast> {
     pub id: Variable<'ast>,
     pub private: bool,
@@ -71,6 +71,10 @@ pub enum UBitwidth {
     B16 = 16,
     #[serde(rename = "32")]
     B32 = 32,
+    #[serde(rename = "64")]
+    B64 = 64,
+    #[serde(rename = "128")]
+    B128 = 128,
 }
 
 impl UBitwidth {
@@ -85,6 +89,8 @@ impl From<usize> for UBitwidth {
             8 => UBitwidth::B8,
             16 => UBitwidth::B16,
             32 => UBitwidth::B32,
+            64 => UBitwidth::B64,
+            128 => UBitwidth::B128,
             _ => unreachable!(),
         }
     }
@@ -220,7 +226,7 @@ impl Type {
 
 pub type FunctionIdentifier<'ast> = &'ast str;
 
-#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Serialize)]
 pub struct FunctionKey<'ast> {
     pub id: FunctionIdentifier<'ast>,
     pub signature: Signature,
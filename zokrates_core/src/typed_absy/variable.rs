@@ -3,9 +3,10 @@ use crate::typed_absy::Identifier;
 use std::fmt;
 use typed_absy::types::{StructType, UBitwidth};
 
-#[derive(Clone, PartialEq, Hash, Eq)]
+#[derive(Clone, PartialEq, Hash, Eq, Serialize)]
 pub struct Variable<'ast> {
     pub id: Identifier<'ast>,
+    #[serde(rename = "type")]
     pub _type: Type,
 }
 
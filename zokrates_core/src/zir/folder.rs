@@ -4,23 +4,23 @@ use crate::zir::types::UBitwidth;
 use crate::zir::*;
 use zokrates_field::Field;
 
-pub trait Folder<'ast, T: Field>: Sized {
-    fn fold_program(&mut self, p: ZirProgram<'ast, T>) -> ZirProgram<'ast, T> {
+pub trait Folder<'ast, T: Field, M = ()>: Sized {
+    fn fold_program(&mut self, p: ZirProgram<'ast, T, M>) -> ZirProgram<'ast, T, M> {
         fold_program(self, p)
     }
 
-    fn fold_module(&mut self, p: ZirModule<'ast, T>) -> ZirModule<'ast, T> {
+    fn fold_module(&mut self, p: ZirModule<'ast, T, M>) -> ZirModule<'ast, T, M> {
         fold_module(self, p)
     }
 
     fn fold_function_symbol(
         &mut self,
-        s: ZirFunctionSymbol<'ast, T>,
-    ) -> ZirFunctionSymbol<'ast, T> {
+        s: ZirFunctionSymbol<'ast, T, M>,
+    ) -> ZirFunctionSymbol<'ast, T, M> {
         fold_function_symbol(self, s)
     }
 
-    fn fold_function(&mut self, f: ZirFunction<'ast, T>) -> ZirFunction<'ast, T> {
+    fn fold_function(&mut self, f: ZirFunction<'ast, T, M>) -> ZirFunction<'ast, T, M> {
         fold_function(self, f)
     }
 
@@ -46,11 +46,11 @@ pub trait Folder<'ast, T: Field>: Sized {
         self.fold_variable(a)
     }
 
-    fn fold_statement(&mut self, s: ZirStatement<'ast, T>) -> Vec<ZirStatement<'ast, T>> {
+    fn fold_statement(&mut self, s: ZirStatement<'ast, T, M>) -> Vec<ZirStatement<'ast, T, M>> {
         fold_statement(self, s)
     }
 
-    fn fold_expression(&mut self, e: ZirExpression<'ast, T>) -> ZirExpression<'ast, T> {
+    fn fold_expression(&mut self, e: ZirExpression<'ast, T, M>) -> ZirExpression<'ast, T, M> {
         match e {
             ZirExpression::FieldElement(e) => self.fold_field_expression(e).into(),
             ZirExpression::Boolean(e) => self.fold_boolean_expression(e).into(),
@@ -60,8 +60,8 @@ pub trait Folder<'ast, T: Field>: Sized {
 
     fn fold_expression_list(
         &mut self,
-        es: ZirExpressionList<'ast, T>,
-    ) -> ZirExpressionList<'ast, T> {
+        es: ZirExpressionList<'ast, T, M>,
+    ) -> ZirExpressionList<'ast, T, M> {
         match es {
             ZirExpressionList::FunctionCall(id, arguments, types) => {
                 ZirExpressionList::FunctionCall(
@@ -78,33 +78,33 @@ pub trait Folder<'ast, T: Field>: Sized {
 
     fn fold_field_expression(
         &mut self,
-        e: FieldElementExpression<'ast, T>,
-    ) -> FieldElementExpression<'ast, T> {
+        e: FieldElementExpression<'ast, T, M>,
+    ) -> FieldElementExpression<'ast, T, M> {
         fold_field_expression(self, e)
     }
     fn fold_boolean_expression(
         &mut self,
-        e: BooleanExpression<'ast, T>,
-    ) -> BooleanExpression<'ast, T> {
+        e: BooleanExpression<'ast, T, M>,
+    ) -> BooleanExpression<'ast, T, M> {
         fold_boolean_expression(self, e)
     }
-    fn fold_uint_expression(&mut self, e: UExpression<'ast, T>) -> UExpression<'ast, T> {
+    fn fold_uint_expression(&mut self, e: UExpression<'ast, T, M>) -> UExpression<'ast, T, M> {
         fold_uint_expression(self, e)
     }
 
     fn fold_uint_expression_inner(
         &mut self,
         bitwidth: UBitwidth,
-        e: UExpressionInner<'ast, T>,
-    ) -> UExpressionInner<'ast, T> {
+        e: UExpressionInner<'ast, T, M>,
+    ) -> UExpressionInner<'ast, T, M> {
         fold_uint_expression_inner(self, bitwidth, e)
     }
 }
 
-pub fn fold_module<'ast, T: Field, F: Folder<'ast, T>>(
+pub fn fold_module<'ast, T: Field, M, F: Folder<'ast, T, M>>(
     f: &mut F,
-    p: ZirModule<'ast, T>,
-) -> ZirModule<'ast, T> {
+    p: ZirModule<'ast, T, M>,
+) -> ZirModule<'ast, T, M> {
     ZirModule {
         functions: p
             .functions
@@ -115,10 +115,10 @@ pub fn fold_module<'ast, T: Field, F: Folder<'ast, T>>(
     }
 }
 
-pub fn fold_statement<'ast, T: Field, F: Folder<'ast, T>>(
+pub fn fold_statement<'ast, T: Field, M, F: Folder<'ast, T, M>>(
     f: &mut F,
-    s: ZirStatement<'ast, T>,
-) -> Vec<ZirStatement<'ast, T>> {
+    s: ZirStatement<'ast, T, M>,
+) -> Vec<ZirStatement<'ast, T, M>> {
     let res = match s {
         ZirStatement::Return(expressions) => ZirStatement::Return(
             expressions
@@ -139,10 +139,10 @@ pub fn fold_statement<'ast, T: Field, F: Folder<'ast, T>>(
     vec![res]
 }
 
-pub fn fold_field_expression<'ast, T: Field, F: Folder<'ast, T>>(
+pub fn fold_field_expression<'ast, T: Field, M, F: Folder<'ast, T, M>>(
     f: &mut F,
-    e: FieldElementExpression<'ast, T>,
-) -> FieldElementExpression<'ast, T> {
+    e: FieldElementExpression<'ast, T, M>,
+) -> FieldElementExpression<'ast, T, M> {
     match e {
         FieldElementExpression::Number(n) => FieldElementExpression::Number(n),
         FieldElementExpression::Identifier(id) => {
@@ -182,10 +182,10 @@ pub fn fold_field_expression<'ast, T: Field, F: Folder<'ast, T>>(
     }
 }
 
-pub fn fold_boolean_expression<'ast, T: Field, F: Folder<'ast, T>>(
+pub fn fold_boolean_expression<'ast, T: Field, M, F: Folder<'ast, T, M>>(
     f: &mut F,
-    e: BooleanExpression<'ast, T>,
-) -> BooleanExpression<'ast, T> {
+    e: BooleanExpression<'ast, T, M>,
+) -> BooleanExpression<'ast, T, M> {
     match e {
         BooleanExpression::Value(v) => BooleanExpression::Value(v),
         BooleanExpression::Identifier(id) => BooleanExpression::Identifier(f.fold_name(id)),
@@ -247,21 +247,21 @@ pub fn fold_boolean_expression<'ast, T: Field, F: Folder<'ast, T>>(
     }
 }
 
-pub fn fold_uint_expression<'ast, T: Field, F: Folder<'ast, T>>(
+pub fn fold_uint_expression<'ast, T: Field, M, F: Folder<'ast, T, M>>(
     f: &mut F,
-    e: UExpression<'ast, T>,
-) -> UExpression<'ast, T> {
+    e: UExpression<'ast, T, M>,
+) -> UExpression<'ast, T, M> {
     UExpression {
         inner: f.fold_uint_expression_inner(e.bitwidth, e.inner),
         ..e
     }
 }
 
-pub fn fold_uint_expression_inner<'ast, T: Field, F: Folder<'ast, T>>(
+pub fn fold_uint_expression_inner<'ast, T: Field, M, F: Folder<'ast, T, M>>(
     f: &mut F,
     _: UBitwidth,
-    e: UExpressionInner<'ast, T>,
-) -> UExpressionInner<'ast, T> {
+    e: UExpressionInner<'ast, T, M>,
+) -> UExpressionInner<'ast, T, M> {
     match e {
         UExpressionInner::Value(v) => UExpressionInner::Value(v),
         UExpressionInner::Identifier(id) => UExpressionInner::Identifier(f.fold_name(id)),
@@ -283,6 +283,18 @@ pub fn fold_uint_expression_inner<'ast, T: Field, F: Folder<'ast, T>>(
 
             UExpressionInner::Mult(box left, box right)
         }
+        UExpressionInner::Div(box left, box right) => {
+            let left = f.fold_uint_expression(left);
+            let right = f.fold_uint_expression(right);
+
+            UExpressionInner::Div(box left, box right)
+        }
+        UExpressionInner::Rem(box left, box right) => {
+            let left = f.fold_uint_expression(left);
+            let right = f.fold_uint_expression(right);
+
+            UExpressionInner::Rem(box left, box right)
+        }
         UExpressionInner::Xor(box left, box right) => {
             let left = f.fold_uint_expression(left);
             let right = f.fold_uint_expression(right);
@@ -327,10 +339,10 @@ pub fn fold_uint_expression_inner<'ast, T: Field, F: Folder<'ast, T>>(
     }
 }
 
-pub fn fold_function<'ast, T: Field, F: Folder<'ast, T>>(
+pub fn fold_function<'ast, T: Field, M, F: Folder<'ast, T, M>>(
     f: &mut F,
-    fun: ZirFunction<'ast, T>,
-) -> ZirFunction<'ast, T> {
+    fun: ZirFunction<'ast, T, M>,
+) -> ZirFunction<'ast, T, M> {
     ZirFunction {
         arguments: fun
             .arguments
@@ -346,20 +358,20 @@ pub fn fold_function<'ast, T: Field, F: Folder<'ast, T>>(
     }
 }
 
-pub fn fold_function_symbol<'ast, T: Field, F: Folder<'ast, T>>(
+pub fn fold_function_symbol<'ast, T: Field, M, F: Folder<'ast, T, M>>(
     f: &mut F,
-    s: ZirFunctionSymbol<'ast, T>,
-) -> ZirFunctionSymbol<'ast, T> {
+    s: ZirFunctionSymbol<'ast, T, M>,
+) -> ZirFunctionSymbol<'ast, T, M> {
     match s {
         ZirFunctionSymbol::Here(fun) => ZirFunctionSymbol::Here(f.fold_function(fun)),
         there => there, // by default, do not fold modules recursively
     }
 }
 
-pub fn fold_program<'ast, T: Field, F: Folder<'ast, T>>(
+pub fn fold_program<'ast, T: Field, M, F: Folder<'ast, T, M>>(
     f: &mut F,
-    p: ZirProgram<'ast, T>,
-) -> ZirProgram<'ast, T> {
+    p: ZirProgram<'ast, T, M>,
+) -> ZirProgram<'ast, T, M> {
     ZirProgram {
         modules: p
             .modules
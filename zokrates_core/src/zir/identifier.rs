@@ -16,6 +16,27 @@ pub enum SourceIdentifier<'ast> {
     Member(Box<SourceIdentifier<'ast>>, MemberId),
 }
 
+impl<'ast> SourceIdentifier<'ast> {
+    /// Returns the name of this identifier in the source of its function, such as `a[1].b`, and the number of its
+    /// assignment, or `None` if it was inlined from a call
+    pub fn source_name(&self) -> Option<(String, usize)> {
+        match self {
+            SourceIdentifier::Basic(id) => match (&id.id, id.stack.len()) {
+                (crate::typed_absy::CoreIdentifier::Source(name), 0) => {
+                    Some((name.to_string(), id.version))
+                }
+                _ => None,
+            },
+            SourceIdentifier::Select(box id, index) => id
+                .source_name()
+                .map(|(name, version)| (format!("{}[{}]", name, index), version)),
+            SourceIdentifier::Member(box id, member) => id
+                .source_name()
+                .map(|(name, version)| (format!("{}.{}", name, member), version)),
+        }
+    }
+}
+
 impl<'ast> fmt::Display for SourceIdentifier<'ast> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
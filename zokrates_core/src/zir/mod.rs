@@ -2,6 +2,7 @@ pub mod folder;
 mod from_typed;
 mod identifier;
 mod parameter;
+pub mod ranged;
 pub mod types;
 mod uint;
 mod variable;
@@ -10,7 +11,7 @@ pub use self::parameter::Parameter;
 pub use self::types::Type;
 pub use self::variable::Variable;
 use std::path::PathBuf;
-pub use zir::uint::{ShouldReduce, UExpression, UExpressionInner, UMetadata};
+pub use zir::uint::{multiplies_on_limbs, UExpression, UExpressionInner, UMetadata};
 
 use embed::FlatEmbed;
 use std::collections::HashMap;
@@ -27,22 +28,23 @@ pub use self::identifier::{Identifier, SourceIdentifier};
 pub type ZirModuleId = PathBuf;
 
 /// A collection of `ZirModule`s
-pub type ZirModules<'ast, T> = HashMap<ZirModuleId, ZirModule<'ast, T>>;
+pub type ZirModules<'ast, T, M = ()> = HashMap<ZirModuleId, ZirModule<'ast, T, M>>;
 
 /// A collection of `ZirFunctionSymbol`s
 /// # Remarks
 /// * It is the role of the semantic checker to make sure there are no duplicates for a given `FunctionKey`
 ///   in a given `ZirModule`, hence the use of a HashMap
-pub type ZirFunctionSymbols<'ast, T> = HashMap<FunctionKey<'ast>, ZirFunctionSymbol<'ast, T>>;
+pub type ZirFunctionSymbols<'ast, T, M = ()> =
+    HashMap<FunctionKey<'ast>, ZirFunctionSymbol<'ast, T, M>>;
 
 /// A typed program as a collection of modules, one of them being the main
 #[derive(PartialEq, Debug)]
-pub struct ZirProgram<'ast, T> {
-    pub modules: ZirModules<'ast, T>,
+pub struct ZirProgram<'ast, T, M = ()> {
+    pub modules: ZirModules<'ast, T, M>,
     pub main: ZirModuleId,
 }
 
-impl<'ast, T: fmt::Display> fmt::Display for ZirProgram<'ast, T> {
+impl<'ast, T: fmt::Display, M> fmt::Display for ZirProgram<'ast, T, M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for (module_id, module) in &self.modules {
             writeln!(
@@ -66,20 +68,20 @@ impl<'ast, T: fmt::Display> fmt::Display for ZirProgram<'ast, T> {
 
 /// A typed program as a collection of functions. Types have been resolved during semantic checking.
 #[derive(PartialEq, Clone)]
-pub struct ZirModule<'ast, T> {
+pub struct ZirModule<'ast, T, M = ()> {
     /// Functions of the program
-    pub functions: ZirFunctionSymbols<'ast, T>,
+    pub functions: ZirFunctionSymbols<'ast, T, M>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub enum ZirFunctionSymbol<'ast, T> {
-    Here(ZirFunction<'ast, T>),
+pub enum ZirFunctionSymbol<'ast, T, M = ()> {
+    Here(ZirFunction<'ast, T, M>),
     There(FunctionKey<'ast>, ZirModuleId),
     Flat(FlatEmbed),
 }
 
-impl<'ast, T> ZirFunctionSymbol<'ast, T> {
-    pub fn signature<'a>(&'a self, modules: &'a ZirModules<T>) -> Signature {
+impl<'ast, T, M> ZirFunctionSymbol<'ast, T, M> {
+    pub fn signature<'a>(&'a self, modules: &'a ZirModules<'ast, T, M>) -> Signature {
         match self {
             ZirFunctionSymbol::Here(f) => f.signature.clone(),
             ZirFunctionSymbol::There(key, module_id) => modules
@@ -95,7 +97,7 @@ impl<'ast, T> ZirFunctionSymbol<'ast, T> {
     }
 }
 
-impl<'ast, T: fmt::Display> fmt::Display for ZirModule<'ast, T> {
+impl<'ast, T: fmt::Display, M> fmt::Display for ZirModule<'ast, T, M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let res = self
             .functions
@@ -118,7 +120,7 @@ impl<'ast, T: fmt::Display> fmt::Display for ZirModule<'ast, T> {
     }
 }
 
-impl<'ast, T: fmt::Debug> fmt::Debug for ZirModule<'ast, T> {
+impl<'ast, T: fmt::Debug, M: fmt::Debug> fmt::Debug for ZirModule<'ast, T, M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
@@ -134,16 +136,16 @@ impl<'ast, T: fmt::Debug> fmt::Debug for ZirModule<'ast, T> {
 
 /// A typed function
 #[derive(Clone, PartialEq)]
-pub struct ZirFunction<'ast, T> {
+pub struct ZirFunction<'ast, T, M = ()> {
     /// Arguments of the function
     pub arguments: Vec<Parameter<'ast>>,
     /// Vector of statements that are executed when running the function
-    pub statements: Vec<ZirStatement<'ast, T>>,
+    pub statements: Vec<ZirStatement<'ast, T, M>>,
     /// function signature
     pub signature: Signature,
 }
 
-impl<'ast, T: fmt::Display> fmt::Display for ZirFunction<'ast, T> {
+impl<'ast, T: fmt::Display, M> fmt::Display for ZirFunction<'ast, T, M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
@@ -168,7 +170,7 @@ impl<'ast, T: fmt::Display> fmt::Display for ZirFunction<'ast, T> {
     }
 }
 
-impl<'ast, T: fmt::Debug> fmt::Debug for ZirFunction<'ast, T> {
+impl<'ast, T: fmt::Debug, M: fmt::Debug> fmt::Debug for ZirFunction<'ast, T, M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
@@ -187,15 +189,15 @@ pub type ZirAssignee<'ast> = Variable<'ast>;
 
 /// A statement in a `ZirFunction`
 #[derive(Clone, PartialEq, Hash, Eq)]
-pub enum ZirStatement<'ast, T> {
-    Return(Vec<ZirExpression<'ast, T>>),
-    Definition(ZirAssignee<'ast>, ZirExpression<'ast, T>),
+pub enum ZirStatement<'ast, T, M = ()> {
+    Return(Vec<ZirExpression<'ast, T, M>>),
+    Definition(ZirAssignee<'ast>, ZirExpression<'ast, T, M>),
     Declaration(Variable<'ast>),
-    Assertion(BooleanExpression<'ast, T>),
-    MultipleDefinition(Vec<Variable<'ast>>, ZirExpressionList<'ast, T>),
+    Assertion(BooleanExpression<'ast, T, M>),
+    MultipleDefinition(Vec<Variable<'ast>>, ZirExpressionList<'ast, T, M>),
 }
 
-impl<'ast, T: fmt::Debug> fmt::Debug for ZirStatement<'ast, T> {
+impl<'ast, T: fmt::Debug, M: fmt::Debug> fmt::Debug for ZirStatement<'ast, T, M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             ZirStatement::Return(ref exprs) => {
@@ -220,7 +222,7 @@ impl<'ast, T: fmt::Debug> fmt::Debug for ZirStatement<'ast, T> {
     }
 }
 
-impl<'ast, T: fmt::Display> fmt::Display for ZirStatement<'ast, T> {
+impl<'ast, T: fmt::Display, M> fmt::Display for ZirStatement<'ast, T, M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             ZirStatement::Return(ref exprs) => {
@@ -255,31 +257,31 @@ pub trait Typed {
 
 /// A typed expression
 #[derive(Clone, PartialEq, Hash, Eq)]
-pub enum ZirExpression<'ast, T> {
-    Boolean(BooleanExpression<'ast, T>),
-    FieldElement(FieldElementExpression<'ast, T>),
-    Uint(UExpression<'ast, T>),
+pub enum ZirExpression<'ast, T, M = ()> {
+    Boolean(BooleanExpression<'ast, T, M>),
+    FieldElement(FieldElementExpression<'ast, T, M>),
+    Uint(UExpression<'ast, T, M>),
 }
 
-impl<'ast, T: Field> From<BooleanExpression<'ast, T>> for ZirExpression<'ast, T> {
-    fn from(e: BooleanExpression<'ast, T>) -> ZirExpression<T> {
+impl<'ast, T: Field, M> From<BooleanExpression<'ast, T, M>> for ZirExpression<'ast, T, M> {
+    fn from(e: BooleanExpression<'ast, T, M>) -> ZirExpression<'ast, T, M> {
         ZirExpression::Boolean(e)
     }
 }
 
-impl<'ast, T: Field> From<FieldElementExpression<'ast, T>> for ZirExpression<'ast, T> {
-    fn from(e: FieldElementExpression<'ast, T>) -> ZirExpression<T> {
+impl<'ast, T: Field, M> From<FieldElementExpression<'ast, T, M>> for ZirExpression<'ast, T, M> {
+    fn from(e: FieldElementExpression<'ast, T, M>) -> ZirExpression<'ast, T, M> {
         ZirExpression::FieldElement(e)
     }
 }
 
-impl<'ast, T: Field> From<UExpression<'ast, T>> for ZirExpression<'ast, T> {
-    fn from(e: UExpression<'ast, T>) -> ZirExpression<T> {
+impl<'ast, T: Field, M> From<UExpression<'ast, T, M>> for ZirExpression<'ast, T, M> {
+    fn from(e: UExpression<'ast, T, M>) -> ZirExpression<'ast, T, M> {
         ZirExpression::Uint(e)
     }
 }
 
-impl<'ast, T: fmt::Display> fmt::Display for ZirExpression<'ast, T> {
+impl<'ast, T: fmt::Display, M> fmt::Display for ZirExpression<'ast, T, M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             ZirExpression::Boolean(ref e) => write!(f, "{}", e),
@@ -289,7 +291,7 @@ impl<'ast, T: fmt::Display> fmt::Display for ZirExpression<'ast, T> {
     }
 }
 
-impl<'ast, T: fmt::Debug> fmt::Debug for ZirExpression<'ast, T> {
+impl<'ast, T: fmt::Debug, M: fmt::Debug> fmt::Debug for ZirExpression<'ast, T, M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             ZirExpression::Boolean(ref e) => write!(f, "{:?}", e),
@@ -299,7 +301,7 @@ impl<'ast, T: fmt::Debug> fmt::Debug for ZirExpression<'ast, T> {
     }
 }
 
-impl<'ast, T: Field> Typed for ZirExpression<'ast, T> {
+impl<'ast, T: Field, M> Typed for ZirExpression<'ast, T, M> {
     fn get_type(&self) -> Type {
         match *self {
             ZirExpression::Boolean(ref e) => e.get_type(),
@@ -309,19 +311,19 @@ impl<'ast, T: Field> Typed for ZirExpression<'ast, T> {
     }
 }
 
-impl<'ast, T: Field> Typed for FieldElementExpression<'ast, T> {
+impl<'ast, T: Field, M> Typed for FieldElementExpression<'ast, T, M> {
     fn get_type(&self) -> Type {
         Type::FieldElement
     }
 }
 
-impl<'ast, T: Field> Typed for UExpression<'ast, T> {
+impl<'ast, T: Field, M> Typed for UExpression<'ast, T, M> {
     fn get_type(&self) -> Type {
         Type::Uint(self.bitwidth)
     }
 }
 
-impl<'ast, T: Field> Typed for BooleanExpression<'ast, T> {
+impl<'ast, T: Field, M> Typed for BooleanExpression<'ast, T, M> {
     fn get_type(&self) -> Type {
         Type::Boolean
     }
@@ -332,11 +334,11 @@ pub trait MultiTyped {
 }
 
 #[derive(Clone, PartialEq, Hash, Eq)]
-pub enum ZirExpressionList<'ast, T> {
-    FunctionCall(FunctionKey<'ast>, Vec<ZirExpression<'ast, T>>, Vec<Type>),
+pub enum ZirExpressionList<'ast, T, M = ()> {
+    FunctionCall(FunctionKey<'ast>, Vec<ZirExpression<'ast, T, M>>, Vec<Type>),
 }
 
-impl<'ast, T: Field> MultiTyped for ZirExpressionList<'ast, T> {
+impl<'ast, T: Field, M> MultiTyped for ZirExpressionList<'ast, T, M> {
     fn get_types(&self) -> &Vec<Type> {
         match *self {
             ZirExpressionList::FunctionCall(_, _, ref types) => types,
@@ -346,89 +348,89 @@ impl<'ast, T: Field> MultiTyped for ZirExpressionList<'ast, T> {
 
 /// An expression of type `field`
 #[derive(Clone, PartialEq, Hash, Eq)]
-pub enum FieldElementExpression<'ast, T> {
+pub enum FieldElementExpression<'ast, T, M = ()> {
     Number(T),
     Identifier(Identifier<'ast>),
     Add(
-        Box<FieldElementExpression<'ast, T>>,
-        Box<FieldElementExpression<'ast, T>>,
+        Box<FieldElementExpression<'ast, T, M>>,
+        Box<FieldElementExpression<'ast, T, M>>,
     ),
     Sub(
-        Box<FieldElementExpression<'ast, T>>,
-        Box<FieldElementExpression<'ast, T>>,
+        Box<FieldElementExpression<'ast, T, M>>,
+        Box<FieldElementExpression<'ast, T, M>>,
     ),
     Mult(
-        Box<FieldElementExpression<'ast, T>>,
-        Box<FieldElementExpression<'ast, T>>,
+        Box<FieldElementExpression<'ast, T, M>>,
+        Box<FieldElementExpression<'ast, T, M>>,
     ),
     Div(
-        Box<FieldElementExpression<'ast, T>>,
-        Box<FieldElementExpression<'ast, T>>,
+        Box<FieldElementExpression<'ast, T, M>>,
+        Box<FieldElementExpression<'ast, T, M>>,
     ),
     Pow(
-        Box<FieldElementExpression<'ast, T>>,
-        Box<FieldElementExpression<'ast, T>>,
+        Box<FieldElementExpression<'ast, T, M>>,
+        Box<FieldElementExpression<'ast, T, M>>,
     ),
     IfElse(
-        Box<BooleanExpression<'ast, T>>,
-        Box<FieldElementExpression<'ast, T>>,
-        Box<FieldElementExpression<'ast, T>>,
+        Box<BooleanExpression<'ast, T, M>>,
+        Box<FieldElementExpression<'ast, T, M>>,
+        Box<FieldElementExpression<'ast, T, M>>,
     ),
 }
 
 /// An expression of type `bool`
 #[derive(Clone, PartialEq, Hash, Eq)]
-pub enum BooleanExpression<'ast, T> {
+pub enum BooleanExpression<'ast, T, M = ()> {
     Identifier(Identifier<'ast>),
     Value(bool),
     Lt(
-        Box<FieldElementExpression<'ast, T>>,
-        Box<FieldElementExpression<'ast, T>>,
+        Box<FieldElementExpression<'ast, T, M>>,
+        Box<FieldElementExpression<'ast, T, M>>,
     ),
     Le(
-        Box<FieldElementExpression<'ast, T>>,
-        Box<FieldElementExpression<'ast, T>>,
+        Box<FieldElementExpression<'ast, T, M>>,
+        Box<FieldElementExpression<'ast, T, M>>,
     ),
     FieldEq(
-        Box<FieldElementExpression<'ast, T>>,
-        Box<FieldElementExpression<'ast, T>>,
+        Box<FieldElementExpression<'ast, T, M>>,
+        Box<FieldElementExpression<'ast, T, M>>,
     ),
     BoolEq(
-        Box<BooleanExpression<'ast, T>>,
-        Box<BooleanExpression<'ast, T>>,
+        Box<BooleanExpression<'ast, T, M>>,
+        Box<BooleanExpression<'ast, T, M>>,
     ),
-    UintEq(Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>),
+    UintEq(Box<UExpression<'ast, T, M>>, Box<UExpression<'ast, T, M>>),
     Ge(
-        Box<FieldElementExpression<'ast, T>>,
-        Box<FieldElementExpression<'ast, T>>,
+        Box<FieldElementExpression<'ast, T, M>>,
+        Box<FieldElementExpression<'ast, T, M>>,
     ),
     Gt(
-        Box<FieldElementExpression<'ast, T>>,
-        Box<FieldElementExpression<'ast, T>>,
+        Box<FieldElementExpression<'ast, T, M>>,
+        Box<FieldElementExpression<'ast, T, M>>,
     ),
     Or(
-        Box<BooleanExpression<'ast, T>>,
-        Box<BooleanExpression<'ast, T>>,
+        Box<BooleanExpression<'ast, T, M>>,
+        Box<BooleanExpression<'ast, T, M>>,
     ),
     And(
-        Box<BooleanExpression<'ast, T>>,
-        Box<BooleanExpression<'ast, T>>,
+        Box<BooleanExpression<'ast, T, M>>,
+        Box<BooleanExpression<'ast, T, M>>,
     ),
-    Not(Box<BooleanExpression<'ast, T>>),
+    Not(Box<BooleanExpression<'ast, T, M>>),
     IfElse(
-        Box<BooleanExpression<'ast, T>>,
-        Box<BooleanExpression<'ast, T>>,
-        Box<BooleanExpression<'ast, T>>,
+        Box<BooleanExpression<'ast, T, M>>,
+        Box<BooleanExpression<'ast, T, M>>,
+        Box<BooleanExpression<'ast, T, M>>,
     ),
 }
 
 // Downcasts
-impl<'ast, T> TryFrom<ZirExpression<'ast, T>> for FieldElementExpression<'ast, T> {
+impl<'ast, T, M> TryFrom<ZirExpression<'ast, T, M>> for FieldElementExpression<'ast, T, M> {
     type Error = ();
 
     fn try_from(
-        te: ZirExpression<'ast, T>,
-    ) -> Result<FieldElementExpression<'ast, T>, Self::Error> {
+        te: ZirExpression<'ast, T, M>,
+    ) -> Result<FieldElementExpression<'ast, T, M>, Self::Error> {
         match te {
             ZirExpression::FieldElement(e) => Ok(e),
             _ => Err(()),
@@ -436,10 +438,12 @@ impl<'ast, T> TryFrom<ZirExpression<'ast, T>> for FieldElementExpression<'ast, T
     }
 }
 
-impl<'ast, T> TryFrom<ZirExpression<'ast, T>> for BooleanExpression<'ast, T> {
+impl<'ast, T, M> TryFrom<ZirExpression<'ast, T, M>> for BooleanExpression<'ast, T, M> {
     type Error = ();
 
-    fn try_from(te: ZirExpression<'ast, T>) -> Result<BooleanExpression<'ast, T>, Self::Error> {
+    fn try_from(
+        te: ZirExpression<'ast, T, M>,
+    ) -> Result<BooleanExpression<'ast, T, M>, Self::Error> {
         match te {
             ZirExpression::Boolean(e) => Ok(e),
             _ => Err(()),
@@ -447,10 +451,10 @@ impl<'ast, T> TryFrom<ZirExpression<'ast, T>> for BooleanExpression<'ast, T> {
     }
 }
 
-impl<'ast, T> TryFrom<ZirExpression<'ast, T>> for UExpression<'ast, T> {
+impl<'ast, T, M> TryFrom<ZirExpression<'ast, T, M>> for UExpression<'ast, T, M> {
     type Error = ();
 
-    fn try_from(te: ZirExpression<'ast, T>) -> Result<UExpression<'ast, T>, Self::Error> {
+    fn try_from(te: ZirExpression<'ast, T, M>) -> Result<UExpression<'ast, T, M>, Self::Error> {
         match te {
             ZirExpression::Uint(e) => Ok(e),
             _ => Err(()),
@@ -458,7 +462,7 @@ impl<'ast, T> TryFrom<ZirExpression<'ast, T>> for UExpression<'ast, T> {
     }
 }
 
-impl<'ast, T: fmt::Display> fmt::Display for FieldElementExpression<'ast, T> {
+impl<'ast, T: fmt::Display, M> fmt::Display for FieldElementExpression<'ast, T, M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             FieldElementExpression::Number(ref i) => write!(f, "{}", i),
@@ -479,7 +483,7 @@ impl<'ast, T: fmt::Display> fmt::Display for FieldElementExpression<'ast, T> {
     }
 }
 
-impl<'ast, T: fmt::Display> fmt::Display for UExpression<'ast, T> {
+impl<'ast, T: fmt::Display, M> fmt::Display for UExpression<'ast, T, M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.inner {
             UExpressionInner::Value(ref v) => write!(f, "{}", v),
@@ -487,6 +491,8 @@ impl<'ast, T: fmt::Display> fmt::Display for UExpression<'ast, T> {
             UExpressionInner::Add(ref lhs, ref rhs) => write!(f, "({} + {})", lhs, rhs),
             UExpressionInner::Sub(ref lhs, ref rhs) => write!(f, "({} - {})", lhs, rhs),
             UExpressionInner::Mult(ref lhs, ref rhs) => write!(f, "({} * {})", lhs, rhs),
+            UExpressionInner::Div(ref lhs, ref rhs) => write!(f, "({} / {})", lhs, rhs),
+            UExpressionInner::Rem(ref lhs, ref rhs) => write!(f, "({} % {})", lhs, rhs),
             UExpressionInner::Xor(ref lhs, ref rhs) => write!(f, "({} ^ {})", lhs, rhs),
             UExpressionInner::And(ref lhs, ref rhs) => write!(f, "({} & {})", lhs, rhs),
             UExpressionInner::Or(ref lhs, ref rhs) => write!(f, "({} | {})", lhs, rhs),
@@ -502,7 +508,7 @@ impl<'ast, T: fmt::Display> fmt::Display for UExpression<'ast, T> {
     }
 }
 
-impl<'ast, T: fmt::Display> fmt::Display for BooleanExpression<'ast, T> {
+impl<'ast, T: fmt::Display, M> fmt::Display for BooleanExpression<'ast, T, M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             BooleanExpression::Identifier(ref var) => write!(f, "{}", var),
@@ -526,13 +532,13 @@ impl<'ast, T: fmt::Display> fmt::Display for BooleanExpression<'ast, T> {
     }
 }
 
-impl<'ast, T: fmt::Debug> fmt::Debug for BooleanExpression<'ast, T> {
+impl<'ast, T: fmt::Debug, M: fmt::Debug> fmt::Debug for BooleanExpression<'ast, T, M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self)
     }
 }
 
-impl<'ast, T: fmt::Debug> fmt::Debug for FieldElementExpression<'ast, T> {
+impl<'ast, T: fmt::Debug, M: fmt::Debug> fmt::Debug for FieldElementExpression<'ast, T, M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             FieldElementExpression::Number(ref i) => write!(f, "Num({:?})", i),
@@ -555,7 +561,7 @@ impl<'ast, T: fmt::Debug> fmt::Debug for FieldElementExpression<'ast, T> {
     }
 }
 
-impl<'ast, T: fmt::Display> fmt::Display for ZirExpressionList<'ast, T> {
+impl<'ast, T: fmt::Display, M> fmt::Display for ZirExpressionList<'ast, T, M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             ZirExpressionList::FunctionCall(ref key, ref p, _) => {
@@ -572,7 +578,7 @@ impl<'ast, T: fmt::Display> fmt::Display for ZirExpressionList<'ast, T> {
     }
 }
 
-impl<'ast, T: fmt::Debug> fmt::Debug for ZirExpressionList<'ast, T> {
+impl<'ast, T: fmt::Debug, M: fmt::Debug> fmt::Debug for ZirExpressionList<'ast, T, M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             ZirExpressionList::FunctionCall(ref i, ref p, _) => {
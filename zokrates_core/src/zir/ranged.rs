@@ -0,0 +1,13 @@
+//! The zir AST annotated by the range analysis, in which every uint expression carries its `UMetadata`
+
+use super::UMetadata;
+
+pub type ZirProgram<'ast, T> = super::ZirProgram<'ast, T, UMetadata<T>>;
+pub type ZirModule<'ast, T> = super::ZirModule<'ast, T, UMetadata<T>>;
+pub type ZirFunctionSymbols<'ast, T> = super::ZirFunctionSymbols<'ast, T, UMetadata<T>>;
+pub type ZirFunction<'ast, T> = super::ZirFunction<'ast, T, UMetadata<T>>;
+pub type ZirStatement<'ast, T> = super::ZirStatement<'ast, T, UMetadata<T>>;
+pub type ZirExpression<'ast, T> = super::ZirExpression<'ast, T, UMetadata<T>>;
+pub type FieldElementExpression<'ast, T> = super::FieldElementExpression<'ast, T, UMetadata<T>>;
+pub type BooleanExpression<'ast, T> = super::BooleanExpression<'ast, T, UMetadata<T>>;
+pub type UExpression<'ast, T> = super::UExpression<'ast, T, UMetadata<T>>;
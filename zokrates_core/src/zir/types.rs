@@ -19,6 +19,10 @@ pub enum UBitwidth {
     B16 = 16,
     #[serde(rename = "32")]
     B32 = 32,
+    #[serde(rename = "64")]
+    B64 = 64,
+    #[serde(rename = "128")]
+    B128 = 128,
 }
 
 impl UBitwidth {
@@ -27,6 +31,8 @@ impl UBitwidth {
             UBitwidth::B8 => 8,
             UBitwidth::B16 => 16,
             UBitwidth::B32 => 32,
+            UBitwidth::B64 => 64,
+            UBitwidth::B128 => 128,
         }
     }
 }
@@ -37,6 +43,8 @@ impl From<usize> for UBitwidth {
             8 => UBitwidth::B8,
             16 => UBitwidth::B16,
             32 => UBitwidth::B32,
+            64 => UBitwidth::B64,
+            128 => UBitwidth::B128,
             _ => unreachable!(),
         }
     }
@@ -1,3 +1,4 @@
+use crate::static_analysis::Range;
 use zir::identifier::Identifier;
 use zir::types::UBitwidth;
 use zir::{BooleanExpression, FieldElementExpression};
@@ -22,6 +23,18 @@ impl<'ast, T: Field> UExpression<'ast, T> {
         UExpressionInner::Mult(box self, box other).annotate(bitwidth)
     }
 
+    pub fn div(self, other: Self) -> UExpression<'ast, T> {
+        let bitwidth = self.bitwidth;
+        assert_eq!(bitwidth, other.bitwidth);
+        UExpressionInner::Div(box self, box other).annotate(bitwidth)
+    }
+
+    pub fn rem(self, other: Self) -> UExpression<'ast, T> {
+        let bitwidth = self.bitwidth;
+        assert_eq!(bitwidth, other.bitwidth);
+        UExpressionInner::Rem(box self, box other).annotate(bitwidth)
+    }
+
     pub fn xor(self, other: Self) -> UExpression<'ast, T> {
         let bitwidth = self.bitwidth;
         assert_eq!(bitwidth, other.bitwidth);
@@ -56,153 +69,157 @@ impl<'ast, T: Field> UExpression<'ast, T> {
     }
 }
 
-impl<'ast, T: Field> From<u128> for UExpressionInner<'ast, T> {
+impl<'ast, T: Field, M> From<u128> for UExpressionInner<'ast, T, M> {
     fn from(e: u128) -> Self {
         UExpressionInner::Value(e)
     }
 }
 
-impl<'ast, T: Field> From<&'ast str> for UExpressionInner<'ast, T> {
+impl<'ast, T: Field, M> From<&'ast str> for UExpressionInner<'ast, T, M> {
     fn from(e: &'ast str) -> Self {
         UExpressionInner::Identifier(e.into())
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
-pub enum ShouldReduce {
-    Unknown,
-    True,
-    False,
-}
-
-impl ShouldReduce {
-    pub fn to_bool(&self) -> bool {
-        match self {
-            ShouldReduce::Unknown => {
-                unreachable!("should_reduce should be convertible to a bool but it's unknown")
-            }
-            ShouldReduce::True => true,
-            ShouldReduce::False => false,
-        }
-    }
-
-    pub fn is_unknown(&self) -> bool {
-        match self {
-            ShouldReduce::Unknown => true,
-            _ => false,
-        }
-    }
-
-    // we can always enable a reduction
-    pub fn make_true(self) -> Self {
-        ShouldReduce::True
-    }
-
-    // we cannot disable a reduction that was enabled
-    pub fn make_false(self) -> Self {
-        match self {
-            ShouldReduce::True => unreachable!("Attempt to disable a required reduction"),
-            _ => ShouldReduce::False,
-        }
-    }
+/// Returns whether the product of two reduced values of `bitwidth` bits does not fit in the field, in which case it is
+/// computed on limbs of half the bitwidth, dropping the product of the high limbs which overflows anyway
+pub fn multiplies_on_limbs<T: Field>(bitwidth: usize) -> bool {
+    let max = T::from(2).pow(bitwidth) - T::from(1);
+    max.checked_mul(&max).is_none()
 }
 
+/// The metadata of a uint expression, computed by the range analysis
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct UMetadata<T> {
-    pub max: T,
-    pub should_reduce: ShouldReduce,
+    pub range: Range<T>,
+    /// whether the value is reduced to its bitwidth where it is used
+    pub should_reduce: bool,
 }
 
 impl<T: Field> UMetadata<T> {
-    pub fn with_max<U: Into<T>>(max: U) -> Self {
+    pub fn with_range(range: Range<T>) -> Self {
         UMetadata {
-            max: max.into(),
-            should_reduce: ShouldReduce::Unknown,
+            range,
+            should_reduce: false,
         }
     }
 
-    pub fn bitwidth(&self) -> u32 {
-        self.max.bits() as u32
-    }
-
     // issue the metadata for a parameter of a given bitwidth
     pub fn parameter<W: Into<UBitwidth>>(bitwidth: W) -> Self {
         Self {
-            should_reduce: ShouldReduce::False,
-            max: T::from(2_u32).pow(bitwidth.into().to_usize()) - T::from(1),
+            should_reduce: false,
+            range: Range::reduced(bitwidth.into().to_usize()),
         }
     }
 }
 
+/// A uint expression, whose metadata is `()` until the range analysis annotates it with its `UMetadata`
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct UExpression<'ast, T> {
+pub struct UExpression<'ast, T, M = ()> {
     pub bitwidth: UBitwidth,
-    pub metadata: Option<UMetadata<T>>,
-    pub inner: UExpressionInner<'ast, T>,
+    pub(super) metadata: M,
+    pub inner: UExpressionInner<'ast, T, M>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub enum UExpressionInner<'ast, T> {
+pub enum UExpressionInner<'ast, T, M = ()> {
     Identifier(Identifier<'ast>),
     Value(u128),
-    Add(Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>),
-    Sub(Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>),
-    Mult(Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>),
-    Xor(Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>),
-    And(Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>),
-    Or(Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>),
+    Add(Box<UExpression<'ast, T, M>>, Box<UExpression<'ast, T, M>>),
+    Sub(Box<UExpression<'ast, T, M>>, Box<UExpression<'ast, T, M>>),
+    Mult(Box<UExpression<'ast, T, M>>, Box<UExpression<'ast, T, M>>),
+    Div(Box<UExpression<'ast, T, M>>, Box<UExpression<'ast, T, M>>),
+    Rem(Box<UExpression<'ast, T, M>>, Box<UExpression<'ast, T, M>>),
+    Xor(Box<UExpression<'ast, T, M>>, Box<UExpression<'ast, T, M>>),
+    And(Box<UExpression<'ast, T, M>>, Box<UExpression<'ast, T, M>>),
+    Or(Box<UExpression<'ast, T, M>>, Box<UExpression<'ast, T, M>>),
     LeftShift(
-        Box<UExpression<'ast, T>>,
-        Box<FieldElementExpression<'ast, T>>,
+        Box<UExpression<'ast, T, M>>,
+        Box<FieldElementExpression<'ast, T, M>>,
     ),
     RightShift(
-        Box<UExpression<'ast, T>>,
-        Box<FieldElementExpression<'ast, T>>,
+        Box<UExpression<'ast, T, M>>,
+        Box<FieldElementExpression<'ast, T, M>>,
     ),
-    Not(Box<UExpression<'ast, T>>),
+    Not(Box<UExpression<'ast, T, M>>),
     IfElse(
-        Box<BooleanExpression<'ast, T>>,
-        Box<UExpression<'ast, T>>,
-        Box<UExpression<'ast, T>>,
+        Box<BooleanExpression<'ast, T, M>>,
+        Box<UExpression<'ast, T, M>>,
+        Box<UExpression<'ast, T, M>>,
     ),
 }
 
 impl<'ast, T> UExpressionInner<'ast, T> {
     pub fn annotate<W: Into<UBitwidth>>(self, bitwidth: W) -> UExpression<'ast, T> {
         UExpression {
-            metadata: None,
+            metadata: (),
             bitwidth: bitwidth.into(),
             inner: self,
         }
     }
 }
 
-impl<'ast, T: Field> UExpression<'ast, T> {
-    pub fn metadata(self, metadata: UMetadata<T>) -> UExpression<'ast, T> {
+impl<'ast, T: Field> UExpressionInner<'ast, T, UMetadata<T>> {
+    /// Annotates an expression whose operands were analysed with its own metadata
+    pub fn with_metadata<W: Into<UBitwidth>>(
+        self,
+        bitwidth: W,
+        metadata: UMetadata<T>,
+    ) -> UExpression<'ast, T, UMetadata<T>> {
         UExpression {
-            metadata: Some(metadata),
-            ..self
+            metadata,
+            bitwidth: bitwidth.into(),
+            inner: self,
         }
     }
 
-    pub fn with_max<U: Into<T>>(self, max: U) -> Self {
-        UExpression {
-            metadata: Some(UMetadata::with_max(max)),
-            ..self
-        }
+    pub fn with_range<W: Into<UBitwidth>>(
+        self,
+        bitwidth: W,
+        range: Range<T>,
+    ) -> UExpression<'ast, T, UMetadata<T>> {
+        self.with_metadata(bitwidth, UMetadata::with_range(range))
+    }
+
+    pub fn with_max<W: Into<UBitwidth>, U: Into<T>>(
+        self,
+        bitwidth: W,
+        max: U,
+    ) -> UExpression<'ast, T, UMetadata<T>> {
+        self.with_range(bitwidth, Range::new(max))
     }
 }
 
-impl<'ast, T> UExpression<'ast, T> {
+impl<'ast, T, M> UExpression<'ast, T, M> {
     pub fn bitwidth(&self) -> UBitwidth {
         self.bitwidth
     }
 
-    pub fn as_inner(&self) -> &UExpressionInner<'ast, T> {
+    pub fn as_inner(&self) -> &UExpressionInner<'ast, T, M> {
         &self.inner
     }
 
-    pub fn into_inner(self) -> UExpressionInner<'ast, T> {
+    pub fn into_inner(self) -> UExpressionInner<'ast, T, M> {
         self.inner
     }
 }
+
+impl<'ast, T> UExpression<'ast, T, UMetadata<T>> {
+    pub fn metadata(&self) -> &UMetadata<T> {
+        &self.metadata
+    }
+
+    pub fn with_metadata(self, metadata: UMetadata<T>) -> Self {
+        UExpression { metadata, ..self }
+    }
+
+    /// Returns the range of the values of this expression, see `UMetadata`
+    pub fn range(&self) -> &Range<T> {
+        &self.metadata.range
+    }
+
+    /// Returns whether this expression is reduced to its bitwidth, see `UMetadata`
+    pub fn should_reduce(&self) -> bool {
+        self.metadata.should_reduce
+    }
+}
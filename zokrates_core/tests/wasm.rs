@@ -23,7 +23,9 @@ fn generate_proof() {
                 FlatVariable::new(0).into(),
             )],
         },
+        functions: vec![],
         private: vec![false],
+        private_outputs: vec![],
     };
 
     let interpreter = Interpreter::default();
@@ -33,5 +35,5 @@ fn generate_proof() {
         .unwrap();
 
     let keys = G16::setup(program.clone());
-    let _proof = G16::generate_proof(program, witness, keys.pk);
+    let _proof = G16::generate_proof(&program, witness, &keys.pk);
 }
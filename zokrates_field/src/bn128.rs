@@ -1,12 +1,20 @@
 use bellman_ce::pairing::bn256::{Bn256, Fq2};
 
+#[cfg(not(feature = "limb-arithmetic"))]
 prime_field!(
     b"21888242871839275222246405745257275088548364400416034343698204186575808495617",
-    Bn256,
-    Fq2,
     "bn128"
 );
 
+#[cfg(feature = "limb-arithmetic")]
+prime_field!(
+    b"21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    "bn128",
+    limbs: crate::fp256::Bn128Parameters
+);
+
+bellman_extensions!(Bn256, Fq2);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -278,6 +286,47 @@ mod tests {
             assert_eq!(fp, FieldPrime::from_byte_vector(bv));
         }
 
+        #[test]
+        fn le_bytes_ser_deser() {
+            let fp = FieldPrime::from("101");
+            let bytes = fp.to_le_bytes();
+            assert_eq!(bytes.len(), 32);
+            assert_eq!(bytes[0], 101);
+            assert_eq!(fp, FieldPrime::try_from_le_bytes(&bytes).unwrap());
+
+            // the modulus is not a canonical encoding
+            let mut modulus = FieldPrime::max_value().to_le_bytes();
+            modulus[0] += 1;
+            assert!(FieldPrime::try_from_le_bytes(&modulus).is_err());
+            assert!(FieldPrime::try_from_le_bytes(&[0; 33]).is_err());
+        }
+
+        #[test]
+        fn hex_string_ser_deser() {
+            let fp = FieldPrime::from(255);
+            assert_eq!(fp.to_hex_string(), "0xff");
+            assert_eq!(fp, FieldPrime::try_from_hex_str("0xff").unwrap());
+            assert_eq!(fp, FieldPrime::try_from_hex_str("FF").unwrap());
+            assert!(FieldPrime::try_from_hex_str("0xzz").is_err());
+            assert!(FieldPrime::try_from_hex_str(
+                "0x30644e72e131a029b85045b68181585d2833e84879b970914fe1f593f0000001"
+            )
+            .is_err());
+        }
+
+        #[test]
+        fn random() {
+            use rand::{SeedableRng, StdRng};
+
+            let seed: &[_] = &[42];
+            let mut rng: StdRng = SeedableRng::from_seed(seed);
+            let values: Vec<_> = (0..100).map(|_| FieldPrime::random(&mut rng)).collect();
+
+            assert!(values.iter().all(|v| v <= &FieldPrime::max_value()));
+            // values of 254 bits are sampled more than a third of the time
+            assert!(values.iter().any(|v| v.bits() == 254));
+        }
+
         #[test]
         fn dec_string_ser_deser() {
             let fp = FieldPrime::from("101");
@@ -384,7 +433,6 @@ mod tests {
         use bellman_ce::pairing::bn256::Fr;
 
         use rand::{thread_rng, Rng};
-        use Field;
 
         #[test]
         fn fr_to_field_to_fr() {
@@ -0,0 +1,156 @@
+// A prime field whose modulus is chosen at runtime with `set_modulus`, before any element is created
+prime_field!(
+    modulus().expect("The modulus of the custom field must be set before it is used"),
+    "custom",
+    |a: &BigInt, b: &BigInt| (a * b) % &*P
+);
+
+lazy_static! {
+    static ref MODULUS: std::sync::Mutex<Option<BigInt>> = std::sync::Mutex::new(None);
+}
+
+/// Returns the modulus of the custom field, if it was set
+pub fn modulus() -> Option<BigInt> {
+    MODULUS.lock().unwrap().clone()
+}
+
+/// Sets the modulus of the custom field, which must be an odd prime. It can only be set once per process, as elements
+/// of the field do not record their modulus.
+pub fn set_modulus(modulus: BigUint) -> Result<(), String> {
+    let modulus = BigInt::from_biguint(Sign::Plus, modulus);
+
+    if !is_probable_prime(&modulus) {
+        return Err(format!("Field modulus {} is not prime", modulus));
+    }
+    // programs rely on 2 being invertible, for example to halve values
+    if modulus.is_even() {
+        return Err(format!("Field modulus {} must be odd", modulus));
+    }
+
+    let mut current = MODULUS.lock().unwrap();
+
+    match &*current {
+        Some(m) if *m == modulus => Ok(()),
+        Some(m) => Err(format!(
+            "The modulus of the custom field is already set to {}",
+            m
+        )),
+        None => {
+            *current = Some(modulus);
+            Ok(())
+        }
+    }
+}
+
+/// Parses a modulus written in decimal, or in hexadecimal with a `0x` prefix
+pub fn parse_modulus(s: &str) -> Result<BigUint, String> {
+    let (digits, radix) = match s.starts_with("0x") || s.starts_with("0X") {
+        true => (&s[2..], 16),
+        false => (s, 10),
+    };
+
+    BigUint::parse_bytes(digits.as_bytes(), radix)
+        .ok_or_else(|| format!("Invalid field modulus {}", s))
+}
+
+/// Miller-Rabin test with the first twelve primes as bases, which is exact below 2^64 and reliable above
+fn is_probable_prime(n: &BigInt) -> bool {
+    const BASES: [u32; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    let n = match n.to_biguint() {
+        Some(n) if n > BigUint::one() => n,
+        _ => return false,
+    };
+
+    for base in BASES.iter() {
+        let base = BigUint::from(*base);
+        if n == base {
+            return true;
+        }
+        if (&n % &base).is_zero() {
+            return false;
+        }
+    }
+
+    // n - 1 = d * 2^s with d odd
+    let n_minus_one = &n - BigUint::one();
+    let mut d = n_minus_one.clone();
+    let mut s = 0;
+    while d.is_even() {
+        d = d >> 1;
+        s += 1;
+    }
+
+    let two = BigUint::from(2u32);
+
+    BASES.iter().all(|base| {
+        let mut x = BigUint::from(*base).modpow(&d, &n);
+        if x == BigUint::one() || x == n_minus_one {
+            return true;
+        }
+        for _ in 1..s {
+            x = x.modpow(&two, &n);
+            if x == n_minus_one {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primality() {
+        assert!(is_probable_prime(&BigInt::from(2)));
+        assert!(is_probable_prime(&BigInt::from(1_000_000_007)));
+        assert!(!is_probable_prime(&BigInt::from(1)));
+        assert!(!is_probable_prime(&BigInt::from(561)));
+        assert!(!is_probable_prime(&BigInt::from(
+            1_000_000_007u64 * 998_244_353
+        )));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_modulus("0x1f"), Ok(BigUint::from(31u32)));
+        assert_eq!(parse_modulus("31"), Ok(BigUint::from(31u32)));
+        assert!(parse_modulus("0xzz").is_err());
+    }
+
+    #[test]
+    fn even_modulus() {
+        assert_eq!(
+            set_modulus(BigUint::from(2u32)),
+            Err(String::from("Field modulus 2 must be odd"))
+        );
+        assert!(modulus() != Some(BigInt::from(2)));
+    }
+
+    #[test]
+    fn arithmetic() {
+        // the Goldilocks prime 2^64 - 2^32 + 1
+        let modulus = BigUint::from(18446744069414584321u64);
+
+        set_modulus(modulus.clone()).unwrap();
+        // setting the same modulus again is allowed, another one is not
+        set_modulus(modulus.clone()).unwrap();
+        assert!(set_modulus(BigUint::from(1_000_000_007u32)).is_err());
+
+        assert_eq!(FieldPrime::max_value().to_biguint(), modulus - 1u32);
+        assert_eq!(FieldPrime::get_required_bits(), 64);
+
+        let a = FieldPrime::from(18446744069414584320u128);
+        assert_eq!(a.clone() * a.clone(), FieldPrime::one());
+        assert_eq!(
+            FieldPrime::from(3) * FieldPrime::from(3).inverse_mul(),
+            FieldPrime::one()
+        );
+        assert_eq!(
+            FieldPrime::from(6) / FieldPrime::from(3),
+            FieldPrime::from(2)
+        );
+    }
+}
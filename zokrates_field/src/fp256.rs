@@ -0,0 +1,354 @@
+//! Arithmetic in prime fields of at most 256 bits, on fixed-size limbs in Montgomery form
+//!
+//! Unlike the `Field` implementations, which rely on heap-allocated big integers, this module only uses `core`, so
+//! it is available without the `std` feature, for verifiers running in `no_std` or WebAssembly environments.
+//!
+//! Operations run in constant time: they neither branch on nor index memory by the values of their operands, so that
+//! their duration does not reveal secret values. Only `inverse` and `from_limbs` branch, on whether their result exists.
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::{Add, Mul, Neg, Sub};
+
+/// The constants defining a prime field of at most 256 bits, as little-endian 64-bit limbs
+pub trait FieldParameters: Copy + Eq + fmt::Debug + 'static {
+    /// The modulus `p`
+    const MODULUS: [u64; 4];
+    /// `-p^-1 mod 2^64`
+    const INV: u64;
+    /// `R^2 mod p` where `R = 2^256`, to convert elements into Montgomery form
+    const R2: [u64; 4];
+}
+
+/// An element of the field defined by `P`, stored as `a * R mod p`
+#[derive(Clone, Copy, Eq)]
+pub struct Fp256<P: FieldParameters> {
+    limbs: [u64; 4],
+    parameters: PhantomData<P>,
+}
+
+/// Returns `a + b * c + carry` as a low and a high limb
+fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + (b as u128) * (c as u128) + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+/// Returns `a + b + carry` as a low limb and a carry
+fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+/// Returns `a - b - borrow` as a low limb and a borrow
+fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let t = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (t as u64, ((t >> 64) as u64) & 1)
+}
+
+/// Returns `a - b` and a borrow, which is 1 if it underflowed
+fn sub_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], u64) {
+    let mut res = [0; 4];
+    let mut borrow = 0;
+    for i in 0..4 {
+        let (limb, b) = sbb(a[i], b[i], borrow);
+        res[i] = limb;
+        borrow = b;
+    }
+    (res, borrow)
+}
+
+/// Returns `a` if `choice` is 1 and `b` if it is 0, without branching on `choice`
+fn select(choice: u64, a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let mask = 0u64.wrapping_sub(choice);
+    let mut res = [0; 4];
+    for (limb, (a, b)) in res.iter_mut().zip(a.iter().zip(b.iter())) {
+        *limb = (a & mask) | (b & !mask);
+    }
+    res
+}
+
+/// Returns `carry * 2^256 + limbs` reduced by `p`, for a value smaller than `2p`
+fn reduce_once(limbs: &[u64; 4], carry: u64, p: &[u64; 4]) -> [u64; 4] {
+    let (reduced, borrow) = sub_limbs(limbs, p);
+    // subtracting `p` underflows if it borrows from a value without carry
+    select(borrow & !carry & 1, limbs, &reduced)
+}
+
+impl<P: FieldParameters> Fp256<P> {
+    /// Returns the element whose Montgomery form is `limbs`, which must be smaller than the modulus
+    pub(crate) fn from_montgomery_limbs(limbs: [u64; 4]) -> Self {
+        Fp256 {
+            limbs,
+            parameters: PhantomData,
+        }
+    }
+
+    /// Returns the Montgomery form of this element
+    #[cfg(feature = "limb-arithmetic")]
+    pub(crate) fn montgomery_limbs(&self) -> [u64; 4] {
+        self.limbs
+    }
+
+    pub fn zero() -> Self {
+        Self::from_montgomery_limbs([0; 4])
+    }
+
+    pub fn one() -> Self {
+        Self::from(1)
+    }
+
+    /// Returns the element of canonical little-endian limbs `limbs`, if they are smaller than the modulus
+    pub fn from_limbs(limbs: [u64; 4]) -> Option<Self> {
+        match sub_limbs(&limbs, &P::MODULUS).1 {
+            0 => None,
+            _ => Some(Self::from_montgomery_limbs(limbs) * Self::from_montgomery_limbs(P::R2)),
+        }
+    }
+
+    /// Returns the canonical little-endian limbs of this element, smaller than the modulus
+    pub fn to_limbs(&self) -> [u64; 4] {
+        // multiplying by 1 divides by `R`
+        (*self * Self::from_montgomery_limbs([1, 0, 0, 0])).limbs
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.iter().fold(0, |acc, limb| acc | limb) == 0
+    }
+
+    /// Returns this element to the power of `exponent`, given as little-endian limbs. Every bit of the exponent costs
+    /// a squaring and a multiplication, whatever its value.
+    pub fn pow(&self, exponent: &[u64]) -> Self {
+        let mut res = Self::one();
+        for limb in exponent.iter().rev() {
+            for i in (0..64).rev() {
+                res = res * res;
+                let product = res * *self;
+                res.limbs = select((limb >> i) & 1, &product.limbs, &res.limbs);
+            }
+        }
+        res
+    }
+
+    /// Returns the multiplicative inverse of this element, if it is not zero
+    pub fn inverse(&self) -> Option<Self> {
+        match self.is_zero() {
+            true => None,
+            // Fermat's little theorem: `a^(p - 2) = a^-1`
+            false => Some(self.pow(&sub_limbs(&P::MODULUS, &[2, 0, 0, 0]).0)),
+        }
+    }
+}
+
+impl<P: FieldParameters> PartialEq for Fp256<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.limbs
+            .iter()
+            .zip(other.limbs.iter())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
+    }
+}
+
+impl<P: FieldParameters> From<u64> for Fp256<P> {
+    fn from(value: u64) -> Self {
+        // any 64-bit value is smaller than a modulus of more than 64 bits, which all fields of this module have
+        Self::from_limbs([value, 0, 0, 0]).unwrap()
+    }
+}
+
+impl<P: FieldParameters> Add for Fp256<P> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let mut limbs = [0; 4];
+        let mut carry = 0;
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let (sum, c) = adc(self.limbs[i], other.limbs[i], carry);
+            *limb = sum;
+            carry = c;
+        }
+
+        // both terms are smaller than `p`, so the sum is smaller than `2p`
+        Self::from_montgomery_limbs(reduce_once(&limbs, carry, &P::MODULUS))
+    }
+}
+
+impl<P: FieldParameters> Sub for Fp256<P> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        let (mut limbs, borrow) = sub_limbs(&self.limbs, &other.limbs);
+
+        // add `p` back if the subtraction underflowed
+        let correction = select(borrow, &P::MODULUS, &[0; 4]);
+        let mut carry = 0;
+        for (limb, m) in limbs.iter_mut().zip(correction.iter()) {
+            let (sum, c) = adc(*limb, *m, carry);
+            *limb = sum;
+            carry = c;
+        }
+
+        Self::from_montgomery_limbs(limbs)
+    }
+}
+
+impl<P: FieldParameters> Neg for Fp256<P> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::zero() - self
+    }
+}
+
+impl<P: FieldParameters> Mul for Fp256<P> {
+    type Output = Self;
+
+    /// Montgomery multiplication with coarsely integrated operand scanning, returning `a * b * R^-1 mod p`
+    fn mul(self, other: Self) -> Self {
+        let (a, b, p) = (self.limbs, other.limbs, P::MODULUS);
+        let mut t = [0u64; 6];
+
+        for b_i in b.iter() {
+            // t += a * b_i
+            let mut carry = 0;
+            for j in 0..4 {
+                let (limb, c) = mac(t[j], a[j], *b_i, carry);
+                t[j] = limb;
+                carry = c;
+            }
+            let (limb, c) = adc(t[4], carry, 0);
+            t[4] = limb;
+            t[5] = c;
+
+            // t = (t + m * p) / 2^64, where `m` is chosen so that the division is exact
+            let m = t[0].wrapping_mul(P::INV);
+            let (_, mut carry) = mac(t[0], m, p[0], 0);
+            for j in 1..4 {
+                let (limb, c) = mac(t[j], m, p[j], carry);
+                t[j - 1] = limb;
+                carry = c;
+            }
+            let (limb, c) = adc(t[4], carry, 0);
+            t[3] = limb;
+            t[4] = t[5] + c;
+            t[5] = 0;
+        }
+
+        // the result is smaller than `2p`
+        Self::from_montgomery_limbs(reduce_once(&[t[0], t[1], t[2], t[3]], t[4], &p))
+    }
+}
+
+impl<P: FieldParameters> fmt::Debug for Fp256<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let limbs = self.to_limbs();
+        write!(
+            f,
+            "0x{:016x}{:016x}{:016x}{:016x}",
+            limbs[3], limbs[2], limbs[1], limbs[0]
+        )
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Bn128Parameters;
+
+impl FieldParameters for Bn128Parameters {
+    const MODULUS: [u64; 4] = [
+        0x43e1f593f0000001,
+        0x2833e84879b97091,
+        0xb85045b68181585d,
+        0x30644e72e131a029,
+    ];
+    const INV: u64 = 0xc2e1f593efffffff;
+    const R2: [u64; 4] = [
+        0x1bb8e645ae216da7,
+        0x53fe3ab1e35c59e3,
+        0x8c49833d53bb8085,
+        0x0216d0b17f4e44a5,
+    ];
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Bls12Parameters;
+
+impl FieldParameters for Bls12Parameters {
+    const MODULUS: [u64; 4] = [
+        0xffffffff00000001,
+        0x53bda402fffe5bfe,
+        0x3339d80809a1d805,
+        0x73eda753299d7d48,
+    ];
+    const INV: u64 = 0xfffffffeffffffff;
+    const R2: [u64; 4] = [
+        0xc999e990f3f29c6d,
+        0x2b6cedcb87925c23,
+        0x05d314967254398f,
+        0x0748d9d99f59ff11,
+    ];
+}
+
+/// The scalar field of ALT_BN128, as `Bn128Field`
+pub type Bn128Fp = Fp256<Bn128Parameters>;
+/// The scalar field of BLS12-381, as `Bls12Field`
+pub type Bls12Fp = Fp256<Bls12Parameters>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let a = Bn128Fp::from(7);
+        let b = Bn128Fp::from(5);
+
+        assert_eq!(a + b, Bn128Fp::from(12));
+        assert_eq!(a - b, Bn128Fp::from(2));
+        assert_eq!(b - a + Bn128Fp::from(2), Bn128Fp::zero());
+        assert_eq!(a * b, Bn128Fp::from(35));
+        assert_eq!(a.pow(&[3]), Bn128Fp::from(343));
+        assert_eq!(a.pow(&[0]), Bn128Fp::one());
+        assert_eq!(a * a.inverse().unwrap(), Bn128Fp::one());
+        assert_eq!(Bn128Fp::zero().inverse(), None);
+
+        // p - 1 squared is 1
+        let minus_one = -Bls12Fp::one();
+        assert_eq!(minus_one * minus_one, Bls12Fp::one());
+        assert_eq!(
+            minus_one.to_limbs(),
+            sub_limbs(&Bls12Parameters::MODULUS, &[1, 0, 0, 0]).0
+        );
+    }
+
+    #[test]
+    fn limbs() {
+        assert_eq!(Bn128Fp::from_limbs(Bn128Parameters::MODULUS), None);
+
+        let limbs = [1, 2, 3, 4];
+        assert_eq!(Bn128Fp::from_limbs(limbs).unwrap().to_limbs(), limbs);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn matches_big_integers() {
+        use crate::{Bn128Field, Field};
+        use num_bigint::BigUint;
+
+        let to_field = |x: Bn128Fp| {
+            let bytes: Vec<u8> = x
+                .to_limbs()
+                .iter()
+                .flat_map(|l| l.to_le_bytes().to_vec())
+                .collect();
+            Bn128Field::from(BigUint::from_bytes_le(&bytes))
+        };
+
+        let a =
+            Bn128Fp::from_limbs([0x0123456789abcdef, 0xfedcba9876543210, 0x1111, 0x2222]).unwrap();
+        let b = Bn128Fp::from(0xdeadbeef);
+
+        assert_eq!(to_field(a * b), to_field(a) * to_field(b));
+        assert_eq!(to_field(a - b), to_field(a) - to_field(b));
+        assert_eq!(to_field(a.inverse().unwrap()), to_field(a).inverse_mul());
+    }
+}
@@ -0,0 +1,22 @@
+// The Goldilocks field of modulus 2^64 - 2^32 + 1, used by STARKs and small-field SNARKs. Its elements only have 64
+// bits, so unsigned integers wider than 31 bits cannot be multiplied, and it is not supported by the Bellman backend.
+prime_field!(b"18446744069414584321", "goldilocks");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modulus() {
+        assert_eq!(FieldPrime::get_required_bits(), 64);
+        assert_eq!(
+            FieldPrime::max_unique_value(),
+            FieldPrime::from(0x7fffffffffffffffu128)
+        );
+        // 2^64 = 2^32 - 1 modulo 2^64 - 2^32 + 1
+        assert_eq!(
+            FieldPrime::from(0x100000000u128) * FieldPrime::from(0x100000000u128),
+            FieldPrime::from(0xffffffffu32)
+        );
+    }
+}
@@ -4,14 +4,26 @@
 // @author Jacob Eberhardt <jacob.eberhardt@tu-berlin.de>
 // @date 2017
 
+// without the `std` feature, only the fixed-size arithmetic of `fp256` is available
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
 use bellman_ce::pairing::ff::ScalarEngine;
+#[cfg(feature = "std")]
 use bellman_ce::pairing::Engine;
+#[cfg(feature = "std")]
 use num_bigint::BigUint;
+#[cfg(feature = "std")]
 use num_traits::{One, Zero};
+#[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
 use std::convert::From;
+#[cfg(feature = "std")]
 use std::fmt::{Debug, Display};
+#[cfg(feature = "std")]
 use std::hash::Hash;
+#[cfg(feature = "std")]
 use std::ops::{Add, Div, Mul, Sub};
 
 pub trait Pow<RHS> {
@@ -19,6 +31,7 @@ pub trait Pow<RHS> {
     fn pow(self, _: RHS) -> Self::Output;
 }
 
+#[cfg(feature = "std")]
 pub trait Field:
     From<i32>
     + From<u32>
@@ -51,24 +64,6 @@ pub trait Field:
     + num_traits::CheckedAdd
     + num_traits::CheckedMul
 {
-    /// An associated type to be able to operate with Bellman ff traits
-    type BellmanEngine: Engine;
-
-    fn from_bellman(e: <Self::BellmanEngine as ScalarEngine>::Fr) -> Self {
-        use bellman_ce::pairing::ff::{PrimeField, PrimeFieldRepr};
-        let mut res: Vec<u8> = vec![];
-        e.into_repr().write_le(&mut res).unwrap();
-        Self::from_byte_vector(res)
-    }
-
-    fn into_bellman(self) -> <Self::BellmanEngine as ScalarEngine>::Fr {
-        use bellman_ce::pairing::ff::PrimeField;
-        let s = self.to_dec_string();
-        <Self::BellmanEngine as ScalarEngine>::Fr::from_str(&s).unwrap()
-    }
-
-    fn new_fq2(c0: &str, c1: &str) -> <Self::BellmanEngine as Engine>::Fqe;
-
     /// Returns this `Field`'s contents as little-endian byte vector
     fn into_byte_vector(&self) -> Vec<u8>;
     /// Returns an element of this `Field` from a little-endian byte vector
@@ -117,12 +112,92 @@ pub trait Field:
     }
     /// Returns the value as a BigUint
     fn to_biguint(&self) -> BigUint;
+    /// Returns the number of bytes of the canonical encoding of any element of this field type
+    fn byte_len() -> usize {
+        (Self::get_required_bits() + 7) / 8
+    }
+    /// Returns the canonical little-endian encoding of this element, on `byte_len()` bytes
+    fn to_le_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.to_biguint().to_bytes_le();
+        bytes.resize(Self::byte_len(), 0);
+        bytes
+    }
+    /// Returns the element encoded by `bytes` in little-endian, if it is canonical: at most `byte_len()` bytes for a
+    /// value smaller than the modulus
+    fn try_from_le_bytes(bytes: &[u8]) -> Result<Self, ()> {
+        let value = BigUint::from_bytes_le(bytes);
+        match bytes.len() <= Self::byte_len() && value <= Self::max_value().to_biguint() {
+            true => Ok(Self::from(value)),
+            false => Err(()),
+        }
+    }
+    /// Returns the hexadecimal representation of this element, prefixed with `0x`
+    fn to_hex_string(&self) -> String {
+        format!("0x{}", self.to_biguint().to_str_radix(16))
+    }
+    /// Parses the hexadecimal representation of an element, with or without the `0x` prefix, if it is smaller than
+    /// the modulus
+    fn try_from_hex_str(s: &str) -> Result<Self, ()> {
+        let digits = match s.starts_with("0x") || s.starts_with("0X") {
+            true => &s[2..],
+            false => s,
+        };
+        let value = BigUint::parse_bytes(digits.as_bytes(), 16).ok_or(())?;
+        match value <= Self::max_value().to_biguint() {
+            true => Ok(Self::from(value)),
+            false => Err(()),
+        }
+    }
+    /// Returns an element sampled uniformly from the field with `rng`
+    fn random<R: rand::Rng + ?Sized>(rng: &mut R) -> Self {
+        let bits = Self::get_required_bits();
+        let max = Self::max_value().to_biguint();
+        let mut bytes = vec![0u8; Self::byte_len()];
+
+        // sample values of as many bits as the modulus until one is smaller than it, which happens more than half of
+        // the time
+        loop {
+            rng.fill_bytes(&mut bytes);
+            let last = bytes.len() - 1;
+            bytes[last] &= 0xff >> (8 * bytes.len() - bits);
+
+            let value = BigUint::from_bytes_le(&bytes);
+            if value <= max {
+                return Self::from(value);
+            }
+        }
+    }
+}
+
+/// Conversions to the scalar field of a pairing-friendly curve supported by Bellman, for the fields which are one
+#[cfg(feature = "std")]
+pub trait BellmanFieldExtensions: Field {
+    /// An associated type to be able to operate with Bellman ff traits
+    type BellmanEngine: Engine;
+
+    fn from_bellman(e: <Self::BellmanEngine as ScalarEngine>::Fr) -> Self {
+        use bellman_ce::pairing::ff::{PrimeField, PrimeFieldRepr};
+        let mut res: Vec<u8> = vec![];
+        e.into_repr().write_le(&mut res).unwrap();
+        Self::from_byte_vector(res)
+    }
+
+    fn into_bellman(self) -> <Self::BellmanEngine as ScalarEngine>::Fr {
+        use bellman_ce::pairing::ff::PrimeField;
+        let s = self.to_dec_string();
+        <Self::BellmanEngine as ScalarEngine>::Fr::from_str(&s).unwrap()
+    }
+
+    fn new_fq2(c0: &str, c1: &str) -> <Self::BellmanEngine as Engine>::Fqe;
 }
 
+#[cfg(feature = "std")]
 #[macro_use]
 mod prime_field {
     macro_rules! prime_field {
-        ($modulus:expr, $bellman_type:ty, $fq2_type: ident, $name:expr) => {
+        // a field whose modulus is computed by an expression on first use, and whose arithmetic on reduced `BigInt`
+        // values is implemented by `$add`, `$sub`, `$mul` and `$inverse`
+        (@arithmetic $modulus:expr, $name:expr, $add:expr, $sub:expr, $mul:expr, $inverse:expr) => {
             use crate::{Field, Pow};
             use lazy_static::lazy_static;
             use num_bigint::{BigInt, BigUint, Sign, ToBigInt};
@@ -135,7 +210,7 @@ mod prime_field {
             use std::ops::{Add, Div, Mul, Sub};
 
             lazy_static! {
-                static ref P: BigInt = BigInt::parse_bytes($modulus, 10).unwrap();
+                static ref P: BigInt = $modulus;
             }
 
             #[derive(PartialEq, PartialOrd, Clone, Eq, Ord, Hash, Serialize, Deserialize)]
@@ -144,19 +219,10 @@ mod prime_field {
             }
 
             impl Field for FieldPrime {
-                type BellmanEngine = $bellman_type;
-
                 fn bits(&self) -> u32 {
                     self.value.bits() as u32
                 }
 
-                fn new_fq2(c0: &str, c1: &str) -> $fq2_type {
-                    $fq2_type {
-                        c0: bellman_ce::pairing::from_hex(c0).unwrap(),
-                        c1: bellman_ce::pairing::from_hex(c1).unwrap(),
-                    }
-                }
-
                 fn to_biguint(&self) -> BigUint {
                     self.value.to_biguint().unwrap()
                 }
@@ -180,10 +246,8 @@ mod prime_field {
                 }
 
                 fn inverse_mul(&self) -> FieldPrime {
-                    let (b, s, _) = extended_euclid(&self.value, &*P);
-                    assert_eq!(b, BigInt::one());
                     FieldPrime {
-                        value: &s - s.div_floor(&*P) * &*P,
+                        value: ($inverse)(&self.value),
                     }
                 }
                 fn min_value() -> FieldPrime {
@@ -331,7 +395,7 @@ mod prime_field {
 
                 fn add(self, other: FieldPrime) -> FieldPrime {
                     FieldPrime {
-                        value: (self.value + other.value) % &*P,
+                        value: ($add)(&self.value, &other.value),
                     }
                 }
             }
@@ -341,7 +405,7 @@ mod prime_field {
 
                 fn add(self, other: &FieldPrime) -> FieldPrime {
                     FieldPrime {
-                        value: (self.value + other.value.clone()) % &*P,
+                        value: ($add)(&self.value, &other.value),
                     }
                 }
             }
@@ -350,9 +414,8 @@ mod prime_field {
                 type Output = FieldPrime;
 
                 fn sub(self, other: FieldPrime) -> FieldPrime {
-                    let x = self.value - other.value;
                     FieldPrime {
-                        value: &x - x.div_floor(&*P) * &*P,
+                        value: ($sub)(&self.value, &other.value),
                     }
                 }
             }
@@ -361,9 +424,8 @@ mod prime_field {
                 type Output = FieldPrime;
 
                 fn sub(self, other: &FieldPrime) -> FieldPrime {
-                    let x = self.value - other.value.clone();
                     FieldPrime {
-                        value: &x - x.div_floor(&*P) * &*P,
+                        value: ($sub)(&self.value, &other.value),
                     }
                 }
             }
@@ -373,7 +435,7 @@ mod prime_field {
 
                 fn mul(self, other: FieldPrime) -> FieldPrime {
                     FieldPrime {
-                        value: (self.value * other.value) % &*P,
+                        value: ($mul)(&self.value, &other.value),
                     }
                 }
             }
@@ -383,7 +445,7 @@ mod prime_field {
 
                 fn mul(self, other: &FieldPrime) -> FieldPrime {
                     FieldPrime {
-                        value: (self.value * other.value.clone()) % &*P,
+                        value: ($mul)(&self.value, &other.value),
                     }
                 }
             }
@@ -489,6 +551,8 @@ mod prime_field {
             /// # Arguments
             /// * `a` - First number as `BigInt`
             /// * `b` - Second number as `BigInt`
+            // unused by the fields whose arithmetic runs on limbs
+            #[allow(dead_code)]
             fn extended_euclid(a: &BigInt, b: &BigInt) -> (BigInt, BigInt, BigInt) {
                 let (mut s, mut old_s) = (BigInt::zero(), BigInt::one());
                 let (mut t, mut old_t) = (BigInt::one(), BigInt::zero());
@@ -508,11 +572,90 @@ mod prime_field {
                 return (old_r, old_s, old_t);
             }
         };
+        ($modulus:expr, $name:expr) => {
+            prime_field!(
+                BigInt::parse_bytes($modulus, 10).unwrap(),
+                $name,
+                |a: &BigInt, b: &BigInt| (a * b) % &*P
+            );
+        };
+        // a field whose arithmetic runs on the fixed-size limbs of `fp256`, with the parameters `$parameters`
+        ($modulus:expr, $name:expr, limbs: $parameters:ty) => {
+            prime_field!(
+                @arithmetic BigInt::parse_bytes($modulus, 10).unwrap(),
+                $name,
+                crate::limb_arithmetic::add::<$parameters>,
+                crate::limb_arithmetic::sub::<$parameters>,
+                crate::limb_arithmetic::mul::<$parameters>,
+                crate::limb_arithmetic::inverse::<$parameters>
+            );
+        };
+        // a field whose modulus is computed by an expression on first use, and whose products are reduced by `$mul`
+        ($modulus:expr, $name:expr, $mul:expr) => {
+            prime_field!(
+                @arithmetic $modulus,
+                $name,
+                |a: &BigInt, b: &BigInt| (a + b) % &*P,
+                |a: &BigInt, b: &BigInt| {
+                    let x = a - b;
+                    &x - x.div_floor(&*P) * &*P
+                },
+                $mul,
+                |a: &BigInt| {
+                    let (b, s, _) = extended_euclid(a, &*P);
+                    assert_eq!(b, BigInt::one());
+                    &s - s.div_floor(&*P) * &*P
+                }
+            );
+        };
+    }
+
+    /// Implements `BellmanFieldExtensions` for the `FieldPrime` of a module declared with `prime_field`
+    macro_rules! bellman_extensions {
+        ($bellman_type:ty, $fq2_type: ident) => {
+            use crate::BellmanFieldExtensions;
+
+            impl BellmanFieldExtensions for FieldPrime {
+                type BellmanEngine = $bellman_type;
+
+                fn new_fq2(c0: &str, c1: &str) -> $fq2_type {
+                    $fq2_type {
+                        c0: bellman_ce::pairing::from_hex(c0).unwrap(),
+                        c1: bellman_ce::pairing::from_hex(c1).unwrap(),
+                    }
+                }
+            }
+        };
     }
 }
 
+pub mod fp256;
+
+#[cfg(feature = "limb-arithmetic")]
+mod limb_arithmetic;
+
+#[cfg(feature = "std")]
 pub mod bls12_381;
+#[cfg(feature = "std")]
 pub mod bn128;
-
+#[cfg(feature = "std")]
+pub mod custom;
+#[cfg(feature = "std")]
+pub mod goldilocks;
+#[cfg(feature = "std")]
+pub mod pallas;
+#[cfg(feature = "std")]
+pub mod vesta;
+
+#[cfg(feature = "std")]
 pub use bls12_381::FieldPrime as Bls12Field;
+#[cfg(feature = "std")]
 pub use bn128::FieldPrime as Bn128Field;
+#[cfg(feature = "std")]
+pub use custom::FieldPrime as CustomField;
+#[cfg(feature = "std")]
+pub use goldilocks::FieldPrime as GoldilocksField;
+#[cfg(feature = "std")]
+pub use pallas::FieldPrime as PallasField;
+#[cfg(feature = "std")]
+pub use vesta::FieldPrime as VestaField;
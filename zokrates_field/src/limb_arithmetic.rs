@@ -0,0 +1,77 @@
+//! Arithmetic of the big integer fields through the constant-time operations of `fp256`, for the fields declared with
+//! `prime_field!(.., limbs: ..)` when the `limb-arithmetic` feature is enabled
+//!
+//! This is a best-effort mitigation of timing side channels, not a constant-time implementation: elements are still
+//! stored as big integers and converted from and to fixed-size limbs around each operation, and those conversions
+//! take a time which depends on the number of bytes of the values, so they reveal their magnitude.
+
+use crate::fp256::{FieldParameters, Fp256};
+use num_bigint::{BigInt, Sign};
+
+/// Returns the little-endian limbs of a reduced element
+fn to_limbs(value: &BigInt) -> [u64; 4] {
+    let mut limbs = [0u64; 4];
+    for (i, byte) in value.to_bytes_le().1.iter().enumerate() {
+        limbs[i / 8] |= (*byte as u64) << (8 * (i % 8));
+    }
+    limbs
+}
+
+fn from_limbs(limbs: [u64; 4]) -> BigInt {
+    let mut bytes = [0u8; 32];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = (limbs[i / 8] >> (8 * (i % 8))) as u8;
+    }
+    BigInt::from_bytes_le(Sign::Plus, &bytes)
+}
+
+// Addition and subtraction are the same in Montgomery form, so canonical limbs are used as they are. A Montgomery
+// product of canonical limbs is divided by `R`, which a second product by `R^2` compensates.
+
+fn canonical<P: FieldParameters>(value: &BigInt) -> Fp256<P> {
+    Fp256::from_montgomery_limbs(to_limbs(value))
+}
+
+pub fn add<P: FieldParameters>(a: &BigInt, b: &BigInt) -> BigInt {
+    from_limbs((canonical::<P>(a) + canonical(b)).montgomery_limbs())
+}
+
+pub fn sub<P: FieldParameters>(a: &BigInt, b: &BigInt) -> BigInt {
+    from_limbs((canonical::<P>(a) - canonical(b)).montgomery_limbs())
+}
+
+pub fn mul<P: FieldParameters>(a: &BigInt, b: &BigInt) -> BigInt {
+    let r2 = Fp256::from_montgomery_limbs(P::R2);
+    from_limbs((canonical::<P>(a) * canonical(b) * r2).montgomery_limbs())
+}
+
+/// Returns the multiplicative inverse of `a`, which must not be zero
+pub fn inverse<P: FieldParameters>(a: &BigInt) -> BigInt {
+    let a = Fp256::<P>::from_limbs(to_limbs(a)).unwrap();
+    from_limbs(a.inverse().expect("Zero has no inverse").to_limbs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fp256::Bn128Parameters;
+
+    #[test]
+    fn matches_big_integers() {
+        let p = BigInt::parse_bytes(
+            b"21888242871839275222246405745257275088548364400416034343698204186575808495617",
+            10,
+        )
+        .unwrap();
+        let a = &p - BigInt::from(3);
+        let b = BigInt::from(0xdeadbeefu32);
+
+        assert_eq!(add::<Bn128Parameters>(&a, &b), (&a + &b) % &p);
+        assert_eq!(sub::<Bn128Parameters>(&b, &a), BigInt::from(0xdeadbef2u32));
+        assert_eq!(mul::<Bn128Parameters>(&a, &b), (&a * &b) % &p);
+        assert_eq!(
+            mul::<Bn128Parameters>(&a, &inverse::<Bn128Parameters>(&a)),
+            BigInt::from(1)
+        );
+    }
+}
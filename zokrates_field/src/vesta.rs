@@ -0,0 +1,24 @@
+// The scalar field of the Vesta curve, which is the base field of the Pallas curve. Vesta has no pairing, so this
+// field is not supported by the Bellman backend.
+prime_field!(
+    b"28948022309329048855892746252171976963363056481941560715954676764349967630337",
+    "vesta"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modulus() {
+        assert_eq!(FieldPrime::get_required_bits(), 255);
+        assert_eq!(
+            FieldPrime::max_value() + FieldPrime::one(),
+            FieldPrime::zero()
+        );
+        assert_eq!(
+            FieldPrime::from(7) * FieldPrime::from(7).inverse_mul(),
+            FieldPrime::one()
+        );
+    }
+}
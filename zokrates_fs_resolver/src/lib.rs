@@ -7,11 +7,91 @@ use zokrates_common::Resolver;
 
 const ZOKRATES_HOME: &str = &"ZOKRATES_HOME";
 
-pub struct FileSystemResolver;
+/// Restrictions on the files a `FileSystemResolver` may read, for embedders compiling untrusted programs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Policy {
+    /// directories which imported files must be located in. Any directory is allowed when empty
+    pub roots: Vec<PathBuf>,
+    /// whether import paths may contain `..`
+    pub allow_parent_dir: bool,
+    /// whether import paths may be absolute
+    pub allow_absolute: bool,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Policy {
+            roots: vec![],
+            allow_parent_dir: true,
+            allow_absolute: true,
+        }
+    }
+}
+
+impl Policy {
+    /// A policy only allowing relative imports without `..`, of files located in `roots`
+    pub fn sandbox(roots: Vec<PathBuf>) -> Self {
+        Policy {
+            roots,
+            allow_parent_dir: false,
+            allow_absolute: false,
+        }
+    }
+
+    fn check_import(&self, import_location: &Path) -> Result<(), io::Error> {
+        if !self.allow_absolute && import_location.has_root() {
+            return Err(denied(format!(
+                "Absolute import paths are not allowed: {}",
+                import_location.display()
+            )));
+        }
+
+        if !self.allow_parent_dir
+            && import_location
+                .components()
+                .any(|c| c == Component::ParentDir)
+        {
+            return Err(denied(format!(
+                "Import paths containing `..` are not allowed: {}",
+                import_location.display()
+            )));
+        }
+
+        Ok(())
+    }
+
+    // paths are canonicalized so that symbolic links cannot be used to escape the roots, which fails for missing files
+    fn allows_location(&self, path: &Path) -> bool {
+        if self.roots.is_empty() {
+            return true;
+        }
+
+        match path.canonicalize() {
+            Ok(path) => self
+                .roots
+                .iter()
+                .filter_map(|root| root.canonicalize().ok())
+                .any(|root| path.starts_with(root)),
+            Err(_) => false,
+        }
+    }
+}
+
+fn denied(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::PermissionDenied, message)
+}
+
+pub struct FileSystemResolver {
+    policy: Policy,
+}
 
 impl FileSystemResolver {
     pub fn new() -> Self {
-        FileSystemResolver {}
+        Self::with_policy(Policy::default())
+    }
+
+    pub fn with_policy(policy: Policy) -> Self {
+        FileSystemResolver { policy }
     }
 }
 
@@ -23,6 +103,8 @@ impl Resolver<io::Error> for FileSystemResolver {
     ) -> Result<(String, PathBuf), io::Error> {
         let source = Path::new(&import_location);
 
+        self.policy.check_import(source)?;
+
         if !current_location.is_file() {
             return Err(io::Error::new(
                 io::ErrorKind::Other,
@@ -50,7 +132,8 @@ impl Resolver<io::Error> for FileSystemResolver {
             .join(PathBuf::from(import_location.clone()))
             .with_extension("zok");
 
-        if !path_owned.is_file() {
+        // the files outside of the roots are reported as missing, so that the policy does not reveal which ones exist
+        if !self.policy.allows_location(&path_owned) || !path_owned.is_file() {
             return Err(io::Error::new(
                 io::ErrorKind::Other,
                 format!("No file found at {}", import_location.display()),
@@ -220,6 +303,54 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn sandbox() {
+        use std::io::Write;
+
+        // create a root folder with a code file, and a file outside of it
+        let folder = tempfile::tempdir().unwrap();
+        let root = folder.path().join("root");
+        std::fs::create_dir(&root).unwrap();
+        let file_path = root.join("foo.zok");
+        File::create(file_path.clone()).unwrap();
+        let mut file = File::create(root.join("bar.zok")).unwrap();
+        writeln!(file, "<user code>").unwrap();
+        let secret_path = folder.path().join("secret.zok");
+        File::create(secret_path.clone()).unwrap();
+
+        let fs_resolver = FileSystemResolver::with_policy(Policy::sandbox(vec![root.clone()]));
+
+        let result = fs_resolver.resolve(file_path.clone(), "./bar".into());
+        assert_eq!(result.unwrap().0, String::from("<user code>\n"));
+
+        let result = fs_resolver.resolve(file_path.clone(), "../secret".into());
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+
+        let result = fs_resolver.resolve(file_path.clone(), secret_path.clone());
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+
+        // `..` is allowed as long as the imported file stays in the roots
+        let fs_resolver = FileSystemResolver::with_policy(Policy {
+            allow_parent_dir: true,
+            ..Policy::sandbox(vec![root.clone()])
+        });
+
+        let result = fs_resolver.resolve(file_path.clone(), "../root/bar".into());
+        assert!(result.is_ok());
+
+        // files outside of the roots cannot be told apart from missing ones
+        let secret = fs_resolver.resolve(file_path.clone(), "../secret".into());
+        let missing = fs_resolver.resolve(file_path, "../missing".into());
+        assert_eq!(
+            secret.unwrap_err().to_string(),
+            "No file found at ../secret"
+        );
+        assert_eq!(
+            missing.unwrap_err().to_string(),
+            "No file found at ../missing"
+        );
+    }
+
     #[test]
     fn panic_if_home_not_set() {
         std::env::remove_var(ZOKRATES_HOME);
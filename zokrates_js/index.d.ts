@@ -7,9 +7,20 @@ declare module 'zokrates-js' {
   export type SolidityAbi = "v1" | "v2";
   export type ResolveCallback = (location: string, path: string) => ResolverResult;
 
+  export interface Limits {
+    max_ast_nodes?: number,
+    max_unrolled_statements?: number,
+  }
+
+  export interface CompileConfig {
+    validate_inputs?: boolean,
+    limits?: Limits,
+  }
+
   export interface CompileOptions {
     location?: string,
     resolveCallback?: ResolveCallback,
+    config?: CompileConfig,
   }
 
   export interface VerificationKey {
@@ -5,7 +5,9 @@ use std::path::PathBuf;
 use wasm_bindgen::prelude::*;
 use zokrates_abi::{parse_strict, Decode, Encode, Inputs};
 use zokrates_common::Resolver;
-use zokrates_core::compile::{compile as core_compile, CompilationArtifacts, CompileError};
+use zokrates_core::compile::{
+    compile as core_compile, CompilationArtifacts, CompileConfig, CompileError,
+};
 use zokrates_core::imports::Error;
 use zokrates_core::ir;
 use zokrates_core::proof_system::bellman::groth16::G16;
@@ -100,12 +102,16 @@ pub fn compile(
     config: JsValue,
 ) -> Result<JsValue, JsValue> {
     let resolver = JsResolver::new(resolve_callback);
+    let config: CompileConfig = config
+        .into_serde()
+        .map_err(|e| JsValue::from_str(&format!("Invalid compilation config: {}", e)))?;
 
     let fmt_error = |e: &CompileError| format!("{}:{}", e.file().display(), e.value());
     let artifacts: CompilationArtifacts<Bn128Field> = core_compile(
         source.as_string().unwrap(),
         PathBuf::from(location.as_string().unwrap()),
         Some(&resolver),
+        &config,
     )
     .map_err(|ce| {
         JsValue::from_str(&format!(
@@ -188,7 +194,7 @@ pub fn generate_proof(program: JsValue, witness: JsValue, pk: JsValue) -> Result
         .map_err(|err| JsValue::from_str(&format!("Could not read witness: {}", err)))?;
 
     let proving_key: Vec<u8> = pk.into_serde().unwrap();
-    let proof = G16::generate_proof(program_flattened, ir_witness, proving_key);
+    let proof = G16::generate_proof(&program_flattened, ir_witness, &proving_key);
 
     Ok(JsValue::from_serde(&proof).unwrap())
 }
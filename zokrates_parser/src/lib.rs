@@ -14,6 +14,65 @@ pub fn parse(input: &str) -> Result<Pairs<Rule>, Error<Rule>> {
     ZoKratesParser::parse(Rule::file, input)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Keyword,
+    /// A basic type such as `field` or `u32`
+    Type,
+    Boolean,
+    Identifier,
+    Number,
+    /// The path of an import
+    String,
+    Comment,
+    Operator,
+    Punctuation,
+    /// A character which starts no token
+    Unknown,
+}
+
+/// A token of a source, spanning the bytes `start..end`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Splits a source into tokens without parsing it, for editors to highlight it. Tokenizing never fails, so that
+/// sources which are being edited can be highlighted: characters which start no token are returned as
+/// `TokenKind::Unknown`, and whitespace is skipped.
+pub fn tokenize(input: &str) -> Vec<Token> {
+    ZoKratesParser::parse(Rule::token_stream, input)
+        .expect("Any character starts a token")
+        .next()
+        .unwrap()
+        .into_inner()
+        .filter_map(|pair| {
+            let kind = match pair.as_rule() {
+                Rule::token_keyword => TokenKind::Keyword,
+                Rule::token_type => TokenKind::Type,
+                Rule::token_boolean => TokenKind::Boolean,
+                Rule::identifier => TokenKind::Identifier,
+                Rule::token_number => TokenKind::Number,
+                Rule::token_string => TokenKind::String,
+                Rule::token_comment => TokenKind::Comment,
+                Rule::token_operator => TokenKind::Operator,
+                Rule::token_punctuation => TokenKind::Punctuation,
+                Rule::token_unknown => TokenKind::Unknown,
+                Rule::EOI => return None,
+                rule => unreachable!("Unexpected token {:?}", rule),
+            };
+            let span = pair.as_span();
+            Some(Token {
+                kind,
+                start: span.start(),
+                end: span.end(),
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -44,6 +103,9 @@ mod tests {
                         file.read_to_string(&mut data).unwrap();
 
                         assert!(ZoKratesParser::parse(Rule::file, &data).is_ok());
+                        assert!(tokenize(&data)
+                            .iter()
+                            .all(|token| token.kind != TokenKind::Unknown));
                     }
                     Err(e) => panic!("{:?}", e),
                 }
@@ -84,15 +146,19 @@ mod tests {
                             ]),
                             identifier(14, 15)
                         ]),
-                        // type_list is not created (silent rule)
-                        ty(21, 26, [
-                            ty_basic(21, 26, [
-                                ty_field(21, 26)
+                        // return_type_list is not created (silent rule)
+                        return_type(21, 26, [
+                            ty(21, 26, [
+                                ty_basic(21, 26, [
+                                    ty_field(21, 26)
+                                ])
                             ])
                         ]),
-                        ty(28, 33, [
-                            ty_basic(28, 33, [
-                                ty_field(28, 33)
+                        return_type(28, 33, [
+                            ty(28, 33, [
+                                ty_basic(28, 33, [
+                                    ty_field(28, 33)
+                                ])
                             ])
                         ]),
                         statement(36, 45, [
@@ -216,6 +282,42 @@ mod tests {
             };
         }
 
+        #[test]
+        fn parse_hex_numbers() {
+            parses_to! {
+                parser: ZoKratesParser,
+                input: "0x0123456789abcdef0123456789abcdef",
+                rule: Rule::constant,
+                tokens: [
+                    constant(0, 34, [
+                        hex_number_128(0, 34)
+                    ])
+                ]
+            };
+
+            parses_to! {
+                parser: ZoKratesParser,
+                input: "0x0123456789abcdef",
+                rule: Rule::constant,
+                tokens: [
+                    constant(0, 18, [
+                        hex_number_64(0, 18)
+                    ])
+                ]
+            };
+
+            parses_to! {
+                parser: ZoKratesParser,
+                input: "0x01234567",
+                rule: Rule::constant,
+                tokens: [
+                    constant(0, 10, [
+                        hex_number_32(0, 10)
+                    ])
+                ]
+            };
+        }
+
         #[test]
         fn parse_invalid_identifier() {
             fails_with! {
@@ -291,5 +393,116 @@ mod tests {
             let parse = ZoKratesParser::parse(Rule::iteration_statement, input);
             assert!(parse.is_ok());
         }
+
+        #[test]
+        fn parse_bounded_for_loop() {
+            let input = "for field i in 0..n max 64 do \n c = c + a[i] \n endfor";
+
+            let parse = ZoKratesParser::parse(Rule::iteration_statement, input);
+            assert!(parse.is_ok());
+
+            // `max` is not reserved
+            let input = "for field i in 0..max max 2 * max do \n c = c + max \n endfor";
+
+            let parse = ZoKratesParser::parse(Rule::iteration_statement, input);
+            assert!(parse.is_ok());
+        }
+
+        #[test]
+        fn parse_remainder() {
+            parses_to! {
+                parser: ZoKratesParser,
+                input: "a % b",
+                rule: Rule::expression,
+                tokens: [
+                    expression(0, 5, [
+                        term(0, 1, [primary_expression(0, 1, [identifier(0, 1)])]),
+                        op_rem(2, 3),
+                        term(4, 5, [primary_expression(4, 5, [identifier(4, 5)])])
+                    ])
+                ]
+            };
+        }
+
+        #[test]
+        fn parse_attributes() {
+            parses_to! {
+                parser: ZoKratesParser,
+                input: "#[inline(never)]\n",
+                rule: Rule::attribute,
+                tokens: [
+                    attribute(0, 17, [
+                        identifier(2, 8),
+                        attribute_argument(9, 14)
+                    ])
+                ]
+            };
+
+            let input = "#[inline(always)]\n#[foo]\ndef main() -> field:\n return 1\n";
+
+            let parse = ZoKratesParser::parse(Rule::function_definition, input);
+            assert!(parse.is_ok());
+        }
+    }
+
+    mod tokens {
+        use super::*;
+
+        fn tokens(input: &str) -> Vec<(TokenKind, &str)> {
+            tokenize(input)
+                .into_iter()
+                .map(|token| (token.kind, &input[token.start..token.end]))
+                .collect()
+        }
+
+        #[test]
+        fn tokenize_definition() {
+            let input = "from \"./foo\" import bar\ndef main(private u32 a) -> bool: // check\n\treturn bar(a) >= 0x0a || true";
+
+            assert_eq!(
+                tokens(input),
+                vec![
+                    (TokenKind::Keyword, "from"),
+                    (TokenKind::String, "\"./foo\""),
+                    (TokenKind::Keyword, "import"),
+                    (TokenKind::Identifier, "bar"),
+                    (TokenKind::Keyword, "def"),
+                    (TokenKind::Identifier, "main"),
+                    (TokenKind::Punctuation, "("),
+                    (TokenKind::Keyword, "private"),
+                    (TokenKind::Type, "u32"),
+                    (TokenKind::Identifier, "a"),
+                    (TokenKind::Punctuation, ")"),
+                    (TokenKind::Operator, "->"),
+                    (TokenKind::Type, "bool"),
+                    (TokenKind::Punctuation, ":"),
+                    (TokenKind::Comment, "// check"),
+                    (TokenKind::Keyword, "return"),
+                    (TokenKind::Identifier, "bar"),
+                    (TokenKind::Punctuation, "("),
+                    (TokenKind::Identifier, "a"),
+                    (TokenKind::Punctuation, ")"),
+                    (TokenKind::Operator, ">="),
+                    (TokenKind::Number, "0x0a"),
+                    (TokenKind::Operator, "||"),
+                    (TokenKind::Boolean, "true"),
+                ]
+            );
+        }
+
+        #[test]
+        fn tokenize_invalid() {
+            // keywords are only recognized as whole words, and unterminated strings and comments end the line or the
+            // source
+            assert_eq!(
+                tokens("fields $ \"foo\n/* bar"),
+                vec![
+                    (TokenKind::Identifier, "fields"),
+                    (TokenKind::Unknown, "$"),
+                    (TokenKind::String, "\"foo"),
+                    (TokenKind::Comment, "/* bar"),
+                ]
+            );
+        }
     }
 }
@@ -9,13 +9,14 @@ extern crate lazy_static;
 
 pub use ast::{
     Access, ArrayAccess, ArrayInitializerExpression, ArrayType, AssertionStatement, Assignee,
-    AssigneeAccess, BasicOrStructType, BasicType, BinaryExpression, BinaryOperator, CallAccess,
-    ConstantExpression, DecimalNumberExpression, DefinitionStatement, Expression, FieldType, File,
-    FromExpression, Function, IdentifierExpression, ImportDirective, ImportSource,
-    InlineArrayExpression, InlineStructExpression, InlineStructMember, IterationStatement,
-    OptionallyTypedAssignee, Parameter, PostfixExpression, Range, RangeOrExpression,
-    ReturnStatement, Span, Spread, SpreadOrExpression, Statement, StructDefinition, StructField,
-    TernaryExpression, ToExpression, Type, UnaryExpression, UnaryOperator, Visibility,
+    AssigneeAccess, Attribute, AttributeArgument, BasicOrStructType, BasicType, BinaryExpression,
+    BinaryOperator, CallAccess, ConstantExpression, DecimalNumberExpression, DefinitionStatement,
+    Expression, FieldType, File, FromExpression, Function, IdentifierExpression, ImportDirective,
+    ImportSource, InlineArrayExpression, InlineStructExpression, InlineStructMember,
+    IterationStatement, MaxBound, OptionallyTypedAssignee, Parameter, PostfixExpression, Range,
+    RangeOrExpression, ReturnStatement, ReturnType, Span, Spread, SpreadOrExpression, Statement,
+    StructDefinition, StructField, TernaryExpression, ToExpression, Type, UnaryExpression,
+    UnaryOperator, Visibility,
 };
 
 mod ast {
@@ -49,7 +50,9 @@ mod ast {
             Operator::new(Rule::op_left_shift, Assoc::Left)
                 | Operator::new(Rule::op_right_shift, Assoc::Left),
             Operator::new(Rule::op_add, Assoc::Left) | Operator::new(Rule::op_sub, Assoc::Left),
-            Operator::new(Rule::op_mul, Assoc::Left) | Operator::new(Rule::op_div, Assoc::Left),
+            Operator::new(Rule::op_mul, Assoc::Left)
+                | Operator::new(Rule::op_div, Assoc::Left)
+                | Operator::new(Rule::op_rem, Assoc::Left),
             Operator::new(Rule::op_pow, Assoc::Left),
         ])
     }
@@ -71,6 +74,7 @@ mod ast {
             Rule::op_sub => Expression::binary(BinaryOperator::Sub, lhs, rhs, span),
             Rule::op_mul => Expression::binary(BinaryOperator::Mul, lhs, rhs, span),
             Rule::op_div => Expression::binary(BinaryOperator::Div, lhs, rhs, span),
+            Rule::op_rem => Expression::binary(BinaryOperator::Rem, lhs, rhs, span),
             Rule::op_pow => Expression::binary(BinaryOperator::Pow, lhs, rhs, span),
             Rule::op_equal => Expression::binary(BinaryOperator::Eq, lhs, rhs, span),
             Rule::op_not_equal => Expression::binary(BinaryOperator::NotEq, lhs, rhs, span),
@@ -214,14 +218,33 @@ mod ast {
     #[derive(Debug, FromPest, PartialEq, Clone)]
     #[pest_ast(rule(Rule::function_definition))]
     pub struct Function<'ast> {
+        pub attributes: Vec<Attribute<'ast>>,
         pub id: IdentifierExpression<'ast>,
         pub parameters: Vec<Parameter<'ast>>,
-        pub returns: Vec<Type<'ast>>,
+        pub returns: Vec<ReturnType<'ast>>,
         pub statements: Vec<Statement<'ast>>,
         #[pest_ast(outer())]
         pub span: Span<'ast>,
     }
 
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::attribute))]
+    pub struct Attribute<'ast> {
+        pub id: IdentifierExpression<'ast>,
+        pub argument: Option<AttributeArgument<'ast>>,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::attribute_argument))]
+    pub struct AttributeArgument<'ast> {
+        #[pest_ast(outer(with(span_into_str)))]
+        pub value: String,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
     #[derive(Debug, FromPest, PartialEq, Clone)]
     #[pest_ast(rule(Rule::import_directive))]
     pub enum ImportDirective<'ast> {
@@ -265,6 +288,16 @@ mod ast {
         Struct(StructType<'ast>),
     }
 
+    impl<'ast> Type<'ast> {
+        pub fn span(&self) -> &Span<'ast> {
+            match self {
+                Type::Basic(t) => t.span(),
+                Type::Array(t) => &t.span,
+                Type::Struct(t) => &t.span,
+            }
+        }
+    }
+
     #[derive(Debug, FromPest, PartialEq, Clone)]
     #[pest_ast(rule(Rule::ty_basic))]
     pub enum BasicType<'ast> {
@@ -273,6 +306,22 @@ mod ast {
         U8(U8Type<'ast>),
         U16(U16Type<'ast>),
         U32(U32Type<'ast>),
+        U64(U64Type<'ast>),
+        U128(U128Type<'ast>),
+    }
+
+    impl<'ast> BasicType<'ast> {
+        pub fn span(&self) -> &Span<'ast> {
+            match self {
+                BasicType::Field(t) => &t.span,
+                BasicType::Boolean(t) => &t.span,
+                BasicType::U8(t) => &t.span,
+                BasicType::U16(t) => &t.span,
+                BasicType::U32(t) => &t.span,
+                BasicType::U64(t) => &t.span,
+                BasicType::U128(t) => &t.span,
+            }
+        }
     }
 
     #[derive(Debug, FromPest, PartialEq, Clone)]
@@ -326,6 +375,20 @@ mod ast {
         pub span: Span<'ast>,
     }
 
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::ty_u64))]
+    pub struct U64Type<'ast> {
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::ty_u128))]
+    pub struct U128Type<'ast> {
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
     #[derive(Debug, FromPest, PartialEq, Clone)]
     #[pest_ast(rule(Rule::ty_struct))]
     pub struct StructType<'ast> {
@@ -344,6 +407,15 @@ mod ast {
         pub span: Span<'ast>,
     }
 
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::return_type))]
+    pub struct ReturnType<'ast> {
+        pub visibility: Option<Visibility>,
+        pub ty: Type<'ast>,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
     #[derive(Debug, FromPest, PartialEq, Clone)]
     #[pest_ast(rule(Rule::vis))]
     pub enum Visibility {
@@ -392,11 +464,16 @@ mod ast {
         pub index: IdentifierExpression<'ast>,
         pub from: Expression<'ast>,
         pub to: Expression<'ast>,
+        pub max: Option<MaxBound<'ast>>,
         pub statements: Vec<Statement<'ast>>,
         #[pest_ast(outer())]
         pub span: Span<'ast>,
     }
 
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::max_bound))]
+    pub struct MaxBound<'ast>(pub Expression<'ast>);
+
     #[derive(Debug, FromPest, PartialEq, Clone)]
     #[pest_ast(rule(Rule::return_statement))]
     pub struct ReturnStatement<'ast> {
@@ -418,6 +495,7 @@ mod ast {
         Sub,
         Mul,
         Div,
+        Rem,
         Eq,
         NotEq,
         Lt,
@@ -686,6 +764,8 @@ mod ast {
         U8(U8NumberExpression<'ast>),
         U16(U16NumberExpression<'ast>),
         U32(U32NumberExpression<'ast>),
+        U64(U64NumberExpression<'ast>),
+        U128(U128NumberExpression<'ast>),
     }
 
     impl<'ast> ConstantExpression<'ast> {
@@ -696,6 +776,8 @@ mod ast {
                 ConstantExpression::U8(c) => &c.span,
                 ConstantExpression::U16(c) => &c.span,
                 ConstantExpression::U32(c) => &c.span,
+                ConstantExpression::U64(c) => &c.span,
+                ConstantExpression::U128(c) => &c.span,
             }
         }
     }
@@ -745,6 +827,24 @@ mod ast {
         pub span: Span<'ast>,
     }
 
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::hex_number_64))]
+    pub struct U64NumberExpression<'ast> {
+        #[pest_ast(outer(with(span_into_str)))]
+        pub value: String,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::hex_number_128))]
+    pub struct U128NumberExpression<'ast> {
+        #[pest_ast(outer(with(span_into_str)))]
+        pub value: String,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
     #[derive(Debug, FromPest, PartialEq, Clone)]
     #[pest_ast(rule(Rule::identifier))]
     pub struct IdentifierExpression<'ast> {
@@ -863,14 +963,19 @@ mod tests {
                 pragma: None,
                 structs: vec![],
                 functions: vec![Function {
+                    attributes: vec![],
                     id: IdentifierExpression {
                         value: String::from("main"),
                         span: Span::new(&source, 33, 37).unwrap()
                     },
                     parameters: vec![],
-                    returns: vec![Type::Basic(BasicType::Field(FieldType {
+                    returns: vec![ReturnType {
+                        visibility: None,
+                        ty: Type::Basic(BasicType::Field(FieldType {
+                            span: Span::new(&source, 44, 49).unwrap()
+                        })),
                         span: Span::new(&source, 44, 49).unwrap()
-                    }))],
+                    }],
                     statements: vec![Statement::Return(ReturnStatement {
                         expressions: vec![Expression::add(
                             Expression::Constant(ConstantExpression::DecimalNumber(
@@ -916,14 +1021,19 @@ mod tests {
                 pragma: None,
                 structs: vec![],
                 functions: vec![Function {
+                    attributes: vec![],
                     id: IdentifierExpression {
                         value: String::from("main"),
                         span: Span::new(&source, 33, 37).unwrap()
                     },
                     parameters: vec![],
-                    returns: vec![Type::Basic(BasicType::Field(FieldType {
+                    returns: vec![ReturnType {
+                        visibility: None,
+                        ty: Type::Basic(BasicType::Field(FieldType {
+                            span: Span::new(&source, 44, 49).unwrap()
+                        })),
                         span: Span::new(&source, 44, 49).unwrap()
-                    }))],
+                    }],
                     statements: vec![Statement::Return(ReturnStatement {
                         expressions: vec![Expression::add(
                             Expression::Constant(ConstantExpression::DecimalNumber(
@@ -987,14 +1097,19 @@ mod tests {
                 pragma: None,
                 structs: vec![],
                 functions: vec![Function {
+                    attributes: vec![],
                     id: IdentifierExpression {
                         value: String::from("main"),
                         span: Span::new(&source, 33, 37).unwrap()
                     },
                     parameters: vec![],
-                    returns: vec![Type::Basic(BasicType::Field(FieldType {
+                    returns: vec![ReturnType {
+                        visibility: None,
+                        ty: Type::Basic(BasicType::Field(FieldType {
+                            span: Span::new(&source, 44, 49).unwrap()
+                        })),
                         span: Span::new(&source, 44, 49).unwrap()
-                    }))],
+                    }],
                     statements: vec![Statement::Return(ReturnStatement {
                         expressions: vec![Expression::if_else(
                             Expression::Constant(ConstantExpression::DecimalNumber(
@@ -1045,14 +1160,19 @@ mod tests {
                 pragma: None,
                 structs: vec![],
                 functions: vec![Function {
+                    attributes: vec![],
                     id: IdentifierExpression {
                         value: String::from("main"),
                         span: Span::new(&source, 4, 8).unwrap()
                     },
                     parameters: vec![],
-                    returns: vec![Type::Basic(BasicType::Field(FieldType {
+                    returns: vec![ReturnType {
+                        visibility: None,
+                        ty: Type::Basic(BasicType::Field(FieldType {
+                            span: Span::new(&source, 15, 20).unwrap()
+                        })),
                         span: Span::new(&source, 15, 20).unwrap()
-                    }))],
+                    }],
                     statements: vec![Statement::Return(ReturnStatement {
                         expressions: vec![Expression::Constant(ConstantExpression::DecimalNumber(
                             DecimalNumberExpression {
@@ -1081,14 +1201,19 @@ mod tests {
                 pragma: None,
                 structs: vec![],
                 functions: vec![Function {
+                    attributes: vec![],
                     id: IdentifierExpression {
                         value: String::from("main"),
                         span: Span::new(&source, 4, 8).unwrap()
                     },
                     parameters: vec![],
-                    returns: vec![Type::Basic(BasicType::Field(FieldType {
+                    returns: vec![ReturnType {
+                        visibility: None,
+                        ty: Type::Basic(BasicType::Field(FieldType {
+                            span: Span::new(&source, 15, 20).unwrap()
+                        })),
                         span: Span::new(&source, 15, 20).unwrap()
-                    }))],
+                    }],
                     statements: vec![Statement::Definition(DefinitionStatement {
                         lhs: vec![
                             OptionallyTypedAssignee {
@@ -0,0 +1,136 @@
+//! Creates Schnorr signatures over Baby Jubjub which verify with `signatures/verifySchnorr`.
+//!
+//! Usage: `cargo run --example schnorr_sign -- <private key> <message>`
+//!
+//! The private key is a decimal integer and the message is 64 bytes in hex. The output is the list of
+//! arguments expected by `signatures/verifySchnorr`, without the curve parameters, in the format
+//! used by `zokrates compute-witness --abi --stdin`.
+
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+use std::env;
+
+type Point = (BigUint, BigUint);
+
+fn modulus() -> BigUint {
+    BigUint::parse_bytes(
+        b"21888242871839275222246405745257275088548364400416034343698204186575808495617",
+        10,
+    )
+    .unwrap()
+}
+
+// order of the subgroup generated by the base point
+fn order() -> BigUint {
+    BigUint::parse_bytes(
+        b"2736030358979909402780800718157159386076813972158567259200215660948447373041",
+        10,
+    )
+    .unwrap()
+}
+
+fn generator() -> Point {
+    (
+        BigUint::parse_bytes(
+            b"16540640123574156134436876038791482806971768689494387082833631921987005038935",
+            10,
+        )
+        .unwrap(),
+        BigUint::parse_bytes(
+            b"20819045374670962167435360035096875258406992893633759881276124905556507972311",
+            10,
+        )
+        .unwrap(),
+    )
+}
+
+fn inverse(x: &BigUint) -> BigUint {
+    let p = modulus();
+    x.modpow(&(&p - 2u32), &p)
+}
+
+fn add(a: &Point, b: &Point) -> Point {
+    let p = modulus();
+    let (x1, y1) = a;
+    let (x2, y2) = b;
+    let t = BigUint::from(168696u32) * x1 * x2 % &p * y1 * y2 % &p;
+    let x = (x1 * y2 + y1 * x2) % &p * inverse(&((BigUint::from(1u32) + &t) % &p)) % &p;
+    let y = (y1 * y2 + (&p - BigUint::from(168700u32) * x1 % &p * x2 % &p)) % &p
+        * inverse(&((BigUint::from(1u32) + &p - &t) % &p))
+        % &p;
+    (x, y)
+}
+
+fn mul(k: &BigUint, pt: &Point) -> Point {
+    let mut acc = (BigUint::from(0u32), BigUint::from(1u32));
+    let mut doubled = pt.clone();
+    for i in 0..k.bits() {
+        if (k >> i) & BigUint::from(1u32) == BigUint::from(1u32) {
+            acc = add(&acc, &doubled);
+        }
+        doubled = add(&doubled, &doubled);
+    }
+    acc
+}
+
+fn to_bytes_32(x: &BigUint) -> Vec<u8> {
+    let bytes = x.to_bytes_be();
+    let mut res = vec![0u8; 32 - bytes.len()];
+    res.extend(bytes);
+    res
+}
+
+fn to_words(bytes: &[u8]) -> Vec<String> {
+    bytes
+        .chunks(4)
+        .map(|c| format!("\"0x{:02x}{:02x}{:02x}{:02x}\"", c[0], c[1], c[2], c[3]))
+        .collect()
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        eprintln!("Usage: schnorr_sign <private key> <message>");
+        std::process::exit(1);
+    }
+
+    let sk = BigUint::parse_bytes(args[1].as_bytes(), 10).expect("invalid private key") % order();
+    let message = (0..args[2].len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&args[2][i..i + 2], 16))
+        .collect::<Result<Vec<_>, _>>()
+        .expect("invalid message");
+    assert_eq!(message.len(), 64, "the message must be 64 bytes");
+
+    let a = mul(&sk, &generator());
+
+    // derive the nonce deterministically from the private key and the message
+    let k = BigUint::from_bytes_be(
+        &Sha256::new()
+            .chain(to_bytes_32(&sk))
+            .chain(&message)
+            .result(),
+    ) % order();
+    let r = mul(&k, &generator());
+
+    // e = sha256(R.x || A.x || M)
+    let e = Sha256::new()
+        .chain(to_bytes_32(&r.0))
+        .chain(to_bytes_32(&a.0))
+        .chain(&message)
+        .result();
+
+    // s = k - e * sk, so that R = s * G + e * A
+    let n = order();
+    let s = (&k + &n - BigUint::from_bytes_be(&e) % &n * &sk % &n) % &n;
+
+    println!(
+        "[[{}], \"{}\", [\"{}\", \"{}\"], [{}], [{}]]",
+        to_words(&e).join(", "),
+        s,
+        a.0,
+        a.1,
+        to_words(&message[..32]).join(", "),
+        to_words(&message[32..]).join(", ")
+    );
+}
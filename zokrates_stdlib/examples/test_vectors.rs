@@ -0,0 +1,248 @@
+//! Writes test vectors for the hash functions and ciphers of the stdlib, whose expected outputs are computed by
+//! reference Rust implementations.
+//!
+//! Usage: `cargo run --example test_vectors`
+//!
+//! Each gadget gets a test file in `tests/tests/vectors`, at the path of the gadget in the stdlib, whose entry point is
+//! the gadget itself. The files are run on all curves along with the other stdlib tests, which catches changes of
+//! constants or byte orders which the hand-written tests would miss. The inputs are derived from the name of the
+//! gadget, so that running the example again gives the same files: they only change when a reference does.
+//!
+//! Gadgets over Baby Jubjub, such as the signatures, and the Rescue-Prime hash are not covered, as there is no reference
+//! implementation of the curve or of the hash among the dependencies.
+
+use aes::cipher::{BlockEncrypt, NewBlockCipher};
+use aes::Aes128;
+use chacha20::stream_cipher::{NewStreamCipher, SyncStreamCipher, SyncStreamCipherSeek};
+use chacha20::ChaCha20;
+use hmac::{Hmac, Mac};
+use ripemd160::Ripemd160;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+
+/// The number of vectors with derived inputs, which follow the vectors whose bytes are all `0x00` or all `0xff`
+const DERIVED_VECTORS: u8 = 2;
+
+/// A gadget of the stdlib, with the reference implementation computing its output bytes from its input bytes, or
+/// `None` if it does not support them
+struct Gadget {
+    /// the path of the gadget in the stdlib, without extension
+    path: &'static str,
+    /// the number of bytes of each parameter, and how they are passed
+    inputs: Vec<(usize, Encoding)>,
+    output: Encoding,
+    reference: fn(&[Vec<u8>]) -> Option<Vec<u8>>,
+}
+
+/// How bytes are passed to or returned by a gadget
+#[derive(Clone, Copy)]
+enum Encoding {
+    Bytes,
+    /// `u32` words, whose bytes are in big-endian order
+    WordsBe,
+    /// `u32` words, whose bytes are in little-endian order
+    WordsLe,
+}
+
+impl Encoding {
+    fn values(self, bytes: &[u8]) -> Vec<String> {
+        match self {
+            Encoding::Bytes => bytes.iter().map(|b| format!("0x{:02x}", b)).collect(),
+            Encoding::WordsBe => bytes
+                .chunks(4)
+                .map(|w| format!("0x{:02x}{:02x}{:02x}{:02x}", w[0], w[1], w[2], w[3]))
+                .collect(),
+            Encoding::WordsLe => bytes
+                .chunks(4)
+                .map(|w| format!("0x{:02x}{:02x}{:02x}{:02x}", w[3], w[2], w[1], w[0]))
+                .collect(),
+        }
+    }
+}
+
+fn gadgets() -> Vec<Gadget> {
+    use self::Encoding::*;
+
+    vec![
+        Gadget {
+            path: "hashes/sha256/512bit",
+            inputs: vec![(32, WordsBe); 2],
+            output: WordsBe,
+            reference: |inputs| Some(compress(&inputs.concat())),
+        },
+        Gadget {
+            path: "hashes/sha256/1536bit",
+            inputs: vec![(32, WordsBe); 6],
+            output: WordsBe,
+            reference: |inputs| Some(compress(&inputs.concat())),
+        },
+        Gadget {
+            path: "hashes/sha256/256bitPadded",
+            inputs: vec![(32, WordsBe)],
+            output: WordsBe,
+            reference: |inputs| Some(Sha256::digest(&inputs.concat()).to_vec()),
+        },
+        Gadget {
+            path: "hashes/sha256/512bitPadded",
+            inputs: vec![(32, WordsBe); 2],
+            output: WordsBe,
+            reference: |inputs| Some(Sha256::digest(&inputs.concat()).to_vec()),
+        },
+        Gadget {
+            path: "hashes/sha256/1024bitPadded",
+            inputs: vec![(32, WordsBe); 4],
+            output: WordsBe,
+            reference: |inputs| Some(Sha256::digest(&inputs.concat()).to_vec()),
+        },
+        Gadget {
+            path: "hashes/hmac/sha256",
+            inputs: vec![(32, WordsBe); 2],
+            output: WordsBe,
+            reference: |inputs| {
+                let mut mac = Hmac::<Sha256>::new_varkey(&inputs[0]).unwrap();
+                mac.input(&inputs[1]);
+                Some(mac.result().code().to_vec())
+            },
+        },
+        Gadget {
+            path: "hashes/ripemd160/256bitPadded",
+            inputs: vec![(32, WordsBe)],
+            output: WordsBe,
+            reference: |inputs| Some(Ripemd160::digest(&inputs[0]).to_vec()),
+        },
+        Gadget {
+            path: "bitcoin/headerHash",
+            inputs: vec![(80, WordsBe)],
+            output: WordsBe,
+            reference: |inputs| Some(Sha256::digest(&Sha256::digest(&inputs[0])).to_vec()),
+        },
+        Gadget {
+            path: "ciphers/aes128/encrypt",
+            inputs: vec![(16, Bytes); 2],
+            output: Bytes,
+            reference: |inputs| {
+                let mut block = array::<[u8; 16]>(&inputs[1]).into();
+                Aes128::new(&array::<[u8; 16]>(&inputs[0]).into()).encrypt_block(&mut block);
+                Some(block.to_vec())
+            },
+        },
+        Gadget {
+            path: "ciphers/chacha20/chacha20Block",
+            inputs: vec![(32, WordsLe), (4, WordsLe), (12, WordsLe)],
+            output: WordsLe,
+            reference: |inputs| {
+                let counter = u32::from_le_bytes(array(&inputs[1]));
+                let mut cipher = ChaCha20::new(
+                    &array::<[u8; 32]>(&inputs[0]).into(),
+                    &array::<[u8; 12]>(&inputs[2]).into(),
+                );
+                cipher.seek(u64::from(counter) * 64);
+
+                // the block is the keystream, which encrypts zeros to itself. The last block of a keystream, whose
+                // counter is `0xffffffff`, is rejected by the reference
+                let mut block = vec![0u8; 64];
+                cipher.try_apply_keystream(&mut block).ok()?;
+                Some(block)
+            },
+        },
+    ]
+}
+
+/// Returns the sha256 compression of `blocks` from the initial hash values, without padding
+fn compress(blocks: &[u8]) -> Vec<u8> {
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    for block in blocks.chunks(64) {
+        let mut b = [0u8; 64];
+        b.copy_from_slice(block);
+        sha2::compress256(&mut state, &b);
+    }
+
+    state
+        .iter()
+        .flat_map(|w| w.to_be_bytes().to_vec())
+        .collect()
+}
+
+/// Returns `bytes` as an array of the same length
+fn array<A: Default + AsMut<[u8]>>(bytes: &[u8]) -> A {
+    let mut array = A::default();
+    array.as_mut().copy_from_slice(bytes);
+    array
+}
+
+/// Returns `len` bytes derived from `label`
+fn derive(label: &str, len: usize) -> Vec<u8> {
+    (0u32..)
+        .flat_map(|i| {
+            Sha256::new()
+                .chain(label)
+                .chain(i.to_be_bytes())
+                .result()
+                .to_vec()
+        })
+        .take(len)
+        .collect()
+}
+
+fn vector(gadget: &Gadget, inputs: Vec<Vec<u8>>) -> Option<Value> {
+    let output = (gadget.reference)(&inputs)?;
+
+    let values: Vec<String> = gadget
+        .inputs
+        .iter()
+        .zip(&inputs)
+        .flat_map(|((_, encoding), bytes)| encoding.values(bytes))
+        .collect();
+
+    Some(json!({
+        "input": { "values": values },
+        "output": { "Ok": { "values": gadget.output.values(&output) } }
+    }))
+}
+
+fn main() {
+    let base = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/tests/vectors");
+
+    for gadget in gadgets() {
+        let filled = |byte| {
+            gadget
+                .inputs
+                .iter()
+                .map(|(len, _)| vec![byte; *len])
+                .collect()
+        };
+
+        let derived = (0..DERIVED_VECTORS).map(|i| {
+            gadget
+                .inputs
+                .iter()
+                .enumerate()
+                .map(|(j, (len, _))| derive(&format!("{}/{}/{}", gadget.path, i, j), *len))
+                .collect()
+        });
+
+        let tests: Vec<_> = vec![filled(0x00), filled(0xff)]
+            .into_iter()
+            .chain(derived)
+            .filter_map(|inputs| vector(&gadget, inputs))
+            .collect();
+
+        let file = json!({
+            "entry_point": format!("./stdlib/{}.zok", gadget.path),
+            "curves": ["Bn128", "Bls12"],
+            "tests": tests
+        });
+
+        let path = base.join(format!("{}.json", gadget.path));
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, serde_json::to_string_pretty(&file).unwrap() + "\n").unwrap();
+
+        println!("{}", path.display());
+    }
+}
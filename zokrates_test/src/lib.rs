@@ -3,7 +3,9 @@ extern crate serde_derive;
 
 use std::path::PathBuf;
 use zokrates_core::ir;
-use zokrates_field::{Bls12Field, Bn128Field, Field};
+use zokrates_core::proof_system::bellman::groth16::G16;
+use zokrates_core::proof_system::ProofSystem;
+use zokrates_field::{BellmanFieldExtensions, Bls12Field, Bn128Field, Field};
 
 #[derive(Serialize, Deserialize, Clone)]
 enum Curve {
@@ -11,10 +13,17 @@ enum Curve {
     Bls12,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+enum Scheme {
+    G16,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 struct Tests {
     pub entry_point: PathBuf,
     pub curves: Option<Vec<Curve>>,
+    /// Proving schemes with which successful tests are also proven and verified
+    pub schemes: Option<Vec<Scheme>>,
     pub max_constraint_count: Option<usize>,
     pub tests: Vec<Test>,
 }
@@ -44,7 +53,7 @@ type Val = String;
 
 fn parse_val<T: Field>(s: String) -> T {
     let s = if s.starts_with("0x") {
-        u32::from_str_radix(s.trim_start_matches("0x"), 16)
+        u128::from_str_radix(s.trim_start_matches("0x"), 16)
             .unwrap()
             .to_string()
     } else {
@@ -82,29 +91,54 @@ fn compare<T: Field>(result: ir::ExecutionResult<T>, expected: TestResult) -> Re
     Ok(())
 }
 
-use std::io::{BufReader, Read};
-use zokrates_core::compile::compile;
+use std::io::BufReader;
+use zokrates_core::compile::{compile, CompileConfig};
 use zokrates_fs_resolver::FileSystemResolver;
 
 pub fn test_inner(test_path: &str) {
-    let t: Tests =
-        serde_json::from_reader(BufReader::new(File::open(Path::new(test_path)).unwrap())).unwrap();
+    run_test_file(Path::new(test_path)).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Runs the test cases described in the file at `test_path` on all of its curves and schemes
+pub fn run_test_file(test_path: &Path) -> Result<(), String> {
+    let file = File::open(test_path)
+        .map_err(|why| format!("Couldn't open {}: {}", test_path.display(), why))?;
+    let t: Tests = serde_json::from_reader(BufReader::new(file))
+        .map_err(|why| format!("Couldn't parse {}: {}", test_path.display(), why))?;
 
     let curves = t.curves.clone().unwrap_or(vec![Curve::Bn128]);
 
     for c in &curves {
         match c {
-            Curve::Bn128 => compile_and_run::<Bn128Field>(t.clone()),
-            Curve::Bls12 => compile_and_run::<Bls12Field>(t.clone()),
+            Curve::Bn128 => compile_and_run::<Bn128Field>(t.clone())?,
+            Curve::Bls12 => compile_and_run::<Bls12Field>(t.clone())?,
         }
     }
+
+    Ok(())
 }
 
-fn compile_and_run<T: Field>(t: Tests) {
-    let code = std::fs::read_to_string(&t.entry_point).unwrap();
+fn compile_and_run<T: Field + BellmanFieldExtensions>(t: Tests) -> Result<(), String> {
+    let code = std::fs::read_to_string(&t.entry_point)
+        .map_err(|why| format!("Couldn't read {}: {}", t.entry_point.display(), why))?;
 
     let resolver = FileSystemResolver::new();
-    let artifacts = compile::<T, _>(code, t.entry_point.clone(), Some(&resolver)).unwrap();
+    let artifacts = compile::<T, _>(
+        code.clone(),
+        t.entry_point.clone(),
+        Some(&resolver),
+        &CompileConfig::default(),
+    )
+    .map_err(|e| {
+        format!(
+            "Compilation of {} failed:\n{}",
+            t.entry_point.display(),
+            e.0.iter()
+                .map(|e| format!("{}:{}", e.file().display(), e.value()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )
+    })?;
 
     let bin = artifacts.prog();
 
@@ -122,30 +156,51 @@ fn compile_and_run<T: Field>(t: Tests) {
     };
 
     let interpreter = zokrates_core::ir::Interpreter::default();
+    let schemes = t.schemes.unwrap_or(vec![]);
 
     for test in t.tests.into_iter() {
         let input = &test.input.values;
 
         let output = interpreter.execute(bin, &(input.iter().cloned().map(parse_val).collect()));
 
-        match compare(output, test.output) {
-            Err(e) => {
-                let mut code = File::open(&t.entry_point).unwrap();
-                let mut s = String::new();
-                code.read_to_string(&mut s).unwrap();
-                let context = format!(
-                    "\n{}\nCalled with input ({})\n",
-                    s,
-                    input
-                        .iter()
-                        .map(|i| format!("{}", i))
-                        .collect::<Vec<_>>()
-                        .join(", ")
-                );
-                panic!("{}{}", context, e)
-            }
-            Ok(..) => {}
+        let context = || {
+            format!(
+                "\n{}\nCalled with input ({})\n",
+                code,
+                input
+                    .iter()
+                    .map(|i| format!("{}", i))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
         };
+
+        // only witnesses of successful executions can be proven
+        if let Ok(witness) = &output {
+            for scheme in &schemes {
+                match scheme {
+                    Scheme::G16 => prove_and_verify::<T, G16>(bin, witness)
+                        .map_err(|e| format!("{}{}", context(), e))?,
+                }
+            }
+        }
+
+        compare(output, test.output).map_err(|e| format!("{}{}", context(), e))?;
+    }
+
+    Ok(())
+}
+
+fn prove_and_verify<T: Field, P: ProofSystem<T>>(
+    program: &ir::Prog<T>,
+    witness: &ir::Witness<T>,
+) -> Result<(), String> {
+    let keypair = P::setup(program.clone());
+    let proof = P::generate_proof(program, witness.clone(), &keypair.pk);
+
+    match P::verify(keypair.vk, proof) {
+        true => Ok(()),
+        false => Err(String::from("Proof verification failed")),
     }
 }
 
@@ -154,6 +209,17 @@ use std::fs::File;
 use std::io::{BufWriter, Write};
 use std::path::Path;
 
+/// Returns the paths of the test files found in `base` and its subdirectories
+pub fn test_files(base: &Path) -> Result<Vec<PathBuf>, String> {
+    let pattern = base.join("**/*.json");
+    let paths = glob::glob(&pattern.to_string_lossy())
+        .map_err(|why| format!("Invalid test directory {}: {}", base.display(), why))?;
+
+    paths
+        .map(|p| p.map_err(|why| format!("Couldn't read {}: {}", why.path().display(), why)))
+        .collect()
+}
+
 pub fn write_tests(base: &str) {
     use glob::glob;
 